@@ -0,0 +1,15 @@
+#version 400
+
+in vec3 position;
+in vec4 color;
+
+uniform mat4 viewMatrix;
+uniform mat4 projectionMatrix;
+
+out vec4 vColor;
+
+void main()
+{
+	vColor = color;
+	gl_Position = projectionMatrix * viewMatrix * vec4(position, 1.0f);
+}
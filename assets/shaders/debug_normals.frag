@@ -0,0 +1,10 @@
+#version 400
+
+in vec3 vNormal;
+
+out vec4 fragColor;
+
+void main()
+{
+	fragColor = vec4(normalize(vNormal) * 0.5 + 0.5, 1.0);
+}
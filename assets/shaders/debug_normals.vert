@@ -0,0 +1,47 @@
+#version 400
+
+const int maxBoneCount = 50;
+
+in vec3 position;
+in vec3 normal;
+in uvec4 boneIds;
+in vec4 boneWeights;
+in mat4 instanceObjectMatrix;
+in mat4 instanceNormalMatrix;
+
+uniform mat4 objectMatrix;
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+uniform mat4 normalMatrix;
+uniform mat4 bones[maxBoneCount];
+uniform int useInstancing;
+
+out vec3 vNormal;
+
+void main()
+{
+	mat4 modelMatrix = objectMatrix;
+	mat3 modelNormalMatrix = mat3(normalMatrix);
+	if (useInstancing == 1)
+	{
+		modelMatrix = instanceObjectMatrix;
+		modelNormalMatrix = mat3(instanceNormalMatrix);
+	}
+
+	mat4 boneTransform = 
+		bones[boneIds[0]] * boneWeights[0] +
+		bones[boneIds[1]] * boneWeights[1] +
+		bones[boneIds[2]] * boneWeights[2] +
+		bones[boneIds[3]] * boneWeights[3];
+
+	vNormal = normalize(modelNormalMatrix * mat3(boneTransform) * normal);
+
+	gl_Position = projectionMatrix * viewMatrix * modelMatrix * boneTransform * vec4(position, 1.0f);
+}
@@ -9,13 +9,22 @@ in vec3 tangent;
 in vec2 uvChannels[maxUvChannelCount];
 in uvec4 boneIds;
 in vec4 boneWeights;
+in mat4 instanceObjectMatrix;
+in mat4 instanceNormalMatrix;
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
 
-uniform vec3 eyePosition;
 uniform mat4 objectMatrix;
-uniform mat4 viewMatrix;
-uniform mat4 projectionMatrix;
 uniform mat4 normalMatrix;
 uniform mat4 bones[maxBoneCount];
+uniform int useInstancing;
 
 uniform int useAlbedoTexture;
 uniform sampler2D albedoTexture;
@@ -42,15 +51,23 @@ out mat3 vInvTangentMatrix;
 
 void main()
 {
+	mat4 modelMatrix = objectMatrix;
+	mat3 modelNormalMatrix = mat3(normalMatrix);
+	if (useInstancing == 1)
+	{
+		modelMatrix = instanceObjectMatrix;
+		modelNormalMatrix = mat3(instanceNormalMatrix);
+	}
+
 	mat4 boneTransform = 
 		bones[boneIds[0]] * boneWeights[0] +
 		bones[boneIds[1]] * boneWeights[1] +
 		bones[boneIds[2]] * boneWeights[2] +
 		bones[boneIds[3]] * boneWeights[3];
 
-	vNormal = normalize(mat3(normalMatrix) * mat3(boneTransform) * normal);
+	vNormal = normalize(modelNormalMatrix * mat3(boneTransform) * normal);
 
-	vTangentMatrix[0] = vec3(normalize(mat3(normalMatrix) * mat3(boneTransform) * tangent));
+	vTangentMatrix[0] = vec3(normalize(modelNormalMatrix * mat3(boneTransform) * tangent));
 	vTangentMatrix[2] = vNormal;
 	vTangentMatrix[1] = normalize(cross(vTangentMatrix[2], vTangentMatrix[0]));
 	vInvTangentMatrix = transpose(vTangentMatrix);
@@ -60,6 +77,6 @@ void main()
 		vUvChannels[i] = uvChannels[i];
 	}
 
-	vWorldPos = objectMatrix * boneTransform * vec4(position, 1.0f);
+	vWorldPos = modelMatrix * boneTransform * vec4(position, 1.0f);
 	gl_Position = projectionMatrix * viewMatrix * vWorldPos;
 }
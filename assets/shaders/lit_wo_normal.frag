@@ -3,10 +3,16 @@
 const int maxUvChannelCount = 10;
 const int maxBoneCount = 50;
 
-uniform vec3 eyePosition;
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
 uniform mat4 objectMatrix;
-uniform mat4 viewMatrix;
-uniform mat4 projectionMatrix;
 uniform mat4 normalMatrix;
 uniform mat4 bones[maxBoneCount];
 
@@ -22,6 +28,20 @@ uniform int useDisplacementTexture;
 uniform sampler2D displacementTexture;
 uniform uint displacementTextureUvChannelId;
 
+uniform int useShininessTexture;
+uniform sampler2D shininessTexture;
+uniform uint shininessTextureUvChannelId;
+
+uniform int useEmissionTexture;
+uniform sampler2D emissionTexture;
+uniform uint emissionTextureUvChannelId;
+
+uniform int useEnvironmentMap;
+uniform samplerCube environmentMap;
+
+uniform int useShadowMap;
+uniform sampler2D shadowMap;
+
 uniform float opacity;
 uniform vec3 albedoColor;
 uniform vec3 emissiveColor;
@@ -35,6 +55,22 @@ in mat3 vInvTangentMatrix;
 
 out vec4 fragColor;
 
+float getShadow() {
+	if (useShadowMap != 1) {
+		return 0.0f;
+	}
+
+	vec4 lightSpacePos = lightSpaceMatrix * vWorldPos;
+	vec3 shadowMapCoords = lightSpacePos.xyz / lightSpacePos.w * 0.5f + 0.5f;
+	if (shadowMapCoords.z > 1.0f) {
+		return 0.0f;
+	}
+
+	const float bias = 0.005f;
+	float closestDepth = texture(shadowMap, shadowMapCoords.xy).r;
+	return shadowMapCoords.z - bias > closestDepth ? 1.0f : 0.0f;
+}
+
 vec4 getAlbedoColor(vec2 texCoordsOffset) {
 	if (useAlbedoTexture == 1) {
 		return texture(
@@ -46,6 +82,31 @@ vec4 getAlbedoColor(vec2 texCoordsOffset) {
 	}
 }
 
+vec3 getShininessColor(vec2 texCoordsOffset) {
+	if (useShininessTexture == 1) {
+		return texture(
+			shininessTexture,
+			vUvChannels[shininessTextureUvChannelId] + texCoordsOffset
+		).rgb * shininessColor;
+	} else {
+		return shininessColor;
+	}
+}
+
+vec3 getEmissiveColor(vec2 texCoordsOffset) {
+	if (useEmissionTexture == 1) {
+		return max(
+			texture(
+				emissionTexture,
+				vUvChannels[emissionTextureUvChannelId] + texCoordsOffset
+			).rgb,
+			emissiveColor
+		);
+	} else {
+		return emissiveColor;
+	}
+}
+
 vec3 getNormal(vec2 texCoordsOffset) {
 	return vNormal;
 }
@@ -71,12 +132,17 @@ void main() {
 
 	vec3 lightDir0 = vec3(1.2f, -0.8f, -1.0f);
 	vec3 lightColor0 = vec3(1.0f, 1.0f, 1.0f);
-	lightIntensity += lightColor0 * clamp(dot(normal, -normalize(lightDir0)), 0.0f, 1.0f);
+	lightIntensity += lightColor0 * clamp(dot(normal, -normalize(lightDir0)), 0.0f, 1.0f) * (1.0f - getShadow());
 
 	vec3 lightDir1 = vec3(-1.0f, 1.0f, 1.0f);
 	vec3 lightColor1 = vec3(0.1f, 0.1f, 0.4f);
 	lightIntensity += lightColor1 * clamp(dot(normal, -normalize(lightDir1)), 0.0f, 1.0f);
 
 	vec3 resultColor = (albedo * lightIntensity + ambient) * albedoColor;
-	fragColor = max(vec4(resultColor, alpha), vec4(emissiveColor, alpha));
+	if (useEnvironmentMap == 1) {
+		vec3 reflectedDir = reflect(viewDir, normal);
+		resultColor += texture(environmentMap, reflectedDir).rgb * getShininessColor(texCoordsOffset);
+	}
+	fragColor = max(vec4(resultColor, alpha), vec4(getEmissiveColor(texCoordsOffset), alpha));
+	fragColor = vec4(fragColor.rgb * exposure, fragColor.a);
 }
@@ -0,0 +1,12 @@
+#version 400
+
+in vec3 position;
+
+uniform mat4 objectMatrix;
+uniform mat4 viewMatrix;
+uniform mat4 projectionMatrix;
+
+void main()
+{
+	gl_Position = projectionMatrix * viewMatrix * objectMatrix * vec4(position, 1.0f);
+}
@@ -1,20 +1,36 @@
+#version 400
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+uniform int useAlbedoTexture;
+uniform sampler2D albedoTexture;
+
+uniform float opacity;
+uniform vec3 albedoColor;
+
 in vec2 vTexCoords;
+in vec4 vColor;
 
 out vec4 fragColor;
 
 void main()
 {
-	vec4 albedo = vec4(1.0, 1.0, 1.0, 1.0);
-	vec4 ambient = vec4(0.1, 0.1, 0.1, 0.0);
-
-	albedo = albedo * (1.0 - textureBlend) + me_texture_2d(albedoTexture, vTexCoords) * textureBlend;
+	vec4 albedo = vec4(1.0f);
+	if (useAlbedoTexture == 1) {
+		albedo = texture(albedoTexture, vTexCoords);
+	}
 
-	float alpha = albedo.a;
-	if (alpha < 0.05) {
+	albedo = albedo * vColor * vec4(albedoColor, opacity);
+	if (albedo.a < 0.01) {
 		discard;
 	}
 
-	vec4 resultColor = albedo * albedoColor;
-	resultColor.a = alpha;
-	fragColor = resultColor;
+	fragColor = vec4(albedo.rgb * exposure, albedo.a);
 }
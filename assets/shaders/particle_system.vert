@@ -1,10 +1,32 @@
+#version 400
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+in vec2 corner;
+in vec3 particlePosition;
+in float particleSize;
+in vec4 particleColor;
+
 out vec2 vTexCoords;
+out vec4 vColor;
 
 void main()
 {
-	vTexCoords = texCoords;
-	gl_Position = projectionMatrix
-		* modelviewMatrix
-		* mat4(transform0, transform1, transform2, transform3)
-		* vec4(position, 1.0);
+	vTexCoords = corner + vec2(0.5f, 0.5f);
+	vColor = particleColor;
+
+	// the quads are billboards, so they are spanned by the right and up axes of the camera
+	vec3 cameraRight = vec3(viewMatrix[0][0], viewMatrix[1][0], viewMatrix[2][0]);
+	vec3 cameraUp = vec3(viewMatrix[0][1], viewMatrix[1][1], viewMatrix[2][1]);
+	vec3 position = particlePosition
+		+ (cameraRight * corner.x + cameraUp * corner.y) * particleSize;
+
+	gl_Position = projectionMatrix * viewMatrix * vec4(position, 1.0f);
 }
@@ -0,0 +1,21 @@
+#version 400
+
+uniform samplerCube environmentMap;
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+in vec3 vDirection;
+
+out vec4 fragColor;
+
+void main()
+{
+	fragColor = vec4(texture(environmentMap, vDirection).rgb * exposure, 1.0f);
+}
@@ -0,0 +1,22 @@
+#version 400
+
+in vec3 position;
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+out vec3 vDirection;
+
+void main()
+{
+	vDirection = position;
+
+	// the skybox surrounds the eye, so only the rotation of the view matrix is applied
+	gl_Position = projectionMatrix * mat4(mat3(viewMatrix)) * vec4(position, 1.0f);
+}
@@ -0,0 +1,27 @@
+#version 400
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+uniform sampler2D spriteTexture;
+
+in vec2 vTexCoords;
+in vec4 vColor;
+
+out vec4 fragColor;
+
+void main()
+{
+	vec4 albedo = texture(spriteTexture, vTexCoords) * vColor;
+	if (albedo.a < 0.01) {
+		discard;
+	}
+
+	fragColor = vec4(albedo.rgb * exposure, albedo.a);
+}
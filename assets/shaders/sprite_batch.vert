@@ -0,0 +1,34 @@
+#version 400
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+uniform mat4 objectMatrix;
+
+in vec2 corner;
+in vec4 spriteRect;
+in vec4 spriteUvRect;
+in vec4 spriteColor;
+
+out vec2 vTexCoords;
+out vec4 vColor;
+
+void main()
+{
+	// the origin of the uv rect is in the top left corner of the texture
+	vTexCoords = vec2(
+		spriteUvRect.x + corner.x * spriteUvRect.z,
+		spriteUvRect.y + (1.0f - corner.y) * spriteUvRect.w
+	);
+	vColor = spriteColor;
+
+	vec2 position = spriteRect.xy + corner * spriteRect.zw;
+
+	gl_Position = projectionMatrix * viewMatrix * objectMatrix * vec4(position, 0.0f, 1.0f);
+}
@@ -0,0 +1,23 @@
+#version 400
+
+const int maxInputTextureCount = 8;
+
+uniform sampler2D sceneTexture;
+uniform sampler2D inputTextures[maxInputTextureCount];
+uniform int numberOfInputTextures;
+uniform vec2 resolution;
+
+in vec2 vTexCoords;
+
+out vec4 fragColor;
+
+void main()
+{
+	vec3 color = texture(sceneTexture, vTexCoords).rgb;
+
+	// reinhard tonemapping followed by gamma correction
+	color = color / (color + vec3(1.0));
+	color = pow(color, vec3(1.0 / 2.2));
+
+	fragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,11 @@
+#version 400
+
+out vec2 vTexCoords;
+
+void main()
+{
+	// a triangle that covers the whole screen
+	vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+	vTexCoords = position;
+	gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -3,10 +3,16 @@
 const int maxUvChannelCount = 10;
 const int maxBoneCount = 50;
 
-uniform vec3 eyePosition;
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
 uniform mat4 objectMatrix;
-uniform mat4 viewMatrix;
-uniform mat4 projectionMatrix;
 uniform mat3 normalMatrix;
 uniform mat4 bones[maxBoneCount];
 
@@ -57,4 +63,5 @@ void main()
 	}
 
 	fragColor = vec4(max(albedo, emissiveColor) * albedoColor, alpha);
+	fragColor = vec4(fragColor.rgb * exposure, fragColor.a);
 }
@@ -9,13 +9,22 @@ in vec3 tangent;
 in vec2 uvChannels[maxUvChannelCount];
 in uvec4 boneIds;
 in vec4 boneWeights;
+in mat4 instanceObjectMatrix;
+in mat4 instanceNormalMatrix;
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
 
-uniform vec3 eyePosition;
 uniform mat4 objectMatrix;
-uniform mat4 viewMatrix;
-uniform mat4 projectionMatrix;
 uniform mat3 normalMatrix;
 uniform mat4 bones[maxBoneCount];
+uniform int useInstancing;
 
 uniform int useAlbedoTexture;
 uniform sampler2D albedoTexture;
@@ -39,6 +48,14 @@ out vec2 vUvChannels[maxUvChannelCount];
 
 void main()
 {
+	mat4 modelMatrix = objectMatrix;
+	mat3 modelNormalMatrix = mat3(normalMatrix);
+	if (useInstancing == 1)
+	{
+		modelMatrix = instanceObjectMatrix;
+		modelNormalMatrix = mat3(instanceNormalMatrix);
+	}
+
 	mat4 boneTransform = 
 		bones[boneIds[0]] * boneWeights[0] +
 		bones[boneIds[1]] * boneWeights[1] +
@@ -49,6 +66,6 @@ void main()
 	{
 		vUvChannels[i] = uvChannels[i];
 	}
-	vNormal = normalMatrix * mat3(boneTransform) * normal;
-	gl_Position = projectionMatrix * viewMatrix * objectMatrix * boneTransform * vec4(position, 1.0f);
+	vNormal = modelNormalMatrix * mat3(boneTransform) * normal;
+	gl_Position = projectionMatrix * viewMatrix * modelMatrix * boneTransform * vec4(position, 1.0f);
 }
@@ -0,0 +1,68 @@
+#version 400
+
+const int maxUvChannelCount = 4;
+const int maxBoneCount = 50;
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+uniform mat4 objectMatrix;
+uniform mat3 normalMatrix;
+uniform mat4 bones[maxBoneCount];
+
+uniform int useAlbedoTexture;
+uniform sampler2D albedoTexture;
+uniform uint albedoTextureUvChannelId;
+
+uniform int useNormalTexture;
+uniform sampler2D normalTexture;
+uniform uint normalTextureUvChannelId;
+
+uniform int useDisplacementTexture;
+uniform sampler2D displacementTexture;
+uniform uint displacementTextureUvChannelId;
+
+uniform float opacity;
+uniform vec3 albedoColor;
+uniform vec3 emissiveColor;
+uniform vec3 shininessColor;
+
+in vec3 vNormal;
+in vec2 vUvChannels[maxUvChannelCount];
+in vec4 vColor;
+
+out vec4 fragColor;
+
+vec4 getAlbedoColor(vec2 texCoordsOffset) {
+	if (useAlbedoTexture == 1) {
+		return texture(
+			albedoTexture,
+			vUvChannels[albedoTextureUvChannelId] + texCoordsOffset
+		);
+	} else {
+		return vec4(1.0f);
+	}
+}
+
+void main()
+{
+	vec3 albedo = vec3(1.0, 1.0, 1.0);
+
+	vec2 texCoordsOffset = vec2(0.0f, 0.0f);
+
+	vec4 tmp = getAlbedoColor(texCoordsOffset) * vColor;
+	albedo = vec3(tmp);
+	float alpha = tmp.a;
+	if (alpha < 0.05) {
+		discard;
+	}
+
+	fragColor = vec4(max(albedo, emissiveColor) * albedoColor, alpha);
+	fragColor = vec4(fragColor.rgb * exposure, fragColor.a);
+}
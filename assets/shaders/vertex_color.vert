@@ -0,0 +1,83 @@
+#version 400
+
+const int maxUvChannelCount = 4;
+const int maxColorChannelCount = 2;
+const int maxBoneCount = 50;
+
+in vec3 position;
+in vec3 normal;
+in vec3 tangent;
+in vec2 uvChannels[maxUvChannelCount];
+in vec4 colorChannels[maxColorChannelCount];
+in uvec4 boneIds;
+in vec4 boneWeights;
+in mat4 instanceObjectMatrix;
+in mat4 instanceNormalMatrix;
+
+layout(std140) uniform FrameUniforms
+{
+	mat4 viewMatrix;
+	mat4 projectionMatrix;
+	mat4 lightSpaceMatrix;
+	vec3 eyePosition;
+	float exposure;
+};
+
+uniform mat4 objectMatrix;
+uniform mat3 normalMatrix;
+uniform mat4 bones[maxBoneCount];
+uniform int useInstancing;
+uniform uint numberOfColorChannels;
+
+uniform int useAlbedoTexture;
+uniform sampler2D albedoTexture;
+uniform uint albedoTextureUvChannelId;
+
+uniform int useNormalTexture;
+uniform sampler2D normalTexture;
+uniform uint normalTextureUvChannelId;
+
+uniform int useDisplacementTexture;
+uniform sampler2D displacementTexture;
+uniform uint displacementTextureUvChannelId;
+
+uniform float opacity;
+uniform vec3 albedoColor;
+uniform vec3 emissiveColor;
+uniform vec3 shininessColor;
+
+out vec3 vNormal;
+out vec2 vUvChannels[maxUvChannelCount];
+out vec4 vColor;
+
+void main()
+{
+	mat4 modelMatrix = objectMatrix;
+	mat3 modelNormalMatrix = mat3(normalMatrix);
+	if (useInstancing == 1)
+	{
+		modelMatrix = instanceObjectMatrix;
+		modelNormalMatrix = mat3(instanceNormalMatrix);
+	}
+
+	mat4 boneTransform = 
+		bones[boneIds[0]] * boneWeights[0] +
+		bones[boneIds[1]] * boneWeights[1] +
+		bones[boneIds[2]] * boneWeights[2] +
+		bones[boneIds[3]] * boneWeights[3];
+
+	for (int i = 0; i < maxUvChannelCount; ++i)
+	{
+		vUvChannels[i] = uvChannels[i];
+	}
+	// the second color channel is blended over the first one by its alpha
+	vColor = vec4(1.0f);
+	if (numberOfColorChannels > 0) {
+		vColor = colorChannels[0];
+	}
+	if (numberOfColorChannels > 1) {
+		vColor = vec4(mix(vColor.rgb, colorChannels[1].rgb, colorChannels[1].a), vColor.a);
+	}
+	vNormal = modelNormalMatrix * mat3(boneTransform) * normal;
+	gl_Position = projectionMatrix * viewMatrix * modelMatrix * boneTransform * vec4(position, 1.0f);
+}
@@ -0,0 +1,145 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use entity_component::{component_type_list, EntityContainer, EntityGroup, EntityId};
+
+struct Position(f32, f32, f32);
+
+struct Velocity(f32, f32, f32);
+
+struct Health(u32);
+
+const NUMBER_OF_ENTITIES: u32 = 10_000;
+
+fn spawn_entities(entity_container: &EntityContainer, count: u32) -> Vec<EntityId> {
+    entity_container.spawn_batch((0..count).map(|index| {
+        (
+            Position(index as f32, 0.0, 0.0),
+            Velocity(1.0, 0.0, 0.0),
+            Health(100),
+        )
+    }))
+}
+
+fn spawn_despawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn_despawn");
+
+    group.bench_function("entity_builder", |b| {
+        let entity_container = EntityContainer::new();
+        b.iter(|| {
+            let entity_ids = (0..NUMBER_OF_ENTITIES)
+                .map(|index| {
+                    entity_container
+                        .entity_builder()
+                        .with_component(Position(index as f32, 0.0, 0.0))
+                        .with_component(Velocity(1.0, 0.0, 0.0))
+                        .with_component(Health(100))
+                        .build()
+                })
+                .collect::<Vec<EntityId>>();
+
+            let mut entity_container_guard = entity_container.lock();
+            for entity_id in entity_ids.iter() {
+                entity_container_guard.remove_entity(entity_id);
+            }
+        });
+    });
+
+    group.bench_function("spawn_batch", |b| {
+        let entity_container = EntityContainer::new();
+        b.iter(|| {
+            let entity_ids = spawn_entities(&entity_container, NUMBER_OF_ENTITIES);
+
+            let mut entity_container_guard = entity_container.lock();
+            for entity_id in entity_ids.iter() {
+                entity_container_guard.remove_entity(entity_id);
+            }
+        });
+    });
+
+    group.finish();
+}
+
+fn group_iteration(c: &mut Criterion) {
+    let entity_container = EntityContainer::new();
+    spawn_entities(&entity_container, NUMBER_OF_ENTITIES);
+    let entity_group = entity_container
+        .lock()
+        .entity_group(component_type_list!(Position, Velocity));
+
+    let mut group = c.benchmark_group("group_iteration");
+
+    group.bench_function("handler_for_entity", |b| {
+        b.iter(|| {
+            let mut entity_container_guard = entity_container.lock();
+            for entity_id in entity_group.iter_entity_ids() {
+                if let Some(mut entity_handler) =
+                    entity_container_guard.handler_for_entity(&entity_id)
+                {
+                    let velocity_x = entity_handler
+                        .get_component_ref::<Velocity>()
+                        .map(|velocity| velocity.0)
+                        .unwrap_or_default();
+                    entity_handler
+                        .change_component(|position: &mut Position| position.0 += velocity_x);
+                }
+            }
+        });
+    });
+
+    group.bench_function("query", |b| {
+        b.iter(|| {
+            let mut entity_container_guard = entity_container.lock();
+            for (position, velocity) in entity_container_guard.query::<(&mut Position, &Velocity)>()
+            {
+                position.0 += velocity.0;
+                position.1 += velocity.1;
+                position.2 += velocity.2;
+            }
+        });
+    });
+
+    group.finish();
+}
+
+fn event_fan_out(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_fan_out");
+
+    for number_of_groups in [1, 10, 100] {
+        let entity_container = EntityContainer::new();
+        let entity_groups = (0..number_of_groups)
+            .map(|_| {
+                entity_container
+                    .lock()
+                    .entity_group(component_type_list!(Position, Health))
+            })
+            .collect::<Vec<EntityGroup>>();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(number_of_groups),
+            &number_of_groups,
+            |b, _| {
+                b.iter(|| {
+                    let entity_ids = spawn_entities(&entity_container, 1_000);
+
+                    let mut entity_container_guard = entity_container.lock();
+                    for entity_id in entity_ids.iter() {
+                        if let Some(mut entity_handler) =
+                            entity_container_guard.handler_for_entity(entity_id)
+                        {
+                            entity_handler.change_component(|health: &mut Health| health.0 -= 1);
+                        }
+                    }
+                    for entity_id in entity_ids.iter() {
+                        entity_container_guard.remove_entity(entity_id);
+                    }
+                });
+            },
+        );
+
+        black_box(entity_groups);
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, spawn_despawn, group_iteration, event_fan_out);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+// spawns and despawns entities in waves while systems iterate over them, the memory stats and the
+// durations of the waves are printed, e.g. cargo run --release --bin stress_test -- 100 50000
+
+use std::time::{Duration, Instant};
+
+use entity_component::{component_type_list, EntityContainer, EntityGroup, EntityId};
+
+struct Position(f32, f32, f32);
+
+struct Velocity(f32, f32, f32);
+
+struct Lifetime(u32);
+
+#[derive(Default)]
+struct EnemyTag;
+
+fn spawn_wave(entity_container: &EntityContainer, wave_size: usize, wave: usize) -> Vec<EntityId> {
+    entity_container.spawn_batch((0..wave_size).map(|index| {
+        (
+            Position(index as f32, wave as f32, 0.0),
+            Velocity(1.0, 0.0, -1.0),
+            Lifetime((index % 10) as u32 + 1),
+        )
+    }))
+}
+
+fn update_positions(entity_container: &EntityContainer) {
+    let mut entity_container_guard = entity_container.lock();
+    for (position, velocity) in entity_container_guard.query::<(&mut Position, &Velocity)>() {
+        position.0 += velocity.0;
+        position.1 += velocity.1;
+        position.2 += velocity.2;
+    }
+}
+
+// ticks the lifetimes down and despawns the entities whose lifetime is over
+fn despawn_expired(entity_container: &EntityContainer, entity_group: &EntityGroup) -> usize {
+    let mut entity_container_guard = entity_container.lock();
+    let mut expired_entity_ids = Vec::new();
+    for entity_id in entity_group.iter_entity_ids() {
+        if let Some(mut entity_handler) = entity_container_guard.handler_for_entity(&entity_id) {
+            let is_expired = entity_handler.change_component(|lifetime: &mut Lifetime| {
+                lifetime.0 = lifetime.0.saturating_sub(1);
+                lifetime.0 == 0
+            });
+            if is_expired == Some(true) {
+                expired_entity_ids.push(entity_id);
+            }
+        }
+    }
+
+    for entity_id in expired_entity_ids.iter() {
+        entity_container_guard.remove_entity(entity_id);
+    }
+
+    expired_entity_ids.len()
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let number_of_waves = args
+        .next()
+        .and_then(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(50);
+    let wave_size = args
+        .next()
+        .and_then(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(10_000);
+
+    let entity_container = EntityContainer::new();
+    entity_container.register_tag::<EnemyTag>();
+
+    let lifetime_entity_group = entity_container
+        .lock()
+        .entity_group(component_type_list!(Lifetime));
+    // the groups are not read, they are only kept up to date, like the groups of idle systems
+    let _entity_groups = (0..10)
+        .map(|_| {
+            entity_container
+                .lock()
+                .entity_group(component_type_list!(Position, Velocity))
+        })
+        .collect::<Vec<EntityGroup>>();
+
+    let mut longest_wave_duration = Duration::ZERO;
+    let start = Instant::now();
+    for wave in 0..number_of_waves {
+        let wave_start = Instant::now();
+
+        let entity_ids = spawn_wave(&entity_container, wave_size, wave);
+        let mut entity_container_guard = entity_container.lock();
+        for entity_id in entity_ids.iter().step_by(2) {
+            if let Some(mut entity_handler) = entity_container_guard.handler_for_entity(entity_id) {
+                entity_handler.add_component(EnemyTag);
+            }
+        }
+        drop(entity_container_guard);
+
+        update_positions(&entity_container);
+        let number_of_despawned_entities =
+            despawn_expired(&entity_container, &lifetime_entity_group);
+
+        let wave_duration = wave_start.elapsed();
+        longest_wave_duration = longest_wave_duration.max(wave_duration);
+
+        let memory_stats = entity_container.memory_stats();
+        println!(
+            "wave {wave}: {wave_duration:?}, despawned {number_of_despawned_entities}, entities {}, component memory {} bytes",
+            memory_stats.number_of_entities, memory_stats.component_memory_bytes,
+        );
+    }
+
+    entity_container.compact();
+    let memory_stats = entity_container.memory_stats();
+    println!(
+        "{number_of_waves} waves of {wave_size} entities in {:?}, longest wave {longest_wave_duration:?}, {} entities left",
+        start.elapsed(),
+        memory_stats.number_of_entities,
+    );
+}
@@ -3,6 +3,7 @@ use std::any::Any;
 pub trait ComponentTrait: Any + Send {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn component_type_name(&self) -> &'static str;
 }
 
 impl<T: Any + Send> ComponentTrait for T {
@@ -13,4 +14,8 @@ impl<T: Any + Send> ComponentTrait for T {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn component_type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
 }
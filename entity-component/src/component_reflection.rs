@@ -0,0 +1,195 @@
+use ron::value::RawValue;
+
+use crate::component_storage::ComponentAnyGuard;
+
+use super::{
+    entity_container::next_change_tick, entity_modified_event::EntityModifiedEvent, ComponentId,
+    ComponentRegistry, EntityHandler,
+};
+
+#[derive(Debug, Clone)]
+pub enum ComponentReflectionError {
+    InvalidComponentId,
+    UnregisteredComponent { name: String },
+    CannotReflect { msg: String },
+    CannotApply { msg: String },
+}
+
+// the value is in the ron format, so a tool can show and edit the components it does not know
+#[derive(Clone)]
+pub struct ReflectedComponent {
+    pub component_id: ComponentId,
+    pub name: String,
+    pub value: String,
+}
+
+impl EntityHandler<'_, '_> {
+    // returns none if the component does not belong to the entity
+    pub fn get_component_dyn(&self, component_id: &ComponentId) -> Option<ComponentAnyGuard> {
+        if !self.entity.component_ids.contains(component_id) {
+            return None;
+        }
+
+        let component_storages = unsafe { &*self.component_storages_guard };
+        component_storages.get_component_ref_any(component_id)
+    }
+
+    pub fn reflect_component(
+        &self,
+        component_registry: &ComponentRegistry,
+        component_id: &ComponentId,
+    ) -> Result<ReflectedComponent, ComponentReflectionError> {
+        let component = self
+            .get_component_dyn(component_id)
+            .ok_or(ComponentReflectionError::InvalidComponentId)?;
+
+        let Some((name, raw_value)) =
+            component_registry.serialize_component(&component_id.component_type_id, &*component)
+        else {
+            return Err(ComponentReflectionError::UnregisteredComponent {
+                name: component.component_type_name().to_string(),
+            });
+        };
+        let raw_value = raw_value.map_err(|msg| ComponentReflectionError::CannotReflect { msg })?;
+
+        Ok(ReflectedComponent {
+            component_id: component_id.clone(),
+            name: name.to_string(),
+            value: raw_value.get_ron().to_string(),
+        })
+    }
+
+    // the components that are not registered are left out
+    pub fn reflect_components(
+        &self,
+        component_registry: &ComponentRegistry,
+    ) -> Vec<ReflectedComponent> {
+        self.entity
+            .component_ids
+            .iter()
+            .filter_map(|component_id| {
+                self.reflect_component(component_registry, component_id)
+                    .ok()
+            })
+            .collect()
+    }
+
+    // the component is replaced by the deserialized value, which counts as a change
+    pub fn apply_component_value(
+        &mut self,
+        component_registry: &ComponentRegistry,
+        component_id: &ComponentId,
+        value: &str,
+    ) -> Result<(), ComponentReflectionError> {
+        let index = self
+            .entity
+            .component_ids
+            .iter()
+            .position(|component_id_ref| *component_id_ref == *component_id)
+            .ok_or(ComponentReflectionError::InvalidComponentId)?;
+
+        let raw_value = RawValue::from_ron(value)
+            .map_err(|e| ComponentReflectionError::CannotApply { msg: e.to_string() })?;
+        let Some(component) = component_registry
+            .deserialize_component_of_type(&component_id.component_type_id, raw_value)
+        else {
+            return Err(ComponentReflectionError::UnregisteredComponent {
+                name: self
+                    .get_component_dyn(component_id)
+                    .map(|component| component.component_type_name().to_string())
+                    .unwrap_or_default(),
+            });
+        };
+        let component = component.map_err(|msg| ComponentReflectionError::CannotApply { msg })?;
+
+        let component_storage = unsafe {
+            (*self.component_storages_guard)
+                .component_storage_mut_for_type_id(&component_id.component_type_id)
+        };
+        if component_storage
+            .replace_component_any(component_id, component)
+            .is_none()
+        {
+            return Err(ComponentReflectionError::InvalidComponentId);
+        }
+
+        self.entity.component_change_ticks[index] = next_change_tick(self.change_tick);
+        self.entity_modified_event_guard
+            .trigger(&EntityModifiedEvent::ComponentChanged {
+                entity_id: self.entity.id,
+                component_id: component_id.clone(),
+                component_ids: &self.entity.component_ids,
+            });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::EntityContainer;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Position {
+        x: f32,
+        y: f32,
+    }
+
+    // not registered, so a tool cannot see into it
+    struct Handler;
+
+    #[test]
+    fn inspect_and_edit_components_dynamically() {
+        let mut component_registry = ComponentRegistry::new();
+        component_registry.register_component_with_name::<Position>("position");
+
+        let entity_container = EntityContainer::new();
+        let entity_id = entity_container
+            .entity_builder()
+            .with_component(Position { x: 1.0, y: 2.0 })
+            .with_component(Handler)
+            .build();
+
+        let mut entity_container_guard = entity_container.lock();
+        let mut handler = entity_container_guard
+            .handler_for_entity(&entity_id)
+            .unwrap();
+
+        let reflected_components = handler.reflect_components(&component_registry);
+        assert_eq!(1, reflected_components.len());
+        assert_eq!("position", reflected_components[0].name);
+
+        let position_id = reflected_components[0].component_id.clone();
+        assert!(handler
+            .get_component_dyn(&position_id)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Position>()
+            .is_some());
+
+        handler
+            .apply_component_value(&component_registry, &position_id, "(x: 3.0, y: 4.0)")
+            .unwrap();
+        assert_eq!(
+            Position { x: 3.0, y: 4.0 },
+            *handler.get_component_ref::<Position>().unwrap()
+        );
+        assert!(matches!(
+            handler.apply_component_value(&component_registry, &position_id, "(z: 1)"),
+            Err(ComponentReflectionError::CannotApply { .. })
+        ));
+
+        let handler_id = handler
+            .iter_entity_component_ids()
+            .find(|component_id| component_id.is_component_type_of::<Handler>())
+            .cloned()
+            .unwrap();
+        assert!(matches!(
+            handler.reflect_component(&component_registry, &handler_id),
+            Err(ComponentReflectionError::UnregisteredComponent { .. })
+        ));
+    }
+}
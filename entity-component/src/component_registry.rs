@@ -0,0 +1,155 @@
+use std::{any::TypeId, collections::BTreeMap};
+
+use ron::value::RawValue;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::component::ComponentTrait;
+
+type SerializeComponentFn = fn(&dyn ComponentTrait) -> Result<Box<RawValue>, String>;
+pub(super) type DeserializeComponentFn = fn(&RawValue) -> Result<Box<dyn ComponentTrait>, String>;
+type CloneComponentFn = fn(&dyn ComponentTrait) -> Box<dyn ComponentTrait>;
+
+struct RegisteredComponent {
+    name: String,
+    serialize: SerializeComponentFn,
+    deserialize: DeserializeComponentFn,
+}
+
+// the components that are not registered are left out of the serialized entities,
+// e.g. the handlers of the renderer
+#[derive(Default)]
+pub struct ComponentRegistry {
+    components: BTreeMap<TypeId, RegisteredComponent>,
+    type_ids_by_name: BTreeMap<String, TypeId>,
+    clone_fns: BTreeMap<TypeId, CloneComponentFn>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the name of the type is used in the serialized entities
+    pub fn register_component<ComponentType>(&mut self)
+    where
+        ComponentType: ComponentTrait + Serialize + DeserializeOwned,
+    {
+        self.register_component_with_name::<ComponentType>(std::any::type_name::<ComponentType>());
+    }
+
+    // a name that does not change when the type is moved, so the saved worlds stay loadable
+    pub fn register_component_with_name<ComponentType>(&mut self, name: &str)
+    where
+        ComponentType: ComponentTrait + Serialize + DeserializeOwned,
+    {
+        let component_type_id = TypeId::of::<ComponentType>();
+
+        if let Some(registered_component) = self.components.remove(&component_type_id) {
+            self.type_ids_by_name.remove(&registered_component.name);
+        }
+
+        self.components.insert(
+            component_type_id,
+            RegisteredComponent {
+                name: name.to_string(),
+                serialize: |component| match component.as_any().downcast_ref::<ComponentType>() {
+                    Some(component) => RawValue::from_rust(component).map_err(|e| e.to_string()),
+                    None => unreachable!(),
+                },
+                deserialize: |raw_value| {
+                    raw_value
+                        .into_rust::<ComponentType>()
+                        .map(|component| Box::new(component) as Box<dyn ComponentTrait>)
+                        .map_err(|e| e.to_string())
+                },
+            },
+        );
+        self.type_ids_by_name
+            .insert(name.to_string(), component_type_id);
+    }
+
+    // the serializable components are cloned by serializing and deserializing them, the rest of the
+    // components have to be registered as cloneable to be copied along with the entities
+    pub fn register_cloneable_component<ComponentType>(&mut self)
+    where
+        ComponentType: ComponentTrait + Clone,
+    {
+        self.clone_fns
+            .insert(TypeId::of::<ComponentType>(), |component| {
+                match component.as_any().downcast_ref::<ComponentType>() {
+                    Some(component) => Box::new(component.clone()),
+                    None => unreachable!(),
+                }
+            });
+    }
+
+    pub fn is_registered(&self, component_type_id: &TypeId) -> bool {
+        self.components.contains_key(component_type_id)
+    }
+
+    pub fn component_name(&self, component_type_id: &TypeId) -> Option<&str> {
+        self.components
+            .get(component_type_id)
+            .map(|registered_component| registered_component.name.as_str())
+    }
+
+    // the names of the registered components, e.g. for the add component menu of an inspector
+    pub fn iter_component_names(&self) -> impl Iterator<Item = &str> {
+        self.type_ids_by_name.keys().map(|name| name.as_str())
+    }
+
+    pub(super) fn serialize_component(
+        &self,
+        component_type_id: &TypeId,
+        component: &dyn ComponentTrait,
+    ) -> Option<(&str, Result<Box<RawValue>, String>)> {
+        let registered_component = self.components.get(component_type_id)?;
+        Some((
+            &registered_component.name,
+            (registered_component.serialize)(component),
+        ))
+    }
+
+    pub(super) fn clone_component(
+        &self,
+        component_type_id: &TypeId,
+        component: &dyn ComponentTrait,
+    ) -> Option<Result<Box<dyn ComponentTrait>, String>> {
+        if let Some(clone) = self.clone_fns.get(component_type_id) {
+            return Some(Ok(clone(component)));
+        }
+
+        let registered_component = self.components.get(component_type_id)?;
+        Some(
+            (registered_component.serialize)(component)
+                .and_then(|raw_value| (registered_component.deserialize)(&raw_value)),
+        )
+    }
+
+    pub(super) fn deserialize_component(
+        &self,
+        name: &str,
+        raw_value: &RawValue,
+    ) -> Option<(TypeId, Result<Box<dyn ComponentTrait>, String>)> {
+        let (component_type_id, deserialize) = self.component_deserializer(name)?;
+        Some((component_type_id, deserialize(raw_value)))
+    }
+
+    pub(super) fn deserialize_component_of_type(
+        &self,
+        component_type_id: &TypeId,
+        raw_value: &RawValue,
+    ) -> Option<Result<Box<dyn ComponentTrait>, String>> {
+        let registered_component = self.components.get(component_type_id)?;
+        Some((registered_component.deserialize)(raw_value))
+    }
+
+    pub(super) fn component_deserializer(
+        &self,
+        name: &str,
+    ) -> Option<(TypeId, DeserializeComponentFn)> {
+        let component_type_id = self.type_ids_by_name.get(name)?;
+        let registered_component = self.components.get(component_type_id)?;
+        Some((*component_type_id, registered_component.deserialize))
+    }
+}
@@ -0,0 +1,120 @@
+use super::{
+    component::ComponentTrait, entity_modified_event::EntityModifiedEvent, ComponentId,
+    EntityContainer, EntityId,
+};
+
+impl EntityContainer {
+    // the callback is called when the component is removed from an entity or its entity is
+    // removed, e.g. to release the rigid body of the entity, it runs while the entity container is
+    // locked, so it must not lock the container, the components of the migrated entities are moved
+    // instead of removed, so they are not reported
+    pub fn on_remove<ComponentType>(
+        &self,
+        callback: impl Fn(EntityId, &mut ComponentType) + Send + Sync + 'static,
+    ) where
+        ComponentType: ComponentTrait,
+    {
+        let subscription = self
+            .entity_modified_event
+            .lock()
+            .create_subscriber()
+            .subscribe(move |event| match event {
+                EntityModifiedEvent::ComponentRemoved {
+                    entity_id,
+                    component_id,
+                    ..
+                } => call_remove_callback(*entity_id, component_id, &callback),
+                EntityModifiedEvent::EntityRemoved {
+                    entity_id,
+                    component_ids,
+                } => {
+                    let component_ids_ref = unsafe { &**component_ids };
+                    for component_id in component_ids_ref.iter() {
+                        call_remove_callback(*entity_id, component_id, &callback);
+                    }
+                }
+                _ => (),
+            });
+
+        self.component_remove_subscriptions
+            .lock()
+            .push(subscription);
+    }
+}
+
+// the component is still in its storage, because the event holds its id
+fn call_remove_callback<ComponentType>(
+    entity_id: EntityId,
+    component_id: &ComponentId,
+    callback: &impl Fn(EntityId, &mut ComponentType),
+) where
+    ComponentType: ComponentTrait,
+{
+    if !component_id.is_component_type_of::<ComponentType>() {
+        return;
+    }
+
+    let mut components_guard = component_id.component_storage.lock();
+    if let Some(component) = components_guard
+        .get_mut(component_id.object_pool_index)
+        .and_then(|component_box| {
+            (**component_box)
+                .as_any_mut()
+                .downcast_mut::<ComponentType>()
+        })
+    {
+        callback(entity_id, component);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::migrate_entity;
+
+    struct RigidBodyHandler(u32);
+
+    #[test]
+    fn on_remove_is_called_for_removed_components_and_entities() {
+        let entity_container = EntityContainer::new();
+        let released_rigid_bodies = Arc::new(Mutex::new(Vec::new()));
+        entity_container.on_remove({
+            let released_rigid_bodies = released_rigid_bodies.clone();
+            move |_, rigid_body_handler: &mut RigidBodyHandler| {
+                released_rigid_bodies
+                    .lock()
+                    .unwrap()
+                    .push(rigid_body_handler.0)
+            }
+        });
+
+        let spawn = |index| {
+            entity_container
+                .entity_builder()
+                .with_component(RigidBodyHandler(index))
+                .build()
+        };
+        let removed_component_entity_id = spawn(1);
+        let removed_entity_id = spawn(2);
+        let migrated_entity_id = spawn(3);
+        spawn(4);
+
+        let mut entity_container_guard = entity_container.lock();
+        entity_container_guard
+            .handler_for_entity(&removed_component_entity_id)
+            .unwrap()
+            .remove_component::<RigidBodyHandler>();
+        entity_container_guard.remove_entity(&removed_entity_id);
+        drop(entity_container_guard);
+
+        migrate_entity(
+            &entity_container,
+            &EntityContainer::new(),
+            &migrated_entity_id,
+        );
+
+        assert_eq!(vec![1, 2], *released_rigid_bodies.lock().unwrap());
+    }
+}
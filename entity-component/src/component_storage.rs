@@ -93,6 +93,12 @@ impl<'a, ComponentType: ComponentTrait> DerefMut for ComponentMutGuard<'a, Compo
 pub(super) struct ComponentStorage {
     pub component_type_id: TypeId,
     pub components: ArcMutex<ObjectPool<Box<dyn ComponentTrait>>>,
+    // recorded when the first component is added, the storage only knows the id of the type
+    pub component_type_name: Option<&'static str>,
+    pub component_size: usize,
+    // the tags cannot differ from each other, so every entity refers to the same object of the pool
+    pub create_tag: Option<fn() -> Box<dyn ComponentTrait>>,
+    pub shared_component_id: Option<ComponentId>,
 }
 
 impl ComponentStorage {
@@ -100,9 +106,17 @@ impl ComponentStorage {
         Self {
             component_type_id,
             components: arc_mutex_new(ObjectPool::new()),
+            component_type_name: None,
+            component_size: 0,
+            create_tag: None,
+            shared_component_id: None,
         }
     }
 
+    pub fn number_of_components(&self) -> usize {
+        self.components.lock().len()
+    }
+
     pub fn add_component<ComponentType>(&mut self, component: ComponentType) -> Option<ComponentId>
     where
         ComponentType: ComponentTrait,
@@ -119,12 +133,50 @@ impl ComponentStorage {
             return None;
         }
 
-        Some(ComponentId {
+        if self.component_type_name.is_none() {
+            self.component_type_name = Some(component.component_type_name());
+            self.component_size = std::mem::size_of_val(&*component);
+        }
+
+        if let Some(shared_component_id) = self.shared_component_id.as_ref() {
+            return Some(shared_component_id.clone());
+        }
+
+        let component_id = ComponentId {
             component_type_id: *component_type_id,
             object_pool_index: self.components.lock().create_object(component),
             usage_counter: UsageCounter::new(),
             component_storage: self.components.clone(),
-        })
+        };
+
+        if self.create_tag.is_some() {
+            self.shared_component_id = Some(component_id.clone());
+        }
+
+        Some(component_id)
+    }
+
+    // the components that were added before keep their own objects
+    pub fn make_tag_storage(&mut self, create_tag: fn() -> Box<dyn ComponentTrait>) {
+        self.create_tag = Some(create_tag);
+    }
+
+    pub fn is_shared_component(&self, id: &ComponentId) -> bool {
+        self.shared_component_id.as_ref() == Some(id)
+    }
+
+    pub fn replace_component_any(
+        &mut self,
+        id: &ComponentId,
+        component: Box<dyn ComponentTrait>,
+    ) -> Option<Box<dyn ComponentTrait>> {
+        if self.component_type_id != id.component_type_id {
+            return None;
+        }
+
+        let mut components_guard = self.components.lock();
+        let component_box = components_guard.get_mut(id.object_pool_index)?;
+        Some(std::mem::replace(component_box, component))
     }
 
     pub fn get_component_ref<ComponentType>(
@@ -138,8 +190,11 @@ impl ComponentStorage {
             return None;
         }
 
-        let mut components_guard = self.components.lock();
-        components_guard.get_mut(id.object_pool_index)?;
+        let components_guard = self.components.lock();
+        // the component of a migrated entity is replaced, while its id is still referenced
+        (**components_guard.get_ref(id.object_pool_index)?)
+            .as_any()
+            .downcast_ref::<ComponentType>()?;
         Some(ComponentGuard {
             components_guard,
             object_pool_index: id.object_pool_index,
@@ -1,19 +1,127 @@
 use std::any::TypeId;
 
+// the plain types and With<A> are required, the entities with Without<B> are excluded, and the
+// changes of Option<C> are reported to the entity group without being required,
+// e.g. component_type_list![Position, Without<Camera>, Option<Velocity>]
 #[macro_export]
 macro_rules! component_type_list {
-    ($type0:ty $(, $types:ty)* $(,)?) => {
-        [
-            std::any::TypeId::of::<$type0>(),
-            $(std::any::TypeId::of::<$types>(),)*
-        ]
+    (@filter $filter:expr;) => {
+        $filter
     };
+    (@filter $filter:expr; With<$type:ty> $(, $($rest:tt)*)?) => {
+        $crate::component_type_list!(@filter $filter.with(std::any::TypeId::of::<$type>()); $($($rest)*)?)
+    };
+    (@filter $filter:expr; Without<$type:ty> $(, $($rest:tt)*)?) => {
+        $crate::component_type_list!(@filter $filter.without(std::any::TypeId::of::<$type>()); $($($rest)*)?)
+    };
+    (@filter $filter:expr; Option<$type:ty> $(, $($rest:tt)*)?) => {
+        $crate::component_type_list!(@filter $filter.optional(std::any::TypeId::of::<$type>()); $($($rest)*)?)
+    };
+    (@filter $filter:expr; $type:ty $(, $($rest:tt)*)?) => {
+        $crate::component_type_list!(@filter $filter.with(std::any::TypeId::of::<$type>()); $($($rest)*)?)
+    };
+    ($($entries:tt)+) => {
+        $crate::component_type_list!(@filter $crate::ComponentTypeFilter::new(); $($entries)+)
+    };
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ComponentTypeFilter {
+    required: Vec<TypeId>,
+    excluded: Vec<TypeId>,
+    optional: Vec<TypeId>,
+}
+
+impl ComponentTypeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, component_type_id: TypeId) -> Self {
+        insert_sorted(&mut self.required, component_type_id);
+        self
+    }
+
+    pub fn without(mut self, component_type_id: TypeId) -> Self {
+        insert_sorted(&mut self.excluded, component_type_id);
+        self
+    }
+
+    pub fn optional(mut self, component_type_id: TypeId) -> Self {
+        insert_sorted(&mut self.optional, component_type_id);
+        self
+    }
+
+    pub fn required_component_types(&self) -> &[TypeId] {
+        &self.required
+    }
+
+    pub fn excluded_component_types(&self) -> &[TypeId] {
+        &self.excluded
+    }
+
+    pub fn optional_component_types(&self) -> &[TypeId] {
+        &self.optional
+    }
+
+    // the component types of an entity are unique, so counting the required ones is enough
+    pub fn matches(&self, component_type_ids: impl Iterator<Item = TypeId>) -> bool {
+        let mut number_of_required_component_types = 0;
+        for component_type_id in component_type_ids {
+            if self.excluded.binary_search(&component_type_id).is_ok() {
+                return false;
+            }
+            if self.required.binary_search(&component_type_id).is_ok() {
+                number_of_required_component_types += 1;
+            }
+        }
+
+        number_of_required_component_types == self.required.len()
+    }
+
+    // the changes of the other components of the member entities are not reported
+    pub fn is_observed(&self, component_type_id: &TypeId) -> bool {
+        self.required.binary_search(component_type_id).is_ok()
+            || self.optional.binary_search(component_type_id).is_ok()
+    }
+}
+
+fn insert_sorted(type_list: &mut Vec<TypeId>, component_type_id: TypeId) {
+    if let Err(index) = type_list.binary_search(&component_type_id) {
+        type_list.insert(index, component_type_id);
+    }
+}
+
+pub trait ToComponentTypeFilter {
+    fn to_component_type_filter(self) -> ComponentTypeFilter;
+}
+
+impl ToComponentTypeFilter for ComponentTypeFilter {
+    fn to_component_type_filter(self) -> ComponentTypeFilter {
+        self
+    }
+}
+
+impl<T: AsRef<[TypeId]>> ToComponentTypeFilter for T {
+    fn to_component_type_filter(self) -> ComponentTypeFilter {
+        self.as_ref()
+            .iter()
+            .fold(ComponentTypeFilter::new(), |filter, component_type_id| {
+                filter.with(*component_type_id)
+            })
+    }
 }
 
 pub trait ToSortedComponentTypeList {
     fn to_sorted_component_type_list(self) -> Vec<TypeId>;
 }
 
+impl ToSortedComponentTypeList for ComponentTypeFilter {
+    fn to_sorted_component_type_list(self) -> Vec<TypeId> {
+        self.required
+    }
+}
+
 impl<T: AsRef<[TypeId]>> ToSortedComponentTypeList for T {
     fn to_sorted_component_type_list(self) -> Vec<TypeId> {
         let mut type_list = self.as_ref().to_vec();
@@ -56,4 +164,29 @@ mod tests {
         assert!(found_velocity);
         assert!(found_orientation);
     }
+
+    #[test]
+    fn excluded_and_optional_component_types() {
+        let filter = component_type_list![Position, Without<Velocity>, Option<Orientation>];
+
+        assert!(filter.matches([std::any::TypeId::of::<Position>()].into_iter()));
+        assert!(filter.matches(
+            [
+                std::any::TypeId::of::<Position>(),
+                std::any::TypeId::of::<Orientation>()
+            ]
+            .into_iter()
+        ));
+        assert!(!filter.matches(
+            [
+                std::any::TypeId::of::<Position>(),
+                std::any::TypeId::of::<Velocity>()
+            ]
+            .into_iter()
+        ));
+        assert!(!filter.matches([std::any::TypeId::of::<Orientation>()].into_iter()));
+
+        assert!(filter.is_observed(&std::any::TypeId::of::<Orientation>()));
+        assert!(!filter.is_observed(&std::any::TypeId::of::<Velocity>()));
+    }
 }
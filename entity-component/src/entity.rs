@@ -3,4 +3,6 @@ use super::{ComponentId, EntityId};
 pub(super) struct Entity {
     pub(super) id: EntityId,
     pub(super) component_ids: Vec<ComponentId>,
+    // the change tick of every component, in the order of the component ids
+    pub(super) component_change_ticks: Vec<u64>,
 }
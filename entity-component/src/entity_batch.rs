@@ -0,0 +1,48 @@
+use std::any::TypeId;
+
+use super::{component::ComponentTrait, EntityBuilder, EntityContainer, EntityId};
+
+// the components of one entity of a batch, e.g. (Position, Velocity)
+pub trait ComponentBundle {
+    fn into_components(self) -> Vec<(TypeId, Box<dyn ComponentTrait>)>;
+}
+
+impl ComponentBundle for EntityBuilder {
+    fn into_components(self) -> Vec<(TypeId, Box<dyn ComponentTrait>)> {
+        self.components
+    }
+}
+
+macro_rules! component_bundle_tuple {
+    ( $( $param:ident ),+ ) => {
+        impl<$( $param: ComponentTrait ),+> ComponentBundle for ( $( $param, )+ ) {
+            #[allow(non_snake_case)]
+            fn into_components(self) -> Vec<(TypeId, Box<dyn ComponentTrait>)> {
+                let ( $( $param, )+ ) = self;
+                vec![
+                    $( (TypeId::of::<$param>(), Box::new($param) as Box<dyn ComponentTrait>), )+
+                ]
+            }
+        }
+    };
+}
+
+component_bundle_tuple!(A);
+component_bundle_tuple!(A, B);
+component_bundle_tuple!(A, B, C);
+component_bundle_tuple!(A, B, C, D);
+component_bundle_tuple!(A, B, C, D, E);
+component_bundle_tuple!(A, B, C, D, E, F);
+component_bundle_tuple!(A, B, C, D, E, F, G);
+component_bundle_tuple!(A, B, C, D, E, F, G, H);
+
+impl EntityContainer {
+    // the entities are added with a single lock of the container, the ids are in the order of the
+    // bundles
+    pub fn spawn_batch<Bundle: ComponentBundle>(
+        &self,
+        bundles: impl IntoIterator<Item = Bundle>,
+    ) -> Vec<EntityId> {
+        self.lock().add_entities(bundles)
+    }
+}
@@ -6,7 +6,7 @@ use super::{component::ComponentTrait, EntityId};
 
 pub struct EntityBuilder {
     entity_container: EntityContainer,
-    components: Vec<(TypeId, Box<dyn ComponentTrait>)>,
+    pub(super) components: Vec<(TypeId, Box<dyn ComponentTrait>)>,
 }
 
 impl EntityBuilder {
@@ -42,6 +42,18 @@ impl EntityBuilder {
         self
     }
 
+    pub(super) fn with_component_any(
+        mut self,
+        component_type_id: TypeId,
+        component: Box<dyn ComponentTrait>,
+    ) -> Self {
+        self.components
+            .retain(|(type_id, _)| *type_id != component_type_id);
+        self.components.push((component_type_id, component));
+
+        self
+    }
+
     pub fn build(self) -> EntityId {
         let mut entity_container_guard = self.entity_container.lock();
         entity_container_guard.add_entity(self.components.into_iter())
@@ -0,0 +1,115 @@
+use super::{ComponentId, ComponentRegistry, EntityContainerGuard, EntityId};
+
+#[derive(Debug, Clone)]
+pub enum EntityCloneError {
+    InvalidEntityId(EntityId),
+    CannotCloneComponent { msg: String },
+}
+
+impl EntityContainerGuard<'_> {
+    // the components that the registry can neither clone nor serialize are left out, e.g. the
+    // handlers of the renderer and the hierarchy components, so the clone is a root entity
+    pub fn clone_entity(
+        &mut self,
+        component_registry: &ComponentRegistry,
+        entity_id: &EntityId,
+    ) -> Result<EntityId, EntityCloneError> {
+        let component_ids = self
+            .handler_for_entity(entity_id)
+            .ok_or(EntityCloneError::InvalidEntityId(*entity_id))?
+            .iter_entity_component_ids()
+            .cloned()
+            .collect::<Vec<ComponentId>>();
+
+        let mut components = Vec::with_capacity(component_ids.len());
+        for component_id in component_ids.iter() {
+            let Some(component) = self.component_storage().get_component_ref_any(component_id)
+            else {
+                continue;
+            };
+
+            if let Some(cloned_component) =
+                component_registry.clone_component(&component_id.component_type_id, &*component)
+            {
+                components.push((
+                    component_id.component_type_id,
+                    cloned_component
+                        .map_err(|msg| EntityCloneError::CannotCloneComponent { msg })?,
+                ));
+            }
+        }
+
+        Ok(self.add_entity(components.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::EntityContainer;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Health(u32);
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Color(u8, u8, u8);
+
+    // neither serializable nor cloneable
+    struct Handler;
+
+    #[test]
+    fn clone_entity_with_registered_components() {
+        let mut component_registry = ComponentRegistry::new();
+        component_registry.register_component::<Health>();
+        component_registry.register_cloneable_component::<Color>();
+
+        let entity_container = EntityContainer::new();
+        let parent_id = entity_container.entity_builder().build();
+        let entity_id = entity_container
+            .entity_builder()
+            .with_component(Health(42))
+            .with_component(Color(255, 0, 0))
+            .with_component(Handler)
+            .build();
+
+        let mut entity_container_guard = entity_container.lock();
+        entity_container_guard
+            .attach_child(&parent_id, &entity_id)
+            .unwrap();
+
+        let cloned_id = entity_container_guard
+            .clone_entity(&component_registry, &entity_id)
+            .unwrap();
+        assert_ne!(entity_id, cloned_id);
+
+        // changing the clone does not change the original
+        let mut handler = entity_container_guard
+            .handler_for_entity(&cloned_id)
+            .unwrap();
+        handler.change_component(|health: &mut Health| health.0 = 1);
+        assert_eq!(
+            Color(255, 0, 0),
+            *handler.get_component_ref::<Color>().unwrap()
+        );
+        assert!(handler.get_component_ref::<Handler>().is_none());
+
+        let handler = entity_container_guard
+            .handler_for_entity(&entity_id)
+            .unwrap();
+        assert_eq!(Health(42), *handler.get_component_ref::<Health>().unwrap());
+
+        assert_eq!(None, entity_container_guard.parent_of(&cloned_id));
+        assert_eq!(
+            vec![entity_id],
+            entity_container_guard.children_of(&parent_id)
+        );
+
+        entity_container_guard.remove_entity(&entity_id);
+        assert!(matches!(
+            entity_container_guard.clone_entity(&component_registry, &entity_id),
+            Err(EntityCloneError::InvalidEntityId(_))
+        ));
+    }
+}
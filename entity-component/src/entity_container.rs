@@ -1,37 +1,81 @@
-use std::any::TypeId;
+use std::{
+    any::TypeId,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use bytifex_utils::{
     containers::object_pool::{ObjectPool, ObjectPoolIndex, ObjectPoolIterMut},
     sync::{
         callback_event,
-        types::{arc_mutex_new, ArcMutex, MutexGuard},
+        types::{
+            arc_mutex_new, arc_rw_lock_new, ArcMutex, ArcRwLock, MutexGuard, RwLockWriteGuard,
+        },
     },
 };
 
 use super::{
-    component::ComponentTrait, component_type_list::ToSortedComponentTypeList, entity::Entity,
-    entity_modified_event::EntityModifiedEvent,
-    multi_type_component_storage::MultiTypeComponentStorage, ComponentId, EntityBuilder,
-    EntityGroup, EntityHandler,
+    component::ComponentTrait, component_type_list::ToComponentTypeFilter, entity::Entity,
+    entity_modified_event::EntityModifiedEvent, entity_name::EntityNameIndex,
+    multi_type_component_storage::MultiTypeComponentStorage, ComponentBundle, ComponentId,
+    EntityBuilder, EntityGroup, EntityHandler, EntityQuery, EntityQueryIter, Resources,
 };
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub struct EntityId(pub ObjectPoolIndex);
+pub struct EntityId {
+    index: ObjectPoolIndex,
+    // unique for every entity of the container, so the id of a removed entity does not refer to
+    // the entity that reuses its slot
+    generation: u64,
+}
+
+impl EntityId {
+    // the slot of the entity, it is reused after the entity is removed
+    pub fn index(&self) -> ObjectPoolIndex {
+        self.index
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
 
 pub struct EntityContainerGuard<'entity_container_guards> {
-    entities_guard: MutexGuard<'entity_container_guards, ObjectPool<Entity>>,
-    component_storages_guard: MutexGuard<'entity_container_guards, MultiTypeComponentStorage>,
+    pub(super) entities_guard: RwLockWriteGuard<'entity_container_guards, ObjectPool<Entity>>,
+    pub(super) component_storages_guard:
+        RwLockWriteGuard<'entity_container_guards, MultiTypeComponentStorage>,
 
     entity_modified_event_guard:
         MutexGuard<'entity_container_guards, callback_event::Sender<EntityModifiedEvent>>,
+
+    change_tick: Arc<AtomicU64>,
+    entity_generation: Arc<AtomicU64>,
+    pub(super) entity_name_index: ArcMutex<EntityNameIndex>,
 }
 
 #[derive(Clone)]
 pub struct EntityContainer {
-    entities: ArcMutex<ObjectPool<Entity>>,
-    component_storages: ArcMutex<MultiTypeComponentStorage>,
+    // the readers of the components do not block each other, see EntityContainer::read
+    pub(super) entities: ArcRwLock<ObjectPool<Entity>>,
+    pub(super) component_storages: ArcRwLock<MultiTypeComponentStorage>,
 
-    entity_modified_event: ArcMutex<callback_event::Sender<EntityModifiedEvent>>,
+    pub(super) entity_modified_event: ArcMutex<callback_event::Sender<EntityModifiedEvent>>,
+
+    // incremented by every component change, the components remember the tick of their last change
+    pub(super) change_tick: Arc<AtomicU64>,
+    // incremented by every added entity
+    entity_generation: Arc<AtomicU64>,
+
+    // locked separately from the entities, e.g. the clock and the input state of the game
+    resources: ArcMutex<Resources>,
+
+    entity_name_index: ArcMutex<EntityNameIndex>,
+    _entity_name_index_subscription: ArcMutex<callback_event::Subscription<EntityModifiedEvent>>,
+
+    pub(super) component_remove_subscriptions:
+        ArcMutex<Vec<callback_event::Subscription<EntityModifiedEvent>>>,
 }
 
 impl Default for EntityContainer {
@@ -42,33 +86,65 @@ impl Default for EntityContainer {
 
 impl EntityContainer {
     pub fn new() -> Self {
+        let entity_modified_event = arc_mutex_new(callback_event::Sender::new());
+
+        let entity_name_index = arc_mutex_new(EntityNameIndex::default());
+        let entity_name_index_subscription =
+            entity_modified_event.lock().create_subscriber().subscribe({
+                let entity_name_index = entity_name_index.clone();
+                move |event| {
+                    entity_name_index
+                        .lock()
+                        .process_entity_modified_event(event)
+                }
+            });
+
         Self {
-            entities: arc_mutex_new(ObjectPool::new()),
-            component_storages: arc_mutex_new(MultiTypeComponentStorage::new()),
+            entities: arc_rw_lock_new(ObjectPool::new()),
+            component_storages: arc_rw_lock_new(MultiTypeComponentStorage::new()),
+
+            entity_modified_event,
+
+            change_tick: Arc::new(AtomicU64::new(0)),
+            entity_generation: Arc::new(AtomicU64::new(0)),
+
+            resources: arc_mutex_new(Resources::new()),
 
-            entity_modified_event: arc_mutex_new(callback_event::Sender::new()),
+            entity_name_index,
+            _entity_name_index_subscription: arc_mutex_new(entity_name_index_subscription),
+
+            component_remove_subscriptions: arc_mutex_new(Vec::new()),
         }
     }
 
     pub fn lock(&self) -> EntityContainerGuard<'_> {
         EntityContainerGuard {
-            entities_guard: self.entities.lock(),
-            component_storages_guard: self.component_storages.lock(),
+            entities_guard: self.entities.write(),
+            component_storages_guard: self.component_storages.write(),
 
             entity_modified_event_guard: self.entity_modified_event.lock(),
+
+            change_tick: self.change_tick.clone(),
+            entity_generation: self.entity_generation.clone(),
+            entity_name_index: self.entity_name_index.clone(),
         }
     }
 
     pub fn entity_builder(&self) -> EntityBuilder {
         EntityBuilder::new(self.clone())
     }
+
+    pub fn is_alive(&self, entity_id: &EntityId) -> bool {
+        self.lock().is_alive(entity_id)
+    }
+
+    pub fn resources(&self) -> MutexGuard<'_, Resources> {
+        self.resources.lock()
+    }
 }
 
 impl<'entity_container_guards> EntityContainerGuard<'entity_container_guards> {
-    pub fn entity_group(
-        &mut self,
-        component_type_list: impl ToSortedComponentTypeList,
-    ) -> EntityGroup {
+    pub fn entity_group(&mut self, component_type_list: impl ToComponentTypeFilter) -> EntityGroup {
         EntityGroup::new(
             self,
             component_type_list,
@@ -76,6 +152,11 @@ impl<'entity_container_guards> EntityContainerGuard<'entity_container_guards> {
         )
     }
 
+    // the components changed after this tick are reported by the queries with Changed<T>
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::SeqCst)
+    }
+
     pub fn component_storage(&self) -> &MultiTypeComponentStorage {
         &self.component_storages_guard
     }
@@ -83,6 +164,68 @@ impl<'entity_container_guards> EntityContainerGuard<'entity_container_guards> {
     pub fn add_entity(
         &mut self,
         components_iter: impl Iterator<Item = (TypeId, Box<dyn ComponentTrait>)>,
+    ) -> EntityId {
+        let id = self.create_entity(components_iter);
+
+        if let Some(entity) = self.entities_guard.get_mut(id.index) {
+            self.entity_modified_event_guard
+                .trigger(&EntityModifiedEvent::EntityAdded {
+                    entity_id: id,
+                    component_ids: &entity.component_ids,
+                });
+        } else {
+            unreachable!();
+        }
+
+        id
+    }
+
+    // the consecutive entities with the same component types are reported by a single event, so
+    // the entity groups are notified once for the whole batch
+    pub fn add_entities<Bundle: ComponentBundle>(
+        &mut self,
+        bundles: impl IntoIterator<Item = Bundle>,
+    ) -> Vec<EntityId> {
+        let mut entity_ids = Vec::new();
+        let mut batch_entity_ids = Vec::new();
+        let mut batch_component_type_ids = Vec::new();
+
+        for bundle in bundles {
+            let components = bundle.into_components();
+            let mut component_type_ids = components
+                .iter()
+                .map(|(type_id, _)| *type_id)
+                .collect::<Vec<TypeId>>();
+            component_type_ids.sort();
+
+            if component_type_ids != batch_component_type_ids {
+                self.trigger_entities_added(&batch_entity_ids, &batch_component_type_ids);
+                entity_ids.append(&mut batch_entity_ids);
+                batch_component_type_ids = component_type_ids;
+            }
+
+            batch_entity_ids.push(self.create_entity(components.into_iter()));
+        }
+
+        self.trigger_entities_added(&batch_entity_ids, &batch_component_type_ids);
+        entity_ids.append(&mut batch_entity_ids);
+
+        entity_ids
+    }
+
+    fn trigger_entities_added(&self, entity_ids: &[EntityId], component_type_ids: &[TypeId]) {
+        if !entity_ids.is_empty() {
+            self.entity_modified_event_guard
+                .trigger(&EntityModifiedEvent::EntitiesAdded {
+                    entity_ids,
+                    component_type_ids,
+                });
+        }
+    }
+
+    fn create_entity(
+        &mut self,
+        components_iter: impl Iterator<Item = (TypeId, Box<dyn ComponentTrait>)>,
     ) -> EntityId {
         let component_ids: Vec<ComponentId> = components_iter
             .into_iter()
@@ -98,19 +241,20 @@ impl<'entity_container_guards> EntityContainerGuard<'entity_container_guards> {
             })
             .collect();
 
-        let id = EntityId(self.entities_guard.create_object(Entity {
-            id: EntityId(ObjectPoolIndex::invalid()),
+        let change_tick = next_change_tick(&self.change_tick);
+        let generation = self.entity_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let index = self.entities_guard.create_object(Entity {
+            id: EntityId {
+                index: ObjectPoolIndex::invalid(),
+                generation,
+            },
+            component_change_ticks: vec![change_tick; component_ids.len()],
             component_ids,
-        }));
+        });
+        let id = EntityId { index, generation };
 
-        if let Some(entity) = self.entities_guard.get_mut(id.0) {
+        if let Some(entity) = self.entities_guard.get_mut(id.index) {
             entity.id = id;
-
-            self.entity_modified_event_guard
-                .trigger(&EntityModifiedEvent::EntityAdded {
-                    entity_id: id,
-                    component_ids: &entity.component_ids,
-                });
         } else {
             unreachable!();
         }
@@ -118,8 +262,21 @@ impl<'entity_container_guards> EntityContainerGuard<'entity_container_guards> {
         id
     }
 
+    // the ids of the removed entities stay invalid, even after their slots are reused
+    pub fn is_alive(&self, entity_id: &EntityId) -> bool {
+        self.entities_guard
+            .get_ref(entity_id.index)
+            .is_some_and(|entity| entity.id == *entity_id)
+    }
+
     pub fn remove_entity(&mut self, entity_id: &EntityId) -> bool {
-        if let Some(entity) = self.entities_guard.release_object(entity_id.0) {
+        if !self.is_alive(entity_id) {
+            return false;
+        }
+
+        self.detach_from_hierarchy(entity_id);
+
+        if let Some(entity) = self.entities_guard.release_object(entity_id.index) {
             self.entity_modified_event_guard
                 .trigger(&EntityModifiedEvent::EntityRemoved {
                     entity_id: *entity_id,
@@ -137,17 +294,43 @@ impl<'entity_container_guards> EntityContainerGuard<'entity_container_guards> {
             inner_iterator: self.entities_guard.iter_mut(),
             component_storages_guard: &mut self.component_storages_guard,
             entity_modified_event_guard: &self.entity_modified_event_guard,
+            change_tick: &self.change_tick,
         }
     }
 
+    // iterates over the entities that have every queried component, e.g. (&Position, &mut Velocity)
+    pub fn query<'a, Query: EntityQuery<'a>>(
+        &'a mut self,
+    ) -> EntityQueryIter<'a, 'entity_container_guards, Query> {
+        self.query_since(0)
+    }
+
+    // the Changed<T> params of the query skip the components that did not change after the tick
+    pub fn query_since<'a, Query: EntityQuery<'a>>(
+        &'a mut self,
+        since_change_tick: u64,
+    ) -> EntityQueryIter<'a, 'entity_container_guards, Query> {
+        EntityQueryIter::new(
+            self.entities_guard.iter_mut(),
+            &self.component_storages_guard,
+            since_change_tick,
+            &self.change_tick,
+            &self.entity_modified_event_guard,
+        )
+    }
+
     pub fn handler_for_entity<'a>(
         &'a mut self,
         entity_id: &EntityId,
     ) -> Option<EntityHandler<'a, 'entity_container_guards>> {
         Some(EntityHandler {
             component_storages_guard: &mut self.component_storages_guard,
-            entity: self.entities_guard.get_mut(entity_id.0)?,
+            entity: self
+                .entities_guard
+                .get_mut(entity_id.index)
+                .filter(|entity| entity.id == *entity_id)?,
             entity_modified_event_guard: &self.entity_modified_event_guard,
+            change_tick: &self.change_tick,
         })
     }
 }
@@ -155,9 +338,10 @@ impl<'entity_container_guards> EntityContainerGuard<'entity_container_guards> {
 pub struct EntityContainerIter<'a, 'entity_container_guards> {
     inner_iterator: ObjectPoolIterMut<'a, Entity>,
     component_storages_guard:
-        &'a mut MutexGuard<'entity_container_guards, MultiTypeComponentStorage>,
+        &'a mut RwLockWriteGuard<'entity_container_guards, MultiTypeComponentStorage>,
     entity_modified_event_guard:
         &'a MutexGuard<'entity_container_guards, callback_event::Sender<EntityModifiedEvent>>,
+    change_tick: &'a AtomicU64,
 }
 
 impl<'a, 'entity_container_guards> Iterator for EntityContainerIter<'a, 'entity_container_guards> {
@@ -169,9 +353,14 @@ impl<'a, 'entity_container_guards> Iterator for EntityContainerIter<'a, 'entity_
                 entity,
                 component_storages_guard: self.component_storages_guard,
                 entity_modified_event_guard: self.entity_modified_event_guard,
+                change_tick: self.change_tick,
             })
         } else {
             None
         }
     }
 }
+
+pub(super) fn next_change_tick(change_tick: &AtomicU64) -> u64 {
+    change_tick.fetch_add(1, Ordering::SeqCst) + 1
+}
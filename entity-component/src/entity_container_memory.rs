@@ -0,0 +1,162 @@
+use bytifex_utils::containers::object_pool::ObjectPool;
+
+use super::{EntityContainer, EntityContainerGuard};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComponentStorageMemoryStats {
+    pub component_type_name: &'static str,
+    pub number_of_components: usize,
+    // the size of the components themselves, without their own heap allocations
+    pub component_memory_bytes: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityContainerMemoryStats {
+    pub number_of_entities: usize,
+    pub number_of_components: usize,
+    pub component_memory_bytes: usize,
+    // the storages are kept after their last component is removed, until the container is
+    // compacted
+    pub component_storages: Vec<ComponentStorageMemoryStats>,
+}
+
+impl EntityContainer {
+    pub fn memory_stats(&self) -> EntityContainerMemoryStats {
+        self.lock().memory_stats()
+    }
+
+    pub fn compact(&self) {
+        self.lock().compact()
+    }
+}
+
+impl EntityContainerGuard<'_> {
+    pub fn memory_stats(&self) -> EntityContainerMemoryStats {
+        let component_storages = self
+            .component_storages_guard
+            .component_storages
+            .values()
+            .map(|component_storage| {
+                let number_of_components = component_storage.number_of_components();
+                ComponentStorageMemoryStats {
+                    component_type_name: component_storage.component_type_name.unwrap_or_default(),
+                    number_of_components,
+                    component_memory_bytes: number_of_components * component_storage.component_size,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        EntityContainerMemoryStats {
+            number_of_entities: self.entities_guard.len(),
+            number_of_components: component_storages
+                .iter()
+                .map(|stats| stats.number_of_components)
+                .sum(),
+            component_memory_bytes: component_storages
+                .iter()
+                .map(|stats| stats.component_memory_bytes)
+                .sum(),
+            component_storages,
+        }
+    }
+
+    // the pools cannot move their objects, because the ids refer to the slots, so only the pools
+    // without any objects are released, e.g. after a wave of entities is despawned
+    pub fn compact(&mut self) {
+        self.component_storages_guard
+            .component_storages
+            .retain(|_, component_storage| component_storage.number_of_components() != 0);
+
+        // the generations of the ids keep the ids of the removed entities invalid
+        if self.entities_guard.iter_mut().next().is_none() {
+            *self.entities_guard = ObjectPool::new();
+        } else {
+            for entity_handler in self.iter() {
+                entity_handler.entity.component_ids.shrink_to_fit();
+                entity_handler.entity.component_change_ticks.shrink_to_fit();
+            }
+        }
+
+        self.entity_name_index.lock().shrink_to_fit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityId;
+
+    struct Position(f32);
+    struct Velocity(f32);
+
+    #[test]
+    fn compact_after_despawning_entities() {
+        let entity_container = EntityContainer::new();
+
+        let still_ids =
+            entity_container.spawn_batch((0..10).map(|index| (Position(index as f32),)));
+        let moving_ids = entity_container
+            .spawn_batch((0..20).map(|index| (Position(index as f32), Velocity(1.0))));
+
+        let memory_stats = entity_container.memory_stats();
+        assert_eq!(30, memory_stats.number_of_entities);
+        assert_eq!(50, memory_stats.number_of_components);
+        assert_eq!(2, memory_stats.component_storages.len());
+        assert_eq!(
+            50 * std::mem::size_of::<f32>(),
+            memory_stats.component_memory_bytes
+        );
+
+        let mut entity_container_guard = entity_container.lock();
+        for entity_id in moving_ids.iter() {
+            entity_container_guard.remove_entity(entity_id);
+        }
+
+        // the storage of the velocities is empty, but it is kept until compacting
+        assert_eq!(
+            2,
+            entity_container_guard
+                .memory_stats()
+                .component_storages
+                .len()
+        );
+        entity_container_guard.compact();
+        let memory_stats = entity_container_guard.memory_stats();
+        assert_eq!(1, memory_stats.component_storages.len());
+        assert_eq!(10, memory_stats.number_of_components);
+        assert_eq!(
+            45.0,
+            entity_container_guard
+                .query::<(&Position,)>()
+                .map(|(position,)| position.0)
+                .sum::<f32>()
+        );
+
+        for entity_id in still_ids.iter() {
+            entity_container_guard.remove_entity(entity_id);
+        }
+        entity_container_guard.compact();
+        assert_eq!(
+            EntityContainerMemoryStats::default(),
+            entity_container_guard.memory_stats()
+        );
+        drop(entity_container_guard);
+
+        // the new entities do not alias the removed ones
+        let new_ids = entity_container.spawn_batch([(Velocity(2.0),)]);
+        assert_eq!(
+            2.0,
+            entity_container
+                .lock()
+                .handler_for_entity(&new_ids[0])
+                .unwrap()
+                .get_component_ref::<Velocity>()
+                .unwrap()
+                .0
+        );
+        assert!(still_ids
+            .iter()
+            .chain(moving_ids.iter())
+            .all(|entity_id: &EntityId| !entity_container.is_alive(entity_id)));
+    }
+}
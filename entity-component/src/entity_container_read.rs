@@ -0,0 +1,164 @@
+use std::{
+    any::TypeId,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use bytifex_utils::{containers::object_pool::ObjectPool, sync::types::RwLockReadGuard};
+
+use crate::component_storage::ComponentGuard;
+
+use super::{
+    component::ComponentTrait, entity::Entity,
+    multi_type_component_storage::MultiTypeComponentStorage, ComponentId, EntityContainer,
+    EntityId,
+};
+
+// the components are only read through this guard, so the systems of a frame can hold it at the
+// same time, the entity container guard waits until every reader is dropped, a thread that holds
+// a read guard must not lock the container again
+pub struct EntityContainerReadGuard<'entity_container_guards> {
+    entities_guard: RwLockReadGuard<'entity_container_guards, ObjectPool<Entity>>,
+    component_storages_guard: RwLockReadGuard<'entity_container_guards, MultiTypeComponentStorage>,
+    change_tick: Arc<AtomicU64>,
+}
+
+impl EntityContainer {
+    pub fn read(&self) -> EntityContainerReadGuard<'_> {
+        EntityContainerReadGuard {
+            entities_guard: self.entities.read(),
+            component_storages_guard: self.component_storages.read(),
+            change_tick: self.change_tick.clone(),
+        }
+    }
+}
+
+impl EntityContainerReadGuard<'_> {
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::SeqCst)
+    }
+
+    pub fn is_alive(&self, entity_id: &EntityId) -> bool {
+        self.entity(entity_id).is_some()
+    }
+
+    pub fn has_component<ComponentType>(&self, entity_id: &EntityId) -> bool
+    where
+        ComponentType: ComponentTrait,
+    {
+        self.component_id_of::<ComponentType>(entity_id).is_some()
+    }
+
+    // the storage of the component type is locked while the returned guard is alive, the other
+    // component types can be read in the meantime
+    pub fn get_component_ref<ComponentType>(
+        &self,
+        entity_id: &EntityId,
+    ) -> Option<ComponentGuard<ComponentType>>
+    where
+        ComponentType: ComponentTrait,
+    {
+        let component_id = self.component_id_of::<ComponentType>(entity_id)?;
+        self.component_storages_guard
+            .get_component_ref::<ComponentType>(component_id)
+    }
+
+    pub fn component_change_tick<ComponentType>(&self, entity_id: &EntityId) -> Option<u64>
+    where
+        ComponentType: ComponentTrait,
+    {
+        let entity = self.entity(entity_id)?;
+        let component_type_id = TypeId::of::<ComponentType>();
+        let index = entity
+            .component_ids
+            .iter()
+            .position(|component_id_ref| component_id_ref.component_type_id == component_type_id)?;
+
+        Some(entity.component_change_ticks[index])
+    }
+
+    fn entity(&self, entity_id: &EntityId) -> Option<&Entity> {
+        self.entities_guard
+            .get_ref(entity_id.index())
+            .filter(|entity| entity.id == *entity_id)
+    }
+
+    fn component_id_of<ComponentType>(&self, entity_id: &EntityId) -> Option<&ComponentId>
+    where
+        ComponentType: ComponentTrait,
+    {
+        let component_type_id = TypeId::of::<ComponentType>();
+        self.entity(entity_id)?
+            .component_ids
+            .iter()
+            .find(|component_id_ref| component_id_ref.component_type_id == component_type_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Position(f32);
+
+    struct Velocity(f32);
+
+    #[test]
+    fn read_components_from_several_threads() {
+        let entity_container = EntityContainer::new();
+        let entity_ids = (0..100)
+            .map(|index| {
+                entity_container
+                    .entity_builder()
+                    .with_component(Position(index as f32))
+                    .with_component(Velocity(1.0))
+                    .build()
+            })
+            .collect::<Vec<EntityId>>();
+
+        // the read guards do not exclude each other
+        let entity_container_read_guard = entity_container.read();
+        let sums = std::thread::scope(|scope| {
+            let join_handles = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let entity_container_read_guard = entity_container.read();
+                        entity_ids
+                            .iter()
+                            .filter_map(|entity_id| {
+                                let position = entity_container_read_guard
+                                    .get_component_ref::<Position>(entity_id)?
+                                    .0;
+                                let velocity = entity_container_read_guard
+                                    .get_component_ref::<Velocity>(entity_id)?
+                                    .0;
+                                Some(position + velocity)
+                            })
+                            .sum::<f32>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            join_handles
+                .into_iter()
+                .map(|join_handle| join_handle.join().unwrap())
+                .collect::<Vec<f32>>()
+        });
+        assert_eq!(vec![5050.0; 4], sums);
+
+        assert!(entity_container_read_guard.has_component::<Velocity>(&entity_ids[0]));
+        drop(entity_container_read_guard);
+
+        entity_container.lock().remove_entity(&entity_ids[0]);
+        let entity_container_read_guard = entity_container.read();
+        assert!(!entity_container_read_guard.is_alive(&entity_ids[0]));
+        assert!(entity_container_read_guard
+            .get_component_ref::<Position>(&entity_ids[0])
+            .is_none());
+        assert!(entity_container_read_guard
+            .component_change_tick::<Position>(&entity_ids[1])
+            .is_some());
+    }
+}
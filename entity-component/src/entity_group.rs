@@ -1,5 +1,6 @@
 use std::{
-    any::TypeId,
+    any::{Any, TypeId},
+    collections::HashMap,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -15,12 +16,13 @@ use EntityHandlingType::*;
 use crate::{EntityGroupEventReceiver, EntityGroupEventSender};
 
 use super::{
-    component_type_list::ToSortedComponentTypeList, entity_modified_event::EntityModifiedEvent,
-    EntityContainerGuard, EntityGroupEvent, EntityId,
+    component_type_list::{ComponentTypeFilter, ToComponentTypeFilter},
+    entity_modified_event::EntityModifiedEvent,
+    ComponentId, EntityContainerGuard, EntityGroupEvent, EntityId,
 };
 
 pub struct EntityGroup {
-    sorted_component_type_list: Vec<TypeId>,
+    component_type_filter: ComponentTypeFilter,
     entity_ids: ArcMutex<Vec<EntityId>>,
 
     to_be_handled_entity_ids: ArcMutex<Vec<(EntityId, EntityHandlingType)>>,
@@ -28,6 +30,9 @@ pub struct EntityGroup {
 
     entity_group_event_sender: EntityGroupEventSender,
 
+    // the sorted views of the group by the type of their components and the keys of the callers
+    pub(super) sorted_views: ArcMutex<HashMap<(TypeId, &'static str), Box<dyn Any + Send>>>,
+
     _entity_modified_event_subscription: callback_event::Subscription<EntityModifiedEvent>,
 }
 
@@ -63,17 +68,17 @@ impl EntityGroup {
 
     pub(super) fn new(
         entity_container_guard: &mut EntityContainerGuard,
-        component_type_list: impl ToSortedComponentTypeList,
+        component_type_list: impl ToComponentTypeFilter,
         entity_modified_event_subscriber: callback_event::Subscriber<EntityModifiedEvent>,
     ) -> Self {
         let entity_ids = arc_mutex_new(Vec::new());
-        let sorted_component_type_list = component_type_list.to_sorted_component_type_list();
+        let component_type_filter = component_type_list.to_component_type_filter();
         let entity_group_event = EntityGroupEventSender::new();
         let is_locked_by_itself = Arc::new(AtomicBool::new(false));
         let to_be_handled_entity_ids = arc_mutex_new(Vec::new());
 
         let mut ret = Self {
-            sorted_component_type_list: sorted_component_type_list.clone(),
+            component_type_filter: component_type_filter.clone(),
             entity_ids: entity_ids.clone(),
 
             is_locked_by_itself: is_locked_by_itself.clone(),
@@ -81,12 +86,14 @@ impl EntityGroup {
 
             entity_group_event_sender: entity_group_event.clone(),
 
+            sorted_views: arc_mutex_new(HashMap::new()),
+
             _entity_modified_event_subscription: entity_modified_event_subscriber.subscribe(
                 move |event| {
                     process_entity_container_event(
                         event,
                         &entity_ids,
-                        &sorted_component_type_list,
+                        &component_type_filter,
                         &entity_group_event,
                         &is_locked_by_itself,
                         &to_be_handled_entity_ids,
@@ -104,20 +111,11 @@ impl EntityGroup {
         *self.entity_ids.lock() = entity_container_guard
             .iter()
             .filter_map(|entity_handler| {
-                let mut add_entity_id = true;
-                for necessary_component_type_id_ref in self.sorted_component_type_list.iter() {
-                    if !entity_handler
+                if self.component_type_filter.matches(
+                    entity_handler
                         .iter_entity_component_ids()
-                        .any(|component_id_ref| {
-                            component_id_ref.component_type_id == *necessary_component_type_id_ref
-                        })
-                    {
-                        add_entity_id = false;
-                        break;
-                    }
-                }
-
-                if add_entity_id {
+                        .map(|component_id_ref| component_id_ref.component_type_id),
+                ) {
                     Some(entity_handler.entity.id)
                 } else {
                     None
@@ -208,67 +206,124 @@ impl Drop for EntityGroupIterator<'_> {
     }
 }
 
-fn matching_component_type_count(
-    type_ids: impl Iterator<Item = TypeId>,
-    component_type_list: &[std::any::TypeId],
-) -> usize {
-    let mut component_match_count = 0;
-
-    for type_id in type_ids {
-        for component_type_id in component_type_list {
-            if *component_type_id == type_id {
-                component_match_count += 1;
-                break;
-            }
+enum EntityHandlingType {
+    Add,
+    Remove,
+}
+
+fn add_entity_id(
+    entity_id: EntityId,
+    entity_ids: &ArcMutex<Vec<EntityId>>,
+    is_locked_by_itself: &Arc<AtomicBool>,
+    to_be_handled_entity_ids: &ArcMutex<Vec<(EntityId, EntityHandlingType)>>,
+) {
+    if is_locked_by_itself.fetch_and(true, Ordering::SeqCst) {
+        to_be_handled_entity_ids.lock().push((entity_id, Add));
+    } else {
+        entity_ids.lock().push(entity_id);
+    }
+}
+
+fn remove_entity_id(
+    entity_id: EntityId,
+    entity_ids: &ArcMutex<Vec<EntityId>>,
+    is_locked_by_itself: &Arc<AtomicBool>,
+    to_be_handled_entity_ids: &ArcMutex<Vec<(EntityId, EntityHandlingType)>>,
+) {
+    if is_locked_by_itself.fetch_and(true, Ordering::SeqCst) {
+        to_be_handled_entity_ids.lock().push((entity_id, Remove));
+    } else {
+        let mut entity_ids = entity_ids.lock();
+        if let Some(index) = entity_ids
+            .iter()
+            .position(|entity_id_ref| *entity_id_ref == entity_id)
+        {
+            entity_ids.swap_remove(index);
         }
     }
+}
+
+fn send_entity_added_events(
+    entity_id: EntityId,
+    component_ids: &[ComponentId],
+    entity_group_event: &EntityGroupEventSender,
+) {
+    entity_group_event.send(EntityGroupEvent::EntityAdded { entity_id });
 
-    component_match_count
+    for component_id_ref in component_ids.iter() {
+        entity_group_event.send(EntityGroupEvent::ComponentAdded {
+            entity_id,
+            component_id: component_id_ref.clone(),
+        });
+    }
 }
 
-enum EntityHandlingType {
-    Add,
-    Remove,
+fn send_entity_removed_events<'a>(
+    entity_id: EntityId,
+    component_ids: impl Iterator<Item = &'a ComponentId>,
+    entity_group_event: &EntityGroupEventSender,
+) {
+    for component_id_ref in component_ids {
+        entity_group_event.send(EntityGroupEvent::ComponentRemoved {
+            entity_id,
+            component_id: component_id_ref.clone(),
+        });
+    }
+
+    entity_group_event.send(EntityGroupEvent::EntityRemoved { entity_id });
 }
 
 fn process_entity_container_event(
     event: &EntityModifiedEvent,
     entity_ids: &ArcMutex<Vec<EntityId>>,
-    sorted_component_type_list: &[TypeId],
+    component_type_filter: &ComponentTypeFilter,
     entity_group_event: &EntityGroupEventSender,
     is_locked_by_itself: &Arc<AtomicBool>,
     to_be_handled_entity_ids: &ArcMutex<Vec<(EntityId, EntityHandlingType)>>,
 ) {
+    let component_type_ids_of = |component_ids: &[ComponentId]| {
+        component_ids
+            .iter()
+            .map(|component_id| component_id.component_type_id)
+            .collect::<Vec<TypeId>>()
+    };
+
     match event {
         EntityModifiedEvent::EntityAdded {
             entity_id,
             component_ids,
         } => {
             let component_ids_ref = unsafe { &**component_ids };
-            if matching_component_type_count(
-                component_ids_ref
-                    .iter()
-                    .map(|component_id| component_id.component_type_id),
-                sorted_component_type_list,
-            ) == sorted_component_type_list.len()
-            {
-                if is_locked_by_itself.fetch_and(true, Ordering::SeqCst) {
-                    to_be_handled_entity_ids.lock().push((*entity_id, Add));
-                } else {
-                    entity_ids.lock().push(*entity_id);
+            if component_type_filter.matches(component_type_ids_of(component_ids_ref).into_iter()) {
+                add_entity_id(
+                    *entity_id,
+                    entity_ids,
+                    is_locked_by_itself,
+                    to_be_handled_entity_ids,
+                );
+
+                send_entity_added_events(*entity_id, component_ids_ref, entity_group_event);
+            }
+        }
+        EntityModifiedEvent::EntitiesAdded {
+            entity_ids: added_entity_ids,
+            component_type_ids,
+        } => {
+            let added_entity_ids_ref = unsafe { &**added_entity_ids };
+            let component_type_ids_ref = unsafe { &**component_type_ids };
+            if component_type_filter.matches(component_type_ids_ref.iter().copied()) {
+                for entity_id in added_entity_ids_ref.iter() {
+                    add_entity_id(
+                        *entity_id,
+                        entity_ids,
+                        is_locked_by_itself,
+                        to_be_handled_entity_ids,
+                    );
                 }
 
-                // send events
-                entity_group_event.send(EntityGroupEvent::EntityAdded {
-                    entity_id: *entity_id,
+                entity_group_event.send(EntityGroupEvent::EntitiesAdded {
+                    entity_ids: added_entity_ids_ref.to_vec(),
                 });
-
-                for component_id_ref in component_ids_ref.iter() {
-                    entity_group_event.send(EntityGroupEvent::ComponentAdded {
-                        entity_id: *entity_id,
-                        component_id: component_id_ref.clone(),
-                    });
-                }
             }
         }
         EntityModifiedEvent::EntityRemoved {
@@ -276,36 +331,19 @@ fn process_entity_container_event(
             component_ids,
         } => {
             let component_ids_ref = unsafe { &**component_ids };
-            if matching_component_type_count(
-                component_ids_ref
-                    .iter()
-                    .map(|component_id| component_id.component_type_id),
-                sorted_component_type_list,
-            ) == sorted_component_type_list.len()
-            {
-                if is_locked_by_itself.fetch_and(true, Ordering::SeqCst) {
-                    to_be_handled_entity_ids.lock().push((*entity_id, Remove));
-                } else {
-                    let mut entity_ids = entity_ids.lock();
-                    if let Some(index) = entity_ids
-                        .iter()
-                        .position(|entity_id_ref| *entity_id_ref == *entity_id)
-                    {
-                        entity_ids.swap_remove(index);
-                    }
-                }
-
-                // send events
-                for component_id in component_ids_ref.iter() {
-                    entity_group_event.send(EntityGroupEvent::ComponentRemoved {
-                        entity_id: *entity_id,
-                        component_id: component_id.clone(),
-                    });
-                }
+            if component_type_filter.matches(component_type_ids_of(component_ids_ref).into_iter()) {
+                remove_entity_id(
+                    *entity_id,
+                    entity_ids,
+                    is_locked_by_itself,
+                    to_be_handled_entity_ids,
+                );
 
-                entity_group_event.send(EntityGroupEvent::EntityRemoved {
-                    entity_id: *entity_id,
-                });
+                send_entity_removed_events(
+                    *entity_id,
+                    component_ids_ref.iter(),
+                    entity_group_event,
+                );
             }
         }
         EntityModifiedEvent::ComponentAdded {
@@ -314,47 +352,50 @@ fn process_entity_container_event(
             component_ids,
         } => {
             let component_ids_ref = unsafe { &**component_ids };
-            if sorted_component_type_list.contains(&component_id.component_type_id) {
-                if matching_component_type_count(
-                    component_ids_ref
-                        .iter()
-                        .map(|component_id| component_id.component_type_id),
-                    sorted_component_type_list,
-                ) == sorted_component_type_list.len()
-                {
-                    if is_locked_by_itself.fetch_and(true, Ordering::SeqCst) {
-                        to_be_handled_entity_ids.lock().push((*entity_id, Add));
-                    } else {
-                        entity_ids.lock().push(*entity_id);
-                    }
-
-                    // send events
-                    entity_group_event.send(EntityGroupEvent::EntityAdded {
-                        entity_id: *entity_id,
-                    });
-
-                    for component_id_ref in component_ids_ref.iter() {
-                        entity_group_event.send(EntityGroupEvent::ComponentAdded {
-                            entity_id: *entity_id,
-                            component_id: component_id_ref.clone(),
-                        });
-                    }
+            let component_type_ids = component_type_ids_of(component_ids_ref);
+            let was_member = component_type_filter.matches(
+                component_type_ids
+                    .iter()
+                    .copied()
+                    .filter(|type_id| *type_id != component_id.component_type_id),
+            );
+            let is_member = component_type_filter.matches(component_type_ids.into_iter());
+
+            match (was_member, is_member) {
+                (false, true) => {
+                    add_entity_id(
+                        *entity_id,
+                        entity_ids,
+                        is_locked_by_itself,
+                        to_be_handled_entity_ids,
+                    );
+
+                    send_entity_added_events(*entity_id, component_ids_ref, entity_group_event);
                 }
-            } else {
-                #[warn(clippy::collapsible_else_if)]
-                if matching_component_type_count(
-                    component_ids_ref
-                        .iter()
-                        .map(|component_id| component_id.component_type_id),
-                    sorted_component_type_list,
-                ) == sorted_component_type_list.len()
-                {
-                    // send event
+                // an excluded component is added
+                (true, false) => {
+                    remove_entity_id(
+                        *entity_id,
+                        entity_ids,
+                        is_locked_by_itself,
+                        to_be_handled_entity_ids,
+                    );
+
+                    send_entity_removed_events(
+                        *entity_id,
+                        component_ids_ref
+                            .iter()
+                            .filter(|component_id_ref| **component_id_ref != *component_id),
+                        entity_group_event,
+                    );
+                }
+                (true, true) => {
                     entity_group_event.send(EntityGroupEvent::ComponentAdded {
                         entity_id: *entity_id,
                         component_id: component_id.clone(),
                     });
                 }
+                (false, false) => (),
             }
         }
         EntityModifiedEvent::ComponentRemoved {
@@ -363,59 +404,48 @@ fn process_entity_container_event(
             component_id,
         } => {
             let component_ids_ref = unsafe { &**component_ids };
-            if sorted_component_type_list.contains(&component_id.component_type_id) {
-                if matching_component_type_count(
-                    component_ids_ref
-                        .iter()
-                        .map(|component_id| component_id.component_type_id),
-                    sorted_component_type_list,
-                ) + 1
-                    == sorted_component_type_list.len()
-                {
-                    if is_locked_by_itself.fetch_and(true, Ordering::SeqCst) {
-                        to_be_handled_entity_ids.lock().push((*entity_id, Remove));
-                    } else {
-                        let mut entity_ids = entity_ids.lock();
-                        if let Some(index) = entity_ids
-                            .iter()
-                            .position(|entity_id_ref| *entity_id_ref == *entity_id)
-                        {
-                            entity_ids.swap_remove(index);
-                        }
-                    }
-
-                    // send events
-                    entity_group_event.send(EntityGroupEvent::ComponentRemoved {
-                        entity_id: *entity_id,
-                        component_id: component_id.clone(),
-                    });
-
-                    for component_id_ref in component_ids_ref.iter() {
-                        entity_group_event.send(EntityGroupEvent::ComponentRemoved {
-                            entity_id: *entity_id,
-                            component_id: component_id_ref.clone(),
-                        });
-                    }
-
-                    entity_group_event.send(EntityGroupEvent::EntityRemoved {
-                        entity_id: *entity_id,
-                    });
+            let component_type_ids = component_type_ids_of(component_ids_ref);
+            let was_member = component_type_filter.matches(
+                component_type_ids
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(component_id.component_type_id)),
+            );
+            let is_member = component_type_filter.matches(component_type_ids.into_iter());
+
+            match (was_member, is_member) {
+                (true, false) => {
+                    remove_entity_id(
+                        *entity_id,
+                        entity_ids,
+                        is_locked_by_itself,
+                        to_be_handled_entity_ids,
+                    );
+
+                    send_entity_removed_events(
+                        *entity_id,
+                        std::iter::once(component_id).chain(component_ids_ref.iter()),
+                        entity_group_event,
+                    );
                 }
-            } else {
-                #[warn(clippy::collapsible_else_if)]
-                if matching_component_type_count(
-                    component_ids_ref
-                        .iter()
-                        .map(|component_id| component_id.component_type_id),
-                    sorted_component_type_list,
-                ) == sorted_component_type_list.len()
-                {
-                    // send event
+                // an excluded component is removed
+                (false, true) => {
+                    add_entity_id(
+                        *entity_id,
+                        entity_ids,
+                        is_locked_by_itself,
+                        to_be_handled_entity_ids,
+                    );
+
+                    send_entity_added_events(*entity_id, component_ids_ref, entity_group_event);
+                }
+                (true, true) => {
                     entity_group_event.send(EntityGroupEvent::ComponentRemoved {
                         entity_id: *entity_id,
                         component_id: component_id.clone(),
                     });
                 }
+                (false, false) => (),
             }
         }
         EntityModifiedEvent::ComponentChanged {
@@ -423,14 +453,10 @@ fn process_entity_container_event(
             component_id,
             component_ids,
         } => {
-            if sorted_component_type_list.contains(&component_id.component_type_id) {
+            if component_type_filter.is_observed(&component_id.component_type_id) {
                 let component_ids_ref = unsafe { &**component_ids };
-                if matching_component_type_count(
-                    component_ids_ref
-                        .iter()
-                        .map(|component_id| component_id.component_type_id),
-                    sorted_component_type_list,
-                ) == sorted_component_type_list.len()
+                if component_type_filter
+                    .matches(component_type_ids_of(component_ids_ref).into_iter())
                 {
                     // send event
                     entity_group_event.send(EntityGroupEvent::ComponentChanged {
@@ -815,6 +841,151 @@ mod tests {
         assert!(!entity_group.contains(&ids[4]));
     }
 
+    #[test]
+    fn entity_group_with_excluded_and_optional_components() {
+        let mut entity_container = EntityContainer::new();
+
+        let entity_group = entity_container.lock().entity_group(component_type_list![
+            Position,
+            Without<Camera>,
+            Option<Playable>,
+        ]);
+
+        let ids = add_test_entities(&mut entity_container);
+
+        // contains
+        assert!(entity_group.contains(&ids[0]));
+        assert!(!entity_group.contains(&ids[1]));
+        assert!(entity_group.contains(&ids[2]));
+        assert!(entity_group.contains(&ids[3]));
+        assert!(!entity_group.contains(&ids[4]));
+
+        let mut entity_container_guard = entity_container.lock();
+
+        let entity_group_event = entity_group.event_receiver(false, &mut entity_container_guard);
+
+        // adding an excluded component removes the entity
+        let mut entity_handler = entity_container_guard.handler_for_entity(&ids[0]).unwrap();
+        entity_handler.add_component(Camera("cam0".to_string()));
+
+        // removing the excluded component adds the entity
+        let mut entity_handler = entity_container_guard.handler_for_entity(&ids[1]).unwrap();
+        entity_handler.remove_component::<Camera>();
+
+        // only the changes of the required and the optional components are reported
+        let mut entity_handler = entity_container_guard.handler_for_entity(&ids[2]).unwrap();
+        entity_handler.change_component(|playable: &mut Playable| {
+            playable.0 = "pla2 changed".to_string();
+        });
+        entity_handler.change_component(|orientation: &mut Orientation| {
+            orientation.0 = "ori2 changed".to_string();
+        });
+
+        let mut entity_removed_counter: i32 = 0;
+        let mut component_removed_counter: i32 = 0;
+        let mut entity_added_counter: i32 = 0;
+        let mut component_added_counter: i32 = 0;
+        let mut component_changed_counter: i32 = 0;
+        while let Some(e) = entity_group_event.pop() {
+            match e {
+                EntityGroupEvent::EntityRemoved { entity_id } => {
+                    entity_removed_counter += 1;
+                    assert!(entity_id == ids[0]);
+                }
+                EntityGroupEvent::ComponentRemoved { .. } => component_removed_counter += 1,
+                EntityGroupEvent::EntityAdded { entity_id } => {
+                    entity_added_counter += 1;
+                    assert!(entity_id == ids[1]);
+                }
+                EntityGroupEvent::ComponentAdded { .. } => component_added_counter += 1,
+                EntityGroupEvent::ComponentChanged { component_id, .. } => {
+                    component_changed_counter += 1;
+                    assert!(component_id.is_component_type_of::<Playable>());
+                }
+                EntityGroupEvent::EntitiesAdded { .. } => assert!(false),
+            }
+        }
+        assert_eq!(entity_removed_counter, 1);
+        assert_eq!(component_removed_counter, 3);
+        assert_eq!(entity_added_counter, 1);
+        assert_eq!(component_added_counter, 3);
+        assert_eq!(component_changed_counter, 1);
+
+        // contains
+        assert!(!entity_group.contains(&ids[0]));
+        assert!(entity_group.contains(&ids[1]));
+    }
+
+    #[test]
+    fn entity_group_entities_added_in_batch() {
+        let entity_container = EntityContainer::new();
+
+        let entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list![Position, Velocity]);
+        let entity_group_event = entity_group.event_receiver(false, &mut entity_container.lock());
+
+        let ids = entity_container.spawn_batch((0..3).map(|index| {
+            (
+                Position(format!("pos{index}")),
+                Velocity(format!("vel{index}")),
+            )
+        }));
+        let other_ids = entity_container.spawn_batch([
+            (Position("pos3".to_string()),),
+            (Position("pos4".to_string()),),
+        ]);
+
+        let mut entities_added_counter: i32 = 0;
+        while let Some(e) = entity_group_event.pop() {
+            match e {
+                EntityGroupEvent::EntitiesAdded { entity_ids } => {
+                    entities_added_counter += 1;
+                    assert!(entity_ids == ids);
+                }
+                _ => assert!(false),
+            }
+        }
+        assert_eq!(entities_added_counter, 1);
+
+        assert_eq!(entity_group.iter_entity_ids().count(), 3);
+        assert!(other_ids
+            .iter()
+            .all(|entity_id| !entity_group.contains(entity_id)));
+    }
+
+    #[tokio::test]
+    async fn entity_group_event_receiver_waits_for_events() {
+        let entity_container = EntityContainer::new();
+
+        let entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list![Position]);
+        let entity_group_event = entity_group.event_receiver(false, &mut entity_container.lock());
+
+        let spawner = tokio::spawn({
+            let entity_container = entity_container.clone();
+            async move {
+                tokio::task::yield_now().await;
+                entity_container
+                    .entity_builder()
+                    .with_component(Position("pos0".to_string()))
+                    .build()
+            }
+        });
+
+        let event = entity_group_event.recv().await;
+        let id = spawner.await.unwrap();
+        assert!(matches!(
+            event,
+            Some(EntityGroupEvent::EntityAdded { entity_id }) if entity_id == id
+        ));
+        assert!(matches!(
+            entity_group_event.recv().await,
+            Some(EntityGroupEvent::ComponentAdded { .. })
+        ));
+    }
+
     #[test]
     fn component_removed_event_when_deleting_an_entity() {
         let mut entity_container = EntityContainer::new();
@@ -1,4 +1,7 @@
+use std::{ops::Deref, sync::Arc};
+
 use bytifex_utils::sync::broadcast;
+use tokio::sync::Notify;
 
 use super::{ComponentId, EntityId};
 
@@ -7,6 +10,10 @@ pub enum EntityGroupEvent {
     EntityAdded {
         entity_id: EntityId,
     },
+    // sent instead of the EntityAdded and ComponentAdded events of the entities spawned in a batch
+    EntitiesAdded {
+        entity_ids: Vec<EntityId>,
+    },
     EntityRemoved {
         entity_id: EntityId,
     },
@@ -24,5 +31,72 @@ pub enum EntityGroupEvent {
     },
 }
 
-pub type EntityGroupEventSender = broadcast::Sender<EntityGroupEvent>;
-pub type EntityGroupEventReceiver = broadcast::Receiver<EntityGroupEvent>;
+// every sent event wakes up the receivers that wait for an event in recv
+#[derive(Clone)]
+pub struct EntityGroupEventSender {
+    sender: broadcast::Sender<EntityGroupEvent>,
+    notify: Arc<Notify>,
+}
+
+impl EntityGroupEventSender {
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::Sender::new(),
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn create_receiver(&self) -> EntityGroupEventReceiver {
+        EntityGroupEventReceiver {
+            receiver: self.sender.create_receiver(),
+            notify: self.notify.clone(),
+        }
+    }
+
+    pub fn send(&self, event: EntityGroupEvent) {
+        self.sender.send(event);
+        self.notify.notify_waiters();
+    }
+
+    pub fn send_directly(&self, event: EntityGroupEvent, receiver: &EntityGroupEventReceiver) {
+        self.sender.send_directly(event, &receiver.receiver);
+        self.notify.notify_waiters();
+    }
+}
+
+impl Default for EntityGroupEventSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct EntityGroupEventReceiver {
+    receiver: broadcast::Receiver<EntityGroupEvent>,
+    notify: Arc<Notify>,
+}
+
+impl EntityGroupEventReceiver {
+    // waits until the next event arrives, returns none if the entity group does not send events
+    // anymore
+    pub async fn recv(&self) -> Option<EntityGroupEvent> {
+        loop {
+            // created before checking the queue, so an event sent in between is not missed
+            let notified = self.notify.notified();
+
+            match self.receiver.try_pop() {
+                Ok(Some(event)) => break Some(event),
+                Ok(None) => notified.await,
+                Err(_) => break None,
+            }
+        }
+    }
+}
+
+// the events can still be popped without waiting, e.g. once per frame
+impl Deref for EntityGroupEventReceiver {
+    type Target = broadcast::Receiver<EntityGroupEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
@@ -0,0 +1,170 @@
+use tokio::task::JoinHandle;
+
+use super::{
+    ComponentId, EntityContainer, EntityContainerGuard, EntityGroup, EntityGroupEvent, EntityId,
+};
+
+// the closure is not called anymore after the reaction is dropped, the entity group has to be
+// kept alive as long as the reaction is used
+pub struct EntityGroupReaction {
+    join_handle: JoinHandle<()>,
+}
+
+impl Drop for EntityGroupReaction {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl EntityGroup {
+    // the closure is called for the entities that are already in the group too, the entity
+    // container is locked while the closure runs
+    pub fn on_entity_added(
+        &self,
+        entity_container: &EntityContainer,
+        mut reaction: impl FnMut(&mut EntityContainerGuard, EntityId) + Send + 'static,
+    ) -> EntityGroupReaction {
+        let event_receiver = self.event_receiver(true, &mut entity_container.lock());
+        let entity_container = entity_container.clone();
+
+        EntityGroupReaction {
+            join_handle: tokio::spawn(async move {
+                while let Some(event) = event_receiver.recv().await {
+                    let entity_ids = match event {
+                        EntityGroupEvent::EntityAdded { entity_id } => vec![entity_id],
+                        EntityGroupEvent::EntitiesAdded { entity_ids } => entity_ids,
+                        _ => continue,
+                    };
+
+                    let mut entity_container_guard = entity_container.lock();
+                    for entity_id in entity_ids {
+                        reaction(&mut entity_container_guard, entity_id);
+                    }
+                }
+            }),
+        }
+    }
+
+    // changing the component in the closure triggers the closure again, so the unchanged values
+    // should not be written back
+    pub fn on_component_changed(
+        &self,
+        entity_container: &EntityContainer,
+        mut reaction: impl FnMut(&mut EntityContainerGuard, EntityId, ComponentId) + Send + 'static,
+    ) -> EntityGroupReaction {
+        let event_receiver = self.event_receiver(false, &mut entity_container.lock());
+        let entity_container = entity_container.clone();
+
+        EntityGroupReaction {
+            join_handle: tokio::spawn(async move {
+                while let Some(event) = event_receiver.recv().await {
+                    if let EntityGroupEvent::ComponentChanged {
+                        entity_id,
+                        component_id,
+                    } = event
+                    {
+                        reaction(&mut entity_container.lock(), entity_id, component_id);
+                    }
+                }
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::mpsc;
+
+    use crate::component_type_list;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(f32);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct RenderedPosition(f32);
+
+    fn couple_position(entity_container_guard: &mut EntityContainerGuard, entity_id: EntityId) {
+        if let Some(mut entity_handler) = entity_container_guard.handler_for_entity(&entity_id) {
+            let position = entity_handler.get_component_ref::<Position>().unwrap().0;
+            entity_handler.change_component(|rendered_position: &mut RenderedPosition| {
+                rendered_position.0 = position
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn couple_components_with_reactions() {
+        let entity_container = EntityContainer::new();
+        let entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list![Position, RenderedPosition,]);
+
+        let (reacted_sender, mut reacted_receiver) = mpsc::unbounded_channel();
+
+        let _entity_added_reaction = entity_group.on_entity_added(&entity_container, {
+            let reacted_sender = reacted_sender.clone();
+            move |entity_container_guard, entity_id| {
+                couple_position(entity_container_guard, entity_id);
+                reacted_sender.send(entity_id).unwrap();
+            }
+        });
+        let component_changed_reaction = entity_group.on_component_changed(
+            &entity_container,
+            move |entity_container_guard, entity_id, component_id| {
+                if component_id.is_component_type_of::<Position>() {
+                    couple_position(entity_container_guard, entity_id);
+                    reacted_sender.send(entity_id).unwrap();
+                }
+            },
+        );
+
+        let id = entity_container
+            .entity_builder()
+            .with_component(Position(1.0))
+            .with_component(RenderedPosition(0.0))
+            .build();
+        assert_eq!(Some(id), reacted_receiver.recv().await);
+        assert_eq!(
+            RenderedPosition(1.0),
+            *entity_container
+                .lock()
+                .handler_for_entity(&id)
+                .unwrap()
+                .get_component_ref::<RenderedPosition>()
+                .unwrap()
+        );
+
+        entity_container
+            .lock()
+            .handler_for_entity(&id)
+            .unwrap()
+            .change_component(|position: &mut Position| position.0 = 2.0);
+        assert_eq!(Some(id), reacted_receiver.recv().await);
+        assert_eq!(
+            RenderedPosition(2.0),
+            *entity_container
+                .lock()
+                .handler_for_entity(&id)
+                .unwrap()
+                .get_component_ref::<RenderedPosition>()
+                .unwrap()
+        );
+
+        // the dropped reaction is not called anymore
+        drop(component_changed_reaction);
+        entity_container
+            .lock()
+            .handler_for_entity(&id)
+            .unwrap()
+            .change_component(|position: &mut Position| position.0 = 3.0);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), reacted_receiver.recv())
+                .await
+                .is_err()
+        );
+    }
+}
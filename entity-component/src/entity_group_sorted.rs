@@ -0,0 +1,197 @@
+use std::any::TypeId;
+
+use super::{
+    component::ComponentTrait, EntityContainerGuard, EntityGroup, EntityGroupEvent,
+    EntityGroupEventReceiver, EntityId,
+};
+
+struct SortedView<Key> {
+    event_receiver: EntityGroupEventReceiver,
+    // sorted by the keys, the entities with equal keys are in the order they got their keys
+    entries: Vec<(Key, EntityId)>,
+}
+
+impl<Key: Ord> SortedView<Key> {
+    fn remove(&mut self, entity_id: &EntityId) {
+        self.entries
+            .retain(|(_, entity_id_ref)| *entity_id_ref != *entity_id);
+    }
+
+    fn insert(&mut self, key: Key, entity_id: EntityId) {
+        let index = self.entries.partition_point(|(key_ref, _)| *key_ref <= key);
+        self.entries.insert(index, (key, entity_id));
+    }
+}
+
+impl EntityGroup {
+    // the view is kept per view key and only the changed entities are sorted again, e.g. the
+    // sprites by their z order, the entities without the component are left out, the key function
+    // has to be the same on every call with the same view key
+    pub fn iter_sorted_by<ComponentType, Key>(
+        &self,
+        entity_container_guard: &mut EntityContainerGuard<'_>,
+        view_key: &'static str,
+        key_fn: impl Fn(&ComponentType) -> Key,
+    ) -> std::vec::IntoIter<EntityId>
+    where
+        ComponentType: ComponentTrait,
+        Key: Ord + Send + 'static,
+    {
+        let component_type_id = TypeId::of::<ComponentType>();
+        let sorted_view_key = (component_type_id, view_key);
+        let mut sorted_views = self.sorted_views.lock();
+
+        // a view key that was used with an other key type gets a new view
+        let is_reusable = sorted_views
+            .get(&sorted_view_key)
+            .is_some_and(|sorted_view| sorted_view.is::<SortedView<Key>>());
+        if !is_reusable {
+            sorted_views.insert(
+                sorted_view_key,
+                Box::new(SortedView::<Key> {
+                    event_receiver: self.event_receiver(true, entity_container_guard),
+                    entries: Vec::new(),
+                }),
+            );
+        }
+        let Some(sorted_view) = sorted_views
+            .get_mut(&sorted_view_key)
+            .and_then(|sorted_view| sorted_view.downcast_mut::<SortedView<Key>>())
+        else {
+            unreachable!();
+        };
+
+        while let Ok(Some(event)) = sorted_view.event_receiver.try_pop() {
+            let entity_ids = match event {
+                EntityGroupEvent::EntityAdded { entity_id } => vec![entity_id],
+                EntityGroupEvent::EntitiesAdded { entity_ids } => entity_ids,
+                EntityGroupEvent::ComponentAdded {
+                    entity_id,
+                    component_id,
+                }
+                | EntityGroupEvent::ComponentChanged {
+                    entity_id,
+                    component_id,
+                }
+                | EntityGroupEvent::ComponentRemoved {
+                    entity_id,
+                    component_id,
+                } if component_id.component_type_id == component_type_id => vec![entity_id],
+                EntityGroupEvent::EntityRemoved { entity_id } => {
+                    sorted_view.remove(&entity_id);
+                    continue;
+                }
+                _ => continue,
+            };
+
+            for entity_id in entity_ids {
+                sorted_view.remove(&entity_id);
+
+                if let Some(entity_handler) = entity_container_guard.handler_for_entity(&entity_id)
+                {
+                    if let Some(component) = entity_handler.get_component_ref::<ComponentType>() {
+                        sorted_view.insert(key_fn(&component), entity_id);
+                    }
+                }
+            }
+        }
+
+        sorted_view
+            .entries
+            .iter()
+            .map(|(_, entity_id)| *entity_id)
+            .collect::<Vec<EntityId>>()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{component_type_list, EntityContainer};
+
+    struct ZOrder(i32);
+
+    struct Sprite;
+
+    #[test]
+    fn iterate_entity_group_sorted_by_component() {
+        let entity_container = EntityContainer::new();
+        let entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list![Sprite]);
+
+        let spawn = |z_order| {
+            entity_container
+                .entity_builder()
+                .with_component(Sprite)
+                .with_component(ZOrder(z_order))
+                .build()
+        };
+        let middle_id = spawn(5);
+        let back_id = spawn(-3);
+        let front_id = spawn(10);
+        let without_z_order_id = entity_container
+            .entity_builder()
+            .with_component(Sprite)
+            .build();
+
+        let by_z_order = |z_order: &ZOrder| z_order.0;
+        let sorted_ids = |entity_container: &EntityContainer| {
+            entity_group
+                .iter_sorted_by(&mut entity_container.lock(), "z_order", by_z_order)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![back_id, middle_id, front_id],
+            sorted_ids(&entity_container)
+        );
+
+        let mut entity_container_guard = entity_container.lock();
+        entity_container_guard
+            .handler_for_entity(&back_id)
+            .unwrap()
+            .change_component(|z_order: &mut ZOrder| z_order.0 = 20);
+        entity_container_guard
+            .handler_for_entity(&without_z_order_id)
+            .unwrap()
+            .add_component(ZOrder(0));
+        entity_container_guard.remove_entity(&front_id);
+        drop(entity_container_guard);
+
+        assert_eq!(
+            vec![without_z_order_id, middle_id, back_id],
+            sorted_ids(&entity_container)
+        );
+    }
+
+    #[test]
+    fn capturing_key_functions_get_their_own_views() {
+        let entity_container = EntityContainer::new();
+        let entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list![Sprite]);
+
+        let spawn = |z_order| {
+            entity_container
+                .entity_builder()
+                .with_component(Sprite)
+                .with_component(ZOrder(z_order))
+                .build()
+        };
+        let back_id = spawn(-3);
+        let front_id = spawn(10);
+
+        // the closures have the same type, only the captured direction differs
+        let sorted_ids = |view_key, direction: i32| {
+            entity_group
+                .iter_sorted_by(
+                    &mut entity_container.lock(),
+                    view_key,
+                    move |z_order: &ZOrder| z_order.0 * direction,
+                )
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![back_id, front_id], sorted_ids("back_to_front", 1));
+        assert_eq!(vec![front_id, back_id], sorted_ids("front_to_back", -1));
+    }
+}
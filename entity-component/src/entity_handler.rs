@@ -1,18 +1,25 @@
-use bytifex_utils::sync::{callback_event, types::MutexGuard};
+use std::sync::atomic::AtomicU64;
+
+use bytifex_utils::sync::{
+    callback_event,
+    types::{MutexGuard, RwLockWriteGuard},
+};
 
 use crate::component_storage::ComponentGuard;
 
 use super::{
-    component::ComponentTrait, entity::Entity, entity_modified_event::EntityModifiedEvent,
-    multi_type_component_storage::MultiTypeComponentStorage, ComponentId,
+    component::ComponentTrait, entity::Entity, entity_container::next_change_tick,
+    entity_modified_event::EntityModifiedEvent,
+    multi_type_component_storage::MultiTypeComponentStorage, ComponentId, Name,
 };
 
 pub struct EntityHandler<'a, 'entity_container_guards> {
     pub(super) entity: &'a mut Entity,
     pub(super) component_storages_guard:
-        *mut MutexGuard<'entity_container_guards, MultiTypeComponentStorage>,
+        *mut RwLockWriteGuard<'entity_container_guards, MultiTypeComponentStorage>,
     pub(super) entity_modified_event_guard:
         &'a MutexGuard<'entity_container_guards, callback_event::Sender<EntityModifiedEvent>>,
+    pub(super) change_tick: &'a AtomicU64,
 }
 
 impl EntityHandler<'_, '_> {
@@ -36,6 +43,9 @@ impl EntityHandler<'_, '_> {
                 None => unreachable!(),
             };
             self.entity.component_ids.push(component_id.clone());
+            self.entity
+                .component_change_ticks
+                .push(next_change_tick(self.change_tick));
 
             self.entity_modified_event_guard
                 .trigger(&EntityModifiedEvent::ComponentAdded {
@@ -62,6 +72,7 @@ impl EntityHandler<'_, '_> {
             .position(|component_id_ref| component_id_ref.component_type_id == component_type_id)?;
 
         let component_id = self.entity.component_ids.swap_remove(index);
+        self.entity.component_change_ticks.swap_remove(index);
 
         self.entity_modified_event_guard
             .trigger(&EntityModifiedEvent::ComponentRemoved {
@@ -99,11 +110,12 @@ impl EntityHandler<'_, '_> {
         ComponentType: ComponentTrait,
     {
         let component_type_id = std::any::TypeId::of::<ComponentType>();
-        if let Some(component_id_ref) = self
+        if let Some((index, component_id_ref)) = self
             .entity
             .component_ids
             .iter()
-            .find(|component_id_ref| component_id_ref.component_type_id == component_type_id)
+            .enumerate()
+            .find(|(_, component_id_ref)| component_id_ref.component_type_id == component_type_id)
         {
             let component_storage = unsafe {
                 (*self.component_storages_guard)
@@ -112,6 +124,7 @@ impl EntityHandler<'_, '_> {
 
             if let Some(mut component_mut) = component_storage.get_component_mut(component_id_ref) {
                 let ret = f(&mut *component_mut);
+                self.entity.component_change_ticks[index] = next_change_tick(self.change_tick);
 
                 self.entity_modified_event_guard
                     .trigger(&EntityModifiedEvent::ComponentChanged {
@@ -129,7 +142,56 @@ impl EntityHandler<'_, '_> {
         }
     }
 
+    // the tick of the last change of the component, adding the component counts as a change
+    pub fn component_change_tick<ComponentType>(&self) -> Option<u64>
+    where
+        ComponentType: ComponentTrait,
+    {
+        let component_type_id = std::any::TypeId::of::<ComponentType>();
+        let index = self
+            .entity
+            .component_ids
+            .iter()
+            .position(|component_id_ref| component_id_ref.component_type_id == component_type_id)?;
+
+        Some(self.entity.component_change_ticks[index])
+    }
+
+    pub fn is_component_changed_since<ComponentType>(&self, change_tick: u64) -> bool
+    where
+        ComponentType: ComponentTrait,
+    {
+        self.component_change_tick::<ComponentType>()
+            .is_some_and(|component_change_tick| component_change_tick > change_tick)
+    }
+
     pub fn iter_entity_component_ids(&self) -> impl std::iter::Iterator<Item = &ComponentId> {
         self.entity.component_ids.iter()
     }
 }
+
+// e.g. Entity { id: EntityId { .. }, name: Some("player"), components: ["game_2::Health", ..] }
+impl std::fmt::Debug for EntityHandler<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let component_type_names = self
+            .entity
+            .component_ids
+            .iter()
+            .filter_map(|component_id| {
+                let component_storages = unsafe { &*self.component_storages_guard };
+                component_storages
+                    .get_component_ref_any(component_id)
+                    .map(|component| component.component_type_name())
+            })
+            .collect::<Vec<&str>>();
+
+        f.debug_struct("Entity")
+            .field("id", &self.entity.id)
+            .field(
+                "name",
+                &self.get_component_ref::<Name>().map(|name| name.0.clone()),
+            )
+            .field("components", &component_type_names)
+            .finish()
+    }
+}
@@ -0,0 +1,144 @@
+use super::{EntityContainerGuard, EntityId};
+
+// the hierarchy components are only modified by the entity container guard, so the parent of an
+// entity always lists the entity as its child
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Parent {
+    entity_id: EntityId,
+}
+
+impl Parent {
+    pub fn entity_id(&self) -> EntityId {
+        self.entity_id
+    }
+}
+
+// the component is removed when the last child is detached
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Children {
+    entity_ids: Vec<EntityId>,
+}
+
+impl Children {
+    pub fn entity_ids(&self) -> &[EntityId] {
+        &self.entity_ids
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EntityHierarchyError {
+    InvalidEntityId(EntityId),
+    CycleDetected {
+        parent_id: EntityId,
+        child_id: EntityId,
+    },
+}
+
+impl EntityContainerGuard<'_> {
+    pub fn parent_of(&mut self, entity_id: &EntityId) -> Option<EntityId> {
+        let entity_handler = self.handler_for_entity(entity_id)?;
+        let parent = entity_handler.get_component_ref::<Parent>()?;
+        Some(parent.entity_id)
+    }
+
+    // the children are in the order they were attached in
+    pub fn children_of(&mut self, entity_id: &EntityId) -> Vec<EntityId> {
+        self.handler_for_entity(entity_id)
+            .and_then(|entity_handler| {
+                entity_handler
+                    .get_component_ref::<Children>()
+                    .map(|children| children.entity_ids.clone())
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn attach_child(
+        &mut self,
+        parent_id: &EntityId,
+        child_id: &EntityId,
+    ) -> Result<(), EntityHierarchyError> {
+        self.attach_child_at(parent_id, child_id, usize::MAX)
+    }
+
+    // the child is detached from its previous parent first, an index past the last child appends it
+    pub fn attach_child_at(
+        &mut self,
+        parent_id: &EntityId,
+        child_id: &EntityId,
+        index: usize,
+    ) -> Result<(), EntityHierarchyError> {
+        for entity_id in [parent_id, child_id] {
+            if self.handler_for_entity(entity_id).is_none() {
+                return Err(EntityHierarchyError::InvalidEntityId(*entity_id));
+            }
+        }
+
+        // the hierarchy is kept free of cycles, so walking up from the parent terminates
+        let mut ancestor_id = Some(*parent_id);
+        while let Some(current_id) = ancestor_id {
+            if current_id == *child_id {
+                return Err(EntityHierarchyError::CycleDetected {
+                    parent_id: *parent_id,
+                    child_id: *child_id,
+                });
+            }
+            ancestor_id = self.parent_of(&current_id);
+        }
+
+        self.detach_child(child_id);
+
+        if let Some(mut entity_handler) = self.handler_for_entity(parent_id) {
+            let is_inserted = entity_handler.change_component(|children: &mut Children| {
+                let index = index.min(children.entity_ids.len());
+                children.entity_ids.insert(index, *child_id);
+            });
+            if is_inserted.is_none() {
+                entity_handler.add_component(Children {
+                    entity_ids: vec![*child_id],
+                });
+            }
+        }
+
+        if let Some(mut entity_handler) = self.handler_for_entity(child_id) {
+            entity_handler.add_component(Parent {
+                entity_id: *parent_id,
+            });
+        }
+
+        Ok(())
+    }
+
+    // returns false if the entity does not have a parent
+    pub fn detach_child(&mut self, child_id: &EntityId) -> bool {
+        let Some(parent_id) = self.parent_of(child_id) else {
+            return false;
+        };
+
+        if let Some(mut entity_handler) = self.handler_for_entity(child_id) {
+            entity_handler.remove_component::<Parent>();
+        }
+
+        if let Some(mut entity_handler) = self.handler_for_entity(&parent_id) {
+            let has_children = entity_handler.change_component(|children: &mut Children| {
+                children
+                    .entity_ids
+                    .retain(|entity_id_ref| *entity_id_ref != *child_id);
+                !children.entity_ids.is_empty()
+            });
+            if has_children == Some(false) {
+                entity_handler.remove_component::<Children>();
+            }
+        }
+
+        true
+    }
+
+    // the entity is detached from its parent and its children become roots
+    pub(super) fn detach_from_hierarchy(&mut self, entity_id: &EntityId) {
+        self.detach_child(entity_id);
+
+        for child_id in self.children_of(entity_id) {
+            self.detach_child(&child_id);
+        }
+    }
+}
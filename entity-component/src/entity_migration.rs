@@ -0,0 +1,120 @@
+use std::any::TypeId;
+
+use super::{component::ComponentTrait, EntityContainer, EntityContainerGuard, EntityId};
+
+// left in the storage of the source container, until the last id of the moved component is dropped
+struct MigratedComponent;
+
+impl EntityContainerGuard<'_> {
+    // the entity is removed, the hierarchy components are not taken, because they refer to the
+    // entities of this container
+    pub(super) fn take_entity(
+        &mut self,
+        entity_id: &EntityId,
+    ) -> Option<Vec<(TypeId, Box<dyn ComponentTrait>)>> {
+        if !self.is_alive(entity_id) {
+            return None;
+        }
+
+        self.detach_from_hierarchy(entity_id);
+
+        let component_ids = self
+            .handler_for_entity(entity_id)?
+            .iter_entity_component_ids()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let components = component_ids
+            .iter()
+            .filter_map(|component_id| {
+                let component_storage = self
+                    .component_storages_guard
+                    .component_storage_ref_for_type_id(&component_id.component_type_id)?;
+                // the tag is shared with the other entities, so a new one is moved instead
+                if let Some(create_tag) = component_storage.create_tag {
+                    if component_storage.is_shared_component(component_id) {
+                        return Some((component_id.component_type_id, create_tag()));
+                    }
+                }
+
+                let mut components_guard = component_storage.components.lock();
+                let component_box = components_guard.get_mut(component_id.object_pool_index)?;
+
+                Some((
+                    component_id.component_type_id,
+                    std::mem::replace(component_box, Box::new(MigratedComponent)),
+                ))
+            })
+            .collect();
+
+        drop(component_ids);
+        self.remove_entity(entity_id);
+
+        Some(components)
+    }
+}
+
+// moves every component of the entity to the destination container, e.g. from the world of the
+// editor to the world of the game, the children of the entity stay in the source container as
+// roots, returns the id of the entity in the destination container
+pub fn migrate_entity(
+    src: &EntityContainer,
+    dst: &EntityContainer,
+    entity_id: &EntityId,
+) -> Option<EntityId> {
+    // the containers are not locked at the same time, so two opposite migrations do not deadlock
+    let components = src.lock().take_entity(entity_id)?;
+
+    Some(dst.lock().add_entity(components.into_iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{component_type_list, Name};
+
+    struct Position(f32);
+
+    // e.g. a handler of the renderer, it cannot be copied, only moved
+    struct RendererHandler(u32);
+
+    #[test]
+    fn migrate_entity_between_containers() {
+        let editor_world = EntityContainer::new();
+        let play_world = EntityContainer::new();
+
+        let play_entity_group = play_world
+            .lock()
+            .entity_group(component_type_list![Position, RendererHandler]);
+
+        let parent_id = editor_world.entity_builder().build();
+        let entity_id = editor_world
+            .entity_builder()
+            .with_component(Position(1.5))
+            .with_component(RendererHandler(7))
+            .with_component(Name::new("crate"))
+            .build();
+        editor_world
+            .lock()
+            .attach_child(&parent_id, &entity_id)
+            .unwrap();
+
+        let migrated_id = migrate_entity(&editor_world, &play_world, &entity_id).unwrap();
+
+        assert!(!editor_world.is_alive(&entity_id));
+        assert!(editor_world.lock().children_of(&parent_id).is_empty());
+        assert_eq!(None, editor_world.find_by_name("crate"));
+
+        assert!(play_entity_group.contains(&migrated_id));
+        assert_eq!(Some(migrated_id), play_world.find_by_name("crate"));
+
+        let mut play_world_guard = play_world.lock();
+        let handler = play_world_guard.handler_for_entity(&migrated_id).unwrap();
+        assert_eq!(1.5, handler.get_component_ref::<Position>().unwrap().0);
+        assert_eq!(7, handler.get_component_ref::<RendererHandler>().unwrap().0);
+        drop(play_world_guard);
+
+        // the entity is not in the source container anymore
+        assert!(migrate_entity(&editor_world, &play_world, &entity_id).is_none());
+    }
+}
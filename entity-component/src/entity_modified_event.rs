@@ -1,3 +1,5 @@
+use std::any::TypeId;
+
 use super::{ComponentId, EntityId};
 
 pub(super) enum EntityModifiedEvent {
@@ -5,6 +7,11 @@ pub(super) enum EntityModifiedEvent {
         entity_id: EntityId,
         component_ids: *const Vec<ComponentId>,
     },
+    // the entities have the same component types
+    EntitiesAdded {
+        entity_ids: *const [EntityId],
+        component_type_ids: *const [TypeId],
+    },
     EntityRemoved {
         entity_id: EntityId,
         component_ids: *const Vec<ComponentId>,
@@ -0,0 +1,148 @@
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+};
+
+use bytifex_utils::containers::object_pool::ObjectPoolIndex;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    entity_modified_event::EntityModifiedEvent, EntityContainer, EntityContainerGuard, EntityId,
+};
+
+// several entities can have the same name, e.g. the spawned instances of a prefab
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Name(pub String);
+
+impl Name {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+// the events only tell which entities were touched, the names are read from the components when
+// the index is used next time, so the index is not locked while the components are modified
+#[derive(Default)]
+pub(super) struct EntityNameIndex {
+    entity_ids_by_name: HashMap<String, Vec<EntityId>>,
+    names_by_entity_index: BTreeMap<ObjectPoolIndex, String>,
+    to_be_refreshed_entity_ids: Vec<EntityId>,
+}
+
+impl EntityNameIndex {
+    pub(super) fn process_entity_modified_event(&mut self, event: &EntityModifiedEvent) {
+        let entity_id = match event {
+            EntityModifiedEvent::EntityAdded {
+                entity_id,
+                component_ids,
+            } => {
+                let component_ids_ref = unsafe { &**component_ids };
+                if !component_ids_ref
+                    .iter()
+                    .any(|component_id| component_id.is_component_type_of::<Name>())
+                {
+                    return;
+                }
+                entity_id
+            }
+            EntityModifiedEvent::EntitiesAdded {
+                entity_ids,
+                component_type_ids,
+            } => {
+                let component_type_ids_ref = unsafe { &**component_type_ids };
+                if component_type_ids_ref.contains(&TypeId::of::<Name>()) {
+                    let entity_ids_ref = unsafe { &**entity_ids };
+                    self.to_be_refreshed_entity_ids
+                        .extend(entity_ids_ref.iter().copied());
+                }
+                return;
+            }
+            EntityModifiedEvent::EntityRemoved { entity_id, .. } => entity_id,
+            EntityModifiedEvent::ComponentAdded {
+                entity_id,
+                component_id,
+                ..
+            }
+            | EntityModifiedEvent::ComponentRemoved {
+                entity_id,
+                component_id,
+                ..
+            }
+            | EntityModifiedEvent::ComponentChanged {
+                entity_id,
+                component_id,
+                ..
+            } => {
+                if !component_id.is_component_type_of::<Name>() {
+                    return;
+                }
+                entity_id
+            }
+        };
+
+        self.to_be_refreshed_entity_ids.push(*entity_id);
+    }
+
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.entity_ids_by_name.shrink_to_fit();
+        self.to_be_refreshed_entity_ids.shrink_to_fit();
+    }
+
+    fn refresh(&mut self, entity_container_guard: &mut EntityContainerGuard) {
+        for entity_id in std::mem::take(&mut self.to_be_refreshed_entity_ids) {
+            if let Some(name) = self.names_by_entity_index.remove(&entity_id.index()) {
+                if let Some(entity_ids) = self.entity_ids_by_name.get_mut(&name) {
+                    entity_ids.retain(|entity_id_ref| *entity_id_ref != entity_id);
+                    if entity_ids.is_empty() {
+                        self.entity_ids_by_name.remove(&name);
+                    }
+                }
+            }
+
+            let name = entity_container_guard
+                .handler_for_entity(&entity_id)
+                .and_then(|entity_handler| {
+                    entity_handler
+                        .get_component_ref::<Name>()
+                        .map(|name| name.0.clone())
+                });
+            if let Some(name) = name {
+                self.entity_ids_by_name
+                    .entry(name.clone())
+                    .or_default()
+                    .push(entity_id);
+                self.names_by_entity_index.insert(entity_id.index(), name);
+            }
+        }
+    }
+}
+
+impl EntityContainer {
+    pub fn find_by_name(&self, name: &str) -> Option<EntityId> {
+        self.lock().find_by_name(name)
+    }
+}
+
+impl EntityContainerGuard<'_> {
+    // the entity that got the name first
+    pub fn find_by_name(&mut self, name: &str) -> Option<EntityId> {
+        self.find_all_by_name(name).first().copied()
+    }
+
+    // the entities are in the order they got the name in
+    pub fn find_all_by_name(&mut self, name: &str) -> Vec<EntityId> {
+        let entity_name_index = self.entity_name_index.clone();
+        let mut entity_name_index = entity_name_index.lock();
+        entity_name_index.refresh(self);
+
+        entity_name_index
+            .entity_ids_by_name
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,294 @@
+use std::{any::TypeId, marker::PhantomData, sync::atomic::AtomicU64};
+
+use bytifex_utils::{
+    containers::object_pool::{ObjectPool, ObjectPoolIterMut},
+    sync::{callback_event, types::MutexGuard},
+};
+
+use super::{
+    component::ComponentTrait, entity::Entity, entity_container::next_change_tick,
+    entity_modified_event::EntityModifiedEvent,
+    multi_type_component_storage::MultiTypeComponentStorage,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EntityQueryAccess {
+    Read,
+    Write,
+    // the entities whose component did not change since the tick of the query are skipped
+    Changed,
+}
+
+// a query filter that does not yield anything, e.g. (&Transform, Changed<Transform>)
+pub struct Changed<ComponentType>(PhantomData<ComponentType>);
+
+pub trait EntityQueryParam<'a> {
+    type Item;
+
+    fn component_type_id() -> TypeId;
+    fn access() -> EntityQueryAccess;
+
+    // the components of the read and the written params, in the order of the params
+    fn fetch(
+        component_boxes: &mut impl Iterator<Item = &'a mut Box<dyn ComponentTrait>>,
+    ) -> Option<Self::Item>;
+}
+
+impl<'a, ComponentType: ComponentTrait> EntityQueryParam<'a> for &ComponentType {
+    type Item = &'a ComponentType;
+
+    fn component_type_id() -> TypeId {
+        TypeId::of::<ComponentType>()
+    }
+
+    fn access() -> EntityQueryAccess {
+        EntityQueryAccess::Read
+    }
+
+    fn fetch(
+        component_boxes: &mut impl Iterator<Item = &'a mut Box<dyn ComponentTrait>>,
+    ) -> Option<Self::Item> {
+        (**component_boxes.next()?)
+            .as_any()
+            .downcast_ref::<ComponentType>()
+    }
+}
+
+impl<'a, ComponentType: ComponentTrait> EntityQueryParam<'a> for &mut ComponentType {
+    type Item = &'a mut ComponentType;
+
+    fn component_type_id() -> TypeId {
+        TypeId::of::<ComponentType>()
+    }
+
+    fn access() -> EntityQueryAccess {
+        EntityQueryAccess::Write
+    }
+
+    fn fetch(
+        component_boxes: &mut impl Iterator<Item = &'a mut Box<dyn ComponentTrait>>,
+    ) -> Option<Self::Item> {
+        (**component_boxes.next()?)
+            .as_any_mut()
+            .downcast_mut::<ComponentType>()
+    }
+}
+
+impl<'a, ComponentType: ComponentTrait> EntityQueryParam<'a> for Changed<ComponentType> {
+    type Item = ();
+
+    fn component_type_id() -> TypeId {
+        TypeId::of::<ComponentType>()
+    }
+
+    fn access() -> EntityQueryAccess {
+        EntityQueryAccess::Changed
+    }
+
+    fn fetch(
+        _component_boxes: &mut impl Iterator<Item = &'a mut Box<dyn ComponentTrait>>,
+    ) -> Option<Self::Item> {
+        Some(())
+    }
+}
+
+pub trait EntityQuery<'a> {
+    type Item;
+
+    // in the order of the params
+    fn component_type_ids() -> Vec<(TypeId, EntityQueryAccess)>;
+
+    // the components of the read and the written params, in the order of the params
+    fn fetch(
+        component_boxes: &mut impl Iterator<Item = &'a mut Box<dyn ComponentTrait>>,
+    ) -> Option<Self::Item>;
+}
+
+macro_rules! entity_query_tuple {
+    ( $( $param:ident ),+ ) => {
+        impl<'a, $( $param: EntityQueryParam<'a> ),+> EntityQuery<'a> for ( $( $param, )+ ) {
+            type Item = ( $( $param::Item, )+ );
+
+            fn component_type_ids() -> Vec<(TypeId, EntityQueryAccess)> {
+                vec![ $( ($param::component_type_id(), $param::access()), )+ ]
+            }
+
+            fn fetch(
+                component_boxes: &mut impl Iterator<Item = &'a mut Box<dyn ComponentTrait>>,
+            ) -> Option<Self::Item> {
+                Some(( $( $param::fetch(component_boxes)?, )+ ))
+            }
+        }
+    };
+}
+
+entity_query_tuple!(A);
+entity_query_tuple!(A, B);
+entity_query_tuple!(A, B, C);
+entity_query_tuple!(A, B, C, D);
+entity_query_tuple!(A, B, C, D, E);
+entity_query_tuple!(A, B, C, D, E, F);
+entity_query_tuple!(A, B, C, D, E, F, G);
+entity_query_tuple!(A, B, C, D, E, F, G, H);
+
+// the component storages of the queried types are locked once for the whole iteration, instead
+// of once per component, the storages do not change while the entity container guard is
+// borrowed, so the yielded references stay valid for 'a
+pub struct EntityQueryIter<'a, 'entity_container_guards, Query: EntityQuery<'a>> {
+    entities_iter: ObjectPoolIterMut<'a, Entity>,
+    component_type_ids: Vec<(TypeId, EntityQueryAccess)>,
+    // the storages of the read and the written params, none if a queried component type has
+    // never been added to any entity
+    components_guards: Option<Vec<MutexGuard<'a, ObjectPool<Box<dyn ComponentTrait>>>>>,
+    component_boxes: Vec<*mut Box<dyn ComponentTrait>>,
+    component_indices: Vec<usize>,
+    since_change_tick: u64,
+    change_tick: &'a AtomicU64,
+    entity_modified_event_guard:
+        &'a MutexGuard<'entity_container_guards, callback_event::Sender<EntityModifiedEvent>>,
+    marker: PhantomData<Query>,
+}
+
+impl<'a, 'entity_container_guards, Query: EntityQuery<'a>>
+    EntityQueryIter<'a, 'entity_container_guards, Query>
+{
+    pub(super) fn new(
+        entities_iter: ObjectPoolIterMut<'a, Entity>,
+        component_storages: &'a MultiTypeComponentStorage,
+        since_change_tick: u64,
+        change_tick: &'a AtomicU64,
+        entity_modified_event_guard: &'a MutexGuard<
+            'entity_container_guards,
+            callback_event::Sender<EntityModifiedEvent>,
+        >,
+    ) -> Self {
+        let component_type_ids = Query::component_type_ids();
+        let accessed_component_type_ids = component_type_ids
+            .iter()
+            .filter(|(_, access)| *access != EntityQueryAccess::Changed)
+            .map(|(component_type_id, _)| *component_type_id)
+            .collect::<Vec<TypeId>>();
+
+        // a storage would be locked twice otherwise
+        for (index, component_type_id) in accessed_component_type_ids.iter().enumerate() {
+            assert!(
+                !accessed_component_type_ids[..index].contains(component_type_id),
+                "Querying entities, msg = a component type is queried more than once"
+            );
+        }
+
+        // every entity refers to the same object of a tag, the mutable references would alias
+        for (component_type_id, access) in component_type_ids.iter() {
+            let is_tag = component_storages
+                .component_storage_ref_for_type_id(component_type_id)
+                .is_some_and(|component_storage| component_storage.create_tag.is_some());
+            assert!(
+                *access != EntityQueryAccess::Write || !is_tag,
+                "Querying entities, msg = a tag component is queried mutably"
+            );
+        }
+
+        let components_guards = accessed_component_type_ids
+            .iter()
+            .map(|component_type_id| {
+                component_storages
+                    .component_storage_ref_for_type_id(component_type_id)
+                    .map(|component_storage| component_storage.components.lock())
+            })
+            .collect();
+
+        Self {
+            entities_iter,
+            component_boxes: Vec::with_capacity(accessed_component_type_ids.len()),
+            component_indices: Vec::with_capacity(component_type_ids.len()),
+            component_type_ids,
+            components_guards,
+            since_change_tick,
+            change_tick,
+            entity_modified_event_guard,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'entity_container_guards, Query: EntityQuery<'a>> Iterator
+    for EntityQueryIter<'a, 'entity_container_guards, Query>
+{
+    type Item = Query::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let components_guards = self.components_guards.as_mut()?;
+
+        for entity in self.entities_iter.by_ref() {
+            self.component_boxes.clear();
+            self.component_indices.clear();
+
+            let mut components_guards_iter = components_guards.iter_mut();
+            for (component_type_id, access) in self.component_type_ids.iter() {
+                let Some(index) = entity.component_ids.iter().position(|component_id_ref| {
+                    component_id_ref.component_type_id == *component_type_id
+                }) else {
+                    break;
+                };
+
+                if *access == EntityQueryAccess::Changed {
+                    if entity.component_change_ticks[index] <= self.since_change_tick {
+                        break;
+                    }
+                } else {
+                    let Some(component_box) =
+                        components_guards_iter.next().and_then(|components_guard| {
+                            components_guard.get_mut(entity.component_ids[index].object_pool_index)
+                        })
+                    else {
+                        break;
+                    };
+
+                    self.component_boxes.push(component_box);
+                }
+
+                self.component_indices.push(index);
+            }
+
+            if self.component_indices.len() != self.component_type_ids.len() {
+                continue;
+            }
+
+            // every entity yields different components, so the mutable references do not alias,
+            // the tags are not queried mutably
+            let Some(item) = Query::fetch(
+                &mut self
+                    .component_boxes
+                    .iter()
+                    .map(|component_box| unsafe { &mut **component_box }),
+            ) else {
+                continue;
+            };
+
+            // the mutably queried components are reported as changed, the entity groups forward
+            // the event to their receivers, that are processed after the iteration
+            for ((_, access), index) in self
+                .component_type_ids
+                .iter()
+                .zip(self.component_indices.iter())
+            {
+                if *access != EntityQueryAccess::Write {
+                    continue;
+                }
+
+                entity.component_change_ticks[*index] = next_change_tick(self.change_tick);
+
+                self.entity_modified_event_guard
+                    .trigger(&EntityModifiedEvent::ComponentChanged {
+                        entity_id: entity.id,
+                        component_id: entity.component_ids[*index].clone(),
+                        component_ids: &entity.component_ids,
+                    });
+            }
+
+            return Some(item);
+        }
+
+        None
+    }
+}
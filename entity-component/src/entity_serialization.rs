@@ -0,0 +1,263 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+};
+
+use bytifex_utils::containers::object_pool::ObjectPoolIndex;
+use ron::value::RawValue;
+use serde::{Deserialize, Serialize};
+
+use super::{ComponentId, ComponentRegistry, EntityContainer, EntityId};
+
+#[derive(Debug, Clone)]
+pub enum EntitySerializationError {
+    CannotSerialize { msg: String },
+    CannotDeserialize { msg: String },
+    UnregisteredComponent { name: String },
+    InvalidChildIndex { index: usize },
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedEntity {
+    components: BTreeMap<String, Box<RawValue>>,
+    // the indices of the children in the serialized entities, in the order of the children
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedEntities {
+    entities: Vec<SerializedEntity>,
+}
+
+impl EntityContainer {
+    // the entities are written in the ron format
+    pub fn serialize_to(
+        &self,
+        component_registry: &ComponentRegistry,
+        writer: impl Write,
+    ) -> Result<(), EntitySerializationError> {
+        let entity_ids = self
+            .lock()
+            .iter()
+            .map(|entity_handler| entity_handler.entity.id)
+            .collect::<Vec<EntityId>>();
+
+        self.serialize_entities_to(component_registry, &entity_ids, writer)
+    }
+
+    // e.g. the entities of a group, the children that are not in the list are left out
+    pub fn serialize_entities_to(
+        &self,
+        component_registry: &ComponentRegistry,
+        entity_ids: &[EntityId],
+        writer: impl Write,
+    ) -> Result<(), EntitySerializationError> {
+        let mut entity_container_guard = self.lock();
+
+        let entity_ids = entity_ids
+            .iter()
+            .filter(|entity_id| {
+                entity_container_guard
+                    .handler_for_entity(entity_id)
+                    .is_some()
+            })
+            .copied()
+            .collect::<Vec<EntityId>>();
+        let indices = entity_ids
+            .iter()
+            .enumerate()
+            .map(|(index, entity_id)| (entity_id.index(), index))
+            .collect::<BTreeMap<ObjectPoolIndex, usize>>();
+
+        let mut serialized_entities = SerializedEntities {
+            entities: Vec::with_capacity(entity_ids.len()),
+        };
+        for entity_id in entity_ids.iter() {
+            let component_ids = match entity_container_guard.handler_for_entity(entity_id) {
+                Some(entity_handler) => entity_handler
+                    .iter_entity_component_ids()
+                    .cloned()
+                    .collect::<Vec<ComponentId>>(),
+                None => unreachable!(),
+            };
+
+            let mut components = BTreeMap::new();
+            for component_id in component_ids.iter() {
+                let Some(component) = entity_container_guard
+                    .component_storage()
+                    .get_component_ref_any(component_id)
+                else {
+                    continue;
+                };
+
+                if let Some((name, raw_value)) = component_registry
+                    .serialize_component(&component_id.component_type_id, &*component)
+                {
+                    let raw_value = raw_value
+                        .map_err(|msg| EntitySerializationError::CannotSerialize { msg })?;
+                    components.insert(name.to_string(), raw_value);
+                }
+            }
+
+            let children = entity_container_guard
+                .children_of(entity_id)
+                .iter()
+                .filter_map(|child_id| indices.get(&child_id.index()).copied())
+                .collect();
+
+            serialized_entities.entities.push(SerializedEntity {
+                components,
+                children,
+            });
+        }
+
+        ron::ser::to_writer_pretty(
+            writer,
+            &serialized_entities,
+            ron::ser::PrettyConfig::default(),
+        )
+        .map_err(|e| EntitySerializationError::CannotSerialize { msg: e.to_string() })
+    }
+
+    // the entities are added next to the existing ones, the ids are in the order of the serialized
+    // entities
+    pub fn deserialize_from(
+        &self,
+        component_registry: &ComponentRegistry,
+        reader: impl Read,
+    ) -> Result<Vec<EntityId>, EntitySerializationError> {
+        let serialized_entities: SerializedEntities = ron::de::from_reader(reader)
+            .map_err(|e| EntitySerializationError::CannotDeserialize { msg: e.to_string() })?;
+
+        // every component is deserialized before the first entity is added, so an invalid file
+        // does not leave a part of the entities behind
+        let mut components_of_entities = Vec::with_capacity(serialized_entities.entities.len());
+        for serialized_entity in serialized_entities.entities.iter() {
+            let mut components = Vec::with_capacity(serialized_entity.components.len());
+            for (name, raw_value) in serialized_entity.components.iter() {
+                let Some((component_type_id, component)) =
+                    component_registry.deserialize_component(name, raw_value)
+                else {
+                    return Err(EntitySerializationError::UnregisteredComponent {
+                        name: name.clone(),
+                    });
+                };
+
+                components.push((
+                    component_type_id,
+                    component.map_err(|msg| EntitySerializationError::CannotDeserialize { msg })?,
+                ));
+            }
+            components_of_entities.push(components);
+
+            if let Some(index) = serialized_entity
+                .children
+                .iter()
+                .find(|index| **index >= serialized_entities.entities.len())
+            {
+                return Err(EntitySerializationError::InvalidChildIndex { index: *index });
+            }
+        }
+
+        let mut entity_container_guard = self.lock();
+
+        let entity_ids = components_of_entities
+            .into_iter()
+            .map(|components| entity_container_guard.add_entity(components.into_iter()))
+            .collect::<Vec<EntityId>>();
+
+        for (serialized_entity, parent_id) in
+            serialized_entities.entities.iter().zip(entity_ids.iter())
+        {
+            for child_index in serialized_entity.children.iter() {
+                entity_container_guard
+                    .attach_child(parent_id, &entity_ids[*child_index])
+                    .map_err(|e| EntitySerializationError::CannotDeserialize {
+                        msg: format!("{e:?}"),
+                    })?;
+            }
+        }
+
+        Ok(entity_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Position {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Team {
+        Red,
+        Blue,
+    }
+
+    // not registered, so it is not saved
+    struct Handler;
+
+    #[test]
+    fn save_and_load_entities() {
+        let mut component_registry = ComponentRegistry::new();
+        component_registry.register_component::<Position>();
+        component_registry.register_component_with_name::<Team>("team");
+
+        let entity_container = EntityContainer::new();
+        let root_id = entity_container
+            .entity_builder()
+            .with_component(Position { x: 1.0, y: 2.0 })
+            .with_component(Team::Red)
+            .with_component(Handler)
+            .build();
+        let child_id = entity_container
+            .entity_builder()
+            .with_component(Team::Blue)
+            .build();
+        entity_container
+            .lock()
+            .attach_child(&root_id, &child_id)
+            .unwrap();
+
+        let mut serialized = Vec::new();
+        entity_container
+            .serialize_to(&component_registry, &mut serialized)
+            .unwrap();
+
+        let loaded_entity_container = EntityContainer::new();
+        let loaded_ids = loaded_entity_container
+            .deserialize_from(&component_registry, serialized.as_slice())
+            .unwrap();
+        assert_eq!(2, loaded_ids.len());
+
+        let mut entity_container_guard = loaded_entity_container.lock();
+        let handler = entity_container_guard
+            .handler_for_entity(&loaded_ids[0])
+            .unwrap();
+        assert_eq!(
+            Position { x: 1.0, y: 2.0 },
+            *handler.get_component_ref::<Position>().unwrap()
+        );
+        assert_eq!(Team::Red, *handler.get_component_ref::<Team>().unwrap());
+        assert!(handler.get_component_ref::<Handler>().is_none());
+
+        assert_eq!(
+            vec![loaded_ids[1]],
+            entity_container_guard.children_of(&loaded_ids[0])
+        );
+        drop(entity_container_guard);
+
+        // the names of the components have to be registered to load them
+        let empty_component_registry = ComponentRegistry::new();
+        assert!(matches!(
+            EntityContainer::new()
+                .deserialize_from(&empty_component_registry, serialized.as_slice()),
+            Err(EntitySerializationError::UnregisteredComponent { .. })
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+use super::{component::ComponentTrait, EntityContainer, EntityContainerGuard};
+
+impl EntityContainer {
+    pub fn register_tag<TagType>(&self) -> bool
+    where
+        TagType: ComponentTrait + Default,
+    {
+        self.lock().register_tag::<TagType>()
+    }
+}
+
+impl EntityContainerGuard<'_> {
+    // every entity refers to the same object of a tag, e.g. the markers of the controlled
+    // characters do not take a slot of the pool per entity, returns false if the type is not zero
+    // sized, because those components can differ
+    pub fn register_tag<TagType>(&mut self) -> bool
+    where
+        TagType: ComponentTrait + Default,
+    {
+        if std::mem::size_of::<TagType>() != 0 {
+            return false;
+        }
+
+        self.component_storages_guard
+            .component_storage_mut_for_type_id(&std::any::TypeId::of::<TagType>())
+            .make_tag_storage(|| Box::new(TagType::default()));
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{component_type_list, migrate_entity};
+
+    #[derive(Default)]
+    struct Enemy;
+
+    #[derive(Default)]
+    struct Health(u32);
+
+    #[test]
+    fn tags_share_a_single_component() {
+        let entity_container = EntityContainer::new();
+        assert!(entity_container.register_tag::<Enemy>());
+        assert!(!entity_container.register_tag::<Health>());
+
+        let enemy_entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list![Enemy]);
+
+        let entity_ids = (0..100)
+            .map(|index| {
+                entity_container
+                    .entity_builder()
+                    .with_component(Enemy)
+                    .with_component(Health(index))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(100, enemy_entity_group.iter_entity_ids().count());
+
+        let memory_stats = entity_container.memory_stats();
+        let number_of_components_of = |type_name: &str| {
+            memory_stats
+                .component_storages
+                .iter()
+                .find(|storage_stats| storage_stats.component_type_name == type_name)
+                .map(|storage_stats| storage_stats.number_of_components)
+        };
+        assert_eq!(
+            Some(1),
+            number_of_components_of(std::any::type_name::<Enemy>())
+        );
+        assert_eq!(
+            Some(100),
+            number_of_components_of(std::any::type_name::<Health>())
+        );
+
+        // removing the tag of an entity does not remove the tag of the others
+        let mut entity_container_guard = entity_container.lock();
+        let mut handler = entity_container_guard
+            .handler_for_entity(&entity_ids[0])
+            .unwrap();
+        assert!(handler.remove_component::<Enemy>().is_some());
+        drop(entity_container_guard);
+        assert!(!enemy_entity_group.contains(&entity_ids[0]));
+        assert!(entity_container
+            .lock()
+            .handler_for_entity(&entity_ids[1])
+            .unwrap()
+            .get_component_ref::<Enemy>()
+            .is_some());
+
+        // the migrated entity takes a new tag, the shared one stays in the source container
+        let other_entity_container = EntityContainer::new();
+        let migrated_id =
+            migrate_entity(&entity_container, &other_entity_container, &entity_ids[1]).unwrap();
+        assert!(other_entity_container
+            .lock()
+            .handler_for_entity(&migrated_id)
+            .unwrap()
+            .get_component_ref::<Enemy>()
+            .is_some());
+        assert!(entity_container
+            .lock()
+            .handler_for_entity(&entity_ids[2])
+            .unwrap()
+            .get_component_ref::<Enemy>()
+            .is_some());
+        assert_eq!(
+            Some(2),
+            entity_container
+                .lock()
+                .handler_for_entity(&entity_ids[2])
+                .unwrap()
+                .get_component_ref::<Health>()
+                .map(|health| health.0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "a tag component is queried mutably")]
+    fn tags_cannot_be_queried_mutably() {
+        let entity_container = EntityContainer::new();
+        assert!(entity_container.register_tag::<Enemy>());
+
+        for _ in 0..2 {
+            entity_container
+                .entity_builder()
+                .with_component(Enemy)
+                .build();
+        }
+
+        let mut entity_container_guard = entity_container.lock();
+        let _ = entity_container_guard.query::<(&mut Enemy,)>().count();
+    }
+}
@@ -1,22 +1,54 @@
 mod component;
 mod component_id;
+mod component_reflection;
+mod component_registry;
+mod component_remove_callback;
 mod component_storage;
 mod component_type_list;
 mod entity;
+mod entity_batch;
 mod entity_builder;
+mod entity_clone;
 mod entity_container;
+mod entity_container_memory;
+mod entity_container_read;
 mod entity_group;
 mod entity_group_event;
+mod entity_group_reaction;
+mod entity_group_sorted;
 mod entity_handler;
+mod entity_hierarchy;
+mod entity_migration;
 mod entity_modified_event;
+mod entity_name;
+mod entity_query;
+mod entity_serialization;
+mod entity_tag;
 mod multi_type_component_storage;
+mod prefab;
+mod resources;
 
 pub use component_id::*;
+pub use component_reflection::*;
+pub use component_registry::*;
+pub use component_type_list::*;
+pub use entity_batch::*;
 pub use entity_builder::*;
+pub use entity_clone::*;
 pub use entity_container::*;
+pub use entity_container_memory::*;
+pub use entity_container_read::*;
 pub use entity_group::*;
 pub use entity_group_event::*;
+pub use entity_group_reaction::*;
 pub use entity_handler::*;
+pub use entity_hierarchy::*;
+pub use entity_migration::*;
+pub use entity_name::*;
+pub use entity_query::*;
+pub use entity_serialization::*;
+pub use prefab::*;
+pub use resources::*;
 
 #[cfg(test)]
 mod tests {
@@ -33,6 +65,45 @@ mod tests {
         assert!(!entity_container_guard.remove_entity(&id));
     }
 
+    #[test]
+    fn stale_entity_ids_are_not_alive() {
+        struct Position(f32);
+
+        let entity_container = EntityContainer::new();
+
+        let removed_id = entity_container
+            .entity_builder()
+            .with_component(Position(1.0))
+            .build();
+        assert!(entity_container.is_alive(&removed_id));
+        assert!(entity_container.lock().remove_entity(&removed_id));
+
+        // the new entity may reuse the slot of the removed one
+        let id = entity_container
+            .entity_builder()
+            .with_component(Position(2.0))
+            .build();
+        assert_ne!(removed_id, id);
+
+        let mut entity_container_guard = entity_container.lock();
+        assert!(!entity_container_guard.is_alive(&removed_id));
+        assert!(entity_container_guard
+            .handler_for_entity(&removed_id)
+            .is_none());
+        assert!(!entity_container_guard.remove_entity(&removed_id));
+
+        assert!(entity_container_guard.is_alive(&id));
+        assert_eq!(
+            2.0,
+            entity_container_guard
+                .handler_for_entity(&id)
+                .unwrap()
+                .get_component_ref::<Position>()
+                .unwrap()
+                .0
+        );
+    }
+
     #[test]
     fn add_component() {
         let entity_container = EntityContainer::new();
@@ -133,4 +204,231 @@ mod tests {
             assert!(components.contains(&format!("component {}", index)));
         }
     }
+
+    #[test]
+    fn attach_detach_children() {
+        let entity_container = EntityContainer::new();
+
+        let root_id = entity_container.entity_builder().build();
+        let child_ids = (0..3)
+            .map(|_| entity_container.entity_builder().build())
+            .collect::<Vec<EntityId>>();
+        let grandchild_id = entity_container.entity_builder().build();
+
+        let mut entity_container_guard = entity_container.lock();
+
+        entity_container_guard
+            .attach_child(&root_id, &child_ids[0])
+            .unwrap();
+        entity_container_guard
+            .attach_child(&root_id, &child_ids[2])
+            .unwrap();
+        entity_container_guard
+            .attach_child_at(&root_id, &child_ids[1], 1)
+            .unwrap();
+        entity_container_guard
+            .attach_child(&child_ids[0], &grandchild_id)
+            .unwrap();
+
+        assert_eq!(child_ids, entity_container_guard.children_of(&root_id));
+        assert_eq!(
+            Some(child_ids[0]),
+            entity_container_guard.parent_of(&grandchild_id)
+        );
+
+        // the root would be its own ancestor
+        assert_eq!(
+            Err(EntityHierarchyError::CycleDetected {
+                parent_id: grandchild_id,
+                child_id: root_id,
+            }),
+            entity_container_guard.attach_child(&grandchild_id, &root_id)
+        );
+
+        // reattaching moves the grandchild
+        entity_container_guard
+            .attach_child(&child_ids[1], &grandchild_id)
+            .unwrap();
+        assert!(entity_container_guard.children_of(&child_ids[0]).is_empty());
+        assert_eq!(
+            vec![grandchild_id],
+            entity_container_guard.children_of(&child_ids[1])
+        );
+
+        assert!(entity_container_guard.detach_child(&child_ids[0]));
+        assert!(!entity_container_guard.detach_child(&child_ids[0]));
+        assert_eq!(
+            vec![child_ids[1], child_ids[2]],
+            entity_container_guard.children_of(&root_id)
+        );
+
+        // the children of a removed entity become roots
+        assert!(entity_container_guard.remove_entity(&child_ids[1]));
+        assert_eq!(None, entity_container_guard.parent_of(&grandchild_id));
+        assert_eq!(
+            vec![child_ids[2]],
+            entity_container_guard.children_of(&root_id)
+        );
+    }
+
+    #[test]
+    fn query_changed_components() {
+        struct Position(f32);
+
+        let entity_container = EntityContainer::new();
+
+        let ids = (0..3)
+            .map(|index| {
+                entity_container
+                    .entity_builder()
+                    .with_component(Position(index as f32))
+                    .build()
+            })
+            .collect::<Vec<EntityId>>();
+
+        let mut entity_container_guard = entity_container.lock();
+
+        // adding the components counts as a change
+        assert_eq!(
+            3,
+            entity_container_guard
+                .query_since::<(&Position, Changed<Position>)>(0)
+                .count()
+        );
+
+        let last_run_tick = entity_container_guard.change_tick();
+        assert_eq!(
+            0,
+            entity_container_guard
+                .query_since::<(&Position, Changed<Position>)>(last_run_tick)
+                .count()
+        );
+
+        let mut handler = entity_container_guard.handler_for_entity(&ids[1]).unwrap();
+        handler.change_component(|position: &mut Position| position.0 = 10.0);
+        assert!(handler.is_component_changed_since::<Position>(last_run_tick));
+
+        let changed_positions = entity_container_guard
+            .query_since::<(&Position, Changed<Position>)>(last_run_tick)
+            .map(|(position, _)| position.0)
+            .collect::<Vec<f32>>();
+        assert_eq!(vec![10.0], changed_positions);
+
+        // querying mutably counts as a change
+        let last_run_tick = entity_container_guard.change_tick();
+        entity_container_guard
+            .query::<(&mut Position,)>()
+            .for_each(|_| ());
+        assert_eq!(
+            3,
+            entity_container_guard
+                .query_since::<(Changed<Position>,)>(last_run_tick)
+                .count()
+        );
+    }
+
+    #[test]
+    fn query_components_of_entities() {
+        struct Position(f32);
+        struct Velocity(f32);
+
+        let entity_container = EntityContainer::new();
+
+        let moving_ids = (0..3)
+            .map(|index| {
+                entity_container
+                    .entity_builder()
+                    .with_component(Position(index as f32))
+                    .with_component(Velocity(10.0))
+                    .build()
+            })
+            .collect::<Vec<EntityId>>();
+        let still_id = entity_container
+            .entity_builder()
+            .with_component(Position(100.0))
+            .build();
+
+        let mut entity_container_guard = entity_container.lock();
+
+        let mut number_of_queried_entities = 0;
+        for (position, velocity) in entity_container_guard.query::<(&mut Position, &Velocity)>() {
+            position.0 += velocity.0;
+            number_of_queried_entities += 1;
+        }
+        assert_eq!(moving_ids.len(), number_of_queried_entities);
+
+        for (index, id) in moving_ids.iter().enumerate() {
+            let handler = entity_container_guard.handler_for_entity(id).unwrap();
+            assert_eq!(
+                index as f32 + 10.0,
+                handler.get_component_ref::<Position>().unwrap().0
+            );
+        }
+        let handler = entity_container_guard
+            .handler_for_entity(&still_id)
+            .unwrap();
+        assert_eq!(100.0, handler.get_component_ref::<Position>().unwrap().0);
+
+        // no entity has a string component
+        assert_eq!(
+            0,
+            entity_container_guard
+                .query::<(&Position, &String)>()
+                .count()
+        );
+    }
+
+    #[test]
+    fn find_entities_by_name() {
+        let entity_container = EntityContainer::new();
+
+        let player_id = entity_container
+            .entity_builder()
+            .with_component(Name::new("player"))
+            .build();
+        let goblin_ids = (0..2)
+            .map(|_| {
+                entity_container
+                    .entity_builder()
+                    .with_component(Name::new("goblin"))
+                    .build()
+            })
+            .collect::<Vec<EntityId>>();
+
+        assert_eq!(Some(player_id), entity_container.find_by_name("player"));
+        assert_eq!(
+            goblin_ids,
+            entity_container.lock().find_all_by_name("goblin")
+        );
+        assert_eq!(None, entity_container.find_by_name("orc"));
+
+        let mut entity_container_guard = entity_container.lock();
+
+        // renaming and removing are followed by the index
+        entity_container_guard
+            .handler_for_entity(&goblin_ids[0])
+            .unwrap()
+            .change_component(|name: &mut Name| *name = Name::new("orc"));
+        entity_container_guard.remove_entity(&goblin_ids[1]);
+        entity_container_guard
+            .handler_for_entity(&player_id)
+            .unwrap()
+            .remove_component::<Name>();
+
+        assert_eq!(
+            Some(goblin_ids[0]),
+            entity_container_guard.find_by_name("orc")
+        );
+        assert!(entity_container_guard.find_all_by_name("goblin").is_empty());
+        assert_eq!(None, entity_container_guard.find_by_name("player"));
+
+        let debug_output = format!(
+            "{:?}",
+            entity_container_guard
+                .handler_for_entity(&goblin_ids[0])
+                .unwrap()
+        );
+        assert!(debug_output.contains("\"orc\""));
+        assert!(debug_output.contains("Name"));
+    }
 }
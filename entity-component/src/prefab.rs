@@ -0,0 +1,144 @@
+use std::{any::TypeId, collections::BTreeMap, io::Read};
+
+use ron::value::RawValue;
+
+use super::{
+    component_registry::DeserializeComponentFn, ComponentRegistry, EntityBuilder, EntityContainer,
+};
+
+#[derive(Debug, Clone)]
+pub enum PrefabError {
+    CannotParsePrefab { msg: String },
+    UnregisteredComponent { name: String },
+    CannotDeserializeComponent { name: String, msg: String },
+}
+
+struct PrefabComponent {
+    name: String,
+    component_type_id: TypeId,
+    deserialize: DeserializeComponentFn,
+    raw_value: Box<RawValue>,
+}
+
+// a set of components in the ron format, e.g. { "health": (100), "team": Red }, every spawned
+// entity gets its own copy of the components
+pub struct Prefab {
+    components: Vec<PrefabComponent>,
+}
+
+impl Prefab {
+    // the components are checked against the registry, so spawning does not fail on a typo
+    pub fn parse(
+        reader: impl Read,
+        component_registry: &ComponentRegistry,
+    ) -> Result<Self, PrefabError> {
+        let raw_values: BTreeMap<String, Box<RawValue>> = ron::de::from_reader(reader)
+            .map_err(|e| PrefabError::CannotParsePrefab { msg: e.to_string() })?;
+
+        let components = raw_values
+            .into_iter()
+            .map(|(name, raw_value)| {
+                let Some((component_type_id, deserialize)) =
+                    component_registry.component_deserializer(&name)
+                else {
+                    return Err(PrefabError::UnregisteredComponent { name });
+                };
+
+                if let Err(msg) = deserialize(&raw_value) {
+                    return Err(PrefabError::CannotDeserializeComponent { name, msg });
+                }
+
+                Ok(PrefabComponent {
+                    name,
+                    component_type_id,
+                    deserialize,
+                    raw_value,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { components })
+    }
+
+    pub fn component_names(&self) -> impl Iterator<Item = &str> {
+        self.components
+            .iter()
+            .map(|component| component.name.as_str())
+    }
+}
+
+impl EntityContainer {
+    // the components that are added to the returned builder override the ones of the prefab
+    pub fn spawn_prefab(&self, prefab: &Prefab) -> Result<EntityBuilder, PrefabError> {
+        prefab.components.iter().try_fold(
+            self.entity_builder(),
+            |entity_builder, prefab_component| {
+                let component = (prefab_component.deserialize)(&prefab_component.raw_value)
+                    .map_err(|msg| PrefabError::CannotDeserializeComponent {
+                        name: prefab_component.name.clone(),
+                        msg,
+                    })?;
+
+                Ok(
+                    entity_builder
+                        .with_component_any(prefab_component.component_type_id, component),
+                )
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Health(u32);
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Name(String);
+
+    #[test]
+    fn spawn_prefab_with_overrides() {
+        let mut component_registry = ComponentRegistry::new();
+        component_registry.register_component_with_name::<Health>("health");
+        component_registry.register_component_with_name::<Name>("name");
+
+        let prefab = Prefab::parse(
+            r#"{ "health": (100), "name": ("goblin") }"#.as_bytes(),
+            &component_registry,
+        )
+        .unwrap();
+
+        let entity_container = EntityContainer::new();
+        let default_id = entity_container.spawn_prefab(&prefab).unwrap().build();
+        let overridden_id = entity_container
+            .spawn_prefab(&prefab)
+            .unwrap()
+            .with_component(Health(10))
+            .build();
+
+        let mut entity_container_guard = entity_container.lock();
+
+        let handler = entity_container_guard
+            .handler_for_entity(&default_id)
+            .unwrap();
+        assert_eq!(Health(100), *handler.get_component_ref::<Health>().unwrap());
+        assert_eq!(
+            Name("goblin".to_string()),
+            *handler.get_component_ref::<Name>().unwrap()
+        );
+
+        let handler = entity_container_guard
+            .handler_for_entity(&overridden_id)
+            .unwrap();
+        assert_eq!(Health(10), *handler.get_component_ref::<Health>().unwrap());
+
+        assert!(matches!(
+            Prefab::parse(r#"{ "mana": (5) }"#.as_bytes(), &component_registry),
+            Err(PrefabError::UnregisteredComponent { .. })
+        ));
+    }
+}
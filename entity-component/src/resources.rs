@@ -0,0 +1,193 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard, TryLockError},
+};
+
+#[derive(Debug, Clone)]
+pub enum ResourceError {
+    Missing { type_name: String },
+    AlreadyBorrowed { type_name: String },
+}
+
+type ResourceBox = Box<dyn Any + Send>;
+
+// the resources are borrowed one by one, so a system can write a resource while an other one is
+// read, borrowing a resource twice at the same time is an error instead of a deadlock
+#[derive(Default)]
+pub struct Resources {
+    resources: HashMap<TypeId, Mutex<ResourceBox>>,
+}
+
+pub struct ResourceGuard<'a, ResourceType> {
+    resource_guard: MutexGuard<'a, ResourceBox>,
+    marker: PhantomData<ResourceType>,
+}
+
+impl<ResourceType: Any> Deref for ResourceGuard<'_, ResourceType> {
+    type Target = ResourceType;
+
+    fn deref(&self) -> &Self::Target {
+        match self.resource_guard.downcast_ref::<ResourceType>() {
+            Some(resource) => resource,
+            None => unreachable!(),
+        }
+    }
+}
+
+pub struct ResourceMutGuard<'a, ResourceType> {
+    resource_guard: MutexGuard<'a, ResourceBox>,
+    marker: PhantomData<ResourceType>,
+}
+
+impl<ResourceType: Any> Deref for ResourceMutGuard<'_, ResourceType> {
+    type Target = ResourceType;
+
+    fn deref(&self) -> &Self::Target {
+        match self.resource_guard.downcast_ref::<ResourceType>() {
+            Some(resource) => resource,
+            None => unreachable!(),
+        }
+    }
+}
+
+impl<ResourceType: Any> DerefMut for ResourceMutGuard<'_, ResourceType> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self.resource_guard.downcast_mut::<ResourceType>() {
+            Some(resource) => resource,
+            None => unreachable!(),
+        }
+    }
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // returns the previous resource of the same type
+    pub fn insert<ResourceType: Any + Send>(
+        &mut self,
+        resource: ResourceType,
+    ) -> Option<ResourceType> {
+        self.resources
+            .insert(TypeId::of::<ResourceType>(), Mutex::new(Box::new(resource)))
+            .and_then(into_resource)
+    }
+
+    pub fn remove<ResourceType: Any + Send>(&mut self) -> Option<ResourceType> {
+        self.resources
+            .remove(&TypeId::of::<ResourceType>())
+            .and_then(into_resource)
+    }
+
+    pub fn contains<ResourceType: Any + Send>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<ResourceType>())
+    }
+
+    pub fn get<ResourceType: Any + Send>(
+        &self,
+    ) -> Result<ResourceGuard<'_, ResourceType>, ResourceError> {
+        Ok(ResourceGuard {
+            resource_guard: self.borrow::<ResourceType>()?,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn get_mut<ResourceType: Any + Send>(
+        &self,
+    ) -> Result<ResourceMutGuard<'_, ResourceType>, ResourceError> {
+        Ok(ResourceMutGuard {
+            resource_guard: self.borrow::<ResourceType>()?,
+            marker: PhantomData,
+        })
+    }
+
+    fn borrow<ResourceType: Any + Send>(
+        &self,
+    ) -> Result<MutexGuard<'_, ResourceBox>, ResourceError> {
+        let resource = self
+            .resources
+            .get(&TypeId::of::<ResourceType>())
+            .ok_or_else(|| ResourceError::Missing {
+                type_name: type_name::<ResourceType>().to_string(),
+            })?;
+
+        match resource.try_lock() {
+            Ok(resource_guard) => Ok(resource_guard),
+            // a panic while the resource was borrowed does not make it unusable
+            Err(TryLockError::Poisoned(e)) => Ok(e.into_inner()),
+            Err(TryLockError::WouldBlock) => Err(ResourceError::AlreadyBorrowed {
+                type_name: type_name::<ResourceType>().to_string(),
+            }),
+        }
+    }
+}
+
+fn into_resource<ResourceType: Any + Send>(resource: Mutex<ResourceBox>) -> Option<ResourceType> {
+    resource
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .downcast::<ResourceType>()
+        .ok()
+        .map(|resource| *resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityContainer;
+
+    #[derive(Debug, PartialEq)]
+    struct ElapsedSecs(f32);
+
+    #[derive(Debug, PartialEq)]
+    struct PressedKeys(Vec<char>);
+
+    #[test]
+    fn insert_borrow_and_remove_resources() {
+        let entity_container = EntityContainer::new();
+        assert!(entity_container
+            .resources()
+            .insert(ElapsedSecs(0.0))
+            .is_none());
+        entity_container.resources().insert(PressedKeys(Vec::new()));
+
+        {
+            let resources = entity_container.resources();
+
+            // different resources can be borrowed at the same time
+            let mut elapsed_secs = resources.get_mut::<ElapsedSecs>().unwrap();
+            let pressed_keys = resources.get::<PressedKeys>().unwrap();
+            elapsed_secs.0 += 0.5;
+            assert!(pressed_keys.0.is_empty());
+
+            assert!(matches!(
+                resources.get::<ElapsedSecs>(),
+                Err(ResourceError::AlreadyBorrowed { .. })
+            ));
+            assert!(matches!(
+                resources.get::<String>(),
+                Err(ResourceError::Missing { .. })
+            ));
+        }
+
+        // the resources do not lock the entities
+        let _entity_container_guard = entity_container.lock();
+        assert_eq!(
+            ElapsedSecs(0.5),
+            *entity_container.resources().get::<ElapsedSecs>().unwrap()
+        );
+        assert_eq!(
+            Some(ElapsedSecs(0.5)),
+            entity_container.resources().insert(ElapsedSecs(1.0))
+        );
+        assert_eq!(
+            Some(PressedKeys(Vec::new())),
+            entity_container.resources().remove::<PressedKeys>()
+        );
+        assert!(!entity_container.resources().contains::<PressedKeys>());
+    }
+}
@@ -1,6 +1,7 @@
-use vek::{Transform, Vec3};
+use vek::{Mat4, Transform, Vec3};
 
 #[repr(C)]
+#[derive(Debug, Clone, Copy)]
 pub struct AxisAlignedBoundingBox {
     min_vertex: Vec3<f32>,
     max_vertex: Vec3<f32>,
@@ -27,6 +28,74 @@ impl AxisAlignedBoundingBox {
         self.max_vertex = Vec3::partial_max(self.max_vertex, vertex);
     }
 
+    pub fn add_aabb(&mut self, other: &AxisAlignedBoundingBox) {
+        self.add_vertex(other.min_vertex);
+        self.add_vertex(other.max_vertex);
+    }
+
+    pub fn corners(&self) -> [Vec3<f32>; 8] {
+        let min = self.min_vertex;
+        let max = self.max_vertex;
+
+        [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(min.x, max.y, max.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    // the result contains the transformed box, so it is larger if the matrix rotates
+    pub fn transformed_by_matrix(&self, matrix: &Mat4<f32>) -> AxisAlignedBoundingBox {
+        let corners = self.corners();
+
+        let mut aabb = AxisAlignedBoundingBox::new(matrix.mul_point(corners[0]));
+        for corner in corners.iter().skip(1) {
+            aabb.add_vertex(matrix.mul_point(*corner));
+        }
+
+        aabb
+    }
+
+    // returns the distance along the ray to the first intersection, or zero if the origin is inside
+    pub fn ray_intersection_distance(
+        &self,
+        ray_origin: Vec3<f32>,
+        ray_direction: Vec3<f32>,
+    ) -> Option<f32> {
+        let mut t_min = 0.0f32;
+        let mut t_max = f32::INFINITY;
+
+        for axis in 0..3 {
+            let origin = ray_origin[axis];
+            let direction = ray_direction[axis];
+            let min = self.min_vertex[axis];
+            let max = self.max_vertex[axis];
+
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return None;
+                }
+            } else {
+                let t0 = (min - origin) / direction;
+                let t1 = (max - origin) / direction;
+
+                t_min = t_min.max(t0.min(t1));
+                t_max = t_max.min(t0.max(t1));
+
+                if t_min > t_max {
+                    return None;
+                }
+            }
+        }
+
+        Some(t_min)
+    }
+
     pub fn collide(&self, other: &AxisAlignedBoundingBox) -> bool {
         let min_coordinates = Vec3::<f32>::partial_min(self.min_vertex, other.min_vertex);
         let max_coordinates = Vec3::<f32>::partial_max(self.max_vertex, other.max_vertex);
@@ -143,4 +212,27 @@ mod tests {
 
         aabb0.collide(&aabb1);
     }
+
+    #[test]
+    fn ray_intersection() {
+        let mut aabb = AxisAlignedBoundingBox::new(Vec3::new(-1.0, -1.0, -1.0));
+        aabb.add_vertex(Vec3::new(1.0, 1.0, 1.0));
+
+        assert_eq!(
+            Some(4.0),
+            aabb.ray_intersection_distance(Vec3::new(0.0, 0.0, 5.0), -Vec3::unit_z())
+        );
+        assert_eq!(
+            Some(0.0),
+            aabb.ray_intersection_distance(Vec3::zero(), Vec3::unit_x())
+        );
+        assert_eq!(
+            None,
+            aabb.ray_intersection_distance(Vec3::new(0.0, 0.0, 5.0), Vec3::unit_z())
+        );
+        assert_eq!(
+            None,
+            aabb.ray_intersection_distance(Vec3::new(2.0, 0.0, 5.0), -Vec3::unit_z())
+        );
+    }
 }
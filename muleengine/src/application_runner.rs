@@ -8,6 +8,7 @@ use tokio::{
 
 use crate::{
     fps_counter::FpsCounter,
+    profile_scope, profiler,
     service_container::ServiceContainer,
     stopwatch::Stopwatch,
     system_container::{SystemContainer, SystemContainerClient},
@@ -140,7 +141,11 @@ pub async fn async_run<ApplicationType>(
             task(&mut app_context);
         }
 
-        application.tick(&loop_start, last_loop_duration_secs, &mut app_context);
+        {
+            profile_scope!("application.tick");
+            application.tick(&loop_start, last_loop_duration_secs, &mut app_context);
+        }
+        profiler::end_frame();
 
         fps_counter.draw_happened();
         if fps_counter_stopwatch.elapsed() > Duration::from_millis(1000) {
@@ -4,7 +4,11 @@ use bytifex_utils::sync::types::ArcRwLock;
 use parking_lot::RwLock;
 
 use crate::{
-    asset_reader::AssetReader, image_container::ImageContainer, scene_container::SceneContainer,
+    asset_reader::{AssetReader, AsyncAssetReader},
+    font_container::FontContainer,
+    image_container::ImageContainer,
+    prefab_container::PrefabContainer,
+    scene_container::SceneContainer,
     service_container::ServiceContainer,
 };
 
@@ -14,6 +18,8 @@ pub struct AssetContainer {
 
     image_container: ArcRwLock<ImageContainer>,
     scene_container: ArcRwLock<SceneContainer>,
+    font_container: ArcRwLock<FontContainer>,
+    prefab_container: ArcRwLock<PrefabContainer>,
 }
 
 impl AssetContainer {
@@ -31,6 +37,14 @@ impl AssetContainer {
                 .get_service::<RwLock<ImageContainer>>()
                 .inspect_err(|e| log::error!("{e:?}"))
                 .unwrap(),
+            font_container: service_container
+                .get_service::<RwLock<FontContainer>>()
+                .inspect_err(|e| log::error!("{e:?}"))
+                .unwrap(),
+            prefab_container: service_container
+                .get_service::<RwLock<PrefabContainer>>()
+                .inspect_err(|e| log::error!("{e:?}"))
+                .unwrap(),
         }
     }
 
@@ -38,6 +52,11 @@ impl AssetContainer {
         &self.asset_reader
     }
 
+    // the async loading of the assets does not depend on the file system backend
+    pub fn async_asset_reader(&self) -> Arc<dyn AsyncAssetReader> {
+        self.asset_reader.clone()
+    }
+
     pub fn image_container(&self) -> &ArcRwLock<ImageContainer> {
         &self.image_container
     }
@@ -45,4 +64,12 @@ impl AssetContainer {
     pub fn scene_container(&self) -> &ArcRwLock<SceneContainer> {
         &self.scene_container
     }
+
+    pub fn font_container(&self) -> &ArcRwLock<FontContainer> {
+        &self.font_container
+    }
+
+    pub fn prefab_container(&self) -> &ArcRwLock<PrefabContainer> {
+        &self.prefab_container
+    }
 }
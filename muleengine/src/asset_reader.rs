@@ -1,5 +1,8 @@
 use std::path::Path;
 
+use futures::{future::BoxFuture, FutureExt};
+use tokio::io::AsyncRead;
+
 // the canonicalize() method of the Path resolves symlinks, therefore the following function is needed
 pub fn canonicalize_path(path: String) -> String {
     if path.is_empty() {
@@ -51,6 +54,15 @@ pub fn parent_path(path: String) -> String {
         .map_or("".to_string(), |path| path.to_str().unwrap().to_string())
 }
 
+pub type AsyncAssetStream = Box<dyn AsyncRead + Send + Unpin>;
+
+// the async loading of the assets depends only on this, so the assets can be read from another
+// backend than the file system, e.g. over http
+pub trait AsyncAssetReader: Send + Sync {
+    fn get_async_reader(&self, path: String) -> BoxFuture<'_, Option<AsyncAssetStream>>;
+    fn read_to_end_async(&self, path: String) -> BoxFuture<'_, Option<Vec<u8>>>;
+}
+
 #[derive(Clone)]
 pub struct AssetReader {}
 
@@ -70,6 +82,22 @@ impl AssetReader {
     }
 }
 
+impl AsyncAssetReader for AssetReader {
+    fn get_async_reader(&self, path: String) -> BoxFuture<'_, Option<AsyncAssetStream>> {
+        async move {
+            tokio::fs::File::open(canonicalize_path(path))
+                .await
+                .ok()
+                .map(|file| Box::new(tokio::io::BufReader::new(file)) as AsyncAssetStream)
+        }
+        .boxed()
+    }
+
+    fn read_to_end_async(&self, path: String) -> BoxFuture<'_, Option<Vec<u8>>> {
+        async move { tokio::fs::read(canonicalize_path(path)).await.ok() }.boxed()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +128,35 @@ mod tests {
         assert_eq!(parent_path("albedo.png".to_string()), "");
         assert_eq!(parent_path("".to_string()), "");
     }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn async_reading() {
+        use std::io::Write;
+        use tokio::io::AsyncReadExt;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"mule").unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+
+        let asset_reader: &dyn AsyncAssetReader = &AssetReader::new();
+
+        let mut content = String::new();
+        asset_reader
+            .get_async_reader(path.clone())
+            .await
+            .unwrap()
+            .read_to_string(&mut content)
+            .await
+            .unwrap();
+        assert_eq!("mule", content);
+
+        assert_eq!(
+            b"mule".to_vec(),
+            asset_reader.read_to_end_async(path).await.unwrap()
+        );
+        assert!(asset_reader
+            .get_async_reader("does/not/exist".to_string())
+            .await
+            .is_none());
+    }
 }
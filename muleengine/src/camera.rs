@@ -1,4 +1,27 @@
-use vek::{Quaternion, Transform, Vec3};
+use vek::{Mat4, Quaternion, Transform, Vec2, Vec3, Vec4};
+
+use crate::aabb::AxisAlignedBoundingBox;
+
+// the depth range of the normalized device coordinates that a projection matrix maps to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NdcDepthRange {
+    // e.g. the _zo projections of vek
+    ZeroToOne,
+    // e.g. the _no projections of vek, the default range of opengl
+    NegativeOneToOne,
+    // the near plane is at 1 and the far plane is at 0, e.g. after reverse_depth()
+    ReversedZeroToOne,
+}
+
+impl NdcDepthRange {
+    pub fn near_and_far_depth(&self) -> (f32, f32) {
+        match self {
+            Self::ZeroToOne => (0.0, 1.0),
+            Self::NegativeOneToOne => (-1.0, 1.0),
+            Self::ReversedZeroToOne => (1.0, 0.0),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Camera {
@@ -81,4 +104,302 @@ impl Camera {
             .orientation
             .rotate_3d(angle_radians, self.transform.orientation * Vec3::unit_z());
     }
+
+    pub fn compute_view_matrix(&self) -> Mat4<f32> {
+        let mut transform_matrix = Into::<Mat4<f32>>::into(self.transform);
+
+        transform_matrix.invert_affine_transform_no_scale();
+
+        transform_matrix
+    }
+
+    pub fn frustum_planes(&self, projection_matrix: &Mat4<f32>) -> Frustum {
+        Frustum::from_view_projection(*projection_matrix * self.compute_view_matrix())
+    }
+
+    // the pixel is in window coordinates with the origin in the top left corner,
+    // returns the point of the ray on the near plane and the direction of the ray
+    pub fn screen_point_to_ray(
+        &self,
+        projection_matrix: &Mat4<f32>,
+        ndc_depth_range: NdcDepthRange,
+        window_dimensions: Vec2<usize>,
+        pixel: Vec2<f32>,
+    ) -> Option<(Vec3<f32>, Vec3<f32>)> {
+        let window_dimensions = window_dimensions.map(|dimension| dimension.max(1) as f32);
+        let ndc_position = Vec2::new(
+            pixel.x / window_dimensions.x * 2.0 - 1.0,
+            1.0 - pixel.y / window_dimensions.y * 2.0,
+        );
+
+        let inverse_view_projection_matrix =
+            (*projection_matrix * self.compute_view_matrix()).inverted();
+        let unproject = |depth: f32| {
+            let position = inverse_view_projection_matrix
+                * Vec4::new(ndc_position.x, ndc_position.y, depth, 1.0);
+            position.xyz() / position.w
+        };
+
+        let (near_depth, far_depth) = ndc_depth_range.near_and_far_depth();
+        let near_position = unproject(near_depth);
+        let far_position = unproject(far_depth);
+
+        Some((
+            near_position,
+            (far_position - near_position).try_normalized()?,
+        ))
+    }
+
+    // returns none for the positions behind the camera, the positions outside of the window
+    // are returned as they are, so ui elements can be anchored to the edge of the window
+    pub fn world_to_screen(
+        &self,
+        projection_matrix: &Mat4<f32>,
+        window_dimensions: Vec2<usize>,
+        world_position: Vec3<f32>,
+    ) -> Option<Vec2<f32>> {
+        let clip_position =
+            *projection_matrix * self.compute_view_matrix() * world_position.with_w(1.0);
+        if clip_position.w <= 0.0 {
+            return None;
+        }
+
+        let ndc_position = clip_position.xy() / clip_position.w;
+        let window_dimensions = window_dimensions.map(|dimension| dimension as f32);
+
+        Some(Vec2::new(
+            (ndc_position.x + 1.0) / 2.0 * window_dimensions.x,
+            (1.0 - ndc_position.y) / 2.0 * window_dimensions.y,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrustumPlane {
+    pub normal: Vec3<f32>,
+    pub distance: f32,
+}
+
+impl FrustumPlane {
+    fn from_coefficients(coefficients: Vec4<f32>) -> Self {
+        let normal = Vec3::new(coefficients.x, coefficients.y, coefficients.z);
+        let length = normal.magnitude();
+        // e.g. a degenerate projection, the plane does not cull anything
+        if length == 0.0 {
+            return Self {
+                normal,
+                distance: coefficients.w.max(0.0),
+            };
+        }
+
+        Self {
+            normal: normal / length,
+            distance: coefficients.w / length,
+        }
+    }
+
+    pub fn signed_distance(&self, point: Vec3<f32>) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    planes: [FrustumPlane; 6],
+}
+
+impl Frustum {
+    pub fn from_view_projection(view_projection: Mat4<f32>) -> Self {
+        let rows = view_projection.transposed().cols;
+
+        Self {
+            planes: [
+                FrustumPlane::from_coefficients(rows.w + rows.x),
+                FrustumPlane::from_coefficients(rows.w - rows.x),
+                FrustumPlane::from_coefficients(rows.w + rows.y),
+                FrustumPlane::from_coefficients(rows.w - rows.y),
+                FrustumPlane::from_coefficients(rows.w + rows.z),
+                FrustumPlane::from_coefficients(rows.w - rows.z),
+            ],
+        }
+    }
+
+    pub fn planes(&self) -> &[FrustumPlane; 6] {
+        &self.planes
+    }
+
+    pub fn contains_point(&self, point: Vec3<f32>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    pub fn intersects_sphere(&self, center: Vec3<f32>, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(center) >= -radius)
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        let min_vertex = aabb.get_min_vertex();
+        let max_vertex = aabb.get_max_vertex();
+
+        self.planes.iter().all(|plane| {
+            let positive_vertex = Vec3::new(
+                if plane.normal.x >= 0.0 {
+                    max_vertex.x
+                } else {
+                    min_vertex.x
+                },
+                if plane.normal.y >= 0.0 {
+                    max_vertex.y
+                } else {
+                    min_vertex.y
+                },
+                if plane.normal.z >= 0.0 {
+                    max_vertex.z
+                } else {
+                    min_vertex.z
+                },
+            );
+
+            plane.signed_distance(positive_vertex) >= 0.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vek::{Mat4, Vec2, Vec3};
+
+    use crate::{aabb::AxisAlignedBoundingBox, renderer::renderer_layer_projection::reverse_depth};
+
+    use super::{Camera, Frustum, NdcDepthRange};
+
+    fn create_frustum() -> Frustum {
+        let projection =
+            Mat4::perspective_fov_rh_zo(std::f32::consts::FRAC_PI_2, 800.0, 600.0, 0.1, 100.0);
+        let view = Mat4::look_at_rh(Vec3::zero(), -Vec3::unit_z(), Vec3::unit_y());
+
+        Frustum::from_view_projection(projection * view)
+    }
+
+    #[test]
+    fn point_and_sphere_culling() {
+        let frustum = create_frustum();
+
+        assert!(frustum.contains_point(Vec3::new(0.0, 0.0, -10.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 10.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -200.0)));
+        assert!(!frustum.contains_point(Vec3::new(50.0, 0.0, -10.0)));
+
+        assert!(frustum.intersects_sphere(Vec3::new(0.0, 0.0, 1.0), 2.0));
+        assert!(!frustum.intersects_sphere(Vec3::new(0.0, 0.0, 10.0), 2.0));
+        assert!(frustum.intersects_sphere(Vec3::new(0.0, 0.0, -101.0), 2.0));
+    }
+
+    #[test]
+    fn aabb_culling() {
+        let frustum = create_frustum();
+
+        let mut visible_aabb = AxisAlignedBoundingBox::new(Vec3::new(-1.0, -1.0, -11.0));
+        visible_aabb.add_vertex(Vec3::new(1.0, 1.0, -9.0));
+
+        let mut straddling_aabb = AxisAlignedBoundingBox::new(Vec3::new(-1.0, -1.0, -1.0));
+        straddling_aabb.add_vertex(Vec3::new(1.0, 1.0, 1.0));
+
+        let mut behind_aabb = AxisAlignedBoundingBox::new(Vec3::new(-1.0, -1.0, 9.0));
+        behind_aabb.add_vertex(Vec3::new(1.0, 1.0, 11.0));
+
+        let mut aside_aabb = AxisAlignedBoundingBox::new(Vec3::new(49.0, -1.0, -11.0));
+        aside_aabb.add_vertex(Vec3::new(51.0, 1.0, -9.0));
+
+        assert!(frustum.intersects_aabb(&visible_aabb));
+        assert!(frustum.intersects_aabb(&straddling_aabb));
+        assert!(!frustum.intersects_aabb(&behind_aabb));
+        assert!(!frustum.intersects_aabb(&aside_aabb));
+    }
+
+    #[test]
+    fn screen_points_and_world_positions_are_converted_back_and_forth() {
+        let projection =
+            Mat4::perspective_fov_rh_zo(std::f32::consts::FRAC_PI_2, 800.0, 600.0, 0.1, 100.0);
+        let window_dimensions = Vec2::new(800, 600);
+
+        let mut camera = Camera::new();
+        camera.move_by(Vec3::new(0.0, 0.0, 5.0));
+
+        let frustum = camera.frustum_planes(&projection);
+        assert!(frustum.contains_point(Vec3::zero()));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 10.0)));
+
+        let (_, direction) = camera
+            .screen_point_to_ray(
+                &projection,
+                NdcDepthRange::ZeroToOne,
+                window_dimensions,
+                Vec2::new(400.0, 300.0),
+            )
+            .unwrap();
+        assert!((direction - -Vec3::unit_z()).magnitude() < 1e-4);
+
+        let pixel = Vec2::new(600.0, 150.0);
+        let (origin, direction) = camera
+            .screen_point_to_ray(
+                &projection,
+                NdcDepthRange::ZeroToOne,
+                window_dimensions,
+                pixel,
+            )
+            .unwrap();
+        let screen_position = camera
+            .world_to_screen(&projection, window_dimensions, origin + direction * 10.0)
+            .unwrap();
+        assert!((screen_position - pixel).magnitude() < 1e-2);
+
+        assert!(camera
+            .world_to_screen(&projection, window_dimensions, Vec3::new(0.0, 0.0, 10.0))
+            .is_none());
+    }
+
+    #[test]
+    fn the_origin_of_the_screen_ray_is_on_the_near_plane() {
+        let near = 0.1;
+        let aspect_ratio = 800.0 / 600.0;
+        let window_dimensions = Vec2::new(800, 600);
+
+        let mut camera = Camera::new();
+        camera.move_by(Vec3::new(1.0, 2.0, 5.0));
+
+        let zero_to_one_projection =
+            Mat4::perspective_fov_rh_zo(std::f32::consts::FRAC_PI_2, 800.0, 600.0, near, 100.0);
+        for (projection, ndc_depth_range) in [
+            (zero_to_one_projection, NdcDepthRange::ZeroToOne),
+            (
+                Mat4::perspective_fov_rh_no(std::f32::consts::FRAC_PI_2, 800.0, 600.0, near, 100.0),
+                NdcDepthRange::NegativeOneToOne,
+            ),
+            (
+                reverse_depth(zero_to_one_projection),
+                NdcDepthRange::ReversedZeroToOne,
+            ),
+        ] {
+            let (origin, direction) = camera
+                .screen_point_to_ray(
+                    &projection,
+                    ndc_depth_range,
+                    window_dimensions,
+                    Vec2::new(800.0, 0.0),
+                )
+                .unwrap();
+
+            // the top right corner of the window on the near plane
+            let view_space_origin = (camera.compute_view_matrix() * origin.with_w(1.0)).xyz();
+            assert!(
+                (view_space_origin - Vec3::new(near * aspect_ratio, near, -near)).magnitude()
+                    < 1e-4
+            );
+            assert!(direction.dot(-Vec3::unit_z()) > 0.0);
+        }
+    }
 }
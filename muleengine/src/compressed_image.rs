@@ -0,0 +1,370 @@
+// block compressed images that are uploaded to the gpu as they are, the pixels cannot be read on the cpu
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CompressedImageFormat {
+    Bc1RgbUnorm,
+    Bc1RgbaUnorm,
+    Bc2Unorm,
+    Bc3Unorm,
+    Bc4Unorm,
+    Bc5Unorm,
+    Bc6hUfloat,
+    Bc7Unorm,
+    Etc2Rgb8Unorm,
+    Etc2Rgba8Unorm,
+}
+
+impl CompressedImageFormat {
+    // every format encodes blocks of 4x4 pixels
+    pub fn block_size_in_bytes(&self) -> usize {
+        match self {
+            CompressedImageFormat::Bc1RgbUnorm
+            | CompressedImageFormat::Bc1RgbaUnorm
+            | CompressedImageFormat::Bc4Unorm
+            | CompressedImageFormat::Etc2Rgb8Unorm => 8,
+            CompressedImageFormat::Bc2Unorm
+            | CompressedImageFormat::Bc3Unorm
+            | CompressedImageFormat::Bc5Unorm
+            | CompressedImageFormat::Bc6hUfloat
+            | CompressedImageFormat::Bc7Unorm
+            | CompressedImageFormat::Etc2Rgba8Unorm => 16,
+        }
+    }
+
+    pub fn level_size_in_bytes(&self, width: usize, height: usize) -> usize {
+        width.div_ceil(4).max(1) * height.div_ceil(4).max(1) * self.block_size_in_bytes()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CompressedImageError {
+    UnknownContainer,
+    UnexpectedEndOfData,
+    UnsupportedFormat(String),
+    UnsupportedLayout(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CompressedImageLevel {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompressedImage {
+    format: CompressedImageFormat,
+    // the first level is the base level, the rest are its mipmaps
+    levels: Vec<CompressedImageLevel>,
+}
+
+const DDS_MAGIC: &[u8; 4] = b"DDS ";
+const DDS_HEADER_SIZE: usize = 4 + 124;
+const DDS_DX10_HEADER_SIZE: usize = 20;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDPF_FOURCC: u32 = 0x4;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+
+const KTX2_IDENTIFIER: &[u8; 12] = &[
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const KTX2_LEVEL_INDEX_OFFSET: usize = 80;
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, CompressedImageError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or(CompressedImageError::UnexpectedEndOfData)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, CompressedImageError> {
+    let low = read_u32(bytes, offset)? as u64;
+    let high = read_u32(bytes, offset + 4)? as u64;
+    Ok(low | (high << 32))
+}
+
+fn read_level(
+    bytes: &[u8],
+    offset: usize,
+    format: CompressedImageFormat,
+    width: usize,
+    height: usize,
+) -> Result<CompressedImageLevel, CompressedImageError> {
+    let size = format.level_size_in_bytes(width, height);
+    let data = bytes
+        .get(offset..offset + size)
+        .ok_or(CompressedImageError::UnexpectedEndOfData)?
+        .to_vec();
+
+    Ok(CompressedImageLevel {
+        width,
+        height,
+        data,
+    })
+}
+
+fn dimensions_of_level(width: usize, height: usize, level: usize) -> (usize, usize) {
+    ((width >> level).max(1), (height >> level).max(1))
+}
+
+impl CompressedImage {
+    pub fn is_compressed_container(bytes: &[u8]) -> bool {
+        bytes.starts_with(DDS_MAGIC) || bytes.starts_with(KTX2_IDENTIFIER)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CompressedImageError> {
+        if bytes.starts_with(DDS_MAGIC) {
+            Self::from_dds_bytes(bytes)
+        } else if bytes.starts_with(KTX2_IDENTIFIER) {
+            Self::from_ktx2_bytes(bytes)
+        } else {
+            Err(CompressedImageError::UnknownContainer)
+        }
+    }
+
+    pub fn from_dds_bytes(bytes: &[u8]) -> Result<Self, CompressedImageError> {
+        if !bytes.starts_with(DDS_MAGIC) {
+            return Err(CompressedImageError::UnknownContainer);
+        }
+
+        let flags = read_u32(bytes, 8)?;
+        let height = read_u32(bytes, 12)? as usize;
+        let width = read_u32(bytes, 16)? as usize;
+        let number_of_levels = if flags & DDSD_MIPMAPCOUNT != 0 {
+            (read_u32(bytes, 28)? as usize).max(1)
+        } else {
+            1
+        };
+        let pixel_format_flags = read_u32(bytes, 80)?;
+        let four_cc = bytes
+            .get(84..88)
+            .ok_or(CompressedImageError::UnexpectedEndOfData)?;
+        let caps2 = read_u32(bytes, 112)?;
+
+        if caps2 & DDSCAPS2_CUBEMAP != 0 {
+            return Err(CompressedImageError::UnsupportedLayout(
+                "cubemaps are not supported".to_string(),
+            ));
+        }
+        if pixel_format_flags & DDPF_FOURCC == 0 {
+            return Err(CompressedImageError::UnsupportedFormat(
+                "uncompressed dds images are not supported".to_string(),
+            ));
+        }
+
+        let (format, mut offset) = match four_cc {
+            b"DXT1" => (CompressedImageFormat::Bc1RgbaUnorm, DDS_HEADER_SIZE),
+            b"DXT3" => (CompressedImageFormat::Bc2Unorm, DDS_HEADER_SIZE),
+            b"DXT5" => (CompressedImageFormat::Bc3Unorm, DDS_HEADER_SIZE),
+            b"ATI1" | b"BC4U" => (CompressedImageFormat::Bc4Unorm, DDS_HEADER_SIZE),
+            b"ATI2" | b"BC5U" => (CompressedImageFormat::Bc5Unorm, DDS_HEADER_SIZE),
+            b"DX10" => {
+                let dxgi_format = read_u32(bytes, DDS_HEADER_SIZE)?;
+                let format = match dxgi_format {
+                    71 => CompressedImageFormat::Bc1RgbaUnorm,
+                    74 => CompressedImageFormat::Bc2Unorm,
+                    77 => CompressedImageFormat::Bc3Unorm,
+                    80 => CompressedImageFormat::Bc4Unorm,
+                    83 => CompressedImageFormat::Bc5Unorm,
+                    95 => CompressedImageFormat::Bc6hUfloat,
+                    98 => CompressedImageFormat::Bc7Unorm,
+                    _ => {
+                        return Err(CompressedImageError::UnsupportedFormat(format!(
+                            "dxgi_format = {dxgi_format}"
+                        )))
+                    }
+                };
+
+                let array_size = read_u32(bytes, DDS_HEADER_SIZE + 12)?;
+                if array_size > 1 {
+                    return Err(CompressedImageError::UnsupportedLayout(format!(
+                        "texture arrays are not supported, array_size = {array_size}"
+                    )));
+                }
+
+                (format, DDS_HEADER_SIZE + DDS_DX10_HEADER_SIZE)
+            }
+            _ => {
+                return Err(CompressedImageError::UnsupportedFormat(format!(
+                    "four_cc = {}",
+                    String::from_utf8_lossy(four_cc)
+                )))
+            }
+        };
+
+        let mut levels = Vec::with_capacity(number_of_levels);
+        for level in 0..number_of_levels {
+            let (level_width, level_height) = dimensions_of_level(width, height, level);
+            let level = read_level(bytes, offset, format, level_width, level_height)?;
+            offset += level.data.len();
+            levels.push(level);
+        }
+
+        Ok(Self { format, levels })
+    }
+
+    pub fn from_ktx2_bytes(bytes: &[u8]) -> Result<Self, CompressedImageError> {
+        if !bytes.starts_with(KTX2_IDENTIFIER) {
+            return Err(CompressedImageError::UnknownContainer);
+        }
+
+        let vk_format = read_u32(bytes, 12)?;
+        let width = read_u32(bytes, 20)? as usize;
+        let height = read_u32(bytes, 24)? as usize;
+        let depth = read_u32(bytes, 28)?;
+        let layer_count = read_u32(bytes, 32)?;
+        let face_count = read_u32(bytes, 36)?;
+        // zero levels means that the mipmaps are generated by the loader, only the base is stored
+        let number_of_levels = (read_u32(bytes, 40)? as usize).max(1);
+        let supercompression_scheme = read_u32(bytes, 44)?;
+
+        let format = match vk_format {
+            131 => CompressedImageFormat::Bc1RgbUnorm,
+            133 => CompressedImageFormat::Bc1RgbaUnorm,
+            135 => CompressedImageFormat::Bc2Unorm,
+            137 => CompressedImageFormat::Bc3Unorm,
+            139 => CompressedImageFormat::Bc4Unorm,
+            141 => CompressedImageFormat::Bc5Unorm,
+            143 => CompressedImageFormat::Bc6hUfloat,
+            145 => CompressedImageFormat::Bc7Unorm,
+            147 => CompressedImageFormat::Etc2Rgb8Unorm,
+            151 => CompressedImageFormat::Etc2Rgba8Unorm,
+            _ => {
+                return Err(CompressedImageError::UnsupportedFormat(format!(
+                    "vk_format = {vk_format}"
+                )))
+            }
+        };
+
+        if supercompression_scheme != 0 {
+            return Err(CompressedImageError::UnsupportedFormat(format!(
+                "supercompression_scheme = {supercompression_scheme}"
+            )));
+        }
+        if depth > 1 || layer_count > 1 || face_count != 1 {
+            return Err(CompressedImageError::UnsupportedLayout(format!(
+                "only 2d images are supported, depth = {depth}, layer_count = {layer_count}, face_count = {face_count}"
+            )));
+        }
+
+        let mut levels = Vec::with_capacity(number_of_levels);
+        for level in 0..number_of_levels {
+            let level_index_offset = KTX2_LEVEL_INDEX_OFFSET + level * 24;
+            let byte_offset = read_u64(bytes, level_index_offset)? as usize;
+            let byte_length = read_u64(bytes, level_index_offset + 8)? as usize;
+
+            let (level_width, level_height) = dimensions_of_level(width, height, level);
+            if byte_length != format.level_size_in_bytes(level_width, level_height) {
+                return Err(CompressedImageError::UnsupportedLayout(format!(
+                    "unexpected size of level, level = {level}, byte_length = {byte_length}"
+                )));
+            }
+
+            levels.push(read_level(
+                bytes,
+                byte_offset,
+                format,
+                level_width,
+                level_height,
+            )?);
+        }
+
+        Ok(Self { format, levels })
+    }
+
+    pub fn format(&self) -> CompressedImageFormat {
+        self.format
+    }
+
+    pub fn levels(&self) -> &[CompressedImageLevel] {
+        &self.levels
+    }
+
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.levels.iter().map(|level| level.data.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dds_bytes(four_cc: &[u8; 4], width: u32, height: u32, number_of_levels: u32) -> Vec<u8> {
+        let mut bytes = vec![0; DDS_HEADER_SIZE];
+        bytes[0..4].copy_from_slice(DDS_MAGIC);
+        bytes[4..8].copy_from_slice(&124u32.to_le_bytes());
+        bytes[8..12].copy_from_slice(&DDSD_MIPMAPCOUNT.to_le_bytes());
+        bytes[12..16].copy_from_slice(&height.to_le_bytes());
+        bytes[16..20].copy_from_slice(&width.to_le_bytes());
+        bytes[28..32].copy_from_slice(&number_of_levels.to_le_bytes());
+        bytes[80..84].copy_from_slice(&DDPF_FOURCC.to_le_bytes());
+        bytes[84..88].copy_from_slice(four_cc);
+        bytes
+    }
+
+    #[test]
+    fn load_dds_with_mipmaps() {
+        // 8x8 with 4 blocks, 4x4 with 1 block, 2x2 with 1 block
+        let mut bytes = dds_bytes(b"DXT5", 8, 8, 3);
+        bytes.extend(std::iter::repeat(7).take((4 + 1 + 1) * 16));
+
+        let image = CompressedImage::from_bytes(&bytes).unwrap();
+        assert_eq!(CompressedImageFormat::Bc3Unorm, image.format());
+        assert_eq!(8, image.width());
+        assert_eq!(8, image.height());
+        assert_eq!(
+            vec![(8, 8, 64), (4, 4, 16), (2, 2, 16)],
+            image
+                .levels()
+                .iter()
+                .map(|level| (level.width, level.height, level.data.len()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(96, image.size_in_bytes());
+
+        bytes.truncate(bytes.len() - 1);
+        assert_eq!(
+            Some(CompressedImageError::UnexpectedEndOfData),
+            CompressedImage::from_bytes(&bytes).err()
+        );
+
+        let bytes = dds_bytes(b"RGBA", 8, 8, 1);
+        assert!(matches!(
+            CompressedImage::from_bytes(&bytes),
+            Err(CompressedImageError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn load_ktx2_base_level() {
+        let mut bytes = vec![0; KTX2_LEVEL_INDEX_OFFSET + 24];
+        bytes[0..12].copy_from_slice(KTX2_IDENTIFIER);
+        bytes[12..16].copy_from_slice(&147u32.to_le_bytes());
+        bytes[20..24].copy_from_slice(&4u32.to_le_bytes());
+        bytes[24..28].copy_from_slice(&4u32.to_le_bytes());
+        bytes[36..40].copy_from_slice(&1u32.to_le_bytes());
+        bytes[40..44].copy_from_slice(&1u32.to_le_bytes());
+        let data_offset = bytes.len() as u64;
+        bytes[80..88].copy_from_slice(&data_offset.to_le_bytes());
+        bytes[88..96].copy_from_slice(&8u64.to_le_bytes());
+        bytes.extend([1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let image = CompressedImage::from_bytes(&bytes).unwrap();
+        assert_eq!(CompressedImageFormat::Etc2Rgb8Unorm, image.format());
+        assert_eq!(1, image.levels().len());
+        assert_eq!(vec![1, 2, 3, 4, 5, 6, 7, 8], image.levels()[0].data);
+
+        assert_eq!(
+            Some(CompressedImageError::UnknownContainer),
+            CompressedImage::from_bytes(b"not an image").err()
+        );
+    }
+}
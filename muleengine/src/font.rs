@@ -7,9 +7,27 @@ use std::{
 };
 
 use ab_glyph::{Font as AbGlyphFont, FontVec, InvalidFont, ScaleFont};
-use vek::Vec2;
+use vek::{Rect, Vec2};
 
-use crate::image::Image;
+use crate::{
+    image::{ColorType, Image},
+    rect_packer::RectPacker,
+};
+
+// the bundled font is part of the binary, so it cannot fail to load
+const HACK_FONT_BYTES: &[u8] = include_bytes!("hack-font/Hack-Bold.ttf");
+
+const GLYPH_ATLAS_WIDTH: usize = 1024;
+const GLYPH_ATLAS_INITIAL_HEIGHT: usize = 256;
+// the glyphs are padded, so the linear filtering does not sample their neighbours
+const GLYPH_ATLAS_PADDING: usize = 1;
+
+// the glyphs are cached by the containers, so rendering them needs mutable access
+pub trait Font: Send + Sync {
+    fn get_rendered_glyph(&mut self, chr: char, pixel_scale: usize) -> Option<RenderedGlyph>;
+    fn get_atlas_glyph(&mut self, chr: char, pixel_scale: usize) -> Option<AtlasGlyph>;
+    fn glyph_atlas(&self) -> &GlyphAtlas;
+}
 
 pub struct GlyphRenderer {
     font: FontVec,
@@ -20,7 +38,7 @@ pub struct RenderedGlyph {
     image: Arc<Image>,
     pixel_scale: usize,
     bounds_min: Vec2<f32>,
-    _bounds_max: Vec2<f32>,
+    bounds_max: Vec2<f32>,
     h_advance: f32,
     v_advance: f32,
 }
@@ -45,11 +63,143 @@ impl RenderedGlyph {
     pub fn compute_render_offset_px(&self) -> Vec2<f32> {
         Vec2::new(self.bounds_min.x, self.bounds_min.y)
     }
+
+    // the outline is drawn from the top left corner of the image
+    pub fn px_size(&self) -> Vec2<usize> {
+        let size = (self.bounds_max - self.bounds_min).map(|value| value.ceil().max(0.0) as usize);
+        Vec2::new(
+            size.x.min(self.image.width()),
+            size.y.min(self.image.height()),
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct AtlasGlyph {
+    glyph: RenderedGlyph,
+    atlas_rect: Rect<usize, usize>,
+}
+
+impl AtlasGlyph {
+    pub fn glyph(&self) -> &RenderedGlyph {
+        &self.glyph
+    }
+
+    // in pixels, use GlyphAtlas::uv_rect to sample it
+    pub fn atlas_rect(&self) -> Rect<usize, usize> {
+        self.atlas_rect
+    }
+}
+
+// every glyph is packed into the atlas once, the atlas only grows, so the rects of the glyphs stay valid
+pub struct GlyphAtlas {
+    image: Arc<Image>,
+    rect_packer: RectPacker,
+    glyph_rects: HashMap<(char, usize), Rect<usize, usize>>,
 }
 
-pub struct FontContainer {
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlyphAtlas {
+    pub fn new() -> Self {
+        let dimensions = Vec2::new(GLYPH_ATLAS_WIDTH, GLYPH_ATLAS_INITIAL_HEIGHT);
+
+        Self {
+            image: Arc::new(Image::new(dimensions.x, dimensions.y, ColorType::La8)),
+            rect_packer: RectPacker::new(dimensions),
+            glyph_rects: HashMap::new(),
+        }
+    }
+
+    pub fn image(&self) -> &Arc<Image> {
+        &self.image
+    }
+
+    // the uvs change when the atlas grows, so they are computed with its current dimensions
+    pub fn uv_rect(&self, atlas_rect: Rect<usize, usize>) -> Rect<f32, f32> {
+        let dimensions = self.rect_packer.dimensions().as_::<f32>();
+
+        Rect::new(
+            atlas_rect.x as f32 / dimensions.x,
+            atlas_rect.y as f32 / dimensions.y,
+            atlas_rect.w as f32 / dimensions.x,
+            atlas_rect.h as f32 / dimensions.y,
+        )
+    }
+
+    fn get_or_insert_glyph(
+        &mut self,
+        chr: char,
+        glyph: &RenderedGlyph,
+    ) -> Option<Rect<usize, usize>> {
+        let key = (chr, glyph.pixel_scale());
+        if let Some(atlas_rect) = self.glyph_rects.get(&key) {
+            return Some(*atlas_rect);
+        }
+
+        let size = glyph.px_size();
+        let padded_size = size + Vec2::broadcast(GLYPH_ATLAS_PADDING);
+        if padded_size.x > self.rect_packer.dimensions().x {
+            return None;
+        }
+
+        let padded_rect = loop {
+            match self.rect_packer.allocate(padded_size) {
+                Some(padded_rect) => break padded_rect,
+                None => self.grow(),
+            }
+        };
+
+        let atlas_rect = Rect::new(padded_rect.x, padded_rect.y, size.x, size.y);
+        self.image_mut().copy_image_to_self(
+            Vec2::new(atlas_rect.x as isize, atlas_rect.y as isize),
+            glyph.image(),
+            Vec2::zero()..Vec2::new(size.x as isize, size.y as isize),
+        );
+        self.glyph_rects.insert(key, atlas_rect);
+
+        Some(atlas_rect)
+    }
+
+    fn grow(&mut self) {
+        let dimensions = self.rect_packer.dimensions();
+        self.rect_packer.grow_height(dimensions.y * 2);
+        self.image = Arc::new(self.copy_of_image());
+    }
+
+    // the images that were handed out keep their content, the atlas is copied on write
+    fn image_mut(&mut self) -> &mut Image {
+        if Arc::get_mut(&mut self.image).is_none() {
+            self.image = Arc::new(self.copy_of_image());
+        }
+
+        match Arc::get_mut(&mut self.image) {
+            Some(image) => image,
+            None => unreachable!(),
+        }
+    }
+
+    fn copy_of_image(&self) -> Image {
+        let dimensions = self.rect_packer.dimensions();
+        let mut image = Image::new(dimensions.x, dimensions.y, ColorType::La8);
+        image.copy_image_to_self(
+            Vec2::zero(),
+            &self.image,
+            Vec2::zero()..Vec2::new(self.image.width() as isize, self.image.height() as isize),
+        );
+
+        image
+    }
+}
+
+pub struct CachedFont {
     glyph_renderer: GlyphRenderer,
     glyph_images: HashMap<(char, usize), RenderedGlyph>,
+    glyph_atlas: GlyphAtlas,
 }
 
 #[derive(Debug)]
@@ -105,7 +255,7 @@ impl GlyphRenderer {
                 pixel_scale,
                 image: Arc::new(image),
                 bounds_min: Vec2::new(px_bounds.min.x, px_bounds.min.y),
-                _bounds_max: Vec2::new(px_bounds.max.x, px_bounds.max.y),
+                bounds_max: Vec2::new(px_bounds.max.x, px_bounds.max.y),
                 h_advance,
                 v_advance,
             })
@@ -115,11 +265,19 @@ impl GlyphRenderer {
     }
 }
 
-impl FontContainer {
+impl CachedFont {
+    pub fn hack() -> Self {
+        match Self::from_bytes(HACK_FONT_BYTES) {
+            Ok(cached_font) => cached_font,
+            Err(_) => unreachable!(),
+        }
+    }
+
     pub fn from_vec(bytes: Vec<u8>) -> Result<Self, FontLoadError> {
         Ok(Self {
             glyph_renderer: GlyphRenderer::from_vec(bytes)?,
             glyph_images: HashMap::new(),
+            glyph_atlas: GlyphAtlas::new(),
         })
     }
 
@@ -127,6 +285,7 @@ impl FontContainer {
         Ok(Self {
             glyph_renderer: GlyphRenderer::from_bytes(bytes)?,
             glyph_images: HashMap::new(),
+            glyph_atlas: GlyphAtlas::new(),
         })
     }
 
@@ -134,6 +293,7 @@ impl FontContainer {
         Ok(Self {
             glyph_renderer: GlyphRenderer::from_file(path)?,
             glyph_images: HashMap::new(),
+            glyph_atlas: GlyphAtlas::new(),
         })
     }
 
@@ -146,25 +306,29 @@ impl FontContainer {
                 .map(|rendered_glyph| entry.insert(rendered_glyph).clone()),
         }
     }
-}
 
-pub struct HackFontContainer(FontContainer);
+    pub fn get_atlas_glyph(&mut self, chr: char, pixel_scale: usize) -> Option<AtlasGlyph> {
+        let glyph = self.get_rendered_glyph(chr, pixel_scale)?;
+        let atlas_rect = self.glyph_atlas.get_or_insert_glyph(chr, &glyph)?;
 
-impl Default for HackFontContainer {
-    fn default() -> Self {
-        Self::new()
+        Some(AtlasGlyph { glyph, atlas_rect })
+    }
+
+    pub fn glyph_atlas(&self) -> &GlyphAtlas {
+        &self.glyph_atlas
     }
 }
 
-impl HackFontContainer {
-    pub fn new() -> Self {
-        match FontContainer::from_bytes(include_bytes!("hack-font/Hack-Bold.ttf")) {
-            Ok(font_container) => Self(font_container),
-            Err(_) => unreachable!(),
-        }
+impl Font for CachedFont {
+    fn get_rendered_glyph(&mut self, chr: char, pixel_scale: usize) -> Option<RenderedGlyph> {
+        CachedFont::get_rendered_glyph(self, chr, pixel_scale)
     }
 
-    pub fn get_rendered_glyph(&mut self, chr: char, pixel_scale: usize) -> Option<RenderedGlyph> {
-        self.0.get_rendered_glyph(chr, pixel_scale)
+    fn get_atlas_glyph(&mut self, chr: char, pixel_scale: usize) -> Option<AtlasGlyph> {
+        CachedFont::get_atlas_glyph(self, chr, pixel_scale)
+    }
+
+    fn glyph_atlas(&self) -> &GlyphAtlas {
+        CachedFont::glyph_atlas(self)
     }
 }
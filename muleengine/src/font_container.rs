@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use bytifex_utils::sync::types::{arc_rw_lock_new, ArcRwLock};
+
+use super::asset_reader::AssetReader;
+use super::font::CachedFont;
+
+// every font keeps its own glyph cache and atlas, so the fonts are shared by the path they were loaded from
+pub struct FontContainer {
+    fonts: HashMap<String, ArcRwLock<CachedFont>>,
+    hack_font: Option<ArcRwLock<CachedFont>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum FontContainerError {
+    CannotOpenAsset { path: String },
+    CannotReadAsset { path: String },
+    CannotDecodeAssetAsFont { path: String },
+}
+
+impl Default for FontContainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontContainer {
+    pub fn new() -> Self {
+        Self {
+            fonts: HashMap::new(),
+            hack_font: None,
+        }
+    }
+
+    // the font that is bundled with the engine, it is available without any assets
+    pub fn hack_font(&mut self) -> ArcRwLock<CachedFont> {
+        self.hack_font
+            .get_or_insert_with(|| arc_rw_lock_new(CachedFont::hack()))
+            .clone()
+    }
+
+    pub fn get_font(
+        &mut self,
+        font_path: impl AsRef<str>,
+        asset_reader: &AssetReader,
+    ) -> Result<ArcRwLock<CachedFont>, FontContainerError> {
+        if let Some(font) = self.fonts.get(font_path.as_ref()) {
+            return Ok(font.clone());
+        }
+
+        let mut reader = asset_reader.get_reader(font_path.as_ref()).ok_or_else(|| {
+            FontContainerError::CannotOpenAsset {
+                path: font_path.as_ref().to_string(),
+            }
+        })?;
+
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|_| FontContainerError::CannotReadAsset {
+                path: font_path.as_ref().to_string(),
+            })?;
+
+        let font = CachedFont::from_vec(bytes).map_err(|_| {
+            FontContainerError::CannotDecodeAssetAsFont {
+                path: font_path.as_ref().to_string(),
+            }
+        })?;
+
+        let font = arc_rw_lock_new(font);
+        self.fonts
+            .insert(font_path.as_ref().to_string(), font.clone());
+
+        Ok(font)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::asset_reader::AssetReader;
+
+    use super::{FontContainer, FontContainerError};
+
+    #[test]
+    fn fonts_are_loaded_once_per_path() {
+        let asset_reader = AssetReader::new();
+        let mut font_container = FontContainer::new();
+
+        let font = font_container
+            .get_font("src/hack-font/Hack-Regular.ttf", &asset_reader)
+            .unwrap();
+        let same_font = font_container
+            .get_font("src/hack-font/Hack-Regular.ttf", &asset_reader)
+            .unwrap();
+        let other_font = font_container
+            .get_font("src/hack-font/Hack-Italic.ttf", &asset_reader)
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&font, &same_font));
+        assert!(!Arc::ptr_eq(&font, &other_font));
+        assert!(font.write().get_rendered_glyph('a', 16).is_some());
+
+        assert!(matches!(
+            font_container.get_font("src/hack-font/missing.ttf", &asset_reader),
+            Err(FontContainerError::CannotOpenAsset { .. })
+        ));
+        assert!(matches!(
+            font_container.get_font("src/font.rs", &asset_reader),
+            Err(FontContainerError::CannotDecodeAssetAsFont { .. })
+        ));
+    }
+}
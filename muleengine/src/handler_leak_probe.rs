@@ -0,0 +1,74 @@
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    sync::{Arc, Weak},
+};
+
+pub trait HandlerLeakProbeSource {
+    fn leak_probe(&self) -> HandlerLeakProbe;
+}
+
+pub struct HandlerLeakProbe {
+    handler_type_name: &'static str,
+    handle: Weak<dyn Any + Send + Sync>,
+    creation_backtrace: Option<Arc<Backtrace>>,
+}
+
+pub fn capture_creation_backtrace() -> Option<Arc<Backtrace>> {
+    if cfg!(debug_assertions) {
+        Some(Arc::new(Backtrace::capture()))
+    } else {
+        None
+    }
+}
+
+impl HandlerLeakProbe {
+    pub fn new<T: Any + Send + Sync>(
+        handler_type_name: &'static str,
+        handle: &Arc<T>,
+        creation_backtrace: Option<Arc<Backtrace>>,
+    ) -> Self {
+        let handle: Weak<dyn Any + Send + Sync> = Arc::downgrade(handle);
+
+        Self {
+            handler_type_name,
+            handle,
+            creation_backtrace,
+        }
+    }
+
+    pub fn handler_type_name(&self) -> &'static str {
+        self.handler_type_name
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.handle.strong_count() > 0
+    }
+
+    pub fn creation_backtrace(&self) -> Option<&Backtrace> {
+        self.creation_backtrace.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::HandlerLeakProbe;
+
+    #[test]
+    fn probe_follows_the_lifetime_of_the_handle() {
+        let handle = Arc::new(0usize);
+        let cloned_handle = handle.clone();
+
+        let probe = HandlerLeakProbe::new("Handle", &handle, None);
+        assert_eq!("Handle", probe.handler_type_name());
+        assert!(probe.is_alive());
+
+        drop(handle);
+        assert!(probe.is_alive());
+
+        drop(cloned_handle);
+        assert!(!probe.is_alive());
+    }
+}
@@ -1,11 +1,14 @@
 use std::{
     convert::TryFrom,
     ops::{Bound, Range, RangeBounds},
+    sync::Arc,
 };
 
 use image::ImageError;
 use vek::Vec2;
 
+use crate::compressed_image::CompressedImage;
+
 #[derive(Debug, Copy, Clone)]
 pub enum ImageFormat {
     Png,
@@ -85,6 +88,7 @@ pub struct Image {
     width: usize,
     height: usize,
     color_type: ColorType,
+    compressed: Option<Arc<CompressedImage>>,
 }
 
 impl Image {
@@ -107,6 +111,19 @@ impl Image {
             width,
             height,
             color_type,
+            compressed: None,
+        }
+    }
+
+    // the image does not have pixels on the cpu side, so the color getters and setters fail on it,
+    // the renderers upload the compressed levels as they are
+    pub fn from_compressed(compressed: CompressedImage) -> Self {
+        Self {
+            image: image::DynamicImage::new_rgba8(0, 0),
+            width: compressed.width(),
+            height: compressed.height(),
+            color_type: ColorType::Rgba8,
+            compressed: Some(Arc::new(compressed)),
         }
     }
 
@@ -132,6 +149,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::L8,
+            compressed: None,
         }
     }
 
@@ -157,6 +175,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::La8,
+            compressed: None,
         }
     }
 
@@ -182,6 +201,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::L16,
+            compressed: None,
         }
     }
 
@@ -207,6 +227,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::La16,
+            compressed: None,
         }
     }
 
@@ -232,6 +253,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::Rgb8,
+            compressed: None,
         }
     }
 
@@ -257,6 +279,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::Rgba8,
+            compressed: None,
         }
     }
 
@@ -282,6 +305,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::Rgb16,
+            compressed: None,
         }
     }
 
@@ -307,6 +331,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::Rgba16,
+            compressed: None,
         }
     }
 
@@ -332,6 +357,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::RgbF32,
+            compressed: None,
         }
     }
 
@@ -357,6 +383,7 @@ impl Image {
             width,
             height,
             color_type: ColorType::RgbaF32,
+            compressed: None,
         }
     }
 
@@ -364,6 +391,13 @@ impl Image {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).ok()?;
 
+        if CompressedImage::is_compressed_container(&bytes) {
+            return CompressedImage::from_bytes(&bytes)
+                .inspect_err(|e| log::error!("Decoding compressed image, msg = {e:?}"))
+                .ok()
+                .map(Self::from_compressed);
+        }
+
         let mut image = image::io::Reader::new(std::io::Cursor::new(bytes))
             .with_guessed_format()
             .ok()?
@@ -384,6 +418,7 @@ impl Image {
             width: width as usize,
             height: height as usize,
             color_type,
+            compressed: None,
         })
     }
 
@@ -423,6 +458,18 @@ impl Image {
         self.image.as_bytes()
     }
 
+    pub fn compressed(&self) -> Option<&Arc<CompressedImage>> {
+        self.compressed.as_ref()
+    }
+
+    // the size of the pixels or the size of the compressed levels
+    pub fn size_in_bytes(&self) -> usize {
+        match &self.compressed {
+            Some(compressed) => compressed.size_in_bytes(),
+            None => self.as_bytes().len(),
+        }
+    }
+
     pub fn set_color_luma_u8_at(
         &mut self,
         x: usize,
@@ -13,14 +13,20 @@ pub mod application_runner;
 pub mod asset_container;
 pub mod asset_reader;
 pub mod camera;
+pub mod compressed_image;
 pub mod font;
+pub mod font_container;
 pub mod fps_counter;
+pub mod handler_leak_probe;
 pub mod heightmap;
 pub mod image;
 pub mod image_container;
 pub mod mesh;
 pub mod mesh_creator;
 pub mod mesh_loader;
+pub mod prefab_container;
+pub mod profiler;
+pub mod rect_packer;
 pub mod renderer;
 pub mod scene_container;
 pub mod service_container;
@@ -29,5 +35,5 @@ pub mod system_container;
 pub mod virtual_clock;
 pub mod window_context;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::path::Path;
 use std::str::Utf8Error;
@@ -25,6 +26,14 @@ pub enum SceneLoadError {
     Unexpected,
 }
 
+#[derive(Debug, Clone)]
+pub enum VertexAttributeError {
+    VertexCountMismatch {
+        number_of_vertices: usize,
+        number_of_values: usize,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub enum MeshConvertError {
     FaceIndexError {
@@ -74,6 +83,11 @@ pub enum MaterialTextureType {
     Normal,
     Displacement,
     Emission,
+    Metallic,
+    Roughness,
+    AmbientOcclusion,
+    // set from a cubemap texture of the renderer, see RendererClient::create_cubemap_texture
+    EnvironmentCube,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -83,6 +97,33 @@ pub enum TextureMapMode {
     Mirror,
 }
 
+// the mipmapped modes sample the mipmaps that are generated for the images,
+// the trilinear one blends between them, so the distant textures do not shimmer
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TextureFilterMode {
+    Nearest,
+    Linear,
+    Bilinear,
+    Trilinear,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TextureAnisotropyMode {
+    Anisotropy1,
+    Anisotropy2,
+    Anisotropy4,
+    Anisotropy8,
+    Anisotropy16,
+}
+
+// transparent materials are drawn after the opaque ones, sorted back to front
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MaterialBlendMode {
+    Opaque,
+    AlphaBlend,
+    Additive,
+}
+
 #[derive(Debug, Clone)]
 pub enum MaterialTextureConversionError {
     Utf8Error(Utf8Error),
@@ -94,6 +135,8 @@ pub struct MaterialTexture {
     pub image: Arc<Image>,
     pub texture_type: MaterialTextureType,
     pub texture_map_mode: TextureMapMode,
+    pub filter_mode: TextureFilterMode,
+    pub anisotropy_mode: TextureAnisotropyMode,
     pub blend: f32,
     pub uv_channel_id: usize,
 }
@@ -105,6 +148,11 @@ pub struct Material {
     pub albedo_color: Vec3<f32>,
     pub shininess_color: Vec3<f32>,
     pub emissive_color: Vec3<f32>,
+    // the metallic-roughness parameters are multiplied with the matching textures
+    pub metallic: f32,
+    pub roughness: f32,
+    pub ambient_occlusion: f32,
+    pub blend_mode: MaterialBlendMode,
 }
 
 pub struct Mesh {
@@ -115,6 +163,8 @@ pub struct Mesh {
     tangents: Vec<Vec3<f32>>,
     bitangents: Vec<Vec3<f32>>,
     uv_channels: Vec<Vec<Vec2<f32>>>,
+    color_channels: Vec<Vec<Vec4<f32>>>,
+    float_attributes: BTreeMap<String, Vec<f32>>,
     bones: Vec<Bone>,
     material: Material,
     aabb: AxisAlignedBoundingBox,
@@ -166,11 +216,23 @@ impl MaterialTexture {
             image,
             texture_type,
             texture_map_mode,
+            filter_mode: TextureFilterMode::Trilinear,
+            anisotropy_mode: TextureAnisotropyMode::Anisotropy8,
             blend,
             uv_channel_id,
         }
     }
 
+    pub fn with_filter_mode(mut self, filter_mode: TextureFilterMode) -> Self {
+        self.filter_mode = filter_mode;
+        self
+    }
+
+    pub fn with_anisotropy_mode(mut self, anisotropy_mode: TextureAnisotropyMode) -> Self {
+        self.anisotropy_mode = anisotropy_mode;
+        self
+    }
+
     // // todo!
     // pub fn from_assimp_material_texture(
     //     asset_reader: &AssetReader,
@@ -246,9 +308,17 @@ impl Material {
             albedo_color: Vec3::broadcast(1.0),
             shininess_color: Vec3::broadcast(0.0),
             emissive_color: Vec3::broadcast(0.0),
+            metallic: 0.0,
+            roughness: 1.0,
+            ambient_occlusion: 1.0,
+            blend_mode: MaterialBlendMode::Opaque,
         }
     }
 
+    pub fn is_transparent(&self) -> bool {
+        self.blend_mode != MaterialBlendMode::Opaque
+    }
+
     pub fn add_texture(&mut self, texture: MaterialTexture) {
         self.textures.push(texture);
     }
@@ -284,6 +354,8 @@ impl Mesh {
             tangents: Vec::new(),
             bitangents: Vec::new(),
             uv_channels: Vec::new(),
+            color_channels: Vec::new(),
+            float_attributes: BTreeMap::new(),
             material,
             aabb: AxisAlignedBoundingBox::new(Vec3::broadcast(0.0)),
         }
@@ -611,6 +683,60 @@ impl Mesh {
         for i in 0..uv_channels.len() {
             self.uv_channels.as_mut_slice()[i].push(uv_channels.as_slice()[i]);
         }
+
+        // keeping the optional attributes in sync with the number of vertices
+        for color_channel in self.color_channels.iter_mut() {
+            color_channel.push(Vec4::broadcast(1.0));
+        }
+        for values in self.float_attributes.values_mut() {
+            values.push(0.0);
+        }
+    }
+
+    pub fn set_color_channel(
+        &mut self,
+        color_channel_id: usize,
+        colors: Vec<Vec4<f32>>,
+    ) -> Result<(), VertexAttributeError> {
+        self.check_number_of_values(colors.len())?;
+
+        let number_of_vertices = self.number_of_vertices();
+        if self.color_channels.len() <= color_channel_id {
+            self.color_channels.resize_with(color_channel_id + 1, || {
+                vec![Vec4::broadcast(1.0); number_of_vertices]
+            });
+        }
+        self.color_channels[color_channel_id] = colors;
+
+        Ok(())
+    }
+
+    pub fn set_float_attribute(
+        &mut self,
+        name: impl Into<String>,
+        values: Vec<f32>,
+    ) -> Result<(), VertexAttributeError> {
+        self.check_number_of_values(values.len())?;
+
+        self.float_attributes.insert(name.into(), values);
+
+        Ok(())
+    }
+
+    pub fn remove_float_attribute(&mut self, name: &str) -> Option<Vec<f32>> {
+        self.float_attributes.remove(name)
+    }
+
+    fn check_number_of_values(&self, number_of_values: usize) -> Result<(), VertexAttributeError> {
+        let number_of_vertices = self.number_of_vertices();
+        if number_of_values != number_of_vertices {
+            return Err(VertexAttributeError::VertexCountMismatch {
+                number_of_vertices,
+                number_of_values,
+            });
+        }
+
+        Ok(())
     }
 
     pub fn add_bone(&mut self, bone: Bone) {
@@ -780,6 +906,18 @@ impl Mesh {
         &self.uv_channels
     }
 
+    pub fn get_color_channels(&self) -> &Vec<Vec<Vec4<f32>>> {
+        &self.color_channels
+    }
+
+    pub fn get_float_attribute(&self, name: &str) -> Option<&Vec<f32>> {
+        self.float_attributes.get(name)
+    }
+
+    pub fn get_float_attributes(&self) -> &BTreeMap<String, Vec<f32>> {
+        &self.float_attributes
+    }
+
     pub fn get_bones(&self) -> &Vec<Bone> {
         &self.bones
     }
@@ -918,3 +1056,68 @@ impl Scene {
 //         m.d4,
 //     )
 // }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use vek::Vec4;
+
+    use crate::{
+        image::{ColorType, Image},
+        mesh_creator::rectangle3d,
+    };
+
+    use super::{
+        MaterialTexture, MaterialTextureType, TextureAnisotropyMode, TextureFilterMode,
+        TextureMapMode, VertexAttributeError,
+    };
+
+    #[test]
+    fn material_textures_are_trilinear_by_default() {
+        let texture = MaterialTexture::new(
+            Arc::new(Image::new(1, 1, ColorType::Rgba8)),
+            MaterialTextureType::Albedo,
+            TextureMapMode::Repeat,
+            1.0,
+            0,
+        );
+        assert_eq!(TextureFilterMode::Trilinear, texture.filter_mode);
+        assert_eq!(TextureAnisotropyMode::Anisotropy8, texture.anisotropy_mode);
+
+        let texture = texture
+            .with_filter_mode(TextureFilterMode::Nearest)
+            .with_anisotropy_mode(TextureAnisotropyMode::Anisotropy1);
+        assert_eq!(TextureFilterMode::Nearest, texture.filter_mode);
+        assert_eq!(TextureAnisotropyMode::Anisotropy1, texture.anisotropy_mode);
+    }
+
+    #[test]
+    fn vertex_colors_and_float_attributes_follow_the_number_of_vertices() {
+        let mut mesh = rectangle3d::create(1.0, 1.0, 1.0);
+        let number_of_vertices = mesh.number_of_vertices();
+
+        let colors = vec![Vec4::new(1.0, 0.0, 0.0, 1.0); number_of_vertices];
+        assert!(mesh.set_color_channel(1, colors.clone()).is_ok());
+        assert_eq!(mesh.get_color_channels().len(), 2);
+        assert_eq!(
+            mesh.get_color_channels()[0],
+            vec![Vec4::broadcast(1.0); number_of_vertices]
+        );
+        assert_eq!(mesh.get_color_channels()[1], colors);
+
+        assert!(mesh
+            .set_float_attribute("windWeight", vec![0.5; number_of_vertices])
+            .is_ok());
+        assert!(matches!(
+            mesh.set_float_attribute("windWeight", vec![0.5; number_of_vertices + 1]),
+            Err(VertexAttributeError::VertexCountMismatch { .. })
+        ));
+        assert_eq!(
+            mesh.get_float_attribute("windWeight")
+                .map(|values| values.len()),
+            Some(number_of_vertices)
+        );
+        assert!(mesh.get_float_attribute("missing").is_none());
+    }
+}
@@ -12,8 +12,8 @@ use crate::{
     asset_reader::AssetReader,
     image_container::{ImageContainer, ImageContainerError},
     mesh::{
-        Bone, Material as MeMaterial, MaterialTexture, MaterialTextureType, Mesh, Scene,
-        TextureMapMode, VertexBoneWeight,
+        Bone, Material as MeMaterial, MaterialBlendMode, MaterialTexture, MaterialTextureType,
+        Mesh, Scene, TextureMapMode, VertexBoneWeight,
     },
 };
 
@@ -67,6 +67,13 @@ fn convert_tobj_material_to_me_material(
         me_material.shininess_color = Vec3::new(specular[0], specular[1], specular[2]);
     }
 
+    if let Some(dissolve) = tobj_material.dissolve {
+        if dissolve < 1.0 {
+            me_material.opacity = dissolve;
+            me_material.blend_mode = MaterialBlendMode::AlphaBlend;
+        }
+    }
+
     if let Some(texture_path) = &tobj_material.diffuse_texture {
         me_material.add_texture(MaterialTexture::new(
             image_container.get_image(texture_path, asset_reader)?,
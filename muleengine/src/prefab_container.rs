@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use entity_component::{ComponentRegistry, Prefab, PrefabError};
+
+use super::asset_reader::AssetReader;
+
+// the prefabs are parsed once per path, the spawned entities get their own copies of the components
+pub struct PrefabContainer {
+    prefabs: HashMap<String, Arc<Prefab>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum PrefabContainerError {
+    CannotOpenAsset { path: String },
+    CannotParseAsset { path: String, error: PrefabError },
+}
+
+impl Default for PrefabContainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrefabContainer {
+    pub fn new() -> Self {
+        Self {
+            prefabs: HashMap::new(),
+        }
+    }
+
+    pub fn get_prefab(
+        &mut self,
+        prefab_path: impl AsRef<str>,
+        asset_reader: &AssetReader,
+        component_registry: &ComponentRegistry,
+    ) -> Result<Arc<Prefab>, PrefabContainerError> {
+        if let Some(prefab) = self.prefabs.get(prefab_path.as_ref()) {
+            return Ok(prefab.clone());
+        }
+
+        let reader = asset_reader
+            .get_reader(prefab_path.as_ref())
+            .ok_or_else(|| PrefabContainerError::CannotOpenAsset {
+                path: prefab_path.as_ref().to_string(),
+            })?;
+
+        let prefab = Prefab::parse(reader, component_registry).map_err(|error| {
+            PrefabContainerError::CannotParseAsset {
+                path: prefab_path.as_ref().to_string(),
+                error,
+            }
+        })?;
+
+        let prefab = Arc::new(prefab);
+        self.prefabs
+            .insert(prefab_path.as_ref().to_string(), prefab.clone());
+
+        Ok(prefab)
+    }
+}
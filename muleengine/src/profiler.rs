@@ -0,0 +1,349 @@
+use std::{
+    cell::RefCell,
+    fmt::Write as _,
+    io::{self, Write},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::stopwatch::Stopwatch;
+
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _profile_scope = $crate::profiler::ProfileScope::new($name);
+    };
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfileScopeRecord {
+    pub name: &'static str,
+    pub thread_index: usize,
+    pub start_offset: Duration,
+    pub duration: Duration,
+    pub children: Vec<ProfileScopeRecord>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FrameProfile {
+    pub frame_index: u64,
+    pub start_offset: Duration,
+    pub duration: Duration,
+    pub scopes: Vec<ProfileScopeRecord>,
+}
+
+pub struct ProfileScope {
+    active: bool,
+}
+
+struct OpenProfileScope {
+    record: ProfileScopeRecord,
+    stopwatch: Stopwatch,
+}
+
+struct ProfilerState {
+    session_stopwatch: Option<Stopwatch>,
+    frame_index: u64,
+    frame_start_offset: Duration,
+    finished_scopes: Vec<ProfileScopeRecord>,
+    last_frame_profile: Option<FrameProfile>,
+}
+
+static PROFILER_ENABLED: AtomicBool = AtomicBool::new(false);
+static NEXT_THREAD_INDEX: AtomicUsize = AtomicUsize::new(0);
+static PROFILER_STATE: Mutex<ProfilerState> = const_mutex(ProfilerState {
+    session_stopwatch: None,
+    frame_index: 0,
+    frame_start_offset: Duration::ZERO,
+    finished_scopes: Vec::new(),
+    last_frame_profile: None,
+});
+
+thread_local! {
+    static THREAD_INDEX: usize = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+    static OPEN_SCOPES: RefCell<Vec<OpenProfileScope>> = RefCell::new(Vec::new());
+}
+
+pub fn set_enabled(enabled: bool) {
+    let mut state = PROFILER_STATE.lock();
+    if enabled && state.session_stopwatch.is_none() {
+        state.session_stopwatch = Some(Stopwatch::start_new());
+    }
+
+    PROFILER_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    PROFILER_ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn end_frame() -> Option<FrameProfile> {
+    if !is_enabled() {
+        return None;
+    }
+
+    let mut state = PROFILER_STATE.lock();
+    let now = session_offset(&state);
+
+    let mut scopes = std::mem::take(&mut state.finished_scopes);
+    scopes.sort_by_key(|scope| (scope.thread_index, scope.start_offset));
+
+    let frame_profile = FrameProfile {
+        frame_index: state.frame_index,
+        start_offset: state.frame_start_offset,
+        duration: now.saturating_sub(state.frame_start_offset),
+        scopes,
+    };
+
+    state.frame_index += 1;
+    state.frame_start_offset = now;
+    state.last_frame_profile = Some(frame_profile.clone());
+
+    Some(frame_profile)
+}
+
+pub fn last_frame_profile() -> Option<FrameProfile> {
+    PROFILER_STATE.lock().last_frame_profile.clone()
+}
+
+fn session_offset(state: &ProfilerState) -> Duration {
+    state
+        .session_stopwatch
+        .as_ref()
+        .map(|stopwatch| stopwatch.elapsed())
+        .unwrap_or_default()
+}
+
+impl ProfileScope {
+    pub fn new(name: &'static str) -> Self {
+        if !is_enabled() {
+            return Self { active: false };
+        }
+
+        let start_offset = session_offset(&PROFILER_STATE.lock());
+
+        OPEN_SCOPES.with(|open_scopes| {
+            open_scopes.borrow_mut().push(OpenProfileScope {
+                record: ProfileScopeRecord {
+                    name,
+                    thread_index: THREAD_INDEX.with(|thread_index| *thread_index),
+                    start_offset,
+                    duration: Duration::ZERO,
+                    children: Vec::new(),
+                },
+                stopwatch: Stopwatch::start_new(),
+            });
+        });
+
+        Self { active: true }
+    }
+}
+
+impl Drop for ProfileScope {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+
+        let finished_record = OPEN_SCOPES.with(|open_scopes| {
+            let mut open_scopes = open_scopes.borrow_mut();
+
+            let open_scope = open_scopes.pop()?;
+            let mut record = open_scope.record;
+            record.duration = open_scope.stopwatch.elapsed();
+
+            if let Some(parent_scope) = open_scopes.last_mut() {
+                parent_scope.record.children.push(record);
+                None
+            } else {
+                Some(record)
+            }
+        });
+
+        if let Some(record) = finished_record {
+            PROFILER_STATE.lock().finished_scopes.push(record);
+        }
+    }
+}
+
+impl ProfileScopeRecord {
+    pub fn self_duration(&self) -> Duration {
+        let children_duration = self
+            .children
+            .iter()
+            .map(|child| child.duration)
+            .sum::<Duration>();
+
+        self.duration.saturating_sub(children_duration)
+    }
+
+    fn append_overlay_lines(&self, depth: usize, lines: &mut Vec<String>) {
+        lines.push(format!(
+            "{:indent$}{} {:.3} ms",
+            "",
+            self.name,
+            self.duration.as_secs_f64() * 1000.0,
+            indent = depth * 2
+        ));
+
+        for child in self.children.iter() {
+            child.append_overlay_lines(depth + 1, lines);
+        }
+    }
+
+    fn append_chrome_tracing_events(&self, events: &mut Vec<String>) {
+        let mut event = String::new();
+        let _ = write!(
+            event,
+            "{{\"name\":\"{}\",\"cat\":\"profile_scope\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":0,\"tid\":{}}}",
+            escape_json_string(self.name),
+            self.start_offset.as_micros(),
+            self.duration.as_micros(),
+            self.thread_index
+        );
+        events.push(event);
+
+        for child in self.children.iter() {
+            child.append_chrome_tracing_events(events);
+        }
+    }
+}
+
+impl FrameProfile {
+    pub fn find_scope(&self, name: &str) -> Option<&ProfileScopeRecord> {
+        fn find_in<'a>(
+            scopes: &'a [ProfileScopeRecord],
+            name: &str,
+        ) -> Option<&'a ProfileScopeRecord> {
+            scopes.iter().find_map(|scope| {
+                if scope.name == name {
+                    Some(scope)
+                } else {
+                    find_in(&scope.children, name)
+                }
+            })
+        }
+
+        find_in(&self.scopes, name)
+    }
+
+    pub fn overlay_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "frame {} {:.3} ms",
+            self.frame_index,
+            self.duration.as_secs_f64() * 1000.0
+        )];
+
+        for scope in self.scopes.iter() {
+            scope.append_overlay_lines(1, &mut lines);
+        }
+
+        lines
+    }
+}
+
+pub struct ChromeTracingExporter {
+    events: Vec<String>,
+}
+
+impl Default for ChromeTracingExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChromeTracingExporter {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn add_frame(&mut self, frame_profile: &FrameProfile) {
+        for scope in frame_profile.scopes.iter() {
+            scope.append_chrome_tracing_events(&mut self.events);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(b"{\"traceEvents\":[")?;
+
+        for (index, event) in self.events.iter().enumerate() {
+            if index != 0 {
+                writer.write_all(b",")?;
+            }
+            writer.write_all(event.as_bytes())?;
+        }
+
+        writer.write_all(b"]}")
+    }
+}
+
+fn escape_json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for chr in value.chars() {
+        match chr {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            chr if chr.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", chr as u32);
+            }
+            chr => escaped.push(chr),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{end_frame, set_enabled, ChromeTracingExporter};
+
+    #[test]
+    fn scopes_are_aggregated_into_a_tree_and_exported() {
+        set_enabled(true);
+        end_frame();
+
+        {
+            profile_scope!("physics.step");
+            {
+                profile_scope!("physics.move_characters");
+            }
+            {
+                profile_scope!("physics.pipeline");
+            }
+        }
+
+        let frame_profile = end_frame().unwrap();
+        set_enabled(false);
+
+        let physics_step = frame_profile.find_scope("physics.step").unwrap();
+        assert_eq!(2, physics_step.children.len());
+        assert_eq!("physics.move_characters", physics_step.children[0].name);
+        assert_eq!("physics.pipeline", physics_step.children[1].name);
+        assert!(physics_step.duration >= physics_step.children[0].duration);
+
+        let overlay_lines = frame_profile.overlay_lines();
+        assert!(overlay_lines
+            .iter()
+            .any(|line| line.starts_with("  physics.step ")));
+        assert!(overlay_lines
+            .iter()
+            .any(|line| line.starts_with("    physics.pipeline ")));
+
+        let mut exporter = ChromeTracingExporter::new();
+        exporter.add_frame(&frame_profile);
+
+        let mut trace = Vec::new();
+        exporter.write_to(&mut trace).unwrap();
+        let trace = String::from_utf8(trace).unwrap();
+
+        assert!(trace.starts_with("{\"traceEvents\":["));
+        assert!(trace.contains("\"name\":\"physics.pipeline\""));
+    }
+}
@@ -0,0 +1,109 @@
+use vek::{Rect, Vec2};
+
+struct Shelf {
+    y: usize,
+    height: usize,
+    used_width: usize,
+}
+
+// a shelf packer, the rects are put next to each other in rows that are as high as their highest rect,
+// it is a good fit for similarly sized rects, like the glyphs of a font
+pub struct RectPacker {
+    dimensions: Vec2<usize>,
+    shelves: Vec<Shelf>,
+}
+
+impl RectPacker {
+    pub fn new(dimensions: Vec2<usize>) -> Self {
+        Self {
+            dimensions,
+            shelves: Vec::new(),
+        }
+    }
+
+    pub fn dimensions(&self) -> Vec2<usize> {
+        self.dimensions
+    }
+
+    // the already allocated rects keep their positions, because the shelves are only added below them
+    pub fn grow_height(&mut self, height: usize) {
+        self.dimensions.y = self.dimensions.y.max(height);
+    }
+
+    pub fn allocate(&mut self, size: Vec2<usize>) -> Option<Rect<usize, usize>> {
+        let width = self.dimensions.x;
+        if size.x > width {
+            return None;
+        }
+
+        // the shortest shelf that fits wastes the least space
+        let shelf = self
+            .shelves
+            .iter_mut()
+            .filter(|shelf| shelf.height >= size.y && width - shelf.used_width >= size.x)
+            .min_by_key(|shelf| shelf.height);
+
+        if let Some(shelf) = shelf {
+            let rect = Rect::new(shelf.used_width, shelf.y, size.x, size.y);
+            shelf.used_width += size.x;
+
+            return Some(rect);
+        }
+
+        let y = self
+            .shelves
+            .last()
+            .map(|shelf| shelf.y + shelf.height)
+            .unwrap_or(0);
+        if y + size.y > self.dimensions.y {
+            return None;
+        }
+
+        self.shelves.push(Shelf {
+            y,
+            height: size.y,
+            used_width: size.x,
+        });
+
+        Some(Rect::new(0, y, size.x, size.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vek::{Rect, Vec2};
+
+    use super::RectPacker;
+
+    #[test]
+    fn rects_are_packed_into_shelves_without_overlapping() {
+        let mut packer = RectPacker::new(Vec2::new(10, 10));
+
+        assert_eq!(
+            Some(Rect::new(0, 0, 4, 5)),
+            packer.allocate(Vec2::new(4, 5))
+        );
+        assert_eq!(
+            Some(Rect::new(4, 0, 4, 3)),
+            packer.allocate(Vec2::new(4, 3))
+        );
+        // the first shelf is full, so a new one is started below it
+        assert_eq!(
+            Some(Rect::new(0, 5, 6, 4)),
+            packer.allocate(Vec2::new(6, 4))
+        );
+        // both shelves have room for it, but the shorter one wastes less space
+        assert_eq!(
+            Some(Rect::new(6, 5, 2, 2)),
+            packer.allocate(Vec2::new(2, 2))
+        );
+        assert_eq!(None, packer.allocate(Vec2::new(11, 1)));
+        assert_eq!(None, packer.allocate(Vec2::new(8, 2)));
+
+        packer.grow_height(20);
+        assert_eq!(
+            Some(Rect::new(0, 9, 8, 2)),
+            packer.allocate(Vec2::new(8, 2))
+        );
+    }
+}
@@ -0,0 +1,2510 @@
+use std::{collections::BTreeSet, ops::Range, sync::Arc, time::Instant};
+
+use bytifex_utils::{
+    containers::object_pool::{ObjectPool, ObjectPoolIndex},
+    sync::types::{arc_rw_lock_new, ArcRwLock},
+};
+use vek::{Mat4, Rect, Transform, Vec2, Vec3, Vec4};
+
+use crate::{
+    image::{ColorType, Image},
+    mesh::{Material, MaterialTextureType, Mesh},
+};
+
+use super::{
+    particle_system::ParticleEmitterSettings,
+    renderer_camera_parameters::RendererCameraParameters,
+    renderer_config::RendererConfig,
+    renderer_debug_lines::RendererDebugLine,
+    renderer_debug_render_mode::RendererDebugRenderMode,
+    renderer_impl::RendererImpl,
+    renderer_layer_projection::{reverse_depth, RendererLayerProjection},
+    renderer_layer_settings::RendererLayerSettings,
+    renderer_mesh_vertices::RendererMeshVertices,
+    renderer_pipeline_step_impl::{set_pipeline_step_clear_color, RendererPipelineStepImpl},
+    renderer_probe_refresh_mode::RendererProbeRefreshMode,
+    renderer_sprite::RendererSprite,
+    renderer_stats::RendererStats,
+    renderer_uniform_value::RendererUniformValue,
+    RendererCamera, RendererGroup, RendererLayer, RendererMaterial, RendererMesh, RendererObject,
+    RendererProbe, RendererShader, RendererTexture, RendererTransform, RendererWindow,
+};
+
+macro_rules! headless_index {
+    ( $index_name:ident, $trait_name:ident, $get_index_fn:ident, $trait_name_literal:literal ) => {
+        #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+        struct $index_name(ObjectPoolIndex);
+
+        impl $trait_name for $index_name {}
+
+        fn $get_index_fn(object: &ArcRwLock<dyn $trait_name>) -> Result<ObjectPoolIndex, String> {
+            object
+                .read()
+                .as_any()
+                .downcast_ref::<$index_name>()
+                .map(|index| index.0)
+                .ok_or_else(|| concat!("invalid ", $trait_name_literal, " provided").to_string())
+        }
+    };
+}
+
+headless_index!(
+    HeadlessCameraIndex,
+    RendererCamera,
+    get_camera_index,
+    "RendererCamera"
+);
+headless_index!(
+    HeadlessGroupIndex,
+    RendererGroup,
+    get_renderer_group_index,
+    "RendererGroup"
+);
+headless_index!(
+    HeadlessLayerIndex,
+    RendererLayer,
+    get_renderer_layer_index,
+    "RendererLayer"
+);
+headless_index!(
+    HeadlessMaterialIndex,
+    RendererMaterial,
+    get_material_index,
+    "RendererMaterial"
+);
+headless_index!(
+    HeadlessMeshIndex,
+    RendererMesh,
+    get_mesh_index,
+    "RendererMesh"
+);
+headless_index!(
+    HeadlessObjectIndex,
+    RendererObject,
+    get_renderer_object_index,
+    "RendererObject"
+);
+headless_index!(
+    HeadlessProbeIndex,
+    RendererProbe,
+    get_probe_index,
+    "RendererProbe"
+);
+headless_index!(
+    HeadlessShaderIndex,
+    RendererShader,
+    get_shader_index,
+    "RendererShader"
+);
+headless_index!(
+    HeadlessTextureIndex,
+    RendererTexture,
+    get_texture_index,
+    "RendererTexture"
+);
+headless_index!(
+    HeadlessTransformIndex,
+    RendererTransform,
+    get_transform_index,
+    "RendererTransform"
+);
+headless_index!(
+    HeadlessWindowIndex,
+    RendererWindow,
+    get_window_index,
+    "RendererWindow"
+);
+
+// a color and a depth buffer in the memory, the origin of the pixels is the top left corner
+#[derive(Default)]
+struct HeadlessFramebuffer {
+    dimensions: Vec2<usize>,
+    colors: Vec<Vec4<f32>>,
+    depths: Vec<f32>,
+    stencils: Vec<u8>,
+}
+
+impl HeadlessFramebuffer {
+    fn new(dimensions: Vec2<usize>) -> Self {
+        let number_of_pixels = dimensions.x * dimensions.y;
+
+        Self {
+            dimensions,
+            colors: vec![Vec4::zero(); number_of_pixels],
+            depths: vec![1.0; number_of_pixels],
+            stencils: vec![0; number_of_pixels],
+        }
+    }
+
+    fn full_viewport(&self) -> Rect<usize, usize> {
+        Rect::new(0, 0, self.dimensions.x.max(1), self.dimensions.y.max(1))
+    }
+
+    // the viewport is clamped to the framebuffer
+    fn clear(
+        &mut self,
+        viewport: Rect<usize, usize>,
+        color: Option<Vec4<f32>>,
+        depth: Option<f32>,
+        stencil: bool,
+    ) {
+        let max_x = (viewport.x + viewport.w).min(self.dimensions.x);
+        let max_y = (viewport.y + viewport.h).min(self.dimensions.y);
+
+        for y in viewport.y..max_y {
+            for x in viewport.x..max_x {
+                let pixel_index = y * self.dimensions.x + x;
+
+                if let Some(color) = color {
+                    self.colors[pixel_index] = color;
+                }
+                if let Some(depth) = depth {
+                    self.depths[pixel_index] = depth;
+                }
+                if stencil {
+                    self.stencils[pixel_index] = 0;
+                }
+            }
+        }
+    }
+
+    fn to_image(&self) -> Image {
+        let mut image = Image::new(self.dimensions.x, self.dimensions.y, ColorType::RgbaF32);
+
+        for y in 0..self.dimensions.y {
+            for x in 0..self.dimensions.x {
+                let color = self.colors[y * self.dimensions.x + x];
+                let _ = image.set_color_rgba_f32_at(x, y, (color.x, color.y, color.z, color.w));
+            }
+        }
+
+        image
+    }
+}
+
+struct HeadlessTransform {
+    local_transform: Transform<f32, f32, f32>,
+    parent: Option<ObjectPoolIndex>,
+}
+
+struct HeadlessCamera {
+    transform: ObjectPoolIndex,
+    parameters: Option<RendererCameraParameters>,
+}
+
+struct HeadlessRendererLayer {
+    camera: ObjectPoolIndex,
+    renderer_groups: BTreeSet<ObjectPoolIndex>,
+    settings: RendererLayerSettings,
+    projection: Option<RendererLayerProjection>,
+}
+
+enum HeadlessTexture {
+    Render(HeadlessFramebuffer),
+    Image(Arc<Image>),
+    Cubemap([Arc<Image>; 6]),
+}
+
+impl HeadlessTexture {
+    fn memory_bytes(&self) -> usize {
+        match self {
+            HeadlessTexture::Render(framebuffer) => {
+                framebuffer.colors.len() * std::mem::size_of::<Vec4<f32>>()
+            }
+            HeadlessTexture::Image(image) => image.size_in_bytes(),
+            HeadlessTexture::Cubemap(faces) => faces.iter().map(|face| face.as_bytes().len()).sum(),
+        }
+    }
+}
+
+enum HeadlessRendererObject {
+    // the single mesh, the instanced and the lod renderer objects,
+    // the lod meshes are sorted by their minimum distance from the eye
+    Mesh {
+        lod_meshes: Vec<(f32, ObjectPoolIndex)>,
+        material: ObjectPoolIndex,
+        transforms: Vec<ObjectPoolIndex>,
+    },
+    SpriteBatch {
+        sprites: Vec<RendererSprite>,
+        transform: ObjectPoolIndex,
+    },
+    // the skyboxes and the particles depend on the shaders of a gpu renderer, they are not drawn
+    Skybox,
+    ParticleEmitter,
+}
+
+struct HeadlessWindow {
+    framebuffer: HeadlessFramebuffer,
+    pipeline_steps: Vec<RendererPipelineStepImpl>,
+}
+
+struct HeadlessTriangle {
+    positions: [Vec3<f32>; 3],
+    color: Vec4<f32>,
+}
+
+#[derive(Default)]
+struct HeadlessDrawStats {
+    draw_calls: usize,
+    triangles: usize,
+}
+
+struct HeadlessRendererState {
+    framebuffer: HeadlessFramebuffer,
+    pipeline_steps: Vec<RendererPipelineStepImpl>,
+
+    cameras: ObjectPool<HeadlessCamera>,
+    renderer_groups: ObjectPool<BTreeSet<ObjectPoolIndex>>,
+    renderer_layers: ObjectPool<HeadlessRendererLayer>,
+    materials: ObjectPool<Material>,
+    meshes: ObjectPool<Arc<Mesh>>,
+    renderer_objects: ObjectPool<HeadlessRendererObject>,
+    probes: ObjectPool<RendererProbeRefreshMode>,
+    shaders: ObjectPool<String>,
+    textures: ObjectPool<HeadlessTexture>,
+    transforms: ObjectPool<HeadlessTransform>,
+    windows: ObjectPool<HeadlessWindow>,
+
+    draw_stats: HeadlessDrawStats,
+    texture_memory_bytes: usize,
+    texture_memory_budget_bytes: Option<usize>,
+}
+
+// draws the renderer objects unlit with the albedo color and the opacity of their materials
+// into framebuffers in the memory, so the frames can be inspected without a window or a gpu,
+// the shaders, the textures, the shadows, the post process and the debug lines are ignored
+#[derive(Clone)]
+pub struct HeadlessRenderer {
+    state: ArcRwLock<HeadlessRendererState>,
+}
+
+impl HeadlessRenderer {
+    pub fn new(window_dimensions: Vec2<usize>) -> Self {
+        Self {
+            state: arc_rw_lock_new(HeadlessRendererState {
+                framebuffer: HeadlessFramebuffer::new(window_dimensions),
+                pipeline_steps: Vec::new(),
+
+                cameras: ObjectPool::new(),
+                renderer_groups: ObjectPool::new(),
+                renderer_layers: ObjectPool::new(),
+                materials: ObjectPool::new(),
+                meshes: ObjectPool::new(),
+                renderer_objects: ObjectPool::new(),
+                probes: ObjectPool::new(),
+                shaders: ObjectPool::new(),
+                textures: ObjectPool::new(),
+                transforms: ObjectPool::new(),
+                windows: ObjectPool::new(),
+
+                draw_stats: HeadlessDrawStats::default(),
+                texture_memory_bytes: 0,
+                texture_memory_budget_bytes: None,
+            }),
+        }
+    }
+
+    // the clones of the renderer share the framebuffers,
+    // so they can be read while the renderer system owns the renderer
+    pub fn framebuffer_image(&self) -> Image {
+        self.state.read().framebuffer.to_image()
+    }
+
+    pub fn window_framebuffer_image(
+        &self,
+        window: &ArcRwLock<dyn RendererWindow>,
+    ) -> Result<Image, String> {
+        let index = get_window_index(window)
+            .map_err(|e| format!("Reading window framebuffer, msg = {e}"))?;
+
+        let state = self.state.read();
+        let window = state.windows.get_ref(index).ok_or_else(|| {
+            "Reading window framebuffer, msg = could not find RendererWindow".to_string()
+        })?;
+
+        Ok(window.framebuffer.to_image())
+    }
+
+    pub fn render_texture_image(
+        &self,
+        texture: &ArcRwLock<dyn RendererTexture>,
+    ) -> Result<Image, String> {
+        let index =
+            get_texture_index(texture).map_err(|e| format!("Reading render texture, msg = {e}"))?;
+
+        let state = self.state.read();
+        match state.textures.get_ref(index) {
+            Some(HeadlessTexture::Render(framebuffer)) => Ok(framebuffer.to_image()),
+            Some(_) => {
+                Err("Reading render texture, msg = the texture is not a render texture".to_string())
+            }
+            None => Err("Reading render texture, msg = could not find RendererTexture".to_string()),
+        }
+    }
+}
+
+fn viewport_ssc(
+    dimensions: Vec2<usize>,
+    viewport_start_ndc: Vec2<f32>,
+    viewport_end_ndc: Vec2<f32>,
+) -> Rect<usize, usize> {
+    let dimensions = dimensions.as_::<f32>();
+
+    // the y axis of the ndc points upwards, the y axis of the pixels points downwards
+    let left = (viewport_start_ndc.x * dimensions.x).round() as usize;
+    let right = (viewport_end_ndc.x * dimensions.x).round() as usize;
+    let top = ((1.0 - viewport_end_ndc.y) * dimensions.y).round() as usize;
+    let bottom = ((1.0 - viewport_start_ndc.y) * dimensions.y).round() as usize;
+
+    Rect::new(
+        left,
+        top,
+        right.saturating_sub(left).max(1),
+        bottom.saturating_sub(top).max(1),
+    )
+}
+
+fn edge_function(a: Vec2<f32>, b: Vec2<f32>, point: Vec2<f32>) -> f32 {
+    (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x)
+}
+
+// calls the callback with the position and the depth of every pixel of the scissor rect,
+// whose center is covered by the triangle, both windings are drawn
+fn for_each_covered_pixel(
+    triangle_ndc: [Vec3<f32>; 3],
+    viewport: Rect<usize, usize>,
+    scissor: Rect<usize, usize>,
+    mut callback: impl FnMut(Vec2<usize>, f32),
+) {
+    let [a, b, c] = triangle_ndc.map(|position| {
+        Vec3::new(
+            viewport.x as f32 + (position.x + 1.0) / 2.0 * viewport.w as f32,
+            viewport.y as f32 + (1.0 - position.y) / 2.0 * viewport.h as f32,
+            position.z,
+        )
+    });
+
+    let area = edge_function(a.xy(), b.xy(), c.xy());
+    if area.abs() < f32::EPSILON {
+        return;
+    }
+
+    let min_x = a.x.min(b.x).min(c.x).floor().max(scissor.x as f32) as usize;
+    let max_x =
+        a.x.max(b.x)
+            .max(c.x)
+            .ceil()
+            .min((scissor.x + scissor.w) as f32) as usize;
+    let min_y = a.y.min(b.y).min(c.y).floor().max(scissor.y as f32) as usize;
+    let max_y =
+        a.y.max(b.y)
+            .max(c.y)
+            .ceil()
+            .min((scissor.y + scissor.h) as f32) as usize;
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+            let weight_a = edge_function(b.xy(), c.xy(), point) / area;
+            let weight_b = edge_function(c.xy(), a.xy(), point) / area;
+            let weight_c = edge_function(a.xy(), b.xy(), point) / area;
+
+            if weight_a >= 0.0 && weight_b >= 0.0 && weight_c >= 0.0 {
+                let depth = weight_a * a.z + weight_b * b.z + weight_c * c.z;
+                callback(Vec2::new(x, y), depth);
+            }
+        }
+    }
+}
+
+// the triangles that are behind the eye are skipped instead of being clipped
+fn project_triangle(
+    view_projection_matrix: &Mat4<f32>,
+    positions: &[Vec3<f32>; 3],
+) -> Option<[Vec3<f32>; 3]> {
+    let mut triangle_ndc = [Vec3::zero(); 3];
+
+    for (position_ndc, position) in triangle_ndc.iter_mut().zip(positions.iter()) {
+        let position_clip = *view_projection_matrix * Vec4::from_point(*position);
+        if position_clip.w <= f32::EPSILON {
+            return None;
+        }
+
+        *position_ndc = position_clip.xyz() / position_clip.w;
+    }
+
+    Some(triangle_ndc)
+}
+
+fn blend_colors(source: Vec4<f32>, destination: Vec4<f32>) -> Vec4<f32> {
+    source * source.w + destination * (1.0 - source.w)
+}
+
+impl HeadlessRendererState {
+    fn compute_world_matrix(&self, transform_index: ObjectPoolIndex) -> Mat4<f32> {
+        let mut world_matrix = Mat4::identity();
+
+        // the hierarchy is kept free of cycles by set_transform_parent
+        let mut current_index = Some(transform_index);
+        while let Some(transform) = current_index.and_then(|index| self.transforms.get_ref(index)) {
+            world_matrix = Into::<Mat4<f32>>::into(transform.local_transform) * world_matrix;
+            current_index = transform.parent;
+        }
+
+        world_matrix
+    }
+
+    fn collect_triangles(
+        &self,
+        renderer_object_index: ObjectPoolIndex,
+        eye_position: Vec3<f32>,
+        triangles: &mut Vec<HeadlessTriangle>,
+    ) {
+        match self.renderer_objects.get_ref(renderer_object_index) {
+            Some(HeadlessRendererObject::Mesh {
+                lod_meshes,
+                material,
+                transforms,
+            }) => {
+                let color = self
+                    .materials
+                    .get_ref(*material)
+                    .map(|material| material.albedo_color.with_w(material.opacity))
+                    .unwrap_or_else(Vec4::one);
+
+                for transform in transforms.iter() {
+                    let world_matrix = self.compute_world_matrix(*transform);
+
+                    let distance_squared = world_matrix.cols.w.xyz().distance_squared(eye_position);
+                    let mesh = lod_meshes
+                        .iter()
+                        .rev()
+                        .find(|(min_distance, _)| min_distance * min_distance <= distance_squared)
+                        .or_else(|| lod_meshes.first())
+                        .and_then(|(_, mesh)| self.meshes.get_ref(*mesh));
+
+                    let Some(mesh) = mesh else {
+                        continue;
+                    };
+
+                    let positions = mesh.get_positions();
+                    for face in mesh.get_faces().chunks_exact(3) {
+                        triangles.push(HeadlessTriangle {
+                            positions: [
+                                world_matrix.mul_point(positions[face[0] as usize]),
+                                world_matrix.mul_point(positions[face[1] as usize]),
+                                world_matrix.mul_point(positions[face[2] as usize]),
+                            ],
+                            color,
+                        });
+                    }
+                }
+            }
+            Some(HeadlessRendererObject::SpriteBatch { sprites, transform }) => {
+                let world_matrix = self.compute_world_matrix(*transform);
+
+                for sprite in sprites.iter() {
+                    let bottom_left = sprite.position;
+                    let top_right = sprite.position + sprite.size;
+                    let corners = [
+                        Vec3::new(bottom_left.x, bottom_left.y, 0.0),
+                        Vec3::new(top_right.x, bottom_left.y, 0.0),
+                        Vec3::new(top_right.x, top_right.y, 0.0),
+                        Vec3::new(bottom_left.x, top_right.y, 0.0),
+                    ]
+                    .map(|corner| world_matrix.mul_point(corner));
+
+                    triangles.push(HeadlessTriangle {
+                        positions: [corners[0], corners[1], corners[2]],
+                        color: sprite.color,
+                    });
+                    triangles.push(HeadlessTriangle {
+                        positions: [corners[0], corners[2], corners[3]],
+                        color: sprite.color,
+                    });
+                }
+            }
+            Some(HeadlessRendererObject::Skybox)
+            | Some(HeadlessRendererObject::ParticleEmitter)
+            | None => (),
+        }
+    }
+
+    // returns the view projection matrix and the position of the eye of the layer
+    fn layer_view_projection(
+        &self,
+        renderer_layer: &HeadlessRendererLayer,
+        viewport: Rect<usize, usize>,
+        compute_projection_matrix: &(dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync),
+    ) -> Result<(Mat4<f32>, Vec3<f32>), String> {
+        let camera = self
+            .cameras
+            .get_ref(renderer_layer.camera)
+            .ok_or_else(|| "could not find RendererCamera of RendererLayer".to_string())?;
+        let camera_world_matrix = self.compute_world_matrix(camera.transform);
+
+        // the projection of the layer overrides the parameters of the camera,
+        // which override the projection matrix of the pipeline step
+        let projection_matrix = match (&renderer_layer.projection, &camera.parameters) {
+            (Some(projection), _) => projection.compute_projection_matrix(viewport.w, viewport.h),
+            (None, Some(parameters)) => {
+                parameters.compute_projection_matrix(viewport.w, viewport.h)
+            }
+            (None, None) => compute_projection_matrix(viewport.w, viewport.h),
+        };
+
+        Ok((
+            projection_matrix * camera_world_matrix.inverted(),
+            camera_world_matrix.cols.w.xyz(),
+        ))
+    }
+
+    fn draw_renderer_layer(
+        &self,
+        renderer_layer: &ArcRwLock<dyn RendererLayer>,
+        framebuffer: &mut HeadlessFramebuffer,
+        viewport: Rect<usize, usize>,
+        compute_projection_matrix: &(dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync),
+        draw_stats: &mut HeadlessDrawStats,
+    ) -> Result<(), String> {
+        let index = get_renderer_layer_index(renderer_layer)?;
+        let renderer_layer = self
+            .renderer_layers
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererLayer".to_string())?;
+
+        let reverse_z = renderer_layer.settings.reverse_z;
+        framebuffer.clear(
+            viewport,
+            renderer_layer.settings.clear_color,
+            renderer_layer
+                .settings
+                .clear_depth
+                .then_some(if reverse_z { 0.0 } else { 1.0 }),
+            false,
+        );
+
+        let (mut view_projection_matrix, eye_position) =
+            self.layer_view_projection(renderer_layer, viewport, compute_projection_matrix)?;
+        if reverse_z {
+            view_projection_matrix = reverse_depth(view_projection_matrix);
+        }
+
+        let mut triangles = Vec::new();
+        for renderer_group in renderer_layer.renderer_groups.iter() {
+            let Some(renderer_objects) = self.renderer_groups.get_ref(*renderer_group) else {
+                continue;
+            };
+
+            for renderer_object in renderer_objects.iter() {
+                let number_of_triangles = triangles.len();
+                self.collect_triangles(*renderer_object, eye_position, &mut triangles);
+
+                if triangles.len() != number_of_triangles {
+                    draw_stats.draw_calls += 1;
+                }
+            }
+        }
+        draw_stats.triangles += triangles.len();
+
+        let exposure = renderer_layer.settings.exposure;
+        let stencil = renderer_layer.settings.stencil;
+        let width = framebuffer.dimensions.x;
+        let scissor = Rect::new(
+            viewport.x,
+            viewport.y,
+            viewport
+                .w
+                .min(framebuffer.dimensions.x.saturating_sub(viewport.x)),
+            viewport
+                .h
+                .min(framebuffer.dimensions.y.saturating_sub(viewport.y)),
+        );
+        for triangle in triangles.iter() {
+            let Some(triangle_ndc) = project_triangle(&view_projection_matrix, &triangle.positions)
+            else {
+                continue;
+            };
+
+            let color = (triangle.color.xyz() * exposure).with_w(triangle.color.w);
+            for_each_covered_pixel(triangle_ndc, viewport, scissor, |pixel, depth| {
+                let pixel_index = pixel.y * width + pixel.x;
+
+                // the depth is clamped instead of clipped for reverse z, like with GL_DEPTH_CLAMP
+                let depth = if reverse_z {
+                    depth.clamp(0.0, 1.0)
+                } else if (0.0..=1.0).contains(&depth) {
+                    depth
+                } else {
+                    return;
+                };
+
+                // the stencil test comes before the depth test, like in gl
+                let stored_stencil = framebuffer.stencils[pixel_index];
+                if let Some(stencil) = &stencil {
+                    if !stencil.passes(stored_stencil) {
+                        framebuffer.stencils[pixel_index] =
+                            stencil.updated_value(stencil.stencil_fail, stored_stencil);
+                        return;
+                    }
+                }
+
+                let depth_passes = if reverse_z {
+                    depth > framebuffer.depths[pixel_index]
+                } else {
+                    depth < framebuffer.depths[pixel_index]
+                };
+                if let Some(stencil) = &stencil {
+                    let operation = if depth_passes {
+                        stencil.depth_pass
+                    } else {
+                        stencil.depth_fail
+                    };
+                    framebuffer.stencils[pixel_index] =
+                        stencil.updated_value(operation, stored_stencil);
+                }
+
+                if depth_passes {
+                    framebuffer.depths[pixel_index] = depth;
+                    framebuffer.colors[pixel_index] =
+                        blend_colors(color, framebuffer.colors[pixel_index]);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    fn draw_pipeline_steps(
+        &mut self,
+        steps: &[RendererPipelineStepImpl],
+        framebuffer: &mut HeadlessFramebuffer,
+        draw_stats: &mut HeadlessDrawStats,
+    ) -> Result<(), String> {
+        for step in steps.iter() {
+            match step {
+                RendererPipelineStepImpl::Clear {
+                    depth,
+                    color,
+                    stencil,
+                    clear_color,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                } => {
+                    let viewport = viewport_ssc(
+                        framebuffer.dimensions,
+                        *viewport_start_ndc,
+                        *viewport_end_ndc,
+                    );
+                    framebuffer.clear(
+                        viewport,
+                        color.then_some(*clear_color),
+                        depth.then_some(1.0),
+                        *stencil,
+                    );
+                }
+                RendererPipelineStepImpl::Draw {
+                    renderer_layer,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                    compute_projection_matrix,
+                } => {
+                    let viewport = viewport_ssc(
+                        framebuffer.dimensions,
+                        *viewport_start_ndc,
+                        *viewport_end_ndc,
+                    );
+                    self.draw_renderer_layer(
+                        renderer_layer,
+                        framebuffer,
+                        viewport,
+                        compute_projection_matrix.as_ref(),
+                        draw_stats,
+                    )?;
+                }
+                RendererPipelineStepImpl::DrawToTexture {
+                    renderer_layer,
+                    renderer_texture,
+                    compute_projection_matrix,
+                } => {
+                    let texture_index = get_texture_index(renderer_texture)?;
+
+                    // the framebuffer of the texture is taken out of the pool while it is drawn into
+                    let mut texture_framebuffer = match self.textures.get_mut(texture_index) {
+                        Some(HeadlessTexture::Render(texture_framebuffer)) => {
+                            std::mem::take(texture_framebuffer)
+                        }
+                        _ => return Err("could not find render RendererTexture".to_string()),
+                    };
+
+                    let viewport = texture_framebuffer.full_viewport();
+                    texture_framebuffer.clear(viewport, Some(Vec4::zero()), Some(1.0), true);
+                    let result = self.draw_renderer_layer(
+                        renderer_layer,
+                        &mut texture_framebuffer,
+                        viewport,
+                        compute_projection_matrix.as_ref(),
+                        draw_stats,
+                    );
+
+                    if let Some(HeadlessTexture::Render(framebuffer)) =
+                        self.textures.get_mut(texture_index)
+                    {
+                        *framebuffer = texture_framebuffer;
+                    }
+
+                    result?;
+                }
+                RendererPipelineStepImpl::ShadowPass { .. }
+                | RendererPipelineStepImpl::PostProcess { .. }
+                | RendererPipelineStepImpl::DrawDebugLines { .. } => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render(&mut self) -> Result<(), String> {
+        let mut draw_stats = HeadlessDrawStats::default();
+
+        let steps = self.pipeline_steps.clone();
+        let mut framebuffer = std::mem::take(&mut self.framebuffer);
+        let result = self.draw_pipeline_steps(&steps, &mut framebuffer, &mut draw_stats);
+        self.framebuffer = framebuffer;
+        result?;
+
+        let mut windows = Vec::new();
+        for window in self.windows.iter_mut() {
+            windows.push((
+                window.pipeline_steps.clone(),
+                std::mem::take(&mut window.framebuffer),
+            ));
+        }
+
+        // the windows are put back in the order of the pool, even if one of them fails to draw
+        let mut result = Ok(());
+        for (steps, framebuffer) in windows.iter_mut() {
+            if result.is_ok() {
+                result = self.draw_pipeline_steps(steps, framebuffer, &mut draw_stats);
+            }
+        }
+        for (window, (_, framebuffer)) in self.windows.iter_mut().zip(windows.into_iter()) {
+            window.framebuffer = framebuffer;
+        }
+
+        self.draw_stats = draw_stats;
+
+        result
+    }
+
+    fn get_transform(
+        &self,
+        transform: &ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ObjectPoolIndex, String> {
+        let index = get_transform_index(transform)?;
+        self.transforms
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererTransform".to_string())?;
+        Ok(index)
+    }
+
+    fn get_material(
+        &self,
+        material: &ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<ObjectPoolIndex, String> {
+        let index = get_material_index(material)?;
+        self.materials
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererMaterial".to_string())?;
+        Ok(index)
+    }
+
+    fn get_shader(
+        &self,
+        shader: &ArcRwLock<dyn RendererShader>,
+    ) -> Result<ObjectPoolIndex, String> {
+        let index = get_shader_index(shader)?;
+        self.shaders
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererShader".to_string())?;
+        Ok(index)
+    }
+
+    fn get_mesh(&self, mesh: &ArcRwLock<dyn RendererMesh>) -> Result<ObjectPoolIndex, String> {
+        let index = get_mesh_index(mesh)?;
+        self.meshes
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererMesh".to_string())?;
+        Ok(index)
+    }
+
+    fn get_texture(
+        &self,
+        texture: &ArcRwLock<dyn RendererTexture>,
+    ) -> Result<ObjectPoolIndex, String> {
+        let index = get_texture_index(texture)?;
+        self.textures
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererTexture".to_string())?;
+        Ok(index)
+    }
+
+    fn get_renderer_layer(
+        &self,
+        renderer_layer: &ArcRwLock<dyn RendererLayer>,
+    ) -> Result<ObjectPoolIndex, String> {
+        let index = get_renderer_layer_index(renderer_layer)?;
+        self.renderer_layers
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererLayer".to_string())?;
+        Ok(index)
+    }
+
+    fn get_renderer_group(
+        &self,
+        renderer_group: &ArcRwLock<dyn RendererGroup>,
+    ) -> Result<ObjectPoolIndex, String> {
+        let index = get_renderer_group_index(renderer_group)?;
+        self.renderer_groups
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererGroup".to_string())?;
+        Ok(index)
+    }
+
+    fn get_renderer_object(
+        &self,
+        renderer_object: &ArcRwLock<dyn RendererObject>,
+    ) -> Result<ObjectPoolIndex, String> {
+        let index = get_renderer_object_index(renderer_object)?;
+        self.renderer_objects
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererObject".to_string())?;
+        Ok(index)
+    }
+
+    fn get_probe(&self, probe: &ArcRwLock<dyn RendererProbe>) -> Result<ObjectPoolIndex, String> {
+        let index = get_probe_index(probe)?;
+        self.probes
+            .get_ref(index)
+            .ok_or_else(|| "could not find RendererProbe".to_string())?;
+        Ok(index)
+    }
+
+    fn create_texture(&mut self, texture: HeadlessTexture) -> ObjectPoolIndex {
+        self.texture_memory_bytes += texture.memory_bytes();
+        self.textures.create_object(texture)
+    }
+
+    fn create_mesh_renderer_object(
+        &mut self,
+        lod_meshes: Vec<(f32, ArcRwLock<dyn RendererMesh>)>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transforms: Vec<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        self.get_shader(&shader)?;
+        let material = self.get_material(&material)?;
+
+        let transforms = transforms
+            .iter()
+            .map(|transform| self.get_transform(transform))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut meshes = Vec::with_capacity(lod_meshes.len());
+        for (distance, mesh) in lod_meshes.iter() {
+            if !distance.is_finite() || *distance < 0.0 {
+                return Err(format!("invalid lod distance, distance = {distance}"));
+            }
+
+            meshes.push((*distance, self.get_mesh(mesh)?));
+        }
+        if meshes.is_empty() {
+            return Err("at least one lod mesh is required".to_string());
+        }
+        meshes.sort_by(|(distance_a, _), (distance_b, _)| distance_a.total_cmp(distance_b));
+
+        let index = self
+            .renderer_objects
+            .create_object(HeadlessRendererObject::Mesh {
+                lod_meshes: meshes,
+                material,
+                transforms,
+            });
+
+        Ok(arc_rw_lock_new(HeadlessObjectIndex(index)))
+    }
+}
+
+impl RendererImpl for HeadlessRenderer {
+    fn render(&mut self) {
+        if let Err(e) = self.state.write().render() {
+            log::error!("Rendering, msg = {e}");
+        }
+    }
+
+    fn window_dimensions_changed(&mut self, width: usize, height: usize) -> Result<(), String> {
+        self.state.write().framebuffer = HeadlessFramebuffer::new(Vec2::new(width, height));
+        Ok(())
+    }
+
+    fn set_renderer_config(&mut self, _renderer_config: RendererConfig) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_renderer_pipeline(
+        &mut self,
+        steps: Vec<RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        self.state.write().pipeline_steps = steps;
+        Ok(())
+    }
+
+    fn set_pipeline_clear_color(
+        &mut self,
+        step_index: usize,
+        clear_color: Vec4<f32>,
+    ) -> Result<(), String> {
+        set_pipeline_step_clear_color(
+            &mut self.state.write().pipeline_steps,
+            step_index,
+            clear_color,
+        )
+    }
+
+    fn create_window(
+        &mut self,
+        _title: &str,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererWindow>, String> {
+        let index = self.state.write().windows.create_object(HeadlessWindow {
+            framebuffer: HeadlessFramebuffer::new(dimensions),
+            pipeline_steps: Vec::new(),
+        });
+
+        Ok(arc_rw_lock_new(HeadlessWindowIndex(index)))
+    }
+
+    fn set_window_pipeline(
+        &mut self,
+        window: ArcRwLock<dyn RendererWindow>,
+        steps: Vec<RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        // the same steps are accepted as by the gpu renderers
+        if steps
+            .iter()
+            .any(|step| matches!(step, RendererPipelineStepImpl::PostProcess { .. }))
+        {
+            return Err(
+                "Setting window pipeline, msg = post process steps are only supported in the main window"
+                    .to_string(),
+            );
+        }
+
+        let index =
+            get_window_index(&window).map_err(|e| format!("Setting window pipeline, msg = {e}"))?;
+
+        self.state
+            .write()
+            .windows
+            .get_mut(index)
+            .ok_or_else(|| {
+                "Setting window pipeline, msg = could not find RendererWindow".to_string()
+            })?
+            .pipeline_steps = steps;
+
+        Ok(())
+    }
+
+    fn release_window(&mut self, window: ArcRwLock<dyn RendererWindow>) -> Result<(), String> {
+        let index =
+            get_window_index(&window).map_err(|e| format!("Releasing window, msg = {e}"))?;
+
+        self.state
+            .write()
+            .windows
+            .release_object(index)
+            .ok_or_else(|| "Releasing window, msg = could not find RendererWindow".to_string())?;
+
+        Ok(())
+    }
+
+    fn create_renderer_layer(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+    ) -> Result<ArcRwLock<dyn RendererLayer>, String> {
+        let camera =
+            get_camera_index(&camera).map_err(|e| format!("Creating renderer layer, msg = {e}"))?;
+
+        let mut state = self.state.write();
+        state.cameras.get_ref(camera).ok_or_else(|| {
+            "Creating renderer layer, msg = could not find RendererCamera".to_string()
+        })?;
+
+        let index = state.renderer_layers.create_object(HeadlessRendererLayer {
+            camera,
+            renderer_groups: BTreeSet::new(),
+            settings: RendererLayerSettings::default(),
+            projection: None,
+        });
+
+        Ok(arc_rw_lock_new(HeadlessLayerIndex(index)))
+    }
+
+    fn release_renderer_layer(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let index = get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Releasing renderer layer, msg = {e}"))?;
+
+        self.state
+            .write()
+            .renderer_layers
+            .release_object(index)
+            .ok_or_else(|| {
+                "Releasing renderer layer, msg = could not find RendererLayer".to_string()
+            })?;
+
+        Ok(())
+    }
+
+    fn set_renderer_layer_settings(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        settings: RendererLayerSettings,
+    ) -> Result<(), String> {
+        let index = get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Setting renderer layer settings, msg = {e}"))?;
+
+        self.state
+            .write()
+            .renderer_layers
+            .get_mut(index)
+            .ok_or_else(|| {
+                "Setting renderer layer settings, msg = could not find RendererLayer".to_string()
+            })?
+            .settings = settings;
+
+        Ok(())
+    }
+
+    fn update_renderer_layer_projection(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        projection: Option<RendererLayerProjection>,
+    ) -> Result<(), String> {
+        let index = get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Updating renderer layer projection, msg = {e}"))?;
+
+        self.state
+            .write()
+            .renderer_layers
+            .get_mut(index)
+            .ok_or_else(|| {
+                "Updating renderer layer projection, msg = could not find RendererLayer".to_string()
+            })?
+            .projection = projection;
+
+        Ok(())
+    }
+
+    fn set_renderer_layer_override_shader(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        shader: Option<ArcRwLock<dyn RendererShader>>,
+    ) -> Result<(), String> {
+        let state = self.state.read();
+
+        state
+            .get_renderer_layer(&renderer_layer)
+            .map_err(|e| format!("Setting renderer layer override shader, msg = {e}"))?;
+        if let Some(shader) = shader {
+            state
+                .get_shader(&shader)
+                .map_err(|e| format!("Setting renderer layer override shader, msg = {e}"))?;
+        }
+
+        Ok(())
+    }
+
+    fn add_renderer_group_to_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_group = state
+            .get_renderer_group(&renderer_group)
+            .map_err(|e| format!("Adding renderer group to layer, msg = {e}"))?;
+        let renderer_layer = state
+            .get_renderer_layer(&renderer_layer)
+            .map_err(|e| format!("Adding renderer group to layer, msg = {e}"))?;
+
+        if let Some(renderer_layer) = state.renderer_layers.get_mut(renderer_layer) {
+            renderer_layer.renderer_groups.insert(renderer_group);
+        }
+
+        Ok(())
+    }
+
+    fn remove_renderer_group_from_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_group = get_renderer_group_index(&renderer_group)
+            .map_err(|e| format!("Removing renderer group from layer, msg = {e}"))?;
+        let renderer_layer = state
+            .get_renderer_layer(&renderer_layer)
+            .map_err(|e| format!("Removing renderer group from layer, msg = {e}"))?;
+
+        if let Some(renderer_layer) = state.renderer_layers.get_mut(renderer_layer) {
+            renderer_layer.renderer_groups.remove(&renderer_group);
+        }
+
+        Ok(())
+    }
+
+    fn create_renderer_group(&mut self) -> Result<ArcRwLock<dyn RendererGroup>, String> {
+        let index = self
+            .state
+            .write()
+            .renderer_groups
+            .create_object(BTreeSet::new());
+
+        Ok(arc_rw_lock_new(HeadlessGroupIndex(index)))
+    }
+
+    fn release_renderer_group(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let index = get_renderer_group_index(&renderer_group)
+            .map_err(|e| format!("Releasing renderer group, msg = {e}"))?;
+
+        let mut state = self.state.write();
+        state.renderer_groups.release_object(index).ok_or_else(|| {
+            "Releasing renderer group, msg = could not find RendererGroup".to_string()
+        })?;
+
+        for renderer_layer in state.renderer_layers.iter_mut() {
+            renderer_layer.renderer_groups.remove(&index);
+        }
+
+        Ok(())
+    }
+
+    fn create_transform(
+        &mut self,
+        transform: Transform<f32, f32, f32>,
+    ) -> Result<ArcRwLock<dyn RendererTransform>, String> {
+        let index = self
+            .state
+            .write()
+            .transforms
+            .create_object(HeadlessTransform {
+                local_transform: transform,
+                parent: None,
+            });
+
+        Ok(arc_rw_lock_new(HeadlessTransformIndex(index)))
+    }
+
+    fn update_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+    ) -> Result<(), String> {
+        let index = get_transform_index(&transform)
+            .map_err(|e| format!("Updating transform, msg = {e}"))?;
+
+        self.state
+            .write()
+            .transforms
+            .get_mut(index)
+            .ok_or_else(|| {
+                "Updating transform, msg = could not find RendererTransform".to_string()
+            })?
+            .local_transform = new_transform;
+
+        Ok(())
+    }
+
+    // the latest transform is drawn, so the frames do not depend on the time of rendering
+    fn update_transform_interpolated(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+        _timestamp: Instant,
+    ) -> Result<(), String> {
+        self.update_transform(transform, new_transform)
+    }
+
+    fn set_transform_parent(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        parent_transform: Option<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let index = state
+            .get_transform(&transform)
+            .map_err(|e| format!("Setting transform parent, msg = {e}"))?;
+
+        let parent_index = if let Some(parent_transform) = parent_transform {
+            let parent_index = state
+                .get_transform(&parent_transform)
+                .map_err(|e| format!("Setting transform parent, msg = {e}"))?;
+
+            let mut ancestor_index = Some(parent_index);
+            while let Some(current_index) = ancestor_index {
+                if current_index == index {
+                    return Err(
+                        "Setting transform parent, msg = transform hierarchy would contain a cycle"
+                            .to_string(),
+                    );
+                }
+
+                ancestor_index = state
+                    .transforms
+                    .get_ref(current_index)
+                    .and_then(|ancestor| ancestor.parent);
+            }
+
+            Some(parent_index)
+        } else {
+            None
+        };
+
+        if let Some(transform) = state.transforms.get_mut(index) {
+            transform.parent = parent_index;
+        }
+
+        Ok(())
+    }
+
+    fn release_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String> {
+        let index = get_transform_index(&transform)
+            .map_err(|e| format!("Releasing transform, msg = {e}"))?;
+
+        let mut state = self.state.write();
+        state.transforms.release_object(index).ok_or_else(|| {
+            "Releasing transform, msg = could not find RendererTransform".to_string()
+        })?;
+
+        // the children of the released transform become roots
+        for child in state.transforms.iter_mut() {
+            if child.parent == Some(index) {
+                child.parent = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_material(
+        &mut self,
+        material: Material,
+    ) -> Result<ArcRwLock<dyn RendererMaterial>, String> {
+        let index = self.state.write().materials.create_object(material);
+
+        Ok(arc_rw_lock_new(HeadlessMaterialIndex(index)))
+    }
+
+    fn update_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        new_material: Material,
+    ) -> Result<(), String> {
+        let index =
+            get_material_index(&material).map_err(|e| format!("Updating material, msg = {e}"))?;
+
+        *self.state.write().materials.get_mut(index).ok_or_else(|| {
+            "Updating material, msg = could not find RendererMaterial".to_string()
+        })? = new_material;
+
+        Ok(())
+    }
+
+    fn release_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
+        let index =
+            get_material_index(&material).map_err(|e| format!("Releasing material, msg = {e}"))?;
+
+        self.state
+            .write()
+            .materials
+            .release_object(index)
+            .ok_or_else(|| {
+                "Releasing material, msg = could not find RendererMaterial".to_string()
+            })?;
+
+        Ok(())
+    }
+
+    fn set_material_render_texture(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        _texture_type: MaterialTextureType,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        let state = self.state.read();
+
+        state
+            .get_material(&material)
+            .map_err(|e| format!("Setting material render texture, msg = {e}"))?;
+        state
+            .get_texture(&texture)
+            .map_err(|e| format!("Setting material render texture, msg = {e}"))?;
+
+        Ok(())
+    }
+
+    fn create_render_texture(
+        &mut self,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let index =
+            self.state
+                .write()
+                .create_texture(HeadlessTexture::Render(HeadlessFramebuffer::new(
+                    dimensions,
+                )));
+
+        Ok(arc_rw_lock_new(HeadlessTextureIndex(index)))
+    }
+
+    fn release_render_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        let index = get_texture_index(&texture)
+            .map_err(|e| format!("Releasing render texture, msg = {e}"))?;
+
+        let mut state = self.state.write();
+        let texture = state.textures.release_object(index).ok_or_else(|| {
+            "Releasing render texture, msg = could not find RendererTexture".to_string()
+        })?;
+        state.texture_memory_bytes -= texture.memory_bytes();
+
+        Ok(())
+    }
+
+    fn create_texture(
+        &mut self,
+        image: Arc<Image>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let index = self
+            .state
+            .write()
+            .create_texture(HeadlessTexture::Image(image));
+
+        Ok(arc_rw_lock_new(HeadlessTextureIndex(index)))
+    }
+
+    fn update_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        image: Arc<Image>,
+    ) -> Result<(), String> {
+        let index =
+            get_texture_index(&texture).map_err(|e| format!("Updating texture, msg = {e}"))?;
+
+        let mut state = self.state.write();
+        match state.textures.get_mut(index) {
+            Some(HeadlessTexture::Image(texture_image)) => {
+                let previous_memory_bytes = texture_image.size_in_bytes();
+                let memory_bytes = image.size_in_bytes();
+                *texture_image = image;
+
+                state.texture_memory_bytes =
+                    state.texture_memory_bytes + memory_bytes - previous_memory_bytes;
+                Ok(())
+            }
+            Some(_) => {
+                Err("Updating texture, msg = only image textures can be updated".to_string())
+            }
+            None => Err("Updating texture, msg = could not find RendererTexture".to_string()),
+        }
+    }
+
+    fn create_cubemap_texture(
+        &mut self,
+        faces: [Arc<Image>; 6],
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let index = self
+            .state
+            .write()
+            .create_texture(HeadlessTexture::Cubemap(faces));
+
+        Ok(arc_rw_lock_new(HeadlessTextureIndex(index)))
+    }
+
+    fn create_shader(
+        &mut self,
+        shader_name: String,
+    ) -> Result<ArcRwLock<dyn RendererShader>, String> {
+        let index = self.state.write().shaders.create_object(shader_name);
+
+        Ok(arc_rw_lock_new(HeadlessShaderIndex(index)))
+    }
+
+    fn update_shader(
+        &mut self,
+        shader: ArcRwLock<dyn RendererShader>,
+        new_shader_name: String,
+    ) -> Result<(), String> {
+        let index = get_shader_index(&shader).map_err(|e| format!("Updating shader, msg = {e}"))?;
+
+        *self
+            .state
+            .write()
+            .shaders
+            .get_mut(index)
+            .ok_or_else(|| "Updating shader, msg = could not find RendererShader".to_string())? =
+            new_shader_name;
+
+        Ok(())
+    }
+
+    fn release_shader(&mut self, shader: ArcRwLock<dyn RendererShader>) -> Result<(), String> {
+        let index =
+            get_shader_index(&shader).map_err(|e| format!("Releasing shader, msg = {e}"))?;
+
+        self.state
+            .write()
+            .shaders
+            .release_object(index)
+            .ok_or_else(|| "Releasing shader, msg = could not find RendererShader".to_string())?;
+
+        Ok(())
+    }
+
+    fn create_mesh(&mut self, mesh: Arc<Mesh>) -> Result<ArcRwLock<dyn RendererMesh>, String> {
+        let index = self.state.write().meshes.create_object(mesh);
+
+        Ok(arc_rw_lock_new(HeadlessMeshIndex(index)))
+    }
+
+    fn update_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        new_mesh: Arc<Mesh>,
+    ) -> Result<(), String> {
+        let index = get_mesh_index(&mesh).map_err(|e| format!("Updating mesh, msg = {e}"))?;
+
+        *self
+            .state
+            .write()
+            .meshes
+            .get_mut(index)
+            .ok_or_else(|| "Updating mesh, msg = could not find RendererMesh".to_string())? =
+            new_mesh;
+
+        Ok(())
+    }
+
+    // nothing is drawn, so only the range and the number of values are validated
+    fn update_mesh_vertices(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        vertex_range: Range<usize>,
+        vertices: RendererMeshVertices,
+    ) -> Result<(), String> {
+        let index =
+            get_mesh_index(&mesh).map_err(|e| format!("Updating mesh vertices, msg = {e}"))?;
+
+        let number_of_vertices = self
+            .state
+            .read()
+            .meshes
+            .get_ref(index)
+            .ok_or_else(|| "Updating mesh vertices, msg = could not find RendererMesh".to_string())?
+            .number_of_vertices();
+
+        vertices
+            .validate(&vertex_range, number_of_vertices)
+            .map_err(|e| format!("Updating mesh vertices, msg = {e}"))
+    }
+
+    fn release_mesh(&mut self, mesh: ArcRwLock<dyn RendererMesh>) -> Result<(), String> {
+        let index = get_mesh_index(&mesh).map_err(|e| format!("Releasing mesh, msg = {e}"))?;
+
+        self.state
+            .write()
+            .meshes
+            .release_object(index)
+            .ok_or_else(|| "Releasing mesh, msg = could not find RendererMesh".to_string())?;
+
+        Ok(())
+    }
+
+    fn create_renderer_object_from_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        self.state
+            .write()
+            .create_mesh_renderer_object(vec![(0.0, mesh)], shader, material, vec![transform])
+            .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))
+    }
+
+    fn create_instanced_renderer_object(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transforms: Vec<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        self.state
+            .write()
+            .create_mesh_renderer_object(vec![(0.0, mesh)], shader, material, transforms)
+            .map_err(|e| format!("Creating instanced renderer object, msg = {e}"))
+    }
+
+    fn create_lod_renderer_object(
+        &mut self,
+        lod_meshes: Vec<(f32, ArcRwLock<dyn RendererMesh>)>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        self.state
+            .write()
+            .create_mesh_renderer_object(lod_meshes, shader, material, vec![transform])
+            .map_err(|e| format!("Creating lod renderer object, msg = {e}"))
+    }
+
+    fn create_skybox_renderer_object(
+        &mut self,
+        cubemap: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let mut state = self.state.write();
+
+        let cubemap = state
+            .get_texture(&cubemap)
+            .map_err(|e| format!("Creating skybox renderer object, msg = {e}"))?;
+        if !matches!(
+            state.textures.get_ref(cubemap),
+            Some(HeadlessTexture::Cubemap(_))
+        ) {
+            return Err(
+                "Creating skybox renderer object, msg = the texture is not a cubemap".to_string(),
+            );
+        }
+
+        let index = state
+            .renderer_objects
+            .create_object(HeadlessRendererObject::Skybox);
+
+        Ok(arc_rw_lock_new(HeadlessObjectIndex(index)))
+    }
+
+    fn create_particle_emitter_renderer_object(
+        &mut self,
+        _settings: ParticleEmitterSettings,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let mut state = self.state.write();
+
+        state
+            .get_material(&material)
+            .map_err(|e| format!("Creating particle emitter renderer object, msg = {e}"))?;
+        state
+            .get_transform(&transform)
+            .map_err(|e| format!("Creating particle emitter renderer object, msg = {e}"))?;
+
+        let index = state
+            .renderer_objects
+            .create_object(HeadlessRendererObject::ParticleEmitter);
+
+        Ok(arc_rw_lock_new(HeadlessObjectIndex(index)))
+    }
+
+    fn create_sprite_batch_renderer_object(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        sprites: Vec<RendererSprite>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let mut state = self.state.write();
+
+        state
+            .get_texture(&texture)
+            .map_err(|e| format!("Creating sprite batch renderer object, msg = {e}"))?;
+        let transform = state
+            .get_transform(&transform)
+            .map_err(|e| format!("Creating sprite batch renderer object, msg = {e}"))?;
+
+        let index = state
+            .renderer_objects
+            .create_object(HeadlessRendererObject::SpriteBatch { sprites, transform });
+
+        Ok(arc_rw_lock_new(HeadlessObjectIndex(index)))
+    }
+
+    fn release_renderer_object(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+    ) -> Result<(), String> {
+        let index = get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Releasing renderer object, msg = {e}"))?;
+
+        let mut state = self.state.write();
+        state
+            .renderer_objects
+            .release_object(index)
+            .ok_or_else(|| {
+                "Releasing renderer object, msg = could not find RendererObject".to_string()
+            })?;
+
+        for renderer_objects in state.renderer_groups.iter_mut() {
+            renderer_objects.remove(&index);
+        }
+
+        Ok(())
+    }
+
+    fn add_renderer_object_to_group(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Adding renderer object to group, msg = {e}"))?;
+        let renderer_group = state
+            .get_renderer_group(&renderer_group)
+            .map_err(|e| format!("Adding renderer object to group, msg = {e}"))?;
+
+        if let Some(renderer_objects) = state.renderer_groups.get_mut(renderer_group) {
+            renderer_objects.insert(renderer_object);
+        }
+
+        Ok(())
+    }
+
+    fn remove_renderer_object_from_group(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_object = get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Removing renderer object from group, msg = {e}"))?;
+        let renderer_group = state
+            .get_renderer_group(&renderer_group)
+            .map_err(|e| format!("Removing renderer object from group, msg = {e}"))?;
+
+        if let Some(renderer_objects) = state.renderer_groups.get_mut(renderer_group) {
+            renderer_objects.remove(&renderer_object);
+        }
+
+        Ok(())
+    }
+
+    fn set_renderer_object_transform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting renderer object transform, msg = {e}"))?;
+        let new_transform = state
+            .get_transform(&transform)
+            .map_err(|e| format!("Setting renderer object transform, msg = {e}"))?;
+
+        match state.renderer_objects.get_mut(renderer_object) {
+            Some(HeadlessRendererObject::Mesh { transforms, .. }) if transforms.len() == 1 => {
+                transforms[0] = new_transform;
+                Ok(())
+            }
+            Some(HeadlessRendererObject::SpriteBatch { transform, .. }) => {
+                *transform = new_transform;
+                Ok(())
+            }
+            Some(HeadlessRendererObject::ParticleEmitter) => Ok(()),
+            _ => Err(
+                "Setting renderer object transform, msg = the renderer object does not have a single transform"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn set_renderer_object_material(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting renderer object material, msg = {e}"))?;
+        let new_material = state
+            .get_material(&material)
+            .map_err(|e| format!("Setting renderer object material, msg = {e}"))?;
+
+        match state.renderer_objects.get_mut(renderer_object) {
+            Some(HeadlessRendererObject::Mesh { material, .. }) => {
+                *material = new_material;
+                Ok(())
+            }
+            Some(HeadlessRendererObject::ParticleEmitter) => Ok(()),
+            _ => Err(
+                "Setting renderer object material, msg = the renderer object does not have a material"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn set_renderer_object_shader(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        shader: ArcRwLock<dyn RendererShader>,
+    ) -> Result<(), String> {
+        let state = self.state.read();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting renderer object shader, msg = {e}"))?;
+        state
+            .get_shader(&shader)
+            .map_err(|e| format!("Setting renderer object shader, msg = {e}"))?;
+
+        match state.renderer_objects.get_ref(renderer_object) {
+            Some(HeadlessRendererObject::Mesh { .. }) => Ok(()),
+            _ => Err(
+                "Setting renderer object shader, msg = the renderer object does not have a shader"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn set_renderer_object_mesh(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        mesh: ArcRwLock<dyn RendererMesh>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting renderer object mesh, msg = {e}"))?;
+        let mesh = state
+            .get_mesh(&mesh)
+            .map_err(|e| format!("Setting renderer object mesh, msg = {e}"))?;
+
+        // the nearest lod mesh is replaced, like in the gpu renderers
+        match state.renderer_objects.get_mut(renderer_object) {
+            Some(HeadlessRendererObject::Mesh { lod_meshes, .. }) => {
+                lod_meshes[0].1 = mesh;
+                Ok(())
+            }
+            _ => Err(
+                "Setting renderer object mesh, msg = the renderer object does not have a mesh"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn set_renderer_object_probe(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        probe: Option<ArcRwLock<dyn RendererProbe>>,
+    ) -> Result<(), String> {
+        let state = self.state.read();
+
+        state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting renderer object probe, msg = {e}"))?;
+        if let Some(probe) = probe {
+            state
+                .get_probe(&probe)
+                .map_err(|e| format!("Setting renderer object probe, msg = {e}"))?;
+        }
+
+        Ok(())
+    }
+
+    // the renderer objects are drawn without shaders, so the uniforms do not change them
+    fn set_renderer_object_uniform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        _name: String,
+        _value: Option<RendererUniformValue>,
+    ) -> Result<(), String> {
+        let state = self.state.read();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting renderer object uniform, msg = {e}"))?;
+
+        match state.renderer_objects.get_ref(renderer_object) {
+            Some(HeadlessRendererObject::Mesh { .. } | HeadlessRendererObject::Skybox) => {
+                Ok(())
+            }
+            _ => Err(
+                "Setting renderer object uniform, msg = the renderer object is not drawn with a mesh shader"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn set_particle_emitter_settings(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        _settings: ParticleEmitterSettings,
+    ) -> Result<(), String> {
+        let state = self.state.read();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting particle emitter settings, msg = {e}"))?;
+
+        match state.renderer_objects.get_ref(renderer_object) {
+            Some(HeadlessRendererObject::ParticleEmitter) => Ok(()),
+            _ => Err(
+                "Setting particle emitter settings, msg = the renderer object is not a particle emitter"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn set_sprite_batch_sprites(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        new_sprites: Vec<RendererSprite>,
+    ) -> Result<(), String> {
+        let mut state = self.state.write();
+
+        let renderer_object = state
+            .get_renderer_object(&renderer_object)
+            .map_err(|e| format!("Setting sprite batch sprites, msg = {e}"))?;
+
+        match state.renderer_objects.get_mut(renderer_object) {
+            Some(HeadlessRendererObject::SpriteBatch { sprites, .. }) => {
+                *sprites = new_sprites;
+                Ok(())
+            }
+            _ => Err(
+                "Setting sprite batch sprites, msg = the renderer object is not a sprite batch"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn create_camera(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererCamera>, String> {
+        let mut state = self.state.write();
+
+        let transform = state
+            .get_transform(&transform)
+            .map_err(|e| format!("Creating camera, msg = {e}"))?;
+        let index = state.cameras.create_object(HeadlessCamera {
+            transform,
+            parameters: None,
+        });
+
+        Ok(arc_rw_lock_new(HeadlessCameraIndex(index)))
+    }
+
+    fn update_camera_parameters(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+        parameters: Option<RendererCameraParameters>,
+    ) -> Result<(), String> {
+        let index = get_camera_index(&camera)
+            .map_err(|e| format!("Updating camera parameters, msg = {e}"))?;
+
+        self.state
+            .write()
+            .cameras
+            .get_mut(index)
+            .ok_or_else(|| {
+                "Updating camera parameters, msg = could not find RendererCamera".to_string()
+            })?
+            .parameters = parameters;
+
+        Ok(())
+    }
+
+    fn release_camera(&mut self, camera: ArcRwLock<dyn RendererCamera>) -> Result<(), String> {
+        let index =
+            get_camera_index(&camera).map_err(|e| format!("Releasing camera, msg = {e}"))?;
+
+        self.state
+            .write()
+            .cameras
+            .release_object(index)
+            .ok_or_else(|| "Releasing camera, msg = could not find RendererCamera".to_string())?;
+
+        Ok(())
+    }
+
+    fn create_probe(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        _resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<ArcRwLock<dyn RendererProbe>, String> {
+        let mut state = self.state.write();
+
+        state
+            .get_transform(&transform)
+            .map_err(|e| format!("Creating probe, msg = {e}"))?;
+        state
+            .get_renderer_layer(&renderer_layer)
+            .map_err(|e| format!("Creating probe, msg = {e}"))?;
+
+        let index = state.probes.create_object(refresh_mode);
+
+        Ok(arc_rw_lock_new(HeadlessProbeIndex(index)))
+    }
+
+    fn refresh_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        self.state
+            .read()
+            .get_probe(&probe)
+            .map_err(|e| format!("Refreshing probe, msg = {e}"))?;
+
+        Ok(())
+    }
+
+    fn set_probe_refresh_mode(
+        &mut self,
+        probe: ArcRwLock<dyn RendererProbe>,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<(), String> {
+        let index = get_probe_index(&probe)
+            .map_err(|e| format!("Setting probe refresh mode, msg = {e}"))?;
+
+        *self.state.write().probes.get_mut(index).ok_or_else(|| {
+            "Setting probe refresh mode, msg = could not find RendererProbe".to_string()
+        })? = refresh_mode;
+
+        Ok(())
+    }
+
+    fn release_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        let index = get_probe_index(&probe).map_err(|e| format!("Releasing probe, msg = {e}"))?;
+
+        self.state
+            .write()
+            .probes
+            .release_object(index)
+            .ok_or_else(|| "Releasing probe, msg = could not find RendererProbe".to_string())?;
+
+        Ok(())
+    }
+
+    fn stats(&self) -> RendererStats {
+        let state = self.state.read();
+
+        RendererStats {
+            number_of_textures: state.textures.len(),
+            texture_memory_bytes: state.texture_memory_bytes,
+            texture_memory_budget_bytes: state.texture_memory_budget_bytes,
+
+            draw_calls: state.draw_stats.draw_calls,
+            triangles: state.draw_stats.triangles,
+
+            number_of_meshes: state.meshes.len(),
+
+            ..Default::default()
+        }
+    }
+
+    // there is no texture memory to evict from, the budget is only reported in the stats
+    fn set_texture_memory_budget(&mut self, budget_bytes: Option<usize>) -> Result<(), String> {
+        self.state.write().texture_memory_budget_bytes = budget_bytes;
+        Ok(())
+    }
+
+    fn set_debug_render_mode(&mut self, _mode: RendererDebugRenderMode) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn add_debug_lines(
+        &mut self,
+        _lines: Vec<RendererDebugLine>,
+        _lifetime_secs: f32,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn reload_shaders(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn pick_renderer_object(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        screen_position: Vec2<f32>,
+        renderer_objects: Vec<ArcRwLock<dyn RendererObject>>,
+    ) -> Result<Option<usize>, String> {
+        let state = self.state.read();
+
+        let layer_index = state
+            .get_renderer_layer(&renderer_layer)
+            .map_err(|e| format!("Picking renderer object, msg = {e}"))?;
+
+        let window_dimensions = state.framebuffer.dimensions;
+        if screen_position.x < 0.0
+            || screen_position.y < 0.0
+            || screen_position.x >= window_dimensions.x as f32
+            || screen_position.y >= window_dimensions.y as f32
+        {
+            return Ok(None);
+        }
+        let pixel = screen_position.as_::<usize>();
+
+        // the first draw step of the layer, whose viewport contains the position, is used
+        let step = state.pipeline_steps.iter().find_map(|step| match step {
+            RendererPipelineStepImpl::Draw {
+                renderer_layer,
+                viewport_start_ndc,
+                viewport_end_ndc,
+                compute_projection_matrix,
+            } if get_renderer_layer_index(renderer_layer) == Ok(layer_index) => {
+                let viewport =
+                    viewport_ssc(window_dimensions, *viewport_start_ndc, *viewport_end_ndc);
+                viewport
+                    .contains_point(pixel)
+                    .then(|| (viewport, compute_projection_matrix.clone()))
+            }
+            _ => None,
+        });
+        let Some((viewport, compute_projection_matrix)) = step else {
+            return Ok(None);
+        };
+
+        let Some(layer) = state.renderer_layers.get_ref(layer_index) else {
+            return Ok(None);
+        };
+        let (view_projection_matrix, eye_position) = state
+            .layer_view_projection(layer, viewport, compute_projection_matrix.as_ref())
+            .map_err(|e| format!("Picking renderer object, msg = {e}"))?;
+
+        // only the picked pixel is rasterized
+        let scissor = Rect::new(pixel.x, pixel.y, 1, 1);
+        let mut nearest = None;
+        let mut nearest_depth = 1.0;
+        let mut triangles = Vec::new();
+        for (candidate_index, renderer_object) in renderer_objects.iter().enumerate() {
+            let renderer_object = state
+                .get_renderer_object(renderer_object)
+                .map_err(|e| format!("Picking renderer object, msg = {e}"))?;
+
+            triangles.clear();
+            state.collect_triangles(renderer_object, eye_position, &mut triangles);
+
+            for triangle in triangles.iter() {
+                let Some(triangle_ndc) =
+                    project_triangle(&view_projection_matrix, &triangle.positions)
+                else {
+                    continue;
+                };
+
+                for_each_covered_pixel(triangle_ndc, viewport, scissor, |_, depth| {
+                    if (0.0..=1.0).contains(&depth) && depth < nearest_depth {
+                        nearest_depth = depth;
+                        nearest = Some(candidate_index);
+                    }
+                });
+            }
+        }
+
+        Ok(nearest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use vek::{Mat4, Transform, Vec2, Vec3, Vec4};
+
+    use crate::{
+        mesh::Material,
+        mesh_creator::rectangle2d,
+        renderer::{
+            renderer_impl::RendererImpl,
+            renderer_layer_settings::RendererLayerSettings,
+            renderer_layer_stencil::{RendererLayerStencil, RendererStencilFunction},
+            renderer_pipeline_step_impl::RendererPipelineStepImpl,
+        },
+    };
+
+    use super::HeadlessRenderer;
+
+    #[test]
+    fn the_mesh_in_front_of_the_camera_is_drawn_with_its_albedo_color() {
+        let mut renderer = HeadlessRenderer::new(Vec2::new(64, 64));
+
+        let camera_transform = renderer
+            .create_transform(Transform {
+                position: Vec3::new(0.0, 0.0, 5.0),
+                ..Default::default()
+            })
+            .unwrap();
+        let camera = renderer.create_camera(camera_transform).unwrap();
+        let renderer_layer = renderer.create_renderer_layer(camera).unwrap();
+        let renderer_group = renderer.create_renderer_group().unwrap();
+        renderer
+            .add_renderer_group_to_layer(renderer_group.clone(), renderer_layer.clone())
+            .unwrap();
+
+        let mut material = Material::new();
+        material.albedo_color = Vec3::new(1.0, 0.0, 0.0);
+        let mesh = renderer
+            .create_mesh(Arc::new(rectangle2d::create(2.0, 2.0)))
+            .unwrap();
+        let shader = renderer.create_shader("unlit".to_string()).unwrap();
+        let material = renderer.create_material(material).unwrap();
+        let transform = renderer.create_transform(Transform::default()).unwrap();
+        let renderer_object = renderer
+            .create_renderer_object_from_mesh(mesh, shader, material, transform)
+            .unwrap();
+        renderer
+            .add_renderer_object_to_group(renderer_object.clone(), renderer_group)
+            .unwrap();
+
+        renderer
+            .set_renderer_pipeline(vec![
+                RendererPipelineStepImpl::Clear {
+                    depth: true,
+                    color: true,
+                    stencil: false,
+                    clear_color: Vec4::zero(),
+                    viewport_start_ndc: Vec2::zero(),
+                    viewport_end_ndc: Vec2::one(),
+                },
+                RendererPipelineStepImpl::Draw {
+                    renderer_layer: renderer_layer.clone(),
+                    viewport_start_ndc: Vec2::zero(),
+                    viewport_end_ndc: Vec2::one(),
+                    compute_projection_matrix: Arc::new(|width, height| {
+                        Mat4::perspective_fov_rh_zo(
+                            std::f32::consts::FRAC_PI_2,
+                            width as f32,
+                            height as f32,
+                            0.1,
+                            100.0,
+                        )
+                    }),
+                },
+            ])
+            .unwrap();
+
+        renderer.render();
+
+        // the rectangle of the size 2 is seen from the distance 5 with a 90 degrees field of view,
+        // so it covers about 6 pixels in every direction from the center of the framebuffer
+        let image = renderer.framebuffer_image();
+        assert_eq!(Some((1.0, 0.0, 0.0, 1.0)), image.color_f32_at(32, 32));
+        assert_eq!(Some((1.0, 0.0, 0.0, 1.0)), image.color_f32_at(27, 37));
+        assert_eq!(Some((0.0, 0.0, 0.0, 0.0)), image.color_f32_at(0, 0));
+        assert_eq!(Some((0.0, 0.0, 0.0, 0.0)), image.color_f32_at(32, 45));
+
+        assert_eq!(1, renderer.stats().draw_calls);
+        assert_eq!(2, renderer.stats().triangles);
+
+        assert_eq!(
+            Ok(Some(0)),
+            renderer.pick_renderer_object(
+                renderer_layer.clone(),
+                Vec2::new(32.0, 32.0),
+                vec![renderer_object.clone()]
+            )
+        );
+        assert_eq!(
+            Ok(None),
+            renderer.pick_renderer_object(
+                renderer_layer,
+                Vec2::new(1.0, 1.0),
+                vec![renderer_object]
+            )
+        );
+    }
+
+    #[test]
+    fn the_clear_color_of_a_clear_step_can_be_changed() {
+        let mut renderer = HeadlessRenderer::new(Vec2::new(8, 8));
+
+        renderer
+            .set_renderer_pipeline(vec![RendererPipelineStepImpl::Clear {
+                depth: true,
+                color: true,
+                stencil: false,
+                clear_color: Vec4::new(0.0, 0.0, 1.0, 1.0),
+                viewport_start_ndc: Vec2::zero(),
+                viewport_end_ndc: Vec2::one(),
+            }])
+            .unwrap();
+
+        renderer.render();
+        assert_eq!(
+            Some((0.0, 0.0, 1.0, 1.0)),
+            renderer.framebuffer_image().color_f32_at(4, 4)
+        );
+
+        renderer
+            .set_pipeline_clear_color(0, Vec4::new(1.0, 0.0, 0.0, 1.0))
+            .unwrap();
+        assert!(renderer.set_pipeline_clear_color(1, Vec4::zero()).is_err());
+
+        renderer.render();
+        assert_eq!(
+            Some((1.0, 0.0, 0.0, 1.0)),
+            renderer.framebuffer_image().color_f32_at(4, 4)
+        );
+    }
+
+    #[test]
+    fn the_stencil_of_the_layer_masks_the_drawn_pixels() {
+        let mut renderer = HeadlessRenderer::new(Vec2::new(64, 64));
+
+        let camera_transform = renderer
+            .create_transform(Transform {
+                position: Vec3::new(0.0, 0.0, 5.0),
+                ..Default::default()
+            })
+            .unwrap();
+        let camera = renderer.create_camera(camera_transform).unwrap();
+        let renderer_layer = renderer.create_renderer_layer(camera).unwrap();
+        let renderer_group = renderer.create_renderer_group().unwrap();
+        renderer
+            .add_renderer_group_to_layer(renderer_group.clone(), renderer_layer.clone())
+            .unwrap();
+
+        let mut material = Material::new();
+        material.albedo_color = Vec3::new(1.0, 0.0, 0.0);
+        let mesh = renderer
+            .create_mesh(Arc::new(rectangle2d::create(2.0, 2.0)))
+            .unwrap();
+        let shader = renderer.create_shader("unlit".to_string()).unwrap();
+        let material = renderer.create_material(material).unwrap();
+        let transform = renderer.create_transform(Transform::default()).unwrap();
+        let renderer_object = renderer
+            .create_renderer_object_from_mesh(mesh, shader, material, transform)
+            .unwrap();
+        renderer
+            .add_renderer_object_to_group(renderer_object, renderer_group)
+            .unwrap();
+
+        let pipeline = |clear_stencil| {
+            vec![
+                RendererPipelineStepImpl::Clear {
+                    depth: true,
+                    color: true,
+                    stencil: clear_stencil,
+                    clear_color: Vec4::zero(),
+                    viewport_start_ndc: Vec2::zero(),
+                    viewport_end_ndc: Vec2::one(),
+                },
+                RendererPipelineStepImpl::Draw {
+                    renderer_layer: renderer_layer.clone(),
+                    viewport_start_ndc: Vec2::zero(),
+                    viewport_end_ndc: Vec2::one(),
+                    compute_projection_matrix: Arc::new(|width, height| {
+                        Mat4::perspective_fov_rh_zo(
+                            std::f32::consts::FRAC_PI_2,
+                            width as f32,
+                            height as f32,
+                            0.1,
+                            100.0,
+                        )
+                    }),
+                },
+            ]
+        };
+        let stencil_test = RendererLayerSettings {
+            stencil: Some(RendererLayerStencil::test(
+                RendererStencilFunction::Equal,
+                1,
+            )),
+            ..Default::default()
+        };
+        let stencil_write = RendererLayerSettings {
+            stencil: Some(RendererLayerStencil::write(1)),
+            ..Default::default()
+        };
+
+        renderer.set_renderer_pipeline(pipeline(false)).unwrap();
+
+        // the stencil buffer is zero, so nothing passes the test
+        renderer
+            .set_renderer_layer_settings(renderer_layer.clone(), stencil_test)
+            .unwrap();
+        renderer.render();
+        assert_eq!(
+            Some((0.0, 0.0, 0.0, 0.0)),
+            renderer.framebuffer_image().color_f32_at(32, 32)
+        );
+
+        renderer
+            .set_renderer_layer_settings(renderer_layer.clone(), stencil_write)
+            .unwrap();
+        renderer.render();
+
+        // the written stencil is kept by the clear step that does not clear it
+        renderer
+            .set_renderer_layer_settings(renderer_layer.clone(), stencil_test)
+            .unwrap();
+        renderer.render();
+        assert_eq!(
+            Some((1.0, 0.0, 0.0, 1.0)),
+            renderer.framebuffer_image().color_f32_at(32, 32)
+        );
+
+        renderer.set_renderer_pipeline(pipeline(true)).unwrap();
+        renderer.render();
+        assert_eq!(
+            Some((0.0, 0.0, 0.0, 0.0)),
+            renderer.framebuffer_image().color_f32_at(32, 32)
+        );
+    }
+
+    #[test]
+    fn the_nearest_object_is_visible_with_reverse_z() {
+        let mut renderer = HeadlessRenderer::new(Vec2::new(64, 64));
+
+        let camera_transform = renderer
+            .create_transform(Transform {
+                position: Vec3::new(0.0, 0.0, 5.0),
+                ..Default::default()
+            })
+            .unwrap();
+        let camera = renderer.create_camera(camera_transform).unwrap();
+        let renderer_layer = renderer.create_renderer_layer(camera).unwrap();
+        let renderer_group = renderer.create_renderer_group().unwrap();
+        renderer
+            .add_renderer_group_to_layer(renderer_group.clone(), renderer_layer.clone())
+            .unwrap();
+
+        let mesh = renderer
+            .create_mesh(Arc::new(rectangle2d::create(2.0, 2.0)))
+            .unwrap();
+        let shader = renderer.create_shader("unlit".to_string()).unwrap();
+
+        // the farther object is drawn last, so it is only hidden if the depth test works
+        for (albedo_color, z) in [
+            (Vec3::new(1.0, 0.0, 0.0), 0.0),
+            (Vec3::new(0.0, 0.0, 1.0), -1.0),
+        ] {
+            let mut material = Material::new();
+            material.albedo_color = albedo_color;
+            let material = renderer.create_material(material).unwrap();
+            let transform = renderer
+                .create_transform(Transform {
+                    position: Vec3::new(0.0, 0.0, z),
+                    ..Default::default()
+                })
+                .unwrap();
+            let renderer_object = renderer
+                .create_renderer_object_from_mesh(mesh.clone(), shader.clone(), material, transform)
+                .unwrap();
+            renderer
+                .add_renderer_object_to_group(renderer_object, renderer_group.clone())
+                .unwrap();
+        }
+
+        renderer
+            .set_renderer_pipeline(vec![RendererPipelineStepImpl::Draw {
+                renderer_layer: renderer_layer.clone(),
+                viewport_start_ndc: Vec2::zero(),
+                viewport_end_ndc: Vec2::one(),
+                compute_projection_matrix: Arc::new(|width, height| {
+                    Mat4::perspective_fov_rh_zo(
+                        std::f32::consts::FRAC_PI_2,
+                        width as f32,
+                        height as f32,
+                        0.01,
+                        1000.0,
+                    )
+                }),
+            }])
+            .unwrap();
+        renderer
+            .set_renderer_layer_settings(
+                renderer_layer,
+                RendererLayerSettings {
+                    clear_color: Some(Vec4::zero()),
+                    clear_depth: true,
+                    reverse_z: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        renderer.render();
+        assert_eq!(
+            Some((1.0, 0.0, 0.0, 1.0)),
+            renderer.framebuffer_image().color_f32_at(32, 32)
+        );
+    }
+
+    #[test]
+    fn the_released_resources_are_not_kept_by_the_renderer() {
+        let mut renderer = HeadlessRenderer::new(Vec2::new(8, 8));
+
+        let mesh = renderer
+            .create_mesh(Arc::new(rectangle2d::create(2.0, 2.0)))
+            .unwrap();
+        let render_texture = renderer.create_render_texture(Vec2::new(4, 4)).unwrap();
+        let shader = renderer.create_shader("unlit".to_string()).unwrap();
+        let material = renderer.create_material(Material::new()).unwrap();
+        let transform = renderer.create_transform(Transform::default()).unwrap();
+        let renderer_object = renderer
+            .create_renderer_object_from_mesh(
+                mesh.clone(),
+                shader.clone(),
+                material.clone(),
+                transform.clone(),
+            )
+            .unwrap();
+
+        renderer.render();
+        let stats = renderer.stats();
+        assert_eq!(1, stats.number_of_meshes);
+        assert_eq!(1, stats.number_of_textures);
+        assert!(stats.texture_memory_bytes > 0);
+
+        renderer.release_renderer_object(renderer_object).unwrap();
+        renderer.release_mesh(mesh).unwrap();
+        renderer.release_render_texture(render_texture).unwrap();
+        renderer.release_material(material).unwrap();
+        renderer.release_shader(shader).unwrap();
+        renderer.release_transform(transform).unwrap();
+
+        renderer.render();
+        let stats = renderer.stats();
+        assert_eq!(0, stats.number_of_meshes);
+        assert_eq!(0, stats.number_of_textures);
+        assert_eq!(0, stats.texture_memory_bytes);
+    }
+}
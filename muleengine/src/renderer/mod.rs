@@ -1,11 +1,33 @@
 #[cfg(test)]
 mod tests;
 
+pub mod headless_renderer;
+pub mod particle_system;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod recording_renderer;
+pub mod renderer_batch;
+pub mod renderer_camera_parameters;
+pub mod renderer_config;
+pub mod renderer_debug_lines;
+pub mod renderer_debug_render_mode;
+pub mod renderer_detached_client;
 pub mod renderer_impl;
+pub mod renderer_interpolated_transform;
+pub mod renderer_layer_projection;
+pub mod renderer_layer_settings;
+pub mod renderer_layer_stencil;
+pub mod renderer_mesh_vertices;
 mod renderer_objects;
+pub mod renderer_pipeline_description;
 pub mod renderer_pipeline_step;
 pub mod renderer_pipeline_step_impl;
+pub mod renderer_probe_refresh_mode;
+pub mod renderer_sprite;
+pub mod renderer_state_dump;
+pub mod renderer_stats;
 pub mod renderer_system;
+pub mod renderer_text;
+pub mod renderer_uniform_value;
 
 pub use renderer_objects::renderer_camera::*;
 pub use renderer_objects::renderer_group::*;
@@ -13,8 +35,15 @@ pub use renderer_objects::renderer_layer::*;
 pub use renderer_objects::renderer_material::*;
 pub use renderer_objects::renderer_mesh::*;
 pub use renderer_objects::renderer_object::*;
+pub use renderer_objects::renderer_probe::*;
 pub use renderer_objects::renderer_shader::*;
+pub use renderer_objects::renderer_texture::*;
 pub use renderer_objects::renderer_transform::*;
+pub use renderer_objects::renderer_window::*;
+
+use vek::Vec2;
+
+use renderer_pipeline_step::InvalidPipelineReason;
 
 #[derive(Debug)]
 pub enum RendererError {
@@ -26,6 +55,18 @@ pub enum RendererError {
     InvalidRendererObjectHandler(RendererObjectHandler),
     InvalidRendererLayerHandler(RendererLayerHandler),
     InvalidRendererGroupHandler(RendererGroupHandler),
+    InvalidRendererProbeHandler(RendererProbeHandler),
+    InvalidRendererTextureHandler(RendererTextureHandler),
+    InvalidRendererWindowHandler(RendererWindowHandler),
+    InvalidRendererBatchSlot(usize),
+    InvalidViewport {
+        viewport_start_ndc: Vec2<f32>,
+        viewport_end_ndc: Vec2<f32>,
+    },
+    InvalidPipeline {
+        step_index: usize,
+        reason: InvalidPipelineReason,
+    },
     RendererImplError(String),
     RendererSystemDropped,
 }
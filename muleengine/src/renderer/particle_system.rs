@@ -0,0 +1,237 @@
+use std::ops::{Add, Mul};
+
+use vek::{Vec3, Vec4};
+
+// the keys are positioned on the normalized lifetime of the particles, from 0.0 to 1.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleCurve<T> {
+    keys: Vec<(f32, T)>,
+}
+
+impl<T> ParticleCurve<T>
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    pub fn new(mut keys: Vec<(f32, T)>) -> Self {
+        keys.sort_by(|(time_a, _), (time_b, _)| time_a.total_cmp(time_b));
+
+        Self { keys }
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self {
+            keys: vec![(0.0, value)],
+        }
+    }
+
+    pub fn linear(start: T, end: T) -> Self {
+        Self {
+            keys: vec![(0.0, start), (1.0, end)],
+        }
+    }
+
+    pub fn keys(&self) -> &[(f32, T)] {
+        &self.keys
+    }
+
+    // the values are clamped to the first and the last keys
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let next_index = self.keys.partition_point(|(key_time, _)| *key_time <= time);
+
+        match (next_index.checked_sub(1), self.keys.get(next_index)) {
+            (Some(previous_index), Some((next_time, next_value))) => {
+                let (previous_time, previous_value) = self.keys[previous_index];
+                let factor = (time - previous_time) / (next_time - previous_time);
+                Some(previous_value * (1.0 - factor) + *next_value * factor)
+            }
+            (Some(previous_index), None) => Some(self.keys[previous_index].1),
+            (None, Some((_, next_value))) => Some(*next_value),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleEmitterSettings {
+    pub spawn_rate: f32,
+    pub lifetime_secs: f32,
+    pub max_particles: usize,
+    // the velocity of every particle differs from the curve by at most this much
+    pub velocity_randomness: f32,
+    pub velocity_curve: ParticleCurve<Vec3<f32>>,
+    pub size_curve: ParticleCurve<f32>,
+    pub color_curve: ParticleCurve<Vec4<f32>>,
+}
+
+impl Default for ParticleEmitterSettings {
+    fn default() -> Self {
+        Self {
+            spawn_rate: 10.0,
+            lifetime_secs: 1.0,
+            max_particles: 1000,
+            velocity_randomness: 0.0,
+            velocity_curve: ParticleCurve::constant(Vec3::unit_y()),
+            size_curve: ParticleCurve::constant(0.1),
+            color_curve: ParticleCurve::constant(Vec4::one()),
+        }
+    }
+}
+
+// the positions are in the space of the emitter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub position: Vec3<f32>,
+    pub size: f32,
+    pub color: Vec4<f32>,
+    pub age_secs: f32,
+    random_velocity: Vec3<f32>,
+}
+
+pub struct ParticleEmitter {
+    settings: ParticleEmitterSettings,
+    particles: Vec<Particle>,
+    unspawned_particles: f32,
+    random_state: u32,
+}
+
+impl ParticleEmitter {
+    pub fn new(settings: ParticleEmitterSettings) -> Self {
+        Self {
+            settings,
+            particles: Vec::new(),
+            unspawned_particles: 0.0,
+            random_state: 0x9e37_79b9,
+        }
+    }
+
+    pub fn settings(&self) -> &ParticleEmitterSettings {
+        &self.settings
+    }
+
+    // the living particles keep their age, so changing the settings does not restart the emitter
+    pub fn set_settings(&mut self, settings: ParticleEmitterSettings) {
+        self.settings = settings;
+        self.particles.truncate(self.settings.max_particles);
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn update(&mut self, delta_secs: f32) {
+        let lifetime_secs = self.settings.lifetime_secs;
+        self.particles.retain_mut(|particle| {
+            particle.age_secs += delta_secs;
+            particle.age_secs < lifetime_secs
+        });
+
+        for particle in self.particles.iter_mut() {
+            let time = particle.age_secs / lifetime_secs;
+
+            let velocity = self
+                .settings
+                .velocity_curve
+                .sample(time)
+                .unwrap_or_else(Vec3::zero)
+                + particle.random_velocity;
+            particle.position += velocity * delta_secs;
+
+            Self::apply_curves(&self.settings, particle);
+        }
+
+        self.unspawned_particles += self.settings.spawn_rate.max(0.0) * delta_secs;
+        while self.unspawned_particles >= 1.0 {
+            self.unspawned_particles -= 1.0;
+
+            if self.particles.len() < self.settings.max_particles && lifetime_secs > 0.0 {
+                let random_velocity =
+                    self.next_random_direction() * self.settings.velocity_randomness;
+
+                let mut particle = Particle {
+                    position: Vec3::zero(),
+                    size: 0.0,
+                    color: Vec4::zero(),
+                    age_secs: 0.0,
+                    random_velocity,
+                };
+                Self::apply_curves(&self.settings, &mut particle);
+                self.particles.push(particle);
+            }
+        }
+    }
+
+    fn apply_curves(settings: &ParticleEmitterSettings, particle: &mut Particle) {
+        let time = particle.age_secs / settings.lifetime_secs;
+
+        particle.size = settings.size_curve.sample(time).unwrap_or(0.0);
+        particle.color = settings.color_curve.sample(time).unwrap_or_else(Vec4::one);
+    }
+
+    // xorshift is enough to scatter the particles, and it keeps the emitters deterministic
+    fn next_random(&mut self) -> f32 {
+        self.random_state ^= self.random_state << 13;
+        self.random_state ^= self.random_state >> 17;
+        self.random_state ^= self.random_state << 5;
+
+        (self.random_state as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    fn next_random_direction(&mut self) -> Vec3<f32> {
+        let direction = Vec3::new(self.next_random(), self.next_random(), self.next_random());
+        direction.try_normalized().unwrap_or_else(Vec3::unit_y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vek::Vec3;
+
+    use super::{ParticleCurve, ParticleEmitter, ParticleEmitterSettings};
+
+    #[test]
+    fn curve_interpolates_between_the_keys_and_clamps_outside_of_them() {
+        let curve = ParticleCurve::new(vec![(1.0, 0.0), (0.0, 2.0), (0.5, 1.0)]);
+
+        assert_eq!(Some(2.0), curve.sample(-1.0));
+        assert_eq!(Some(1.5), curve.sample(0.25));
+        assert_eq!(Some(1.0), curve.sample(0.5));
+        assert_eq!(Some(0.5), curve.sample(0.75));
+        assert_eq!(Some(0.0), curve.sample(2.0));
+
+        assert_eq!(None, ParticleCurve::<f32>::new(Vec::new()).sample(0.5));
+    }
+
+    #[test]
+    fn emitter_spawns_particles_at_its_rate_and_removes_them_after_their_lifetime() {
+        let mut emitter = ParticleEmitter::new(ParticleEmitterSettings {
+            spawn_rate: 10.0,
+            lifetime_secs: 1.0,
+            max_particles: 100,
+            velocity_randomness: 0.0,
+            velocity_curve: ParticleCurve::constant(Vec3::unit_y()),
+            size_curve: ParticleCurve::linear(1.0, 0.0),
+            ..Default::default()
+        });
+
+        for _ in 0..5 {
+            emitter.update(0.1);
+        }
+        assert_eq!(5, emitter.particles().len());
+
+        let oldest_particle = emitter.particles()[0];
+        assert!((oldest_particle.position.y - 0.4).abs() < 0.0001);
+        assert!((oldest_particle.size - 0.6).abs() < 0.0001);
+
+        for _ in 0..20 {
+            emitter.update(0.1);
+        }
+        // the particles live for ten updates
+        assert!((9..=10).contains(&emitter.particles().len()));
+
+        emitter.set_settings(ParticleEmitterSettings {
+            max_particles: 3,
+            ..emitter.settings().clone()
+        });
+        assert_eq!(3, emitter.particles().len());
+    }
+}
@@ -0,0 +1,1832 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    sync::Arc,
+    time::Instant,
+};
+
+use bytifex_utils::sync::types::{arc_rw_lock_new, ArcRwLock};
+use vek::{Transform, Vec2, Vec4};
+
+use crate::{
+    image::Image,
+    mesh::{Material, MaterialTextureType, Mesh},
+    test_utils::sendable_ptr::SendablePtr,
+};
+
+use super::{
+    particle_system::ParticleEmitterSettings, renderer_camera_parameters::RendererCameraParameters,
+    renderer_config::RendererConfig, renderer_debug_lines::RendererDebugLine,
+    renderer_debug_render_mode::RendererDebugRenderMode, renderer_impl::RendererImpl,
+    renderer_layer_projection::RendererLayerProjection,
+    renderer_layer_settings::RendererLayerSettings, renderer_mesh_vertices::RendererMeshVertices,
+    renderer_pipeline_step_impl::RendererPipelineStepImpl,
+    renderer_probe_refresh_mode::RendererProbeRefreshMode, renderer_sprite::RendererSprite,
+    renderer_stats::RendererStats, renderer_uniform_value::RendererUniformValue, RendererCamera,
+    RendererGroup, RendererLayer, RendererMaterial, RendererMesh, RendererObject, RendererProbe,
+    RendererShader, RendererTexture, RendererTransform, RendererWindow,
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum RendererCallKind {
+    Render,
+    WindowDimensionsChanged,
+    SetRendererConfig,
+    SetRendererPipeline,
+    SetPipelineClearColor,
+    CreateWindow,
+    SetWindowPipeline,
+    ReleaseWindow,
+    CreateRendererLayer,
+    ReleaseRendererLayer,
+    SetRendererLayerSettings,
+    UpdateRendererLayerProjection,
+    SetRendererLayerOverrideShader,
+    AddRendererGroupToLayer,
+    RemoveRendererGroupFromLayer,
+    CreateRendererGroup,
+    ReleaseRendererGroup,
+    CreateTransform,
+    UpdateTransform,
+    UpdateTransformInterpolated,
+    SetTransformParent,
+    ReleaseTransform,
+    CreateMaterial,
+    UpdateMaterial,
+    ReleaseMaterial,
+    SetMaterialRenderTexture,
+    CreateRenderTexture,
+    ReleaseRenderTexture,
+    CreateTexture,
+    UpdateTexture,
+    CreateCubemapTexture,
+    CreateShader,
+    UpdateShader,
+    ReleaseShader,
+    CreateMesh,
+    UpdateMesh,
+    UpdateMeshVertices,
+    ReleaseMesh,
+    CreateRendererObjectFromMesh,
+    CreateInstancedRendererObject,
+    CreateLodRendererObject,
+    CreateSkyboxRendererObject,
+    CreateParticleEmitterRendererObject,
+    CreateSpriteBatchRendererObject,
+    ReleaseRendererObject,
+    AddRendererObjectToGroup,
+    RemoveRendererObjectFromGroup,
+    SetRendererObjectTransform,
+    SetRendererObjectMaterial,
+    SetRendererObjectShader,
+    SetRendererObjectMesh,
+    SetRendererObjectProbe,
+    SetRendererObjectUniform,
+    SetParticleEmitterSettings,
+    SetSpriteBatchSprites,
+    CreateCamera,
+    UpdateCameraParameters,
+    ReleaseCamera,
+    CreateProbe,
+    RefreshProbe,
+    SetProbeRefreshMode,
+    ReleaseProbe,
+    SetTextureMemoryBudget,
+    SetDebugRenderMode,
+    AddDebugLines,
+    ReloadShaders,
+    PickRendererObject,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum RendererHandleKind {
+    RendererLayer,
+    RendererGroup,
+    Transform,
+    Material,
+    Shader,
+    Mesh,
+    RendererObject,
+    Camera,
+    Probe,
+    Texture,
+    Window,
+}
+
+#[derive(Clone)]
+pub enum RendererCall {
+    Render,
+    WindowDimensionsChanged {
+        width: usize,
+        height: usize,
+    },
+    SetRendererConfig {
+        renderer_config: RendererConfig,
+    },
+    SetRendererPipeline {
+        number_of_steps: usize,
+    },
+    SetPipelineClearColor {
+        step_index: usize,
+        clear_color: Vec4<f32>,
+    },
+    CreateWindow {
+        title: String,
+        dimensions: Vec2<usize>,
+        window: Option<SendablePtr<dyn RendererWindow>>,
+    },
+    SetWindowPipeline {
+        window: SendablePtr<dyn RendererWindow>,
+        number_of_steps: usize,
+    },
+    ReleaseWindow {
+        window: SendablePtr<dyn RendererWindow>,
+    },
+    CreateRendererLayer {
+        camera: SendablePtr<dyn RendererCamera>,
+        renderer_layer: Option<SendablePtr<dyn RendererLayer>>,
+    },
+    ReleaseRendererLayer {
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+    },
+    SetRendererLayerSettings {
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+        settings: RendererLayerSettings,
+    },
+    UpdateRendererLayerProjection {
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+        projection: Option<RendererLayerProjection>,
+    },
+    SetRendererLayerOverrideShader {
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+        shader: Option<SendablePtr<dyn RendererShader>>,
+    },
+    AddRendererGroupToLayer {
+        renderer_group: SendablePtr<dyn RendererGroup>,
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+    },
+    RemoveRendererGroupFromLayer {
+        renderer_group: SendablePtr<dyn RendererGroup>,
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+    },
+    CreateRendererGroup {
+        renderer_group: Option<SendablePtr<dyn RendererGroup>>,
+    },
+    ReleaseRendererGroup {
+        renderer_group: SendablePtr<dyn RendererGroup>,
+    },
+    CreateTransform {
+        transform: Transform<f32, f32, f32>,
+        renderer_transform: Option<SendablePtr<dyn RendererTransform>>,
+    },
+    UpdateTransform {
+        renderer_transform: SendablePtr<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+    },
+    UpdateTransformInterpolated {
+        renderer_transform: SendablePtr<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+        timestamp: Instant,
+    },
+    SetTransformParent {
+        renderer_transform: SendablePtr<dyn RendererTransform>,
+        parent_transform: Option<SendablePtr<dyn RendererTransform>>,
+    },
+    ReleaseTransform {
+        renderer_transform: SendablePtr<dyn RendererTransform>,
+    },
+    CreateMaterial {
+        material: Material,
+        renderer_material: Option<SendablePtr<dyn RendererMaterial>>,
+    },
+    UpdateMaterial {
+        renderer_material: SendablePtr<dyn RendererMaterial>,
+        new_material: Material,
+    },
+    ReleaseMaterial {
+        renderer_material: SendablePtr<dyn RendererMaterial>,
+    },
+    SetMaterialRenderTexture {
+        renderer_material: SendablePtr<dyn RendererMaterial>,
+        texture_type: MaterialTextureType,
+        renderer_texture: SendablePtr<dyn RendererTexture>,
+    },
+    CreateRenderTexture {
+        dimensions: Vec2<usize>,
+        renderer_texture: Option<SendablePtr<dyn RendererTexture>>,
+    },
+    ReleaseRenderTexture {
+        renderer_texture: SendablePtr<dyn RendererTexture>,
+    },
+    CreateTexture {
+        image: Arc<Image>,
+        renderer_texture: Option<SendablePtr<dyn RendererTexture>>,
+    },
+    UpdateTexture {
+        renderer_texture: SendablePtr<dyn RendererTexture>,
+        image: Arc<Image>,
+    },
+    CreateCubemapTexture {
+        faces: [Arc<Image>; 6],
+        renderer_texture: Option<SendablePtr<dyn RendererTexture>>,
+    },
+    CreateShader {
+        shader_name: String,
+        renderer_shader: Option<SendablePtr<dyn RendererShader>>,
+    },
+    UpdateShader {
+        renderer_shader: SendablePtr<dyn RendererShader>,
+        new_shader_name: String,
+    },
+    ReleaseShader {
+        renderer_shader: SendablePtr<dyn RendererShader>,
+    },
+    CreateMesh {
+        mesh: Arc<Mesh>,
+        renderer_mesh: Option<SendablePtr<dyn RendererMesh>>,
+    },
+    UpdateMesh {
+        renderer_mesh: SendablePtr<dyn RendererMesh>,
+        new_mesh: Arc<Mesh>,
+    },
+    UpdateMeshVertices {
+        renderer_mesh: SendablePtr<dyn RendererMesh>,
+        vertex_range: Range<usize>,
+        vertices: RendererMeshVertices,
+    },
+    ReleaseMesh {
+        renderer_mesh: SendablePtr<dyn RendererMesh>,
+    },
+    CreateRendererObjectFromMesh {
+        mesh: SendablePtr<dyn RendererMesh>,
+        shader: SendablePtr<dyn RendererShader>,
+        material: SendablePtr<dyn RendererMaterial>,
+        transform: SendablePtr<dyn RendererTransform>,
+        renderer_object: Option<SendablePtr<dyn RendererObject>>,
+    },
+    CreateInstancedRendererObject {
+        mesh: SendablePtr<dyn RendererMesh>,
+        shader: SendablePtr<dyn RendererShader>,
+        material: SendablePtr<dyn RendererMaterial>,
+        transforms: Vec<SendablePtr<dyn RendererTransform>>,
+        renderer_object: Option<SendablePtr<dyn RendererObject>>,
+    },
+    CreateLodRendererObject {
+        lod_meshes: Vec<(f32, SendablePtr<dyn RendererMesh>)>,
+        shader: SendablePtr<dyn RendererShader>,
+        material: SendablePtr<dyn RendererMaterial>,
+        transform: SendablePtr<dyn RendererTransform>,
+        renderer_object: Option<SendablePtr<dyn RendererObject>>,
+    },
+    CreateSkyboxRendererObject {
+        cubemap: SendablePtr<dyn RendererTexture>,
+        renderer_object: Option<SendablePtr<dyn RendererObject>>,
+    },
+    CreateParticleEmitterRendererObject {
+        settings: ParticleEmitterSettings,
+        material: SendablePtr<dyn RendererMaterial>,
+        transform: SendablePtr<dyn RendererTransform>,
+        renderer_object: Option<SendablePtr<dyn RendererObject>>,
+    },
+    CreateSpriteBatchRendererObject {
+        texture: SendablePtr<dyn RendererTexture>,
+        sprites: Vec<RendererSprite>,
+        transform: SendablePtr<dyn RendererTransform>,
+        renderer_object: Option<SendablePtr<dyn RendererObject>>,
+    },
+    ReleaseRendererObject {
+        renderer_object: SendablePtr<dyn RendererObject>,
+    },
+    AddRendererObjectToGroup {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        renderer_group: SendablePtr<dyn RendererGroup>,
+    },
+    RemoveRendererObjectFromGroup {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        renderer_group: SendablePtr<dyn RendererGroup>,
+    },
+    SetRendererObjectTransform {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        transform: SendablePtr<dyn RendererTransform>,
+    },
+    SetRendererObjectMaterial {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        material: SendablePtr<dyn RendererMaterial>,
+    },
+    SetRendererObjectShader {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        shader: SendablePtr<dyn RendererShader>,
+    },
+    SetRendererObjectMesh {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        mesh: SendablePtr<dyn RendererMesh>,
+    },
+    SetRendererObjectProbe {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        probe: Option<SendablePtr<dyn RendererProbe>>,
+    },
+    SetRendererObjectUniform {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        name: String,
+        value: Option<RendererUniformValue>,
+    },
+    SetParticleEmitterSettings {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        settings: ParticleEmitterSettings,
+    },
+    SetSpriteBatchSprites {
+        renderer_object: SendablePtr<dyn RendererObject>,
+        sprites: Vec<RendererSprite>,
+    },
+    CreateCamera {
+        transform: SendablePtr<dyn RendererTransform>,
+        camera: Option<SendablePtr<dyn RendererCamera>>,
+    },
+    UpdateCameraParameters {
+        camera: SendablePtr<dyn RendererCamera>,
+        parameters: Option<RendererCameraParameters>,
+    },
+    ReleaseCamera {
+        camera: SendablePtr<dyn RendererCamera>,
+    },
+    CreateProbe {
+        transform: SendablePtr<dyn RendererTransform>,
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+        resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+        probe: Option<SendablePtr<dyn RendererProbe>>,
+    },
+    RefreshProbe {
+        probe: SendablePtr<dyn RendererProbe>,
+    },
+    SetProbeRefreshMode {
+        probe: SendablePtr<dyn RendererProbe>,
+        refresh_mode: RendererProbeRefreshMode,
+    },
+    ReleaseProbe {
+        probe: SendablePtr<dyn RendererProbe>,
+    },
+    SetTextureMemoryBudget {
+        budget_bytes: Option<usize>,
+    },
+    SetDebugRenderMode {
+        mode: RendererDebugRenderMode,
+    },
+    AddDebugLines {
+        lines: Vec<RendererDebugLine>,
+        lifetime_secs: f32,
+    },
+    ReloadShaders,
+    PickRendererObject {
+        renderer_layer: SendablePtr<dyn RendererLayer>,
+        screen_position: Vec2<f32>,
+        renderer_objects: Vec<SendablePtr<dyn RendererObject>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct RecordedRendererCall {
+    pub call: RendererCall,
+    pub succeeded: bool,
+}
+
+type RecordedHandle = (RendererHandleKind, *const ());
+
+impl RendererCall {
+    pub fn kind(&self) -> RendererCallKind {
+        match self {
+            Self::Render => RendererCallKind::Render,
+            Self::WindowDimensionsChanged { .. } => RendererCallKind::WindowDimensionsChanged,
+            Self::SetRendererConfig { .. } => RendererCallKind::SetRendererConfig,
+            Self::SetRendererPipeline { .. } => RendererCallKind::SetRendererPipeline,
+            Self::SetPipelineClearColor { .. } => RendererCallKind::SetPipelineClearColor,
+            Self::CreateWindow { .. } => RendererCallKind::CreateWindow,
+            Self::SetWindowPipeline { .. } => RendererCallKind::SetWindowPipeline,
+            Self::ReleaseWindow { .. } => RendererCallKind::ReleaseWindow,
+            Self::CreateRendererLayer { .. } => RendererCallKind::CreateRendererLayer,
+            Self::ReleaseRendererLayer { .. } => RendererCallKind::ReleaseRendererLayer,
+            Self::SetRendererLayerSettings { .. } => RendererCallKind::SetRendererLayerSettings,
+            Self::UpdateRendererLayerProjection { .. } => {
+                RendererCallKind::UpdateRendererLayerProjection
+            }
+            Self::SetRendererLayerOverrideShader { .. } => {
+                RendererCallKind::SetRendererLayerOverrideShader
+            }
+            Self::AddRendererGroupToLayer { .. } => RendererCallKind::AddRendererGroupToLayer,
+            Self::RemoveRendererGroupFromLayer { .. } => {
+                RendererCallKind::RemoveRendererGroupFromLayer
+            }
+            Self::CreateRendererGroup { .. } => RendererCallKind::CreateRendererGroup,
+            Self::ReleaseRendererGroup { .. } => RendererCallKind::ReleaseRendererGroup,
+            Self::CreateTransform { .. } => RendererCallKind::CreateTransform,
+            Self::UpdateTransform { .. } => RendererCallKind::UpdateTransform,
+            Self::UpdateTransformInterpolated { .. } => {
+                RendererCallKind::UpdateTransformInterpolated
+            }
+            Self::SetTransformParent { .. } => RendererCallKind::SetTransformParent,
+            Self::ReleaseTransform { .. } => RendererCallKind::ReleaseTransform,
+            Self::CreateMaterial { .. } => RendererCallKind::CreateMaterial,
+            Self::UpdateMaterial { .. } => RendererCallKind::UpdateMaterial,
+            Self::ReleaseMaterial { .. } => RendererCallKind::ReleaseMaterial,
+            Self::SetMaterialRenderTexture { .. } => RendererCallKind::SetMaterialRenderTexture,
+            Self::CreateRenderTexture { .. } => RendererCallKind::CreateRenderTexture,
+            Self::ReleaseRenderTexture { .. } => RendererCallKind::ReleaseRenderTexture,
+            Self::CreateTexture { .. } => RendererCallKind::CreateTexture,
+            Self::UpdateTexture { .. } => RendererCallKind::UpdateTexture,
+            Self::CreateCubemapTexture { .. } => RendererCallKind::CreateCubemapTexture,
+            Self::CreateShader { .. } => RendererCallKind::CreateShader,
+            Self::UpdateShader { .. } => RendererCallKind::UpdateShader,
+            Self::ReleaseShader { .. } => RendererCallKind::ReleaseShader,
+            Self::CreateMesh { .. } => RendererCallKind::CreateMesh,
+            Self::UpdateMesh { .. } => RendererCallKind::UpdateMesh,
+            Self::UpdateMeshVertices { .. } => RendererCallKind::UpdateMeshVertices,
+            Self::ReleaseMesh { .. } => RendererCallKind::ReleaseMesh,
+            Self::CreateRendererObjectFromMesh { .. } => {
+                RendererCallKind::CreateRendererObjectFromMesh
+            }
+            Self::CreateInstancedRendererObject { .. } => {
+                RendererCallKind::CreateInstancedRendererObject
+            }
+            Self::CreateLodRendererObject { .. } => RendererCallKind::CreateLodRendererObject,
+            Self::CreateSkyboxRendererObject { .. } => RendererCallKind::CreateSkyboxRendererObject,
+            Self::CreateParticleEmitterRendererObject { .. } => {
+                RendererCallKind::CreateParticleEmitterRendererObject
+            }
+            Self::CreateSpriteBatchRendererObject { .. } => {
+                RendererCallKind::CreateSpriteBatchRendererObject
+            }
+            Self::ReleaseRendererObject { .. } => RendererCallKind::ReleaseRendererObject,
+            Self::AddRendererObjectToGroup { .. } => RendererCallKind::AddRendererObjectToGroup,
+            Self::RemoveRendererObjectFromGroup { .. } => {
+                RendererCallKind::RemoveRendererObjectFromGroup
+            }
+            Self::SetRendererObjectTransform { .. } => RendererCallKind::SetRendererObjectTransform,
+            Self::SetRendererObjectMaterial { .. } => RendererCallKind::SetRendererObjectMaterial,
+            Self::SetRendererObjectShader { .. } => RendererCallKind::SetRendererObjectShader,
+            Self::SetRendererObjectMesh { .. } => RendererCallKind::SetRendererObjectMesh,
+            Self::SetRendererObjectProbe { .. } => RendererCallKind::SetRendererObjectProbe,
+            Self::SetRendererObjectUniform { .. } => RendererCallKind::SetRendererObjectUniform,
+            Self::SetParticleEmitterSettings { .. } => RendererCallKind::SetParticleEmitterSettings,
+            Self::SetSpriteBatchSprites { .. } => RendererCallKind::SetSpriteBatchSprites,
+            Self::CreateCamera { .. } => RendererCallKind::CreateCamera,
+            Self::UpdateCameraParameters { .. } => RendererCallKind::UpdateCameraParameters,
+            Self::ReleaseCamera { .. } => RendererCallKind::ReleaseCamera,
+            Self::CreateProbe { .. } => RendererCallKind::CreateProbe,
+            Self::RefreshProbe { .. } => RendererCallKind::RefreshProbe,
+            Self::SetProbeRefreshMode { .. } => RendererCallKind::SetProbeRefreshMode,
+            Self::ReleaseProbe { .. } => RendererCallKind::ReleaseProbe,
+            Self::SetTextureMemoryBudget { .. } => RendererCallKind::SetTextureMemoryBudget,
+            Self::SetDebugRenderMode { .. } => RendererCallKind::SetDebugRenderMode,
+            Self::AddDebugLines { .. } => RendererCallKind::AddDebugLines,
+            Self::ReloadShaders => RendererCallKind::ReloadShaders,
+            Self::PickRendererObject { .. } => RendererCallKind::PickRendererObject,
+        }
+    }
+
+    fn created_handle(&self) -> Option<RecordedHandle> {
+        match self {
+            Self::CreateRendererLayer { renderer_layer, .. } => {
+                renderer_layer.map(|ptr| (RendererHandleKind::RendererLayer, ptr.as_ptr()))
+            }
+            Self::CreateRendererGroup { renderer_group } => {
+                renderer_group.map(|ptr| (RendererHandleKind::RendererGroup, ptr.as_ptr()))
+            }
+            Self::CreateTransform {
+                renderer_transform, ..
+            } => renderer_transform.map(|ptr| (RendererHandleKind::Transform, ptr.as_ptr())),
+            Self::CreateMaterial {
+                renderer_material, ..
+            } => renderer_material.map(|ptr| (RendererHandleKind::Material, ptr.as_ptr())),
+            Self::CreateShader {
+                renderer_shader, ..
+            } => renderer_shader.map(|ptr| (RendererHandleKind::Shader, ptr.as_ptr())),
+            Self::CreateMesh { renderer_mesh, .. } => {
+                renderer_mesh.map(|ptr| (RendererHandleKind::Mesh, ptr.as_ptr()))
+            }
+            Self::CreateRendererObjectFromMesh {
+                renderer_object, ..
+            }
+            | Self::CreateInstancedRendererObject {
+                renderer_object, ..
+            }
+            | Self::CreateLodRendererObject {
+                renderer_object, ..
+            }
+            | Self::CreateSkyboxRendererObject {
+                renderer_object, ..
+            }
+            | Self::CreateParticleEmitterRendererObject {
+                renderer_object, ..
+            }
+            | Self::CreateSpriteBatchRendererObject {
+                renderer_object, ..
+            } => renderer_object.map(|ptr| (RendererHandleKind::RendererObject, ptr.as_ptr())),
+            Self::CreateCamera { camera, .. } => {
+                camera.map(|ptr| (RendererHandleKind::Camera, ptr.as_ptr()))
+            }
+            Self::CreateProbe { probe, .. } => {
+                probe.map(|ptr| (RendererHandleKind::Probe, ptr.as_ptr()))
+            }
+            Self::CreateWindow { window, .. } => {
+                window.map(|ptr| (RendererHandleKind::Window, ptr.as_ptr()))
+            }
+            Self::CreateRenderTexture {
+                renderer_texture, ..
+            }
+            | Self::CreateTexture {
+                renderer_texture, ..
+            }
+            | Self::CreateCubemapTexture {
+                renderer_texture, ..
+            } => renderer_texture.map(|ptr| (RendererHandleKind::Texture, ptr.as_ptr())),
+            _ => None,
+        }
+    }
+
+    fn released_handle(&self) -> Option<RecordedHandle> {
+        match self {
+            Self::ReleaseRendererLayer { renderer_layer } => {
+                Some((RendererHandleKind::RendererLayer, renderer_layer.as_ptr()))
+            }
+            Self::ReleaseRendererGroup { renderer_group } => {
+                Some((RendererHandleKind::RendererGroup, renderer_group.as_ptr()))
+            }
+            Self::ReleaseTransform { renderer_transform } => {
+                Some((RendererHandleKind::Transform, renderer_transform.as_ptr()))
+            }
+            Self::ReleaseMaterial { renderer_material } => {
+                Some((RendererHandleKind::Material, renderer_material.as_ptr()))
+            }
+            Self::ReleaseShader { renderer_shader } => {
+                Some((RendererHandleKind::Shader, renderer_shader.as_ptr()))
+            }
+            Self::ReleaseMesh { renderer_mesh } => {
+                Some((RendererHandleKind::Mesh, renderer_mesh.as_ptr()))
+            }
+            Self::ReleaseRendererObject { renderer_object } => {
+                Some((RendererHandleKind::RendererObject, renderer_object.as_ptr()))
+            }
+            Self::ReleaseCamera { camera } => Some((RendererHandleKind::Camera, camera.as_ptr())),
+            Self::ReleaseProbe { probe } => Some((RendererHandleKind::Probe, probe.as_ptr())),
+            Self::ReleaseWindow { window } => Some((RendererHandleKind::Window, window.as_ptr())),
+            Self::ReleaseRenderTexture { renderer_texture } => {
+                Some((RendererHandleKind::Texture, renderer_texture.as_ptr()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RecordingRenderer<T: RendererImpl> {
+    inner: T,
+    calls: ArcRwLock<Vec<RecordedRendererCall>>,
+    injected_failures: ArcRwLock<BTreeMap<RendererCallKind, String>>,
+}
+
+impl<T: RendererImpl> RecordingRenderer<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            calls: arc_rw_lock_new(Vec::new()),
+            injected_failures: arc_rw_lock_new(BTreeMap::new()),
+        }
+    }
+
+    // the calls of the kind return the error without reaching the inner renderer,
+    // they are recorded as failed calls
+    pub fn fail_calls(&self, kind: RendererCallKind, error: impl Into<String>) {
+        self.injected_failures.write().insert(kind, error.into());
+    }
+
+    pub fn stop_failing_calls(&self, kind: RendererCallKind) {
+        self.injected_failures.write().remove(&kind);
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn calls(&self) -> Vec<RecordedRendererCall> {
+        self.calls.read().clone()
+    }
+
+    pub fn call_kinds(&self) -> Vec<RendererCallKind> {
+        self.calls
+            .read()
+            .iter()
+            .map(|recorded_call| recorded_call.call.kind())
+            .collect()
+    }
+
+    pub fn clear_calls(&self) {
+        self.calls.write().clear();
+    }
+
+    pub fn count_calls(&self, kind: RendererCallKind) -> usize {
+        self.calls
+            .read()
+            .iter()
+            .filter(|recorded_call| recorded_call.call.kind() == kind)
+            .count()
+    }
+
+    pub fn count_failed_calls(&self, kind: RendererCallKind) -> usize {
+        self.calls
+            .read()
+            .iter()
+            .filter(|recorded_call| recorded_call.call.kind() == kind && !recorded_call.succeeded)
+            .count()
+    }
+
+    pub fn live_handle_count(&self, kind: RendererHandleKind) -> usize {
+        self.live_handles()
+            .iter()
+            .filter(|(handle_kind, _)| *handle_kind == kind)
+            .count()
+    }
+
+    pub fn assert_call_count(&self, kind: RendererCallKind, expected: usize) {
+        let actual = self.count_calls(kind);
+        assert_eq!(
+            expected, actual,
+            "expected {expected} call(s) of {kind:?}, recorded {actual}"
+        );
+    }
+
+    pub fn assert_no_failed_calls(&self) {
+        let failed_calls = self
+            .calls
+            .read()
+            .iter()
+            .filter(|recorded_call| !recorded_call.succeeded)
+            .map(|recorded_call| recorded_call.call.kind())
+            .collect::<Vec<_>>();
+        assert!(
+            failed_calls.is_empty(),
+            "expected no failed renderer calls, failed calls = {failed_calls:?}"
+        );
+    }
+
+    pub fn assert_call_order(&self, expected_order: &[RendererCallKind]) {
+        let call_kinds = self.call_kinds();
+
+        let mut call_kinds_iter = call_kinds.iter();
+        for expected_kind in expected_order {
+            assert!(
+                call_kinds_iter.any(|kind| kind == expected_kind),
+                "expected renderer calls in order {expected_order:?}, {expected_kind:?} is missing or out of order, recorded calls = {:?}",
+                call_kinds
+                    .iter()
+                    .filter(|kind| **kind != RendererCallKind::Render)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    pub fn assert_live_handle_count(&self, kind: RendererHandleKind, expected: usize) {
+        let actual = self.live_handle_count(kind);
+        assert_eq!(
+            expected, actual,
+            "expected {expected} live {kind:?} handle(s), found {actual}"
+        );
+    }
+
+    pub fn assert_no_live_handles(&self) {
+        let live_handle_kinds = self
+            .live_handles()
+            .iter()
+            .map(|(kind, _)| *kind)
+            .collect::<Vec<_>>();
+        assert!(
+            live_handle_kinds.is_empty(),
+            "expected every renderer handle to be released, live handles = {live_handle_kinds:?}"
+        );
+    }
+
+    fn live_handles(&self) -> BTreeSet<RecordedHandle> {
+        let mut live_handles = BTreeSet::new();
+
+        for recorded_call in self.calls.read().iter() {
+            if !recorded_call.succeeded {
+                continue;
+            }
+
+            if let Some(handle) = recorded_call.call.created_handle() {
+                live_handles.insert(handle);
+            }
+
+            if let Some(handle) = recorded_call.call.released_handle() {
+                live_handles.remove(&handle);
+            }
+        }
+
+        live_handles
+    }
+
+    fn call_inner<R>(
+        &mut self,
+        kind: RendererCallKind,
+        func: impl FnOnce(&mut T) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let injected_failure = self.injected_failures.read().get(&kind).cloned();
+        match injected_failure {
+            Some(error) => Err(error),
+            None => func(&mut self.inner),
+        }
+    }
+
+    fn record<R>(&self, call: RendererCall, result: &Result<R, String>) {
+        self.calls.write().push(RecordedRendererCall {
+            call,
+            succeeded: result.is_ok(),
+        });
+    }
+}
+
+fn sendable_ptr<T: ?Sized>(object: &ArcRwLock<T>) -> SendablePtr<T> {
+    SendablePtr::new(object.data_ptr())
+}
+
+impl<T: RendererImpl> RendererImpl for RecordingRenderer<T> {
+    fn render(&mut self) {
+        self.inner.render();
+        self.record(RendererCall::Render, &Ok(()));
+    }
+
+    fn window_dimensions_changed(&mut self, width: usize, height: usize) -> Result<(), String> {
+        let result = self.call_inner(RendererCallKind::WindowDimensionsChanged, |inner| {
+            inner.window_dimensions_changed(width, height)
+        });
+        self.record(
+            RendererCall::WindowDimensionsChanged { width, height },
+            &result,
+        );
+        result
+    }
+
+    fn set_renderer_config(&mut self, renderer_config: RendererConfig) -> Result<(), String> {
+        let result = self.call_inner(RendererCallKind::SetRendererConfig, |inner| {
+            inner.set_renderer_config(renderer_config)
+        });
+        self.record(RendererCall::SetRendererConfig { renderer_config }, &result);
+        result
+    }
+
+    fn set_renderer_pipeline(
+        &mut self,
+        steps: Vec<RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        let number_of_steps = steps.len();
+        let result = self.call_inner(RendererCallKind::SetRendererPipeline, |inner| {
+            inner.set_renderer_pipeline(steps)
+        });
+        self.record(
+            RendererCall::SetRendererPipeline { number_of_steps },
+            &result,
+        );
+        result
+    }
+
+    fn set_pipeline_clear_color(
+        &mut self,
+        step_index: usize,
+        clear_color: Vec4<f32>,
+    ) -> Result<(), String> {
+        let result = self.call_inner(RendererCallKind::SetPipelineClearColor, |inner| {
+            inner.set_pipeline_clear_color(step_index, clear_color)
+        });
+        self.record(
+            RendererCall::SetPipelineClearColor {
+                step_index,
+                clear_color,
+            },
+            &result,
+        );
+        result
+    }
+
+    fn create_window(
+        &mut self,
+        title: &str,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererWindow>, String> {
+        let result = self.call_inner(RendererCallKind::CreateWindow, |inner| {
+            inner.create_window(title, dimensions)
+        });
+        self.record(
+            RendererCall::CreateWindow {
+                title: title.to_string(),
+                dimensions,
+                window: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn set_window_pipeline(
+        &mut self,
+        window: ArcRwLock<dyn RendererWindow>,
+        steps: Vec<RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetWindowPipeline {
+            window: sendable_ptr(&window),
+            number_of_steps: steps.len(),
+        };
+        let result = self.call_inner(RendererCallKind::SetWindowPipeline, |inner| {
+            inner.set_window_pipeline(window, steps)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn release_window(&mut self, window: ArcRwLock<dyn RendererWindow>) -> Result<(), String> {
+        let call = RendererCall::ReleaseWindow {
+            window: sendable_ptr(&window),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseWindow, |inner| {
+            inner.release_window(window)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_renderer_layer(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+    ) -> Result<ArcRwLock<dyn RendererLayer>, String> {
+        let camera_ptr = sendable_ptr(&camera);
+        let result = self.call_inner(RendererCallKind::CreateRendererLayer, |inner| {
+            inner.create_renderer_layer(camera)
+        });
+        self.record(
+            RendererCall::CreateRendererLayer {
+                camera: camera_ptr,
+                renderer_layer: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn release_renderer_layer(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let renderer_layer_ptr = sendable_ptr(&renderer_layer);
+        let result = self.call_inner(RendererCallKind::ReleaseRendererLayer, |inner| {
+            inner.release_renderer_layer(renderer_layer)
+        });
+        self.record(
+            RendererCall::ReleaseRendererLayer {
+                renderer_layer: renderer_layer_ptr,
+            },
+            &result,
+        );
+        result
+    }
+
+    fn set_renderer_layer_settings(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        settings: RendererLayerSettings,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererLayerSettings {
+            renderer_layer: sendable_ptr(&renderer_layer),
+            settings,
+        };
+        let result = self
+            .inner
+            .set_renderer_layer_settings(renderer_layer, settings);
+        self.record(call, &result);
+        result
+    }
+
+    fn update_renderer_layer_projection(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        projection: Option<RendererLayerProjection>,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateRendererLayerProjection {
+            renderer_layer: sendable_ptr(&renderer_layer),
+            projection,
+        };
+        let result = self
+            .inner
+            .update_renderer_layer_projection(renderer_layer, projection);
+        self.record(call, &result);
+        result
+    }
+
+    fn set_renderer_layer_override_shader(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        shader: Option<ArcRwLock<dyn RendererShader>>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererLayerOverrideShader {
+            renderer_layer: sendable_ptr(&renderer_layer),
+            shader: shader.as_ref().map(sendable_ptr),
+        };
+        let result = self
+            .inner
+            .set_renderer_layer_override_shader(renderer_layer, shader);
+        self.record(call, &result);
+        result
+    }
+
+    fn add_renderer_group_to_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let call = RendererCall::AddRendererGroupToLayer {
+            renderer_group: sendable_ptr(&renderer_group),
+            renderer_layer: sendable_ptr(&renderer_layer),
+        };
+        let result = self
+            .inner
+            .add_renderer_group_to_layer(renderer_group, renderer_layer);
+        self.record(call, &result);
+        result
+    }
+
+    fn remove_renderer_group_from_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let call = RendererCall::RemoveRendererGroupFromLayer {
+            renderer_group: sendable_ptr(&renderer_group),
+            renderer_layer: sendable_ptr(&renderer_layer),
+        };
+        let result = self
+            .inner
+            .remove_renderer_group_from_layer(renderer_group, renderer_layer);
+        self.record(call, &result);
+        result
+    }
+
+    fn create_renderer_group(&mut self) -> Result<ArcRwLock<dyn RendererGroup>, String> {
+        let result = self.call_inner(RendererCallKind::CreateRendererGroup, |inner| {
+            inner.create_renderer_group()
+        });
+        self.record(
+            RendererCall::CreateRendererGroup {
+                renderer_group: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn release_renderer_group(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let call = RendererCall::ReleaseRendererGroup {
+            renderer_group: sendable_ptr(&renderer_group),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseRendererGroup, |inner| {
+            inner.release_renderer_group(renderer_group)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_transform(
+        &mut self,
+        transform: Transform<f32, f32, f32>,
+    ) -> Result<ArcRwLock<dyn RendererTransform>, String> {
+        let result = self.call_inner(RendererCallKind::CreateTransform, |inner| {
+            inner.create_transform(transform)
+        });
+        self.record(
+            RendererCall::CreateTransform {
+                transform,
+                renderer_transform: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn update_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateTransform {
+            renderer_transform: sendable_ptr(&transform),
+            new_transform,
+        };
+        let result = self.call_inner(RendererCallKind::UpdateTransform, |inner| {
+            inner.update_transform(transform, new_transform)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn update_transform_interpolated(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+        timestamp: Instant,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateTransformInterpolated {
+            renderer_transform: sendable_ptr(&transform),
+            new_transform,
+            timestamp,
+        };
+        let result = self.call_inner(RendererCallKind::UpdateTransformInterpolated, |inner| {
+            inner.update_transform_interpolated(transform, new_transform, timestamp)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn set_transform_parent(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        parent_transform: Option<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetTransformParent {
+            renderer_transform: sendable_ptr(&transform),
+            parent_transform: parent_transform.as_ref().map(sendable_ptr),
+        };
+        let result = self.call_inner(RendererCallKind::SetTransformParent, |inner| {
+            inner.set_transform_parent(transform, parent_transform)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn release_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String> {
+        let call = RendererCall::ReleaseTransform {
+            renderer_transform: sendable_ptr(&transform),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseTransform, |inner| {
+            inner.release_transform(transform)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_material(
+        &mut self,
+        material: Material,
+    ) -> Result<ArcRwLock<dyn RendererMaterial>, String> {
+        let result = self.call_inner(RendererCallKind::CreateMaterial, |inner| {
+            inner.create_material(material.clone())
+        });
+        self.record(
+            RendererCall::CreateMaterial {
+                material,
+                renderer_material: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn update_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        new_material: Material,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateMaterial {
+            renderer_material: sendable_ptr(&material),
+            new_material: new_material.clone(),
+        };
+        let result = self.call_inner(RendererCallKind::UpdateMaterial, |inner| {
+            inner.update_material(material, new_material)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn release_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
+        let call = RendererCall::ReleaseMaterial {
+            renderer_material: sendable_ptr(&material),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseMaterial, |inner| {
+            inner.release_material(material)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn set_material_render_texture(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        texture_type: MaterialTextureType,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetMaterialRenderTexture {
+            renderer_material: sendable_ptr(&material),
+            texture_type,
+            renderer_texture: sendable_ptr(&texture),
+        };
+        let result = self
+            .inner
+            .set_material_render_texture(material, texture_type, texture);
+        self.record(call, &result);
+        result
+    }
+
+    fn create_render_texture(
+        &mut self,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let result = self.call_inner(RendererCallKind::CreateRenderTexture, |inner| {
+            inner.create_render_texture(dimensions)
+        });
+        self.record(
+            RendererCall::CreateRenderTexture {
+                dimensions,
+                renderer_texture: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn release_render_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        let call = RendererCall::ReleaseRenderTexture {
+            renderer_texture: sendable_ptr(&texture),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseRenderTexture, |inner| {
+            inner.release_render_texture(texture)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_texture(
+        &mut self,
+        image: Arc<Image>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let result = self.call_inner(RendererCallKind::CreateTexture, |inner| {
+            inner.create_texture(image.clone())
+        });
+        self.record(
+            RendererCall::CreateTexture {
+                image,
+                renderer_texture: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn update_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        image: Arc<Image>,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateTexture {
+            renderer_texture: sendable_ptr(&texture),
+            image: image.clone(),
+        };
+        let result = self.call_inner(RendererCallKind::UpdateTexture, |inner| {
+            inner.update_texture(texture, image)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_cubemap_texture(
+        &mut self,
+        faces: [Arc<Image>; 6],
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let result = self.call_inner(RendererCallKind::CreateCubemapTexture, |inner| {
+            inner.create_cubemap_texture(faces.clone())
+        });
+        self.record(
+            RendererCall::CreateCubemapTexture {
+                faces,
+                renderer_texture: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn create_shader(
+        &mut self,
+        shader_name: String,
+    ) -> Result<ArcRwLock<dyn RendererShader>, String> {
+        let result = self.call_inner(RendererCallKind::CreateShader, |inner| {
+            inner.create_shader(shader_name.clone())
+        });
+        self.record(
+            RendererCall::CreateShader {
+                shader_name,
+                renderer_shader: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn update_shader(
+        &mut self,
+        shader: ArcRwLock<dyn RendererShader>,
+        new_shader_name: String,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateShader {
+            renderer_shader: sendable_ptr(&shader),
+            new_shader_name: new_shader_name.clone(),
+        };
+        let result = self.call_inner(RendererCallKind::UpdateShader, |inner| {
+            inner.update_shader(shader, new_shader_name)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn release_shader(&mut self, shader: ArcRwLock<dyn RendererShader>) -> Result<(), String> {
+        let call = RendererCall::ReleaseShader {
+            renderer_shader: sendable_ptr(&shader),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseShader, |inner| {
+            inner.release_shader(shader)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_mesh(&mut self, mesh: Arc<Mesh>) -> Result<ArcRwLock<dyn RendererMesh>, String> {
+        let result = self.call_inner(RendererCallKind::CreateMesh, |inner| {
+            inner.create_mesh(mesh.clone())
+        });
+        self.record(
+            RendererCall::CreateMesh {
+                mesh,
+                renderer_mesh: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn update_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        new_mesh: Arc<Mesh>,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateMesh {
+            renderer_mesh: sendable_ptr(&mesh),
+            new_mesh: new_mesh.clone(),
+        };
+        let result = self.call_inner(RendererCallKind::UpdateMesh, |inner| {
+            inner.update_mesh(mesh, new_mesh)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn update_mesh_vertices(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        vertex_range: Range<usize>,
+        vertices: RendererMeshVertices,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateMeshVertices {
+            renderer_mesh: sendable_ptr(&mesh),
+            vertex_range: vertex_range.clone(),
+            vertices: vertices.clone(),
+        };
+        let result = self
+            .inner
+            .update_mesh_vertices(mesh, vertex_range, vertices);
+        self.record(call, &result);
+        result
+    }
+
+    fn release_mesh(&mut self, mesh: ArcRwLock<dyn RendererMesh>) -> Result<(), String> {
+        let call = RendererCall::ReleaseMesh {
+            renderer_mesh: sendable_ptr(&mesh),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseMesh, |inner| {
+            inner.release_mesh(mesh)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_renderer_object_from_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let (mesh_ptr, shader_ptr, material_ptr, transform_ptr) = (
+            sendable_ptr(&mesh),
+            sendable_ptr(&shader),
+            sendable_ptr(&material),
+            sendable_ptr(&transform),
+        );
+        let result = self
+            .inner
+            .create_renderer_object_from_mesh(mesh, shader, material, transform);
+        self.record(
+            RendererCall::CreateRendererObjectFromMesh {
+                mesh: mesh_ptr,
+                shader: shader_ptr,
+                material: material_ptr,
+                transform: transform_ptr,
+                renderer_object: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn create_instanced_renderer_object(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transforms: Vec<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let (mesh_ptr, shader_ptr, material_ptr, transform_ptrs) = (
+            sendable_ptr(&mesh),
+            sendable_ptr(&shader),
+            sendable_ptr(&material),
+            transforms.iter().map(sendable_ptr).collect(),
+        );
+        let result = self
+            .inner
+            .create_instanced_renderer_object(mesh, shader, material, transforms);
+        self.record(
+            RendererCall::CreateInstancedRendererObject {
+                mesh: mesh_ptr,
+                shader: shader_ptr,
+                material: material_ptr,
+                transforms: transform_ptrs,
+                renderer_object: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn create_lod_renderer_object(
+        &mut self,
+        lod_meshes: Vec<(f32, ArcRwLock<dyn RendererMesh>)>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let (lod_mesh_ptrs, shader_ptr, material_ptr, transform_ptr) = (
+            lod_meshes
+                .iter()
+                .map(|(distance, mesh)| (*distance, sendable_ptr(mesh)))
+                .collect(),
+            sendable_ptr(&shader),
+            sendable_ptr(&material),
+            sendable_ptr(&transform),
+        );
+        let result = self
+            .inner
+            .create_lod_renderer_object(lod_meshes, shader, material, transform);
+        self.record(
+            RendererCall::CreateLodRendererObject {
+                lod_meshes: lod_mesh_ptrs,
+                shader: shader_ptr,
+                material: material_ptr,
+                transform: transform_ptr,
+                renderer_object: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn create_skybox_renderer_object(
+        &mut self,
+        cubemap: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let cubemap_ptr = sendable_ptr(&cubemap);
+        let result = self.call_inner(RendererCallKind::CreateSkyboxRendererObject, |inner| {
+            inner.create_skybox_renderer_object(cubemap)
+        });
+        self.record(
+            RendererCall::CreateSkyboxRendererObject {
+                cubemap: cubemap_ptr,
+                renderer_object: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn create_particle_emitter_renderer_object(
+        &mut self,
+        settings: ParticleEmitterSettings,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let material_ptr = sendable_ptr(&material);
+        let transform_ptr = sendable_ptr(&transform);
+        let result = self.call_inner(
+            RendererCallKind::CreateParticleEmitterRendererObject,
+            |inner| {
+                inner.create_particle_emitter_renderer_object(settings.clone(), material, transform)
+            },
+        );
+        self.record(
+            RendererCall::CreateParticleEmitterRendererObject {
+                settings,
+                material: material_ptr,
+                transform: transform_ptr,
+                renderer_object: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn create_sprite_batch_renderer_object(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        sprites: Vec<RendererSprite>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let texture_ptr = sendable_ptr(&texture);
+        let transform_ptr = sendable_ptr(&transform);
+        let result =
+            self.inner
+                .create_sprite_batch_renderer_object(texture, sprites.clone(), transform);
+        self.record(
+            RendererCall::CreateSpriteBatchRendererObject {
+                texture: texture_ptr,
+                sprites,
+                transform: transform_ptr,
+                renderer_object: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn release_renderer_object(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+    ) -> Result<(), String> {
+        let call = RendererCall::ReleaseRendererObject {
+            renderer_object: sendable_ptr(&renderer_object),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseRendererObject, |inner| {
+            inner.release_renderer_object(renderer_object)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn add_renderer_object_to_group(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let call = RendererCall::AddRendererObjectToGroup {
+            renderer_object: sendable_ptr(&renderer_object),
+            renderer_group: sendable_ptr(&renderer_group),
+        };
+        let result = self
+            .inner
+            .add_renderer_object_to_group(renderer_object, renderer_group);
+        self.record(call, &result);
+        result
+    }
+
+    fn remove_renderer_object_from_group(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let call = RendererCall::RemoveRendererObjectFromGroup {
+            renderer_object: sendable_ptr(&renderer_object),
+            renderer_group: sendable_ptr(&renderer_group),
+        };
+        let result = self
+            .inner
+            .remove_renderer_object_from_group(renderer_object, renderer_group);
+        self.record(call, &result);
+        result
+    }
+
+    fn set_renderer_object_transform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererObjectTransform {
+            renderer_object: sendable_ptr(&renderer_object),
+            transform: sendable_ptr(&transform),
+        };
+        let result = self
+            .inner
+            .set_renderer_object_transform(renderer_object, transform);
+        self.record(call, &result);
+        result
+    }
+
+    fn set_renderer_object_material(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererObjectMaterial {
+            renderer_object: sendable_ptr(&renderer_object),
+            material: sendable_ptr(&material),
+        };
+        let result = self
+            .inner
+            .set_renderer_object_material(renderer_object, material);
+        self.record(call, &result);
+        result
+    }
+
+    fn set_renderer_object_shader(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        shader: ArcRwLock<dyn RendererShader>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererObjectShader {
+            renderer_object: sendable_ptr(&renderer_object),
+            shader: sendable_ptr(&shader),
+        };
+        let result = self
+            .inner
+            .set_renderer_object_shader(renderer_object, shader);
+        self.record(call, &result);
+        result
+    }
+
+    fn set_renderer_object_mesh(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        mesh: ArcRwLock<dyn RendererMesh>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererObjectMesh {
+            renderer_object: sendable_ptr(&renderer_object),
+            mesh: sendable_ptr(&mesh),
+        };
+        let result = self.call_inner(RendererCallKind::SetRendererObjectMesh, |inner| {
+            inner.set_renderer_object_mesh(renderer_object, mesh)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn set_renderer_object_probe(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        probe: Option<ArcRwLock<dyn RendererProbe>>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererObjectProbe {
+            renderer_object: sendable_ptr(&renderer_object),
+            probe: probe.as_ref().map(sendable_ptr),
+        };
+        let result = self.call_inner(RendererCallKind::SetRendererObjectProbe, |inner| {
+            inner.set_renderer_object_probe(renderer_object, probe)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn set_renderer_object_uniform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        name: String,
+        value: Option<RendererUniformValue>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetRendererObjectUniform {
+            renderer_object: sendable_ptr(&renderer_object),
+            name: name.clone(),
+            value,
+        };
+        let result = self
+            .inner
+            .set_renderer_object_uniform(renderer_object, name, value);
+        self.record(call, &result);
+        result
+    }
+
+    fn set_particle_emitter_settings(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        settings: ParticleEmitterSettings,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetParticleEmitterSettings {
+            renderer_object: sendable_ptr(&renderer_object),
+            settings: settings.clone(),
+        };
+        let result = self
+            .inner
+            .set_particle_emitter_settings(renderer_object, settings);
+        self.record(call, &result);
+        result
+    }
+
+    fn set_sprite_batch_sprites(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        sprites: Vec<RendererSprite>,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetSpriteBatchSprites {
+            renderer_object: sendable_ptr(&renderer_object),
+            sprites: sprites.clone(),
+        };
+        let result = self
+            .inner
+            .set_sprite_batch_sprites(renderer_object, sprites);
+        self.record(call, &result);
+        result
+    }
+
+    fn create_camera(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererCamera>, String> {
+        let transform_ptr = sendable_ptr(&transform);
+        let result = self.call_inner(RendererCallKind::CreateCamera, |inner| {
+            inner.create_camera(transform)
+        });
+        self.record(
+            RendererCall::CreateCamera {
+                transform: transform_ptr,
+                camera: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn update_camera_parameters(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+        parameters: Option<RendererCameraParameters>,
+    ) -> Result<(), String> {
+        let call = RendererCall::UpdateCameraParameters {
+            camera: sendable_ptr(&camera),
+            parameters,
+        };
+        let result = self.call_inner(RendererCallKind::UpdateCameraParameters, |inner| {
+            inner.update_camera_parameters(camera, parameters)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn release_camera(&mut self, camera: ArcRwLock<dyn RendererCamera>) -> Result<(), String> {
+        let call = RendererCall::ReleaseCamera {
+            camera: sendable_ptr(&camera),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseCamera, |inner| {
+            inner.release_camera(camera)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn create_probe(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<ArcRwLock<dyn RendererProbe>, String> {
+        let (transform_ptr, renderer_layer_ptr) =
+            (sendable_ptr(&transform), sendable_ptr(&renderer_layer));
+        let result = self
+            .inner
+            .create_probe(transform, renderer_layer, resolution, refresh_mode);
+        self.record(
+            RendererCall::CreateProbe {
+                transform: transform_ptr,
+                renderer_layer: renderer_layer_ptr,
+                resolution,
+                refresh_mode,
+                probe: result.as_ref().ok().map(sendable_ptr),
+            },
+            &result,
+        );
+        result
+    }
+
+    fn refresh_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        let call = RendererCall::RefreshProbe {
+            probe: sendable_ptr(&probe),
+        };
+        let result = self.call_inner(RendererCallKind::RefreshProbe, |inner| {
+            inner.refresh_probe(probe)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn set_probe_refresh_mode(
+        &mut self,
+        probe: ArcRwLock<dyn RendererProbe>,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<(), String> {
+        let call = RendererCall::SetProbeRefreshMode {
+            probe: sendable_ptr(&probe),
+            refresh_mode,
+        };
+        let result = self.call_inner(RendererCallKind::SetProbeRefreshMode, |inner| {
+            inner.set_probe_refresh_mode(probe, refresh_mode)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn release_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        let call = RendererCall::ReleaseProbe {
+            probe: sendable_ptr(&probe),
+        };
+        let result = self.call_inner(RendererCallKind::ReleaseProbe, |inner| {
+            inner.release_probe(probe)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn stats(&self) -> RendererStats {
+        self.inner.stats()
+    }
+
+    fn set_texture_memory_budget(&mut self, budget_bytes: Option<usize>) -> Result<(), String> {
+        let result = self.call_inner(RendererCallKind::SetTextureMemoryBudget, |inner| {
+            inner.set_texture_memory_budget(budget_bytes)
+        });
+        self.record(
+            RendererCall::SetTextureMemoryBudget { budget_bytes },
+            &result,
+        );
+        result
+    }
+
+    fn set_debug_render_mode(&mut self, mode: RendererDebugRenderMode) -> Result<(), String> {
+        let result = self.call_inner(RendererCallKind::SetDebugRenderMode, |inner| {
+            inner.set_debug_render_mode(mode)
+        });
+        self.record(RendererCall::SetDebugRenderMode { mode }, &result);
+        result
+    }
+
+    fn add_debug_lines(
+        &mut self,
+        lines: Vec<RendererDebugLine>,
+        lifetime_secs: f32,
+    ) -> Result<(), String> {
+        let call = RendererCall::AddDebugLines {
+            lines: lines.clone(),
+            lifetime_secs,
+        };
+        let result = self.call_inner(RendererCallKind::AddDebugLines, |inner| {
+            inner.add_debug_lines(lines, lifetime_secs)
+        });
+        self.record(call, &result);
+        result
+    }
+
+    fn reload_shaders(&mut self) -> Result<(), String> {
+        let result = self.call_inner(RendererCallKind::ReloadShaders, |inner| {
+            inner.reload_shaders()
+        });
+        self.record(RendererCall::ReloadShaders, &result);
+        result
+    }
+
+    fn pick_renderer_object(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        screen_position: Vec2<f32>,
+        renderer_objects: Vec<ArcRwLock<dyn RendererObject>>,
+    ) -> Result<Option<usize>, String> {
+        let call = RendererCall::PickRendererObject {
+            renderer_layer: sendable_ptr(&renderer_layer),
+            screen_position,
+            renderer_objects: renderer_objects.iter().map(sendable_ptr).collect(),
+        };
+        let result =
+            self.inner
+                .pick_renderer_object(renderer_layer, screen_position, renderer_objects);
+        self.record(call, &result);
+        result
+    }
+}
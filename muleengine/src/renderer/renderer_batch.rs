@@ -0,0 +1,244 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use vek::Transform;
+
+use crate::mesh::{Material, Mesh};
+
+use super::{
+    renderer_system::RendererClient, RendererError, RendererGroupHandler, RendererMaterialHandler,
+    RendererMeshHandler, RendererObjectHandler, RendererShaderHandler, RendererTransformHandler,
+};
+
+// refers to the handler that an earlier command of the same batch creates
+#[derive(Debug)]
+pub struct RendererBatchSlot<H> {
+    index: usize,
+    _handler: PhantomData<fn() -> H>,
+}
+
+impl<H> Clone for RendererBatchSlot<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for RendererBatchSlot<H> {}
+
+impl<H> RendererBatchSlot<H> {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            _handler: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RendererBatchArg<H> {
+    Handler(H),
+    Slot(usize),
+}
+
+impl<H> From<RendererBatchSlot<H>> for RendererBatchArg<H> {
+    fn from(slot: RendererBatchSlot<H>) -> Self {
+        RendererBatchArg::Slot(slot.index)
+    }
+}
+
+impl<H: RendererBatchHandler> RendererBatchArg<H> {
+    pub(super) fn resolve(self, results: &[RendererBatchResult]) -> Result<H, RendererError> {
+        match self {
+            RendererBatchArg::Handler(handler) => Ok(handler),
+            RendererBatchArg::Slot(index) => results
+                .get(index)
+                .and_then(H::from_batch_result)
+                .ok_or(RendererError::InvalidRendererBatchSlot(index)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RendererBatchResult {
+    Transform(RendererTransformHandler),
+    Material(RendererMaterialHandler),
+    Shader(RendererShaderHandler),
+    Mesh(RendererMeshHandler),
+    RendererObject(RendererObjectHandler),
+    Done,
+}
+
+pub trait RendererBatchHandler: Sized {
+    fn from_batch_result(result: &RendererBatchResult) -> Option<Self>;
+}
+
+macro_rules! impl_renderer_batch_handler {
+    ($handler:ty, $variant:ident) => {
+        impl RendererBatchHandler for $handler {
+            fn from_batch_result(result: &RendererBatchResult) -> Option<Self> {
+                match result {
+                    RendererBatchResult::$variant(handler) => Some(handler.clone()),
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<$handler> for RendererBatchArg<$handler> {
+            fn from(handler: $handler) -> Self {
+                RendererBatchArg::Handler(handler)
+            }
+        }
+    };
+}
+
+impl_renderer_batch_handler!(RendererTransformHandler, Transform);
+impl_renderer_batch_handler!(RendererMaterialHandler, Material);
+impl_renderer_batch_handler!(RendererShaderHandler, Shader);
+impl_renderer_batch_handler!(RendererMeshHandler, Mesh);
+impl_renderer_batch_handler!(RendererObjectHandler, RendererObject);
+
+#[derive(Debug, Clone)]
+pub enum RendererBatchCommand {
+    CreateTransform(Transform<f32, f32, f32>),
+    UpdateTransform {
+        transform: RendererBatchArg<RendererTransformHandler>,
+        new_transform: Transform<f32, f32, f32>,
+    },
+    CreateMaterial(Material),
+    CreateShader(String),
+    CreateMesh(Arc<Mesh>),
+    CreateRendererObjectFromMesh {
+        mesh: RendererBatchArg<RendererMeshHandler>,
+        shader: RendererBatchArg<RendererShaderHandler>,
+        material: RendererBatchArg<RendererMaterialHandler>,
+        transform: RendererBatchArg<RendererTransformHandler>,
+    },
+    AddRendererObjectToGroup {
+        renderer_object: RendererBatchArg<RendererObjectHandler>,
+        renderer_group: RendererGroupHandler,
+    },
+}
+
+#[derive(Debug)]
+pub struct RendererBatchResults {
+    results: Vec<RendererBatchResult>,
+}
+
+impl RendererBatchResults {
+    pub fn get<H: RendererBatchHandler>(&self, slot: RendererBatchSlot<H>) -> Option<H> {
+        self.results.get(slot.index).and_then(H::from_batch_result)
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
+// records the commands and sends them to the renderer system as a single task,
+// the commands are executed in order and the execution stops at the first error
+#[derive(Debug, Clone, Default)]
+pub struct RendererBatchBuilder {
+    commands: Vec<RendererBatchCommand>,
+}
+
+impl RendererBatchBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn push<H>(&mut self, command: RendererBatchCommand) -> RendererBatchSlot<H> {
+        self.commands.push(command);
+        RendererBatchSlot::new(self.commands.len() - 1)
+    }
+
+    pub fn create_transform(
+        &mut self,
+        transform: Transform<f32, f32, f32>,
+    ) -> RendererBatchSlot<RendererTransformHandler> {
+        self.push(RendererBatchCommand::CreateTransform(transform))
+    }
+
+    pub fn update_transform(
+        &mut self,
+        transform: impl Into<RendererBatchArg<RendererTransformHandler>>,
+        new_transform: Transform<f32, f32, f32>,
+    ) {
+        self.push::<()>(RendererBatchCommand::UpdateTransform {
+            transform: transform.into(),
+            new_transform,
+        });
+    }
+
+    pub fn create_material(
+        &mut self,
+        material: Material,
+    ) -> RendererBatchSlot<RendererMaterialHandler> {
+        self.push(RendererBatchCommand::CreateMaterial(material))
+    }
+
+    pub fn create_shader(
+        &mut self,
+        shader_name: impl Into<String>,
+    ) -> RendererBatchSlot<RendererShaderHandler> {
+        self.push(RendererBatchCommand::CreateShader(shader_name.into()))
+    }
+
+    pub fn create_mesh(&mut self, mesh: Arc<Mesh>) -> RendererBatchSlot<RendererMeshHandler> {
+        self.push(RendererBatchCommand::CreateMesh(mesh))
+    }
+
+    pub fn create_renderer_object_from_mesh(
+        &mut self,
+        mesh: impl Into<RendererBatchArg<RendererMeshHandler>>,
+        shader: impl Into<RendererBatchArg<RendererShaderHandler>>,
+        material: impl Into<RendererBatchArg<RendererMaterialHandler>>,
+        transform: impl Into<RendererBatchArg<RendererTransformHandler>>,
+    ) -> RendererBatchSlot<RendererObjectHandler> {
+        self.push(RendererBatchCommand::CreateRendererObjectFromMesh {
+            mesh: mesh.into(),
+            shader: shader.into(),
+            material: material.into(),
+            transform: transform.into(),
+        })
+    }
+
+    pub fn add_renderer_object_to_group(
+        &mut self,
+        renderer_object: impl Into<RendererBatchArg<RendererObjectHandler>>,
+        renderer_group: RendererGroupHandler,
+    ) {
+        self.push::<()>(RendererBatchCommand::AddRendererObjectToGroup {
+            renderer_object: renderer_object.into(),
+            renderer_group,
+        });
+    }
+
+    pub async fn submit(
+        self,
+        renderer_client: &RendererClient,
+    ) -> Result<RendererBatchResults, RendererError> {
+        if self.commands.is_empty() {
+            return Ok(RendererBatchResults {
+                results: Vec::new(),
+            });
+        }
+
+        let results = renderer_client
+            .execute_renderer_batch(self.commands)
+            .await
+            .map_err(|_| RendererError::RendererSystemDropped)??;
+
+        Ok(RendererBatchResults { results })
+    }
+}
@@ -0,0 +1,23 @@
+use vek::Mat4;
+
+use super::renderer_layer_projection::RendererLayerProjection;
+
+// the parameters of a camera override the projection of the pipeline steps that draw its layers,
+// the projection of the renderer layer takes precedence over them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererCameraParameters {
+    pub fov_y_degrees: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl RendererCameraParameters {
+    pub fn compute_projection_matrix(&self, width: usize, height: usize) -> Mat4<f32> {
+        RendererLayerProjection::Perspective {
+            fov_y_degrees: self.fov_y_degrees,
+            near: self.near,
+            far: self.far,
+        }
+        .compute_projection_matrix(width, height)
+    }
+}
@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct RendererConfig {
+    // the number of samples of the default framebuffer, 0 disables multisampling,
+    // it has to be requested when the window context is created
+    pub msaa_samples: u8,
+    // the number of samples of the offscreen render targets, 0 disables multisampling,
+    // the multisampled targets are resolved before they are sampled
+    pub offscreen_msaa_samples: u8,
+    // the messages of the graphics driver are logged, it can slow the renderer down
+    pub debug_output: bool,
+}
@@ -0,0 +1,145 @@
+use std::time::{Duration, Instant};
+
+use vek::{Vec3, Vec4};
+
+use crate::aabb::AxisAlignedBoundingBox;
+
+const SPHERE_CIRCLE_SEGMENTS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererDebugLine {
+    pub start: Vec3<f32>,
+    pub end: Vec3<f32>,
+    pub color: Vec4<f32>,
+}
+
+pub fn aabb_debug_lines(aabb: &AxisAlignedBoundingBox, color: Vec4<f32>) -> Vec<RendererDebugLine> {
+    let corners = aabb.corners();
+
+    // the corners are ordered by x, y, z bits, so the edges connect corners differing in one bit
+    let mut lines = Vec::with_capacity(12);
+    for (index, start) in corners.iter().enumerate() {
+        for bit in [1, 2, 4] {
+            if index & bit == 0 {
+                lines.push(RendererDebugLine {
+                    start: *start,
+                    end: corners[index | bit],
+                    color,
+                });
+            }
+        }
+    }
+
+    lines
+}
+
+// a sphere is drawn as three circles, one around each axis
+pub fn sphere_debug_lines(
+    center: Vec3<f32>,
+    radius: f32,
+    color: Vec4<f32>,
+) -> Vec<RendererDebugLine> {
+    let point_on_circle = |axis: usize, angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        let offset = match axis {
+            0 => Vec3::new(0.0, cos, sin),
+            1 => Vec3::new(cos, 0.0, sin),
+            _ => Vec3::new(cos, sin, 0.0),
+        };
+        center + offset * radius
+    };
+
+    let mut lines = Vec::with_capacity(3 * SPHERE_CIRCLE_SEGMENTS);
+    for axis in 0..3 {
+        for segment in 0..SPHERE_CIRCLE_SEGMENTS {
+            let start_angle =
+                std::f32::consts::TAU * segment as f32 / SPHERE_CIRCLE_SEGMENTS as f32;
+            let end_angle =
+                std::f32::consts::TAU * (segment + 1) as f32 / SPHERE_CIRCLE_SEGMENTS as f32;
+
+            lines.push(RendererDebugLine {
+                start: point_on_circle(axis, start_angle),
+                end: point_on_circle(axis, end_angle),
+                color,
+            });
+        }
+    }
+
+    lines
+}
+
+// every line is drawn at least once, even if its lifetime is over before the next frame
+pub struct RendererDebugLineBuffer {
+    lines: Vec<RendererDebugLine>,
+    expires_at: Vec<Instant>,
+}
+
+impl Default for RendererDebugLineBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RendererDebugLineBuffer {
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            expires_at: Vec::new(),
+        }
+    }
+
+    pub fn add_lines(&mut self, lines: Vec<RendererDebugLine>, lifetime_secs: f32, now: Instant) {
+        let expires_at = now + Duration::from_secs_f32(lifetime_secs.max(0.0));
+
+        self.expires_at
+            .extend(std::iter::repeat(expires_at).take(lines.len()));
+        self.lines.extend(lines);
+    }
+
+    pub fn lines(&self) -> &[RendererDebugLine] {
+        &self.lines
+    }
+
+    pub fn remove_expired_lines(&mut self, now: Instant) {
+        let mut index = 0;
+        while index < self.lines.len() {
+            if self.expires_at[index] <= now {
+                self.lines.swap_remove(index);
+                self.expires_at.swap_remove(index);
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use vek::{Vec3, Vec4};
+
+    use super::{RendererDebugLine, RendererDebugLineBuffer};
+
+    #[test]
+    fn lines_are_removed_after_their_lifetime() {
+        let line = RendererDebugLine {
+            start: Vec3::zero(),
+            end: Vec3::unit_x(),
+            color: Vec4::one(),
+        };
+
+        let now = Instant::now();
+
+        let mut buffer = RendererDebugLineBuffer::new();
+        buffer.add_lines(vec![line], 0.0, now);
+        buffer.add_lines(vec![line, line], 1.0, now);
+        assert_eq!(buffer.lines().len(), 3);
+
+        buffer.remove_expired_lines(now);
+        assert_eq!(buffer.lines().len(), 2);
+
+        buffer.remove_expired_lines(now + Duration::from_secs(2));
+        assert!(buffer.lines().is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum RendererDebugRenderMode {
+    #[default]
+    Normal,
+    Wireframe,
+    // the renderer objects are colored by their normal vectors
+    Normals,
+}
@@ -0,0 +1,172 @@
+use std::{collections::VecDeque, sync::Arc, time::Instant};
+
+use bytifex_utils::sync::types::{arc_mutex_new, ArcMutex};
+use method_taskifier::task_channel::TaskReceiver;
+use tokio::sync::Notify;
+use vek::Transform;
+
+use super::{
+    particle_system::ParticleEmitterSettings, renderer_system::ChanneledTask,
+    RendererObjectHandler, RendererTransformHandler,
+};
+
+// the messages are not answered, so no result channel is allocated for them, the errors are only
+// logged by the renderer
+pub(super) enum RendererDetachedCommand {
+    UpdateTransform {
+        transform_handler: RendererTransformHandler,
+        new_transform: Transform<f32, f32, f32>,
+    },
+    UpdateTransformInterpolated {
+        transform_handler: RendererTransformHandler,
+        new_transform: Transform<f32, f32, f32>,
+        timestamp: Instant,
+    },
+    SetParticleEmitterSettings {
+        renderer_object_handler: RendererObjectHandler,
+        settings: ParticleEmitterSettings,
+    },
+}
+
+enum RendererMessage {
+    Task(ChanneledTask),
+    Command(RendererDetachedCommand),
+}
+
+struct RendererMessages {
+    messages: VecDeque<RendererMessage>,
+    task_receiver: TaskReceiver<ChanneledTask>,
+}
+
+// the tasks that are waiting in the task channel when a detached command is sent are moved in
+// front of the command, so the renderer receives the tasks and the commands in the order of
+// sending
+#[derive(Clone)]
+pub(super) struct RendererMessageQueue {
+    messages: ArcMutex<RendererMessages>,
+    command_added: Arc<Notify>,
+}
+
+impl RendererMessageQueue {
+    pub(super) fn new(task_receiver: TaskReceiver<ChanneledTask>) -> Self {
+        Self {
+            messages: arc_mutex_new(RendererMessages {
+                messages: VecDeque::new(),
+                task_receiver,
+            }),
+            command_added: Arc::new(Notify::new()),
+        }
+    }
+
+    fn push_command(&self, command: RendererDetachedCommand) {
+        {
+            let mut messages = self.messages.lock();
+            while let Ok(task) = messages.task_receiver.try_recv() {
+                messages.messages.push_back(RendererMessage::Task(task));
+            }
+            messages
+                .messages
+                .push_back(RendererMessage::Command(command));
+        }
+
+        self.command_added.notify_one();
+    }
+
+    // the commands in front of the next task are executed while the queue is locked, so they are
+    // finished before any later message is taken by another executor
+    pub(super) fn try_recv_task(
+        &self,
+        mut execute_command: impl FnMut(RendererDetachedCommand),
+    ) -> Option<ChanneledTask> {
+        let mut messages = self.messages.lock();
+        while let Some(message) = messages.messages.pop_front() {
+            match message {
+                RendererMessage::Task(task) => return Some(task),
+                RendererMessage::Command(command) => execute_command(command),
+            }
+        }
+
+        messages.task_receiver.try_recv().ok()
+    }
+
+    // returns none if every task sender is dropped
+    pub(super) async fn recv_task(
+        &self,
+        mut execute_command: impl FnMut(RendererDetachedCommand),
+    ) -> Option<ChanneledTask> {
+        loop {
+            if let Some(task) = self.try_recv_task(&mut execute_command) {
+                return Some(task);
+            }
+
+            let mut task_receiver = self.messages.lock().task_receiver.clone();
+            tokio::select! {
+                task = task_receiver.recv() => {
+                    let task = task.ok()?;
+
+                    // the lock is not held while the task is taken from the channel, so a
+                    // command that is sent in the meantime is executed before the task
+                    let mut messages = self.messages.lock();
+                    if messages.messages.is_empty() {
+                        return Some(task);
+                    }
+                    messages.messages.push_back(RendererMessage::Task(task));
+                }
+                _ = self.command_added.notified() => (),
+            }
+        }
+    }
+}
+
+// e.g. the transform updates of every frame, a command is executed after the tasks of the
+// renderer client that are sent before it, and before the tasks that are sent after it
+#[derive(Clone)]
+pub struct RendererDetachedClient {
+    message_queue: RendererMessageQueue,
+}
+
+impl RendererDetachedClient {
+    pub(super) fn new(message_queue: RendererMessageQueue) -> Self {
+        Self { message_queue }
+    }
+
+    pub fn update_transform(
+        &self,
+        transform_handler: RendererTransformHandler,
+        new_transform: Transform<f32, f32, f32>,
+    ) {
+        self.send(RendererDetachedCommand::UpdateTransform {
+            transform_handler,
+            new_transform,
+        });
+    }
+
+    // the timestamp is the time the transform belongs to, e.g. the time of the physics tick
+    pub fn update_transform_interpolated(
+        &self,
+        transform_handler: RendererTransformHandler,
+        new_transform: Transform<f32, f32, f32>,
+        timestamp: Instant,
+    ) {
+        self.send(RendererDetachedCommand::UpdateTransformInterpolated {
+            transform_handler,
+            new_transform,
+            timestamp,
+        });
+    }
+
+    pub fn set_particle_emitter_settings(
+        &self,
+        renderer_object_handler: RendererObjectHandler,
+        settings: ParticleEmitterSettings,
+    ) {
+        self.send(RendererDetachedCommand::SetParticleEmitterSettings {
+            renderer_object_handler,
+            settings,
+        });
+    }
+
+    fn send(&self, command: RendererDetachedCommand) {
+        self.message_queue.push_command(command);
+    }
+}
@@ -1,15 +1,30 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc, time::Instant};
 
 use bytifex_utils::sync::types::ArcRwLock;
-use vek::Transform;
+use vek::{Transform, Vec2, Vec4};
 
-use crate::mesh::{Material, Mesh};
+use crate::{
+    image::Image,
+    mesh::{Material, MaterialTextureType, Mesh},
+};
 
 use super::{
+    particle_system::ParticleEmitterSettings,
+    renderer_camera_parameters::RendererCameraParameters,
+    renderer_config::RendererConfig,
+    renderer_debug_lines::RendererDebugLine,
+    renderer_debug_render_mode::RendererDebugRenderMode,
+    renderer_layer_projection::RendererLayerProjection,
+    renderer_layer_settings::RendererLayerSettings,
+    renderer_mesh_vertices::RendererMeshVertices,
     renderer_objects::{renderer_camera::RendererCamera, renderer_layer::RendererLayer},
     renderer_pipeline_step_impl::RendererPipelineStepImpl,
-    RendererGroup, RendererMaterial, RendererMesh, RendererObject, RendererShader,
-    RendererTransform,
+    renderer_probe_refresh_mode::RendererProbeRefreshMode,
+    renderer_sprite::RendererSprite,
+    renderer_stats::RendererStats,
+    renderer_uniform_value::RendererUniformValue,
+    RendererGroup, RendererMaterial, RendererMesh, RendererObject, RendererProbe, RendererShader,
+    RendererTexture, RendererTransform, RendererWindow,
 };
 
 pub trait RendererImpl {
@@ -17,8 +32,29 @@ pub trait RendererImpl {
 
     fn window_dimensions_changed(&mut self, width: usize, height: usize) -> Result<(), String>;
 
+    fn set_renderer_config(&mut self, renderer_config: RendererConfig) -> Result<(), String>;
+
     fn set_renderer_pipeline(&mut self, steps: Vec<RendererPipelineStepImpl>)
         -> Result<(), String>;
+    // changes the color of a clear step of the main pipeline without rebuilding the pipeline
+    fn set_pipeline_clear_color(
+        &mut self,
+        step_index: usize,
+        clear_color: Vec4<f32>,
+    ) -> Result<(), String>;
+
+    // a secondary window is drawn with its own pipeline after the main window
+    fn create_window(
+        &mut self,
+        title: &str,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererWindow>, String>;
+    fn set_window_pipeline(
+        &mut self,
+        window: ArcRwLock<dyn RendererWindow>,
+        steps: Vec<RendererPipelineStepImpl>,
+    ) -> Result<(), String>;
+    fn release_window(&mut self, window: ArcRwLock<dyn RendererWindow>) -> Result<(), String>;
 
     fn create_renderer_layer(
         &mut self,
@@ -29,6 +65,21 @@ pub trait RendererImpl {
         renderer_layer: ArcRwLock<dyn RendererLayer>,
     ) -> Result<(), String>;
 
+    fn set_renderer_layer_settings(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        settings: RendererLayerSettings,
+    ) -> Result<(), String>;
+    fn update_renderer_layer_projection(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        projection: Option<RendererLayerProjection>,
+    ) -> Result<(), String>;
+    fn set_renderer_layer_override_shader(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        shader: Option<ArcRwLock<dyn RendererShader>>,
+    ) -> Result<(), String>;
     fn add_renderer_group_to_layer(
         &mut self,
         renderer_group: ArcRwLock<dyn RendererGroup>,
@@ -55,6 +106,19 @@ pub trait RendererImpl {
         transform: ArcRwLock<dyn RendererTransform>,
         new_transform: Transform<f32, f32, f32>,
     ) -> Result<(), String>;
+    // the renderers can lerp between the last two submitted transforms at render time,
+    // an update_transform call stops the interpolation of the transform
+    fn update_transform_interpolated(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+        timestamp: Instant,
+    ) -> Result<(), String>;
+    fn set_transform_parent(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        parent_transform: Option<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<(), String>;
     fn release_transform(
         &mut self,
         transform: ArcRwLock<dyn RendererTransform>,
@@ -71,6 +135,38 @@ pub trait RendererImpl {
     ) -> Result<(), String>;
     fn release_material(&mut self, material: ArcRwLock<dyn RendererMaterial>)
         -> Result<(), String>;
+    fn set_material_render_texture(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        texture_type: MaterialTextureType,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String>;
+
+    fn create_render_texture(
+        &mut self,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String>;
+    fn release_render_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String>;
+    // image textures are shared by the materials that reference them,
+    // they are released with release_render_texture
+    fn create_texture(
+        &mut self,
+        image: Arc<Image>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String>;
+    fn update_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        image: Arc<Image>,
+    ) -> Result<(), String>;
+    // the faces are in the +x, -x, +y, -y, +z, -z order,
+    // the cubemap is released with release_render_texture
+    fn create_cubemap_texture(
+        &mut self,
+        faces: [Arc<Image>; 6],
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String>;
 
     fn create_shader(
         &mut self,
@@ -89,6 +185,12 @@ pub trait RendererImpl {
         mesh: ArcRwLock<dyn RendererMesh>,
         new_mesh: Arc<Mesh>,
     ) -> Result<(), String>;
+    fn update_mesh_vertices(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        vertex_range: Range<usize>,
+        vertices: RendererMeshVertices,
+    ) -> Result<(), String>;
     fn release_mesh(&mut self, mesh: ArcRwLock<dyn RendererMesh>) -> Result<(), String>;
 
     fn create_renderer_object_from_mesh(
@@ -98,6 +200,36 @@ pub trait RendererImpl {
         material: ArcRwLock<dyn RendererMaterial>,
         transform: ArcRwLock<dyn RendererTransform>,
     ) -> Result<ArcRwLock<dyn RendererObject>, String>;
+    fn create_instanced_renderer_object(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transforms: Vec<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String>;
+    fn create_lod_renderer_object(
+        &mut self,
+        lod_meshes: Vec<(f32, ArcRwLock<dyn RendererMesh>)>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String>;
+    fn create_skybox_renderer_object(
+        &mut self,
+        cubemap: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String>;
+    fn create_particle_emitter_renderer_object(
+        &mut self,
+        settings: ParticleEmitterSettings,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String>;
+    fn create_sprite_batch_renderer_object(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        sprites: Vec<RendererSprite>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String>;
     fn release_renderer_object(
         &mut self,
         renderer_object: ArcRwLock<dyn RendererObject>,
@@ -114,11 +246,96 @@ pub trait RendererImpl {
         renderer_group: ArcRwLock<dyn RendererGroup>,
     ) -> Result<(), String>;
 
+    fn set_renderer_object_transform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String>;
+    fn set_renderer_object_material(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String>;
+    fn set_renderer_object_shader(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        shader: ArcRwLock<dyn RendererShader>,
+    ) -> Result<(), String>;
+    fn set_renderer_object_mesh(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        mesh: ArcRwLock<dyn RendererMesh>,
+    ) -> Result<(), String>;
+    fn set_renderer_object_probe(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        probe: Option<ArcRwLock<dyn RendererProbe>>,
+    ) -> Result<(), String>;
+    // the value takes precedence over the material for this renderer object only,
+    // None removes the override of the uniform
+    fn set_renderer_object_uniform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        name: String,
+        value: Option<RendererUniformValue>,
+    ) -> Result<(), String>;
+    fn set_particle_emitter_settings(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        settings: ParticleEmitterSettings,
+    ) -> Result<(), String>;
+    fn set_sprite_batch_sprites(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        sprites: Vec<RendererSprite>,
+    ) -> Result<(), String>;
+
     fn create_camera(
         &mut self,
         transform: ArcRwLock<dyn RendererTransform>,
     ) -> Result<ArcRwLock<dyn RendererCamera>, String>;
+    // None makes the camera use the projection of the pipeline steps
+    fn update_camera_parameters(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+        parameters: Option<RendererCameraParameters>,
+    ) -> Result<(), String>;
     fn release_camera(&mut self, camera: ArcRwLock<dyn RendererCamera>) -> Result<(), String>;
+
+    fn create_probe(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<ArcRwLock<dyn RendererProbe>, String>;
+    fn refresh_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String>;
+    fn set_probe_refresh_mode(
+        &mut self,
+        probe: ArcRwLock<dyn RendererProbe>,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<(), String>;
+    fn release_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String>;
+
+    fn stats(&self) -> RendererStats;
+    fn set_texture_memory_budget(&mut self, budget_bytes: Option<usize>) -> Result<(), String>;
+    fn set_debug_render_mode(&mut self, mode: RendererDebugRenderMode) -> Result<(), String>;
+    fn add_debug_lines(
+        &mut self,
+        lines: Vec<RendererDebugLine>,
+        lifetime_secs: f32,
+    ) -> Result<(), String>;
+
+    fn reload_shaders(&mut self) -> Result<(), String>;
+
+    // screen_position is in window pixels with the origin in the top left corner,
+    // the result is the index of the nearest renderer object under it
+    fn pick_renderer_object(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        screen_position: Vec2<f32>,
+        renderer_objects: Vec<ArcRwLock<dyn RendererObject>>,
+    ) -> Result<Option<usize>, String>;
 }
 
 pub trait AsRendererImpl {
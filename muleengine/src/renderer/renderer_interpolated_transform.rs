@@ -0,0 +1,121 @@
+use std::time::Instant;
+
+use vek::{Quaternion, Transform};
+
+// the last two transforms submitted for a renderer transform, the one shown at render time lags
+// one submission interval behind, so the game can tick slower than the renderer draws
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererInterpolatedTransform {
+    previous: Option<(Instant, Transform<f32, f32, f32>)>,
+    latest: (Instant, Transform<f32, f32, f32>),
+}
+
+impl RendererInterpolatedTransform {
+    pub fn new(timestamp: Instant, transform: Transform<f32, f32, f32>) -> Self {
+        Self {
+            previous: None,
+            latest: (timestamp, transform),
+        }
+    }
+
+    // the transforms that are older than the latest one are dropped
+    pub fn submit(&mut self, timestamp: Instant, transform: Transform<f32, f32, f32>) {
+        if timestamp <= self.latest.0 {
+            return;
+        }
+
+        self.previous = Some(self.latest);
+        self.latest = (timestamp, transform);
+    }
+
+    pub fn latest_transform(&self) -> Transform<f32, f32, f32> {
+        self.latest.1
+    }
+
+    pub fn transform_at(&self, now: Instant) -> Transform<f32, f32, f32> {
+        let Some((previous_timestamp, previous_transform)) = self.previous else {
+            return self.latest.1;
+        };
+        let (latest_timestamp, latest_transform) = self.latest;
+
+        let interval_secs = (latest_timestamp - previous_timestamp).as_secs_f32();
+        let elapsed_secs = now
+            .saturating_duration_since(latest_timestamp)
+            .as_secs_f32();
+        let q = (elapsed_secs / interval_secs).clamp(0.0, 1.0);
+
+        Transform {
+            position: previous_transform.position * (1.0 - q) + latest_transform.position * q,
+            orientation: Quaternion::slerp(
+                previous_transform.orientation,
+                latest_transform.orientation,
+                q,
+            ),
+            scale: previous_transform.scale * (1.0 - q) + latest_transform.scale * q,
+        }
+    }
+
+    // the latest transform is reached, so it does not change until the next submission
+    pub fn is_settled(&self, now: Instant) -> bool {
+        match self.previous {
+            Some((previous_timestamp, _)) => {
+                now.saturating_duration_since(self.latest.0) >= self.latest.0 - previous_timestamp
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use vek::{Transform, Vec3};
+
+    use super::RendererInterpolatedTransform;
+
+    #[test]
+    fn transform_is_interpolated_between_the_last_two_submissions() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let transform_at_x = |x: f32| Transform {
+            position: Vec3::new(x, 0.0, 0.0),
+            ..Default::default()
+        };
+
+        let mut interpolated_transform =
+            RendererInterpolatedTransform::new(start, transform_at_x(0.0));
+        assert!(interpolated_transform.is_settled(start));
+
+        interpolated_transform.submit(start + interval, transform_at_x(10.0));
+        // an older transform does not replace the latest one
+        interpolated_transform.submit(start, transform_at_x(-10.0));
+
+        assert_eq!(
+            0.0,
+            interpolated_transform
+                .transform_at(start + interval)
+                .position
+                .x
+        );
+        assert!(
+            (interpolated_transform
+                .transform_at(start + interval + interval / 2)
+                .position
+                .x
+                - 5.0)
+                .abs()
+                < 1e-3
+        );
+        assert!(!interpolated_transform.is_settled(start + interval + interval / 2));
+
+        assert_eq!(
+            10.0,
+            interpolated_transform
+                .transform_at(start + interval * 3)
+                .position
+                .x
+        );
+        assert!(interpolated_transform.is_settled(start + interval * 3));
+    }
+}
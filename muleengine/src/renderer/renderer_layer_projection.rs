@@ -0,0 +1,151 @@
+use vek::{FrustumPlanes, Mat4};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ScreenSpaceUnits {
+    // the origin is the top left corner of the viewport and the y axis points downwards
+    Pixels,
+    // both axes are in [-1, 1] regardless of the aspect ratio, the y axis points upwards
+    Ndc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RendererLayerProjection {
+    Perspective {
+        fov_y_degrees: f32,
+        near: f32,
+        far: f32,
+    },
+    // the width of the view volume follows the aspect ratio of the window
+    Orthographic {
+        height: f32,
+        near: f32,
+        far: f32,
+    },
+    // the objects placed relative to the viewport keep their positions when the window is resized,
+    // the view volume spans the z range [-1, 1]
+    ScreenSpace {
+        units: ScreenSpaceUnits,
+    },
+    Custom {
+        projection_matrix: Mat4<f32>,
+    },
+}
+
+impl RendererLayerProjection {
+    pub fn compute_projection_matrix(&self, width: usize, height: usize) -> Mat4<f32> {
+        let width = width.max(1) as f32;
+        let height = height.max(1) as f32;
+
+        match self {
+            Self::Perspective {
+                fov_y_degrees,
+                near,
+                far,
+            } => {
+                Mat4::perspective_fov_rh_zo(fov_y_degrees.to_radians(), width, height, *near, *far)
+            }
+            Self::Orthographic {
+                height: view_height,
+                near,
+                far,
+            } => {
+                let half_height = view_height / 2.0;
+                let half_width = half_height * width / height;
+
+                Mat4::orthographic_rh_zo(FrustumPlanes {
+                    left: -half_width,
+                    right: half_width,
+                    bottom: -half_height,
+                    top: half_height,
+                    near: *near,
+                    far: *far,
+                })
+            }
+            Self::ScreenSpace { units } => {
+                let (left, right, bottom, top) = match units {
+                    ScreenSpaceUnits::Pixels => (0.0, width, height, 0.0),
+                    ScreenSpaceUnits::Ndc => (-1.0, 1.0, -1.0, 1.0),
+                };
+
+                Mat4::orthographic_rh_zo(FrustumPlanes {
+                    left,
+                    right,
+                    bottom,
+                    top,
+                    near: 1.0,
+                    far: -1.0,
+                })
+            }
+            Self::Custom { projection_matrix } => *projection_matrix,
+        }
+    }
+}
+
+// maps the depth d of a zero to one projection to 1 - d
+pub fn reverse_depth(projection_matrix: Mat4<f32>) -> Mat4<f32> {
+    Mat4::new(
+        1.0, 0.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, -1.0, 1.0, //
+        0.0, 0.0, 0.0, 1.0,
+    ) * projection_matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use vek::Vec4;
+
+    use super::{reverse_depth, RendererLayerProjection, ScreenSpaceUnits};
+
+    #[test]
+    fn orthographic_projection_follows_the_aspect_ratio_of_the_window() {
+        let projection = RendererLayerProjection::Orthographic {
+            height: 10.0,
+            near: 0.1,
+            far: 100.0,
+        };
+
+        let projection_matrix = projection.compute_projection_matrix(800, 400);
+
+        let top_right = projection_matrix * Vec4::new(10.0, 5.0, -1.0, 1.0);
+        assert!((top_right.x - 1.0).abs() < 1e-5);
+        assert!((top_right.y - 1.0).abs() < 1e-5);
+        assert_eq!(top_right.w, 1.0);
+    }
+
+    #[test]
+    fn pixel_screen_space_projection_maps_the_corners_of_the_viewport() {
+        let projection = RendererLayerProjection::ScreenSpace {
+            units: ScreenSpaceUnits::Pixels,
+        };
+
+        for (width, height) in [(800, 400), (1920, 1080)] {
+            let projection_matrix = projection.compute_projection_matrix(width, height);
+
+            let top_left = projection_matrix * Vec4::new(0.0, 0.0, 0.0, 1.0);
+            assert!((top_left.x + 1.0).abs() < 1e-5);
+            assert!((top_left.y - 1.0).abs() < 1e-5);
+
+            let bottom_right = projection_matrix * Vec4::new(width as f32, height as f32, 0.0, 1.0);
+            assert!((bottom_right.x - 1.0).abs() < 1e-5);
+            assert!((bottom_right.y + 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn reversed_depth_is_one_at_the_near_plane_and_zero_at_the_far_plane() {
+        let projection = RendererLayerProjection::Perspective {
+            fov_y_degrees: 60.0,
+            near: 0.1,
+            far: 1000.0,
+        };
+
+        let projection_matrix = reverse_depth(projection.compute_projection_matrix(800, 400));
+
+        let near = projection_matrix * Vec4::new(0.0, 0.0, -0.1, 1.0);
+        assert!((near.z / near.w - 1.0).abs() < 1e-5);
+
+        let far = projection_matrix * Vec4::new(0.0, 0.0, -1000.0, 1.0);
+        assert!((far.z / far.w).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,32 @@
+use vek::Vec4;
+
+use super::renderer_layer_stencil::RendererLayerStencil;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererLayerSettings {
+    // the color buffer is only cleared before drawing the layer if a clear color is given
+    pub clear_color: Option<Vec4<f32>>,
+    pub clear_depth: bool,
+    pub exposure: f32,
+    // the renderer objects whose bounding boxes were hidden in the previous frame are skipped,
+    // it pays off for scenes with many occluders, e.g. indoor scenes
+    pub occlusion_culling: bool,
+    // without a stencil the objects of the layer neither test nor write the stencil buffer
+    pub stencil: Option<RendererLayerStencil>,
+    // the depth is 1 at the near plane and 0 at the far plane, it keeps the precision for distant far planes,
+    // the depth buffer of the layer has to be cleared by its own clear_depth, since clear steps clear it to 1
+    pub reverse_z: bool,
+}
+
+impl Default for RendererLayerSettings {
+    fn default() -> Self {
+        Self {
+            clear_color: None,
+            clear_depth: false,
+            exposure: 1.0,
+            occlusion_culling: false,
+            stencil: None,
+            reverse_z: false,
+        }
+    }
+}
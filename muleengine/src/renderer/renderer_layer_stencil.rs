@@ -0,0 +1,126 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RendererStencilFunction {
+    Never,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    Always,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RendererStencilOperation {
+    Keep,
+    Zero,
+    Replace,
+    Increment,
+    IncrementWrap,
+    Decrement,
+    DecrementWrap,
+    Invert,
+}
+
+// the stencil test of the objects of a layer, the masked reference is compared to the masked stored value,
+// e.g. an outline is drawn by writing the object into the stencil buffer with one layer
+// and drawing its enlarged copy with another layer where the stored value is not equal to the reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RendererLayerStencil {
+    pub function: RendererStencilFunction,
+    pub reference: u8,
+    pub test_mask: u8,
+    pub write_mask: u8,
+    pub stencil_fail: RendererStencilOperation,
+    pub depth_fail: RendererStencilOperation,
+    pub depth_pass: RendererStencilOperation,
+}
+
+impl RendererLayerStencil {
+    // the drawn pixels store the reference
+    pub fn write(reference: u8) -> Self {
+        Self {
+            function: RendererStencilFunction::Always,
+            reference,
+            test_mask: u8::MAX,
+            write_mask: u8::MAX,
+            stencil_fail: RendererStencilOperation::Keep,
+            depth_fail: RendererStencilOperation::Keep,
+            depth_pass: RendererStencilOperation::Replace,
+        }
+    }
+
+    // only the pixels that pass the test are drawn, the stencil buffer is not changed
+    pub fn test(function: RendererStencilFunction, reference: u8) -> Self {
+        Self {
+            function,
+            reference,
+            test_mask: u8::MAX,
+            write_mask: 0,
+            stencil_fail: RendererStencilOperation::Keep,
+            depth_fail: RendererStencilOperation::Keep,
+            depth_pass: RendererStencilOperation::Keep,
+        }
+    }
+
+    pub fn passes(&self, stored_value: u8) -> bool {
+        let reference = self.reference & self.test_mask;
+        let stored_value = stored_value & self.test_mask;
+
+        match self.function {
+            RendererStencilFunction::Never => false,
+            RendererStencilFunction::Less => reference < stored_value,
+            RendererStencilFunction::LessOrEqual => reference <= stored_value,
+            RendererStencilFunction::Greater => reference > stored_value,
+            RendererStencilFunction::GreaterOrEqual => reference >= stored_value,
+            RendererStencilFunction::Equal => reference == stored_value,
+            RendererStencilFunction::NotEqual => reference != stored_value,
+            RendererStencilFunction::Always => true,
+        }
+    }
+
+    // only the bits of the write mask are changed
+    pub fn updated_value(&self, operation: RendererStencilOperation, stored_value: u8) -> u8 {
+        let new_value = match operation {
+            RendererStencilOperation::Keep => stored_value,
+            RendererStencilOperation::Zero => 0,
+            RendererStencilOperation::Replace => self.reference,
+            RendererStencilOperation::Increment => stored_value.saturating_add(1),
+            RendererStencilOperation::IncrementWrap => stored_value.wrapping_add(1),
+            RendererStencilOperation::Decrement => stored_value.saturating_sub(1),
+            RendererStencilOperation::DecrementWrap => stored_value.wrapping_sub(1),
+            RendererStencilOperation::Invert => !stored_value,
+        };
+
+        (stored_value & !self.write_mask) | (new_value & self.write_mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RendererLayerStencil, RendererStencilFunction, RendererStencilOperation};
+
+    #[test]
+    fn stencil_test_and_write_use_the_masks() {
+        let write = RendererLayerStencil::write(1);
+        assert!(write.passes(0));
+        assert_eq!(1, write.updated_value(write.depth_pass, 0));
+        assert_eq!(0, write.updated_value(write.depth_fail, 0));
+
+        let test = RendererLayerStencil::test(RendererStencilFunction::NotEqual, 1);
+        assert!(test.passes(0));
+        assert!(!test.passes(1));
+        assert_eq!(1, test.updated_value(RendererStencilOperation::Zero, 1));
+
+        let masked = RendererLayerStencil {
+            test_mask: 0x0f,
+            write_mask: 0x0f,
+            ..RendererLayerStencil::test(RendererStencilFunction::Equal, 0x01)
+        };
+        assert!(masked.passes(0xf1));
+        assert_eq!(
+            0xf0,
+            masked.updated_value(RendererStencilOperation::Zero, 0xf1)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::ops::Range;
+
+use vek::Vec3;
+
+// the new values of a range of vertices, the attributes that are none are kept as they are
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RendererMeshVertices {
+    pub positions: Vec<Vec3<f32>>,
+    pub normals: Option<Vec<Vec3<f32>>>,
+    pub tangents: Option<Vec<Vec3<f32>>>,
+}
+
+impl RendererMeshVertices {
+    pub fn new(positions: Vec<Vec3<f32>>) -> Self {
+        Self {
+            positions,
+            normals: None,
+            tangents: None,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: Vec<Vec3<f32>>) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn with_tangents(mut self, tangents: Vec<Vec3<f32>>) -> Self {
+        self.tangents = Some(tangents);
+        self
+    }
+
+    pub fn validate(
+        &self,
+        vertex_range: &Range<usize>,
+        number_of_vertices: usize,
+    ) -> Result<(), String> {
+        if vertex_range.start > vertex_range.end || vertex_range.end > number_of_vertices {
+            return Err(format!(
+                "invalid vertex range, range = {vertex_range:?}, number_of_vertices = {number_of_vertices}"
+            ));
+        }
+
+        let attributes = [
+            ("positions", Some(&self.positions)),
+            ("normals", self.normals.as_ref()),
+            ("tangents", self.tangents.as_ref()),
+        ];
+        for (name, values) in attributes {
+            if let Some(values) = values {
+                if values.len() != vertex_range.len() {
+                    return Err(format!(
+                        "number of {name} does not match the vertex range, range = {vertex_range:?}, number_of_values = {}",
+                        values.len()
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vek::Vec3;
+
+    use super::RendererMeshVertices;
+
+    #[test]
+    fn validate_vertex_range_and_number_of_values() {
+        let vertices = RendererMeshVertices::new(vec![Vec3::zero(); 2]);
+
+        assert!(vertices.validate(&(1..3), 3).is_ok());
+        assert!(vertices.validate(&(2..4), 3).is_err());
+        assert!(vertices.validate(&(0..3), 3).is_err());
+
+        let vertices = vertices.with_normals(vec![Vec3::unit_y(); 1]);
+        assert!(vertices.validate(&(1..3), 3).is_err());
+    }
+}
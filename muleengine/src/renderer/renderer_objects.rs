@@ -1,10 +1,13 @@
 macro_rules! renderer_object_mod {
     ( $mod_name:ident, $trait_name:ident, $handler_name:ident, $release_fn:ident, $trait_name_literal:literal ) => {
         pub mod $mod_name {
-            use std::{cmp::Ordering, fmt::Debug, sync::Arc};
+            use std::{backtrace::Backtrace, cmp::Ordering, fmt::Debug, sync::Arc};
 
             use crate::{
                 bytifex_utils::{cast::AsAny, containers::object_pool::ObjectPoolIndex},
+                handler_leak_probe::{
+                    capture_creation_backtrace, HandlerLeakProbe, HandlerLeakProbeSource,
+                },
                 renderer::renderer_system::RendererClient,
             };
 
@@ -14,6 +17,7 @@ macro_rules! renderer_object_mod {
             pub(crate) struct HandlerDestructor {
                 pub(crate) object_pool_index: ObjectPoolIndex,
                 renderer_client: RendererClient,
+                creation_backtrace: Option<Arc<Backtrace>>,
             }
 
             #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -27,10 +31,21 @@ macro_rules! renderer_object_mod {
                     Self(Arc::new(HandlerDestructor {
                         object_pool_index,
                         renderer_client,
+                        creation_backtrace: capture_creation_backtrace(),
                     }))
                 }
             }
 
+            impl HandlerLeakProbeSource for $handler_name {
+                fn leak_probe(&self) -> HandlerLeakProbe {
+                    HandlerLeakProbe::new(
+                        stringify!($handler_name),
+                        &self.0,
+                        self.0.creation_backtrace.clone(),
+                    )
+                }
+            }
+
             impl Debug for HandlerDestructor {
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                     f.debug_struct("HandlerDestructor")
@@ -116,6 +131,14 @@ renderer_object_mod!(
     "RendererObject"
 );
 
+renderer_object_mod!(
+    renderer_probe,
+    RendererProbe,
+    RendererProbeHandler,
+    release_probe,
+    "RendererProbe"
+);
+
 renderer_object_mod!(
     renderer_shader,
     RendererShader,
@@ -124,6 +147,22 @@ renderer_object_mod!(
     "RendererShader"
 );
 
+renderer_object_mod!(
+    renderer_texture,
+    RendererTexture,
+    RendererTextureHandler,
+    release_render_texture,
+    "RendererTexture"
+);
+
+renderer_object_mod!(
+    renderer_window,
+    RendererWindow,
+    RendererWindowHandler,
+    release_window,
+    "RendererWindow"
+);
+
 renderer_object_mod!(
     renderer_transform,
     RendererTransform,
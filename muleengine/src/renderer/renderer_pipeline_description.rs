@@ -0,0 +1,323 @@
+use std::{collections::BTreeMap, io::Read, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use vek::{Mat4, Vec2, Vec4};
+
+use super::{
+    renderer_pipeline_step::RendererPipelineStep, RendererCameraHandler, RendererLayerHandler,
+    RendererShaderHandler, RendererTextureHandler,
+};
+
+// the handlers and the projections are referred to by their names in the description,
+// they are resolved with the RendererPipelineHandlers when the steps are built
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RendererPipelineStepDescription {
+    Clear {
+        depth: bool,
+        color: bool,
+        #[serde(default)]
+        stencil: bool,
+        #[serde(default)]
+        clear_color: [f32; 4],
+
+        #[serde(default = "full_viewport_start_ndc")]
+        viewport_start_ndc: [f32; 2],
+        #[serde(default = "full_viewport_end_ndc")]
+        viewport_end_ndc: [f32; 2],
+    },
+    Draw {
+        renderer_layer: String,
+
+        #[serde(default = "full_viewport_start_ndc")]
+        viewport_start_ndc: [f32; 2],
+        #[serde(default = "full_viewport_end_ndc")]
+        viewport_end_ndc: [f32; 2],
+
+        projection: String,
+    },
+    // the light projection is computed for a viewport of resolution x resolution pixels
+    ShadowPass {
+        renderer_layer: String,
+        light_camera: String,
+
+        resolution: usize,
+        light_projection: String,
+    },
+    DrawToTexture {
+        renderer_layer: String,
+        renderer_texture: String,
+
+        projection: String,
+    },
+    PostProcess {
+        shader: String,
+        #[serde(default)]
+        input_textures: Vec<String>,
+    },
+    DrawDebugLines {
+        renderer_layer: String,
+
+        #[serde(default = "full_viewport_start_ndc")]
+        viewport_start_ndc: [f32; 2],
+        #[serde(default = "full_viewport_end_ndc")]
+        viewport_end_ndc: [f32; 2],
+
+        projection: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum RendererPipelineDescriptionError {
+    CannotOpenAsset { path: String },
+    CannotParseDescription { msg: String },
+    CannotSerializeDescription { msg: String },
+    UnknownRendererLayer { name: String },
+    UnknownCamera { name: String },
+    UnknownTexture { name: String },
+    UnknownShader { name: String },
+    UnknownProjection { name: String },
+}
+
+#[derive(Clone, Default)]
+pub struct RendererPipelineHandlers {
+    pub renderer_layers: BTreeMap<String, RendererLayerHandler>,
+    pub cameras: BTreeMap<String, RendererCameraHandler>,
+    pub textures: BTreeMap<String, RendererTextureHandler>,
+    pub shaders: BTreeMap<String, RendererShaderHandler>,
+    pub projections: BTreeMap<String, Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>>,
+}
+
+fn full_viewport_start_ndc() -> [f32; 2] {
+    [0.0, 0.0]
+}
+
+fn full_viewport_end_ndc() -> [f32; 2] {
+    [1.0, 1.0]
+}
+
+// the description is a list of steps in the ron format
+pub fn parse_renderer_pipeline_description(
+    reader: impl Read,
+) -> Result<Vec<RendererPipelineStepDescription>, RendererPipelineDescriptionError> {
+    ron::de::from_reader(reader).map_err(|e| {
+        RendererPipelineDescriptionError::CannotParseDescription { msg: e.to_string() }
+    })
+}
+
+pub fn serialize_renderer_pipeline_description(
+    descriptions: &[RendererPipelineStepDescription],
+) -> Result<String, RendererPipelineDescriptionError> {
+    ron::ser::to_string_pretty(descriptions, ron::ser::PrettyConfig::default()).map_err(|e| {
+        RendererPipelineDescriptionError::CannotSerializeDescription { msg: e.to_string() }
+    })
+}
+
+impl RendererPipelineHandlers {
+    pub fn build_renderer_pipeline(
+        &self,
+        descriptions: &[RendererPipelineStepDescription],
+    ) -> Result<Vec<RendererPipelineStep>, RendererPipelineDescriptionError> {
+        descriptions
+            .iter()
+            .map(|description| self.build_renderer_pipeline_step(description))
+            .collect()
+    }
+
+    fn build_renderer_pipeline_step(
+        &self,
+        description: &RendererPipelineStepDescription,
+    ) -> Result<RendererPipelineStep, RendererPipelineDescriptionError> {
+        let step = match description {
+            RendererPipelineStepDescription::Clear {
+                depth,
+                color,
+                stencil,
+                clear_color,
+                viewport_start_ndc,
+                viewport_end_ndc,
+            } => RendererPipelineStep::Clear {
+                depth: *depth,
+                color: *color,
+                stencil: *stencil,
+                clear_color: Vec4::from(*clear_color),
+
+                viewport_start_ndc: Vec2::from(*viewport_start_ndc),
+                viewport_end_ndc: Vec2::from(*viewport_end_ndc),
+            },
+            RendererPipelineStepDescription::Draw {
+                renderer_layer,
+                viewport_start_ndc,
+                viewport_end_ndc,
+                projection,
+            } => RendererPipelineStep::Draw {
+                renderer_layer_handler: self.renderer_layer(renderer_layer)?,
+
+                viewport_start_ndc: Vec2::from(*viewport_start_ndc),
+                viewport_end_ndc: Vec2::from(*viewport_end_ndc),
+
+                compute_projection_matrix: self.projection(projection)?,
+            },
+            RendererPipelineStepDescription::ShadowPass {
+                renderer_layer,
+                light_camera,
+                resolution,
+                light_projection,
+            } => RendererPipelineStep::ShadowPass {
+                renderer_layer_handler: self.renderer_layer(renderer_layer)?,
+                light_camera_handler: self.camera(light_camera)?,
+
+                resolution: *resolution,
+                light_projection_matrix: self.projection(light_projection)?(
+                    *resolution,
+                    *resolution,
+                ),
+            },
+            RendererPipelineStepDescription::DrawToTexture {
+                renderer_layer,
+                renderer_texture,
+                projection,
+            } => RendererPipelineStep::DrawToTexture {
+                renderer_layer_handler: self.renderer_layer(renderer_layer)?,
+                renderer_texture_handler: self.texture(renderer_texture)?,
+
+                compute_projection_matrix: self.projection(projection)?,
+            },
+            RendererPipelineStepDescription::PostProcess {
+                shader,
+                input_textures,
+            } => RendererPipelineStep::PostProcess {
+                shader_handler: self.shader(shader)?,
+                input_texture_handlers: input_textures
+                    .iter()
+                    .map(|input_texture| self.texture(input_texture))
+                    .collect::<Result<_, _>>()?,
+            },
+            RendererPipelineStepDescription::DrawDebugLines {
+                renderer_layer,
+                viewport_start_ndc,
+                viewport_end_ndc,
+                projection,
+            } => RendererPipelineStep::DrawDebugLines {
+                renderer_layer_handler: self.renderer_layer(renderer_layer)?,
+
+                viewport_start_ndc: Vec2::from(*viewport_start_ndc),
+                viewport_end_ndc: Vec2::from(*viewport_end_ndc),
+
+                compute_projection_matrix: self.projection(projection)?,
+            },
+        };
+
+        Ok(step)
+    }
+
+    fn renderer_layer(
+        &self,
+        name: &str,
+    ) -> Result<RendererLayerHandler, RendererPipelineDescriptionError> {
+        self.renderer_layers.get(name).cloned().ok_or_else(|| {
+            RendererPipelineDescriptionError::UnknownRendererLayer {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    fn camera(
+        &self,
+        name: &str,
+    ) -> Result<RendererCameraHandler, RendererPipelineDescriptionError> {
+        self.cameras.get(name).cloned().ok_or_else(|| {
+            RendererPipelineDescriptionError::UnknownCamera {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    fn texture(
+        &self,
+        name: &str,
+    ) -> Result<RendererTextureHandler, RendererPipelineDescriptionError> {
+        self.textures.get(name).cloned().ok_or_else(|| {
+            RendererPipelineDescriptionError::UnknownTexture {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    fn shader(
+        &self,
+        name: &str,
+    ) -> Result<RendererShaderHandler, RendererPipelineDescriptionError> {
+        self.shaders.get(name).cloned().ok_or_else(|| {
+            RendererPipelineDescriptionError::UnknownShader {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    fn projection(
+        &self,
+        name: &str,
+    ) -> Result<
+        Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>,
+        RendererPipelineDescriptionError,
+    > {
+        self.projections.get(name).cloned().ok_or_else(|| {
+            RendererPipelineDescriptionError::UnknownProjection {
+                name: name.to_string(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_renderer_pipeline_description, serialize_renderer_pipeline_description,
+        RendererPipelineDescriptionError, RendererPipelineHandlers,
+        RendererPipelineStepDescription,
+    };
+
+    #[test]
+    fn pipeline_description_survives_a_round_trip_and_reports_unknown_names() {
+        let descriptions = parse_renderer_pipeline_description(
+            r#"[
+                Clear(depth: true, color: true),
+                Draw(renderer_layer: "main", projection: "perspective"),
+            ]"#
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            descriptions,
+            vec![
+                RendererPipelineStepDescription::Clear {
+                    depth: true,
+                    color: true,
+                    stencil: false,
+                    clear_color: [0.0, 0.0, 0.0, 0.0],
+                    viewport_start_ndc: [0.0, 0.0],
+                    viewport_end_ndc: [1.0, 1.0],
+                },
+                RendererPipelineStepDescription::Draw {
+                    renderer_layer: "main".to_string(),
+                    viewport_start_ndc: [0.0, 0.0],
+                    viewport_end_ndc: [1.0, 1.0],
+                    projection: "perspective".to_string(),
+                },
+            ]
+        );
+
+        let serialized = serialize_renderer_pipeline_description(&descriptions).unwrap();
+        assert_eq!(
+            parse_renderer_pipeline_description(serialized.as_bytes()).unwrap(),
+            descriptions
+        );
+
+        let result = RendererPipelineHandlers::default().build_renderer_pipeline(&descriptions);
+        assert!(matches!(
+            result,
+            Err(RendererPipelineDescriptionError::UnknownRendererLayer { name }) if name == "main"
+        ));
+    }
+}
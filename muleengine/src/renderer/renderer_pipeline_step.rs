@@ -1,13 +1,19 @@
 use std::sync::Arc;
 
-use vek::{Mat4, Vec2};
+use bytifex_utils::containers::object_pool::ObjectPoolIndex;
+use vek::{Mat4, Vec2, Vec4};
 
-use super::RendererLayerHandler;
+use super::{
+    RendererCameraHandler, RendererLayerHandler, RendererShaderHandler, RendererTextureHandler,
+};
 
 pub enum RendererPipelineStep {
+    // the color buffer is cleared with the clear color if color is set
     Clear {
         depth: bool,
         color: bool,
+        stencil: bool,
+        clear_color: Vec4<f32>,
 
         viewport_start_ndc: Vec2<f32>,
         viewport_end_ndc: Vec2<f32>,
@@ -20,4 +26,230 @@ pub enum RendererPipelineStep {
 
         compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>,
     },
+    // renders the depth of the layer from the light camera into a shadow map,
+    // the shadow map is bound by the subsequent Draw steps
+    ShadowPass {
+        renderer_layer_handler: RendererLayerHandler,
+        light_camera_handler: RendererCameraHandler,
+
+        resolution: usize,
+        light_projection_matrix: Mat4<f32>,
+    },
+    // renders the layer into an offscreen texture that can be used in materials
+    DrawToTexture {
+        renderer_layer_handler: RendererLayerHandler,
+        renderer_texture_handler: RendererTextureHandler,
+
+        compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>,
+    },
+    // draws a fullscreen pass with the shader, which samples what the previous steps rendered
+    // and the input textures, the steps after the last post process step draw over its result
+    PostProcess {
+        shader_handler: RendererShaderHandler,
+        input_texture_handlers: Vec<RendererTextureHandler>,
+    },
+    // draws the debug lines from the camera of the layer
+    DrawDebugLines {
+        renderer_layer_handler: RendererLayerHandler,
+
+        viewport_start_ndc: Vec2<f32>,
+        viewport_end_ndc: Vec2<f32>,
+
+        compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>,
+    },
+}
+
+impl RendererPipelineStep {
+    pub fn viewport_ndc(&self) -> Option<(Vec2<f32>, Vec2<f32>)> {
+        match self {
+            RendererPipelineStep::Clear {
+                viewport_start_ndc,
+                viewport_end_ndc,
+                ..
+            }
+            | RendererPipelineStep::Draw {
+                viewport_start_ndc,
+                viewport_end_ndc,
+                ..
+            }
+            | RendererPipelineStep::DrawDebugLines {
+                viewport_start_ndc,
+                viewport_end_ndc,
+                ..
+            } => Some((*viewport_start_ndc, *viewport_end_ndc)),
+            RendererPipelineStep::ShadowPass { .. }
+            | RendererPipelineStep::DrawToTexture { .. }
+            | RendererPipelineStep::PostProcess { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InvalidPipelineReason {
+    InvalidRendererLayer(RendererLayerHandler),
+    InvalidRendererCamera(RendererCameraHandler),
+    InvalidRendererShader(RendererShaderHandler),
+    InvalidRendererTexture(RendererTextureHandler),
+    // the texture is drawn into by a later step only, so the step would sample an empty texture,
+    // e.g. the input of a post process step, the render texture of a material in the layer of a
+    // draw step or the shadow map of the layer
+    TextureConsumedBeforeProduced { producer_step_index: usize },
+}
+
+// returns the index of the step that uses a render texture before the pipeline draws into it,
+// the render textures of the materials are the ones of the objects that are in the layer when
+// the pipeline is set
+pub fn find_texture_consumed_before_produced(
+    steps: &[RendererPipelineStep],
+    material_render_textures_of_layer: impl Fn(&RendererLayerHandler) -> Vec<ObjectPoolIndex>,
+) -> Option<(usize, InvalidPipelineReason)> {
+    let texture_consumed_before_produced = |step_index, producer_step_index| {
+        Some((
+            step_index,
+            InvalidPipelineReason::TextureConsumedBeforeProduced {
+                producer_step_index,
+            },
+        ))
+    };
+
+    for (step_index, step) in steps.iter().enumerate() {
+        let consumed_textures = match step {
+            RendererPipelineStep::PostProcess {
+                input_texture_handlers,
+                ..
+            } => input_texture_handlers
+                .iter()
+                .map(|input_texture_handler| input_texture_handler.0.object_pool_index)
+                .collect(),
+            RendererPipelineStep::Draw {
+                renderer_layer_handler,
+                ..
+            }
+            | RendererPipelineStep::DrawToTexture {
+                renderer_layer_handler,
+                ..
+            } => {
+                // the shadow map is bound by the subsequent draw steps only
+                let is_shadow_pass_of_layer = |step: &RendererPipelineStep| {
+                    matches!(
+                        step,
+                        RendererPipelineStep::ShadowPass {
+                            renderer_layer_handler: shadow_renderer_layer_handler,
+                            ..
+                        } if shadow_renderer_layer_handler == renderer_layer_handler
+                    )
+                };
+                if !steps[..step_index].iter().any(is_shadow_pass_of_layer) {
+                    if let Some(producer_step_index) =
+                        steps[step_index..].iter().position(is_shadow_pass_of_layer)
+                    {
+                        return texture_consumed_before_produced(
+                            step_index,
+                            step_index + producer_step_index,
+                        );
+                    }
+                }
+
+                material_render_textures_of_layer(renderer_layer_handler)
+            }
+            RendererPipelineStep::Clear { .. }
+            | RendererPipelineStep::ShadowPass { .. }
+            | RendererPipelineStep::DrawDebugLines { .. } => Vec::new(),
+        };
+
+        for consumed_texture in consumed_textures {
+            let producer_step_index = steps.iter().position(|step| {
+                matches!(
+                    step,
+                    RendererPipelineStep::DrawToTexture {
+                        renderer_texture_handler,
+                        ..
+                    } if renderer_texture_handler.0.object_pool_index == consumed_texture
+                )
+            });
+
+            // the textures that are not drawn by the pipeline are images or drawn elsewhere
+            if let Some(producer_step_index) = producer_step_index {
+                if producer_step_index > step_index {
+                    return texture_consumed_before_produced(step_index, producer_step_index);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// the viewport has to be inside the window and has to cover a non-empty area of it
+pub fn is_valid_viewport_ndc(viewport_start_ndc: Vec2<f32>, viewport_end_ndc: Vec2<f32>) -> bool {
+    viewport_start_ndc.x >= 0.0
+        && viewport_start_ndc.y >= 0.0
+        && viewport_end_ndc.x <= 1.0
+        && viewport_end_ndc.y <= 1.0
+        && viewport_start_ndc.x < viewport_end_ndc.x
+        && viewport_start_ndc.y < viewport_end_ndc.y
+}
+
+// splits the window into a grid of equally sized viewports, one for every player,
+// the first player is in the top left corner and the players follow each other row by row,
+// two players are put next to each other
+pub fn split_screen_viewports_ndc(player_count: usize) -> Vec<(Vec2<f32>, Vec2<f32>)> {
+    if player_count == 0 {
+        return Vec::new();
+    }
+
+    let column_count = (player_count as f32).sqrt().ceil() as usize;
+    let row_count = player_count.div_ceil(column_count);
+
+    let viewport_dimensions_ndc = Vec2::new(1.0 / column_count as f32, 1.0 / row_count as f32);
+
+    (0..player_count)
+        .map(|player_index| {
+            let column = player_index % column_count;
+            // the y axis of the ndc points upwards, so the first row is at the top
+            let row = row_count - 1 - player_index / column_count;
+
+            let viewport_start_ndc = Vec2::new(column as f32, row as f32) * viewport_dimensions_ndc;
+            (
+                viewport_start_ndc,
+                viewport_start_ndc + viewport_dimensions_ndc,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use vek::Vec2;
+
+    use super::{is_valid_viewport_ndc, split_screen_viewports_ndc};
+
+    #[test]
+    fn split_screen_viewports_cover_the_window_row_by_row_from_the_top_left() {
+        assert!(split_screen_viewports_ndc(0).is_empty());
+        assert_eq!(
+            vec![(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0))],
+            split_screen_viewports_ndc(1)
+        );
+        assert_eq!(
+            vec![
+                (Vec2::new(0.0, 0.0), Vec2::new(0.5, 1.0)),
+                (Vec2::new(0.5, 0.0), Vec2::new(1.0, 1.0)),
+            ],
+            split_screen_viewports_ndc(2)
+        );
+
+        let viewports = split_screen_viewports_ndc(3);
+        assert_eq!(
+            vec![
+                (Vec2::new(0.0, 0.5), Vec2::new(0.5, 1.0)),
+                (Vec2::new(0.5, 0.5), Vec2::new(1.0, 1.0)),
+                (Vec2::new(0.0, 0.0), Vec2::new(0.5, 0.5)),
+            ],
+            viewports
+        );
+        assert!(viewports
+            .iter()
+            .all(|(start, end)| is_valid_viewport_ndc(*start, *end)));
+    }
 }
@@ -1,15 +1,17 @@
 use std::sync::Arc;
 
 use bytifex_utils::sync::types::ArcRwLock;
-use vek::{Mat4, Vec2};
+use vek::{Mat4, Vec2, Vec4};
 
-use super::RendererLayer;
+use super::{RendererCamera, RendererLayer, RendererShader, RendererTexture};
 
 #[derive(Clone)]
 pub enum RendererPipelineStepImpl {
     Clear {
         depth: bool,
         color: bool,
+        stencil: bool,
+        clear_color: Vec4<f32>,
 
         viewport_start_ndc: Vec2<f32>,
         viewport_end_ndc: Vec2<f32>,
@@ -22,4 +24,51 @@ pub enum RendererPipelineStepImpl {
 
         compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>,
     },
+    ShadowPass {
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        light_camera: ArcRwLock<dyn RendererCamera>,
+
+        resolution: usize,
+        light_projection_matrix: Mat4<f32>,
+    },
+    DrawToTexture {
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        renderer_texture: ArcRwLock<dyn RendererTexture>,
+
+        compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>,
+    },
+    PostProcess {
+        shader: ArcRwLock<dyn RendererShader>,
+        input_textures: Vec<ArcRwLock<dyn RendererTexture>>,
+    },
+    DrawDebugLines {
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+
+        viewport_start_ndc: Vec2<f32>,
+        viewport_end_ndc: Vec2<f32>,
+
+        compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send + Sync>,
+    },
+}
+
+pub fn set_pipeline_step_clear_color(
+    steps: &mut [RendererPipelineStepImpl],
+    step_index: usize,
+    clear_color: Vec4<f32>,
+) -> Result<(), String> {
+    match steps.get_mut(step_index) {
+        Some(RendererPipelineStepImpl::Clear {
+            clear_color: step_clear_color,
+            ..
+        }) => {
+            *step_clear_color = clear_color;
+            Ok(())
+        }
+        Some(_) => Err(format!(
+            "Setting pipeline clear color, msg = the step is not a clear step, step_index = {step_index}"
+        )),
+        None => Err(format!(
+            "Setting pipeline clear color, msg = could not find the step, step_index = {step_index}"
+        )),
+    }
 }
@@ -0,0 +1,5 @@
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RendererProbeRefreshMode {
+    Manual,
+    EveryFrame,
+}
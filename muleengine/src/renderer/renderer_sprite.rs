@@ -0,0 +1,22 @@
+use vek::{Rect, Vec2, Vec4};
+
+// the position is the bottom left corner of the sprite in the space of the sprite batch,
+// the uv rect is normalized with its origin in the top left corner of the texture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererSprite {
+    pub position: Vec2<f32>,
+    pub size: Vec2<f32>,
+    pub uv_rect: Rect<f32, f32>,
+    pub color: Vec4<f32>,
+}
+
+impl Default for RendererSprite {
+    fn default() -> Self {
+        Self {
+            position: Vec2::zero(),
+            size: Vec2::one(),
+            uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            color: Vec4::one(),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use super::{
+    RendererCameraHandler, RendererGroupHandler, RendererLayerHandler, RendererMaterialHandler,
+    RendererMeshHandler, RendererObjectHandler, RendererProbeHandler, RendererShaderHandler,
+    RendererTextureHandler, RendererTransformHandler, RendererWindowHandler,
+};
+
+// the number of alive objects of the other pools are in RendererStats
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RendererStateDump {
+    pub number_of_renderer_layers: usize,
+    pub number_of_renderer_groups: usize,
+    pub number_of_renderer_objects: usize,
+    // an object that is added to two groups is counted twice
+    pub number_of_groups_in_layers: usize,
+    pub number_of_objects_in_groups: usize,
+    // the renderer objects whose handlers are dropped, but are still in the pool,
+    // the ones whose release is still queued are counted as well
+    pub number_of_orphaned_renderer_objects: usize,
+    // the links between the layers, the groups and the objects that are not mutual
+    pub inconsistencies: Vec<String>,
+}
+
+impl RendererStateDump {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty() && self.number_of_orphaned_renderer_objects == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RendererHandler {
+    Camera(RendererCameraHandler),
+    Group(RendererGroupHandler),
+    Layer(RendererLayerHandler),
+    Material(RendererMaterialHandler),
+    Mesh(RendererMeshHandler),
+    Object(RendererObjectHandler),
+    Probe(RendererProbeHandler),
+    Shader(RendererShaderHandler),
+    Texture(RendererTextureHandler),
+    Transform(RendererTransformHandler),
+    Window(RendererWindowHandler),
+}
+
+macro_rules! renderer_handler_from {
+    ( $variant:ident, $handler_name:ident ) => {
+        impl From<$handler_name> for RendererHandler {
+            fn from(handler: $handler_name) -> Self {
+                Self::$variant(handler)
+            }
+        }
+    };
+}
+
+renderer_handler_from!(Camera, RendererCameraHandler);
+renderer_handler_from!(Group, RendererGroupHandler);
+renderer_handler_from!(Layer, RendererLayerHandler);
+renderer_handler_from!(Material, RendererMaterialHandler);
+renderer_handler_from!(Mesh, RendererMeshHandler);
+renderer_handler_from!(Object, RendererObjectHandler);
+renderer_handler_from!(Probe, RendererProbeHandler);
+renderer_handler_from!(Shader, RendererShaderHandler);
+renderer_handler_from!(Texture, RendererTextureHandler);
+renderer_handler_from!(Transform, RendererTransformHandler);
+renderer_handler_from!(Window, RendererWindowHandler);
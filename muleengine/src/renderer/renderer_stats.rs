@@ -0,0 +1,28 @@
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RendererStats {
+    pub number_of_textures: usize,
+    pub texture_memory_bytes: usize,
+    pub texture_memory_budget_bytes: Option<usize>,
+    pub number_of_evicted_textures: usize,
+
+    // collected during the last rendered frame
+    pub draw_calls: usize,
+    pub triangles: usize,
+    // the gpu time of every step of the main pipeline in the order of the steps, the renderers
+    // can report them a few frames late, None means that the step was not measured
+    pub pipeline_step_gpu_times_ms: Vec<Option<f32>>,
+
+    pub number_of_meshes: usize,
+    pub mesh_memory_bytes: usize,
+
+    // the number of alive objects in the pools of the renderer system
+    pub number_of_renderer_layers: usize,
+    pub number_of_renderer_groups: usize,
+    pub number_of_renderer_objects: usize,
+    pub number_of_transforms: usize,
+    pub number_of_materials: usize,
+    pub number_of_shaders: usize,
+    pub number_of_cameras: usize,
+    pub number_of_probes: usize,
+    pub number_of_render_textures: usize,
+}
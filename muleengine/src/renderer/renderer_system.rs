@@ -1,35 +1,64 @@
-use std::{collections::BTreeSet, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    sync::{Arc, Weak},
+    time::Instant,
+};
 
 use bytifex_utils::{
     containers::object_pool::{ObjectPool, ObjectPoolIndex},
     sync::types::{arc_rw_lock_new, ArcRwLock},
 };
 use method_taskifier::{
-    method_taskifier_impl,
-    task_channel::{TaskReceiver, TaskSender},
-    AsyncWorkerRunner, InvalidNumberOfExecutors,
+    method_taskifier_impl, task_channel::TaskSender, AsyncWorkerRunner, InvalidNumberOfExecutors,
 };
 use option_inspect_none::OptionInspectNone;
-use vek::Transform;
+use vek::{Transform, Vec2, Vec3, Vec4};
 
 use crate::{
-    mesh::{Material, Mesh},
+    aabb::AxisAlignedBoundingBox,
+    font::Font,
+    image::Image,
+    mesh::{Material, MaterialTextureType, Mesh},
     system_container::System,
     window_context::{Event, EventReceiver, WindowContext},
 };
 
 use super::{
+    particle_system::ParticleEmitterSettings,
+    renderer_batch::{RendererBatchCommand, RendererBatchResult},
+    renderer_camera_parameters::RendererCameraParameters,
+    renderer_config::RendererConfig,
+    renderer_debug_lines::{aabb_debug_lines, sphere_debug_lines, RendererDebugLine},
+    renderer_debug_render_mode::RendererDebugRenderMode,
+    renderer_detached_client::{
+        RendererDetachedClient, RendererDetachedCommand, RendererMessageQueue,
+    },
     renderer_impl::{RendererImpl, RendererImplAsync},
+    renderer_layer_projection::RendererLayerProjection,
+    renderer_layer_settings::RendererLayerSettings,
+    renderer_mesh_vertices::RendererMeshVertices,
     renderer_objects::{
         renderer_camera::RendererCameraHandler,
         renderer_layer::{RendererLayer, RendererLayerHandler},
+        renderer_object::HandlerDestructor as RendererObjectHandlerDestructor,
+    },
+    renderer_pipeline_step::{
+        find_texture_consumed_before_produced, is_valid_viewport_ndc, InvalidPipelineReason,
+        RendererPipelineStep,
     },
-    renderer_pipeline_step::RendererPipelineStep,
     renderer_pipeline_step_impl::RendererPipelineStepImpl,
+    renderer_probe_refresh_mode::RendererProbeRefreshMode,
+    renderer_sprite::RendererSprite,
+    renderer_state_dump::{RendererHandler, RendererStateDump},
+    renderer_stats::RendererStats,
+    renderer_text::layout_text,
+    renderer_uniform_value::RendererUniformValue,
     RendererCamera, RendererError, RendererGroup, RendererGroupHandler, RendererMaterial,
     RendererMaterialHandler, RendererMesh, RendererMeshHandler, RendererObject,
-    RendererObjectHandler, RendererShader, RendererShaderHandler, RendererTransform,
-    RendererTransformHandler,
+    RendererObjectHandler, RendererProbe, RendererProbeHandler, RendererShader,
+    RendererShaderHandler, RendererTexture, RendererTextureHandler, RendererTransform,
+    RendererTransformHandler, RendererWindow, RendererWindowHandler,
 };
 
 pub struct SyncRenderer {
@@ -59,6 +88,19 @@ pub(super) struct RendererGroupData {
 pub(super) struct RendererObjectData {
     pub(super) renderer_object: ArcRwLock<dyn RendererObject>,
     pub(super) contained_by_renderer_groups: BTreeSet<ObjectPoolIndex>,
+    // a weak reference, otherwise the renderer object would never be released
+    pub(super) handler: Weak<RendererObjectHandlerDestructor>,
+    pub(super) text: Option<RendererTextData>,
+    // the render textures of the material are checked when a pipeline is set
+    pub(super) material_index: Option<ObjectPoolIndex>,
+}
+
+// the atlas texture is owned by the text, so it is not in the texture pool
+pub(super) struct RendererTextData {
+    pub(super) font: ArcRwLock<dyn Font>,
+    pub(super) size: f32,
+    pub(super) color: Vec4<f32>,
+    pub(super) atlas_texture: ArcRwLock<dyn RendererTexture>,
 }
 
 pub(super) struct RendererPri<T: RendererImpl + ?Sized> {
@@ -70,9 +112,16 @@ pub(super) struct RendererPri<T: RendererImpl + ?Sized> {
     pub(super) renderer_shaders: ArcRwLock<ObjectPool<ArcRwLock<dyn RendererShader>>>,
     pub(super) renderer_meshes: ArcRwLock<ObjectPool<ArcRwLock<dyn RendererMesh>>>,
     pub(super) renderer_objects: ArcRwLock<ObjectPool<RendererObjectData>>,
+    pub(super) renderer_probes: ArcRwLock<ObjectPool<ArcRwLock<dyn RendererProbe>>>,
+    pub(super) renderer_textures: ArcRwLock<ObjectPool<ArcRwLock<dyn RendererTexture>>>,
+    pub(super) renderer_windows: ArcRwLock<ObjectPool<ArcRwLock<dyn RendererWindow>>>,
+
+    // the indices of the render textures that are set with set_material_render_texture
+    material_render_textures:
+        ArcRwLock<BTreeMap<ObjectPoolIndex, Vec<(MaterialTextureType, ObjectPoolIndex)>>>,
 
-    task_receiver: TaskReceiver<ChanneledTask>,
     task_sender: TaskSender<ChanneledTask>,
+    message_queue: RendererMessageQueue,
 
     renderer_impl: Box<T>,
 }
@@ -88,9 +137,14 @@ impl Clone for RendererPri<dyn RendererImplAsync> {
             renderer_shaders: self.renderer_shaders.clone(),
             renderer_meshes: self.renderer_meshes.clone(),
             renderer_objects: self.renderer_objects.clone(),
+            renderer_probes: self.renderer_probes.clone(),
+            renderer_textures: self.renderer_textures.clone(),
+            renderer_windows: self.renderer_windows.clone(),
+
+            material_render_textures: self.material_render_textures.clone(),
 
-            task_receiver: self.task_receiver.clone(),
             task_sender: self.task_sender.clone(),
+            message_queue: self.message_queue.clone(),
 
             renderer_impl: self.renderer_impl.box_clone(),
         }
@@ -101,15 +155,22 @@ impl SyncRenderer {
     pub fn new(
         renderer_impl: impl RendererImpl + 'static,
         window_context: ArcRwLock<dyn WindowContext>,
+        renderer_config: RendererConfig,
     ) -> Self {
-        Self::new_from_box(Box::new(renderer_impl), window_context)
+        Self::new_from_box(Box::new(renderer_impl), window_context, renderer_config)
     }
 
     pub fn new_from_box(
         renderer_impl: Box<dyn RendererImpl + 'static>,
         window_context: ArcRwLock<dyn WindowContext>,
+        renderer_config: RendererConfig,
     ) -> Self {
         let mut renderer_pri = RendererPri::new(renderer_impl);
+        let _ = renderer_pri
+            .set_renderer_config(renderer_config)
+            .inspect_err(|e| {
+                log::error!("SyncRenderer::new_from_box: set_renderer_config, error = {e:?}")
+            });
         let window_dimensions = window_context.read().window_dimensions();
         let _ = renderer_pri
             .window_dimensions_changed(window_dimensions.x, window_dimensions.y)
@@ -127,6 +188,10 @@ impl SyncRenderer {
     pub fn client(&self) -> RendererClient {
         self.renderer_pri.client()
     }
+
+    pub fn detached_client(&self) -> RendererDetachedClient {
+        self.renderer_pri.detached_client()
+    }
 }
 
 impl AsyncRenderer {
@@ -134,37 +199,44 @@ impl AsyncRenderer {
         number_of_executors: u8,
         renderer_impl: impl RendererImplAsync,
         window_context: ArcRwLock<dyn WindowContext>,
+        renderer_config: RendererConfig,
     ) -> Result<Self, InvalidNumberOfExecutors> {
         let renderer_impl = Box::new(renderer_impl);
 
         let mut renderer_pri = RendererPri::new(renderer_impl.box_clone());
+        let _ = renderer_pri
+            .set_renderer_config(renderer_config)
+            .inspect_err(|e| log::error!("AsyncRenderer::new: set_renderer_config, error = {e:?}"));
         let window_dimensions = window_context.read().window_dimensions();
         let _ = renderer_pri
             .window_dimensions_changed(window_dimensions.x, window_dimensions.y)
             .inspect_err(|e| {
                 log::error!("AsyncRenderer::new: window_dimensions_changed, error = {e:?}")
             });
-        let task_receiver = renderer_pri.task_receiver.clone();
         let mut event_receiver = window_context.read().event_receiver();
         event_receiver.stop();
 
         let worker_runner = {
             let renderer_pri = renderer_pri.clone();
-            let task_receiver = task_receiver.clone();
             let mut event_receiver = event_receiver.clone();
             event_receiver.stop();
 
             AsyncWorkerRunner::run_worker(number_of_executors, move || {
                 let mut renderer_pri = renderer_pri.clone();
-                let mut task_receiver = task_receiver.clone();
+                let message_queue = renderer_pri.message_queue.clone();
                 let event_receiver = event_receiver.clone();
 
                 || async move {
-                    let _ = renderer_pri
-                        .execute_channeled_tasks_from_queue_until_clients_dropped(
-                            &mut task_receiver,
-                        )
-                        .await;
+                    loop {
+                        let task = message_queue
+                            .recv_task(|command| renderer_pri.execute_detached_command(command))
+                            .await;
+                        let Some(task) = task else {
+                            break;
+                        };
+
+                        renderer_pri.execute_channeled_task(task);
+                    }
 
                     while let Ok(Some(event)) = event_receiver.try_pop() {
                         if let Event::Resized { width, height } = event {
@@ -190,6 +262,10 @@ impl AsyncRenderer {
     pub fn client(&self) -> RendererClient {
         self.renderer_pri.client()
     }
+
+    pub fn detached_client(&self) -> RendererDetachedClient {
+        self.renderer_pri.detached_client()
+    }
 }
 
 #[method_taskifier_impl(
@@ -210,9 +286,14 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             renderer_shaders: arc_rw_lock_new(ObjectPool::new()),
             renderer_meshes: arc_rw_lock_new(ObjectPool::new()),
             renderer_objects: arc_rw_lock_new(ObjectPool::new()),
+            renderer_probes: arc_rw_lock_new(ObjectPool::new()),
+            renderer_textures: arc_rw_lock_new(ObjectPool::new()),
+            renderer_windows: arc_rw_lock_new(ObjectPool::new()),
+
+            material_render_textures: arc_rw_lock_new(BTreeMap::new()),
 
-            task_receiver: receiver,
             task_sender: sender,
+            message_queue: RendererMessageQueue::new(receiver),
 
             renderer_impl,
         }
@@ -228,26 +309,227 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .map_err(RendererError::RendererImplError)
     }
 
+    pub fn set_renderer_config(
+        &mut self,
+        renderer_config: RendererConfig,
+    ) -> Result<(), RendererError> {
+        self.renderer_impl
+            .set_renderer_config(renderer_config)
+            .map_err(RendererError::RendererImplError)
+    }
+
     pub fn client(&self) -> RendererClient {
         RendererClient::new(self.task_sender.clone())
     }
 
-    #[method_taskifier_worker_fn]
-    fn set_renderer_pipeline(
-        &mut self,
+    pub fn detached_client(&self) -> RendererDetachedClient {
+        RendererDetachedClient::new(self.message_queue.clone())
+    }
+
+    fn execute_detached_command(&mut self, command: RendererDetachedCommand) {
+        let result = match command {
+            RendererDetachedCommand::UpdateTransform {
+                transform_handler,
+                new_transform,
+            } => self.update_transform(transform_handler, new_transform),
+            RendererDetachedCommand::UpdateTransformInterpolated {
+                transform_handler,
+                new_transform,
+                timestamp,
+            } => self.update_transform_interpolated(transform_handler, new_transform, timestamp),
+            RendererDetachedCommand::SetParticleEmitterSettings {
+                renderer_object_handler,
+                settings,
+            } => self.set_particle_emitter_settings(renderer_object_handler, settings),
+        };
+        let _ = result.inspect_err(|e| log::warn!("RendererDetachedCommand, msg = {e:?}"));
+    }
+
+    fn material_render_textures_of_layer(
+        &self,
+        renderer_layer_handler: &RendererLayerHandler,
+    ) -> Vec<ObjectPoolIndex> {
+        // in the order of the other workers, e.g. add_renderer_object_to_group
+        let renderer_objects = self.renderer_objects.read();
+        let renderer_groups = self.renderer_groups.read();
+        let renderer_layers = self.renderer_layers.read();
+        let material_render_textures = self.material_render_textures.read();
+
+        let Some(renderer_layer_data) =
+            renderer_layers.get_ref(renderer_layer_handler.0.object_pool_index)
+        else {
+            return Vec::new();
+        };
+
+        renderer_layer_data
+            .added_renderer_groups
+            .iter()
+            .filter_map(|renderer_group_index| renderer_groups.get_ref(*renderer_group_index))
+            .flat_map(|renderer_group_data| renderer_group_data.added_renderer_objects.iter())
+            .filter_map(|renderer_object_index| {
+                renderer_objects
+                    .get_ref(*renderer_object_index)?
+                    .material_index
+                    .and_then(|material_index| material_render_textures.get(&material_index))
+            })
+            .flat_map(|render_textures| {
+                render_textures
+                    .iter()
+                    .map(|(_, renderer_texture_index)| *renderer_texture_index)
+            })
+            .collect()
+    }
+
+    fn get_probe(
+        &self,
+        probe_handler: RendererProbeHandler,
+    ) -> Result<ArcRwLock<dyn RendererProbe>, RendererError> {
+        Ok(self
+            .renderer_probes
+            .read()
+            .get_ref(probe_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererProbeHandler(probe_handler))?
+            .clone())
+    }
+
+    fn get_renderer_texture(
+        &self,
+        renderer_texture_handler: RendererTextureHandler,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, RendererError> {
+        Ok(self
+            .renderer_textures
+            .read()
+            .get_ref(renderer_texture_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTextureHandler(
+                renderer_texture_handler,
+            ))?
+            .clone())
+    }
+
+    fn get_window(
+        &self,
+        window_handler: RendererWindowHandler,
+    ) -> Result<ArcRwLock<dyn RendererWindow>, RendererError> {
+        Ok(self
+            .renderer_windows
+            .read()
+            .get_ref(window_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererWindowHandler(window_handler))?
+            .clone())
+    }
+
+    fn get_renderer_layer(
+        &self,
+        renderer_layer_handler: RendererLayerHandler,
+    ) -> Result<ArcRwLock<dyn RendererLayer>, RendererError> {
+        Ok(self
+            .renderer_layers
+            .read()
+            .get_ref(renderer_layer_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererLayerHandler(
+                renderer_layer_handler,
+            ))?
+            .renderer_layer
+            .clone())
+    }
+
+    fn get_renderer_object(
+        &self,
+        renderer_object_handler: RendererObjectHandler,
+    ) -> Result<ArcRwLock<dyn RendererObject>, RendererError> {
+        Ok(self
+            .renderer_objects
+            .read()
+            .get_ref(renderer_object_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererObjectHandler(
+                renderer_object_handler,
+            ))?
+            .renderer_object
+            .clone())
+    }
+
+    fn add_renderer_object_data(
+        &self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        material_index: Option<ObjectPoolIndex>,
+    ) -> RendererObjectHandler {
+        let mut renderer_objects = self.renderer_objects.write();
+        let object_pool_index = renderer_objects.create_object(RendererObjectData {
+            renderer_object,
+            contained_by_renderer_groups: BTreeSet::new(),
+            handler: Weak::new(),
+            text: None,
+            material_index,
+        });
+
+        let renderer_object_handler = RendererObjectHandler::new(object_pool_index, self.client());
+        if let Some(renderer_object_data) = renderer_objects.get_mut(object_pool_index) {
+            renderer_object_data.handler = Arc::downgrade(&renderer_object_handler.0);
+        }
+
+        renderer_object_handler
+    }
+
+    fn create_renderer_pipeline_steps_impl(
+        &self,
         steps: Vec<RendererPipelineStep>,
-    ) -> Result<(), RendererError> {
+    ) -> Result<Vec<RendererPipelineStepImpl>, RendererError> {
+        // the whole pipeline is rejected before any of its steps is used
+        for step in steps.iter() {
+            if let Some((viewport_start_ndc, viewport_end_ndc)) = step.viewport_ndc() {
+                if !is_valid_viewport_ndc(viewport_start_ndc, viewport_end_ndc) {
+                    return Err(RendererError::InvalidViewport {
+                        viewport_start_ndc,
+                        viewport_end_ndc,
+                    });
+                }
+            }
+        }
+
+        if let Some((step_index, reason)) =
+            find_texture_consumed_before_produced(&steps, |renderer_layer_handler| {
+                self.material_render_textures_of_layer(renderer_layer_handler)
+            })
+        {
+            return Err(RendererError::InvalidPipeline { step_index, reason });
+        }
+
         let mut steps_impl = Vec::with_capacity(steps.capacity());
-        for step in steps {
+        for (step_index, step) in steps.into_iter().enumerate() {
+            // the invalid handlers are reported with the index of the step that refers to them
+            let invalid_pipeline = |error: RendererError| {
+                let reason = match error {
+                    RendererError::InvalidRendererLayerHandler(handler) => {
+                        InvalidPipelineReason::InvalidRendererLayer(handler)
+                    }
+                    RendererError::InvalidRendererCameraHandler(handler) => {
+                        InvalidPipelineReason::InvalidRendererCamera(handler)
+                    }
+                    RendererError::InvalidRendererShaderHandler(handler) => {
+                        InvalidPipelineReason::InvalidRendererShader(handler)
+                    }
+                    RendererError::InvalidRendererTextureHandler(handler) => {
+                        InvalidPipelineReason::InvalidRendererTexture(handler)
+                    }
+                    error => return error,
+                };
+
+                RendererError::InvalidPipeline { step_index, reason }
+            };
+
             let step_impl = match step {
                 RendererPipelineStep::Clear {
                     depth,
                     color,
+                    stencil,
+                    clear_color,
                     viewport_start_ndc,
                     viewport_end_ndc,
                 } => RendererPipelineStepImpl::Clear {
                     depth,
                     color,
+                    stencil,
+                    clear_color,
                     viewport_start_ndc,
                     viewport_end_ndc,
                 },
@@ -258,16 +540,102 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
                     compute_projection_matrix,
                 } => {
                     let renderer_layer = self
-                        .renderer_layers
+                        .get_renderer_layer(renderer_layer_handler)
+                        .map_err(invalid_pipeline)?;
+
+                    RendererPipelineStepImpl::Draw {
+                        renderer_layer,
+                        viewport_start_ndc,
+                        viewport_end_ndc,
+
+                        compute_projection_matrix,
+                    }
+                }
+                RendererPipelineStep::ShadowPass {
+                    renderer_layer_handler,
+                    light_camera_handler,
+                    resolution,
+                    light_projection_matrix,
+                } => {
+                    let renderer_layer = self
+                        .get_renderer_layer(renderer_layer_handler)
+                        .map_err(invalid_pipeline)?;
+
+                    let light_camera = self
+                        .renderer_cameras
+                        .read()
+                        .get_ref(light_camera_handler.0.object_pool_index)
+                        .ok_or_else(|| {
+                            invalid_pipeline(RendererError::InvalidRendererCameraHandler(
+                                light_camera_handler,
+                            ))
+                        })?
+                        .clone();
+
+                    RendererPipelineStepImpl::ShadowPass {
+                        renderer_layer,
+                        light_camera,
+                        resolution,
+                        light_projection_matrix,
+                    }
+                }
+                RendererPipelineStep::DrawToTexture {
+                    renderer_layer_handler,
+                    renderer_texture_handler,
+                    compute_projection_matrix,
+                } => {
+                    let renderer_layer = self
+                        .get_renderer_layer(renderer_layer_handler)
+                        .map_err(invalid_pipeline)?;
+                    let renderer_texture = self
+                        .get_renderer_texture(renderer_texture_handler)
+                        .map_err(invalid_pipeline)?;
+
+                    RendererPipelineStepImpl::DrawToTexture {
+                        renderer_layer,
+                        renderer_texture,
+                        compute_projection_matrix,
+                    }
+                }
+                RendererPipelineStep::PostProcess {
+                    shader_handler,
+                    input_texture_handlers,
+                } => {
+                    let shader = self
+                        .renderer_shaders
                         .read()
-                        .get_ref(renderer_layer_handler.0.object_pool_index)
+                        .get_ref(shader_handler.0.object_pool_index)
                         .ok_or_else(|| {
-                            RendererError::InvalidRendererLayerHandler(renderer_layer_handler)
+                            invalid_pipeline(RendererError::InvalidRendererShaderHandler(
+                                shader_handler,
+                            ))
                         })?
-                        .renderer_layer
                         .clone();
 
-                    RendererPipelineStepImpl::Draw {
+                    let mut input_textures = Vec::with_capacity(input_texture_handlers.len());
+                    for input_texture_handler in input_texture_handlers {
+                        input_textures.push(
+                            self.get_renderer_texture(input_texture_handler)
+                                .map_err(invalid_pipeline)?,
+                        );
+                    }
+
+                    RendererPipelineStepImpl::PostProcess {
+                        shader,
+                        input_textures,
+                    }
+                }
+                RendererPipelineStep::DrawDebugLines {
+                    renderer_layer_handler,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                    compute_projection_matrix,
+                } => {
+                    let renderer_layer = self
+                        .get_renderer_layer(renderer_layer_handler)
+                        .map_err(invalid_pipeline)?;
+
+                    RendererPipelineStepImpl::DrawDebugLines {
                         renderer_layer,
                         viewport_start_ndc,
                         viewport_end_ndc,
@@ -280,11 +648,64 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             steps_impl.push(step_impl);
         }
 
+        Ok(steps_impl)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_renderer_pipeline(
+        &mut self,
+        steps: Vec<RendererPipelineStep>,
+    ) -> Result<(), RendererError> {
+        let steps_impl = self.create_renderer_pipeline_steps_impl(steps)?;
+
         self.renderer_impl
             .set_renderer_pipeline(steps_impl)
             .map_err(RendererError::RendererImplError)
     }
 
+    // the index of the step in the main pipeline, e.g. for day and night fades
+    #[method_taskifier_worker_fn]
+    fn set_pipeline_clear_color(
+        &mut self,
+        step_index: usize,
+        clear_color: Vec4<f32>,
+    ) -> Result<(), RendererError> {
+        self.renderer_impl
+            .set_pipeline_clear_color(step_index, clear_color)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn create_window(
+        &mut self,
+        title: String,
+        dimensions: Vec2<usize>,
+    ) -> Result<RendererWindowHandler, RendererError> {
+        self.renderer_impl
+            .create_window(&title, dimensions)
+            .map(|window| {
+                RendererWindowHandler::new(
+                    self.renderer_windows.write().create_object(window),
+                    self.client(),
+                )
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_window_pipeline(
+        &mut self,
+        window_handler: RendererWindowHandler,
+        steps: Vec<RendererPipelineStep>,
+    ) -> Result<(), RendererError> {
+        let window = self.get_window(window_handler)?;
+        let steps_impl = self.create_renderer_pipeline_steps_impl(steps)?;
+
+        self.renderer_impl
+            .set_window_pipeline(window, steps_impl)
+            .map_err(RendererError::RendererImplError)
+    }
+
     #[method_taskifier_worker_fn]
     fn create_renderer_layer(
         &mut self,
@@ -313,6 +734,57 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .map_err(RendererError::RendererImplError)
     }
 
+    #[method_taskifier_worker_fn]
+    fn set_renderer_layer_settings(
+        &mut self,
+        renderer_layer_handler: RendererLayerHandler,
+        settings: RendererLayerSettings,
+    ) -> Result<(), RendererError> {
+        let renderer_layer = self.get_renderer_layer(renderer_layer_handler)?;
+
+        self.renderer_impl
+            .set_renderer_layer_settings(renderer_layer, settings)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    // without a projection the layer uses the projection matrix of the pipeline step that draws it
+    #[method_taskifier_worker_fn]
+    fn update_renderer_layer_projection(
+        &mut self,
+        renderer_layer_handler: RendererLayerHandler,
+        projection: Option<RendererLayerProjection>,
+    ) -> Result<(), RendererError> {
+        let renderer_layer = self.get_renderer_layer(renderer_layer_handler)?;
+
+        self.renderer_impl
+            .update_renderer_layer_projection(renderer_layer, projection)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_renderer_layer_override_shader(
+        &mut self,
+        renderer_layer_handler: RendererLayerHandler,
+        shader_handler: Option<RendererShaderHandler>,
+    ) -> Result<(), RendererError> {
+        let renderer_layer = self.get_renderer_layer(renderer_layer_handler)?;
+
+        let shader = match shader_handler {
+            Some(shader_handler) => Some(
+                self.renderer_shaders
+                    .read()
+                    .get_ref(shader_handler.0.object_pool_index)
+                    .ok_or(RendererError::InvalidRendererShaderHandler(shader_handler))?
+                    .clone(),
+            ),
+            None => None,
+        };
+
+        self.renderer_impl
+            .set_renderer_layer_override_shader(renderer_layer, shader)
+            .map_err(RendererError::RendererImplError)
+    }
+
     #[method_taskifier_worker_fn]
     fn release_renderer_layer(&mut self, object_pool_index: ObjectPoolIndex) {
         let renderer_layer_data = self
@@ -465,6 +937,64 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .map_err(RendererError::RendererImplError)
     }
 
+    // the timestamp is the time the transform belongs to, e.g. the time of the physics tick,
+    // the renderer shows the transforms one submission interval late to have something to lerp to
+    #[method_taskifier_worker_fn]
+    fn update_transform_interpolated(
+        &mut self,
+        transform_handler: RendererTransformHandler,
+        new_transform: Transform<f32, f32, f32>,
+        timestamp: Instant,
+    ) -> Result<(), RendererError> {
+        let transform = self
+            .renderer_transforms
+            .read()
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        self.renderer_impl
+            .update_transform_interpolated(transform, new_transform, timestamp)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_transform_parent(
+        &mut self,
+        transform_handler: RendererTransformHandler,
+        parent_transform_handler: Option<RendererTransformHandler>,
+    ) -> Result<(), RendererError> {
+        let renderer_transforms = self.renderer_transforms.read();
+
+        let transform = renderer_transforms
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        let parent_transform = if let Some(parent_transform_handler) = parent_transform_handler {
+            Some(
+                renderer_transforms
+                    .get_ref(parent_transform_handler.0.object_pool_index)
+                    .ok_or(RendererError::InvalidRendererTransformHandler(
+                        parent_transform_handler,
+                    ))?
+                    .clone(),
+            )
+        } else {
+            None
+        };
+
+        drop(renderer_transforms);
+
+        self.renderer_impl
+            .set_transform_parent(transform, parent_transform)
+            .map_err(RendererError::RendererImplError)
+    }
+
     #[method_taskifier_worker_fn]
     fn release_transform(&mut self, object_pool_index: ObjectPoolIndex) {
         let transform = self
@@ -524,6 +1054,9 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .renderer_materials
             .write()
             .release_object(object_pool_index);
+        self.material_render_textures
+            .write()
+            .remove(&object_pool_index);
 
         if let Some(material) = material {
             let _ = self
@@ -536,15 +1069,49 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
     }
 
     #[method_taskifier_worker_fn]
-    fn create_shader(
+    fn set_material_render_texture(
         &mut self,
-        shader_name: String,
-    ) -> Result<RendererShaderHandler, RendererError> {
+        material_handler: RendererMaterialHandler,
+        texture_type: MaterialTextureType,
+        renderer_texture_handler: RendererTextureHandler,
+    ) -> Result<(), RendererError> {
+        let material_index = material_handler.0.object_pool_index;
+        let material = self
+            .renderer_materials
+            .read()
+            .get_ref(material_index)
+            .ok_or(RendererError::InvalidRendererMaterialHandler(
+                material_handler,
+            ))?
+            .clone();
+
+        let renderer_texture_index = renderer_texture_handler.0.object_pool_index;
+        let renderer_texture = self.get_renderer_texture(renderer_texture_handler)?;
+
         self.renderer_impl
-            .create_shader(shader_name)
-            .map(|shader| {
-                RendererShaderHandler::new(
-                    self.renderer_shaders.write().create_object(shader),
+            .set_material_render_texture(material, texture_type, renderer_texture)
+            .map_err(RendererError::RendererImplError)?;
+
+        let mut material_render_textures = self.material_render_textures.write();
+        let render_textures = material_render_textures.entry(material_index).or_default();
+        render_textures.retain(|(render_texture_type, _)| *render_texture_type != texture_type);
+        render_textures.push((texture_type, renderer_texture_index));
+
+        Ok(())
+    }
+
+    #[method_taskifier_worker_fn]
+    fn create_render_texture(
+        &mut self,
+        dimensions: Vec2<usize>,
+    ) -> Result<RendererTextureHandler, RendererError> {
+        self.renderer_impl
+            .create_render_texture(dimensions)
+            .map(|renderer_texture| {
+                RendererTextureHandler::new(
+                    self.renderer_textures
+                        .write()
+                        .create_object(renderer_texture),
                     self.client(),
                 )
             })
@@ -552,10 +1119,92 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
     }
 
     #[method_taskifier_worker_fn]
-    fn update_shader(
+    fn create_texture(
         &mut self,
-        shader_handler: RendererShaderHandler,
-        new_shader_name: String,
+        image: Arc<Image>,
+    ) -> Result<RendererTextureHandler, RendererError> {
+        self.renderer_impl
+            .create_texture(image)
+            .map(|renderer_texture| {
+                RendererTextureHandler::new(
+                    self.renderer_textures
+                        .write()
+                        .create_object(renderer_texture),
+                    self.client(),
+                )
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn update_texture(
+        &mut self,
+        renderer_texture_handler: RendererTextureHandler,
+        image: Arc<Image>,
+    ) -> Result<(), RendererError> {
+        let renderer_texture = self.get_renderer_texture(renderer_texture_handler)?;
+
+        self.renderer_impl
+            .update_texture(renderer_texture, image)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn create_cubemap_texture(
+        &mut self,
+        faces: [Arc<Image>; 6],
+    ) -> Result<RendererTextureHandler, RendererError> {
+        self.renderer_impl
+            .create_cubemap_texture(faces)
+            .map(|renderer_texture| {
+                RendererTextureHandler::new(
+                    self.renderer_textures
+                        .write()
+                        .create_object(renderer_texture),
+                    self.client(),
+                )
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn release_render_texture(&mut self, object_pool_index: ObjectPoolIndex) {
+        let renderer_texture = self
+            .renderer_textures
+            .write()
+            .release_object(object_pool_index);
+
+        if let Some(renderer_texture) = renderer_texture {
+            let _ = self
+                .renderer_impl
+                .release_render_texture(renderer_texture)
+                .inspect_err(|e| log::error!("ReleaseRenderTexture, msg = {e}"));
+        } else {
+            log::error!("ReleaseRenderTexture, msg = could not find render texture");
+        }
+    }
+
+    #[method_taskifier_worker_fn]
+    fn create_shader(
+        &mut self,
+        shader_name: String,
+    ) -> Result<RendererShaderHandler, RendererError> {
+        self.renderer_impl
+            .create_shader(shader_name)
+            .map(|shader| {
+                RendererShaderHandler::new(
+                    self.renderer_shaders.write().create_object(shader),
+                    self.client(),
+                )
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn update_shader(
+        &mut self,
+        shader_handler: RendererShaderHandler,
+        new_shader_name: String,
     ) -> Result<(), RendererError> {
         let shader = self
             .renderer_shaders
@@ -617,6 +1266,25 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .map_err(RendererError::RendererImplError)
     }
 
+    #[method_taskifier_worker_fn]
+    fn update_mesh_vertices(
+        &mut self,
+        mesh_handler: RendererMeshHandler,
+        vertex_range: Range<usize>,
+        vertices: RendererMeshVertices,
+    ) -> Result<(), RendererError> {
+        let mesh = self
+            .renderer_meshes
+            .read()
+            .get_ref(mesh_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererMeshHandler(mesh_handler))?
+            .clone();
+
+        self.renderer_impl
+            .update_mesh_vertices(mesh, vertex_range, vertices)
+            .map_err(RendererError::RendererImplError)
+    }
+
     #[method_taskifier_worker_fn]
     fn release_mesh(&mut self, object_pool_index: ObjectPoolIndex) {
         let mesh = self
@@ -656,6 +1324,7 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .ok_or(RendererError::InvalidRendererShaderHandler(shader_handler))?
             .clone();
 
+        let material_index = material_handler.0.object_pool_index;
         let material = self
             .renderer_materials
             .read()
@@ -677,19 +1346,309 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
         self.renderer_impl
             .create_renderer_object_from_mesh(mesh, shader, material, transform)
             .map(|renderer_object| {
-                RendererObjectHandler::new(
-                    self.renderer_objects
-                        .write()
-                        .create_object(RendererObjectData {
-                            renderer_object,
-                            contained_by_renderer_groups: BTreeSet::new(),
-                        }),
-                    self.client(),
-                )
+                self.add_renderer_object_data(renderer_object, Some(material_index))
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn execute_renderer_batch(
+        &mut self,
+        commands: Vec<RendererBatchCommand>,
+    ) -> Result<Vec<RendererBatchResult>, RendererError> {
+        let mut results = Vec::with_capacity(commands.len());
+
+        for command in commands {
+            let result = match command {
+                RendererBatchCommand::CreateTransform(transform) => {
+                    RendererBatchResult::Transform(self.create_transform(transform)?)
+                }
+                RendererBatchCommand::UpdateTransform {
+                    transform,
+                    new_transform,
+                } => {
+                    let transform = transform.resolve(&results)?;
+                    self.update_transform(transform, new_transform)?;
+                    RendererBatchResult::Done
+                }
+                RendererBatchCommand::CreateMaterial(material) => {
+                    RendererBatchResult::Material(self.create_material(material)?)
+                }
+                RendererBatchCommand::CreateShader(shader_name) => {
+                    RendererBatchResult::Shader(self.create_shader(shader_name)?)
+                }
+                RendererBatchCommand::CreateMesh(mesh) => {
+                    RendererBatchResult::Mesh(self.create_mesh(mesh)?)
+                }
+                RendererBatchCommand::CreateRendererObjectFromMesh {
+                    mesh,
+                    shader,
+                    material,
+                    transform,
+                } => {
+                    let mesh = mesh.resolve(&results)?;
+                    let shader = shader.resolve(&results)?;
+                    let material = material.resolve(&results)?;
+                    let transform = transform.resolve(&results)?;
+                    RendererBatchResult::RendererObject(
+                        self.create_renderer_object_from_mesh(mesh, shader, material, transform)?,
+                    )
+                }
+                RendererBatchCommand::AddRendererObjectToGroup {
+                    renderer_object,
+                    renderer_group,
+                } => {
+                    let renderer_object = renderer_object.resolve(&results)?;
+                    self.add_renderer_object_to_group(renderer_object, renderer_group)?;
+                    RendererBatchResult::Done
+                }
+            };
+
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn create_instanced_renderer_object(
+        &mut self,
+        mesh_handler: RendererMeshHandler,
+        shader_handler: RendererShaderHandler,
+        material_handler: RendererMaterialHandler,
+        transform_handlers: Vec<RendererTransformHandler>,
+    ) -> Result<RendererObjectHandler, RendererError> {
+        let mesh = self
+            .renderer_meshes
+            .read()
+            .get_ref(mesh_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererMeshHandler(mesh_handler))?
+            .clone();
+
+        let shader = self
+            .renderer_shaders
+            .read()
+            .get_ref(shader_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererShaderHandler(shader_handler))?
+            .clone();
+
+        let material_index = material_handler.0.object_pool_index;
+        let material = self
+            .renderer_materials
+            .read()
+            .get_ref(material_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererMaterialHandler(
+                material_handler,
+            ))?
+            .clone();
+
+        let mut transforms = Vec::with_capacity(transform_handlers.len());
+        for transform_handler in transform_handlers {
+            let transform = self
+                .renderer_transforms
+                .read()
+                .get_ref(transform_handler.0.object_pool_index)
+                .ok_or(RendererError::InvalidRendererTransformHandler(
+                    transform_handler,
+                ))?
+                .clone();
+            transforms.push(transform);
+        }
+
+        self.renderer_impl
+            .create_instanced_renderer_object(mesh, shader, material, transforms)
+            .map(|renderer_object| {
+                self.add_renderer_object_data(renderer_object, Some(material_index))
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    // every mesh is drawn from its distance until the distance of the next one
+    #[method_taskifier_worker_fn]
+    fn create_lod_renderer_object(
+        &mut self,
+        lod_mesh_handlers: Vec<(f32, RendererMeshHandler)>,
+        shader_handler: RendererShaderHandler,
+        material_handler: RendererMaterialHandler,
+        transform_handler: RendererTransformHandler,
+    ) -> Result<RendererObjectHandler, RendererError> {
+        let mut lod_meshes = Vec::with_capacity(lod_mesh_handlers.len());
+        for (distance, mesh_handler) in lod_mesh_handlers {
+            let mesh = self
+                .renderer_meshes
+                .read()
+                .get_ref(mesh_handler.0.object_pool_index)
+                .ok_or(RendererError::InvalidRendererMeshHandler(mesh_handler))?
+                .clone();
+            lod_meshes.push((distance, mesh));
+        }
+
+        let shader = self
+            .renderer_shaders
+            .read()
+            .get_ref(shader_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererShaderHandler(shader_handler))?
+            .clone();
+
+        let material_index = material_handler.0.object_pool_index;
+        let material = self
+            .renderer_materials
+            .read()
+            .get_ref(material_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererMaterialHandler(
+                material_handler,
+            ))?
+            .clone();
+
+        let transform = self
+            .renderer_transforms
+            .read()
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        self.renderer_impl
+            .create_lod_renderer_object(lod_meshes, shader, material, transform)
+            .map(|renderer_object| {
+                self.add_renderer_object_data(renderer_object, Some(material_index))
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn create_skybox_renderer_object(
+        &mut self,
+        cubemap_handler: RendererTextureHandler,
+    ) -> Result<RendererObjectHandler, RendererError> {
+        let cubemap = self.get_renderer_texture(cubemap_handler)?;
+
+        self.renderer_impl
+            .create_skybox_renderer_object(cubemap)
+            .map(|renderer_object| self.add_renderer_object_data(renderer_object, None))
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn create_particle_emitter_renderer_object(
+        &mut self,
+        settings: ParticleEmitterSettings,
+        material_handler: RendererMaterialHandler,
+        transform_handler: RendererTransformHandler,
+    ) -> Result<RendererObjectHandler, RendererError> {
+        let material_index = material_handler.0.object_pool_index;
+        let material = self
+            .renderer_materials
+            .read()
+            .get_ref(material_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererMaterialHandler(
+                material_handler,
+            ))?
+            .clone();
+
+        let transform = self
+            .renderer_transforms
+            .read()
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        self.renderer_impl
+            .create_particle_emitter_renderer_object(settings, material, transform)
+            .map(|renderer_object| {
+                self.add_renderer_object_data(renderer_object, Some(material_index))
             })
             .map_err(RendererError::RendererImplError)
     }
 
+    // every sprite of the batch is drawn from the same texture with a single draw call
+    #[method_taskifier_worker_fn]
+    fn create_sprite_batch_renderer_object(
+        &mut self,
+        texture_handler: RendererTextureHandler,
+        sprites: Vec<RendererSprite>,
+        transform_handler: RendererTransformHandler,
+    ) -> Result<RendererObjectHandler, RendererError> {
+        let texture = self.get_renderer_texture(texture_handler)?;
+
+        let transform = self
+            .renderer_transforms
+            .read()
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        self.renderer_impl
+            .create_sprite_batch_renderer_object(texture, sprites, transform)
+            .map(|renderer_object| self.add_renderer_object_data(renderer_object, None))
+            .map_err(RendererError::RendererImplError)
+    }
+
+    // the text is laid out by the renderer, so updating it does not recreate the renderer object
+    #[method_taskifier_worker_fn]
+    fn create_text_renderer_object(
+        &mut self,
+        text: String,
+        font: ArcRwLock<dyn Font>,
+        size: f32,
+        color: Vec4<f32>,
+        transform_handler: RendererTransformHandler,
+    ) -> Result<RendererObjectHandler, RendererError> {
+        let transform = self
+            .renderer_transforms
+            .read()
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        let text_layout = layout_text(&text, &mut *font.write(), size, color);
+
+        let atlas_texture = self
+            .renderer_impl
+            .create_texture(text_layout.atlas)
+            .map_err(RendererError::RendererImplError)?;
+
+        let renderer_object = match self.renderer_impl.create_sprite_batch_renderer_object(
+            atlas_texture.clone(),
+            text_layout.sprites,
+            transform,
+        ) {
+            Ok(renderer_object) => renderer_object,
+            Err(e) => {
+                let _ = self
+                    .renderer_impl
+                    .release_render_texture(atlas_texture)
+                    .inspect_err(|e| {
+                        log::error!("CreateTextRendererObject, releasing text atlas, msg = {e}")
+                    });
+                return Err(RendererError::RendererImplError(e));
+            }
+        };
+
+        let renderer_object_handler = self.add_renderer_object_data(renderer_object, None);
+        if let Some(renderer_object_data) = self
+            .renderer_objects
+            .write()
+            .get_mut(renderer_object_handler.0.object_pool_index)
+        {
+            renderer_object_data.text = Some(RendererTextData {
+                font,
+                size,
+                color,
+                atlas_texture,
+            });
+        }
+
+        Ok(renderer_object_handler)
+    }
+
     #[method_taskifier_worker_fn]
     fn release_renderer_object(&mut self, object_pool_index: ObjectPoolIndex) {
         let renderer_object_data = self
@@ -723,8 +1682,17 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
                 .renderer_impl
                 .release_renderer_object(renderer_object_data.renderer_object.clone())
                 .inspect_err(|e| log::error!("ReleaseRendererObject, msg = {e}"));
-        } else {
-            log::error!("ReleaseRendererObject, msg = could not find renderer object");
+
+            if let Some(text_data) = renderer_object_data.text {
+                let _ = self
+                    .renderer_impl
+                    .release_render_texture(text_data.atlas_texture)
+                    .inspect_err(|e| {
+                        log::error!("ReleaseRendererObject, releasing text atlas, msg = {e}")
+                    });
+            }
+        } else {
+            log::error!("ReleaseRendererObject, msg = could not find renderer object");
         }
     }
 
@@ -886,6 +1854,195 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .map_err(RendererError::RendererImplError)
     }
 
+    #[method_taskifier_worker_fn]
+    fn set_renderer_object_transform(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        transform_handler: RendererTransformHandler,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        let transform = self
+            .renderer_transforms
+            .read()
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        self.renderer_impl
+            .set_renderer_object_transform(renderer_object, transform)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_renderer_object_material(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        material_handler: RendererMaterialHandler,
+    ) -> Result<(), RendererError> {
+        let renderer_object_index = renderer_object_handler.0.object_pool_index;
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        let material_index = material_handler.0.object_pool_index;
+        let material = self
+            .renderer_materials
+            .read()
+            .get_ref(material_index)
+            .ok_or(RendererError::InvalidRendererMaterialHandler(
+                material_handler,
+            ))?
+            .clone();
+
+        self.renderer_impl
+            .set_renderer_object_material(renderer_object, material)
+            .map_err(RendererError::RendererImplError)?;
+
+        if let Some(renderer_object_data) =
+            self.renderer_objects.write().get_mut(renderer_object_index)
+        {
+            renderer_object_data.material_index = Some(material_index);
+        }
+
+        Ok(())
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_renderer_object_shader(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        shader_handler: RendererShaderHandler,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        let shader = self
+            .renderer_shaders
+            .read()
+            .get_ref(shader_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererShaderHandler(shader_handler))?
+            .clone();
+
+        self.renderer_impl
+            .set_renderer_object_shader(renderer_object, shader)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_renderer_object_mesh(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        mesh_handler: RendererMeshHandler,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        let mesh = self
+            .renderer_meshes
+            .read()
+            .get_ref(mesh_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererMeshHandler(mesh_handler))?
+            .clone();
+
+        self.renderer_impl
+            .set_renderer_object_mesh(renderer_object, mesh)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_renderer_object_probe(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        probe_handler: Option<RendererProbeHandler>,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        let probe = if let Some(probe_handler) = probe_handler {
+            Some(self.get_probe(probe_handler)?)
+        } else {
+            None
+        };
+
+        self.renderer_impl
+            .set_renderer_object_probe(renderer_object, probe)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_renderer_object_uniform(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        name: String,
+        value: Option<RendererUniformValue>,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        self.renderer_impl
+            .set_renderer_object_uniform(renderer_object, name, value)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_particle_emitter_settings(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        settings: ParticleEmitterSettings,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        self.renderer_impl
+            .set_particle_emitter_settings(renderer_object, settings)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_text_renderer_object_text(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        text: String,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler.clone())?;
+
+        let (font, size, color, atlas_texture) = self
+            .renderer_objects
+            .read()
+            .get_ref(renderer_object_handler.0.object_pool_index)
+            .and_then(|renderer_object_data| renderer_object_data.text.as_ref())
+            .map(|text_data| {
+                (
+                    text_data.font.clone(),
+                    text_data.size,
+                    text_data.color,
+                    text_data.atlas_texture.clone(),
+                )
+            })
+            .ok_or(RendererError::InvalidRendererObjectHandler(
+                renderer_object_handler,
+            ))?;
+
+        let text_layout = layout_text(&text, &mut *font.write(), size, color);
+
+        self.renderer_impl
+            .update_texture(atlas_texture, text_layout.atlas)
+            .map_err(RendererError::RendererImplError)?;
+
+        self.renderer_impl
+            .set_sprite_batch_sprites(renderer_object, text_layout.sprites)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_sprite_batch_sprites(
+        &mut self,
+        renderer_object_handler: RendererObjectHandler,
+        sprites: Vec<RendererSprite>,
+    ) -> Result<(), RendererError> {
+        let renderer_object = self.get_renderer_object(renderer_object_handler)?;
+
+        self.renderer_impl
+            .set_sprite_batch_sprites(renderer_object, sprites)
+            .map_err(RendererError::RendererImplError)
+    }
+
     #[method_taskifier_worker_fn]
     fn create_camera(
         &mut self,
@@ -911,6 +2068,24 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             .map_err(RendererError::RendererImplError)
     }
 
+    #[method_taskifier_worker_fn]
+    fn update_camera_parameters(
+        &mut self,
+        camera_handler: RendererCameraHandler,
+        parameters: Option<RendererCameraParameters>,
+    ) -> Result<(), RendererError> {
+        let camera = self
+            .renderer_cameras
+            .read()
+            .get_ref(camera_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererCameraHandler(camera_handler))?
+            .clone();
+
+        self.renderer_impl
+            .update_camera_parameters(camera, parameters)
+            .map_err(RendererError::RendererImplError)
+    }
+
     #[method_taskifier_worker_fn]
     fn release_camera(&mut self, object_pool_index: ObjectPoolIndex) {
         let camera = self
@@ -927,11 +2102,418 @@ impl<T: RendererImpl + ?Sized> RendererPri<T> {
             log::error!("ReleaseCamera, msg = could not find camera");
         }
     }
+
+    #[method_taskifier_worker_fn]
+    fn create_probe(
+        &mut self,
+        transform_handler: RendererTransformHandler,
+        renderer_layer_handler: RendererLayerHandler,
+        resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<RendererProbeHandler, RendererError> {
+        let transform = self
+            .renderer_transforms
+            .read()
+            .get_ref(transform_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererTransformHandler(
+                transform_handler,
+            ))?
+            .clone();
+
+        let renderer_layer = self
+            .renderer_layers
+            .read()
+            .get_ref(renderer_layer_handler.0.object_pool_index)
+            .ok_or(RendererError::InvalidRendererLayerHandler(
+                renderer_layer_handler,
+            ))?
+            .renderer_layer
+            .clone();
+
+        self.renderer_impl
+            .create_probe(transform, renderer_layer, resolution, refresh_mode)
+            .map(|probe| {
+                RendererProbeHandler::new(
+                    self.renderer_probes.write().create_object(probe),
+                    self.client(),
+                )
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn refresh_probe(&mut self, probe_handler: RendererProbeHandler) -> Result<(), RendererError> {
+        let probe = self.get_probe(probe_handler)?;
+
+        self.renderer_impl
+            .refresh_probe(probe)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_probe_refresh_mode(
+        &mut self,
+        probe_handler: RendererProbeHandler,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<(), RendererError> {
+        let probe = self.get_probe(probe_handler)?;
+
+        self.renderer_impl
+            .set_probe_refresh_mode(probe, refresh_mode)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn get_renderer_stats(&mut self) -> RendererStats {
+        RendererStats {
+            number_of_renderer_layers: self.renderer_layers.read().len(),
+            number_of_renderer_groups: self.renderer_groups.read().len(),
+            number_of_renderer_objects: self.renderer_objects.read().len(),
+            number_of_transforms: self.renderer_transforms.read().len(),
+            number_of_materials: self.renderer_materials.read().len(),
+            number_of_shaders: self.renderer_shaders.read().len(),
+            number_of_cameras: self.renderer_cameras.read().len(),
+            number_of_probes: self.renderer_probes.read().len(),
+            number_of_render_textures: self.renderer_textures.read().len(),
+            ..self.renderer_impl.stats()
+        }
+    }
+
+    #[method_taskifier_worker_fn]
+    fn dump_state(&mut self) -> RendererStateDump {
+        let renderer_layers = self.renderer_layers.read();
+        let renderer_groups = self.renderer_groups.read();
+        let renderer_objects = self.renderer_objects.read();
+
+        let mut inconsistencies = Vec::new();
+        let mut check_link = |from: &str,
+                              from_index: ObjectPoolIndex,
+                              to: &str,
+                              to_index: ObjectPoolIndex,
+                              links_back: Option<bool>| {
+            match links_back {
+                Some(true) => {}
+                Some(false) => inconsistencies.push(format!(
+                    "{to} does not link back to {from}, {from} = {from_index:?}, {to} = {to_index:?}"
+                )),
+                None => inconsistencies.push(format!(
+                    "{from} links to invalid {to}, {from} = {from_index:?}, {to} = {to_index:?}"
+                )),
+            }
+        };
+
+        let mut number_of_groups_in_layers = 0;
+        for (renderer_layer_index, renderer_layer_data) in renderer_layers.iter() {
+            number_of_groups_in_layers += renderer_layer_data.added_renderer_groups.len();
+
+            for renderer_group_index in renderer_layer_data.added_renderer_groups.iter() {
+                check_link(
+                    "renderer_layer",
+                    renderer_layer_index,
+                    "renderer_group",
+                    *renderer_group_index,
+                    renderer_groups
+                        .get_ref(*renderer_group_index)
+                        .map(|renderer_group_data| {
+                            renderer_group_data
+                                .contained_by_renderer_layers
+                                .contains(&renderer_layer_index)
+                        }),
+                );
+            }
+        }
+
+        let mut number_of_objects_in_groups = 0;
+        for (renderer_group_index, renderer_group_data) in renderer_groups.iter() {
+            number_of_objects_in_groups += renderer_group_data.added_renderer_objects.len();
+
+            for renderer_layer_index in renderer_group_data.contained_by_renderer_layers.iter() {
+                check_link(
+                    "renderer_group",
+                    renderer_group_index,
+                    "renderer_layer",
+                    *renderer_layer_index,
+                    renderer_layers
+                        .get_ref(*renderer_layer_index)
+                        .map(|renderer_layer_data| {
+                            renderer_layer_data
+                                .added_renderer_groups
+                                .contains(&renderer_group_index)
+                        }),
+                );
+            }
+
+            for renderer_object_index in renderer_group_data.added_renderer_objects.iter() {
+                check_link(
+                    "renderer_group",
+                    renderer_group_index,
+                    "renderer_object",
+                    *renderer_object_index,
+                    renderer_objects
+                        .get_ref(*renderer_object_index)
+                        .map(|renderer_object_data| {
+                            renderer_object_data
+                                .contained_by_renderer_groups
+                                .contains(&renderer_group_index)
+                        }),
+                );
+            }
+        }
+
+        let mut number_of_orphaned_renderer_objects = 0;
+        for (renderer_object_index, renderer_object_data) in renderer_objects.iter() {
+            if renderer_object_data.handler.strong_count() == 0 {
+                number_of_orphaned_renderer_objects += 1;
+            }
+
+            for renderer_group_index in renderer_object_data.contained_by_renderer_groups.iter() {
+                check_link(
+                    "renderer_object",
+                    renderer_object_index,
+                    "renderer_group",
+                    *renderer_group_index,
+                    renderer_groups
+                        .get_ref(*renderer_group_index)
+                        .map(|renderer_group_data| {
+                            renderer_group_data
+                                .added_renderer_objects
+                                .contains(&renderer_object_index)
+                        }),
+                );
+            }
+        }
+
+        RendererStateDump {
+            number_of_renderer_layers: renderer_layers.len(),
+            number_of_renderer_groups: renderer_groups.len(),
+            number_of_renderer_objects: renderer_objects.len(),
+            number_of_groups_in_layers,
+            number_of_objects_in_groups,
+            number_of_orphaned_renderer_objects,
+            inconsistencies,
+        }
+    }
+
+    // false for the handlers whose objects are not in the pools of this renderer system
+    #[method_taskifier_worker_fn]
+    fn is_valid(&mut self, handler: RendererHandler) -> bool {
+        match handler {
+            RendererHandler::Camera(handler) => self
+                .renderer_cameras
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Group(handler) => self
+                .renderer_groups
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Layer(handler) => self
+                .renderer_layers
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Material(handler) => self
+                .renderer_materials
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Mesh(handler) => self
+                .renderer_meshes
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Object(handler) => self
+                .renderer_objects
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Probe(handler) => self
+                .renderer_probes
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Shader(handler) => self
+                .renderer_shaders
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Texture(handler) => self
+                .renderer_textures
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Transform(handler) => self
+                .renderer_transforms
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+            RendererHandler::Window(handler) => self
+                .renderer_windows
+                .read()
+                .get_ref(handler.0.object_pool_index)
+                .is_some(),
+        }
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_texture_memory_budget(
+        &mut self,
+        budget_bytes: Option<usize>,
+    ) -> Result<(), RendererError> {
+        self.renderer_impl
+            .set_texture_memory_budget(budget_bytes)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn set_debug_render_mode(
+        &mut self,
+        mode: RendererDebugRenderMode,
+    ) -> Result<(), RendererError> {
+        self.renderer_impl
+            .set_debug_render_mode(mode)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn reload_shaders(&mut self) -> Result<(), RendererError> {
+        self.renderer_impl
+            .reload_shaders()
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn pick_renderer_object(
+        &mut self,
+        renderer_layer_handler: RendererLayerHandler,
+        screen_position: Vec2<f32>,
+    ) -> Result<Option<RendererObjectHandler>, RendererError> {
+        let renderer_layers = self.renderer_layers.read();
+        let renderer_layer_data = renderer_layers
+            .get_ref(renderer_layer_handler.0.object_pool_index)
+            .ok_or_else(|| {
+                RendererError::InvalidRendererLayerHandler(renderer_layer_handler.clone())
+            })?;
+
+        let renderer_groups = self.renderer_groups.read();
+        let renderer_objects = self.renderer_objects.read();
+
+        // an object can be in multiple groups of the layer, but it is a candidate only once
+        let (candidate_renderer_objects, candidate_handlers): (Vec<_>, Vec<_>) =
+            renderer_layer_data
+                .added_renderer_groups
+                .iter()
+                .filter_map(|renderer_group_index| renderer_groups.get_ref(*renderer_group_index))
+                .flat_map(|renderer_group_data| renderer_group_data.added_renderer_objects.iter())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .filter_map(|renderer_object_index| {
+                    let renderer_object_data = renderer_objects.get_ref(*renderer_object_index)?;
+                    let handler = renderer_object_data.handler.upgrade()?;
+
+                    Some((
+                        renderer_object_data.renderer_object.clone(),
+                        RendererObjectHandler(handler),
+                    ))
+                })
+                .unzip();
+
+        self.renderer_impl
+            .pick_renderer_object(
+                renderer_layer_data.renderer_layer.clone(),
+                screen_position,
+                candidate_renderer_objects,
+            )
+            .map(|picked_index| {
+                picked_index.and_then(|picked_index| candidate_handlers.get(picked_index).cloned())
+            })
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn draw_debug_line(
+        &mut self,
+        start: Vec3<f32>,
+        end: Vec3<f32>,
+        color: Vec4<f32>,
+        lifetime_secs: f32,
+    ) -> Result<(), RendererError> {
+        self.renderer_impl
+            .add_debug_lines(vec![RendererDebugLine { start, end, color }], lifetime_secs)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn draw_debug_aabb(
+        &mut self,
+        aabb: AxisAlignedBoundingBox,
+        color: Vec4<f32>,
+        lifetime_secs: f32,
+    ) -> Result<(), RendererError> {
+        self.renderer_impl
+            .add_debug_lines(aabb_debug_lines(&aabb, color), lifetime_secs)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn draw_debug_sphere(
+        &mut self,
+        center: Vec3<f32>,
+        radius: f32,
+        color: Vec4<f32>,
+        lifetime_secs: f32,
+    ) -> Result<(), RendererError> {
+        self.renderer_impl
+            .add_debug_lines(sphere_debug_lines(center, radius, color), lifetime_secs)
+            .map_err(RendererError::RendererImplError)
+    }
+
+    #[method_taskifier_worker_fn]
+    fn release_probe(&mut self, object_pool_index: ObjectPoolIndex) {
+        let probe = self
+            .renderer_probes
+            .write()
+            .release_object(object_pool_index);
+
+        if let Some(probe) = probe {
+            let _ = self
+                .renderer_impl
+                .release_probe(probe)
+                .inspect_err(|e| log::error!("ReleaseProbe, msg = {e}"));
+        } else {
+            log::error!("ReleaseProbe, msg = could not find probe");
+        }
+    }
+
+    #[method_taskifier_worker_fn]
+    fn release_window(&mut self, object_pool_index: ObjectPoolIndex) {
+        let window = self
+            .renderer_windows
+            .write()
+            .release_object(object_pool_index);
+
+        if let Some(window) = window {
+            let _ = self
+                .renderer_impl
+                .release_window(window)
+                .inspect_err(|e| log::error!("ReleaseWindow, msg = {e}"));
+        } else {
+            log::error!("ReleaseWindow, msg = could not find window");
+        }
+    }
 }
 
 impl System for SyncRenderer {
     fn tick(&mut self, _loop_start: &std::time::Instant, _last_loop_time_secs: f32) {
-        while let Ok(task) = self.renderer_pri.task_receiver.try_recv() {
+        let message_queue = self.renderer_pri.message_queue.clone();
+        loop {
+            let task = message_queue
+                .try_recv_task(|command| self.renderer_pri.execute_detached_command(command));
+            let Some(task) = task else {
+                break;
+            };
+
             self.renderer_pri.execute_channeled_task(task);
         }
 
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use vek::{Vec2, Vec4};
+
+use crate::{font::Font, image::Image};
+
+use super::renderer_sprite::RendererSprite;
+
+// the resolution of the glyphs in the atlas, independent of the size of the text
+pub const TEXT_GLYPH_PIXEL_SCALE: usize = 128;
+
+pub struct RendererTextLayout {
+    // the glyph atlas of the font, shared by every text laid out with it
+    pub atlas: Arc<Image>,
+    pub sprites: Vec<RendererSprite>,
+}
+
+// the origin is the top left corner of the text, the lines grow downwards along the -y axis
+pub fn layout_text(
+    text: &str,
+    font: &mut dyn Font,
+    size: f32,
+    color: Vec4<f32>,
+) -> RendererTextLayout {
+    let pixel_scale_f32 = TEXT_GLYPH_PIXEL_SCALE as f32;
+    let size = size.abs();
+
+    // the uvs are computed after every glyph is in the atlas, because adding a glyph can grow it
+    let mut placed_glyphs = Vec::new();
+    let mut line_index = 0;
+    let mut pen_x = 0.0;
+    for chr in text.chars() {
+        if chr == '\n' {
+            line_index += 1;
+            pen_x = 0.0;
+            continue;
+        }
+
+        if let Some(atlas_glyph) = font.get_atlas_glyph(chr, TEXT_GLYPH_PIXEL_SCALE) {
+            let glyph = atlas_glyph.glyph();
+
+            // the baseline of a line is one size below its top
+            let baseline = Vec2::new(pen_x, -((line_index + 1) as f32) * size);
+            let top_left = baseline
+                + glyph.compute_render_offset_px() / pixel_scale_f32 * size * Vec2::new(1.0, -1.0);
+            let sprite_size = glyph.px_size().as_::<f32>() / pixel_scale_f32 * size;
+
+            placed_glyphs.push((
+                top_left - Vec2::new(0.0, sprite_size.y),
+                sprite_size,
+                atlas_glyph.atlas_rect(),
+            ));
+
+            pen_x += glyph.h_advance() / pixel_scale_f32 * size;
+        } else {
+            // the glyphs without an outline, like the space, are not rendered by the font
+            pen_x += size / 2.0;
+        }
+    }
+
+    let glyph_atlas = font.glyph_atlas();
+    let sprites = placed_glyphs
+        .into_iter()
+        .map(|(position, size, atlas_rect)| RendererSprite {
+            position,
+            size,
+            uv_rect: glyph_atlas.uv_rect(atlas_rect),
+            color,
+        })
+        .collect();
+
+    RendererTextLayout {
+        atlas: glyph_atlas.image().clone(),
+        sprites,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use vek::Vec4;
+
+    use crate::font::CachedFont;
+
+    use super::layout_text;
+
+    #[test]
+    fn repeated_glyphs_share_their_atlas_rect_and_lines_start_at_the_left_edge() {
+        let mut font = CachedFont::hack();
+        let layout = layout_text("aa b\na", &mut font, 2.0, Vec4::one());
+
+        assert_eq!(4, layout.sprites.len());
+        assert!(Arc::ptr_eq(&layout.atlas, font.glyph_atlas().image()));
+
+        let first_a = layout.sprites[0];
+        let second_a = layout.sprites[1];
+        let b = layout.sprites[2];
+        let a_of_the_second_line = layout.sprites[3];
+
+        assert_eq!(first_a.uv_rect, second_a.uv_rect);
+        assert_eq!(first_a.uv_rect, a_of_the_second_line.uv_rect);
+        assert_ne!(first_a.uv_rect, b.uv_rect);
+
+        assert!(first_a.position.x < second_a.position.x);
+        assert!(second_a.position.x < b.position.x);
+        assert_eq!(first_a.position.x, a_of_the_second_line.position.x);
+        assert!((first_a.position.y - 2.0 - a_of_the_second_line.position.y).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,11 @@
+use vek::{Mat4, Vec2, Vec3, Vec4};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RendererUniformValue {
+    Float(f32),
+    Int(i32),
+    Vec2(Vec2<f32>),
+    Vec3(Vec3<f32>),
+    Vec4(Vec4<f32>),
+    Mat4(Mat4<f32>),
+}
@@ -2,13 +2,33 @@ mod test_renderer;
 
 use std::{sync::Arc, time::Duration};
 
+use bytifex_utils::sync::types::arc_rw_lock_new;
 use tokio::sync::RwLock as AsyncRwLock;
-use vek::{Transform, Vec3};
+use vek::{Mat4, Transform, Vec2, Vec3, Vec4};
 
 use crate::{
-    mesh::{Material, Mesh},
-    renderer::tests::test_renderer::{init_test_async, init_test_sync},
-    renderer::RendererGroupHandler,
+    aabb::AxisAlignedBoundingBox,
+    font::CachedFont,
+    image::{ColorType, Image},
+    mesh::{Material, MaterialTextureType, Mesh, VertexBoneWeight},
+    renderer::particle_system::{ParticleCurve, ParticleEmitterSettings},
+    renderer::recording_renderer::{RendererCallKind, RendererHandleKind},
+    renderer::renderer_batch::{RendererBatchArg, RendererBatchBuilder},
+    renderer::renderer_camera_parameters::RendererCameraParameters,
+    renderer::renderer_config::RendererConfig,
+    renderer::renderer_debug_render_mode::RendererDebugRenderMode,
+    renderer::renderer_layer_projection::RendererLayerProjection,
+    renderer::renderer_layer_settings::RendererLayerSettings,
+    renderer::renderer_mesh_vertices::RendererMeshVertices,
+    renderer::renderer_pipeline_step::{
+        split_screen_viewports_ndc, InvalidPipelineReason, RendererPipelineStep,
+    },
+    renderer::renderer_probe_refresh_mode::RendererProbeRefreshMode,
+    renderer::renderer_sprite::RendererSprite,
+    renderer::renderer_state_dump::RendererHandler,
+    renderer::renderer_uniform_value::RendererUniformValue,
+    renderer::tests::test_renderer::{init_test_async, init_test_sync, init_test_sync_with_config},
+    renderer::{RendererError, RendererGroupHandler, RendererLayerHandler},
 };
 
 #[tokio::test(flavor = "current_thread")]
@@ -91,6 +111,122 @@ async fn update_transform() {
     test_task.await.unwrap();
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn update_transform_detached() {
+    let (mut test_loop, test_client) = init_test_async();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let mut transform = Transform::default();
+
+            let handler = test_client
+                .renderer_client()
+                .create_transform(transform)
+                .await
+                .unwrap()
+                .unwrap();
+
+            for i in 0..10 {
+                transform.position = Vec3::new(i as f32, 0.0, 0.0);
+                test_client
+                    .renderer_detached_client()
+                    .update_transform(handler.clone(), transform);
+            }
+
+            // the detached commands are executed before the tasks that are sent after them
+            test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(
+                transform,
+                *test_client
+                    .renderer_impl()
+                    .transforms
+                    .read()
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .1
+            );
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn update_transform_detached_after_a_pending_task() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let mut transform = Transform::default();
+
+            let handler = test_client
+                .renderer_client()
+                .create_transform(transform)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let update_task = {
+                let renderer_client = test_client.renderer_client().clone();
+                let handler = handler.clone();
+                let transform = Transform {
+                    position: Vec3::new(1.0, 0.0, 0.0),
+                    ..transform
+                };
+                tokio::spawn(
+                    async move { renderer_client.update_transform(handler, transform).await },
+                )
+            };
+            // the update task is sent, but it is not executed yet
+            tokio::task::yield_now().await;
+
+            transform.position = Vec3::new(2.0, 0.0, 0.0);
+            test_client
+                .renderer_detached_client()
+                .update_transform(handler.clone(), transform);
+
+            update_task.await.unwrap().unwrap().unwrap();
+            test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(
+                transform,
+                *test_client
+                    .renderer_impl()
+                    .transforms
+                    .read()
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .1
+            );
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn shader_is_released_when_handlers_are_dropped() {
     let (mut test_loop, test_client) = init_test_sync();
@@ -301,3 +437,2819 @@ async fn renderer_object_is_released_when_handlers_are_dropped() {
     let renderer_group = renderer_groups.iter().next().unwrap().1;
     assert_eq!(0, renderer_group.renderer_objects.read().len());
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn submit_renderer_batch_and_use_the_created_handlers() {
+    let (mut test_loop, test_client) = init_test_async();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let renderer_group_handler = test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut batch = RendererBatchBuilder::new();
+            let shader_slot = batch.create_shader("some shader name");
+            let mesh_slot = batch.create_mesh(Arc::new(Mesh::default()));
+            let material_slot = batch.create_material(Material::default());
+
+            let mut transform_slots = Vec::new();
+            let mut renderer_object_slots = Vec::new();
+            for i in 0..3 {
+                let transform_slot = batch.create_transform(Transform::default());
+                batch.update_transform(
+                    transform_slot,
+                    Transform {
+                        position: Vec3::new(i as f32, 0.0, 0.0),
+                        ..Default::default()
+                    },
+                );
+
+                let renderer_object_slot = batch.create_renderer_object_from_mesh(
+                    mesh_slot,
+                    shader_slot,
+                    material_slot,
+                    transform_slot,
+                );
+                batch.add_renderer_object_to_group(
+                    renderer_object_slot,
+                    renderer_group_handler.clone(),
+                );
+
+                transform_slots.push(transform_slot);
+                renderer_object_slots.push(renderer_object_slot);
+            }
+            assert_eq!(15, batch.len());
+
+            let results = batch.submit(test_client.renderer_client()).await.unwrap();
+            assert_eq!(15, results.len());
+
+            let renderer_object_handlers = renderer_object_slots
+                .iter()
+                .map(|slot| results.get(*slot).unwrap())
+                .collect::<Vec<_>>();
+            let transform_handlers = transform_slots
+                .iter()
+                .map(|slot| results.get(*slot).unwrap())
+                .collect::<Vec<_>>();
+
+            assert_eq!(3, test_client.renderer_impl().renderer_objects.read().len());
+            assert_eq!(3, test_client.renderer_impl().transforms.read().len());
+            assert_eq!(1, test_client.renderer_impl().materials.read().len());
+            {
+                let renderer_groups = test_client.renderer_impl().renderer_groups.read();
+                let renderer_group = renderer_groups.iter().next().unwrap().1;
+                assert_eq!(3, renderer_group.renderer_objects.read().len());
+            }
+
+            // the slots only refer to the commands of their own batch
+            let mut batch = RendererBatchBuilder::new();
+            batch.add_renderer_object_to_group(
+                RendererBatchArg::Slot(7),
+                renderer_group_handler.clone(),
+            );
+            assert!(matches!(
+                batch.submit(test_client.renderer_client()).await,
+                Err(RendererError::InvalidRendererBatchSlot(7))
+            ));
+
+            drop(results);
+            drop(renderer_object_handlers);
+            drop(transform_handlers);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().renderer_objects.read().len());
+    assert_eq!(0, test_client.renderer_impl().transforms.read().len());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn update_a_range_of_mesh_vertices() {
+    let (mut test_loop, test_client) = init_test_async();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let mut mesh = Mesh::new();
+            for i in 0..4 {
+                mesh.add_vertex(
+                    Vec3::new(i as f32, 0.0, 0.0),
+                    Vec3::unit_y(),
+                    None,
+                    None,
+                    Vec::new(),
+                    VertexBoneWeight {
+                        bone_ids: Vec4::new(0, 0, 0, 0),
+                        weights: Vec4::new(0.0, 0.0, 0.0, 0.0),
+                    },
+                );
+            }
+
+            let mesh_handler = test_client
+                .renderer_client()
+                .create_mesh(Arc::new(mesh))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let vertices = RendererMeshVertices::new(vec![Vec3::broadcast(1.0); 2])
+                .with_normals(vec![Vec3::unit_z(); 2]);
+            test_client
+                .renderer_client()
+                .update_mesh_vertices(mesh_handler.clone(), 1..3, vertices.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let mesh_vertex_updates = test_client.renderer_impl().mesh_vertex_updates.read();
+                assert_eq!(1, mesh_vertex_updates.len());
+                assert_eq!(
+                    vec![(1..3, vertices.clone())],
+                    *mesh_vertex_updates.values().next().unwrap()
+                );
+            }
+
+            // the range has to be inside of the mesh and match the number of values
+            assert!(matches!(
+                test_client
+                    .renderer_client()
+                    .update_mesh_vertices(mesh_handler.clone(), 3..5, vertices.clone())
+                    .await
+                    .unwrap(),
+                Err(RendererError::RendererImplError(_))
+            ));
+            assert!(matches!(
+                test_client
+                    .renderer_client()
+                    .update_mesh_vertices(mesh_handler.clone(), 0..3, vertices)
+                    .await
+                    .unwrap(),
+                Err(RendererError::RendererImplError(_))
+            ));
+
+            drop(mesh_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert!(test_client
+        .renderer_impl()
+        .mesh_vertex_updates
+        .read()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rebind_transform_and_mesh_of_renderer_object() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mesh_handler = test_client
+                .renderer_client()
+                .create_mesh(Arc::new(Mesh::default()))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_object_handler = test_client
+                .renderer_client()
+                .create_renderer_object_from_mesh(
+                    mesh_handler,
+                    shader_handler,
+                    material_handler,
+                    transform_handler,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let new_transform = Transform {
+                position: Vec3::new(1.0, 2.0, 3.0),
+                ..Default::default()
+            };
+            let new_transform_handler = test_client
+                .renderer_client()
+                .create_transform(new_transform)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let new_mesh = Arc::new(Mesh::default());
+            let new_mesh_handler = test_client
+                .renderer_client()
+                .create_mesh(new_mesh.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_renderer_object_transform(
+                    renderer_object_handler.clone(),
+                    new_transform_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_renderer_object_mesh(renderer_object_handler.clone(), new_mesh_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_objects = renderer_impl.renderer_objects.read();
+                assert_eq!(1, renderer_objects.len());
+                let renderer_object_data = renderer_objects.iter().next().unwrap().1;
+
+                assert_eq!(
+                    new_transform,
+                    *renderer_impl
+                        .transforms
+                        .read()
+                        .get(&renderer_object_data.transform)
+                        .unwrap()
+                );
+                assert!(Arc::ptr_eq(
+                    &new_mesh,
+                    renderer_impl
+                        .meshes
+                        .read()
+                        .get(&renderer_object_data.mesh)
+                        .unwrap()
+                ));
+            }
+
+            drop(renderer_object_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().renderer_objects.read().len());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_and_remove_uniform_overrides_of_renderer_object() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mesh_handler = test_client
+                .renderer_client()
+                .create_mesh(Arc::new(Mesh::default()))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_object_handler = test_client
+                .renderer_client()
+                .create_renderer_object_from_mesh(
+                    mesh_handler,
+                    shader_handler,
+                    material_handler,
+                    transform_handler,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let tint = RendererUniformValue::Vec4(Vec4::new(1.0, 0.5, 0.5, 1.0));
+            for (name, value) in [
+                ("tintColor", Some(tint)),
+                ("highlight", Some(RendererUniformValue::Float(1.0))),
+                ("highlight", None),
+            ] {
+                test_client
+                    .renderer_client()
+                    .set_renderer_object_uniform(
+                        renderer_object_handler.clone(),
+                        name.to_string(),
+                        value,
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap();
+            }
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_objects = renderer_impl.renderer_objects.read();
+                let renderer_object_data = renderer_objects.iter().next().unwrap().1;
+
+                assert_eq!(1, renderer_object_data.uniforms.len());
+                assert_eq!(Some(&tint), renderer_object_data.uniforms.get("tintColor"));
+            }
+
+            drop(renderer_object_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().renderer_objects.read().len());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn refresh_probe_and_change_refresh_mode() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(transform_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let probe_handler = test_client
+                .renderer_client()
+                .create_probe(
+                    transform_handler,
+                    renderer_layer_handler,
+                    128,
+                    RendererProbeRefreshMode::Manual,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            for _ in 0..2 {
+                test_client
+                    .renderer_client()
+                    .refresh_probe(probe_handler.clone())
+                    .await
+                    .unwrap()
+                    .unwrap();
+            }
+
+            test_client
+                .renderer_client()
+                .set_probe_refresh_mode(probe_handler.clone(), RendererProbeRefreshMode::EveryFrame)
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let probes = renderer_impl.probes.read();
+                assert_eq!(1, probes.len());
+                let probe_data = probes.iter().next().unwrap().1;
+
+                assert_eq!(128, probe_data.resolution);
+                assert_eq!(2, probe_data.refresh_count);
+                assert_eq!(
+                    RendererProbeRefreshMode::EveryFrame,
+                    probe_data.refresh_mode
+                );
+            }
+
+            drop(probe_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().probes.read().len());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_transform_parent() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let parent_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let child_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_transform_parent(child_handler.clone(), Some(parent_handler.clone()))
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(
+                1,
+                test_client.renderer_impl().transform_parents.read().len()
+            );
+
+            assert!(matches!(
+                test_client
+                    .renderer_client()
+                    .set_transform_parent(parent_handler.clone(), Some(child_handler.clone()))
+                    .await
+                    .unwrap(),
+                Err(RendererError::RendererImplError(_))
+            ));
+
+            drop(parent_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(
+        0,
+        test_client.renderer_impl().transform_parents.read().len()
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn recording_renderer_records_calls_and_handle_lifetimes() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(transform_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .recording_renderer()
+                .assert_live_handle_count(RendererHandleKind::RendererLayer, 1);
+
+            drop(renderer_layer_handler);
+            drop(camera_handler);
+            drop(transform_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let recording_renderer = test_client.recording_renderer();
+
+    recording_renderer.assert_call_count(RendererCallKind::CreateTransform, 1);
+    recording_renderer.assert_call_count(RendererCallKind::ReleaseTransform, 1);
+    recording_renderer.assert_call_order(&[
+        RendererCallKind::CreateTransform,
+        RendererCallKind::CreateCamera,
+        RendererCallKind::CreateRendererLayer,
+        RendererCallKind::ReleaseRendererLayer,
+    ]);
+    recording_renderer.assert_no_failed_calls();
+    recording_renderer.assert_no_live_handles();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn injected_failure_is_returned_without_creating_a_handler() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            test_client
+                .recording_renderer()
+                .fail_calls(RendererCallKind::CreateShader, "shader compilation failed");
+
+            let result = test_client
+                .renderer_client()
+                .create_shader("unlit".to_string())
+                .await
+                .unwrap();
+            assert!(matches!(
+                result,
+                Err(RendererError::RendererImplError(error)) if error == "shader compilation failed"
+            ));
+            // the failed call does not reach the inner renderer
+            assert_eq!(0, test_client.renderer_impl().shaders.read().len());
+
+            test_client
+                .recording_renderer()
+                .stop_failing_calls(RendererCallKind::CreateShader);
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("unlit".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(1, test_client.renderer_impl().shaders.read().len());
+
+            drop(shader_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(
+        0,
+        test_loop
+            .renderer_system()
+            .renderer_pri
+            .renderer_shaders
+            .read()
+            .len()
+    );
+
+    let recording_renderer = test_client.recording_renderer();
+    recording_renderer.assert_call_count(RendererCallKind::CreateShader, 2);
+    assert_eq!(
+        1,
+        recording_renderer.count_failed_calls(RendererCallKind::CreateShader)
+    );
+    recording_renderer.assert_call_order(&[
+        RendererCallKind::CreateShader,
+        RendererCallKind::CreateShader,
+        RendererCallKind::ReleaseShader,
+    ]);
+    recording_renderer.assert_no_live_handles();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_texture_memory_budget_and_read_renderer_stats() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            test_client
+                .renderer_client()
+                .set_texture_memory_budget(Some(64 * 1024 * 1024))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_stats = test_client
+                .renderer_client()
+                .get_renderer_stats()
+                .await
+                .unwrap();
+
+            assert_eq!(
+                Some(64 * 1024 * 1024),
+                renderer_stats.texture_memory_budget_bytes
+            );
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn renderer_stats_count_the_alive_objects() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_stats = test_client
+                .renderer_client()
+                .get_renderer_stats()
+                .await
+                .unwrap();
+            assert_eq!(1, renderer_stats.number_of_transforms);
+            assert_eq!(1, renderer_stats.number_of_cameras);
+            assert_eq!(0, renderer_stats.number_of_renderer_layers);
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_stats = test_client
+                .renderer_client()
+                .get_renderer_stats()
+                .await
+                .unwrap();
+            assert_eq!(1, renderer_stats.number_of_renderer_layers);
+
+            drop(renderer_layer_handler);
+
+            let renderer_stats = test_client
+                .renderer_client()
+                .get_renderer_stats()
+                .await
+                .unwrap();
+            assert_eq!(0, renderer_stats.number_of_renderer_layers);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn dump_state_counts_the_groups_in_layers_and_handlers_are_valid() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(transform_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_group_handler = test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .add_renderer_group_to_layer(
+                    renderer_group_handler.clone(),
+                    renderer_layer_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let state_dump = test_client.renderer_client().dump_state().await.unwrap();
+            assert_eq!(1, state_dump.number_of_renderer_layers);
+            assert_eq!(1, state_dump.number_of_renderer_groups);
+            assert_eq!(1, state_dump.number_of_groups_in_layers);
+            assert_eq!(0, state_dump.number_of_objects_in_groups);
+            assert!(state_dump.is_consistent(), "{state_dump:?}");
+
+            for handler in [
+                RendererHandler::from(transform_handler),
+                renderer_layer_handler.clone().into(),
+                renderer_group_handler.clone().into(),
+            ] {
+                assert!(test_client
+                    .renderer_client()
+                    .is_valid(handler)
+                    .await
+                    .unwrap());
+            }
+
+            drop(renderer_layer_handler);
+
+            let state_dump = test_client.renderer_client().dump_state().await.unwrap();
+            assert_eq!(0, state_dump.number_of_renderer_layers);
+            assert_eq!(0, state_dump.number_of_groups_in_layers);
+            assert!(state_dump.is_consistent(), "{state_dump:?}");
+
+            drop(renderer_group_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_debug_render_mode() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            test_client
+                .renderer_client()
+                .set_debug_render_mode(RendererDebugRenderMode::Wireframe)
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(
+                RendererDebugRenderMode::Wireframe,
+                *test_client.renderer_impl().debug_render_mode.read()
+            );
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn reload_shaders_keeps_shader_handlers_valid() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .reload_shaders()
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(
+                1,
+                *test_client.renderer_impl().number_of_shader_reloads.read()
+            );
+
+            test_client
+                .renderer_client()
+                .update_shader(shader_handler, "other shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let recording_renderer = test_client.recording_renderer();
+    recording_renderer.assert_call_order(&[
+        RendererCallKind::CreateShader,
+        RendererCallKind::ReloadShaders,
+        RendererCallKind::UpdateShader,
+        RendererCallKind::ReleaseShader,
+    ]);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn pick_renderer_object_of_layer() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let renderer_client = test_client.renderer_client();
+
+            let transform_handler = renderer_client
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+            let camera_handler = renderer_client
+                .create_camera(transform_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+            let renderer_layer_handler = renderer_client
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+            let renderer_group_handler = renderer_client
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            let material_handler = renderer_client
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+            let shader_handler = renderer_client
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+            let mesh_handler = renderer_client
+                .create_mesh(Arc::new(Mesh::default()))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut renderer_object_handlers = Vec::new();
+            for _ in 0..2 {
+                renderer_object_handlers.push(
+                    renderer_client
+                        .create_renderer_object_from_mesh(
+                            mesh_handler.clone(),
+                            shader_handler.clone(),
+                            material_handler.clone(),
+                            transform_handler.clone(),
+                        )
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                );
+            }
+
+            // only the first renderer object is in the layer
+            renderer_client
+                .add_renderer_object_to_group(
+                    renderer_object_handlers[0].clone(),
+                    renderer_group_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+            renderer_client
+                .add_renderer_group_to_layer(
+                    renderer_group_handler.clone(),
+                    renderer_layer_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let (renderer_object_in_layer, renderer_object_not_in_layer) = {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_groups = renderer_impl.renderer_groups.read();
+                let renderer_group = renderer_groups.iter().next().unwrap().1;
+                let renderer_object_in_layer = *renderer_group
+                    .renderer_objects
+                    .read()
+                    .iter()
+                    .next()
+                    .unwrap();
+                let renderer_object_not_in_layer = *renderer_impl
+                    .renderer_objects
+                    .read()
+                    .keys()
+                    .find(|renderer_object| **renderer_object != renderer_object_in_layer)
+                    .unwrap();
+
+                (renderer_object_in_layer, renderer_object_not_in_layer)
+            };
+
+            let screen_position = Vec2::new(400.0, 300.0);
+
+            assert_eq!(
+                None,
+                renderer_client
+                    .pick_renderer_object(renderer_layer_handler.clone(), screen_position)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            );
+
+            *test_client.renderer_impl().picked_renderer_object.write() =
+                Some(renderer_object_in_layer);
+            assert_eq!(
+                Some(renderer_object_handlers[0].clone()),
+                renderer_client
+                    .pick_renderer_object(renderer_layer_handler.clone(), screen_position)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            );
+
+            *test_client.renderer_impl().picked_renderer_object.write() =
+                Some(renderer_object_not_in_layer);
+            assert_eq!(
+                None,
+                renderer_client
+                    .pick_renderer_object(renderer_layer_handler, screen_position)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            );
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn draw_debug_gizmos() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let color = Vec4::new(1.0, 0.0, 0.0, 1.0);
+
+            test_client
+                .renderer_client()
+                .draw_debug_line(Vec3::zero(), Vec3::unit_x(), color, 0.0)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut aabb = AxisAlignedBoundingBox::new(Vec3::broadcast(-1.0));
+            aabb.add_vertex(Vec3::broadcast(1.0));
+            test_client
+                .renderer_client()
+                .draw_debug_aabb(aabb, color, 2.0)
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let debug_lines = renderer_impl.debug_lines.read();
+
+                assert_eq!(1 + 12, debug_lines.len());
+                assert_eq!(0.0, debug_lines[0].1);
+                assert!(debug_lines[1..]
+                    .iter()
+                    .all(|(line, lifetime_secs)| *lifetime_secs == 2.0
+                        && (line.end - line.start).magnitude() == 2.0));
+            }
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_renderer_layer_settings_and_override_shader() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let settings = RendererLayerSettings {
+                clear_color: Some(Vec4::new(0.0, 0.0, 0.0, 1.0)),
+                clear_depth: true,
+                exposure: 2.0,
+                occlusion_culling: true,
+                stencil: None,
+                reverse_z: false,
+            };
+
+            test_client
+                .renderer_client()
+                .set_renderer_layer_settings(renderer_layer_handler.clone(), settings)
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_renderer_layer_override_shader(
+                    renderer_layer_handler.clone(),
+                    Some(shader_handler),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_layers = renderer_impl.renderer_layers.read();
+                let renderer_layer = renderer_layers.values().next().unwrap();
+
+                assert_eq!(settings, *renderer_layer.settings.read());
+                assert!(renderer_layer.override_shader.read().is_some());
+            }
+
+            test_client
+                .renderer_client()
+                .set_renderer_layer_override_shader(renderer_layer_handler, None)
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_layers = renderer_impl.renderer_layers.read();
+                let renderer_layer = renderer_layers.values().next().unwrap();
+
+                assert!(renderer_layer.override_shader.read().is_none());
+            }
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn update_renderer_layer_projection() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let projection = RendererLayerProjection::Orthographic {
+                height: 10.0,
+                near: 0.1,
+                far: 100.0,
+            };
+
+            test_client
+                .renderer_client()
+                .update_renderer_layer_projection(renderer_layer_handler.clone(), Some(projection))
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_layers = renderer_impl.renderer_layers.read();
+                let renderer_layer = renderer_layers.values().next().unwrap();
+
+                assert_eq!(Some(projection), *renderer_layer.projection.read());
+            }
+
+            test_client
+                .renderer_client()
+                .update_renderer_layer_projection(renderer_layer_handler, None)
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_layers = renderer_impl.renderer_layers.read();
+                let renderer_layer = renderer_layers.values().next().unwrap();
+
+                assert!(renderer_layer.projection.read().is_none());
+            }
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn update_camera_parameters_and_release_them_with_the_camera() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let parameters = RendererCameraParameters {
+                fov_y_degrees: 30.0,
+                near: 0.1,
+                far: 500.0,
+            };
+
+            test_client
+                .renderer_client()
+                .update_camera_parameters(camera_handler.clone(), Some(parameters))
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let camera_parameters = renderer_impl.camera_parameters.read();
+
+                assert_eq!(
+                    vec![&parameters],
+                    camera_parameters.values().collect::<Vec<_>>()
+                );
+            }
+
+            drop(camera_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert!(test_client.renderer_impl().cameras.read().is_empty());
+    assert!(test_client
+        .renderer_impl()
+        .camera_parameters
+        .read()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_renderer_pipeline_with_shadow_pass() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let camera_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(camera_transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let light_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let light_camera_handler = test_client
+                .renderer_client()
+                .create_camera(light_transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_renderer_pipeline(vec![
+                    RendererPipelineStep::ShadowPass {
+                        renderer_layer_handler: renderer_layer_handler.clone(),
+                        light_camera_handler,
+                        resolution: 1024,
+                        light_projection_matrix: Mat4::identity(),
+                    },
+                    RendererPipelineStep::Draw {
+                        renderer_layer_handler,
+                        viewport_start_ndc: Vec2::zero(),
+                        viewport_end_ndc: Vec2::broadcast(1.0),
+                        compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+                    },
+                ])
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let recording_renderer = test_client.recording_renderer();
+
+    recording_renderer.assert_call_count(RendererCallKind::SetRendererPipeline, 1);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_renderer_pipeline_with_split_screen_viewports() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let mut renderer_layer_handlers = Vec::new();
+            for _ in 0..2 {
+                let camera_transform_handler = test_client
+                    .renderer_client()
+                    .create_transform(Transform::default())
+                    .await
+                    .unwrap()
+                    .unwrap();
+
+                let camera_handler = test_client
+                    .renderer_client()
+                    .create_camera(camera_transform_handler)
+                    .await
+                    .unwrap()
+                    .unwrap();
+
+                renderer_layer_handlers.push(
+                    test_client
+                        .renderer_client()
+                        .create_renderer_layer(camera_handler)
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                );
+            }
+
+            let draw_steps = |viewports: Vec<(Vec2<f32>, Vec2<f32>)>| {
+                viewports
+                    .into_iter()
+                    .zip(renderer_layer_handlers.iter())
+                    .map(
+                        |((viewport_start_ndc, viewport_end_ndc), renderer_layer_handler)| {
+                            RendererPipelineStep::Draw {
+                                renderer_layer_handler: renderer_layer_handler.clone(),
+                                viewport_start_ndc,
+                                viewport_end_ndc,
+                                compute_projection_matrix: Arc::new(|_width, _height| {
+                                    Mat4::identity()
+                                }),
+                            }
+                        },
+                    )
+                    .collect::<Vec<_>>()
+            };
+
+            test_client
+                .renderer_client()
+                .set_renderer_pipeline(draw_steps(split_screen_viewports_ndc(2)))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let result = test_client
+                .renderer_client()
+                .set_renderer_pipeline(draw_steps(vec![
+                    (Vec2::zero(), Vec2::new(0.5, 1.0)),
+                    (Vec2::new(0.5, 0.0), Vec2::new(1.5, 1.0)),
+                ]))
+                .await
+                .unwrap();
+            assert!(matches!(result, Err(RendererError::InvalidViewport { .. })));
+
+            let result = test_client
+                .renderer_client()
+                .set_renderer_pipeline(draw_steps(vec![(Vec2::new(0.5, 0.0), Vec2::new(0.5, 1.0))]))
+                .await
+                .unwrap();
+            assert!(matches!(result, Err(RendererError::InvalidViewport { .. })));
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let recording_renderer = test_client.recording_renderer();
+
+    // the invalid pipelines never reach the renderer implementation
+    recording_renderer.assert_call_count(RendererCallKind::SetRendererPipeline, 1);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn secondary_window_gets_its_own_pipeline_and_is_released_with_its_handler() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let camera_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(camera_transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let window_handler = test_client
+                .renderer_client()
+                .create_window("inspector".to_string(), Vec2::new(320, 240))
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_window_pipeline(
+                    window_handler.clone(),
+                    vec![
+                        RendererPipelineStep::Clear {
+                            depth: true,
+                            color: true,
+                            stencil: false,
+                            clear_color: Vec4::zero(),
+                            viewport_start_ndc: Vec2::zero(),
+                            viewport_end_ndc: Vec2::broadcast(1.0),
+                        },
+                        RendererPipelineStep::Draw {
+                            renderer_layer_handler: renderer_layer_handler.clone(),
+                            viewport_start_ndc: Vec2::zero(),
+                            viewport_end_ndc: Vec2::broadcast(1.0),
+                            compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+                        },
+                    ],
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let windows = test_client.renderer_impl().windows.read();
+                assert_eq!(1, windows.len());
+
+                let window = windows.values().next().unwrap();
+                assert_eq!("inspector", window.title);
+                assert_eq!(Vec2::new(320, 240), window.dimensions);
+                assert_eq!(2, window.number_of_pipeline_steps);
+            }
+
+            let result = test_client
+                .renderer_client()
+                .set_window_pipeline(
+                    window_handler.clone(),
+                    vec![RendererPipelineStep::Draw {
+                        renderer_layer_handler,
+                        viewport_start_ndc: Vec2::broadcast(1.0),
+                        viewport_end_ndc: Vec2::zero(),
+                        compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+                    }],
+                )
+                .await
+                .unwrap();
+            assert!(matches!(result, Err(RendererError::InvalidViewport { .. })));
+
+            drop(window_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().windows.read().len());
+
+    let recording_renderer = test_client.recording_renderer();
+
+    recording_renderer.assert_call_count(RendererCallKind::CreateWindow, 1);
+    recording_renderer.assert_call_count(RendererCallKind::SetWindowPipeline, 1);
+    recording_renderer.assert_call_count(RendererCallKind::ReleaseWindow, 1);
+    recording_renderer.assert_live_handle_count(RendererHandleKind::Window, 0);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_instanced_renderer_object_with_a_transform_for_each_instance() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let mut transforms = Vec::new();
+            let mut transform_handlers = Vec::new();
+            for i in 0..3 {
+                let transform = Transform {
+                    position: Vec3::new(i as f32, 0.0, 0.0),
+                    ..Default::default()
+                };
+                transforms.push(transform);
+                transform_handlers.push(
+                    test_client
+                        .renderer_client()
+                        .create_transform(transform)
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                );
+            }
+
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mesh_handler = test_client
+                .renderer_client()
+                .create_mesh(Arc::new(Mesh::default()))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_object_handler = test_client
+                .renderer_client()
+                .create_instanced_renderer_object(
+                    mesh_handler,
+                    shader_handler,
+                    material_handler,
+                    transform_handlers.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_objects = renderer_impl.renderer_objects.read();
+                assert_eq!(1, renderer_objects.len());
+                let renderer_object_data = renderer_objects.iter().next().unwrap().1;
+
+                let instance_transforms = renderer_object_data
+                    .instance_transforms
+                    .iter()
+                    .map(|transform| *renderer_impl.transforms.read().get(transform).unwrap())
+                    .collect::<Vec<_>>();
+                assert_eq!(transforms, instance_transforms);
+            }
+
+            drop(renderer_object_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().renderer_objects.read().len());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_lod_renderer_object_with_meshes_ordered_by_distance() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let near_mesh = Arc::new(Mesh::default());
+            let middle_mesh = Arc::new(Mesh::default());
+            let far_mesh = Arc::new(Mesh::default());
+
+            let mut lod_mesh_handlers = Vec::new();
+            for (distance, mesh) in [(50.0, &far_mesh), (0.0, &near_mesh), (20.0, &middle_mesh)] {
+                let mesh_handler = test_client
+                    .renderer_client()
+                    .create_mesh(mesh.clone())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                lod_mesh_handlers.push((distance, mesh_handler));
+            }
+
+            let result = test_client
+                .renderer_client()
+                .create_lod_renderer_object(
+                    Vec::new(),
+                    shader_handler.clone(),
+                    material_handler.clone(),
+                    transform_handler.clone(),
+                )
+                .await
+                .unwrap();
+            assert!(matches!(result, Err(RendererError::RendererImplError(_))));
+
+            let renderer_object_handler = test_client
+                .renderer_client()
+                .create_lod_renderer_object(
+                    lod_mesh_handlers,
+                    shader_handler,
+                    material_handler,
+                    transform_handler,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let renderer_objects = renderer_impl.renderer_objects.read();
+                assert_eq!(1, renderer_objects.len());
+                let renderer_object_data = renderer_objects.iter().next().unwrap().1;
+
+                let meshes = renderer_impl.meshes.read();
+                let lod_meshes = renderer_object_data
+                    .lod_meshes
+                    .iter()
+                    .map(|(distance, mesh)| (*distance, meshes.get(mesh).unwrap().clone()))
+                    .collect::<Vec<_>>();
+
+                assert_eq!(3, lod_meshes.len());
+                for ((distance, mesh), (expected_distance, expected_mesh)) in lod_meshes
+                    .iter()
+                    .zip([(0.0, &near_mesh), (20.0, &middle_mesh), (50.0, &far_mesh)])
+                {
+                    assert_eq!(expected_distance, *distance);
+                    assert!(Arc::ptr_eq(expected_mesh, mesh));
+                }
+
+                assert!(Arc::ptr_eq(
+                    &near_mesh,
+                    meshes.get(&renderer_object_data.mesh).unwrap()
+                ));
+            }
+
+            drop(renderer_object_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().renderer_objects.read().len());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn draw_to_texture_and_use_the_render_texture_in_a_material() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let camera_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(camera_transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_texture_handler = test_client
+                .renderer_client()
+                .create_render_texture(Vec2::new(256, 128))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_renderer_pipeline(vec![RendererPipelineStep::DrawToTexture {
+                    renderer_layer_handler,
+                    renderer_texture_handler: renderer_texture_handler.clone(),
+                    compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+                }])
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_material_render_texture(
+                    material_handler.clone(),
+                    MaterialTextureType::Albedo,
+                    renderer_texture_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+
+                let render_textures = renderer_impl.render_textures.read();
+                assert_eq!(1, render_textures.len());
+                assert_eq!(
+                    Vec2::new(256, 128),
+                    *render_textures.values().next().unwrap()
+                );
+
+                let material_render_textures = renderer_impl.material_render_textures.read();
+                assert_eq!(1, material_render_textures.len());
+                let (texture_type, render_texture) =
+                    material_render_textures.values().next().unwrap()[0];
+                assert_eq!(MaterialTextureType::Albedo, texture_type);
+                assert!(*render_textures.keys().next().unwrap() == render_texture);
+            }
+
+            test_client
+                .renderer_client()
+                .set_renderer_pipeline(Vec::new())
+                .await
+                .unwrap()
+                .unwrap();
+
+            drop(material_handler);
+            drop(renderer_texture_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().render_textures.read().len());
+
+    let recording_renderer = test_client.recording_renderer();
+
+    recording_renderer.assert_call_count(RendererCallKind::CreateRenderTexture, 1);
+    recording_renderer.assert_call_count(RendererCallKind::ReleaseRenderTexture, 1);
+    recording_renderer.assert_live_handle_count(RendererHandleKind::Texture, 0);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn set_renderer_pipeline_with_post_process() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let camera_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(camera_transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some post process shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let input_texture_handler = test_client
+                .renderer_client()
+                .create_render_texture(Vec2::new(256, 256))
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_renderer_pipeline(vec![
+                    RendererPipelineStep::Draw {
+                        renderer_layer_handler,
+                        viewport_start_ndc: Vec2::zero(),
+                        viewport_end_ndc: Vec2::broadcast(1.0),
+                        compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+                    },
+                    RendererPipelineStep::PostProcess {
+                        shader_handler,
+                        input_texture_handlers: vec![input_texture_handler],
+                    },
+                ])
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let recording_renderer = test_client.recording_renderer();
+
+    recording_renderer.assert_call_count(RendererCallKind::SetRendererPipeline, 1);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn pipeline_that_samples_a_render_texture_before_drawing_it_is_rejected() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let camera_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(camera_transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some post process shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let render_texture_handler = test_client
+                .renderer_client()
+                .create_render_texture(Vec2::new(256, 256))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let pipeline = |post_process_first: bool| {
+                let mut steps = vec![
+                    RendererPipelineStep::DrawToTexture {
+                        renderer_layer_handler: renderer_layer_handler.clone(),
+                        renderer_texture_handler: render_texture_handler.clone(),
+                        compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+                    },
+                    RendererPipelineStep::PostProcess {
+                        shader_handler: shader_handler.clone(),
+                        input_texture_handlers: vec![render_texture_handler.clone()],
+                    },
+                ];
+                if post_process_first {
+                    steps.reverse();
+                }
+                steps
+            };
+
+            let result = test_client
+                .renderer_client()
+                .set_renderer_pipeline(pipeline(true))
+                .await
+                .unwrap();
+            assert!(matches!(
+                result,
+                Err(RendererError::InvalidPipeline {
+                    step_index: 0,
+                    reason: InvalidPipelineReason::TextureConsumedBeforeProduced {
+                        producer_step_index: 1
+                    },
+                })
+            ));
+
+            test_client
+                .renderer_client()
+                .set_renderer_pipeline(pipeline(false))
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    // the rejected pipeline does not reach the renderer
+    let recording_renderer = test_client.recording_renderer();
+    recording_renderer.assert_call_count(RendererCallKind::SetRendererPipeline, 1);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn pipeline_that_draws_a_material_or_shadow_texture_before_producing_it_is_rejected() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let camera_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let camera_handler = test_client
+                .renderer_client()
+                .create_camera(camera_transform_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mirrored_renderer_layer_handler = test_client
+                .renderer_client()
+                .create_renderer_layer(camera_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let render_texture_handler = test_client
+                .renderer_client()
+                .create_render_texture(Vec2::new(256, 256))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .set_material_render_texture(
+                    material_handler.clone(),
+                    MaterialTextureType::Albedo,
+                    render_texture_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_object_handler = test_client
+                .renderer_client()
+                .create_renderer_object_from_mesh(
+                    test_client
+                        .renderer_client()
+                        .create_mesh(Arc::new(Mesh::default()))
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                    test_client
+                        .renderer_client()
+                        .create_shader("some shader name".to_string())
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                    material_handler,
+                    test_client
+                        .renderer_client()
+                        .create_transform(Transform::default())
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_group_handler = test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .add_renderer_object_to_group(
+                    renderer_object_handler.clone(),
+                    renderer_group_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .add_renderer_group_to_layer(
+                    renderer_group_handler.clone(),
+                    renderer_layer_handler.clone(),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let draw_step =
+                |renderer_layer_handler: &RendererLayerHandler| RendererPipelineStep::Draw {
+                    renderer_layer_handler: renderer_layer_handler.clone(),
+                    viewport_start_ndc: Vec2::zero(),
+                    viewport_end_ndc: Vec2::broadcast(1.0),
+                    compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+                };
+            let draw_to_texture_step = || RendererPipelineStep::DrawToTexture {
+                renderer_layer_handler: mirrored_renderer_layer_handler.clone(),
+                renderer_texture_handler: render_texture_handler.clone(),
+                compute_projection_matrix: Arc::new(|_width, _height| Mat4::identity()),
+            };
+            let shadow_pass_step = || RendererPipelineStep::ShadowPass {
+                renderer_layer_handler: renderer_layer_handler.clone(),
+                light_camera_handler: camera_handler.clone(),
+                resolution: 1024,
+                light_projection_matrix: Mat4::identity(),
+            };
+
+            // the material of the object in the layer samples the texture of the later step
+            let result = test_client
+                .renderer_client()
+                .set_renderer_pipeline(vec![
+                    draw_step(&renderer_layer_handler),
+                    draw_to_texture_step(),
+                ])
+                .await
+                .unwrap();
+            assert!(matches!(
+                result,
+                Err(RendererError::InvalidPipeline {
+                    step_index: 0,
+                    reason: InvalidPipelineReason::TextureConsumedBeforeProduced {
+                        producer_step_index: 1
+                    },
+                })
+            ));
+
+            // the shadow map of the layer is bound to the draw steps after the shadow pass only
+            let result = test_client
+                .renderer_client()
+                .set_renderer_pipeline(vec![
+                    draw_to_texture_step(),
+                    draw_step(&renderer_layer_handler),
+                    shadow_pass_step(),
+                ])
+                .await
+                .unwrap();
+            assert!(matches!(
+                result,
+                Err(RendererError::InvalidPipeline {
+                    step_index: 1,
+                    reason: InvalidPipelineReason::TextureConsumedBeforeProduced {
+                        producer_step_index: 2
+                    },
+                })
+            ));
+
+            test_client
+                .renderer_client()
+                .set_renderer_pipeline(vec![
+                    draw_to_texture_step(),
+                    shadow_pass_step(),
+                    draw_step(&renderer_layer_handler),
+                ])
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let recording_renderer = test_client.recording_renderer();
+    recording_renderer.assert_call_count(RendererCallKind::SetRendererPipeline, 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn renderer_config_is_applied_before_the_first_frame() {
+    let renderer_config = RendererConfig {
+        msaa_samples: 4,
+        offscreen_msaa_samples: 2,
+        debug_output: true,
+    };
+    let (mut test_loop, test_client) = init_test_sync_with_config(renderer_config);
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            assert_eq!(
+                Some(renderer_config),
+                *test_client.renderer_impl().renderer_config.read()
+            );
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let recording_renderer = test_client.recording_renderer();
+    recording_renderer.assert_call_count(RendererCallKind::SetRendererConfig, 1);
+    recording_renderer.assert_call_order(&[
+        RendererCallKind::SetRendererConfig,
+        RendererCallKind::WindowDimensionsChanged,
+        RendererCallKind::Render,
+    ]);
+    recording_renderer.assert_no_failed_calls();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_skybox_renderer_object_from_cubemap() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let faces = std::array::from_fn(|_| Arc::new(Image::new(4, 4, ColorType::Rgba8)));
+            let cubemap_handler = test_client
+                .renderer_client()
+                .create_cubemap_texture(faces)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let result = test_client
+                .renderer_client()
+                .create_cubemap_texture(std::array::from_fn(|index| {
+                    Arc::new(Image::new(4, 4 + index, ColorType::Rgba8))
+                }))
+                .await
+                .unwrap();
+            assert!(matches!(result, Err(RendererError::RendererImplError(_))));
+
+            let renderer_group_handler = test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            let skybox_handler = test_client
+                .renderer_client()
+                .create_skybox_renderer_object(cubemap_handler.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .add_renderer_object_to_group(skybox_handler.clone(), renderer_group_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+
+                let cubemap_textures = renderer_impl.cubemap_textures.read();
+                assert_eq!(1, cubemap_textures.len());
+                assert_eq!(4, *cubemap_textures.values().next().unwrap());
+
+                let skybox_renderer_objects = renderer_impl.skybox_renderer_objects.read();
+                assert_eq!(1, skybox_renderer_objects.len());
+                assert!(
+                    *cubemap_textures.keys().next().unwrap()
+                        == *skybox_renderer_objects.values().next().unwrap()
+                );
+            }
+
+            drop(skybox_handler);
+            drop(cubemap_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().cubemap_textures.read().len());
+    assert_eq!(
+        0,
+        test_client
+            .renderer_impl()
+            .skybox_renderer_objects
+            .read()
+            .len()
+    );
+
+    let recording_renderer = test_client.recording_renderer();
+    recording_renderer.assert_call_order(&[
+        RendererCallKind::CreateCubemapTexture,
+        RendererCallKind::CreateSkyboxRendererObject,
+        RendererCallKind::AddRendererObjectToGroup,
+        RendererCallKind::ReleaseRendererObject,
+        RendererCallKind::ReleaseRenderTexture,
+    ]);
+    recording_renderer.assert_no_live_handles();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_particle_emitter_and_change_its_settings() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_group_handler = test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            let emitter_handler = test_client
+                .renderer_client()
+                .create_particle_emitter_renderer_object(
+                    ParticleEmitterSettings::default(),
+                    material_handler,
+                    transform_handler,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .add_renderer_object_to_group(emitter_handler.clone(), renderer_group_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let settings = ParticleEmitterSettings {
+                spawn_rate: 100.0,
+                size_curve: ParticleCurve::linear(0.5, 0.0),
+                color_curve: ParticleCurve::linear(Vec4::one(), Vec4::zero()),
+                ..Default::default()
+            };
+            test_client
+                .renderer_client()
+                .set_particle_emitter_settings(emitter_handler.clone(), settings.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let particle_emitters = renderer_impl.particle_emitter_renderer_objects.read();
+                assert_eq!(1, particle_emitters.len());
+                assert_eq!(
+                    settings,
+                    particle_emitters.values().next().unwrap().settings
+                );
+            }
+
+            let mesh_handler = test_client
+                .renderer_client()
+                .create_mesh(Arc::new(Mesh::default()))
+                .await
+                .unwrap()
+                .unwrap();
+
+            // the particles are billboards, so emitters have no mesh
+            let result = test_client
+                .renderer_client()
+                .set_renderer_object_mesh(emitter_handler.clone(), mesh_handler)
+                .await
+                .unwrap();
+            assert!(matches!(result, Err(RendererError::RendererImplError(_))));
+
+            drop(emitter_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(
+        0,
+        test_client
+            .renderer_impl()
+            .particle_emitter_renderer_objects
+            .read()
+            .len()
+    );
+    test_client.recording_renderer().assert_no_live_handles();
+}
+
+#[tokio::test]
+async fn create_sprite_batch_and_replace_its_sprites() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let texture_handler = test_client
+                .renderer_client()
+                .create_texture(Arc::new(Image::new(4, 4, ColorType::Rgba8)))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let cubemap_handler = test_client
+                .renderer_client()
+                .create_cubemap_texture(std::array::from_fn(|_| {
+                    Arc::new(Image::new(4, 4, ColorType::Rgba8))
+                }))
+                .await
+                .unwrap()
+                .unwrap();
+
+            // the uv rects of the sprites cannot address a cubemap
+            let result = test_client
+                .renderer_client()
+                .create_sprite_batch_renderer_object(
+                    cubemap_handler,
+                    Vec::new(),
+                    transform_handler.clone(),
+                )
+                .await
+                .unwrap();
+            assert!(matches!(result, Err(RendererError::RendererImplError(_))));
+
+            let sprite_batch_handler = test_client
+                .renderer_client()
+                .create_sprite_batch_renderer_object(
+                    texture_handler,
+                    vec![RendererSprite::default()],
+                    transform_handler,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let renderer_group_handler = test_client
+                .renderer_client()
+                .create_renderer_group()
+                .await
+                .unwrap()
+                .unwrap();
+
+            test_client
+                .renderer_client()
+                .add_renderer_object_to_group(sprite_batch_handler.clone(), renderer_group_handler)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let sprites = vec![
+                RendererSprite {
+                    position: Vec2::new(0.0, 0.0),
+                    size: Vec2::new(8.0, 8.0),
+                    ..Default::default()
+                },
+                RendererSprite {
+                    position: Vec2::new(8.0, 0.0),
+                    size: Vec2::new(8.0, 8.0),
+                    color: Vec4::new(1.0, 0.0, 0.0, 1.0),
+                    ..Default::default()
+                },
+            ];
+            test_client
+                .renderer_client()
+                .set_sprite_batch_sprites(sprite_batch_handler.clone(), sprites.clone())
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                let sprite_batches = renderer_impl.sprite_batch_renderer_objects.read();
+                assert_eq!(1, sprite_batches.len());
+                assert_eq!(sprites, sprite_batches.values().next().unwrap().sprites);
+            }
+
+            drop(sprite_batch_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(
+        0,
+        test_client
+            .renderer_impl()
+            .sprite_batch_renderer_objects
+            .read()
+            .len()
+    );
+    test_client.recording_renderer().assert_no_live_handles();
+}
+
+#[tokio::test]
+async fn text_renderer_object_is_updated_in_place_and_releases_its_atlas() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let text_handler = test_client
+                .renderer_client()
+                .create_text_renderer_object(
+                    "ab".to_string(),
+                    arc_rw_lock_new(CachedFont::hack()),
+                    0.1,
+                    Vec4::one(),
+                    transform_handler,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                assert_eq!(1, renderer_impl.image_textures.read().len());
+                let sprite_batches = renderer_impl.sprite_batch_renderer_objects.read();
+                assert_eq!(1, sprite_batches.len());
+                assert_eq!(2, sprite_batches.values().next().unwrap().sprites.len());
+            }
+
+            // the space has no glyph, so it only moves the following ones
+            test_client
+                .renderer_client()
+                .set_text_renderer_object_text(text_handler.clone(), "ab c".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+                assert_eq!(1, renderer_impl.image_textures.read().len());
+                let sprite_batches = renderer_impl.sprite_batch_renderer_objects.read();
+                assert_eq!(1, sprite_batches.len());
+                assert_eq!(3, sprite_batches.values().next().unwrap().sprites.len());
+            }
+
+            let mesh_handler = test_client
+                .renderer_client()
+                .create_mesh(Arc::new(Mesh::default()))
+                .await
+                .unwrap()
+                .unwrap();
+            let shader_handler = test_client
+                .renderer_client()
+                .create_shader("some shader name".to_string())
+                .await
+                .unwrap()
+                .unwrap();
+            let material_handler = test_client
+                .renderer_client()
+                .create_material(Material::default())
+                .await
+                .unwrap()
+                .unwrap();
+            let mesh_transform_handler = test_client
+                .renderer_client()
+                .create_transform(Transform::default())
+                .await
+                .unwrap()
+                .unwrap();
+            let mesh_renderer_object_handler = test_client
+                .renderer_client()
+                .create_renderer_object_from_mesh(
+                    mesh_handler,
+                    shader_handler,
+                    material_handler,
+                    mesh_transform_handler,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            let result = test_client
+                .renderer_client()
+                .set_text_renderer_object_text(mesh_renderer_object_handler, "a".to_string())
+                .await
+                .unwrap();
+            assert!(matches!(
+                result,
+                Err(RendererError::InvalidRendererObjectHandler(_))
+            ));
+
+            drop(text_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    let renderer_impl = test_client.renderer_impl();
+    assert_eq!(0, renderer_impl.sprite_batch_renderer_objects.read().len());
+    assert_eq!(0, renderer_impl.image_textures.read().len());
+    test_client.recording_renderer().assert_no_live_handles();
+}
+
+#[tokio::test]
+async fn image_texture_is_shared_by_materials_and_updated_in_place() {
+    let (mut test_loop, test_client) = init_test_sync();
+
+    let test_task = {
+        let test_client = test_client.clone();
+        tokio::spawn(async move {
+            let texture_handler = test_client
+                .renderer_client()
+                .create_texture(Arc::new(Image::new(2, 2, ColorType::Rgba8)))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut material_handlers = Vec::new();
+            for _ in 0..2 {
+                let material_handler = test_client
+                    .renderer_client()
+                    .create_material(Material::default())
+                    .await
+                    .unwrap()
+                    .unwrap();
+
+                test_client
+                    .renderer_client()
+                    .set_material_render_texture(
+                        material_handler.clone(),
+                        MaterialTextureType::Albedo,
+                        texture_handler.clone(),
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap();
+
+                material_handlers.push(material_handler);
+            }
+
+            test_client
+                .renderer_client()
+                .update_texture(
+                    texture_handler.clone(),
+                    Arc::new(Image::new(4, 4, ColorType::Rgba8)),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            {
+                let renderer_impl = test_client.renderer_impl();
+
+                let image_textures = renderer_impl.image_textures.read();
+                assert_eq!(1, image_textures.len());
+                let (texture_ptr, image) = image_textures.iter().next().unwrap();
+                assert_eq!(4, image.width());
+
+                let material_render_textures = renderer_impl.material_render_textures.read();
+                assert_eq!(2, material_render_textures.len());
+                assert!(material_render_textures.values().all(|render_textures| {
+                    render_textures
+                        .iter()
+                        .any(|(_, render_texture)| render_texture == texture_ptr)
+                }));
+            }
+
+            drop(material_handlers);
+            drop(texture_handler);
+
+            test_client.stop_main_loop();
+        })
+    };
+
+    test_loop.block_on_main_loop(Duration::from_secs(1)).await;
+
+    test_task.await.unwrap();
+
+    assert_eq!(0, test_client.renderer_impl().image_textures.read().len());
+
+    let recording_renderer = test_client.recording_renderer();
+    recording_renderer.assert_call_order(&[
+        RendererCallKind::CreateTexture,
+        RendererCallKind::SetMaterialRenderTexture,
+        RendererCallKind::SetMaterialRenderTexture,
+        RendererCallKind::UpdateTexture,
+        RendererCallKind::ReleaseRenderTexture,
+    ]);
+    recording_renderer.assert_no_failed_calls();
+    recording_renderer.assert_no_live_handles();
+}
@@ -1,4 +1,13 @@
-use std::{any::TypeId, collections::HashMap};
+use std::{
+    any::{type_name, TypeId},
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
 
 use method_taskifier::{
     method_taskifier_impl,
@@ -12,6 +21,8 @@ use bytifex_utils::{
     sync::types::ArcRwLock,
 };
 
+use crate::profile_scope;
+
 pub trait System: 'static {
     fn tick(&mut self, loop_start: &std::time::Instant, last_loop_time_secs: f32);
 }
@@ -19,9 +30,66 @@ pub trait System: 'static {
 pub struct SystemContainer {
     systems_multi_type_dict: MultiTypeDict,
     systems_by_type_id: HashMap<TypeId, ArcRwLock<dyn System>>,
+    dedicated_system_threads: HashMap<TypeId, DedicatedSystemThread>,
     task_receiver: TaskReceiver<ChanneledTask>,
 }
 
+struct DedicatedSystemThread {
+    should_run: Arc<AtomicBool>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl DedicatedSystemThread {
+    fn spawn<SystemType: System + Send + Sync>(
+        system: ArcRwLock<SystemType>,
+        tick_interval: Duration,
+    ) -> std::io::Result<Self> {
+        let should_run = Arc::new(AtomicBool::new(true));
+
+        let join_handle = {
+            let should_run = should_run.clone();
+            thread::Builder::new()
+                .name(type_name::<SystemType>().to_string())
+                .spawn(move || {
+                    let mut last_loop_time_secs = tick_interval.as_secs_f32();
+
+                    while should_run.load(Ordering::Relaxed) {
+                        let loop_start = Instant::now();
+
+                        {
+                            profile_scope!(type_name::<SystemType>());
+                            system.write().tick(&loop_start, last_loop_time_secs);
+                        }
+
+                        let tick_duration = loop_start.elapsed();
+                        if tick_duration < tick_interval {
+                            thread::sleep(tick_interval - tick_duration);
+                        }
+
+                        last_loop_time_secs = loop_start.elapsed().as_secs_f32();
+                    }
+                })?
+        };
+
+        Ok(Self {
+            should_run,
+            join_handle: Some(join_handle),
+        })
+    }
+}
+
+impl Drop for DedicatedSystemThread {
+    fn drop(&mut self) {
+        self.should_run.store(false, Ordering::Relaxed);
+
+        if let Some(join_handle) = self.join_handle.take() {
+            if join_handle.join().is_err() {
+                log::error!("StopDedicatedSystemThread, msg = system thread panicked");
+            }
+        }
+    }
+}
+
 #[method_taskifier_impl(
     task_definitions_module_path = self,
     client_name = SystemContainerClient,
@@ -35,6 +103,7 @@ impl SystemContainer {
             Self {
                 systems_multi_type_dict: MultiTypeDict::new(),
                 systems_by_type_id: HashMap::new(),
+                dedicated_system_threads: HashMap::new(),
                 task_receiver,
             },
             SystemContainerClient::new(task_sender),
@@ -83,12 +152,47 @@ impl SystemContainer {
         if self.systems_by_type_id.contains_key(&type_id) {
             self.systems_by_type_id.remove(&type_id);
         }
+        self.dedicated_system_threads.remove(&type_id);
         let result = self.systems_multi_type_dict.insert(RwLock::new(system));
         self.systems_by_type_id
             .insert(type_id, result.new_item.as_arc_ref().clone());
         result
     }
 
+    pub fn add_system_on_dedicated_thread<SystemType: System + Send + Sync>(
+        &mut self,
+        system: SystemType,
+        tick_interval: Duration,
+    ) -> MultiTypeDictInsertResult<RwLock<SystemType>> {
+        let type_id = TypeId::of::<SystemType>();
+        self.systems_by_type_id.remove(&type_id);
+        self.dedicated_system_threads.remove(&type_id);
+
+        let result = self.systems_multi_type_dict.insert(RwLock::new(system));
+
+        match DedicatedSystemThread::spawn(result.new_item.as_arc_ref().clone(), tick_interval) {
+            Ok(dedicated_system_thread) => {
+                self.dedicated_system_threads
+                    .insert(type_id, dedicated_system_thread);
+            }
+            Err(e) => {
+                log::error!(
+                    "AddSystemOnDedicatedThread, system = {}, msg = {e}, falling back to the main loop",
+                    type_name::<SystemType>()
+                );
+                self.systems_by_type_id
+                    .insert(type_id, result.new_item.as_arc_ref().clone());
+            }
+        }
+
+        result
+    }
+
+    pub fn is_system_on_dedicated_thread<SystemType: System>(&self) -> bool {
+        self.dedicated_system_threads
+            .contains_key(&TypeId::of::<SystemType>())
+    }
+
     pub fn get_system<SystemType: System>(&self) -> Option<MultiTypeDictItem<RwLock<SystemType>>> {
         self.systems_multi_type_dict.get_item_ref()
     }
@@ -102,9 +206,11 @@ impl SystemContainer {
             .get_or_insert_item_ref(|| RwLock::new(system_constructor()));
 
         let type_id = TypeId::of::<SystemType>();
-        self.systems_by_type_id
-            .entry(type_id)
-            .or_insert_with(|| result.as_arc_ref().clone());
+        if !self.dedicated_system_threads.contains_key(&type_id) {
+            self.systems_by_type_id
+                .entry(type_id)
+                .or_insert_with(|| result.as_arc_ref().clone());
+        }
 
         result
     }
@@ -112,6 +218,69 @@ impl SystemContainer {
     pub fn remove<SystemType: System>(&mut self) {
         let type_id = TypeId::of::<SystemType>();
         self.systems_by_type_id.remove(&type_id);
+        self.dedicated_system_threads.remove(&type_id);
         self.systems_multi_type_dict.remove::<RwLock<SystemType>>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::{System, SystemContainer};
+
+    struct TickCounterSystem {
+        thread_id: Option<thread::ThreadId>,
+        tick_count: usize,
+    }
+
+    impl System for TickCounterSystem {
+        fn tick(&mut self, _loop_start: &Instant, _last_loop_time_secs: f32) {
+            self.thread_id = Some(thread::current().id());
+            self.tick_count += 1;
+        }
+    }
+
+    #[test]
+    fn system_on_dedicated_thread_ticks_without_main_loop() {
+        let (mut system_container, _client) = SystemContainer::new_with_client();
+
+        system_container.add_system_on_dedicated_thread(
+            TickCounterSystem {
+                thread_id: None,
+                tick_count: 0,
+            },
+            Duration::from_millis(1),
+        );
+        assert!(system_container.is_system_on_dedicated_thread::<TickCounterSystem>());
+
+        let start = Instant::now();
+        while system_container
+            .get_system::<TickCounterSystem>()
+            .unwrap()
+            .as_arc_ref()
+            .read()
+            .tick_count
+            == 0
+        {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let system = system_container.get_system::<TickCounterSystem>().unwrap();
+        assert_ne!(
+            Some(thread::current().id()),
+            system.as_arc_ref().read().thread_id
+        );
+
+        system_container.remove::<TickCounterSystem>();
+        assert!(!system_container.is_system_on_dedicated_thread::<TickCounterSystem>());
+
+        let tick_count = system.as_arc_ref().read().tick_count;
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(tick_count, system.as_arc_ref().read().tick_count);
+    }
+}
@@ -1 +1,2 @@
 pub mod sendable_ptr;
+pub mod test_renderer_impl;
@@ -0,0 +1,1678 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    sync::Arc,
+    time::Instant,
+};
+
+use bytifex_utils::sync::types::{arc_rw_lock_new, ArcRwLock};
+use vek::{Transform, Vec2, Vec4};
+
+use crate::{
+    image::Image,
+    mesh::{Material, MaterialTextureType, Mesh},
+    renderer::{
+        particle_system::ParticleEmitterSettings,
+        renderer_camera_parameters::RendererCameraParameters, renderer_config::RendererConfig,
+        renderer_debug_lines::RendererDebugLine,
+        renderer_debug_render_mode::RendererDebugRenderMode, renderer_impl::RendererImpl,
+        renderer_layer_projection::RendererLayerProjection,
+        renderer_layer_settings::RendererLayerSettings,
+        renderer_mesh_vertices::RendererMeshVertices, renderer_pipeline_step_impl,
+        renderer_probe_refresh_mode::RendererProbeRefreshMode, renderer_sprite::RendererSprite,
+        renderer_stats::RendererStats, renderer_uniform_value::RendererUniformValue,
+        RendererCamera, RendererGroup, RendererLayer, RendererMaterial, RendererMesh,
+        RendererObject, RendererProbe, RendererShader, RendererTexture, RendererTransform,
+        RendererWindow,
+    },
+    system_container::System,
+    test_utils::sendable_ptr::SendablePtr,
+    window_context::{EventSender, WindowContext},
+};
+
+pub struct TestWindowContext {
+    event_sender: EventSender,
+}
+
+impl Default for TestWindowContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestWindowContext {
+    pub fn new() -> Self {
+        Self {
+            event_sender: EventSender::new(),
+        }
+    }
+}
+
+impl WindowContext for TestWindowContext {
+    fn is_key_pressed(&self, _key: crate::window_context::Key) -> bool {
+        todo!()
+    }
+
+    fn is_mouse_button_pressed(&self, _button: crate::window_context::MouseButton) -> bool {
+        todo!()
+    }
+
+    fn mouse_pos(&self) -> vek::Vec2<isize> {
+        todo!()
+    }
+
+    fn set_fullscreen(&mut self, _fullscreen: bool) {
+        todo!()
+    }
+
+    fn window_dimensions(&self) -> vek::Vec2<usize> {
+        vek::Vec2::new(800, 600)
+    }
+
+    fn show_cursor(&mut self, _show: bool) {
+        todo!()
+    }
+
+    fn warp_mouse_normalized_screen_space(&mut self, _pos: vek::Vec2<f32>) {
+        todo!()
+    }
+
+    fn event_sender(&self) -> &crate::window_context::EventSender {
+        &self.event_sender
+    }
+
+    fn poll_event(&mut self) -> Option<crate::window_context::Event> {
+        todo!()
+    }
+
+    fn swap_buffers(&self) {
+        todo!()
+    }
+
+    fn create_secondary_window(
+        &mut self,
+        _title: &str,
+        _dimensions: vek::Vec2<usize>,
+    ) -> Result<crate::window_context::SecondaryWindowId, String> {
+        todo!()
+    }
+
+    fn destroy_secondary_window(
+        &mut self,
+        _secondary_window_id: crate::window_context::SecondaryWindowId,
+    ) -> Result<(), String> {
+        todo!()
+    }
+
+    fn secondary_window_dimensions(
+        &self,
+        _secondary_window_id: crate::window_context::SecondaryWindowId,
+    ) -> Option<vek::Vec2<usize>> {
+        todo!()
+    }
+
+    fn make_window_current(
+        &self,
+        _secondary_window_id: Option<crate::window_context::SecondaryWindowId>,
+    ) -> Result<(), String> {
+        todo!()
+    }
+
+    fn swap_secondary_window_buffers(
+        &self,
+        _secondary_window_id: crate::window_context::SecondaryWindowId,
+    ) {
+        todo!()
+    }
+}
+
+impl System for TestWindowContext {
+    fn tick(&mut self, _loop_start: &std::time::Instant, _last_loop_time_secs: f32) {}
+}
+
+#[derive(Clone)]
+pub struct TestRendererImpl {
+    pub renderer_steps: Vec<renderer_pipeline_step_impl::RendererPipelineStepImpl>,
+    pub renderer_config: ArcRwLock<Option<RendererConfig>>,
+    pub renderer_groups: ArcRwLock<BTreeMap<SendablePtr<dyn RendererGroup>, TestRendererGroupImpl>>,
+    pub renderer_layers: ArcRwLock<BTreeMap<SendablePtr<dyn RendererLayer>, TestRendererLayerImpl>>,
+    pub transforms:
+        ArcRwLock<BTreeMap<SendablePtr<dyn RendererTransform>, Transform<f32, f32, f32>>>,
+    pub transform_parents:
+        ArcRwLock<BTreeMap<SendablePtr<dyn RendererTransform>, SendablePtr<dyn RendererTransform>>>,
+    pub materials: ArcRwLock<BTreeMap<SendablePtr<dyn RendererMaterial>, Material>>,
+    pub shaders: ArcRwLock<BTreeMap<SendablePtr<dyn RendererShader>, String>>,
+    pub meshes: ArcRwLock<BTreeMap<SendablePtr<dyn RendererMesh>, Arc<Mesh>>>,
+    // the partial updates of each mesh in the order they were made
+    pub mesh_vertex_updates: ArcRwLock<
+        BTreeMap<SendablePtr<dyn RendererMesh>, Vec<(Range<usize>, RendererMeshVertices)>>,
+    >,
+    pub cameras: ArcRwLock<BTreeSet<SendablePtr<dyn RendererCamera>>>,
+    pub camera_parameters:
+        ArcRwLock<BTreeMap<SendablePtr<dyn RendererCamera>, RendererCameraParameters>>,
+    pub probes: ArcRwLock<BTreeMap<SendablePtr<dyn RendererProbe>, TestRendererProbeData>>,
+    pub windows: ArcRwLock<BTreeMap<SendablePtr<dyn RendererWindow>, TestRendererWindowData>>,
+    pub render_textures: ArcRwLock<BTreeMap<SendablePtr<dyn RendererTexture>, Vec2<usize>>>,
+    pub image_textures: ArcRwLock<BTreeMap<SendablePtr<dyn RendererTexture>, Arc<Image>>>,
+    // the resolution of the faces of the cubemaps
+    pub cubemap_textures: ArcRwLock<BTreeMap<SendablePtr<dyn RendererTexture>, usize>>,
+    pub material_render_textures: ArcRwLock<
+        BTreeMap<
+            SendablePtr<dyn RendererMaterial>,
+            Vec<(MaterialTextureType, SendablePtr<dyn RendererTexture>)>,
+        >,
+    >,
+    pub texture_memory_budget_bytes: ArcRwLock<Option<usize>>,
+    pub debug_render_mode: ArcRwLock<RendererDebugRenderMode>,
+    pub debug_lines: ArcRwLock<Vec<(RendererDebugLine, f32)>>,
+    pub number_of_shader_reloads: ArcRwLock<usize>,
+    // the renderer object that is reported to be under the cursor
+    pub picked_renderer_object: ArcRwLock<Option<SendablePtr<dyn RendererObject>>>,
+
+    pub renderer_objects:
+        ArcRwLock<BTreeMap<SendablePtr<dyn RendererObject>, TestRendererObjectData>>,
+    // the cubemap of each skybox renderer object
+    pub skybox_renderer_objects:
+        ArcRwLock<BTreeMap<SendablePtr<dyn RendererObject>, SendablePtr<dyn RendererTexture>>>,
+    pub particle_emitter_renderer_objects:
+        ArcRwLock<BTreeMap<SendablePtr<dyn RendererObject>, TestParticleEmitterData>>,
+    pub sprite_batch_renderer_objects:
+        ArcRwLock<BTreeMap<SendablePtr<dyn RendererObject>, TestSpriteBatchData>>,
+}
+
+#[derive(Clone)]
+pub struct TestRendererObjectData {
+    pub mesh: SendablePtr<dyn RendererMesh>,
+    pub shader: SendablePtr<dyn RendererShader>,
+    pub material: SendablePtr<dyn RendererMaterial>,
+    pub transform: SendablePtr<dyn RendererTransform>,
+    pub instance_transforms: Vec<SendablePtr<dyn RendererTransform>>,
+    pub lod_meshes: Vec<(f32, SendablePtr<dyn RendererMesh>)>,
+    pub probe: Option<SendablePtr<dyn RendererProbe>>,
+    pub uniforms: BTreeMap<String, RendererUniformValue>,
+}
+
+#[derive(Clone)]
+pub struct TestParticleEmitterData {
+    pub settings: ParticleEmitterSettings,
+    pub material: SendablePtr<dyn RendererMaterial>,
+    pub transform: SendablePtr<dyn RendererTransform>,
+}
+
+#[derive(Clone)]
+pub struct TestSpriteBatchData {
+    pub texture: SendablePtr<dyn RendererTexture>,
+    pub sprites: Vec<RendererSprite>,
+    pub transform: SendablePtr<dyn RendererTransform>,
+}
+
+#[derive(Clone)]
+pub struct TestRendererWindowData {
+    pub title: String,
+    pub dimensions: Vec2<usize>,
+    pub number_of_pipeline_steps: usize,
+}
+
+#[derive(Clone)]
+pub struct TestRendererProbeData {
+    pub transform: SendablePtr<dyn RendererTransform>,
+    pub renderer_layer: SendablePtr<dyn RendererLayer>,
+    pub resolution: usize,
+    pub refresh_mode: RendererProbeRefreshMode,
+    pub refresh_count: usize,
+}
+
+impl Default for TestRendererImpl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestRendererImpl {
+    pub fn new() -> Self {
+        Self {
+            renderer_steps: Vec::new(),
+            renderer_config: arc_rw_lock_new(None),
+            renderer_groups: arc_rw_lock_new(BTreeMap::new()),
+            renderer_layers: arc_rw_lock_new(BTreeMap::new()),
+            transforms: arc_rw_lock_new(BTreeMap::new()),
+            transform_parents: arc_rw_lock_new(BTreeMap::new()),
+            materials: arc_rw_lock_new(BTreeMap::new()),
+            shaders: arc_rw_lock_new(BTreeMap::new()),
+            meshes: arc_rw_lock_new(BTreeMap::new()),
+            mesh_vertex_updates: arc_rw_lock_new(BTreeMap::new()),
+            cameras: arc_rw_lock_new(BTreeSet::new()),
+            camera_parameters: arc_rw_lock_new(BTreeMap::new()),
+            probes: arc_rw_lock_new(BTreeMap::new()),
+            windows: arc_rw_lock_new(BTreeMap::new()),
+            render_textures: arc_rw_lock_new(BTreeMap::new()),
+            image_textures: arc_rw_lock_new(BTreeMap::new()),
+            cubemap_textures: arc_rw_lock_new(BTreeMap::new()),
+            material_render_textures: arc_rw_lock_new(BTreeMap::new()),
+            texture_memory_budget_bytes: arc_rw_lock_new(None),
+            debug_render_mode: arc_rw_lock_new(RendererDebugRenderMode::default()),
+            debug_lines: arc_rw_lock_new(Vec::new()),
+            number_of_shader_reloads: arc_rw_lock_new(0),
+            picked_renderer_object: arc_rw_lock_new(None),
+            renderer_objects: arc_rw_lock_new(BTreeMap::new()),
+            skybox_renderer_objects: arc_rw_lock_new(BTreeMap::new()),
+            particle_emitter_renderer_objects: arc_rw_lock_new(BTreeMap::new()),
+            sprite_batch_renderer_objects: arc_rw_lock_new(BTreeMap::new()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TestRendererLayerImpl {
+    pub renderer_groups: ArcRwLock<BTreeSet<SendablePtr<dyn RendererGroup>>>,
+    pub settings: ArcRwLock<RendererLayerSettings>,
+    pub projection: ArcRwLock<Option<RendererLayerProjection>>,
+    pub override_shader: ArcRwLock<Option<SendablePtr<dyn RendererShader>>>,
+}
+
+impl RendererLayer for TestRendererLayerImpl {}
+
+impl Default for TestRendererLayerImpl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestRendererLayerImpl {
+    pub fn new() -> Self {
+        Self {
+            renderer_groups: arc_rw_lock_new(BTreeSet::new()),
+            settings: arc_rw_lock_new(RendererLayerSettings::default()),
+            projection: arc_rw_lock_new(None),
+            override_shader: arc_rw_lock_new(None),
+        }
+    }
+
+    pub fn add_renderer_group(&mut self, renderer_group: ArcRwLock<dyn RendererGroup>) -> bool {
+        self.renderer_groups
+            .write()
+            .insert(SendablePtr::new(renderer_group.data_ptr()))
+    }
+
+    pub fn remove_renderer_group(&mut self, renderer_group: &ArcRwLock<dyn RendererGroup>) -> bool {
+        self.renderer_groups
+            .write()
+            .remove(&SendablePtr::new(renderer_group.data_ptr()))
+    }
+}
+
+#[derive(Clone)]
+pub struct TestRendererGroupImpl {
+    pub renderer_objects: ArcRwLock<BTreeSet<SendablePtr<dyn RendererObject>>>,
+}
+
+impl RendererGroup for TestRendererGroupImpl {}
+
+impl Default for TestRendererGroupImpl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestRendererGroupImpl {
+    pub fn new() -> Self {
+        Self {
+            renderer_objects: arc_rw_lock_new(BTreeSet::new()),
+        }
+    }
+
+    pub fn add_renderer_object(&mut self, renderer_object: ArcRwLock<dyn RendererObject>) -> bool {
+        self.renderer_objects
+            .write()
+            .insert(SendablePtr::new(renderer_object.data_ptr()))
+    }
+
+    pub fn remove_renderer_object(
+        &mut self,
+        renderer_object: &ArcRwLock<dyn RendererObject>,
+    ) -> bool {
+        self.renderer_objects
+            .write()
+            .remove(&SendablePtr::new(renderer_object.data_ptr()))
+    }
+}
+
+pub struct TestRendererTransformImpl;
+impl RendererTransform for TestRendererTransformImpl {}
+
+pub struct TestRendererMaterialImpl;
+impl RendererMaterial for TestRendererMaterialImpl {}
+
+pub struct TestRendererShaderImpl;
+impl RendererShader for TestRendererShaderImpl {}
+
+pub struct TestRendererMeshImpl;
+impl RendererMesh for TestRendererMeshImpl {}
+
+pub struct TestRendererCameraImpl;
+impl RendererCamera for TestRendererCameraImpl {}
+
+pub struct TestRendererObjectImpl;
+impl RendererObject for TestRendererObjectImpl {}
+
+pub struct TestRendererProbeImpl;
+impl RendererProbe for TestRendererProbeImpl {}
+
+pub struct TestRendererTextureImpl;
+impl RendererTexture for TestRendererTextureImpl {}
+
+pub struct TestRendererWindowImpl;
+impl RendererWindow for TestRendererWindowImpl {}
+
+impl RendererImpl for TestRendererImpl {
+    fn window_dimensions_changed(&mut self, _width: usize, _height: usize) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_renderer_config(&mut self, renderer_config: RendererConfig) -> Result<(), String> {
+        *self.renderer_config.write() = Some(renderer_config);
+        Ok(())
+    }
+
+    fn set_renderer_pipeline(
+        &mut self,
+        steps: Vec<renderer_pipeline_step_impl::RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        self.renderer_steps = steps;
+        Ok(())
+    }
+
+    fn set_pipeline_clear_color(
+        &mut self,
+        step_index: usize,
+        clear_color: Vec4<f32>,
+    ) -> Result<(), String> {
+        renderer_pipeline_step_impl::set_pipeline_step_clear_color(
+            &mut self.renderer_steps,
+            step_index,
+            clear_color,
+        )
+    }
+
+    fn create_window(
+        &mut self,
+        title: &str,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererWindow>, String> {
+        let window = arc_rw_lock_new(TestRendererWindowImpl);
+        self.windows.write().insert(
+            SendablePtr::new(window.data_ptr()),
+            TestRendererWindowData {
+                title: title.to_string(),
+                dimensions,
+                number_of_pipeline_steps: 0,
+            },
+        );
+        Ok(window)
+    }
+
+    fn set_window_pipeline(
+        &mut self,
+        window: ArcRwLock<dyn RendererWindow>,
+        steps: Vec<renderer_pipeline_step_impl::RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        self.windows
+            .write()
+            .get_mut(&SendablePtr::new(window.data_ptr()))
+            .ok_or_else(|| "Setting window pipeline, msg = could not find window".to_string())?
+            .number_of_pipeline_steps = steps.len();
+        Ok(())
+    }
+
+    fn release_window(&mut self, window: ArcRwLock<dyn RendererWindow>) -> Result<(), String> {
+        self.windows
+            .write()
+            .remove(&SendablePtr::new(window.data_ptr()))
+            .ok_or_else(|| "Releasing window, msg = could not find RendererWindow")?;
+        Ok(())
+    }
+
+    fn create_renderer_layer(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+    ) -> Result<ArcRwLock<dyn RendererLayer>, String> {
+        self.cameras
+            .read()
+            .get(&SendablePtr::new(camera.data_ptr()))
+            .ok_or_else(|| "Creating renderer layer, msg = could not find camera".to_string())?;
+
+        let renderer_layer = arc_rw_lock_new(TestRendererLayerImpl::new());
+        self.renderer_layers.write().insert(
+            SendablePtr::new(renderer_layer.data_ptr()),
+            renderer_layer.read().clone(),
+        );
+        Ok(renderer_layer)
+    }
+
+    fn release_renderer_layer(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        self.renderer_layers
+            .write()
+            .remove(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| "Releasing renderer layer, msg = could not find RendererLayer")?;
+        Ok(())
+    }
+
+    fn set_renderer_layer_settings(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        settings: RendererLayerSettings,
+    ) -> Result<(), String> {
+        *self
+            .renderer_layers
+            .read()
+            .get(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| {
+                "Setting renderer layer settings, msg = could not find renderer layer".to_string()
+            })?
+            .settings
+            .write() = settings;
+        Ok(())
+    }
+
+    fn update_renderer_layer_projection(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        projection: Option<RendererLayerProjection>,
+    ) -> Result<(), String> {
+        *self
+            .renderer_layers
+            .read()
+            .get(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| {
+                "Updating renderer layer projection, msg = could not find renderer layer"
+                    .to_string()
+            })?
+            .projection
+            .write() = projection;
+        Ok(())
+    }
+
+    fn set_renderer_layer_override_shader(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        shader: Option<ArcRwLock<dyn RendererShader>>,
+    ) -> Result<(), String> {
+        let shader = match shader {
+            Some(shader) => {
+                let shader = SendablePtr::new(shader.data_ptr());
+                if !self.shaders.read().contains_key(&shader) {
+                    return Err(
+                        "Setting override shader of renderer layer, msg = could not find shader"
+                            .to_string(),
+                    );
+                }
+                Some(shader)
+            }
+            None => None,
+        };
+
+        *self
+            .renderer_layers
+            .read()
+            .get(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| {
+                "Setting override shader of renderer layer, msg = could not find renderer layer"
+                    .to_string()
+            })?
+            .override_shader
+            .write() = shader;
+        Ok(())
+    }
+
+    fn add_renderer_group_to_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        self.renderer_groups
+            .read()
+            .get(&SendablePtr::new(renderer_group.data_ptr()))
+            .ok_or_else(|| {
+                "Adding renderer group to layer, msg = could not find renderer group".to_string()
+            })?;
+
+        self.renderer_layers
+            .write()
+            .get_mut(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| {
+                "Adding renderer group to layer, msg = could not find renderer layer".to_string()
+            })?
+            .add_renderer_group(renderer_group);
+
+        Ok(())
+    }
+
+    fn remove_renderer_group_from_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        self.renderer_groups
+            .read()
+            .get(&SendablePtr::new(renderer_group.data_ptr()))
+            .ok_or_else(|| {
+                "Removing renderer group from layer, msg = could not find renderer group"
+                    .to_string()
+            })?;
+
+        self.renderer_layers
+            .write()
+            .get_mut(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| {
+                "Removing renderer group from layer, msg = could not find renderer layer"
+                    .to_string()
+            })?
+            .remove_renderer_group(&renderer_group);
+
+        Ok(())
+    }
+
+    fn create_renderer_group(&mut self) -> Result<ArcRwLock<dyn RendererGroup>, String> {
+        let renderer_group = arc_rw_lock_new(TestRendererGroupImpl::new());
+        self.renderer_groups.write().insert(
+            SendablePtr::new(renderer_group.data_ptr()),
+            renderer_group.read().clone(),
+        );
+        Ok(renderer_group)
+    }
+
+    fn release_renderer_group(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        self.renderer_groups
+            .write()
+            .remove(&SendablePtr::new(renderer_group.data_ptr()))
+            .ok_or_else(|| "Releasing renderer group, msg = could not find RendererGroup")?;
+        Ok(())
+    }
+
+    fn create_transform(
+        &mut self,
+        transform: Transform<f32, f32, f32>,
+    ) -> Result<ArcRwLock<dyn RendererTransform>, String> {
+        let renderer_transform = arc_rw_lock_new(TestRendererTransformImpl);
+        self.transforms
+            .write()
+            .insert(SendablePtr::new(renderer_transform.data_ptr()), transform);
+        Ok(renderer_transform)
+    }
+
+    fn update_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+    ) -> Result<(), String> {
+        self.transforms
+            .write()
+            .get_mut(&SendablePtr::new(transform.data_ptr()))
+            .and_then(|transform| {
+                *transform = new_transform;
+                Some(())
+            })
+            .ok_or_else(|| "Updating transform, msg = could not find transform".to_string())
+    }
+
+    fn update_transform_interpolated(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+        _timestamp: Instant,
+    ) -> Result<(), String> {
+        self.update_transform(transform, new_transform)
+    }
+
+    fn set_transform_parent(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        parent_transform: Option<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<(), String> {
+        let transform = SendablePtr::new(transform.data_ptr());
+        self.transforms
+            .read()
+            .contains_key(&transform)
+            .then_some(())
+            .ok_or_else(|| {
+                "Setting transform parent, msg = could not find transform".to_string()
+            })?;
+
+        let mut transform_parents = self.transform_parents.write();
+
+        if let Some(parent_transform) = parent_transform {
+            let parent_transform = SendablePtr::new(parent_transform.data_ptr());
+            self.transforms
+                .read()
+                .contains_key(&parent_transform)
+                .then_some(())
+                .ok_or_else(|| {
+                    "Setting transform parent, msg = could not find parent transform".to_string()
+                })?;
+
+            let mut ancestor = Some(&parent_transform);
+            while let Some(current) = ancestor {
+                if *current == transform {
+                    return Err(
+                        "Setting transform parent, msg = transform hierarchy would contain a cycle"
+                            .to_string(),
+                    );
+                }
+                ancestor = transform_parents.get(current);
+            }
+
+            transform_parents.insert(transform, parent_transform);
+        } else {
+            transform_parents.remove(&transform);
+        }
+
+        Ok(())
+    }
+
+    fn release_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String> {
+        let transform = SendablePtr::new(transform.data_ptr());
+        self.transforms
+            .write()
+            .remove(&transform)
+            .ok_or_else(|| "Releasing transform, msg = could not find RendererTransform")?;
+
+        self.transform_parents
+            .write()
+            .retain(|child, parent| *child != transform && *parent != transform);
+
+        Ok(())
+    }
+
+    fn create_material(
+        &mut self,
+        material: crate::mesh::Material,
+    ) -> Result<ArcRwLock<dyn RendererMaterial>, String> {
+        let renderer_material = arc_rw_lock_new(TestRendererMaterialImpl);
+        self.materials
+            .write()
+            .insert(SendablePtr::new(renderer_material.data_ptr()), material);
+        Ok(renderer_material)
+    }
+
+    fn update_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        new_material: Material,
+    ) -> Result<(), String> {
+        self.materials
+            .write()
+            .get_mut(&SendablePtr::new(material.data_ptr()))
+            .and_then(|material| {
+                *material = new_material;
+                Some(())
+            })
+            .ok_or_else(|| "Updating material, msg = could not find material".to_string())
+    }
+
+    fn release_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
+        self.materials
+            .write()
+            .remove(&SendablePtr::new(material.data_ptr()))
+            .ok_or_else(|| "Releasing material, msg = could not find RendererMaterial")?;
+        self.material_render_textures
+            .write()
+            .remove(&SendablePtr::new(material.data_ptr()));
+        Ok(())
+    }
+
+    fn set_material_render_texture(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        texture_type: MaterialTextureType,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        self.materials
+            .read()
+            .get(&SendablePtr::new(material.data_ptr()))
+            .ok_or_else(|| {
+                "Setting render texture of material, msg = could not find material".to_string()
+            })?;
+
+        let texture_ptr = SendablePtr::new(texture.data_ptr());
+        if texture_type == MaterialTextureType::EnvironmentCube {
+            self.cubemap_textures
+                .read()
+                .get(&texture_ptr)
+                .ok_or_else(|| {
+                    "Setting render texture of material, msg = could not find cubemap texture"
+                        .to_string()
+                })?;
+        } else if !self.render_textures.read().contains_key(&texture_ptr)
+            && !self.image_textures.read().contains_key(&texture_ptr)
+        {
+            return Err(
+                "Setting render texture of material, msg = could not find render texture"
+                    .to_string(),
+            );
+        }
+
+        let mut material_render_textures = self.material_render_textures.write();
+        let render_textures = material_render_textures
+            .entry(SendablePtr::new(material.data_ptr()))
+            .or_default();
+        render_textures.retain(|(render_texture_type, _)| *render_texture_type != texture_type);
+        render_textures.push((texture_type, SendablePtr::new(texture.data_ptr())));
+
+        Ok(())
+    }
+
+    fn create_render_texture(
+        &mut self,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let render_texture = arc_rw_lock_new(TestRendererTextureImpl);
+        self.render_textures
+            .write()
+            .insert(SendablePtr::new(render_texture.data_ptr()), dimensions);
+        Ok(render_texture)
+    }
+
+    fn release_render_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        let texture_ptr = SendablePtr::new(texture.data_ptr());
+        self.render_textures
+            .write()
+            .remove(&texture_ptr)
+            .map(|_| ())
+            .or_else(|| self.image_textures.write().remove(&texture_ptr).map(|_| ()))
+            .or_else(|| {
+                self.cubemap_textures
+                    .write()
+                    .remove(&texture_ptr)
+                    .map(|_| ())
+            })
+            .ok_or_else(|| "Releasing render texture, msg = could not find RendererTexture")?;
+        Ok(())
+    }
+
+    fn create_texture(
+        &mut self,
+        image: Arc<Image>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let texture = arc_rw_lock_new(TestRendererTextureImpl);
+        self.image_textures
+            .write()
+            .insert(SendablePtr::new(texture.data_ptr()), image);
+        Ok(texture)
+    }
+
+    fn update_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        image: Arc<Image>,
+    ) -> Result<(), String> {
+        self.image_textures
+            .write()
+            .get_mut(&SendablePtr::new(texture.data_ptr()))
+            .map(|texture_image| *texture_image = image)
+            .ok_or_else(|| "Updating texture, msg = could not find image texture".to_string())
+    }
+
+    fn create_cubemap_texture(
+        &mut self,
+        faces: [Arc<Image>; 6],
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let resolution = faces[0].width();
+        if faces
+            .iter()
+            .any(|face| face.width() != resolution || face.height() != resolution)
+        {
+            return Err(
+                "Creating cubemap texture, msg = the faces must be squares of the same size"
+                    .to_string(),
+            );
+        }
+
+        let cubemap_texture = arc_rw_lock_new(TestRendererTextureImpl);
+        self.cubemap_textures
+            .write()
+            .insert(SendablePtr::new(cubemap_texture.data_ptr()), resolution);
+        Ok(cubemap_texture)
+    }
+
+    fn create_shader(
+        &mut self,
+        shader_name: String,
+    ) -> Result<ArcRwLock<dyn RendererShader>, String> {
+        let renderer_shader = arc_rw_lock_new(TestRendererShaderImpl);
+        self.shaders
+            .write()
+            .insert(SendablePtr::new(renderer_shader.data_ptr()), shader_name);
+        Ok(renderer_shader)
+    }
+
+    fn update_shader(
+        &mut self,
+        shader: ArcRwLock<dyn RendererShader>,
+        new_shader_name: String,
+    ) -> Result<(), String> {
+        self.shaders
+            .write()
+            .get_mut(&SendablePtr::new(shader.data_ptr()))
+            .and_then(|shader| {
+                *shader = new_shader_name;
+                Some(())
+            })
+            .ok_or_else(|| "Updating shader, msg = could not find shader".to_string())
+    }
+
+    fn release_shader(&mut self, shader: ArcRwLock<dyn RendererShader>) -> Result<(), String> {
+        self.shaders
+            .write()
+            .remove(&SendablePtr::new(shader.data_ptr()))
+            .ok_or_else(|| "Releasing shader, msg = could not find RendererShader")?;
+        Ok(())
+    }
+
+    fn create_mesh(
+        &mut self,
+        mesh: Arc<crate::mesh::Mesh>,
+    ) -> Result<ArcRwLock<dyn RendererMesh>, String> {
+        let renderer_mesh = arc_rw_lock_new(TestRendererMeshImpl);
+        self.meshes
+            .write()
+            .insert(SendablePtr::new(renderer_mesh.data_ptr()), mesh);
+        Ok(renderer_mesh)
+    }
+
+    fn update_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        new_mesh: Arc<Mesh>,
+    ) -> Result<(), String> {
+        self.meshes
+            .write()
+            .get_mut(&SendablePtr::new(mesh.data_ptr()))
+            .and_then(|mesh| {
+                *mesh = new_mesh;
+                Some(())
+            })
+            .ok_or_else(|| "Updating mesh, msg = could not find mesh".to_string())
+    }
+
+    fn update_mesh_vertices(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        vertex_range: Range<usize>,
+        vertices: RendererMeshVertices,
+    ) -> Result<(), String> {
+        let mesh_ptr = SendablePtr::new(mesh.data_ptr());
+        let number_of_vertices = self
+            .meshes
+            .read()
+            .get(&mesh_ptr)
+            .ok_or_else(|| "Updating mesh vertices, msg = could not find mesh".to_string())?
+            .number_of_vertices();
+
+        vertices
+            .validate(&vertex_range, number_of_vertices)
+            .map_err(|e| format!("Updating mesh vertices, msg = {e}"))?;
+
+        self.mesh_vertex_updates
+            .write()
+            .entry(mesh_ptr)
+            .or_default()
+            .push((vertex_range, vertices));
+
+        Ok(())
+    }
+
+    fn release_mesh(&mut self, mesh: ArcRwLock<dyn RendererMesh>) -> Result<(), String> {
+        let mesh_ptr = SendablePtr::new(mesh.data_ptr());
+        self.meshes
+            .write()
+            .remove(&mesh_ptr)
+            .ok_or_else(|| "Releasing mesh, msg = could not find RendererMesh")?;
+        self.mesh_vertex_updates.write().remove(&mesh_ptr);
+        Ok(())
+    }
+
+    fn create_renderer_object_from_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        self.shaders
+            .read()
+            .get(&SendablePtr::new(shader.data_ptr()))
+            .ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find shader".to_string()
+            })?;
+
+        self.materials
+            .read()
+            .get(&SendablePtr::new(material.data_ptr()))
+            .ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find material".to_string()
+            })?;
+
+        self.meshes
+            .read()
+            .get(&SendablePtr::new(mesh.data_ptr()))
+            .ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find mesh".to_string()
+            })?;
+
+        self.transforms
+            .read()
+            .get(&SendablePtr::new(transform.data_ptr()))
+            .ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find transform".to_string()
+            })?;
+
+        let renderer_object = arc_rw_lock_new(TestRendererObjectImpl);
+        self.renderer_objects.write().insert(
+            SendablePtr::new(renderer_object.data_ptr()),
+            TestRendererObjectData {
+                mesh: SendablePtr::new(mesh.data_ptr()),
+                shader: SendablePtr::new(shader.data_ptr()),
+                material: SendablePtr::new(material.data_ptr()),
+                transform: SendablePtr::new(transform.data_ptr()),
+                instance_transforms: Vec::new(),
+                lod_meshes: Vec::new(),
+                probe: None,
+                uniforms: BTreeMap::new(),
+            },
+        );
+        Ok(renderer_object)
+    }
+
+    fn create_instanced_renderer_object(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transforms: Vec<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        self.shaders
+            .read()
+            .get(&SendablePtr::new(shader.data_ptr()))
+            .ok_or_else(|| {
+                "Creating instanced renderer object, msg = could not find shader".to_string()
+            })?;
+
+        self.materials
+            .read()
+            .get(&SendablePtr::new(material.data_ptr()))
+            .ok_or_else(|| {
+                "Creating instanced renderer object, msg = could not find material".to_string()
+            })?;
+
+        self.meshes
+            .read()
+            .get(&SendablePtr::new(mesh.data_ptr()))
+            .ok_or_else(|| {
+                "Creating instanced renderer object, msg = could not find mesh".to_string()
+            })?;
+
+        let mut instance_transforms = Vec::with_capacity(transforms.len());
+        for transform in transforms {
+            self.transforms
+                .read()
+                .get(&SendablePtr::new(transform.data_ptr()))
+                .ok_or_else(|| {
+                    "Creating instanced renderer object, msg = could not find transform".to_string()
+                })?;
+            instance_transforms.push(SendablePtr::new(transform.data_ptr()));
+        }
+
+        let transform = instance_transforms.first().cloned().ok_or_else(|| {
+            "Creating instanced renderer object, msg = at least one transform is required"
+                .to_string()
+        })?;
+
+        let renderer_object = arc_rw_lock_new(TestRendererObjectImpl);
+        self.renderer_objects.write().insert(
+            SendablePtr::new(renderer_object.data_ptr()),
+            TestRendererObjectData {
+                mesh: SendablePtr::new(mesh.data_ptr()),
+                shader: SendablePtr::new(shader.data_ptr()),
+                material: SendablePtr::new(material.data_ptr()),
+                transform,
+                instance_transforms,
+                lod_meshes: Vec::new(),
+                probe: None,
+                uniforms: BTreeMap::new(),
+            },
+        );
+        Ok(renderer_object)
+    }
+
+    fn create_lod_renderer_object(
+        &mut self,
+        lod_meshes: Vec<(f32, ArcRwLock<dyn RendererMesh>)>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        self.shaders
+            .read()
+            .get(&SendablePtr::new(shader.data_ptr()))
+            .ok_or_else(|| {
+                "Creating lod renderer object, msg = could not find shader".to_string()
+            })?;
+
+        self.materials
+            .read()
+            .get(&SendablePtr::new(material.data_ptr()))
+            .ok_or_else(|| {
+                "Creating lod renderer object, msg = could not find material".to_string()
+            })?;
+
+        self.transforms
+            .read()
+            .get(&SendablePtr::new(transform.data_ptr()))
+            .ok_or_else(|| {
+                "Creating lod renderer object, msg = could not find transform".to_string()
+            })?;
+
+        let mut lod_mesh_ptrs = Vec::with_capacity(lod_meshes.len());
+        for (distance, mesh) in lod_meshes {
+            if !distance.is_finite() || distance < 0.0 {
+                return Err(format!(
+                    "Creating lod renderer object, msg = invalid lod distance, distance = {distance}"
+                ));
+            }
+
+            self.meshes
+                .read()
+                .get(&SendablePtr::new(mesh.data_ptr()))
+                .ok_or_else(|| {
+                    "Creating lod renderer object, msg = could not find mesh".to_string()
+                })?;
+            lod_mesh_ptrs.push((distance, SendablePtr::new(mesh.data_ptr())));
+        }
+        lod_mesh_ptrs.sort_by(|(distance_a, _), (distance_b, _)| distance_a.total_cmp(distance_b));
+
+        let mesh = lod_mesh_ptrs
+            .first()
+            .map(|(_, mesh)| mesh.clone())
+            .ok_or_else(|| {
+                "Creating lod renderer object, msg = at least one lod mesh is required".to_string()
+            })?;
+
+        let renderer_object = arc_rw_lock_new(TestRendererObjectImpl);
+        self.renderer_objects.write().insert(
+            SendablePtr::new(renderer_object.data_ptr()),
+            TestRendererObjectData {
+                mesh,
+                shader: SendablePtr::new(shader.data_ptr()),
+                material: SendablePtr::new(material.data_ptr()),
+                transform: SendablePtr::new(transform.data_ptr()),
+                instance_transforms: Vec::new(),
+                lod_meshes: lod_mesh_ptrs,
+                probe: None,
+                uniforms: BTreeMap::new(),
+            },
+        );
+        Ok(renderer_object)
+    }
+
+    fn create_skybox_renderer_object(
+        &mut self,
+        cubemap: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let cubemap_ptr = SendablePtr::new(cubemap.data_ptr());
+        self.cubemap_textures
+            .read()
+            .get(&cubemap_ptr)
+            .ok_or_else(|| {
+                "Creating skybox renderer object, msg = could not find cubemap texture".to_string()
+            })?;
+
+        let renderer_object = arc_rw_lock_new(TestRendererObjectImpl);
+        self.skybox_renderer_objects
+            .write()
+            .insert(SendablePtr::new(renderer_object.data_ptr()), cubemap_ptr);
+        Ok(renderer_object)
+    }
+
+    fn create_particle_emitter_renderer_object(
+        &mut self,
+        settings: ParticleEmitterSettings,
+        material: ArcRwLock<dyn RendererMaterial>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let material_ptr = SendablePtr::new(material.data_ptr());
+        self.materials.read().get(&material_ptr).ok_or_else(|| {
+            "Creating particle emitter renderer object, msg = could not find material".to_string()
+        })?;
+
+        let transform_ptr = SendablePtr::new(transform.data_ptr());
+        self.transforms.read().get(&transform_ptr).ok_or_else(|| {
+            "Creating particle emitter renderer object, msg = could not find transform".to_string()
+        })?;
+
+        let renderer_object = arc_rw_lock_new(TestRendererObjectImpl);
+        self.particle_emitter_renderer_objects.write().insert(
+            SendablePtr::new(renderer_object.data_ptr()),
+            TestParticleEmitterData {
+                settings,
+                material: material_ptr,
+                transform: transform_ptr,
+            },
+        );
+        Ok(renderer_object)
+    }
+
+    fn create_sprite_batch_renderer_object(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        sprites: Vec<RendererSprite>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        // cubemaps cannot be sampled with the uv rects of the sprites
+        let texture_ptr = SendablePtr::new(texture.data_ptr());
+        (self.image_textures.read().contains_key(&texture_ptr)
+            || self.render_textures.read().contains_key(&texture_ptr))
+        .then(|| ())
+        .ok_or_else(|| {
+            "Creating sprite batch renderer object, msg = could not find 2d texture".to_string()
+        })?;
+
+        let transform_ptr = SendablePtr::new(transform.data_ptr());
+        self.transforms.read().get(&transform_ptr).ok_or_else(|| {
+            "Creating sprite batch renderer object, msg = could not find transform".to_string()
+        })?;
+
+        let renderer_object = arc_rw_lock_new(TestRendererObjectImpl);
+        self.sprite_batch_renderer_objects.write().insert(
+            SendablePtr::new(renderer_object.data_ptr()),
+            TestSpriteBatchData {
+                texture: texture_ptr,
+                sprites,
+                transform: transform_ptr,
+            },
+        );
+        Ok(renderer_object)
+    }
+
+    fn release_renderer_object(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+    ) -> Result<(), String> {
+        let renderer_object_ptr = SendablePtr::new(renderer_object.data_ptr());
+        self.renderer_objects
+            .write()
+            .remove(&renderer_object_ptr)
+            .map(|_| ())
+            .or_else(|| {
+                self.skybox_renderer_objects
+                    .write()
+                    .remove(&renderer_object_ptr)
+                    .map(|_| ())
+            })
+            .or_else(|| {
+                self.particle_emitter_renderer_objects
+                    .write()
+                    .remove(&renderer_object_ptr)
+                    .map(|_| ())
+            })
+            .or_else(|| {
+                self.sprite_batch_renderer_objects
+                    .write()
+                    .remove(&renderer_object_ptr)
+                    .map(|_| ())
+            })
+            .ok_or_else(|| "Releasing renderer object, msg = could not find RendererObject")?;
+
+        for (_, renderer_group) in self.renderer_groups.write().iter_mut() {
+            renderer_group.remove_renderer_object(&renderer_object);
+        }
+
+        Ok(())
+    }
+
+    fn add_renderer_object_to_group(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let renderer_object_ptr = SendablePtr::new(renderer_object.data_ptr());
+        (self
+            .renderer_objects
+            .read()
+            .contains_key(&renderer_object_ptr)
+            || self
+                .skybox_renderer_objects
+                .read()
+                .contains_key(&renderer_object_ptr)
+            || self
+                .particle_emitter_renderer_objects
+                .read()
+                .contains_key(&renderer_object_ptr)
+            || self
+                .sprite_batch_renderer_objects
+                .read()
+                .contains_key(&renderer_object_ptr))
+        .then(|| ())
+        .ok_or_else(|| {
+            "Adding renderer object to group, msg = could not find renderer object".to_string()
+        })?;
+
+        self.renderer_groups
+            .write()
+            .get_mut(&SendablePtr::new(renderer_group.data_ptr()))
+            .ok_or_else(|| {
+                "Adding renderer object to group, msg = could not find renderer group".to_string()
+            })?
+            .add_renderer_object(renderer_object);
+
+        Ok(())
+    }
+
+    fn remove_renderer_object_from_group(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        self.renderer_groups
+            .write()
+            .get_mut(&SendablePtr::new(renderer_group.data_ptr()))
+            .ok_or_else(|| {
+                "Removing renderer object from group, msg = could not find renderer group"
+                    .to_string()
+            })?
+            .remove_renderer_object(&renderer_object)
+            .then(|| ())
+            .ok_or_else(|| {
+                "Removing renderer object from group, msg = could not find renderer object in group"
+                    .to_string()
+            })
+    }
+
+    fn set_renderer_object_transform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String> {
+        self.transforms
+            .read()
+            .get(&SendablePtr::new(transform.data_ptr()))
+            .ok_or_else(|| {
+                "Setting transform of renderer object, msg = could not find transform".to_string()
+            })?;
+
+        let renderer_object_ptr = SendablePtr::new(renderer_object.data_ptr());
+        let transform_ptr = SendablePtr::new(transform.data_ptr());
+        if let Some(particle_emitter_data) = self
+            .particle_emitter_renderer_objects
+            .write()
+            .get_mut(&renderer_object_ptr)
+        {
+            particle_emitter_data.transform = transform_ptr;
+            return Ok(());
+        }
+
+        if let Some(sprite_batch_data) = self
+            .sprite_batch_renderer_objects
+            .write()
+            .get_mut(&renderer_object_ptr)
+        {
+            sprite_batch_data.transform = transform_ptr;
+            return Ok(());
+        }
+
+        self.renderer_objects
+            .write()
+            .get_mut(&renderer_object_ptr)
+            .ok_or_else(|| {
+                "Setting transform of renderer object, msg = could not find renderer object"
+                    .to_string()
+            })?
+            .transform = transform_ptr;
+
+        Ok(())
+    }
+
+    fn set_renderer_object_material(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
+        self.materials
+            .read()
+            .get(&SendablePtr::new(material.data_ptr()))
+            .ok_or_else(|| {
+                "Setting material of renderer object, msg = could not find material".to_string()
+            })?;
+
+        let renderer_object_ptr = SendablePtr::new(renderer_object.data_ptr());
+        let material_ptr = SendablePtr::new(material.data_ptr());
+        if let Some(particle_emitter_data) = self
+            .particle_emitter_renderer_objects
+            .write()
+            .get_mut(&renderer_object_ptr)
+        {
+            particle_emitter_data.material = material_ptr;
+            return Ok(());
+        }
+
+        self.renderer_objects
+            .write()
+            .get_mut(&renderer_object_ptr)
+            .ok_or_else(|| {
+                "Setting material of renderer object, msg = could not find renderer object"
+                    .to_string()
+            })?
+            .material = material_ptr;
+
+        Ok(())
+    }
+
+    fn set_renderer_object_shader(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        shader: ArcRwLock<dyn RendererShader>,
+    ) -> Result<(), String> {
+        self.shaders
+            .read()
+            .get(&SendablePtr::new(shader.data_ptr()))
+            .ok_or_else(|| {
+                "Setting shader of renderer object, msg = could not find shader".to_string()
+            })?;
+
+        self.renderer_objects
+            .write()
+            .get_mut(&SendablePtr::new(renderer_object.data_ptr()))
+            .ok_or_else(|| {
+                "Setting shader of renderer object, msg = could not find renderer object"
+                    .to_string()
+            })?
+            .shader = SendablePtr::new(shader.data_ptr());
+
+        Ok(())
+    }
+
+    fn set_renderer_object_mesh(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        mesh: ArcRwLock<dyn RendererMesh>,
+    ) -> Result<(), String> {
+        self.meshes
+            .read()
+            .get(&SendablePtr::new(mesh.data_ptr()))
+            .ok_or_else(|| {
+                "Setting mesh of renderer object, msg = could not find mesh".to_string()
+            })?;
+
+        let mut renderer_objects = self.renderer_objects.write();
+        let renderer_object_data = renderer_objects
+            .get_mut(&SendablePtr::new(renderer_object.data_ptr()))
+            .ok_or_else(|| {
+                "Setting mesh of renderer object, msg = could not find renderer object".to_string()
+            })?;
+
+        // a single mesh replaces the lod meshes
+        renderer_object_data.mesh = SendablePtr::new(mesh.data_ptr());
+        renderer_object_data.lod_meshes.clear();
+
+        Ok(())
+    }
+
+    fn set_renderer_object_probe(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        probe: Option<ArcRwLock<dyn RendererProbe>>,
+    ) -> Result<(), String> {
+        let probe = probe.map(|probe| SendablePtr::new(probe.data_ptr()));
+
+        if let Some(probe) = &probe {
+            self.probes.read().get(probe).ok_or_else(|| {
+                "Setting probe of renderer object, msg = could not find probe".to_string()
+            })?;
+        }
+
+        self.renderer_objects
+            .write()
+            .get_mut(&SendablePtr::new(renderer_object.data_ptr()))
+            .ok_or_else(|| {
+                "Setting probe of renderer object, msg = could not find renderer object".to_string()
+            })?
+            .probe = probe;
+
+        Ok(())
+    }
+
+    fn set_renderer_object_uniform(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        name: String,
+        value: Option<RendererUniformValue>,
+    ) -> Result<(), String> {
+        let mut renderer_objects = self.renderer_objects.write();
+        let uniforms = &mut renderer_objects
+            .get_mut(&SendablePtr::new(renderer_object.data_ptr()))
+            .ok_or_else(|| {
+                "Setting uniform of renderer object, msg = could not find renderer object"
+                    .to_string()
+            })?
+            .uniforms;
+
+        match value {
+            Some(value) => uniforms.insert(name, value),
+            None => uniforms.remove(&name),
+        };
+
+        Ok(())
+    }
+
+    fn set_particle_emitter_settings(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        settings: ParticleEmitterSettings,
+    ) -> Result<(), String> {
+        self.particle_emitter_renderer_objects
+            .write()
+            .get_mut(&SendablePtr::new(renderer_object.data_ptr()))
+            .ok_or_else(|| {
+                "Setting particle emitter settings, msg = could not find particle emitter"
+                    .to_string()
+            })?
+            .settings = settings;
+
+        Ok(())
+    }
+
+    fn set_sprite_batch_sprites(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        sprites: Vec<RendererSprite>,
+    ) -> Result<(), String> {
+        self.sprite_batch_renderer_objects
+            .write()
+            .get_mut(&SendablePtr::new(renderer_object.data_ptr()))
+            .ok_or_else(|| {
+                "Setting sprites of sprite batch, msg = could not find sprite batch".to_string()
+            })?
+            .sprites = sprites;
+
+        Ok(())
+    }
+
+    fn create_camera(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererCamera>, String> {
+        self.transforms
+            .read()
+            .get(&SendablePtr::new(transform.data_ptr()))
+            .ok_or_else(|| "Creating camera, msg = could not find transform".to_string())?;
+
+        let camera = arc_rw_lock_new(TestRendererCameraImpl);
+        self.cameras
+            .write()
+            .insert(SendablePtr::new(camera.data_ptr()));
+        Ok(camera)
+    }
+
+    fn update_camera_parameters(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+        parameters: Option<RendererCameraParameters>,
+    ) -> Result<(), String> {
+        let camera = SendablePtr::new(camera.data_ptr());
+        if !self.cameras.read().contains(&camera) {
+            return Err(
+                "Updating camera parameters, msg = could not find RendererCamera".to_string(),
+            );
+        }
+
+        let mut camera_parameters = self.camera_parameters.write();
+        match parameters {
+            Some(parameters) => camera_parameters.insert(camera, parameters),
+            None => camera_parameters.remove(&camera),
+        };
+
+        Ok(())
+    }
+
+    fn release_camera(&mut self, camera: ArcRwLock<dyn RendererCamera>) -> Result<(), String> {
+        let camera = SendablePtr::new(camera.data_ptr());
+        self.cameras
+            .write()
+            .remove(&camera)
+            .then_some(())
+            .ok_or_else(|| "Releasing camera, msg = could not find RendererCamera")?;
+        self.camera_parameters.write().remove(&camera);
+        Ok(())
+    }
+
+    fn create_probe(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<ArcRwLock<dyn RendererProbe>, String> {
+        self.transforms
+            .read()
+            .get(&SendablePtr::new(transform.data_ptr()))
+            .ok_or_else(|| "Creating probe, msg = could not find transform".to_string())?;
+
+        self.renderer_layers
+            .read()
+            .get(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| "Creating probe, msg = could not find renderer layer".to_string())?;
+
+        let probe = arc_rw_lock_new(TestRendererProbeImpl);
+        self.probes.write().insert(
+            SendablePtr::new(probe.data_ptr()),
+            TestRendererProbeData {
+                transform: SendablePtr::new(transform.data_ptr()),
+                renderer_layer: SendablePtr::new(renderer_layer.data_ptr()),
+                resolution,
+                refresh_mode,
+                refresh_count: 0,
+            },
+        );
+        Ok(probe)
+    }
+
+    fn refresh_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        self.probes
+            .write()
+            .get_mut(&SendablePtr::new(probe.data_ptr()))
+            .ok_or_else(|| "Refreshing probe, msg = could not find probe".to_string())?
+            .refresh_count += 1;
+        Ok(())
+    }
+
+    fn set_probe_refresh_mode(
+        &mut self,
+        probe: ArcRwLock<dyn RendererProbe>,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<(), String> {
+        self.probes
+            .write()
+            .get_mut(&SendablePtr::new(probe.data_ptr()))
+            .ok_or_else(|| "Setting probe refresh mode, msg = could not find probe".to_string())?
+            .refresh_mode = refresh_mode;
+        Ok(())
+    }
+
+    fn release_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        self.probes
+            .write()
+            .remove(&SendablePtr::new(probe.data_ptr()))
+            .ok_or_else(|| "Releasing probe, msg = could not find RendererProbe")?;
+        Ok(())
+    }
+
+    fn stats(&self) -> RendererStats {
+        RendererStats {
+            texture_memory_budget_bytes: *self.texture_memory_budget_bytes.read(),
+            ..Default::default()
+        }
+    }
+
+    fn set_texture_memory_budget(&mut self, budget_bytes: Option<usize>) -> Result<(), String> {
+        *self.texture_memory_budget_bytes.write() = budget_bytes;
+        Ok(())
+    }
+
+    fn set_debug_render_mode(&mut self, mode: RendererDebugRenderMode) -> Result<(), String> {
+        *self.debug_render_mode.write() = mode;
+        Ok(())
+    }
+
+    fn add_debug_lines(
+        &mut self,
+        lines: Vec<RendererDebugLine>,
+        lifetime_secs: f32,
+    ) -> Result<(), String> {
+        self.debug_lines
+            .write()
+            .extend(lines.into_iter().map(|line| (line, lifetime_secs)));
+        Ok(())
+    }
+
+    fn reload_shaders(&mut self) -> Result<(), String> {
+        *self.number_of_shader_reloads.write() += 1;
+        Ok(())
+    }
+
+    fn pick_renderer_object(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        _screen_position: Vec2<f32>,
+        renderer_objects: Vec<ArcRwLock<dyn RendererObject>>,
+    ) -> Result<Option<usize>, String> {
+        self.renderer_layers
+            .read()
+            .get(&SendablePtr::new(renderer_layer.data_ptr()))
+            .ok_or_else(|| {
+                "Picking renderer object, msg = could not find renderer layer".to_string()
+            })?;
+
+        let picked_renderer_object = *self.picked_renderer_object.read();
+        Ok(picked_renderer_object.and_then(|picked_renderer_object| {
+            renderer_objects.iter().position(|renderer_object| {
+                SendablePtr::new(renderer_object.data_ptr()) == picked_renderer_object
+            })
+        }))
+    }
+
+    fn render(&mut self) {}
+}
@@ -4,6 +4,8 @@ use bytifex_utils::sync::types::{arc_mutex_new, ArcMutex};
 pub struct VirtualClock {
     frac_1_speed_multiplier_tx: ArcMutex<tokio::sync::watch::Sender<f64>>,
     frac_1_speed_multiplier_rx: tokio::sync::watch::Receiver<f64>,
+    is_paused_tx: ArcMutex<tokio::sync::watch::Sender<bool>>,
+    is_paused_rx: tokio::sync::watch::Receiver<bool>,
 }
 
 impl Default for VirtualClock {
@@ -15,9 +17,12 @@ impl Default for VirtualClock {
 impl VirtualClock {
     pub fn new() -> Self {
         let (tx, rx) = tokio::sync::watch::channel(1.0);
+        let (is_paused_tx, is_paused_rx) = tokio::sync::watch::channel(false);
         Self {
             frac_1_speed_multiplier_tx: arc_mutex_new(tx),
             frac_1_speed_multiplier_rx: rx,
+            is_paused_tx: arc_mutex_new(is_paused_tx),
+            is_paused_rx,
         }
     }
 
@@ -25,29 +30,54 @@ impl VirtualClock {
         tokio::time::Instant::now()
     }
 
+    // the paused clock does not advance, the remaining time is slept after the clock is resumed
     pub async fn sleep_for(&self, mut timeout: std::time::Duration) {
         let mut rx = self.frac_1_speed_multiplier_rx.clone();
-        let mut frac_1_speed = *rx.borrow();
-        let mut real_time_instance = tokio::time::Instant::now() + timeout.mul_f64(frac_1_speed);
+        let mut is_paused_rx = self.is_paused_rx.clone();
 
         loop {
+            if *is_paused_rx.borrow_and_update() {
+                if is_paused_rx.changed().await.is_err() {
+                    break;
+                }
+                continue;
+            }
+
+            let frac_1_speed = *rx.borrow_and_update();
+            let start_time = tokio::time::Instant::now();
+            let real_time_instance = start_time + timeout.mul_f64(frac_1_speed);
+
             tokio::select!(
                 _ = tokio::time::sleep_until(real_time_instance) => {
                     break;
                 }
                 res = rx.changed() => {
                     if res.is_ok() {
-                        timeout = real_time_instance - tokio::time::Instant::now();
-
-                        let last_frac_1_speed = frac_1_speed;
-                        frac_1_speed = *rx.borrow();
-                        real_time_instance = tokio::time::Instant::now() + timeout.mul_f64(frac_1_speed / last_frac_1_speed);
+                        timeout = timeout.saturating_sub(start_time.elapsed().mul_f64(1.0 / frac_1_speed));
+                    }
+                }
+                res = is_paused_rx.changed() => {
+                    if res.is_ok() {
+                        timeout = timeout.saturating_sub(start_time.elapsed().mul_f64(1.0 / frac_1_speed));
                     }
                 }
             );
         }
     }
 
+    // e.g. the menu of the game, the conversions keep using the speed multiplier
+    pub fn pause(&mut self) {
+        let _ = self.is_paused_tx.lock().send(true);
+    }
+
+    pub fn resume(&mut self) {
+        let _ = self.is_paused_tx.lock().send(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.is_paused_rx.borrow()
+    }
+
     pub fn set_speed_multiplier(&mut self, multiplier: f64) {
         let _ = self
             .frac_1_speed_multiplier_tx
@@ -205,4 +235,38 @@ mod tests {
             assert!(time_elapsed_millis - 750 < THRESHOLD_MILLIS);
         });
     }
+
+    #[test]
+    fn sleep_for_pause_on_the_fly() {
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            let virtual_clock = VirtualClock::new();
+
+            let start_time = std::time::Instant::now();
+
+            {
+                let mut virtual_clock = virtual_clock.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    virtual_clock.pause();
+                    assert!(virtual_clock.is_paused());
+
+                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    virtual_clock.resume();
+                });
+            }
+
+            virtual_clock
+                .sleep_for(std::time::Duration::from_millis(1000))
+                .await;
+
+            let time_elapsed_millis = start_time.elapsed().as_millis();
+            assert!(time_elapsed_millis >= 1500);
+            assert!(time_elapsed_millis - 1500 < THRESHOLD_MILLIS);
+        });
+    }
 }
@@ -175,9 +175,16 @@ pub enum MouseButton {
     X2,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SecondaryWindowId(pub usize);
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Event {
     Closed,
+    // the secondary window is not destroyed, its owner decides what to do with it
+    SecondaryWindowClosed {
+        secondary_window_id: SecondaryWindowId,
+    },
     Resized {
         width: usize,
         height: usize,
@@ -229,6 +236,27 @@ pub trait WindowContext: System {
 
     fn swap_buffers(&self);
 
+    // the secondary windows share the drawing context of the main window
+    fn create_secondary_window(
+        &mut self,
+        title: &str,
+        dimensions: Vec2<usize>,
+    ) -> Result<SecondaryWindowId, String>;
+    fn destroy_secondary_window(
+        &mut self,
+        secondary_window_id: SecondaryWindowId,
+    ) -> Result<(), String>;
+    fn secondary_window_dimensions(
+        &self,
+        secondary_window_id: SecondaryWindowId,
+    ) -> Option<Vec2<usize>>;
+    // the subsequent draw calls go into the given window, None is the main window
+    fn make_window_current(
+        &self,
+        secondary_window_id: Option<SecondaryWindowId>,
+    ) -> Result<(), String>;
+    fn swap_secondary_window_buffers(&self, secondary_window_id: SecondaryWindowId);
+
     fn event_receiver(&self) -> EventReceiver {
         self.event_sender().create_receiver()
     }
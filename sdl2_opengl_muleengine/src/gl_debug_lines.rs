@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use muleengine::renderer::renderer_debug_lines::RendererDebugLine;
+use vek::{Mat4, Vec3, Vec4};
+
+use super::{
+    gl_draw_stats::GLDrawStats,
+    gl_shader_program::GLShaderProgram,
+    opengl_utils::{
+        shader_input::ShaderUniform,
+        vertex_array_object::VertexArrayObject,
+        vertex_buffer_object::{DataCount, DataType, VertexBufferObject},
+    },
+};
+
+const MIN_VERTEX_CAPACITY: usize = 256;
+
+struct GLDebugLineBuffers {
+    positions_vbo: VertexBufferObject,
+    colors_vbo: VertexBufferObject,
+    vertex_array_object: VertexArrayObject,
+}
+
+pub struct GLDebugLines {
+    // the vbos keep pointers to the vectors, so they are only resized together with the vbos
+    positions: Vec<Vec3<f32>>,
+    colors: Vec<Vec4<f32>>,
+    number_of_vertices: usize,
+    buffers: Option<GLDebugLineBuffers>,
+
+    gl_shader_program: Arc<GLShaderProgram>,
+    view_matrix_uniform: Option<ShaderUniform>,
+    projection_matrix_uniform: Option<ShaderUniform>,
+}
+
+impl GLDebugLines {
+    pub fn new(gl_shader_program: Arc<GLShaderProgram>) -> Self {
+        let shader_program = &gl_shader_program.shader_program;
+        let view_matrix_uniform = shader_program.get_uniform_by_name("viewMatrix");
+        let projection_matrix_uniform = shader_program.get_uniform_by_name("projectionMatrix");
+
+        Self {
+            positions: Vec::new(),
+            colors: Vec::new(),
+            number_of_vertices: 0,
+            buffers: None,
+
+            gl_shader_program,
+            view_matrix_uniform,
+            projection_matrix_uniform,
+        }
+    }
+
+    pub fn set_lines(&mut self, lines: &[RendererDebugLine]) {
+        self.number_of_vertices = lines.len() * 2;
+        if self.number_of_vertices == 0 {
+            return;
+        }
+
+        if self.buffers.is_none() || self.number_of_vertices > self.positions.len() {
+            let capacity = self
+                .number_of_vertices
+                .next_power_of_two()
+                .max(MIN_VERTEX_CAPACITY);
+            self.positions = vec![Vec3::zero(); capacity];
+            self.colors = vec![Vec4::zero(); capacity];
+            self.buffers = Some(self.create_buffers());
+        }
+
+        for (index, line) in lines.iter().enumerate() {
+            self.positions[index * 2] = line.start;
+            self.positions[index * 2 + 1] = line.end;
+            self.colors[index * 2] = line.color;
+            self.colors[index * 2 + 1] = line.color;
+        }
+
+        if let Some(buffers) = &mut self.buffers {
+            buffers
+                .positions_vbo
+                .update_from_pointer(0, self.number_of_vertices);
+            buffers
+                .colors_vbo
+                .update_from_pointer(0, self.number_of_vertices);
+        }
+    }
+
+    pub fn draw(
+        &self,
+        projection_matrix: &Mat4<f32>,
+        view_matrix: &Mat4<f32>,
+        draw_stats: &GLDrawStats,
+    ) {
+        let buffers = match &self.buffers {
+            Some(buffers) if self.number_of_vertices > 0 => buffers,
+            _ => return,
+        };
+
+        self.gl_shader_program.shader_program.use_program();
+
+        if let Some(uniform) = &self.view_matrix_uniform {
+            uniform.send_uniform_matrix_4fv(view_matrix.as_col_slice(), 1);
+        }
+
+        if let Some(uniform) = &self.projection_matrix_uniform {
+            uniform.send_uniform_matrix_4fv(projection_matrix.as_col_slice(), 1);
+        }
+
+        buffers.vertex_array_object.use_vao(|| unsafe {
+            gl::DrawArrays(gl::LINES, 0, self.number_of_vertices as i32);
+        });
+        draw_stats.add_draw_call(0);
+    }
+
+    fn create_buffers(&self) -> GLDebugLineBuffers {
+        let positions_vbo = VertexBufferObject::new_dynamic(
+            self.positions.as_ptr(),
+            self.positions.len(),
+            DataType::F32,
+            DataCount::Coords3,
+        );
+        let colors_vbo = VertexBufferObject::new_dynamic(
+            self.colors.as_ptr(),
+            self.colors.len(),
+            DataType::F32,
+            DataCount::Rgba,
+        );
+
+        let shader_program = &self.gl_shader_program.shader_program;
+        let vertex_array_object = VertexArrayObject::new(|vao_interface| {
+            if let Some(attrib) = shader_program.get_attribute_by_name("position") {
+                vao_interface.bind_vbo_to_shader_attrib(&positions_vbo, &attrib);
+            }
+
+            if let Some(attrib) = shader_program.get_attribute_by_name("color") {
+                vao_interface.bind_vbo_to_shader_attrib(&colors_vbo, &attrib);
+            }
+        });
+
+        GLDebugLineBuffers {
+            positions_vbo,
+            colors_vbo,
+            vertex_array_object,
+        }
+    }
+}
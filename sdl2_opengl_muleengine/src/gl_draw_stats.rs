@@ -0,0 +1,31 @@
+use std::cell::Cell;
+
+#[derive(Default)]
+pub struct GLDrawStats {
+    draw_calls: Cell<usize>,
+    triangles: Cell<usize>,
+}
+
+impl GLDrawStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_draw_call(&self, triangles: usize) {
+        self.draw_calls.set(self.draw_calls.get() + 1);
+        self.triangles.set(self.triangles.get() + triangles);
+    }
+
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls.get()
+    }
+
+    pub fn triangles(&self) -> usize {
+        self.triangles.get()
+    }
+
+    pub fn reset(&self) {
+        self.draw_calls.set(0);
+        self.triangles.set(0);
+    }
+}
@@ -1,24 +1,90 @@
-use std::{rc::Rc, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    rc::Rc,
+    sync::Arc,
+};
 
 use vek::{Mat4, Transform, Vec3};
 
-use muleengine::mesh::MaterialTextureType;
+use muleengine::{
+    aabb::AxisAlignedBoundingBox,
+    bytifex_utils::sync::types::RcRwLock,
+    camera::Frustum,
+    mesh::{MaterialBlendMode, MaterialTextureType},
+    renderer::renderer_uniform_value::RendererUniformValue,
+};
 
 use crate::gl_mesh::GLMesh;
 
 use super::{
+    gl_draw_stats::GLDrawStats,
     gl_material::{GLMaterial, GLMaterialTexture},
     gl_mesh_shader_program::GLMeshShaderProgram,
-    opengl_utils::{shader_input::ShaderUniform, vertex_array_object::VertexArrayObject},
+    opengl_utils::{
+        depth_texture_2d::DepthTexture2D,
+        gl_state_cache::with_gl_state_cache,
+        shader_input::ShaderUniform,
+        texture_cubemap::TextureCubemap,
+        vertex_array_object::VertexArrayObject,
+        vertex_buffer_object::{DataCount, DataType, VertexBufferObject},
+    },
 };
 
+const SHADOW_MAP_TEXTURE_LAYER: usize = 14;
+const ENVIRONMENT_MAP_TEXTURE_LAYER: usize = 15;
+
+pub struct ShadowMapBinding {
+    pub light_space_matrix: Mat4<f32>,
+    pub shadow_map: Rc<DepthTexture2D>,
+}
+
+pub struct DrawParameters<'a> {
+    pub eye_position: &'a Vec3<f32>,
+    pub projection_matrix: &'a Mat4<f32>,
+    pub view_matrix: &'a Mat4<f32>,
+    pub exposure: f32,
+    pub override_shader_program: Option<&'a Arc<GLMeshShaderProgram>>,
+    pub shadow_map_binding: Option<&'a ShadowMapBinding>,
+    pub draw_stats: &'a GLDrawStats,
+}
+
+// the normal matrices are computed once per instance, so the shaders do not invert the object
+// matrices per vertex
+struct GLMeshInstances {
+    object_matrices: Vec<Mat4<f32>>,
+    object_matrices_vbo: VertexBufferObject,
+    normal_matrices: Vec<Mat4<f32>>,
+    normal_matrices_vbo: VertexBufferObject,
+}
+
+struct GLMeshLod {
+    min_distance: f32,
+    gl_mesh: Rc<GLMesh>,
+    vertex_array_object: VertexArrayObject,
+}
+
 pub struct GLDrawableMesh {
     gl_mesh: Rc<GLMesh>,
+    // ordered by distance, the gl_mesh is drawn until the distance of the first one
+    farther_lods: Vec<GLMeshLod>,
+    instances: Option<GLMeshInstances>,
     gl_material: Arc<GLMaterial>,
     object_matrix: Mat4<f32>,
+    world_aabb: Option<AxisAlignedBoundingBox>,
     bone_transforms: Option<Vec<Mat4<f32>>>,
     vertex_array_object: VertexArrayObject,
+    // keyed by the lod index, 0 is the gl_mesh
+    override_vertex_array_objects:
+        RefCell<BTreeMap<usize, (*const GLMeshShaderProgram, VertexArrayObject)>>,
     gl_mesh_shader_program: Arc<GLMeshShaderProgram>,
+    environment_map: Option<RcRwLock<Rc<TextureCubemap>>>,
+    // sent after the uniforms of the material, the uniforms that the material does not set
+    // keep the overridden values for the next objects that are drawn with the same shader program
+    uniform_overrides: BTreeMap<String, RendererUniformValue>,
+    is_unbounded: bool,
+    // one flag per draw order that contains the mesh, they are set when the draw sort key changes
+    draw_order_dirty_flags: Vec<Rc<Cell<bool>>>,
 }
 
 impl GLDrawableMesh {
@@ -28,69 +94,344 @@ impl GLDrawableMesh {
         transform: Transform<f32, f32, f32>,
         gl_mesh_shader_program: Arc<GLMeshShaderProgram>,
     ) -> Self {
-        Self {
-            vertex_array_object: create_vao(&gl_mesh, &gl_mesh_shader_program),
+        let mut drawable_mesh = Self {
+            vertex_array_object: create_vao(&gl_mesh, None, &gl_mesh_shader_program),
             gl_mesh,
+            farther_lods: Vec::new(),
+            instances: None,
             gl_material: material,
             object_matrix: transform.into(),
+            world_aabb: None,
+            bone_transforms: None,
+            override_vertex_array_objects: RefCell::new(BTreeMap::new()),
+            gl_mesh_shader_program,
+            environment_map: None,
+            uniform_overrides: BTreeMap::new(),
+            is_unbounded: false,
+            draw_order_dirty_flags: Vec::new(),
+        };
+        drawable_mesh.update_world_aabb();
+
+        drawable_mesh
+    }
+
+    // unbounded meshes (e.g. skyboxes) surround the eye, so they are neither culled nor picked
+    pub fn new_unbounded(
+        gl_mesh: Rc<GLMesh>,
+        material: Arc<GLMaterial>,
+        gl_mesh_shader_program: Arc<GLMeshShaderProgram>,
+    ) -> Self {
+        let mut drawable_mesh = Self::new(
+            gl_mesh,
+            material,
+            Transform::default(),
+            gl_mesh_shader_program,
+        );
+        drawable_mesh.is_unbounded = true;
+        drawable_mesh.update_world_aabb();
+
+        drawable_mesh
+    }
+
+    // the farther meshes have to be ordered by their distance
+    pub fn new_lod(
+        nearest_gl_mesh: Rc<GLMesh>,
+        farther_gl_meshes: Vec<(f32, Rc<GLMesh>)>,
+        material: Arc<GLMaterial>,
+        transform: Transform<f32, f32, f32>,
+        gl_mesh_shader_program: Arc<GLMeshShaderProgram>,
+    ) -> Self {
+        let mut drawable_mesh =
+            Self::new(nearest_gl_mesh, material, transform, gl_mesh_shader_program);
+        drawable_mesh.farther_lods = farther_gl_meshes
+            .into_iter()
+            .map(|(min_distance, gl_mesh)| GLMeshLod {
+                min_distance,
+                vertex_array_object: create_vao(
+                    &gl_mesh,
+                    None,
+                    &drawable_mesh.gl_mesh_shader_program,
+                ),
+                gl_mesh,
+            })
+            .collect();
+
+        drawable_mesh
+    }
+
+    pub fn new_instanced(
+        gl_mesh: Rc<GLMesh>,
+        material: Arc<GLMaterial>,
+        transforms: Vec<Transform<f32, f32, f32>>,
+        gl_mesh_shader_program: Arc<GLMeshShaderProgram>,
+    ) -> Self {
+        let object_matrices = transforms
+            .into_iter()
+            .map(|transform| transform.into())
+            .collect::<Vec<Mat4<f32>>>();
+
+        // the vbo keeps a pointer to the matrices, so the vector must not be reallocated afterwards
+        let object_matrices_vbo = VertexBufferObject::new(
+            object_matrices.as_ptr(),
+            object_matrices.len(),
+            DataType::F32,
+            DataCount::Coords4,
+        );
+        let normal_matrices = object_matrices
+            .iter()
+            .map(normal_matrix_of)
+            .collect::<Vec<Mat4<f32>>>();
+        let normal_matrices_vbo = VertexBufferObject::new(
+            normal_matrices.as_ptr(),
+            normal_matrices.len(),
+            DataType::F32,
+            DataCount::Coords4,
+        );
+
+        let instances = GLMeshInstances {
+            object_matrices,
+            object_matrices_vbo,
+            normal_matrices,
+            normal_matrices_vbo,
+        };
+
+        let mut drawable_mesh = Self {
+            vertex_array_object: create_vao(&gl_mesh, Some(&instances), &gl_mesh_shader_program),
+            gl_mesh,
+            farther_lods: Vec::new(),
+            instances: Some(instances),
+            gl_material: material,
+            object_matrix: Mat4::identity(),
+            world_aabb: None,
             bone_transforms: None,
+            override_vertex_array_objects: RefCell::new(BTreeMap::new()),
             gl_mesh_shader_program,
+            environment_map: None,
+            uniform_overrides: BTreeMap::new(),
+            is_unbounded: false,
+            draw_order_dirty_flags: Vec::new(),
+        };
+        drawable_mesh.update_world_aabb();
+
+        drawable_mesh
+    }
+
+    pub fn world_aabb(&self) -> Option<&AxisAlignedBoundingBox> {
+        self.world_aabb.as_ref()
+    }
+
+    // objects without a bounding box are never culled
+    pub fn is_visible_in(&self, frustum: &Frustum) -> bool {
+        match &self.world_aabb {
+            Some(world_aabb) => frustum.intersects_aabb(world_aabb),
+            None => true,
         }
     }
 
-    pub fn draw(
+    // objects without a bounding box cannot be picked
+    pub fn ray_intersection_distance(
         &self,
-        eye_position: &Vec3<f32>,
-        projection_matrix: &Mat4<f32>,
-        view_matrix: &Mat4<f32>,
+        ray_origin: Vec3<f32>,
+        ray_direction: Vec3<f32>,
+    ) -> Option<f32> {
+        self.world_aabb
+            .as_ref()
+            .and_then(|world_aabb| world_aabb.ray_intersection_distance(ray_origin, ray_direction))
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.gl_material.blend_mode != MaterialBlendMode::Opaque
+    }
+
+    // objects with equal keys share the shader program, the material and the mesh
+    pub fn draw_sort_key(&self) -> (*const GLMeshShaderProgram, *const GLMaterial, *const GLMesh) {
+        (
+            Arc::as_ptr(&self.gl_mesh_shader_program),
+            Arc::as_ptr(&self.gl_material),
+            Rc::as_ptr(&self.gl_mesh),
+        )
+    }
+
+    pub fn add_draw_order_dirty_flag(&mut self, dirty_flag: Rc<Cell<bool>>) {
+        self.draw_order_dirty_flags.push(dirty_flag);
+    }
+
+    pub fn remove_draw_order_dirty_flag(&mut self, dirty_flag: &Rc<Cell<bool>>) {
+        self.draw_order_dirty_flags
+            .retain(|flag| !Rc::ptr_eq(flag, dirty_flag));
+    }
+
+    fn mark_draw_orders_dirty(&self) {
+        for dirty_flag in self.draw_order_dirty_flags.iter() {
+            dirty_flag.set(true);
+        }
+    }
+
+    pub fn distance_squared_from(&self, position: &Vec3<f32>) -> f32 {
+        let center = match &self.world_aabb {
+            Some(world_aabb) => (*world_aabb.get_min_vertex() + *world_aabb.get_max_vertex()) / 2.0,
+            None => self.object_matrix.mul_point(Vec3::zero()),
+        };
+
+        center.distance_squared(*position)
+    }
+
+    fn update_world_aabb(&mut self) {
+        let aabb = match self.gl_mesh.aabb() {
+            Some(aabb) if !self.is_unbounded => aabb,
+            _ => {
+                self.world_aabb = None;
+                return;
+            }
+        };
+
+        self.world_aabb = match &self.instances {
+            Some(instances) => {
+                instances
+                    .object_matrices
+                    .iter()
+                    .fold(None, |world_aabb, object_matrix| {
+                        let instance_aabb = aabb.transformed_by_matrix(object_matrix);
+                        match world_aabb {
+                            Some(mut world_aabb) => {
+                                world_aabb.add_aabb(&instance_aabb);
+                                Some(world_aabb)
+                            }
+                            None => Some(instance_aabb),
+                        }
+                    })
+            }
+            None => Some(aabb.transformed_by_matrix(&self.object_matrix)),
+        };
+    }
+
+    // the lod is selected by the distance from the eye of the current draw
+    fn select_lod(&self, eye_position: &Vec3<f32>) -> (usize, &Rc<GLMesh>, &VertexArrayObject) {
+        if self.farther_lods.is_empty() {
+            return (0, &self.gl_mesh, &self.vertex_array_object);
+        }
+
+        let distance_squared = self.distance_squared_from(eye_position);
+        self.farther_lods
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, lod)| lod.min_distance * lod.min_distance <= distance_squared)
+            .map(|(index, lod)| (index + 1, &lod.gl_mesh, &lod.vertex_array_object))
+            .unwrap_or((0, &self.gl_mesh, &self.vertex_array_object))
+    }
+
+    pub fn draw(&self, parameters: &DrawParameters) {
+        let (lod_index, gl_mesh, vertex_array_object) = self.select_lod(parameters.eye_position);
+
+        match parameters.override_shader_program {
+            Some(override_shader_program) => {
+                let mut override_vertex_array_objects =
+                    self.override_vertex_array_objects.borrow_mut();
+
+                // the vertex array object depends on the attribute locations of the shader program
+                let ptr = Arc::as_ptr(override_shader_program);
+                let is_cached = matches!(
+                    override_vertex_array_objects.get(&lod_index),
+                    Some((cached_ptr, _)) if *cached_ptr == ptr
+                );
+                if !is_cached {
+                    override_vertex_array_objects.insert(
+                        lod_index,
+                        (
+                            ptr,
+                            create_vao(gl_mesh, self.instances.as_ref(), override_shader_program),
+                        ),
+                    );
+                }
+
+                if let Some((_, vertex_array_object)) =
+                    override_vertex_array_objects.get(&lod_index)
+                {
+                    self.draw_with_shader_program(
+                        override_shader_program,
+                        gl_mesh,
+                        vertex_array_object,
+                        parameters,
+                    );
+                }
+            }
+            None => self.draw_with_shader_program(
+                &self.gl_mesh_shader_program,
+                gl_mesh,
+                vertex_array_object,
+                parameters,
+            ),
+        }
+    }
+
+    fn draw_with_shader_program(
+        &self,
+        gl_mesh_shader_program: &GLMeshShaderProgram,
+        gl_mesh: &GLMesh,
+        vertex_array_object: &VertexArrayObject,
+        parameters: &DrawParameters,
     ) {
-        self.gl_mesh_shader_program
+        // the buffers and the textures that the upload queue did not fill yet are not drawn
+        if !gl_mesh.is_uploaded() || !self.gl_material.is_uploaded() {
+            return;
+        }
+
+        gl_mesh_shader_program
             .gl_shader_program
             .shader_program
             .use_program();
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.eye_position {
-            uniform.send_uniform_3fv(eye_position.as_slice(), 1);
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.eye_position {
+            uniform.send_uniform_3fv(parameters.eye_position.as_slice(), 1);
         }
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.object_matrix {
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.object_matrix {
             uniform.send_uniform_matrix_4fv(self.object_matrix.as_col_slice(), 1);
         }
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.view_matrix {
-            uniform.send_uniform_matrix_4fv(view_matrix.as_col_slice(), 1);
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.view_matrix {
+            uniform.send_uniform_matrix_4fv(parameters.view_matrix.as_col_slice(), 1);
         }
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.projection_matrix {
-            uniform.send_uniform_matrix_4fv(projection_matrix.as_col_slice(), 1);
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.projection_matrix {
+            uniform.send_uniform_matrix_4fv(parameters.projection_matrix.as_col_slice(), 1);
         }
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.normal_matrix {
-            let mut normal_matrix = self.object_matrix.inverted_affine_transform();
-            normal_matrix.transpose();
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.normal_matrix {
+            let normal_matrix = normal_matrix_of(&self.object_matrix);
             uniform.send_uniform_matrix_4fv(normal_matrix.as_col_slice(), 1);
         }
 
         let bone_transforms = self
             .bone_transforms
             .as_ref()
-            .unwrap_or(&self.gl_mesh.bone_transforms);
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.bones {
+            .unwrap_or(&gl_mesh.bone_transforms);
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.bones {
             uniform
                 .send_uniform_matrix_4fv(bone_transforms[0].as_col_slice(), bone_transforms.len());
         }
 
+        if let Some(uniform) = &self
+            .gl_mesh_shader_program
+            .uniforms
+            .number_of_color_channels
+        {
+            uniform.send_uniform_1ui(gl_mesh.color_channel_vbos.len() as u32);
+        }
+
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.use_instancing {
+            uniform.send_uniform_1i(self.instances.is_some() as i32);
+        }
+
         let mut texture_layer_counter = 0;
 
         self.use_texture(
             &mut texture_layer_counter,
             find_texture_with_min_uv_id(&self.gl_material.textures, MaterialTextureType::Albedo),
-            self.gl_mesh_shader_program
-                .uniforms
-                .use_albedo_texture
-                .as_ref(),
-            self.gl_mesh_shader_program.uniforms.albedo_texture.as_ref(),
-            self.gl_mesh_shader_program
+            gl_mesh_shader_program.uniforms.use_albedo_texture.as_ref(),
+            gl_mesh_shader_program.uniforms.albedo_texture.as_ref(),
+            gl_mesh_shader_program
                 .uniforms
                 .albedo_texture_uv_channel_id
                 .as_ref(),
@@ -99,12 +440,9 @@ impl GLDrawableMesh {
         self.use_texture(
             &mut texture_layer_counter,
             find_texture_with_min_uv_id(&self.gl_material.textures, MaterialTextureType::Normal),
-            self.gl_mesh_shader_program
-                .uniforms
-                .use_normal_texture
-                .as_ref(),
-            self.gl_mesh_shader_program.uniforms.normal_texture.as_ref(),
-            self.gl_mesh_shader_program
+            gl_mesh_shader_program.uniforms.use_normal_texture.as_ref(),
+            gl_mesh_shader_program.uniforms.normal_texture.as_ref(),
+            gl_mesh_shader_program
                 .uniforms
                 .normal_texture_uv_channel_id
                 .as_ref(),
@@ -116,39 +454,203 @@ impl GLDrawableMesh {
                 &self.gl_material.textures,
                 MaterialTextureType::Displacement,
             ),
-            self.gl_mesh_shader_program
+            gl_mesh_shader_program
                 .uniforms
                 .use_displacement_texture
                 .as_ref(),
-            self.gl_mesh_shader_program
+            gl_mesh_shader_program
                 .uniforms
                 .displacement_texture
                 .as_ref(),
-            self.gl_mesh_shader_program
+            gl_mesh_shader_program
                 .uniforms
                 .displacement_texture_uv_channel_id
                 .as_ref(),
         );
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.opacity {
+        self.use_texture(
+            &mut texture_layer_counter,
+            find_texture_with_min_uv_id(&self.gl_material.textures, MaterialTextureType::Shininess),
+            gl_mesh_shader_program
+                .uniforms
+                .use_shininess_texture
+                .as_ref(),
+            gl_mesh_shader_program.uniforms.shininess_texture.as_ref(),
+            gl_mesh_shader_program
+                .uniforms
+                .shininess_texture_uv_channel_id
+                .as_ref(),
+        );
+
+        self.use_texture(
+            &mut texture_layer_counter,
+            find_texture_with_min_uv_id(&self.gl_material.textures, MaterialTextureType::Emission),
+            gl_mesh_shader_program
+                .uniforms
+                .use_emission_texture
+                .as_ref(),
+            gl_mesh_shader_program.uniforms.emission_texture.as_ref(),
+            gl_mesh_shader_program
+                .uniforms
+                .emission_texture_uv_channel_id
+                .as_ref(),
+        );
+
+        self.use_texture(
+            &mut texture_layer_counter,
+            find_texture_with_min_uv_id(&self.gl_material.textures, MaterialTextureType::Metallic),
+            gl_mesh_shader_program
+                .uniforms
+                .use_metallic_texture
+                .as_ref(),
+            gl_mesh_shader_program.uniforms.metallic_texture.as_ref(),
+            gl_mesh_shader_program
+                .uniforms
+                .metallic_texture_uv_channel_id
+                .as_ref(),
+        );
+
+        self.use_texture(
+            &mut texture_layer_counter,
+            find_texture_with_min_uv_id(&self.gl_material.textures, MaterialTextureType::Roughness),
+            gl_mesh_shader_program
+                .uniforms
+                .use_roughness_texture
+                .as_ref(),
+            gl_mesh_shader_program.uniforms.roughness_texture.as_ref(),
+            gl_mesh_shader_program
+                .uniforms
+                .roughness_texture_uv_channel_id
+                .as_ref(),
+        );
+
+        self.use_texture(
+            &mut texture_layer_counter,
+            find_texture_with_min_uv_id(
+                &self.gl_material.textures,
+                MaterialTextureType::AmbientOcclusion,
+            ),
+            gl_mesh_shader_program
+                .uniforms
+                .use_ambient_occlusion_texture
+                .as_ref(),
+            gl_mesh_shader_program
+                .uniforms
+                .ambient_occlusion_texture
+                .as_ref(),
+            gl_mesh_shader_program
+                .uniforms
+                .ambient_occlusion_texture_uv_channel_id
+                .as_ref(),
+        );
+
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.environment_map {
+            uniform.send_uniform_1i(ENVIRONMENT_MAP_TEXTURE_LAYER as i32);
+        }
+
+        // the cubemap of the material takes precedence over the one of the probe
+        if let Some(environment_cubemap) = &self.gl_material.environment_cubemap {
+            environment_cubemap.use_texture(ENVIRONMENT_MAP_TEXTURE_LAYER);
+
+            if let Some(uniform) = &gl_mesh_shader_program.uniforms.use_environment_map {
+                uniform.send_uniform_1i(1);
+            }
+        } else if let Some(environment_map) = &self.environment_map {
+            environment_map
+                .read()
+                .use_texture(ENVIRONMENT_MAP_TEXTURE_LAYER);
+
+            if let Some(uniform) = &gl_mesh_shader_program.uniforms.use_environment_map {
+                uniform.send_uniform_1i(1);
+            }
+        } else if let Some(uniform) = &gl_mesh_shader_program.uniforms.use_environment_map {
+            uniform.send_uniform_1i(0);
+        }
+
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.opacity {
             uniform.send_uniform_1f(self.gl_material.opacity);
         }
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.albedo_color {
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.albedo_color {
             uniform.send_uniform_3fv(self.gl_material.albedo_color.as_slice(), 1);
         }
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.emissive_color {
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.emissive_color {
             uniform.send_uniform_3fv(self.gl_material.emissive_color.as_slice(), 1);
         }
 
-        if let Some(uniform) = &self.gl_mesh_shader_program.uniforms.shininess_color {
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.shininess_color {
             uniform.send_uniform_3fv(self.gl_material.shininess_color.as_slice(), 1);
         }
 
-        self.vertex_array_object.use_vao(|| {
-            self.gl_mesh.index_buffer_object.draw();
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.metallic {
+            uniform.send_uniform_1f(self.gl_material.metallic);
+        }
+
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.roughness {
+            uniform.send_uniform_1f(self.gl_material.roughness);
+        }
+
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.ambient_occlusion {
+            uniform.send_uniform_1f(self.gl_material.ambient_occlusion);
+        }
+
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.exposure {
+            uniform.send_uniform_1f(parameters.exposure);
+        }
+
+        if let Some(uniform) = &gl_mesh_shader_program.uniforms.shadow_map {
+            uniform.send_uniform_1i(SHADOW_MAP_TEXTURE_LAYER as i32);
+        }
+
+        if let Some(shadow_map_binding) = parameters.shadow_map_binding {
+            shadow_map_binding
+                .shadow_map
+                .use_texture(SHADOW_MAP_TEXTURE_LAYER);
+
+            if let Some(uniform) = &gl_mesh_shader_program.uniforms.light_space_matrix {
+                uniform.send_uniform_matrix_4fv(
+                    shadow_map_binding.light_space_matrix.as_col_slice(),
+                    1,
+                );
+            }
+
+            if let Some(uniform) = &gl_mesh_shader_program.uniforms.use_shadow_map {
+                uniform.send_uniform_1i(1);
+            }
+        } else if let Some(uniform) = &gl_mesh_shader_program.uniforms.use_shadow_map {
+            uniform.send_uniform_1i(0);
+        }
+
+        for (name, value) in self.uniform_overrides.iter() {
+            if let Some(uniform) = gl_mesh_shader_program
+                .gl_shader_program
+                .shader_program
+                .get_uniform_by_name(name)
+            {
+                send_uniform_value(&uniform, value);
+            }
+        }
+
+        set_blend_mode(self.gl_material.blend_mode);
+
+        vertex_array_object.use_vao(|| match &self.instances {
+            Some(instances) => gl_mesh
+                .index_buffer_object
+                .draw_instances(instances.object_matrices.len()),
+            None => gl_mesh.index_buffer_object.draw(),
         });
+
+        let number_of_instances = self
+            .instances
+            .as_ref()
+            .map(|instances| instances.object_matrices.len())
+            .unwrap_or(1);
+        parameters
+            .draw_stats
+            .add_draw_call(gl_mesh.number_of_triangles() * number_of_instances);
+
+        with_gl_state_cache(|cache| cache.restore_depth_mask());
     }
 
     fn use_texture(
@@ -164,6 +666,12 @@ impl GLDrawableMesh {
             material_texture
                 .texture
                 .set_texture_map_mode(material_texture.texture_map_mode);
+            material_texture
+                .texture
+                .set_texture_sampling_mode(material_texture.sampling_mode);
+            material_texture
+                .texture
+                .set_texture_anisotropy_mode(material_texture.anisotropy_mode);
 
             if let Some(use_texture) = use_texture {
                 use_texture.send_uniform_1i(1);
@@ -185,21 +693,129 @@ impl GLDrawableMesh {
 
     pub fn set_transform(&mut self, transform: &Transform<f32, f32, f32>) {
         self.object_matrix = (*transform).into();
+        self.update_world_aabb();
+    }
+
+    pub fn set_instance_transform(
+        &mut self,
+        instance_index: usize,
+        transform: &Transform<f32, f32, f32>,
+    ) {
+        if let Some(instances) = &mut self.instances {
+            if let Some(object_matrix) = instances.object_matrices.get_mut(instance_index) {
+                *object_matrix = (*transform).into();
+                instances
+                    .object_matrices_vbo
+                    .update_from_pointer(instance_index, 1);
+
+                if let Some(normal_matrix) = instances.normal_matrices.get_mut(instance_index) {
+                    *normal_matrix = normal_matrix_of(object_matrix);
+                    instances
+                        .normal_matrices_vbo
+                        .update_from_pointer(instance_index, 1);
+                }
+            }
+        }
+        self.update_world_aabb();
     }
 
     pub fn set_gl_material(&mut self, gl_material: Arc<GLMaterial>) {
         self.gl_material = gl_material;
+        self.mark_draw_orders_dirty();
     }
 
+    // lod index 0 is the nearest mesh
+    pub fn set_lod_gl_mesh(&mut self, lod_index: usize, gl_mesh: Rc<GLMesh>) {
+        if lod_index == 0 {
+            self.gl_mesh = gl_mesh;
+            self.mark_draw_orders_dirty();
+            self.vertex_array_object = create_vao(
+                &self.gl_mesh,
+                self.instances.as_ref(),
+                &self.gl_mesh_shader_program,
+            );
+            self.update_world_aabb();
+        } else if let Some(lod) = self.farther_lods.get_mut(lod_index - 1) {
+            lod.vertex_array_object = create_vao(&gl_mesh, None, &self.gl_mesh_shader_program);
+            lod.gl_mesh = gl_mesh;
+        } else {
+            return;
+        }
+
+        self.override_vertex_array_objects
+            .borrow_mut()
+            .remove(&lod_index);
+    }
+
+    // a single mesh replaces the lods
     pub fn set_gl_mesh(&mut self, gl_mesh: Rc<GLMesh>) {
         self.gl_mesh = gl_mesh;
-        self.vertex_array_object = create_vao(&self.gl_mesh, &self.gl_mesh_shader_program);
+        self.mark_draw_orders_dirty();
+        self.farther_lods.clear();
+        self.vertex_array_object = create_vao(
+            &self.gl_mesh,
+            self.instances.as_ref(),
+            &self.gl_mesh_shader_program,
+        );
+        self.override_vertex_array_objects.borrow_mut().clear();
+        self.update_world_aabb();
     }
 
     pub fn set_gl_mesh_shader_program(&mut self, gl_mesh_shader_program: Arc<GLMeshShaderProgram>) {
         self.gl_mesh_shader_program = gl_mesh_shader_program;
-        self.vertex_array_object = create_vao(&self.gl_mesh, &self.gl_mesh_shader_program);
+        self.mark_draw_orders_dirty();
+        self.vertex_array_object = create_vao(
+            &self.gl_mesh,
+            self.instances.as_ref(),
+            &self.gl_mesh_shader_program,
+        );
+        for lod in self.farther_lods.iter_mut() {
+            lod.vertex_array_object = create_vao(&lod.gl_mesh, None, &self.gl_mesh_shader_program);
+        }
     }
+
+    pub fn set_environment_map(&mut self, environment_map: Option<RcRwLock<Rc<TextureCubemap>>>) {
+        self.environment_map = environment_map;
+    }
+
+    pub fn set_uniform_override(&mut self, name: String, value: Option<RendererUniformValue>) {
+        match value {
+            Some(value) => self.uniform_overrides.insert(name, value),
+            None => self.uniform_overrides.remove(&name),
+        };
+    }
+}
+
+fn send_uniform_value(uniform: &ShaderUniform, value: &RendererUniformValue) {
+    match value {
+        RendererUniformValue::Float(value) => uniform.send_uniform_1f(*value),
+        RendererUniformValue::Int(value) => uniform.send_uniform_1i(*value),
+        RendererUniformValue::Vec2(value) => uniform.send_uniform_2fv(value.as_slice(), 1),
+        RendererUniformValue::Vec3(value) => uniform.send_uniform_3fv(value.as_slice(), 1),
+        RendererUniformValue::Vec4(value) => uniform.send_uniform_4fv(value.as_slice(), 1),
+        RendererUniformValue::Mat4(value) => {
+            uniform.send_uniform_matrix_4fv(value.as_col_slice(), 1)
+        }
+    }
+}
+
+pub(crate) fn set_blend_mode(blend_mode: MaterialBlendMode) {
+    with_gl_state_cache(|cache| match blend_mode {
+        MaterialBlendMode::Opaque => {
+            cache.set_capability(gl::BLEND, false);
+            cache.set_depth_mask(true);
+        }
+        MaterialBlendMode::AlphaBlend => {
+            cache.set_capability(gl::BLEND, true);
+            cache.set_blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            cache.set_depth_mask(false);
+        }
+        MaterialBlendMode::Additive => {
+            cache.set_capability(gl::BLEND, true);
+            cache.set_blend_func(gl::SRC_ALPHA, gl::ONE);
+            cache.set_depth_mask(false);
+        }
+    });
 }
 
 fn find_texture_with_min_uv_id(
@@ -212,7 +828,11 @@ fn find_texture_with_min_uv_id(
         .min_by(|item0, item1| item0.uv_channel_id.cmp(&item1.uv_channel_id))
 }
 
-fn create_vao(gl_mesh: &GLMesh, gl_mesh_shader_program: &GLMeshShaderProgram) -> VertexArrayObject {
+fn create_vao(
+    gl_mesh: &GLMesh,
+    instances: Option<&GLMeshInstances>,
+    gl_mesh_shader_program: &GLMeshShaderProgram,
+) -> VertexArrayObject {
     VertexArrayObject::new(|vao_interface| {
         vao_interface.use_index_buffer_object(&gl_mesh.index_buffer_object);
 
@@ -235,6 +855,24 @@ fn create_vao(gl_mesh: &GLMesh, gl_mesh_shader_program: &GLMeshShaderProgram) ->
             }
         }
 
+        if let Some(attribute) = &gl_mesh_shader_program.attributes.color_channels {
+            for i in 0..gl_mesh.color_channel_vbos.len() {
+                let color_channel_vbo = &gl_mesh.color_channel_vbos[i];
+                vao_interface.bind_vbo_to_shader_attrib_array(color_channel_vbo, attribute, i);
+            }
+        }
+
+        // custom float attributes are bound to the shader attributes with the same name
+        for (name, float_attribute_vbo) in gl_mesh.float_attribute_vbos.iter() {
+            if let Some(attribute) = gl_mesh_shader_program
+                .gl_shader_program
+                .shader_program
+                .get_attribute_by_name(name)
+            {
+                vao_interface.bind_vbo_to_shader_attrib(float_attribute_vbo, &attribute);
+            }
+        }
+
         if let Some(attribute) = &gl_mesh_shader_program.attributes.bone_ids {
             vao_interface.bind_vbo_to_shader_attrib(&gl_mesh.bone_ids_vbo, attribute);
         }
@@ -242,5 +880,31 @@ fn create_vao(gl_mesh: &GLMesh, gl_mesh_shader_program: &GLMeshShaderProgram) ->
         if let Some(attribute) = &gl_mesh_shader_program.attributes.bone_weights {
             vao_interface.bind_vbo_to_shader_attrib(&gl_mesh.bone_weights_vbo, attribute);
         }
+
+        if let (Some(instances), Some(attribute)) = (
+            instances,
+            &gl_mesh_shader_program.attributes.instance_object_matrix,
+        ) {
+            vao_interface.bind_instance_matrix_vbo_to_shader_attrib(
+                &instances.object_matrices_vbo,
+                attribute,
+            );
+        }
+
+        if let (Some(instances), Some(attribute)) = (
+            instances,
+            &gl_mesh_shader_program.attributes.instance_normal_matrix,
+        ) {
+            vao_interface.bind_instance_matrix_vbo_to_shader_attrib(
+                &instances.normal_matrices_vbo,
+                attribute,
+            );
+        }
     })
 }
+
+fn normal_matrix_of(object_matrix: &Mat4<f32>) -> Mat4<f32> {
+    let mut normal_matrix = object_matrix.inverted_affine_transform();
+    normal_matrix.transpose();
+    normal_matrix
+}
@@ -0,0 +1,59 @@
+use std::mem::size_of;
+
+use vek::{Mat4, Vec3};
+
+use super::opengl_utils::uniform_buffer_object::UniformBufferObject;
+
+pub const FRAME_UNIFORMS_BLOCK_NAME: &str = "FrameUniforms";
+pub const FRAME_UNIFORMS_BINDING_POINT: u32 = 0;
+
+// std140 layout of the FrameUniforms block of the mesh shaders
+#[repr(C)]
+struct FrameUniformsData {
+    view_matrix: [f32; 16],
+    projection_matrix: [f32; 16],
+    light_space_matrix: [f32; 16],
+    eye_position: [f32; 3],
+    exposure: f32,
+}
+
+// the camera and light data is uploaded once per pipeline step instead of once per object
+pub struct GLFrameUniforms {
+    uniform_buffer_object: UniformBufferObject,
+}
+
+impl Default for GLFrameUniforms {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GLFrameUniforms {
+    pub fn new() -> Self {
+        let uniform_buffer_object = UniformBufferObject::new(size_of::<FrameUniformsData>());
+        uniform_buffer_object.bind_to_binding_point(FRAME_UNIFORMS_BINDING_POINT);
+
+        Self {
+            uniform_buffer_object,
+        }
+    }
+
+    pub fn update(
+        &self,
+        eye_position: &Vec3<f32>,
+        projection_matrix: &Mat4<f32>,
+        view_matrix: &Mat4<f32>,
+        light_space_matrix: Option<&Mat4<f32>>,
+        exposure: f32,
+    ) {
+        let light_space_matrix = light_space_matrix.copied().unwrap_or_else(Mat4::identity);
+
+        self.uniform_buffer_object.update(&FrameUniformsData {
+            view_matrix: view_matrix.into_col_array(),
+            projection_matrix: projection_matrix.into_col_array(),
+            light_space_matrix: light_space_matrix.into_col_array(),
+            eye_position: eye_position.into_array(),
+            exposure,
+        });
+    }
+}
@@ -2,17 +2,26 @@ use std::sync::Arc;
 
 use vek::Vec3;
 
-use muleengine::mesh::{Material, MaterialTexture, MaterialTextureType, TextureMapMode};
+use muleengine::mesh::{
+    Material, MaterialBlendMode, MaterialTexture, MaterialTextureType, TextureAnisotropyMode,
+    TextureFilterMode, TextureMapMode,
+};
 
 use super::{
     gl_texture_container::GLTextureContainer,
-    opengl_utils::texture_2d::{GLTextureMapMode, Texture2D},
+    opengl_utils::{
+        texture_2d::{GLTextureAnisotropyMode, GLTextureMapMode, GLTextureSamplingMode, Texture2D},
+        texture_cubemap::TextureCubemap,
+    },
 };
 
+#[derive(Clone)]
 pub struct GLMaterialTexture {
     pub texture: Arc<Texture2D>,
     pub texture_type: MaterialTextureType,
     pub texture_map_mode: GLTextureMapMode,
+    pub sampling_mode: GLTextureSamplingMode,
+    pub anisotropy_mode: GLTextureAnisotropyMode,
     pub uv_channel_id: usize,
     pub blend: f32,
 }
@@ -22,7 +31,12 @@ pub struct GLMaterial {
     pub albedo_color: Vec3<f32>,
     pub emissive_color: Vec3<f32>,
     pub shininess_color: Vec3<f32>,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub ambient_occlusion: f32,
+    pub blend_mode: MaterialBlendMode,
     pub textures: Vec<GLMaterialTexture>,
+    pub environment_cubemap: Option<Arc<TextureCubemap>>,
 }
 
 pub struct RendererMaterialObject {
@@ -42,9 +56,58 @@ impl GLMaterial {
             albedo_color: material.albedo_color,
             emissive_color: material.emissive_color,
             shininess_color: material.shininess_color,
+            metallic: material.metallic,
+            roughness: material.roughness,
+            ambient_occlusion: material.ambient_occlusion,
+            blend_mode: material.blend_mode,
             textures,
+            environment_cubemap: None,
         }
     }
+
+    pub fn with_texture(&self, texture: GLMaterialTexture) -> Self {
+        let mut textures = self
+            .textures
+            .iter()
+            .filter(|material_texture| material_texture.texture_type != texture.texture_type)
+            .cloned()
+            .collect::<Vec<_>>();
+        textures.push(texture);
+
+        Self {
+            opacity: self.opacity,
+            albedo_color: self.albedo_color,
+            emissive_color: self.emissive_color,
+            shininess_color: self.shininess_color,
+            metallic: self.metallic,
+            roughness: self.roughness,
+            ambient_occlusion: self.ambient_occlusion,
+            blend_mode: self.blend_mode,
+            textures,
+            environment_cubemap: self.environment_cubemap.clone(),
+        }
+    }
+
+    pub fn with_environment_cubemap(&self, environment_cubemap: Arc<TextureCubemap>) -> Self {
+        Self {
+            opacity: self.opacity,
+            albedo_color: self.albedo_color,
+            emissive_color: self.emissive_color,
+            shininess_color: self.shininess_color,
+            metallic: self.metallic,
+            roughness: self.roughness,
+            ambient_occlusion: self.ambient_occlusion,
+            blend_mode: self.blend_mode,
+            textures: self.textures.clone(),
+            environment_cubemap: Some(environment_cubemap),
+        }
+    }
+
+    pub fn is_uploaded(&self) -> bool {
+        self.textures
+            .iter()
+            .all(|material_texture| material_texture.texture.is_uploaded())
+    }
 }
 
 impl GLMaterialTexture {
@@ -55,10 +118,27 @@ impl GLMaterialTexture {
             TextureMapMode::Mirror => GLTextureMapMode::Mirror,
         };
 
+        let sampling_mode = match texture.filter_mode {
+            TextureFilterMode::Nearest => GLTextureSamplingMode::Nearest,
+            TextureFilterMode::Linear => GLTextureSamplingMode::Linear,
+            TextureFilterMode::Bilinear => GLTextureSamplingMode::LinearMipmapNearest,
+            TextureFilterMode::Trilinear => GLTextureSamplingMode::LinearMipmapLinear,
+        };
+
+        let anisotropy_mode = match texture.anisotropy_mode {
+            TextureAnisotropyMode::Anisotropy1 => GLTextureAnisotropyMode::Anisotropy1,
+            TextureAnisotropyMode::Anisotropy2 => GLTextureAnisotropyMode::Anisotropy2,
+            TextureAnisotropyMode::Anisotropy4 => GLTextureAnisotropyMode::Anisotropy4,
+            TextureAnisotropyMode::Anisotropy8 => GLTextureAnisotropyMode::Anisotropy8,
+            TextureAnisotropyMode::Anisotropy16 => GLTextureAnisotropyMode::Anisotropy16,
+        };
+
         Self {
             texture: gl_texture_container.get_texture(texture.image.clone()),
             texture_type: texture.texture_type,
             texture_map_mode,
+            sampling_mode,
+            anisotropy_mode,
             blend: texture.blend,
             uv_channel_id: texture.uv_channel_id,
         }
@@ -1,16 +1,29 @@
-use std::{rc::Rc, sync::Arc};
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    ops::Range,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use vek::{Mat4, Vec4};
 
-use muleengine::mesh::Mesh;
+use muleengine::{
+    aabb::AxisAlignedBoundingBox, mesh::Mesh,
+    renderer::renderer_mesh_vertices::RendererMeshVertices,
+};
 
 use super::opengl_utils::{
+    deferred_buffer_upload::{BufferUploadMode, DeferredBufferUpload},
     index_buffer_object::{IndexBufferObject, PrimitiveMode},
     vertex_buffer_object::{DataCount, DataType, VertexBufferObject},
 };
 
 pub struct GLMesh {
-    _mesh: Arc<Mesh>,
+    mesh: Arc<Mesh>,
 
     pub(super) bone_transforms: Vec<Mat4<f32>>,
 
@@ -19,11 +32,16 @@ pub struct GLMesh {
     pub(super) normals_vbo: VertexBufferObject,
     pub(super) tangents_vbo: VertexBufferObject,
     pub(super) uv_channel_vbos: Vec<VertexBufferObject>,
+    pub(super) color_channel_vbos: Vec<VertexBufferObject>,
+    pub(super) float_attribute_vbos: BTreeMap<String, VertexBufferObject>,
     pub(super) bone_ids_vbo: VertexBufferObject,
     pub(super) bone_weights_vbo: VertexBufferObject,
 
-    _bone_weights_vector: Vec<Vec4<f32>>,
-    _bone_ids_vector: Vec<Vec4<u32>>,
+    // the deferred uploads read the bone data from another thread
+    bone_weights_vector: Arc<Vec<Vec4<f32>>>,
+    bone_ids_vector: Arc<Vec<Vec4<u32>>>,
+
+    uploaded: Arc<AtomicBool>,
 }
 
 pub struct RendererMeshObject {
@@ -32,41 +50,80 @@ pub struct RendererMeshObject {
 
 impl GLMesh {
     pub fn new(mesh: Arc<Mesh>) -> Self {
-        let index_buffer_object = IndexBufferObject::new(
+        Self::new_with_upload_mode(mesh, BufferUploadMode::Immediate)
+    }
+
+    // the buffers are only allocated, the mesh is not drawn until the upload queue fills them
+    pub fn new_deferred(mesh: Arc<Mesh>) -> Self {
+        Self::new_with_upload_mode(mesh, BufferUploadMode::Deferred)
+    }
+
+    fn new_with_upload_mode(mesh: Arc<Mesh>, upload_mode: BufferUploadMode) -> Self {
+        let index_buffer_object = IndexBufferObject::new_with_upload_mode(
             mesh.get_faces().as_ptr(),
             mesh.get_faces().len(),
             PrimitiveMode::Triangles,
+            upload_mode,
         );
 
-        let positions_vbo = VertexBufferObject::new(
+        let positions_vbo = VertexBufferObject::new_with_upload_mode(
             mesh.get_positions().as_ptr(),
             mesh.get_positions().len(),
             DataType::F32,
             DataCount::Coords3,
+            upload_mode,
         );
-        let normals_vbo = VertexBufferObject::new(
+        let normals_vbo = VertexBufferObject::new_with_upload_mode(
             mesh.get_normals().as_ptr(),
             mesh.get_normals().len(),
             DataType::F32,
             DataCount::Coords3,
+            upload_mode,
         );
-        let tangents_vbo = VertexBufferObject::new(
+        let tangents_vbo = VertexBufferObject::new_with_upload_mode(
             mesh.get_tangents().as_ptr(),
             mesh.get_tangents().len(),
             DataType::F32,
             DataCount::Coords3,
+            upload_mode,
         );
 
         let mut uv_channel_vbos = Vec::new();
         for uv_channel in mesh.get_uv_channels() {
-            uv_channel_vbos.push(VertexBufferObject::new(
+            uv_channel_vbos.push(VertexBufferObject::new_with_upload_mode(
                 uv_channel.as_ptr(),
                 uv_channel.len(),
                 DataType::F32,
                 DataCount::Coords2,
+                upload_mode,
+            ));
+        }
+
+        let mut color_channel_vbos = Vec::new();
+        for color_channel in mesh.get_color_channels() {
+            color_channel_vbos.push(VertexBufferObject::new_with_upload_mode(
+                color_channel.as_ptr(),
+                color_channel.len(),
+                DataType::F32,
+                DataCount::Rgba,
+                upload_mode,
             ));
         }
 
+        let mut float_attribute_vbos = BTreeMap::new();
+        for (name, values) in mesh.get_float_attributes() {
+            float_attribute_vbos.insert(
+                name.clone(),
+                VertexBufferObject::new_with_upload_mode(
+                    values.as_ptr(),
+                    values.len(),
+                    DataType::F32,
+                    DataCount::Single,
+                    upload_mode,
+                ),
+            );
+        }
+
         let mut bone_weights_vector = Vec::new();
         let mut bone_ids_vector = Vec::new();
 
@@ -89,17 +146,19 @@ impl GLMesh {
             bone_ids_vector.push(bone_ids);
         }
 
-        let bone_ids_vbo = VertexBufferObject::new(
+        let bone_ids_vbo = VertexBufferObject::new_with_upload_mode(
             bone_ids_vector.as_ptr(),
             bone_ids_vector.len(),
             DataType::U32,
             DataCount::Coords4,
+            upload_mode,
         );
-        let bone_weights_vbo = VertexBufferObject::new(
+        let bone_weights_vbo = VertexBufferObject::new_with_upload_mode(
             bone_weights_vector.as_ptr(),
             bone_weights_vector.len(),
             DataType::F32,
             DataCount::Coords4,
+            upload_mode,
         );
 
         let bone_transforms = mesh
@@ -109,7 +168,7 @@ impl GLMesh {
             .collect();
 
         Self {
-            _mesh: mesh,
+            mesh,
             bone_transforms,
 
             index_buffer_object,
@@ -118,11 +177,111 @@ impl GLMesh {
             normals_vbo,
             tangents_vbo,
             uv_channel_vbos,
+            color_channel_vbos,
+            float_attribute_vbos,
             bone_ids_vbo,
             bone_weights_vbo,
 
-            _bone_weights_vector: bone_weights_vector,
-            _bone_ids_vector: bone_ids_vector,
+            bone_weights_vector: Arc::new(bone_weights_vector),
+            bone_ids_vector: Arc::new(bone_ids_vector),
+
+            uploaded: Arc::new(AtomicBool::new(upload_mode == BufferUploadMode::Immediate)),
+        }
+    }
+
+    pub fn is_uploaded(&self) -> bool {
+        self.uploaded.load(Ordering::Acquire)
+    }
+
+    pub(super) fn uploaded_flag(&self) -> Arc<AtomicBool> {
+        self.uploaded.clone()
+    }
+
+    pub(super) fn deferred_uploads(&self) -> Vec<DeferredBufferUpload> {
+        let mut deferred_uploads = vec![
+            self.index_buffer_object.deferred_upload(),
+            self.positions_vbo.deferred_upload(),
+            self.normals_vbo.deferred_upload(),
+            self.tangents_vbo.deferred_upload(),
+            self.bone_ids_vbo.deferred_upload(),
+            self.bone_weights_vbo.deferred_upload(),
+        ];
+        deferred_uploads.extend(
+            self.uv_channel_vbos
+                .iter()
+                .chain(self.color_channel_vbos.iter())
+                .chain(self.float_attribute_vbos.values())
+                .map(VertexBufferObject::deferred_upload),
+        );
+
+        deferred_uploads
+    }
+
+    // the buffers of the deferred uploads point into these
+    pub(super) fn deferred_upload_data_owners(&self) -> Vec<Arc<dyn Any + Send + Sync>> {
+        vec![
+            self.mesh.clone(),
+            self.bone_weights_vector.clone(),
+            self.bone_ids_vector.clone(),
+        ]
+    }
+
+    pub fn number_of_triangles(&self) -> usize {
+        self.index_buffer_object.number_of_elements() / 3
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.index_buffer_object.size_in_bytes()
+            + self.positions_vbo.size_in_bytes()
+            + self.normals_vbo.size_in_bytes()
+            + self.tangents_vbo.size_in_bytes()
+            + self
+                .uv_channel_vbos
+                .iter()
+                .chain(self.color_channel_vbos.iter())
+                .chain(self.float_attribute_vbos.values())
+                .map(VertexBufferObject::size_in_bytes)
+                .sum::<usize>()
+            + self.bone_ids_vbo.size_in_bytes()
+            + self.bone_weights_vbo.size_in_bytes()
+    }
+
+    // only the buffers are updated, the mesh and its bounding box keep the values it was created from,
+    // the renderer meshes that were created from the same mesh share the buffers
+    pub fn update_vertices(
+        &self,
+        vertex_range: Range<usize>,
+        vertices: &RendererMeshVertices,
+    ) -> Result<(), String> {
+        vertices.validate(&vertex_range, self.positions_vbo.number_of_elements())?;
+
+        // the upload queue would overwrite the updated vertices
+        if !self.is_uploaded() {
+            return Err("the mesh is not uploaded yet".to_string());
+        }
+
+        if vertices.tangents.is_some() && self.tangents_vbo.number_of_elements() == 0 {
+            return Err("the mesh does not have tangents".to_string());
+        }
+
+        self.positions_vbo
+            .update_data(vertex_range.start, &vertices.positions);
+        if let Some(normals) = &vertices.normals {
+            self.normals_vbo.update_data(vertex_range.start, normals);
+        }
+        if let Some(tangents) = &vertices.tangents {
+            self.tangents_vbo.update_data(vertex_range.start, tangents);
+        }
+
+        Ok(())
+    }
+
+    // skinned meshes can leave the box of their bind pose, so they do not have one
+    pub fn aabb(&self) -> Option<&AxisAlignedBoundingBox> {
+        if self.mesh.get_faces().is_empty() || !self.mesh.get_bones().is_empty() {
+            None
+        } else {
+            Some(self.mesh.get_aabb())
         }
     }
 }
@@ -1,12 +1,16 @@
 use std::sync::Arc;
 use std::{collections::BTreeMap, rc::Rc};
 
-use muleengine::mesh::{Mesh, MeshConvertError, Scene};
+use muleengine::{
+    bytifex_utils::sync::types::RcRwLock,
+    mesh::{Mesh, MeshConvertError, Scene},
+};
 
-use super::gl_mesh::GLMesh;
+use super::{gl_mesh::GLMesh, gl_upload_queue::GLUploadQueue};
 
 pub struct GLMeshContainer {
     meshes: BTreeMap<*const Mesh, Rc<GLMesh>>,
+    upload_queue: Option<RcRwLock<GLUploadQueue>>,
 }
 
 impl Default for GLMeshContainer {
@@ -19,9 +23,15 @@ impl GLMeshContainer {
     pub fn new() -> Self {
         Self {
             meshes: BTreeMap::new(),
+            upload_queue: None,
         }
     }
 
+    // with an upload queue the data of the new meshes is uploaded on another thread
+    pub fn set_upload_queue(&mut self, upload_queue: Option<RcRwLock<GLUploadQueue>>) {
+        self.upload_queue = upload_queue;
+    }
+
     pub fn get_gl_meshes_from_scene(
         &mut self,
         scene: Rc<Scene>,
@@ -44,16 +54,52 @@ impl GLMeshContainer {
     }
 
     pub fn get_gl_mesh(&mut self, mesh: Arc<Mesh>) -> Rc<GLMesh> {
+        let upload_queue = &self.upload_queue;
         let mesh = self
             .meshes
             .entry(&*mesh)
-            .or_insert_with(|| Rc::new(GLMesh::new(mesh)));
+            .or_insert_with(|| match upload_queue {
+                Some(upload_queue) => {
+                    let gl_mesh = GLMesh::new_deferred(mesh);
+                    upload_queue.write().upload_mesh(&gl_mesh);
+                    Rc::new(gl_mesh)
+                }
+                None => Rc::new(GLMesh::new(mesh)),
+            });
 
         mesh.clone()
     }
 
+    pub fn number_of_meshes(&self) -> usize {
+        self.meshes.len()
+    }
+
+    pub fn mesh_memory_bytes(&self) -> usize {
+        self.meshes
+            .values()
+            .map(|gl_mesh| gl_mesh.size_in_bytes())
+            .sum()
+    }
+
+    // the buffers of a mesh that is still uploaded are kept, release_unused_meshes releases them later
     pub fn release_mesh(&mut self, mesh: Arc<Mesh>) {
         let key: *const Mesh = &*mesh;
-        self.meshes.remove(&key);
+        if self
+            .meshes
+            .get(&key)
+            .is_some_and(|gl_mesh| gl_mesh.is_uploaded())
+        {
+            self.meshes.remove(&key);
+        }
+    }
+
+    // a mesh is unused if the container holds the only reference to it,
+    // the buffers of the released meshes are deleted, except the ones that are still uploaded
+    pub fn release_unused_meshes(&mut self) -> usize {
+        let number_of_meshes = self.meshes.len();
+        self.meshes
+            .retain(|_, gl_mesh| Rc::strong_count(gl_mesh) > 1 || !gl_mesh.is_uploaded());
+
+        number_of_meshes - self.meshes.len()
     }
 }
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use crate::gl_shader_program::GLShaderProgram;
+use crate::{
+    gl_frame_uniforms::{FRAME_UNIFORMS_BINDING_POINT, FRAME_UNIFORMS_BLOCK_NAME},
+    gl_shader_program::GLShaderProgram,
+};
 
 use super::opengl_utils::shader_input::{ShaderAttribute, ShaderUniform};
 
@@ -9,8 +12,11 @@ pub(super) struct Attributes {
     pub(super) normal: Option<ShaderAttribute>,
     pub(super) tangent: Option<ShaderAttribute>,
     pub(super) uv_channels: Option<ShaderAttribute>,
+    pub(super) color_channels: Option<ShaderAttribute>,
     pub(super) bone_ids: Option<ShaderAttribute>,
     pub(super) bone_weights: Option<ShaderAttribute>,
+    pub(super) instance_object_matrix: Option<ShaderAttribute>,
+    pub(super) instance_normal_matrix: Option<ShaderAttribute>,
 }
 
 pub(super) struct Uniforms {
@@ -20,6 +26,8 @@ pub(super) struct Uniforms {
     pub(super) projection_matrix: Option<ShaderUniform>,
     pub(super) normal_matrix: Option<ShaderUniform>,
     pub(super) bones: Option<ShaderUniform>,
+    pub(super) number_of_color_channels: Option<ShaderUniform>,
+    pub(super) use_instancing: Option<ShaderUniform>,
 
     pub(super) use_albedo_texture: Option<ShaderUniform>,
     pub(super) albedo_texture: Option<ShaderUniform>,
@@ -33,10 +41,42 @@ pub(super) struct Uniforms {
     pub(super) displacement_texture: Option<ShaderUniform>,
     pub(super) displacement_texture_uv_channel_id: Option<ShaderUniform>,
 
+    pub(super) use_shininess_texture: Option<ShaderUniform>,
+    pub(super) shininess_texture: Option<ShaderUniform>,
+    pub(super) shininess_texture_uv_channel_id: Option<ShaderUniform>,
+
+    pub(super) use_emission_texture: Option<ShaderUniform>,
+    pub(super) emission_texture: Option<ShaderUniform>,
+    pub(super) emission_texture_uv_channel_id: Option<ShaderUniform>,
+
+    pub(super) use_metallic_texture: Option<ShaderUniform>,
+    pub(super) metallic_texture: Option<ShaderUniform>,
+    pub(super) metallic_texture_uv_channel_id: Option<ShaderUniform>,
+
+    pub(super) use_roughness_texture: Option<ShaderUniform>,
+    pub(super) roughness_texture: Option<ShaderUniform>,
+    pub(super) roughness_texture_uv_channel_id: Option<ShaderUniform>,
+
+    pub(super) use_ambient_occlusion_texture: Option<ShaderUniform>,
+    pub(super) ambient_occlusion_texture: Option<ShaderUniform>,
+    pub(super) ambient_occlusion_texture_uv_channel_id: Option<ShaderUniform>,
+
+    pub(super) use_environment_map: Option<ShaderUniform>,
+    pub(super) environment_map: Option<ShaderUniform>,
+
     pub(super) opacity: Option<ShaderUniform>,
     pub(super) albedo_color: Option<ShaderUniform>,
     pub(super) emissive_color: Option<ShaderUniform>,
     pub(super) shininess_color: Option<ShaderUniform>,
+    pub(super) metallic: Option<ShaderUniform>,
+    pub(super) roughness: Option<ShaderUniform>,
+    pub(super) ambient_occlusion: Option<ShaderUniform>,
+
+    pub(super) exposure: Option<ShaderUniform>,
+
+    pub(super) use_shadow_map: Option<ShaderUniform>,
+    pub(super) shadow_map: Option<ShaderUniform>,
+    pub(super) light_space_matrix: Option<ShaderUniform>,
 }
 
 pub struct GLMeshShaderProgram {
@@ -47,6 +87,11 @@ pub struct GLMeshShaderProgram {
 
 impl GLMeshShaderProgram {
     pub fn new(gl_shader_program: Arc<GLShaderProgram>) -> Self {
+        // shaders without the block still get the camera and light data as separate uniforms
+        gl_shader_program
+            .shader_program
+            .bind_uniform_block(FRAME_UNIFORMS_BLOCK_NAME, FRAME_UNIFORMS_BINDING_POINT);
+
         let attributes = Attributes {
             position: gl_shader_program
                 .shader_program
@@ -60,12 +105,21 @@ impl GLMeshShaderProgram {
             uv_channels: gl_shader_program
                 .shader_program
                 .get_attribute_by_name("uvChannels"),
+            color_channels: gl_shader_program
+                .shader_program
+                .get_attribute_by_name("colorChannels"),
             bone_ids: gl_shader_program
                 .shader_program
                 .get_attribute_by_name("boneIds"),
             bone_weights: gl_shader_program
                 .shader_program
                 .get_attribute_by_name("boneWeights"),
+            instance_object_matrix: gl_shader_program
+                .shader_program
+                .get_attribute_by_name("instanceObjectMatrix"),
+            instance_normal_matrix: gl_shader_program
+                .shader_program
+                .get_attribute_by_name("instanceNormalMatrix"),
         };
 
         let uniforms = Uniforms {
@@ -87,6 +141,12 @@ impl GLMeshShaderProgram {
             bones: gl_shader_program
                 .shader_program
                 .get_uniform_by_name("bones"),
+            number_of_color_channels: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("numberOfColorChannels"),
+            use_instancing: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useInstancing"),
 
             use_albedo_texture: gl_shader_program
                 .shader_program
@@ -118,6 +178,63 @@ impl GLMeshShaderProgram {
                 .shader_program
                 .get_uniform_by_name("displacementTextureUvChannelId"),
 
+            use_shininess_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useShininessTexture"),
+            shininess_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("shininessTexture"),
+            shininess_texture_uv_channel_id: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("shininessTextureUvChannelId"),
+
+            use_emission_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useEmissionTexture"),
+            emission_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("emissionTexture"),
+            emission_texture_uv_channel_id: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("emissionTextureUvChannelId"),
+
+            use_metallic_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useMetallicTexture"),
+            metallic_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("metallicTexture"),
+            metallic_texture_uv_channel_id: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("metallicTextureUvChannelId"),
+
+            use_roughness_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useRoughnessTexture"),
+            roughness_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("roughnessTexture"),
+            roughness_texture_uv_channel_id: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("roughnessTextureUvChannelId"),
+
+            use_ambient_occlusion_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useAmbientOcclusionTexture"),
+            ambient_occlusion_texture: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("ambientOcclusionTexture"),
+            ambient_occlusion_texture_uv_channel_id: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("ambientOcclusionTextureUvChannelId"),
+
+            use_environment_map: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useEnvironmentMap"),
+            environment_map: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("environmentMap"),
+
             opacity: gl_shader_program
                 .shader_program
                 .get_uniform_by_name("opacity"),
@@ -130,6 +247,29 @@ impl GLMeshShaderProgram {
             shininess_color: gl_shader_program
                 .shader_program
                 .get_uniform_by_name("shininessColor"),
+            metallic: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("metallic"),
+            roughness: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("roughness"),
+            ambient_occlusion: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("ambientOcclusion"),
+
+            exposure: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("exposure"),
+
+            use_shadow_map: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("useShadowMap"),
+            shadow_map: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("shadowMap"),
+            light_space_matrix: gl_shader_program
+                .shader_program
+                .get_uniform_by_name("lightSpaceMatrix"),
         };
 
         Self {
@@ -0,0 +1,232 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use muleengine::aabb::AxisAlignedBoundingBox;
+use vek::{Mat4, Vec3};
+
+use super::{
+    gl_draw_stats::GLDrawStats,
+    gl_drawable_mesh::GLDrawableMesh,
+    gl_shader_program::GLShaderProgram,
+    opengl_utils::{
+        gl_state_cache::with_gl_state_cache,
+        occlusion_query::OcclusionQuery,
+        shader_input::ShaderUniform,
+        vertex_array_object::VertexArrayObject,
+        vertex_buffer_object::{DataCount, DataType, VertexBufferObject},
+    },
+};
+
+// the boxes are enlarged, so the surfaces of the objects do not hide their own boxes
+const BOX_MARGIN: f32 = 0.01;
+
+struct GLOcclusionQueryState {
+    query: OcclusionQuery,
+    is_occluded: bool,
+    // a query is not issued again until its previous result is read
+    is_result_pending: bool,
+}
+
+pub struct GLOcclusionCuller {
+    // keyed by the renderer objects, the states of the objects that were not drawn in a frame
+    // are dropped, so the removed objects do not keep their queries
+    query_states: RefCell<BTreeMap<*const GLDrawableMesh, GLOcclusionQueryState>>,
+    queried_boxes: RefCell<Vec<(*const GLDrawableMesh, AxisAlignedBoundingBox)>>,
+
+    // the vbo keeps a pointer to the vector
+    _box_positions: Vec<Vec3<f32>>,
+    _positions_vbo: VertexBufferObject,
+    vertex_array_object: VertexArrayObject,
+
+    gl_shader_program: Arc<GLShaderProgram>,
+    object_matrix_uniform: Option<ShaderUniform>,
+    view_matrix_uniform: Option<ShaderUniform>,
+    projection_matrix_uniform: Option<ShaderUniform>,
+}
+
+impl GLOcclusionCuller {
+    pub fn new(gl_shader_program: Arc<GLShaderProgram>) -> Self {
+        let shader_program = &gl_shader_program.shader_program;
+        let object_matrix_uniform = shader_program.get_uniform_by_name("objectMatrix");
+        let view_matrix_uniform = shader_program.get_uniform_by_name("viewMatrix");
+        let projection_matrix_uniform = shader_program.get_uniform_by_name("projectionMatrix");
+
+        let box_positions = unit_box_triangles();
+        let positions_vbo = VertexBufferObject::new(
+            box_positions.as_ptr(),
+            box_positions.len(),
+            DataType::F32,
+            DataCount::Coords3,
+        );
+        let vertex_array_object = VertexArrayObject::new(|vao_interface| {
+            if let Some(attrib) = shader_program.get_attribute_by_name("position") {
+                vao_interface.bind_vbo_to_shader_attrib(&positions_vbo, &attrib);
+            }
+        });
+
+        Self {
+            query_states: RefCell::new(BTreeMap::new()),
+            queried_boxes: RefCell::new(Vec::new()),
+
+            _box_positions: box_positions,
+            _positions_vbo: positions_vbo,
+            vertex_array_object,
+
+            gl_shader_program,
+            object_matrix_uniform,
+            view_matrix_uniform,
+            projection_matrix_uniform,
+        }
+    }
+
+    // the result of the previous frame is used, so reading it does not wait for the gpu,
+    // the bounding box of the object is queried again by issue_queries
+    pub fn is_occluded(&self, renderer_object: &GLDrawableMesh, eye_position: &Vec3<f32>) -> bool {
+        let world_aabb = match renderer_object.world_aabb() {
+            Some(world_aabb) => enlarged_aabb(world_aabb),
+            None => return false,
+        };
+
+        // the faces of the box are behind the eye if the eye is inside of it
+        if contains_point(&world_aabb, eye_position) {
+            return false;
+        }
+
+        let key: *const GLDrawableMesh = renderer_object;
+        self.queried_boxes.borrow_mut().push((key, world_aabb));
+
+        match self.query_states.borrow_mut().get_mut(&key) {
+            Some(state) => {
+                if let Some(any_samples_passed) = state.query.any_samples_passed() {
+                    state.is_occluded = !any_samples_passed;
+                    state.is_result_pending = false;
+                }
+
+                state.is_occluded
+            }
+            None => false,
+        }
+    }
+
+    // has to be called after the opaque objects are drawn, so the boxes are tested against them
+    pub fn issue_queries(
+        &self,
+        projection_matrix: &Mat4<f32>,
+        view_matrix: &Mat4<f32>,
+        draw_stats: &GLDrawStats,
+    ) {
+        let queried_boxes = std::mem::take(&mut *self.queried_boxes.borrow_mut());
+        let queried_objects = queried_boxes
+            .iter()
+            .map(|(key, _)| *key)
+            .collect::<BTreeSet<_>>();
+
+        let mut query_states = self.query_states.borrow_mut();
+        query_states.retain(|key, _| queried_objects.contains(key));
+
+        if queried_boxes.is_empty() {
+            return;
+        }
+
+        self.gl_shader_program.shader_program.use_program();
+
+        if let Some(uniform) = &self.view_matrix_uniform {
+            uniform.send_uniform_matrix_4fv(view_matrix.as_col_slice(), 1);
+        }
+
+        if let Some(uniform) = &self.projection_matrix_uniform {
+            uniform.send_uniform_matrix_4fv(projection_matrix.as_col_slice(), 1);
+        }
+
+        // the depth test of the boxes follows the depth direction of the layer
+        let reverse_z = with_gl_state_cache(|cache| {
+            cache.set_capability(gl::BLEND, false);
+            cache.set_depth_mask(false);
+            cache.set_color_mask(false);
+            let reverse_z = cache.is_reverse_z();
+            cache.set_depth_func(if reverse_z { gl::GEQUAL } else { gl::LEQUAL });
+            reverse_z
+        });
+
+        self.vertex_array_object.use_vao(|| {
+            for (key, world_aabb) in queried_boxes.iter() {
+                let state = query_states
+                    .entry(*key)
+                    .or_insert_with(|| GLOcclusionQueryState {
+                        query: OcclusionQuery::new(),
+                        is_occluded: false,
+                        is_result_pending: false,
+                    });
+                if state.is_result_pending {
+                    continue;
+                }
+
+                let min_vertex = *world_aabb.get_min_vertex();
+                let object_matrix = Mat4::<f32>::translation_3d(min_vertex)
+                    * Mat4::scaling_3d(*world_aabb.get_max_vertex() - min_vertex);
+                if let Some(uniform) = &self.object_matrix_uniform {
+                    uniform.send_uniform_matrix_4fv(object_matrix.as_col_slice(), 1);
+                }
+
+                state.query.begin();
+                unsafe {
+                    gl::DrawArrays(gl::TRIANGLES, 0, 36);
+                }
+                state.query.end();
+                state.is_result_pending = true;
+
+                draw_stats.add_draw_call(12);
+            }
+        });
+
+        with_gl_state_cache(|cache| {
+            cache.set_color_mask(true);
+            cache.set_depth_func(if reverse_z { gl::GREATER } else { gl::LESS });
+            cache.restore_depth_mask();
+        });
+    }
+}
+
+fn enlarged_aabb(aabb: &AxisAlignedBoundingBox) -> AxisAlignedBoundingBox {
+    let margin = (*aabb.get_max_vertex() - *aabb.get_min_vertex()) * BOX_MARGIN + BOX_MARGIN;
+
+    let mut enlarged_aabb = AxisAlignedBoundingBox::new(*aabb.get_min_vertex() - margin);
+    enlarged_aabb.add_vertex(*aabb.get_max_vertex() + margin);
+
+    enlarged_aabb
+}
+
+fn contains_point(aabb: &AxisAlignedBoundingBox, point: &Vec3<f32>) -> bool {
+    let min = aabb.get_min_vertex();
+    let max = aabb.get_max_vertex();
+
+    (min.x..=max.x).contains(&point.x)
+        && (min.y..=max.y).contains(&point.y)
+        && (min.z..=max.z).contains(&point.z)
+}
+
+// the triangles of the box between (0, 0, 0) and (1, 1, 1)
+fn unit_box_triangles() -> Vec<Vec3<f32>> {
+    let mut unit_box = AxisAlignedBoundingBox::new(Vec3::zero());
+    unit_box.add_vertex(Vec3::one());
+    let corners = unit_box.corners();
+
+    // the x, y and z coordinates of the corners are the bits of their indices
+    const FACES: [[usize; 4]; 6] = [
+        [0, 1, 3, 2],
+        [4, 6, 7, 5],
+        [0, 4, 5, 1],
+        [2, 3, 7, 6],
+        [0, 2, 6, 4],
+        [1, 5, 7, 3],
+    ];
+
+    FACES
+        .iter()
+        .flat_map(|face| [face[0], face[1], face[2], face[0], face[2], face[3]])
+        .map(|corner_index| corners[corner_index])
+        .collect()
+}
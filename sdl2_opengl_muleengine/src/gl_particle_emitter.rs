@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use muleengine::{
+    mesh::MaterialTextureType,
+    renderer::particle_system::{ParticleEmitter, ParticleEmitterSettings},
+};
+use vek::{Mat4, Transform, Vec3, Vec4};
+
+use super::{
+    gl_drawable_mesh::{set_blend_mode, DrawParameters},
+    gl_frame_uniforms::{FRAME_UNIFORMS_BINDING_POINT, FRAME_UNIFORMS_BLOCK_NAME},
+    gl_material::GLMaterial,
+    gl_shader_program::GLShaderProgram,
+    opengl_utils::{
+        gl_state_cache::with_gl_state_cache,
+        shader_input::ShaderUniform,
+        vertex_array_object::VertexArrayObject,
+        vertex_buffer_object::{DataCount, DataType, VertexBufferObject},
+    },
+};
+
+const MIN_PARTICLE_CAPACITY: usize = 64;
+const ALBEDO_TEXTURE_LAYER: usize = 0;
+
+// the corners of the billboard quad in the order of a triangle strip
+static QUAD_CORNERS: [[f32; 2]; 4] = [[-0.5, -0.5], [0.5, -0.5], [-0.5, 0.5], [0.5, 0.5]];
+
+struct GLParticleBuffers {
+    _corners_vbo: VertexBufferObject,
+    positions_vbo: VertexBufferObject,
+    sizes_vbo: VertexBufferObject,
+    colors_vbo: VertexBufferObject,
+    vertex_array_object: VertexArrayObject,
+}
+
+struct GLParticleUniforms {
+    use_albedo_texture: Option<ShaderUniform>,
+    albedo_texture: Option<ShaderUniform>,
+    albedo_color: Option<ShaderUniform>,
+    opacity: Option<ShaderUniform>,
+}
+
+pub struct GLParticleEmitter {
+    emitter: ParticleEmitter,
+    object_matrix: Mat4<f32>,
+    gl_material: Arc<GLMaterial>,
+
+    // the vbos keep pointers to the vectors, so they are only resized together with the vbos
+    positions: Vec<Vec3<f32>>,
+    sizes: Vec<f32>,
+    colors: Vec<Vec4<f32>>,
+    number_of_particles: usize,
+    buffers: Option<GLParticleBuffers>,
+
+    gl_shader_program: Arc<GLShaderProgram>,
+    uniforms: GLParticleUniforms,
+}
+
+impl GLParticleEmitter {
+    pub fn new(
+        settings: ParticleEmitterSettings,
+        gl_material: Arc<GLMaterial>,
+        transform: Transform<f32, f32, f32>,
+        gl_shader_program: Arc<GLShaderProgram>,
+    ) -> Self {
+        let uniforms = Self::gather_uniforms(&gl_shader_program);
+
+        Self {
+            emitter: ParticleEmitter::new(settings),
+            object_matrix: transform.into(),
+            gl_material,
+
+            positions: Vec::new(),
+            sizes: Vec::new(),
+            colors: Vec::new(),
+            number_of_particles: 0,
+            buffers: None,
+
+            gl_shader_program,
+            uniforms,
+        }
+    }
+
+    pub fn set_settings(&mut self, settings: ParticleEmitterSettings) {
+        self.emitter.set_settings(settings);
+    }
+
+    pub fn set_transform(&mut self, transform: &Transform<f32, f32, f32>) {
+        self.object_matrix = (*transform).into();
+    }
+
+    pub fn set_gl_material(&mut self, gl_material: Arc<GLMaterial>) {
+        self.gl_material = gl_material;
+    }
+
+    // the vertex array object is bound to the attributes of the old shader program
+    pub fn set_gl_shader_program(&mut self, gl_shader_program: Arc<GLShaderProgram>) {
+        self.uniforms = Self::gather_uniforms(&gl_shader_program);
+        self.gl_shader_program = gl_shader_program;
+        self.buffers = None;
+    }
+
+    pub fn update(&mut self, delta_secs: f32) {
+        self.emitter.update(delta_secs);
+
+        self.number_of_particles = self.emitter.particles().len();
+        if self.number_of_particles == 0 {
+            return;
+        }
+
+        if self.buffers.is_none() || self.number_of_particles > self.positions.len() {
+            let capacity = self
+                .number_of_particles
+                .next_power_of_two()
+                .max(MIN_PARTICLE_CAPACITY);
+            self.positions = vec![Vec3::zero(); capacity];
+            self.sizes = vec![0.0; capacity];
+            self.colors = vec![Vec4::zero(); capacity];
+            self.buffers = Some(self.create_buffers());
+        }
+
+        // the particles are simulated in the space of the emitter, but drawn in world space
+        for (index, particle) in self.emitter.particles().iter().enumerate() {
+            self.positions[index] = self.object_matrix.mul_point(particle.position);
+            self.sizes[index] = particle.size;
+            self.colors[index] = particle.color;
+        }
+
+        if let Some(buffers) = &mut self.buffers {
+            buffers
+                .positions_vbo
+                .update_from_pointer(0, self.number_of_particles);
+            buffers
+                .sizes_vbo
+                .update_from_pointer(0, self.number_of_particles);
+            buffers
+                .colors_vbo
+                .update_from_pointer(0, self.number_of_particles);
+        }
+    }
+
+    pub fn draw(&self, parameters: &DrawParameters) {
+        let buffers = match &self.buffers {
+            Some(buffers) if self.number_of_particles > 0 => buffers,
+            _ => return,
+        };
+
+        self.gl_shader_program.shader_program.use_program();
+
+        let albedo_texture = self
+            .gl_material
+            .textures
+            .iter()
+            .filter(|texture| texture.texture_type == MaterialTextureType::Albedo)
+            .min_by_key(|texture| texture.uv_channel_id);
+        if let Some(albedo_texture) = albedo_texture {
+            albedo_texture.texture.use_texture(ALBEDO_TEXTURE_LAYER);
+            albedo_texture
+                .texture
+                .set_texture_map_mode(albedo_texture.texture_map_mode);
+            albedo_texture
+                .texture
+                .set_texture_sampling_mode(albedo_texture.sampling_mode);
+            albedo_texture
+                .texture
+                .set_texture_anisotropy_mode(albedo_texture.anisotropy_mode);
+
+            if let Some(uniform) = &self.uniforms.albedo_texture {
+                uniform.send_uniform_1i(ALBEDO_TEXTURE_LAYER as i32);
+            }
+        }
+
+        if let Some(uniform) = &self.uniforms.use_albedo_texture {
+            uniform.send_uniform_1i(albedo_texture.is_some() as i32);
+        }
+
+        if let Some(uniform) = &self.uniforms.albedo_color {
+            uniform.send_uniform_3fv(self.gl_material.albedo_color.as_slice(), 1);
+        }
+
+        if let Some(uniform) = &self.uniforms.opacity {
+            uniform.send_uniform_1f(self.gl_material.opacity);
+        }
+
+        set_blend_mode(self.gl_material.blend_mode);
+
+        buffers.vertex_array_object.use_vao(|| unsafe {
+            gl::DrawArraysInstanced(
+                gl::TRIANGLE_STRIP,
+                0,
+                QUAD_CORNERS.len() as i32,
+                self.number_of_particles as i32,
+            );
+        });
+        parameters
+            .draw_stats
+            .add_draw_call(self.number_of_particles * 2);
+
+        with_gl_state_cache(|cache| cache.restore_depth_mask());
+    }
+
+    fn gather_uniforms(gl_shader_program: &GLShaderProgram) -> GLParticleUniforms {
+        let shader_program = &gl_shader_program.shader_program;
+        shader_program.bind_uniform_block(FRAME_UNIFORMS_BLOCK_NAME, FRAME_UNIFORMS_BINDING_POINT);
+
+        GLParticleUniforms {
+            use_albedo_texture: shader_program.get_uniform_by_name("useAlbedoTexture"),
+            albedo_texture: shader_program.get_uniform_by_name("albedoTexture"),
+            albedo_color: shader_program.get_uniform_by_name("albedoColor"),
+            opacity: shader_program.get_uniform_by_name("opacity"),
+        }
+    }
+
+    fn create_buffers(&self) -> GLParticleBuffers {
+        let corners_vbo = VertexBufferObject::new(
+            QUAD_CORNERS.as_ptr(),
+            QUAD_CORNERS.len(),
+            DataType::F32,
+            DataCount::Coords2,
+        );
+        let positions_vbo = VertexBufferObject::new_dynamic(
+            self.positions.as_ptr(),
+            self.positions.len(),
+            DataType::F32,
+            DataCount::Coords3,
+        );
+        let sizes_vbo = VertexBufferObject::new_dynamic(
+            self.sizes.as_ptr(),
+            self.sizes.len(),
+            DataType::F32,
+            DataCount::Single,
+        );
+        let colors_vbo = VertexBufferObject::new_dynamic(
+            self.colors.as_ptr(),
+            self.colors.len(),
+            DataType::F32,
+            DataCount::Rgba,
+        );
+
+        let shader_program = &self.gl_shader_program.shader_program;
+        let vertex_array_object = VertexArrayObject::new(|vao_interface| {
+            if let Some(attrib) = shader_program.get_attribute_by_name("corner") {
+                vao_interface.bind_vbo_to_shader_attrib(&corners_vbo, &attrib);
+            }
+
+            if let Some(attrib) = shader_program.get_attribute_by_name("particlePosition") {
+                vao_interface.bind_instance_vbo_to_shader_attrib(&positions_vbo, &attrib);
+            }
+
+            if let Some(attrib) = shader_program.get_attribute_by_name("particleSize") {
+                vao_interface.bind_instance_vbo_to_shader_attrib(&sizes_vbo, &attrib);
+            }
+
+            if let Some(attrib) = shader_program.get_attribute_by_name("particleColor") {
+                vao_interface.bind_instance_vbo_to_shader_attrib(&colors_vbo, &attrib);
+            }
+        });
+
+        GLParticleBuffers {
+            _corners_vbo: corners_vbo,
+            positions_vbo,
+            sizes_vbo,
+            colors_vbo,
+            vertex_array_object,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::cell::RefCell;
+
+use crate::opengl_utils::{glsl_version::GLSLVersion, time_elapsed_query::TimeElapsedQuery};
+
+// the results of a frame are read when its queries are about to be reused,
+// so reading them does not stall the cpu until the gpu catches up
+const NUMBER_OF_FRAMES_IN_FLIGHT: usize = 3;
+
+pub struct GLPipelineStepTimer {
+    is_supported: bool,
+    // the queries of the frames in flight, one for every pipeline step
+    frames: RefCell<Vec<Vec<TimeElapsedQuery>>>,
+    current_frame: usize,
+    gpu_times_ms: Vec<Option<f32>>,
+}
+
+impl GLPipelineStepTimer {
+    // opengl es only has the time elapsed queries through an extension
+    pub fn new(glsl_version: GLSLVersion) -> Self {
+        Self {
+            is_supported: !glsl_version.is_es() && TimeElapsedQuery::is_supported(),
+            frames: RefCell::new(
+                (0..NUMBER_OF_FRAMES_IN_FLIGHT)
+                    .map(|_| Vec::new())
+                    .collect(),
+            ),
+            current_frame: 0,
+            gpu_times_ms: Vec::new(),
+        }
+    }
+
+    pub fn begin_frame(&mut self, number_of_steps: usize) {
+        if !self.is_supported {
+            return;
+        }
+
+        self.current_frame = (self.current_frame + 1) % NUMBER_OF_FRAMES_IN_FLIGHT;
+
+        let mut frames = self.frames.borrow_mut();
+        let queries = &mut frames[self.current_frame];
+
+        self.gpu_times_ms.resize(number_of_steps, None);
+        for (gpu_time_ms, query) in self.gpu_times_ms.iter_mut().zip(queries.iter()) {
+            if let Some(elapsed_nanoseconds) = query.elapsed_nanoseconds() {
+                *gpu_time_ms = Some(elapsed_nanoseconds as f32 / 1_000_000.0);
+            }
+        }
+
+        queries.resize_with(number_of_steps, TimeElapsedQuery::new);
+    }
+
+    // the indices of the steps are changed by a new pipeline
+    pub fn reset(&mut self) {
+        for queries in self.frames.borrow_mut().iter_mut() {
+            queries.clear();
+        }
+        self.gpu_times_ms.clear();
+    }
+
+    pub fn begin_step(&self, step_index: usize) {
+        if let Some(query) = self.frames.borrow_mut()[self.current_frame].get_mut(step_index) {
+            query.begin();
+        }
+    }
+
+    pub fn end_step(&self, step_index: usize) {
+        if let Some(query) = self.frames.borrow_mut()[self.current_frame].get_mut(step_index) {
+            query.end();
+        }
+    }
+
+    // the times are from NUMBER_OF_FRAMES_IN_FLIGHT frames earlier,
+    // the steps that were not measured yet are None
+    pub fn gpu_times_ms(&self) -> &[Option<f32>] {
+        &self.gpu_times_ms
+    }
+}
@@ -3,6 +3,7 @@ use std::{io::Read, sync::Arc};
 use muleengine::asset_reader::AssetReader;
 
 use super::opengl_utils::{
+    glsl_version::GLSLVersion,
     shader::{Shader, ShaderCreationError, ShaderType},
     shader_program::{ShaderProgram, ShaderProgramError},
 };
@@ -33,36 +34,59 @@ pub enum GLShaderProgramError {
     ShaderProgramError(ShaderProgramError),
 }
 
+// the variant of the shader that is written for the glsl version is preferred,
+// the returned source is rewritten to the glsl version
+fn read_shader_source(
+    shader_base_path: &str,
+    extension: &str,
+    glsl_version: GLSLVersion,
+    asset_reader: &AssetReader,
+) -> Result<(String, String), GLShaderProgramError> {
+    let variant_reader = glsl_version.variant_suffix().and_then(|variant_suffix| {
+        let variant_path = format!("{shader_base_path}{variant_suffix}{extension}");
+        asset_reader
+            .get_reader(variant_path.clone())
+            .map(|reader| (variant_path, reader))
+    });
+
+    let (shader_path, mut reader) = match variant_reader {
+        Some(variant_reader) => variant_reader,
+        None => {
+            let shader_path = format!("{shader_base_path}{extension}");
+            let reader = asset_reader
+                .get_reader(shader_path.clone())
+                .ok_or_else(|| GLShaderProgramError::AssetNotFoundError {
+                    path: shader_path.clone(),
+                })?;
+
+            (shader_path, reader)
+        }
+    };
+
+    let mut shader_source = String::new();
+    reader.read_to_string(&mut shader_source).map_err(|e| {
+        GLShaderProgramError::AssetReadError {
+            error: e,
+            path: shader_path.clone(),
+        }
+    })?;
+
+    Ok((
+        shader_path,
+        glsl_version.rewrite_shader_source(&shader_source),
+    ))
+}
+
 impl GLShaderProgram {
     pub fn new(
         shader_base_path: String,
+        glsl_version: GLSLVersion,
         asset_reader: &AssetReader,
     ) -> Result<Self, GLShaderProgramError> {
-        let vertex_shader_path = shader_base_path.clone() + ".vert";
-        let fragment_shader_path = shader_base_path.clone() + ".frag";
-
-        let mut vertex_shader_source = String::new();
-        asset_reader
-            .get_reader(&vertex_shader_path)
-            .ok_or(GLShaderProgramError::AssetNotFoundError {
-                path: vertex_shader_path.clone(),
-            })?
-            .read_to_string(&mut vertex_shader_source)
-            .map_err(|e| GLShaderProgramError::AssetReadError {
-                error: e,
-                path: vertex_shader_source.clone(),
-            })?;
-        let mut fragment_shader_source = String::new();
-        asset_reader
-            .get_reader(&fragment_shader_path)
-            .ok_or(GLShaderProgramError::AssetNotFoundError {
-                path: fragment_shader_path.clone(),
-            })?
-            .read_to_string(&mut fragment_shader_source)
-            .map_err(|e| GLShaderProgramError::AssetReadError {
-                error: e,
-                path: fragment_shader_source.clone(),
-            })?;
+        let (vertex_shader_path, vertex_shader_source) =
+            read_shader_source(&shader_base_path, ".vert", glsl_version, asset_reader)?;
+        let (fragment_shader_path, fragment_shader_source) =
+            read_shader_source(&shader_base_path, ".frag", glsl_version, asset_reader)?;
 
         let vertex_shader =
             Shader::new(ShaderType::Vertex, &vertex_shader_source).map_err(|e| {
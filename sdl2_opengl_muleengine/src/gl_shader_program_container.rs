@@ -3,24 +3,34 @@ use std::sync::Arc;
 
 use muleengine::asset_reader::AssetReader;
 
-use crate::gl_shader_program::{GLShaderProgram, GLShaderProgramError};
+use crate::{
+    gl_shader_program::{GLShaderProgram, GLShaderProgramError},
+    opengl_utils::glsl_version::GLSLVersion,
+};
 
 use super::gl_mesh_shader_program::GLMeshShaderProgram;
 
+pub struct ReloadedShaderProgram {
+    pub old_shader_program: Arc<GLShaderProgram>,
+    pub new_shader_program: Arc<GLShaderProgram>,
+}
+
 pub struct GLShaderProgramContainer {
+    glsl_version: GLSLVersion,
     shader_programs: HashMap<String, Arc<GLShaderProgram>>,
     mesh_shader_programs: HashMap<*const GLShaderProgram, Arc<GLMeshShaderProgram>>,
 }
 
 impl Default for GLShaderProgramContainer {
     fn default() -> Self {
-        Self::new()
+        Self::new(GLSLVersion::Desktop400)
     }
 }
 
 impl GLShaderProgramContainer {
-    pub fn new() -> Self {
+    pub fn new(glsl_version: GLSLVersion) -> Self {
         Self {
+            glsl_version,
             shader_programs: HashMap::new(),
             mesh_shader_programs: HashMap::new(),
         }
@@ -36,6 +46,7 @@ impl GLShaderProgramContainer {
         } else {
             let shader_program = Arc::new(GLShaderProgram::new(
                 shader_basepath.to_string(),
+                self.glsl_version,
                 asset_reader,
             )?);
             self.shader_programs
@@ -45,6 +56,52 @@ impl GLShaderProgramContainer {
         }
     }
 
+    // a program that fails to compile is kept, so whatever uses it can still be drawn
+    pub fn reload_shader_programs(
+        &mut self,
+        asset_reader: &AssetReader,
+    ) -> Vec<Result<ReloadedShaderProgram, GLShaderProgramError>> {
+        let mut results = Vec::new();
+
+        for (shader_basepath, shader_program) in self.shader_programs.iter_mut() {
+            match GLShaderProgram::new(shader_basepath.clone(), self.glsl_version, asset_reader) {
+                Ok(new_shader_program) => {
+                    let old_shader_program =
+                        std::mem::replace(shader_program, Arc::new(new_shader_program));
+
+                    // the address of the old program can be reused by a new one
+                    self.mesh_shader_programs
+                        .remove(&Arc::as_ptr(&old_shader_program));
+
+                    results.push(Ok(ReloadedShaderProgram {
+                        old_shader_program,
+                        new_shader_program: shader_program.clone(),
+                    }));
+                }
+                Err(e) => results.push(Err(e)),
+            }
+        }
+
+        results
+    }
+
+    pub fn glsl_version(&self) -> GLSLVersion {
+        self.glsl_version
+    }
+
+    // the mesh shader programs hold their shader programs, so they are released first,
+    // a program that is released is compiled again when it is requested next time
+    pub fn release_unused_shader_programs(&mut self) -> usize {
+        self.mesh_shader_programs
+            .retain(|_, mesh_shader_program| Arc::strong_count(mesh_shader_program) > 1);
+
+        let number_of_shader_programs = self.shader_programs.len();
+        self.shader_programs
+            .retain(|_, shader_program| Arc::strong_count(shader_program) > 1);
+
+        number_of_shader_programs - self.shader_programs.len()
+    }
+
     pub fn get_mesh_shader_program(
         &mut self,
         gl_shader_program: Arc<GLShaderProgram>,
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use muleengine::{mesh::MaterialBlendMode, renderer::renderer_sprite::RendererSprite};
+use vek::{Mat4, Transform, Vec4};
+
+use super::{
+    gl_drawable_mesh::{set_blend_mode, DrawParameters},
+    gl_frame_uniforms::{FRAME_UNIFORMS_BINDING_POINT, FRAME_UNIFORMS_BLOCK_NAME},
+    gl_shader_program::GLShaderProgram,
+    opengl_utils::{
+        gl_state_cache::with_gl_state_cache,
+        shader_input::ShaderUniform,
+        texture_2d::{GLTextureMapMode, Texture2D},
+        vertex_array_object::VertexArrayObject,
+        vertex_buffer_object::{DataCount, DataType, VertexBufferObject},
+    },
+};
+
+const SPRITE_TEXTURE_LAYER: usize = 0;
+
+// the corners of the unit quad in the order of a triangle strip, starting from the bottom left one
+static QUAD_CORNERS: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]];
+
+struct GLSpriteBuffers {
+    _corners_vbo: VertexBufferObject,
+    _rects_vbo: VertexBufferObject,
+    _uv_rects_vbo: VertexBufferObject,
+    _colors_vbo: VertexBufferObject,
+    vertex_array_object: VertexArrayObject,
+}
+
+struct GLSpriteUniforms {
+    object_matrix: Option<ShaderUniform>,
+    sprite_texture: Option<ShaderUniform>,
+}
+
+pub struct GLSpriteBatch {
+    object_matrix: Mat4<f32>,
+    texture: Arc<Texture2D>,
+
+    // the vbos keep pointers to the vectors, so they are only replaced together with the vbos
+    rects: Vec<Vec4<f32>>,
+    uv_rects: Vec<Vec4<f32>>,
+    colors: Vec<Vec4<f32>>,
+    buffers: Option<GLSpriteBuffers>,
+
+    gl_shader_program: Arc<GLShaderProgram>,
+    uniforms: GLSpriteUniforms,
+}
+
+impl GLSpriteBatch {
+    pub fn new(
+        texture: Arc<Texture2D>,
+        sprites: &[RendererSprite],
+        transform: Transform<f32, f32, f32>,
+        gl_shader_program: Arc<GLShaderProgram>,
+    ) -> Self {
+        let uniforms = Self::gather_uniforms(&gl_shader_program);
+
+        let mut sprite_batch = Self {
+            object_matrix: transform.into(),
+            texture,
+
+            rects: Vec::new(),
+            uv_rects: Vec::new(),
+            colors: Vec::new(),
+            buffers: None,
+
+            gl_shader_program,
+            uniforms,
+        };
+        sprite_batch.set_sprites(sprites);
+
+        sprite_batch
+    }
+
+    // the sprites rarely change, so the vbos are recreated with the exact size of the batch
+    pub fn set_sprites(&mut self, sprites: &[RendererSprite]) {
+        self.rects = sprites
+            .iter()
+            .map(|sprite| {
+                Vec4::new(
+                    sprite.position.x,
+                    sprite.position.y,
+                    sprite.size.x,
+                    sprite.size.y,
+                )
+            })
+            .collect();
+        self.uv_rects = sprites
+            .iter()
+            .map(|sprite| {
+                Vec4::new(
+                    sprite.uv_rect.x,
+                    sprite.uv_rect.y,
+                    sprite.uv_rect.w,
+                    sprite.uv_rect.h,
+                )
+            })
+            .collect();
+        self.colors = sprites.iter().map(|sprite| sprite.color).collect();
+
+        self.buffers = self.create_buffers();
+    }
+
+    pub fn set_transform(&mut self, transform: &Transform<f32, f32, f32>) {
+        self.object_matrix = (*transform).into();
+    }
+
+    // the vertex array object is bound to the attributes of the old shader program
+    pub fn set_gl_shader_program(&mut self, gl_shader_program: Arc<GLShaderProgram>) {
+        self.uniforms = Self::gather_uniforms(&gl_shader_program);
+        self.gl_shader_program = gl_shader_program;
+        self.buffers = self.create_buffers();
+    }
+
+    pub fn draw(&self, parameters: &DrawParameters) {
+        let buffers = match &self.buffers {
+            Some(buffers) => buffers,
+            None => return,
+        };
+
+        self.gl_shader_program.shader_program.use_program();
+
+        if let Some(uniform) = &self.uniforms.object_matrix {
+            uniform.send_uniform_matrix_4fv(self.object_matrix.as_col_slice(), 1);
+        }
+
+        self.texture.use_texture(SPRITE_TEXTURE_LAYER);
+        self.texture.set_texture_map_mode(GLTextureMapMode::Clamp);
+        if let Some(uniform) = &self.uniforms.sprite_texture {
+            uniform.send_uniform_1i(SPRITE_TEXTURE_LAYER as i32);
+        }
+
+        set_blend_mode(MaterialBlendMode::AlphaBlend);
+
+        buffers.vertex_array_object.use_vao(|| unsafe {
+            gl::DrawArraysInstanced(
+                gl::TRIANGLE_STRIP,
+                0,
+                QUAD_CORNERS.len() as i32,
+                self.rects.len() as i32,
+            );
+        });
+        parameters.draw_stats.add_draw_call(self.rects.len() * 2);
+
+        with_gl_state_cache(|cache| cache.restore_depth_mask());
+    }
+
+    fn gather_uniforms(gl_shader_program: &GLShaderProgram) -> GLSpriteUniforms {
+        let shader_program = &gl_shader_program.shader_program;
+        shader_program.bind_uniform_block(FRAME_UNIFORMS_BLOCK_NAME, FRAME_UNIFORMS_BINDING_POINT);
+
+        GLSpriteUniforms {
+            object_matrix: shader_program.get_uniform_by_name("objectMatrix"),
+            sprite_texture: shader_program.get_uniform_by_name("spriteTexture"),
+        }
+    }
+
+    fn create_buffers(&self) -> Option<GLSpriteBuffers> {
+        if self.rects.is_empty() {
+            return None;
+        }
+
+        let corners_vbo = VertexBufferObject::new(
+            QUAD_CORNERS.as_ptr(),
+            QUAD_CORNERS.len(),
+            DataType::F32,
+            DataCount::Coords2,
+        );
+        let rects_vbo = VertexBufferObject::new(
+            self.rects.as_ptr(),
+            self.rects.len(),
+            DataType::F32,
+            DataCount::Rgba,
+        );
+        let uv_rects_vbo = VertexBufferObject::new(
+            self.uv_rects.as_ptr(),
+            self.uv_rects.len(),
+            DataType::F32,
+            DataCount::Rgba,
+        );
+        let colors_vbo = VertexBufferObject::new(
+            self.colors.as_ptr(),
+            self.colors.len(),
+            DataType::F32,
+            DataCount::Rgba,
+        );
+
+        let shader_program = &self.gl_shader_program.shader_program;
+        let vertex_array_object = VertexArrayObject::new(|vao_interface| {
+            if let Some(attrib) = shader_program.get_attribute_by_name("corner") {
+                vao_interface.bind_vbo_to_shader_attrib(&corners_vbo, &attrib);
+            }
+
+            if let Some(attrib) = shader_program.get_attribute_by_name("spriteRect") {
+                vao_interface.bind_instance_vbo_to_shader_attrib(&rects_vbo, &attrib);
+            }
+
+            if let Some(attrib) = shader_program.get_attribute_by_name("spriteUvRect") {
+                vao_interface.bind_instance_vbo_to_shader_attrib(&uv_rects_vbo, &attrib);
+            }
+
+            if let Some(attrib) = shader_program.get_attribute_by_name("spriteColor") {
+                vao_interface.bind_instance_vbo_to_shader_attrib(&colors_vbo, &attrib);
+            }
+        });
+
+        Some(GLSpriteBuffers {
+            _corners_vbo: corners_vbo,
+            _rects_vbo: rects_vbo,
+            _uv_rects_vbo: uv_rects_vbo,
+            _colors_vbo: colors_vbo,
+            vertex_array_object,
+        })
+    }
+}
@@ -1,12 +1,24 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use muleengine::image::Image;
+use muleengine::{bytifex_utils::sync::types::RcRwLock, image::Image};
 
-use super::opengl_utils::texture_2d::Texture2D;
+use super::{gl_upload_queue::GLUploadQueue, opengl_utils::texture_2d::Texture2D};
+
+struct GLTextureEntry {
+    _image: Arc<Image>,
+    texture: Arc<Texture2D>,
+    size_in_bytes: usize,
+    last_used: u64,
+}
 
 pub struct GLTextureContainer {
-    textures_2d: HashMap<*const Image, (Arc<Image>, Arc<Texture2D>)>,
+    textures_2d: HashMap<*const Image, GLTextureEntry>,
+    usage_counter: u64,
+    texture_memory_bytes: usize,
+    texture_memory_budget_bytes: Option<usize>,
+    number_of_evicted_textures: usize,
+    upload_queue: Option<RcRwLock<GLUploadQueue>>,
 }
 
 impl Default for GLTextureContainer {
@@ -19,14 +31,121 @@ impl GLTextureContainer {
     pub fn new() -> Self {
         Self {
             textures_2d: HashMap::new(),
+            usage_counter: 0,
+            texture_memory_bytes: 0,
+            texture_memory_budget_bytes: None,
+            number_of_evicted_textures: 0,
+            upload_queue: None,
         }
     }
 
+    // with an upload queue the images of the new textures are uploaded on another thread
+    pub fn set_upload_queue(&mut self, upload_queue: Option<RcRwLock<GLUploadQueue>>) {
+        self.upload_queue = upload_queue;
+    }
+
     pub fn get_texture(&mut self, image: Arc<Image>) -> Arc<Texture2D> {
-        self.textures_2d
-            .entry(&*image)
-            .or_insert_with(|| (image.clone(), Arc::new(Texture2D::new(image))))
-            .1
-            .clone()
+        self.usage_counter += 1;
+        let usage_counter = self.usage_counter;
+
+        let upload_queue = &self.upload_queue;
+        let entry = self.textures_2d.entry(&*image).or_insert_with(|| {
+            // mipmaps add roughly one third to the size of the base level,
+            // the compressed images contain their mipmaps
+            let size_in_bytes = match image.compressed() {
+                Some(compressed) => compressed.size_in_bytes(),
+                None => image.as_bytes().len() * 4 / 3,
+            };
+            self.texture_memory_bytes += size_in_bytes;
+
+            GLTextureEntry {
+                _image: image.clone(),
+                texture: match upload_queue {
+                    Some(upload_queue) => {
+                        let texture = Texture2D::new_deferred();
+                        upload_queue.write().upload_texture(&texture, image);
+                        Arc::new(texture)
+                    }
+                    None => Arc::new(Texture2D::new(image)),
+                },
+                size_in_bytes,
+                last_used: usage_counter,
+            }
+        });
+        entry.last_used = usage_counter;
+
+        let texture = entry.texture.clone();
+
+        self.evict_unused_textures();
+
+        texture
+    }
+
+    pub fn number_of_textures(&self) -> usize {
+        self.textures_2d.len()
+    }
+
+    pub fn texture_memory_bytes(&self) -> usize {
+        self.texture_memory_bytes
+    }
+
+    pub fn texture_memory_budget_bytes(&self) -> Option<usize> {
+        self.texture_memory_budget_bytes
+    }
+
+    pub fn number_of_evicted_textures(&self) -> usize {
+        self.number_of_evicted_textures
+    }
+
+    pub fn set_texture_memory_budget(&mut self, budget_bytes: Option<usize>) {
+        self.texture_memory_budget_bytes = budget_bytes;
+        self.evict_unused_textures();
+    }
+
+    // without a budget the unused textures are released right away,
+    // with a budget they are kept until the budget is exceeded, so they can be reused
+    pub fn evict_unused_textures(&mut self) {
+        let budget_bytes = match self.texture_memory_budget_bytes {
+            Some(budget_bytes) if self.texture_memory_bytes > budget_bytes => budget_bytes,
+            Some(_) => return,
+            None => {
+                self.release_unused_textures();
+                return;
+            }
+        };
+
+        // a texture is unused if the container holds the only reference to it
+        let mut unused_textures = self
+            .textures_2d
+            .iter()
+            .filter(|(_, entry)| {
+                Arc::strong_count(&entry.texture) == 1 && entry.texture.is_uploaded()
+            })
+            .map(|(key, entry)| (entry.last_used, *key))
+            .collect::<Vec<_>>();
+        unused_textures.sort();
+
+        for (_, key) in unused_textures {
+            if self.texture_memory_bytes <= budget_bytes {
+                break;
+            }
+
+            if let Some(entry) = self.textures_2d.remove(&key) {
+                self.texture_memory_bytes -= entry.size_in_bytes;
+                self.number_of_evicted_textures += 1;
+            }
+        }
+    }
+
+    fn release_unused_textures(&mut self) {
+        let texture_memory_bytes = &mut self.texture_memory_bytes;
+        self.textures_2d.retain(|_, entry| {
+            // the textures that are still uploaded are kept like the used ones
+            let is_used = Arc::strong_count(&entry.texture) > 1 || !entry.texture.is_uploaded();
+            if !is_used {
+                *texture_memory_bytes -= entry.size_in_bytes;
+            }
+            is_used
+        });
     }
 }
@@ -0,0 +1,208 @@
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+use gl::types::{GLsync, GLuint};
+
+use muleengine::image::Image;
+
+use super::{
+    gl_mesh::GLMesh,
+    opengl_utils::{deferred_buffer_upload::DeferredBufferUpload, texture_2d::Texture2D},
+    sdl2_gl_context::SharedGLContext,
+};
+
+enum GLUploadJob {
+    Buffers {
+        deferred_uploads: Vec<DeferredBufferUpload>,
+        _data_owners: Vec<Arc<dyn Any + Send + Sync>>,
+        uploaded: Arc<AtomicBool>,
+    },
+    Texture {
+        texture_id: GLuint,
+        image: Arc<Image>,
+        uploaded: Arc<AtomicBool>,
+    },
+}
+
+// the data pointers of the deferred uploads point into the data owners of the same job
+unsafe impl Send for GLUploadJob {}
+
+struct GLUploadFence {
+    fence: GLsync,
+    uploaded: Arc<AtomicBool>,
+}
+
+// the sync objects are shared between the contexts
+unsafe impl Send for GLUploadFence {}
+
+// uploads the data of the meshes and the textures on a thread with a shared gl context,
+// so creating them does not stall the render thread, the uploaded flag of an object is set
+// by poll_completed_uploads on the render thread after the gpu finished the upload
+pub struct GLUploadQueue {
+    job_sender: Option<Sender<GLUploadJob>>,
+    fence_receiver: Receiver<GLUploadFence>,
+    pending_fences: Vec<GLUploadFence>,
+    number_of_pending_uploads: usize,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl GLUploadQueue {
+    pub fn new(shared_gl_context: SharedGLContext) -> Result<Self, String> {
+        let (job_sender, job_receiver) = channel::<GLUploadJob>();
+        let (fence_sender, fence_receiver) = channel();
+        let (started_sender, started_receiver) = channel();
+
+        let worker = std::thread::Builder::new()
+            .name("gl_upload_queue".to_string())
+            .spawn(move || {
+                let started = shared_gl_context.make_current();
+                let is_started = started.is_ok();
+                let _ = started_sender.send(started);
+                if !is_started {
+                    return;
+                }
+
+                while let Ok(job) = job_receiver.recv() {
+                    let uploaded = match job {
+                        GLUploadJob::Buffers {
+                            deferred_uploads,
+                            uploaded,
+                            ..
+                        } => {
+                            for deferred_upload in deferred_uploads.iter() {
+                                deferred_upload.execute();
+                            }
+                            uploaded
+                        }
+                        GLUploadJob::Texture {
+                            texture_id,
+                            image,
+                            uploaded,
+                        } => {
+                            Texture2D::upload_deferred(texture_id, &image);
+                            uploaded
+                        }
+                    };
+
+                    // the commands have to reach the gpu before the render thread waits for the fence
+                    let fence = unsafe {
+                        let fence = gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+                        gl::Flush();
+                        fence
+                    };
+
+                    if fence_sender
+                        .send(GLUploadFence { fence, uploaded })
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+
+                shared_gl_context.release_current();
+            })
+            .map_err(|e| format!("Creating upload queue, msg = {e}"))?;
+
+        started_receiver
+            .recv()
+            .map_err(|e| format!("Creating upload queue, msg = {e}"))?
+            .map_err(|e| format!("Creating upload queue, msg = {e}"))?;
+
+        Ok(Self {
+            job_sender: Some(job_sender),
+            fence_receiver,
+            pending_fences: Vec::new(),
+            number_of_pending_uploads: 0,
+            worker: Some(worker),
+        })
+    }
+
+    pub fn upload_mesh(&mut self, gl_mesh: &GLMesh) {
+        self.send_job(GLUploadJob::Buffers {
+            deferred_uploads: gl_mesh.deferred_uploads(),
+            _data_owners: gl_mesh.deferred_upload_data_owners(),
+            uploaded: gl_mesh.uploaded_flag(),
+        });
+    }
+
+    pub fn upload_texture(&mut self, texture: &Texture2D, image: Arc<Image>) {
+        self.send_job(GLUploadJob::Texture {
+            texture_id: texture.texture_id(),
+            image,
+            uploaded: texture.uploaded_flag(),
+        });
+    }
+
+    fn send_job(&mut self, job: GLUploadJob) {
+        if let Some(job_sender) = &self.job_sender {
+            if job_sender.send(job).is_ok() {
+                self.number_of_pending_uploads += 1;
+            } else {
+                log::error!("Sending upload job, msg = the upload thread stopped");
+            }
+        }
+    }
+
+    // does not block, the uploads that are still executed by the gpu are checked in the next frame
+    pub fn poll_completed_uploads(&mut self) {
+        while let Ok(upload_fence) = self.fence_receiver.try_recv() {
+            self.pending_fences.push(upload_fence);
+        }
+
+        let number_of_pending_uploads = &mut self.number_of_pending_uploads;
+        self.pending_fences.retain(|upload_fence| {
+            let result = unsafe { gl::ClientWaitSync(upload_fence.fence, 0, 0) };
+            match result {
+                gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => {
+                    unsafe {
+                        gl::DeleteSync(upload_fence.fence);
+                    }
+                    upload_fence.uploaded.store(true, Ordering::Release);
+                    *number_of_pending_uploads -= 1;
+                    false
+                }
+                // the object is drawn anyway, otherwise it would be hidden forever
+                gl::WAIT_FAILED => {
+                    log::error!("Waiting for upload, msg = could not wait for the fence");
+                    unsafe {
+                        gl::DeleteSync(upload_fence.fence);
+                    }
+                    upload_fence.uploaded.store(true, Ordering::Release);
+                    *number_of_pending_uploads -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+
+    pub fn number_of_pending_uploads(&self) -> usize {
+        self.number_of_pending_uploads
+    }
+}
+
+impl Drop for GLUploadQueue {
+    fn drop(&mut self) {
+        // the worker stops after the queued jobs are executed
+        self.job_sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+
+        while let Ok(upload_fence) = self.fence_receiver.try_recv() {
+            self.pending_fences.push(upload_fence);
+        }
+        for upload_fence in self.pending_fences.drain(..) {
+            unsafe {
+                gl::DeleteSync(upload_fence.fence);
+            }
+        }
+    }
+}
@@ -6,15 +6,23 @@
     clippy::collapsible_if
 )]
 
+pub mod gl_debug_lines;
+pub mod gl_draw_stats;
 pub mod gl_drawable_mesh;
+pub mod gl_frame_uniforms;
 pub mod gl_material;
 pub mod gl_mesh;
 pub mod gl_mesh_container;
 pub mod gl_mesh_shader_program;
+pub mod gl_occlusion_culler;
+pub mod gl_particle_emitter;
+pub mod gl_pipeline_step_timer;
 pub mod gl_scene;
 pub mod gl_shader_program;
 pub mod gl_shader_program_container;
+pub mod gl_sprite_batch;
 pub mod gl_texture_container;
+pub mod gl_upload_queue;
 pub mod me_renderer_indices;
 pub mod opengl_utils;
 pub mod sdl2_gl_context;
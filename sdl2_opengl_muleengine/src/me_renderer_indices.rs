@@ -2,7 +2,8 @@ use muleengine::{
     bytifex_utils::containers::object_pool::ObjectPoolIndex,
     renderer::{
         RendererCamera, RendererGroup, RendererLayer, RendererMaterial, RendererMesh,
-        RendererObject, RendererShader, RendererTransform,
+        RendererObject, RendererProbe, RendererShader, RendererTexture, RendererTransform,
+        RendererWindow,
     },
 };
 
@@ -21,9 +22,23 @@ pub struct RendererMeshIndex(pub(super) ObjectPoolIndex);
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum RendererObjectIndex {
     Mesh(ObjectPoolIndex),
+    Instanced(ObjectPoolIndex),
+    Skybox(ObjectPoolIndex),
+    ParticleEmitter(ObjectPoolIndex),
+    SpriteBatch(ObjectPoolIndex),
 }
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct RendererCameraIndex(pub(super) ObjectPoolIndex);
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct RendererProbeIndex(pub(super) ObjectPoolIndex);
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum RendererTextureIndex {
+    RenderTexture(ObjectPoolIndex),
+    Image(ObjectPoolIndex),
+    Cubemap(ObjectPoolIndex),
+}
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct RendererWindowIndex(pub(super) ObjectPoolIndex);
 
 impl RendererLayer for RendererLayerIndex {}
 impl RendererGroup for RendererGroupIndex {}
@@ -33,3 +48,6 @@ impl RendererShader for RendererShaderIndex {}
 impl RendererMesh for RendererMeshIndex {}
 impl RendererObject for RendererObjectIndex {}
 impl RendererCamera for RendererCameraIndex {}
+impl RendererProbe for RendererProbeIndex {}
+impl RendererTexture for RendererTextureIndex {}
+impl RendererWindow for RendererWindowIndex {}
@@ -0,0 +1,38 @@
+use std::ffi::c_void;
+
+use gl::types::GLuint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferUploadMode {
+    Immediate,
+    // the storage of the buffer is allocated, its data is uploaded later by an upload queue
+    Deferred,
+}
+
+// the data of a buffer that was created with BufferUploadMode::Deferred,
+// the owner of the data pointer has to keep it alive until the upload is executed
+pub struct DeferredBufferUpload {
+    pub(super) buffer_id: GLuint,
+    pub(super) data_pointer: *const c_void,
+    pub(super) size_in_bytes: usize,
+}
+
+impl DeferredBufferUpload {
+    // executed on the thread of a shared context, the copy write target is not part of any vao state
+    pub fn execute(&self) {
+        if self.size_in_bytes == 0 {
+            return;
+        }
+
+        unsafe {
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.buffer_id);
+            gl::BufferSubData(
+                gl::COPY_WRITE_BUFFER,
+                0,
+                self.size_in_bytes as isize,
+                self.data_pointer,
+            );
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, 0);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::ptr::null;
+
+use gl::types::GLuint;
+
+use super::gl_state_cache::with_gl_state_cache;
+
+pub struct DepthTexture2D {
+    texture_id: GLuint,
+    resolution: usize,
+}
+
+impl DepthTexture2D {
+    pub fn new(resolution: usize) -> Self {
+        let mut texture_id = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut texture_id);
+        }
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_2D, texture_id));
+
+        unsafe {
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::DEPTH_COMPONENT24 as i32,
+                resolution as i32,
+                resolution as i32,
+                0,
+                gl::DEPTH_COMPONENT,
+                gl::FLOAT,
+                null(),
+            );
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+
+            // everything outside of the shadow map is considered to be lit
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                gl::CLAMP_TO_BORDER as i32,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                gl::CLAMP_TO_BORDER as i32,
+            );
+            let border_color = [1.0f32, 1.0, 1.0, 1.0];
+            gl::TexParameterfv(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_BORDER_COLOR,
+                border_color.as_ptr(),
+            );
+        }
+
+        Self {
+            texture_id,
+            resolution,
+        }
+    }
+
+    pub fn texture_id(&self) -> GLuint {
+        self.texture_id
+    }
+
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    pub fn use_texture(&self, layer: usize) {
+        with_gl_state_cache(|cache| {
+            cache.bind_texture(layer as u32, gl::TEXTURE_2D, self.texture_id)
+        });
+    }
+}
+
+impl Drop for DepthTexture2D {
+    fn drop(&mut self) {
+        with_gl_state_cache(|cache| cache.forget_texture(self.texture_id));
+
+        unsafe {
+            gl::DeleteTextures(1, &self.texture_id);
+        }
+    }
+}
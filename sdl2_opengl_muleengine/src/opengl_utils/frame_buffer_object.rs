@@ -0,0 +1,328 @@
+use gl::types::{GLenum, GLuint};
+
+use super::depth_texture_2d::DepthTexture2D;
+
+pub struct FrameBufferObject {
+    frame_buffer_id: GLuint,
+    depth_render_buffer_id: GLuint,
+    width: usize,
+    height: usize,
+}
+
+impl FrameBufferObject {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut frame_buffer_id = 0;
+        let mut depth_render_buffer_id = 0;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut frame_buffer_id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, frame_buffer_id);
+
+            gl::GenRenderbuffers(1, &mut depth_render_buffer_id);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, depth_render_buffer_id);
+            gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH_COMPONENT24,
+                width as i32,
+                height as i32,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::RENDERBUFFER,
+                depth_render_buffer_id,
+            );
+
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        Self {
+            frame_buffer_id,
+            depth_render_buffer_id,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bind(&self, texture_target: GLenum, texture_id: GLuint) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.frame_buffer_id);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                texture_target,
+                texture_id,
+                0,
+            );
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+        }
+    }
+
+    pub fn use_fbo(&self, texture_target: GLenum, texture_id: GLuint, f: impl FnOnce()) {
+        let previous_frame_buffer_id = bound_frame_buffer_id();
+
+        self.bind(texture_target, texture_id);
+
+        f();
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous_frame_buffer_id);
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.frame_buffer_id);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            status == gl::FRAMEBUFFER_COMPLETE
+        }
+    }
+}
+
+impl Drop for FrameBufferObject {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteRenderbuffers(1, &self.depth_render_buffer_id);
+            gl::DeleteFramebuffers(1, &self.frame_buffer_id);
+        }
+    }
+}
+
+// draws into multisampled render buffers, which have to be resolved into a texture before sampling
+pub struct MultisampleFrameBufferObject {
+    frame_buffer_id: GLuint,
+    color_render_buffer_id: GLuint,
+    depth_render_buffer_id: GLuint,
+    width: usize,
+    height: usize,
+}
+
+impl MultisampleFrameBufferObject {
+    pub fn new(width: usize, height: usize, samples: usize) -> Self {
+        let mut frame_buffer_id = 0;
+        let mut color_render_buffer_id = 0;
+        let mut depth_render_buffer_id = 0;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut frame_buffer_id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, frame_buffer_id);
+
+            gl::GenRenderbuffers(1, &mut color_render_buffer_id);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, color_render_buffer_id);
+            gl::RenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                samples as i32,
+                gl::RGBA8,
+                width as i32,
+                height as i32,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::RENDERBUFFER,
+                color_render_buffer_id,
+            );
+
+            gl::GenRenderbuffers(1, &mut depth_render_buffer_id);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, depth_render_buffer_id);
+            gl::RenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                samples as i32,
+                gl::DEPTH_COMPONENT24,
+                width as i32,
+                height as i32,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::RENDERBUFFER,
+                depth_render_buffer_id,
+            );
+
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        Self {
+            frame_buffer_id,
+            color_render_buffer_id,
+            depth_render_buffer_id,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.frame_buffer_id);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+        }
+    }
+
+    pub fn use_fbo(&self, f: impl FnOnce()) {
+        let previous_frame_buffer_id = bound_frame_buffer_id();
+
+        self.bind();
+
+        f();
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous_frame_buffer_id);
+        }
+    }
+
+    // the target framebuffer stays bound after resolving
+    pub fn resolve_into(
+        &self,
+        frame_buffer_object: &FrameBufferObject,
+        texture_target: GLenum,
+        texture_id: GLuint,
+    ) {
+        frame_buffer_object.bind(texture_target, texture_id);
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.frame_buffer_id);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                0,
+                0,
+                frame_buffer_object.width() as i32,
+                frame_buffer_object.height() as i32,
+                gl::COLOR_BUFFER_BIT,
+                gl::NEAREST,
+            );
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, frame_buffer_object.frame_buffer_id);
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.frame_buffer_id);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            status == gl::FRAMEBUFFER_COMPLETE
+        }
+    }
+}
+
+impl Drop for MultisampleFrameBufferObject {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteRenderbuffers(1, &self.color_render_buffer_id);
+            gl::DeleteRenderbuffers(1, &self.depth_render_buffer_id);
+            gl::DeleteFramebuffers(1, &self.frame_buffer_id);
+        }
+    }
+}
+
+pub struct DepthFrameBufferObject {
+    frame_buffer_id: GLuint,
+    width: usize,
+    height: usize,
+}
+
+impl DepthFrameBufferObject {
+    pub fn new(depth_texture: &DepthTexture2D) -> Self {
+        let mut frame_buffer_id = 0;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut frame_buffer_id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, frame_buffer_id);
+
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::TEXTURE_2D,
+                depth_texture.texture_id(),
+                0,
+            );
+
+            // there is no color attachment, only the depth is written
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        Self {
+            frame_buffer_id,
+            width: depth_texture.resolution(),
+            height: depth_texture.resolution(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn use_fbo(&self, f: impl FnOnce()) {
+        let previous_frame_buffer_id = bound_frame_buffer_id();
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.frame_buffer_id);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+        }
+
+        f();
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous_frame_buffer_id);
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.frame_buffer_id);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            status == gl::FRAMEBUFFER_COMPLETE
+        }
+    }
+}
+
+impl Drop for DepthFrameBufferObject {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.frame_buffer_id);
+        }
+    }
+}
+
+// the offscreen passes can run while a post process framebuffer is bound, so they restore it
+fn bound_frame_buffer_id() -> GLuint {
+    let mut frame_buffer_id = 0;
+
+    unsafe {
+        gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut frame_buffer_id);
+    }
+
+    frame_buffer_id as GLuint
+}
@@ -0,0 +1,86 @@
+use std::{ffi::c_void, ptr};
+
+use gl::types::{GLchar, GLenum, GLsizei, GLuint};
+
+use super::gl_state_cache::with_gl_state_cache;
+
+fn source_to_str(source: GLenum) -> &'static str {
+    match source {
+        gl::DEBUG_SOURCE_API => "api",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "window system",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "shader compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "third party",
+        gl::DEBUG_SOURCE_APPLICATION => "application",
+        _ => "other",
+    }
+}
+
+fn type_to_str(message_type: GLenum) -> &'static str {
+    match message_type {
+        gl::DEBUG_TYPE_ERROR => "error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated behavior",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behavior",
+        gl::DEBUG_TYPE_PORTABILITY => "portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "performance",
+        gl::DEBUG_TYPE_MARKER => "marker",
+        gl::DEBUG_TYPE_PUSH_GROUP => "push group",
+        gl::DEBUG_TYPE_POP_GROUP => "pop group",
+        _ => "other",
+    }
+}
+
+fn severity_to_log_level(severity: GLenum) -> log::Level {
+    match severity {
+        gl::DEBUG_SEVERITY_HIGH => log::Level::Error,
+        gl::DEBUG_SEVERITY_MEDIUM => log::Level::Warn,
+        gl::DEBUG_SEVERITY_LOW => log::Level::Info,
+        _ => log::Level::Debug,
+    }
+}
+
+extern "system" fn gl_debug_message_callback(
+    source: GLenum,
+    message_type: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    length: GLsizei,
+    message: *const GLchar,
+    _user_param: *mut c_void,
+) {
+    let message = if message.is_null() || length < 0 {
+        String::new()
+    } else {
+        let message = unsafe { std::slice::from_raw_parts(message as *const u8, length as usize) };
+        String::from_utf8_lossy(message).into_owned()
+    };
+
+    log::log!(
+        severity_to_log_level(severity),
+        "OpenGL debug message, source = {}, type = {}, id = {id}, msg = {message}",
+        source_to_str(source),
+        type_to_str(message_type),
+    );
+}
+
+// the messages are reported synchronously, so they are logged from the gl call that caused them,
+// returns false if the context does not provide the debug output (KHR_debug or opengl 4.3)
+pub fn set_gl_debug_output_enabled(enabled: bool) -> bool {
+    if !gl::DebugMessageCallback::is_loaded() {
+        return false;
+    }
+
+    with_gl_state_cache(|cache| {
+        cache.set_capability(gl::DEBUG_OUTPUT, enabled);
+        cache.set_capability(gl::DEBUG_OUTPUT_SYNCHRONOUS, enabled);
+    });
+
+    unsafe {
+        if enabled {
+            gl::DebugMessageCallback(Some(gl_debug_message_callback), ptr::null());
+        } else {
+            gl::DebugMessageCallback(None, ptr::null());
+        }
+    }
+
+    true
+}
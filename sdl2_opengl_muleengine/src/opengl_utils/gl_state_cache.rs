@@ -0,0 +1,263 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+};
+
+use gl::types::{GLenum, GLuint};
+
+use super::glsl_version::GLSLVersion;
+
+thread_local! {
+    // the gl context is current on a single thread, so every thread gets its own cache
+    static GL_STATE_CACHE: GlStateCache = GlStateCache::new();
+}
+
+pub fn with_gl_state_cache<ReturnType>(
+    func: impl FnOnce(&GlStateCache) -> ReturnType,
+) -> ReturnType {
+    GL_STATE_CACHE.with(func)
+}
+
+// the states that are None are unknown, so the next change of them always reaches the driver
+pub struct GlStateCache {
+    program_id: Cell<Option<GLuint>>,
+    vao_id: Cell<Option<GLuint>>,
+    active_texture_layer: Cell<Option<u32>>,
+    bound_textures: RefCell<BTreeMap<(u32, GLenum), GLuint>>,
+    capabilities: RefCell<BTreeMap<GLenum, bool>>,
+    blend_func: Cell<Option<(GLenum, GLenum)>>,
+    depth_mask: Cell<Option<bool>>,
+    // every channel is masked or written together
+    color_mask: Cell<Option<bool>>,
+    depth_func: Cell<Option<GLenum>>,
+    polygon_mode: Cell<Option<GLenum>>,
+    reverse_z: Cell<Option<bool>>,
+    // the type of the context does not change, so it is not invalidated
+    is_es_context: Cell<Option<bool>>,
+}
+
+impl Default for GlStateCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlStateCache {
+    pub fn new() -> Self {
+        Self {
+            program_id: Cell::new(None),
+            vao_id: Cell::new(None),
+            active_texture_layer: Cell::new(None),
+            bound_textures: RefCell::new(BTreeMap::new()),
+            capabilities: RefCell::new(BTreeMap::new()),
+            blend_func: Cell::new(None),
+            depth_mask: Cell::new(None),
+            color_mask: Cell::new(None),
+            depth_func: Cell::new(None),
+            polygon_mode: Cell::new(None),
+            reverse_z: Cell::new(None),
+            is_es_context: Cell::new(None),
+        }
+    }
+
+    // has to be called when something outside of opengl_utils could have changed the gl state
+    pub fn invalidate(&self) {
+        self.program_id.set(None);
+        self.vao_id.set(None);
+        self.active_texture_layer.set(None);
+        self.bound_textures.borrow_mut().clear();
+        self.capabilities.borrow_mut().clear();
+        self.blend_func.set(None);
+        self.depth_mask.set(None);
+        self.color_mask.set(None);
+        self.depth_func.set(None);
+        self.polygon_mode.set(None);
+        self.reverse_z.set(None);
+    }
+
+    pub fn use_program(&self, program_id: GLuint) {
+        if self.program_id.get() != Some(program_id) {
+            unsafe {
+                gl::UseProgram(program_id);
+            }
+            self.program_id.set(Some(program_id));
+        }
+    }
+
+    pub fn bind_vertex_array(&self, vao_id: GLuint) {
+        if self.vao_id.get() != Some(vao_id) {
+            unsafe {
+                gl::BindVertexArray(vao_id);
+            }
+            self.vao_id.set(Some(vao_id));
+        }
+    }
+
+    pub fn bind_texture(&self, layer: u32, target: GLenum, texture_id: GLuint) {
+        self.set_active_texture_layer(layer);
+        self.bind_texture_to_active_layer(target, texture_id);
+    }
+
+    // creating and updating textures binds them to whichever layer is active
+    pub fn bind_texture_to_active_layer(&self, target: GLenum, texture_id: GLuint) {
+        let layer = match self.active_texture_layer.get() {
+            Some(layer) => layer,
+            None => {
+                self.set_active_texture_layer(0);
+                0
+            }
+        };
+
+        let mut bound_textures = self.bound_textures.borrow_mut();
+        if bound_textures.get(&(layer, target)) != Some(&texture_id) {
+            unsafe {
+                gl::BindTexture(target, texture_id);
+            }
+            bound_textures.insert((layer, target), texture_id);
+        }
+    }
+
+    pub fn set_capability(&self, capability: GLenum, enabled: bool) {
+        let mut capabilities = self.capabilities.borrow_mut();
+        if capabilities.get(&capability) != Some(&enabled) {
+            unsafe {
+                if enabled {
+                    gl::Enable(capability);
+                } else {
+                    gl::Disable(capability);
+                }
+            }
+            capabilities.insert(capability, enabled);
+        }
+    }
+
+    pub fn set_blend_func(&self, source_factor: GLenum, destination_factor: GLenum) {
+        if self.blend_func.get() != Some((source_factor, destination_factor)) {
+            unsafe {
+                gl::BlendFunc(source_factor, destination_factor);
+            }
+            self.blend_func
+                .set(Some((source_factor, destination_factor)));
+        }
+    }
+
+    pub fn set_depth_mask(&self, enabled: bool) {
+        if self.depth_mask.get() != Some(enabled) {
+            unsafe {
+                gl::DepthMask(if enabled { gl::TRUE } else { gl::FALSE });
+            }
+            self.depth_mask.set(Some(enabled));
+        }
+    }
+
+    // has to be called after a draw that disabled the depth mask, clearing the depth buffer is
+    // masked by the depth mask too
+    pub fn restore_depth_mask(&self) {
+        self.set_depth_mask(true);
+    }
+
+    pub fn set_color_mask(&self, enabled: bool) {
+        if self.color_mask.get() != Some(enabled) {
+            let mask = if enabled { gl::TRUE } else { gl::FALSE };
+            unsafe {
+                gl::ColorMask(mask, mask, mask, mask);
+            }
+            self.color_mask.set(Some(enabled));
+        }
+    }
+
+    pub fn set_depth_func(&self, depth_func: GLenum) {
+        if self.depth_func.get() != Some(depth_func) {
+            unsafe {
+                gl::DepthFunc(depth_func);
+            }
+            self.depth_func.set(Some(depth_func));
+        }
+    }
+
+    // opengl es does not have polygon modes, everything is filled there
+    pub fn set_polygon_mode(&self, mode: GLenum) {
+        if !self.is_es_context() && self.polygon_mode.get() != Some(mode) {
+            unsafe {
+                gl::PolygonMode(gl::FRONT_AND_BACK, mode);
+            }
+            self.polygon_mode.set(Some(mode));
+        }
+    }
+
+    // sets the depth function and the clear depth as well, without glClipControl the depth range
+    // stays [-1, 1] in ndc, so the depth order is right but the precision is not better
+    pub fn set_reverse_z(&self, enabled: bool) {
+        if self.reverse_z.get() == Some(enabled) {
+            return;
+        }
+
+        unsafe {
+            if gl::ClipControl::is_loaded() {
+                gl::ClipControl(
+                    gl::LOWER_LEFT,
+                    if enabled {
+                        gl::ZERO_TO_ONE
+                    } else {
+                        gl::NEGATIVE_ONE_TO_ONE
+                    },
+                );
+            }
+            let clear_depth = if enabled { 0.0 } else { 1.0 };
+            if gl::ClearDepthf::is_loaded() {
+                gl::ClearDepthf(clear_depth);
+            } else {
+                gl::ClearDepth(clear_depth as f64);
+            }
+        }
+        self.set_depth_func(if enabled { gl::GREATER } else { gl::LESS });
+        // the objects in front of the near plane are clamped instead of clipped
+        if !self.is_es_context() {
+            self.set_capability(gl::DEPTH_CLAMP, enabled);
+        }
+        self.reverse_z.set(Some(enabled));
+    }
+
+    pub fn is_reverse_z(&self) -> bool {
+        self.reverse_z.get().unwrap_or(false)
+    }
+
+    // deleted names can be reused by the driver, so they must not stay cached
+    pub fn forget_program(&self, program_id: GLuint) {
+        if self.program_id.get() == Some(program_id) {
+            self.program_id.set(None);
+        }
+    }
+
+    pub fn forget_vertex_array(&self, vao_id: GLuint) {
+        if self.vao_id.get() == Some(vao_id) {
+            self.vao_id.set(None);
+        }
+    }
+
+    pub fn forget_texture(&self, texture_id: GLuint) {
+        self.bound_textures
+            .borrow_mut()
+            .retain(|_, bound_texture_id| *bound_texture_id != texture_id);
+    }
+
+    fn is_es_context(&self) -> bool {
+        match self.is_es_context.get() {
+            Some(is_es_context) => is_es_context,
+            None => {
+                let is_es_context = GLSLVersion::detect().is_es();
+                self.is_es_context.set(Some(is_es_context));
+                is_es_context
+            }
+        }
+    }
+
+    fn set_active_texture_layer(&self, layer: u32) {
+        if self.active_texture_layer.get() != Some(layer) {
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0 + layer);
+            }
+            self.active_texture_layer.set(Some(layer));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::ffi::CStr;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GLSLVersion {
+    // the version the shaders of the assets are written in
+    Desktop400,
+    // opengl es 3.0 and webgl2
+    Es300,
+}
+
+impl GLSLVersion {
+    // the version string of an opengl es context starts with "OpenGL ES",
+    // a gl context has to be current on the calling thread
+    pub fn detect() -> Self {
+        let version = unsafe {
+            let version = gl::GetString(gl::VERSION);
+            if version.is_null() {
+                return GLSLVersion::Desktop400;
+            }
+
+            CStr::from_ptr(version as *const _)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        if version.starts_with("OpenGL ES") {
+            GLSLVersion::Es300
+        } else {
+            GLSLVersion::Desktop400
+        }
+    }
+
+    pub fn is_es(&self) -> bool {
+        *self == GLSLVersion::Es300
+    }
+
+    // the shaders that use features missing from this version can have a variant next to them,
+    // e.g. assets/shaders/unlit.es.vert is loaded instead of assets/shaders/unlit.vert
+    pub fn variant_suffix(&self) -> Option<&'static str> {
+        match self {
+            GLSLVersion::Desktop400 => None,
+            GLSLVersion::Es300 => Some(".es"),
+        }
+    }
+
+    // the version directive of the source is replaced, the sources that are already written
+    // for this version are kept as they are
+    pub fn rewrite_shader_source(&self, source: &str) -> String {
+        let (version_directive, body) = match source.trim_start().strip_prefix("#version") {
+            Some(rest) => {
+                let (version, body) = rest.split_once('\n').unwrap_or((rest, ""));
+                (Some(version.trim()), body)
+            }
+            None => (None, source),
+        };
+
+        match self {
+            GLSLVersion::Desktop400 => {
+                if version_directive.is_some() {
+                    source.to_string()
+                } else {
+                    format!("#version 400\n{body}")
+                }
+            }
+            GLSLVersion::Es300 => {
+                if version_directive == Some("300 es") {
+                    return source.to_string();
+                }
+
+                // the fragment shaders of es do not have a default precision for floats
+                // and for the shadow samplers, the line directive keeps the line numbers
+                // of the compilation errors pointing into the original source
+                let first_line_of_body = if version_directive.is_some() { 2 } else { 1 };
+                format!(
+                    "#version 300 es\n\
+                     precision highp float;\n\
+                     precision highp int;\n\
+                     precision highp sampler2D;\n\
+                     precision highp samplerCube;\n\
+                     precision highp sampler2DShadow;\n\
+                     #line {first_line_of_body}\n\
+                     {body}"
+                )
+            }
+        }
+    }
+}
@@ -2,6 +2,11 @@ use std::{ffi::c_void, mem::size_of, ptr::null};
 
 use gl::types::{GLenum, GLuint};
 
+use super::{
+    deferred_buffer_upload::{BufferUploadMode, DeferredBufferUpload},
+    gl_state_cache::with_gl_state_cache,
+};
+
 pub enum PrimitiveMode {
     Points,
     LineStrip,
@@ -38,6 +43,7 @@ impl PrimitiveMode {
 
 pub struct IndexBufferObject {
     pub(super) buffer_id: GLuint,
+    data_pointer: *const u32,
     number_of_elements: usize,
     primitive_mode: GLenum,
 }
@@ -48,6 +54,28 @@ impl IndexBufferObject {
         number_of_elements: usize,
         primitive_mode: PrimitiveMode,
     ) -> Self {
+        Self::new_with_upload_mode(
+            data_pointer,
+            number_of_elements,
+            primitive_mode,
+            BufferUploadMode::Immediate,
+        )
+    }
+
+    pub fn new_with_upload_mode(
+        data_pointer: *const u32,
+        number_of_elements: usize,
+        primitive_mode: PrimitiveMode,
+        upload_mode: BufferUploadMode,
+    ) -> Self {
+        let initial_data_pointer = match upload_mode {
+            BufferUploadMode::Immediate => data_pointer as *const c_void,
+            BufferUploadMode::Deferred => null(),
+        };
+
+        // the element array binding belongs to the bound vao, so no vao may be bound
+        with_gl_state_cache(|cache| cache.bind_vertex_array(0));
+
         let mut buffer_id = 0;
         unsafe {
             gl::GenBuffers(1, &mut buffer_id);
@@ -55,18 +83,35 @@ impl IndexBufferObject {
             gl::BufferData(
                 gl::ELEMENT_ARRAY_BUFFER,
                 (size_of::<u32>() * number_of_elements) as isize,
-                data_pointer as *const c_void,
+                initial_data_pointer,
                 gl::STATIC_DRAW,
             );
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
         };
         Self {
             buffer_id,
+            data_pointer,
             number_of_elements,
             primitive_mode: primitive_mode.to_gl_enum(),
         }
     }
 
+    pub fn number_of_elements(&self) -> usize {
+        self.number_of_elements
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        size_of::<u32>() * self.number_of_elements
+    }
+
+    pub fn deferred_upload(&self) -> DeferredBufferUpload {
+        DeferredBufferUpload {
+            buffer_id: self.buffer_id,
+            data_pointer: self.data_pointer as *const c_void,
+            size_in_bytes: self.size_in_bytes(),
+        }
+    }
+
     pub fn draw(&self) {
         unsafe {
             gl::DrawElements(
@@ -1,8 +1,18 @@
+pub mod deferred_buffer_upload;
+pub mod depth_texture_2d;
+pub mod frame_buffer_object;
+pub mod gl_debug_output;
+pub mod gl_state_cache;
+pub mod glsl_version;
 pub mod index_buffer_object;
+pub mod occlusion_query;
 pub mod shader;
 pub mod shader_input;
 pub mod shader_program;
 pub mod texture_2d;
+pub mod texture_cubemap;
+pub mod time_elapsed_query;
+pub mod uniform_buffer_object;
 pub mod vertex_array_object;
 pub mod vertex_buffer_object;
 
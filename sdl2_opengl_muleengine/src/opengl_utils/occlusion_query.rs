@@ -0,0 +1,69 @@
+use gl::types::GLuint;
+
+pub struct OcclusionQuery {
+    query_id: GLuint,
+    // the result of a query that was never ended must not be read
+    is_issued: bool,
+}
+
+impl Default for OcclusionQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OcclusionQuery {
+    pub fn new() -> Self {
+        let mut query_id = 0;
+        unsafe {
+            gl::GenQueries(1, &mut query_id);
+        }
+
+        Self {
+            query_id,
+            is_issued: false,
+        }
+    }
+
+    // the queries of the same target can not be nested
+    pub fn begin(&mut self) {
+        unsafe {
+            gl::BeginQuery(gl::ANY_SAMPLES_PASSED, self.query_id);
+        }
+    }
+
+    pub fn end(&mut self) {
+        unsafe {
+            gl::EndQuery(gl::ANY_SAMPLES_PASSED);
+        }
+        self.is_issued = true;
+    }
+
+    // does not wait for the gpu, None is returned until the result is available
+    pub fn any_samples_passed(&self) -> Option<bool> {
+        if !self.is_issued {
+            return None;
+        }
+
+        unsafe {
+            let mut is_available = 0;
+            gl::GetQueryObjectuiv(self.query_id, gl::QUERY_RESULT_AVAILABLE, &mut is_available);
+            if is_available == 0 {
+                return None;
+            }
+
+            let mut any_samples_passed = 0;
+            gl::GetQueryObjectuiv(self.query_id, gl::QUERY_RESULT, &mut any_samples_passed);
+
+            Some(any_samples_passed != 0)
+        }
+    }
+}
+
+impl Drop for OcclusionQuery {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteQueries(1, &self.query_id);
+        }
+    }
+}
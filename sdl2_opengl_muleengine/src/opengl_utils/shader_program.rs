@@ -1,8 +1,9 @@
-use std::string::FromUtf8Error;
+use std::{ffi::CString, string::FromUtf8Error};
 
 use gl::types::GLuint;
 
 use super::{
+    gl_state_cache::with_gl_state_cache,
     shader::Shader,
     shader_input::{ShaderAttribute, ShaderInput, ShaderUniform},
 };
@@ -40,7 +41,7 @@ impl ShaderProgram {
     }
 
     pub fn use_program(&self) {
-        unsafe { gl::UseProgram(self.program_id) }
+        with_gl_state_cache(|cache| cache.use_program(self.program_id));
     }
 
     pub fn attach_shader(&mut self, shader: Shader) {
@@ -154,6 +155,24 @@ impl ShaderProgram {
         ret
     }
 
+    pub fn bind_uniform_block(&self, name: &str, binding_point: GLuint) -> bool {
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return false,
+        };
+
+        unsafe {
+            let block_index = gl::GetUniformBlockIndex(self.program_id, name.as_ptr());
+            if block_index == gl::INVALID_INDEX {
+                return false;
+            }
+
+            gl::UniformBlockBinding(self.program_id, block_index, binding_point);
+        }
+
+        true
+    }
+
     unsafe fn gather_attributes(&mut self) {
         let mut attributes_count = 0;
 
@@ -245,6 +264,11 @@ impl ShaderProgram {
             let location =
                 gl::GetUniformLocation(self.program_id, uniform_name.as_ptr() as *const i8);
 
+            // the members of uniform blocks have no location, they are sourced from uniform buffers
+            if location == -1 {
+                continue;
+            }
+
             match String::from_utf8(uniform_name) {
                 Ok(mut uniform_name) => {
                     if uniform_name.ends_with("[0]") {
@@ -268,6 +292,8 @@ impl ShaderProgram {
 
 impl Drop for ShaderProgram {
     fn drop(&mut self) {
+        with_gl_state_cache(|cache| cache.forget_program(self.program_id));
+
         unsafe {
             gl::DeleteProgram(self.program_id);
         }
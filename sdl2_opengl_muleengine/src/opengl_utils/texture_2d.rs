@@ -1,8 +1,20 @@
-use std::{ffi::c_void, sync::Arc};
+use std::{
+    ffi::c_void,
+    ptr::null,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
-use gl::types::GLuint;
+use gl::types::{GLenum, GLuint};
 
-use muleengine::image::{ColorType, Image};
+use muleengine::{
+    compressed_image::{CompressedImage, CompressedImageFormat},
+    image::{ColorType, Image},
+};
+
+use super::gl_state_cache::with_gl_state_cache;
 
 #[derive(Clone, Copy)]
 pub enum GLTextureAnisotropyMode {
@@ -10,6 +22,7 @@ pub enum GLTextureAnisotropyMode {
     Anisotropy2,
     Anisotropy4,
     Anisotropy8,
+    Anisotropy16,
 }
 
 #[derive(Clone, Copy)]
@@ -31,6 +44,44 @@ pub enum GLTextureSamplingMode {
 
 pub struct Texture2D {
     texture_id: GLuint,
+    uploaded: Arc<AtomicBool>,
+}
+
+// the format and the data type of the pixels of the image
+pub(crate) fn gl_pixel_format_of_color_type(color_type: ColorType) -> (GLenum, GLenum) {
+    match color_type {
+        ColorType::L8 => (gl::RED, gl::UNSIGNED_BYTE),
+        ColorType::La8 => (gl::RG, gl::UNSIGNED_BYTE),
+        ColorType::Rgb8 => (gl::RGB, gl::UNSIGNED_BYTE),
+        ColorType::Rgba8 => (gl::RGBA, gl::UNSIGNED_BYTE),
+        ColorType::L16 => (gl::RED, gl::UNSIGNED_SHORT),
+        ColorType::La16 => (gl::RG, gl::UNSIGNED_SHORT),
+        ColorType::Rgb16 => (gl::RGB, gl::UNSIGNED_SHORT),
+        ColorType::Rgba16 => (gl::RGBA, gl::UNSIGNED_SHORT),
+        ColorType::RgbF32 => (gl::RGB, gl::FLOAT),
+        ColorType::RgbaF32 => (gl::RGBA, gl::FLOAT),
+    }
+}
+
+// the s3tc formats are not part of the core profile, they are provided by EXT_texture_compression_s3tc
+pub(crate) fn gl_internal_format_of_compressed_format(format: CompressedImageFormat) -> GLenum {
+    const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: u32 = 0x83F0;
+    const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: u32 = 0x83F1;
+    const GL_COMPRESSED_RGBA_S3TC_DXT3_EXT: u32 = 0x83F2;
+    const GL_COMPRESSED_RGBA_S3TC_DXT5_EXT: u32 = 0x83F3;
+
+    match format {
+        CompressedImageFormat::Bc1RgbUnorm => GL_COMPRESSED_RGB_S3TC_DXT1_EXT,
+        CompressedImageFormat::Bc1RgbaUnorm => GL_COMPRESSED_RGBA_S3TC_DXT1_EXT,
+        CompressedImageFormat::Bc2Unorm => GL_COMPRESSED_RGBA_S3TC_DXT3_EXT,
+        CompressedImageFormat::Bc3Unorm => GL_COMPRESSED_RGBA_S3TC_DXT5_EXT,
+        CompressedImageFormat::Bc4Unorm => gl::COMPRESSED_RED_RGTC1,
+        CompressedImageFormat::Bc5Unorm => gl::COMPRESSED_RG_RGTC2,
+        CompressedImageFormat::Bc6hUfloat => gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT,
+        CompressedImageFormat::Bc7Unorm => gl::COMPRESSED_RGBA_BPTC_UNORM,
+        CompressedImageFormat::Etc2Rgb8Unorm => gl::COMPRESSED_RGB8_ETC2,
+        CompressedImageFormat::Etc2Rgba8Unorm => gl::COMPRESSED_RGBA8_ETC2_EAC,
+    }
 }
 
 fn set_texture_anisotropy_mode(mode: GLTextureAnisotropyMode) {
@@ -49,6 +100,9 @@ fn set_texture_anisotropy_mode(mode: GLTextureAnisotropyMode) {
         GLTextureAnisotropyMode::Anisotropy8 => unsafe {
             gl::TexParameterf(gl::TEXTURE_2D, GL_TEXTURE_MAX_ANISOTROPY_EXT, 8.0);
         },
+        GLTextureAnisotropyMode::Anisotropy16 => unsafe {
+            gl::TexParameterf(gl::TEXTURE_2D, GL_TEXTURE_MAX_ANISOTROPY_EXT, 16.0);
+        },
     }
 }
 
@@ -122,99 +176,234 @@ fn set_texture_sampling_mode(mode: GLTextureSamplingMode) {
     }
 }
 
+// the mipmaps are not generated, the levels that are missing from the image are not sampled
+fn upload_compressed_image(compressed: &CompressedImage) {
+    let internal_format = gl_internal_format_of_compressed_format(compressed.format());
+
+    for (level_index, level) in compressed.levels().iter().enumerate() {
+        unsafe {
+            gl::CompressedTexImage2D(
+                gl::TEXTURE_2D,
+                level_index as i32,
+                internal_format,
+                level.width as i32,
+                level.height as i32,
+                0,
+                level.data.len() as i32,
+                level.data.as_ptr() as *const c_void,
+            );
+        }
+    }
+
+    unsafe {
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, 0);
+        gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_MAX_LEVEL,
+            compressed.levels().len() as i32 - 1,
+        );
+
+        // the formats of the image that are not supported by the driver are reported as invalid enums
+        let error = gl::GetError();
+        if error != gl::NO_ERROR {
+            log::error!(
+                "Uploading compressed texture, msg = format is not supported, format = {:?}, error = {error:#x}",
+                compressed.format()
+            );
+        }
+    }
+}
+
+fn upload_image(image: &Image) {
+    if let Some(compressed) = image.compressed() {
+        upload_compressed_image(compressed);
+        return;
+    }
+
+    let (format, data_type) = gl_pixel_format_of_color_type(image.color_type());
+
+    // if image.width() % 4 != 0 {
+    //     unsafe {
+    //         gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+    //     }
+    // }
+
+    unsafe {
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            format as i32,
+            image.width() as i32,
+            image.height() as i32,
+            0,
+            format,
+            data_type,
+            image.as_bytes().as_ptr() as *const c_void,
+        );
+    }
+
+    // if image.height() % 4 != 0 {
+    //     unsafe {
+    //         gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+    //     }
+    // }
+
+    unsafe {
+        gl::GenerateMipmap(gl::TEXTURE_2D);
+    }
+
+    match image.color_type() {
+        ColorType::L8 => unsafe {
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
+        },
+        ColorType::La8 => unsafe {
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_A, gl::GREEN as i32);
+        },
+        ColorType::Rgb8 => {}
+        ColorType::Rgba8 => {}
+        ColorType::L16 => unsafe {
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
+        },
+        ColorType::La16 => unsafe {
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_A, gl::GREEN as i32);
+        },
+        ColorType::Rgb16 => {}
+        ColorType::Rgba16 => {}
+        ColorType::RgbF32 => {}
+        ColorType::RgbaF32 => {}
+    }
+}
+
+fn upload_image_with_default_parameters(image: &Image) {
+    upload_image(image);
+
+    set_texture_anisotropy_mode(GLTextureAnisotropyMode::Anisotropy8);
+
+    set_texture_map_mode(GLTextureMapMode::Repeat);
+    set_texture_sampling_mode(GLTextureSamplingMode::LinearMipmapLinear);
+}
+
 impl Texture2D {
     pub fn new(image: Arc<Image>) -> Self {
         let mut texture_id = 0;
 
         unsafe {
             gl::GenTextures(1, &mut texture_id);
+        }
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_2D, texture_id));
+
+        upload_image_with_default_parameters(&image);
+
+        Self {
+            texture_id,
+            uploaded: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    // only the texture object is created, its image is uploaded by the upload queue
+    pub fn new_deferred() -> Self {
+        let mut texture_id = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut texture_id);
+        }
+        // the object is created by the first binding, so both contexts see the same one
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_2D, texture_id));
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_2D, 0));
+
+        Self {
+            texture_id,
+            uploaded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // executed on the thread of a shared context
+    pub fn upload_deferred(texture_id: GLuint, image: &Image) {
+        unsafe {
             gl::BindTexture(gl::TEXTURE_2D, texture_id);
         }
 
-        let (format, data_type) = match image.color_type() {
-            ColorType::L8 => (gl::RED, gl::UNSIGNED_BYTE),
-            ColorType::La8 => (gl::RG, gl::UNSIGNED_BYTE),
-            ColorType::Rgb8 => (gl::RGB, gl::UNSIGNED_BYTE),
-            ColorType::Rgba8 => (gl::RGBA, gl::UNSIGNED_BYTE),
-            ColorType::L16 => (gl::RED, gl::UNSIGNED_SHORT),
-            ColorType::La16 => (gl::RG, gl::UNSIGNED_SHORT),
-            ColorType::Rgb16 => (gl::RGB, gl::UNSIGNED_SHORT),
-            ColorType::Rgba16 => (gl::RGBA, gl::UNSIGNED_SHORT),
-            ColorType::RgbF32 => (gl::RGB, gl::FLOAT),
-            ColorType::RgbaF32 => (gl::RGBA, gl::FLOAT),
-        };
-
-        // if image.width() % 4 != 0 {
-        //     unsafe {
-        //         gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
-        //     }
-        // }
+        upload_image_with_default_parameters(image);
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    pub fn is_uploaded(&self) -> bool {
+        self.uploaded.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn uploaded_flag(&self) -> Arc<AtomicBool> {
+        self.uploaded.clone()
+    }
+
+    // the texture keeps its id, so the materials using it see the new image
+    pub fn update(&self, image: &Image) {
+        with_gl_state_cache(|cache| {
+            cache.bind_texture_to_active_layer(gl::TEXTURE_2D, self.texture_id)
+        });
+
+        upload_image(image);
+    }
+
+    pub fn new_render_target(width: usize, height: usize) -> Self {
+        let mut texture_id = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut texture_id);
+        }
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_2D, texture_id));
 
         unsafe {
             gl::TexImage2D(
                 gl::TEXTURE_2D,
                 0,
-                format as i32,
-                image.width() as i32,
-                image.height() as i32,
+                gl::RGBA as i32,
+                width as i32,
+                height as i32,
                 0,
-                format,
-                data_type,
-                image.as_bytes().as_ptr() as *const c_void,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                null(),
             );
         }
 
-        // if image.height() % 4 != 0 {
-        //     unsafe {
-        //         gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
-        //     }
-        // }
+        // the content is rendered every frame, so there are no mipmaps to sample from
+        set_texture_map_mode(GLTextureMapMode::Clamp);
+        set_texture_sampling_mode(GLTextureSamplingMode::Linear);
 
-        unsafe {
-            gl::GenerateMipmap(gl::TEXTURE_2D);
-        }
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_2D, 0));
 
-        set_texture_anisotropy_mode(GLTextureAnisotropyMode::Anisotropy8);
-
-        set_texture_map_mode(GLTextureMapMode::Repeat);
-        set_texture_sampling_mode(GLTextureSamplingMode::LinearMipmapLinear);
-
-        match image.color_type() {
-            ColorType::L8 => unsafe {
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
-            },
-            ColorType::La8 => unsafe {
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_A, gl::GREEN as i32);
-            },
-            ColorType::Rgb8 => {}
-            ColorType::Rgba8 => {}
-            ColorType::L16 => unsafe {
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
-            },
-            ColorType::La16 => unsafe {
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, gl::RED as i32);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, gl::RED as i32);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_A, gl::GREEN as i32);
-            },
-            ColorType::Rgb16 => {}
-            ColorType::Rgba16 => {}
-            ColorType::RgbF32 => {}
-            ColorType::RgbaF32 => {}
+        Self {
+            texture_id,
+            uploaded: Arc::new(AtomicBool::new(true)),
         }
+    }
 
-        Self { texture_id }
+    pub fn texture_id(&self) -> GLuint {
+        self.texture_id
     }
 
     pub fn use_texture(&self, layer: usize) {
-        unsafe {
-            gl::ActiveTexture(gl::TEXTURE0 + layer as u32);
+        with_gl_state_cache(|cache| {
+            cache.bind_texture(layer as u32, gl::TEXTURE_2D, self.texture_id)
+        });
+    }
 
-            gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
-        }
+    // the texture has to be bound, like for set_texture_map_mode
+    pub fn set_texture_sampling_mode(&self, mode: GLTextureSamplingMode) {
+        set_texture_sampling_mode(mode);
+    }
+
+    pub fn set_texture_anisotropy_mode(&self, mode: GLTextureAnisotropyMode) {
+        set_texture_anisotropy_mode(mode);
     }
 
     pub fn set_texture_map_mode(&self, mode: GLTextureMapMode) {
@@ -244,3 +433,13 @@ impl Texture2D {
         }
     }
 }
+
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        with_gl_state_cache(|cache| cache.forget_texture(self.texture_id));
+
+        unsafe {
+            gl::DeleteTextures(1, &self.texture_id);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::{ffi::c_void, ptr::null, sync::Arc};
+
+use gl::types::GLuint;
+
+use muleengine::image::Image;
+
+use super::{gl_state_cache::with_gl_state_cache, texture_2d::gl_pixel_format_of_color_type};
+
+pub struct TextureCubemap {
+    texture_id: GLuint,
+    resolution: usize,
+}
+
+impl TextureCubemap {
+    pub fn new(resolution: usize) -> Self {
+        let mut texture_id = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut texture_id);
+        }
+        with_gl_state_cache(|cache| {
+            cache.bind_texture_to_active_layer(gl::TEXTURE_CUBE_MAP, texture_id)
+        });
+
+        unsafe {
+            for face_index in 0..6 {
+                gl::TexImage2D(
+                    gl::TEXTURE_CUBE_MAP_POSITIVE_X + face_index,
+                    0,
+                    gl::RGBA8 as i32,
+                    resolution as i32,
+                    resolution as i32,
+                    0,
+                    gl::RGBA,
+                    gl::UNSIGNED_BYTE,
+                    null(),
+                );
+            }
+        }
+
+        set_cubemap_parameters();
+
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_CUBE_MAP, 0));
+
+        Self {
+            texture_id,
+            resolution,
+        }
+    }
+
+    // the faces are in the +x, -x, +y, -y, +z, -z order
+    pub fn from_images(faces: &[Arc<Image>; 6]) -> Result<Self, String> {
+        let resolution = faces[0].width();
+        if faces
+            .iter()
+            .any(|face| face.width() != resolution || face.height() != resolution)
+        {
+            return Err("the faces of a cubemap must be squares of the same size".to_string());
+        }
+
+        let mut texture_id = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut texture_id);
+        }
+        with_gl_state_cache(|cache| {
+            cache.bind_texture_to_active_layer(gl::TEXTURE_CUBE_MAP, texture_id)
+        });
+
+        unsafe {
+            for (face_index, face) in faces.iter().enumerate() {
+                let (format, data_type) = gl_pixel_format_of_color_type(face.color_type());
+
+                gl::TexImage2D(
+                    gl::TEXTURE_CUBE_MAP_POSITIVE_X + face_index as u32,
+                    0,
+                    format as i32,
+                    resolution as i32,
+                    resolution as i32,
+                    0,
+                    format,
+                    data_type,
+                    face.as_bytes().as_ptr() as *const c_void,
+                );
+            }
+        }
+
+        set_cubemap_parameters();
+
+        with_gl_state_cache(|cache| cache.bind_texture_to_active_layer(gl::TEXTURE_CUBE_MAP, 0));
+
+        Ok(Self {
+            texture_id,
+            resolution,
+        })
+    }
+
+    pub fn texture_id(&self) -> GLuint {
+        self.texture_id
+    }
+
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    pub fn use_texture(&self, layer: usize) {
+        with_gl_state_cache(|cache| {
+            cache.bind_texture(layer as u32, gl::TEXTURE_CUBE_MAP, self.texture_id)
+        });
+    }
+}
+
+fn set_cubemap_parameters() {
+    unsafe {
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_MIN_FILTER,
+            gl::LINEAR as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_MAG_FILTER,
+            gl::LINEAR as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_WRAP_S,
+            gl::CLAMP_TO_EDGE as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_WRAP_T,
+            gl::CLAMP_TO_EDGE as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_WRAP_R,
+            gl::CLAMP_TO_EDGE as i32,
+        );
+    }
+}
+
+impl Drop for TextureCubemap {
+    fn drop(&mut self) {
+        with_gl_state_cache(|cache| cache.forget_texture(self.texture_id));
+
+        unsafe {
+            gl::DeleteTextures(1, &self.texture_id);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use gl::types::GLuint;
+
+pub struct TimeElapsedQuery {
+    query_id: GLuint,
+    // the result of a query that was never ended must not be read
+    is_issued: bool,
+}
+
+impl Default for TimeElapsedQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeElapsedQuery {
+    pub fn new() -> Self {
+        let mut query_id = 0;
+        unsafe {
+            gl::GenQueries(1, &mut query_id);
+        }
+
+        Self {
+            query_id,
+            is_issued: false,
+        }
+    }
+
+    // the queries of the same target can not be nested
+    pub fn begin(&mut self) {
+        unsafe {
+            gl::BeginQuery(gl::TIME_ELAPSED, self.query_id);
+        }
+    }
+
+    pub fn end(&mut self) {
+        unsafe {
+            gl::EndQuery(gl::TIME_ELAPSED);
+        }
+        self.is_issued = true;
+    }
+
+    // does not wait for the gpu, None is returned until the result is available
+    pub fn elapsed_nanoseconds(&self) -> Option<u64> {
+        if !self.is_issued {
+            return None;
+        }
+
+        unsafe {
+            let mut is_available = 0;
+            gl::GetQueryObjectiv(self.query_id, gl::QUERY_RESULT_AVAILABLE, &mut is_available);
+            if is_available == 0 {
+                return None;
+            }
+
+            let mut elapsed_nanoseconds = 0;
+            gl::GetQueryObjectui64v(self.query_id, gl::QUERY_RESULT, &mut elapsed_nanoseconds);
+
+            Some(elapsed_nanoseconds)
+        }
+    }
+
+    pub fn is_supported() -> bool {
+        gl::GetQueryObjectui64v::is_loaded()
+    }
+}
+
+impl Drop for TimeElapsedQuery {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteQueries(1, &self.query_id);
+        }
+    }
+}
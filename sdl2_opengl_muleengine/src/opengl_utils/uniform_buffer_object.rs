@@ -0,0 +1,61 @@
+use std::{ffi::c_void, mem::size_of, ptr::null};
+
+use gl::types::GLuint;
+
+pub struct UniformBufferObject {
+    buffer_id: GLuint,
+    size_in_bytes: usize,
+}
+
+impl UniformBufferObject {
+    pub fn new(size_in_bytes: usize) -> Self {
+        let mut buffer_id = 0;
+
+        unsafe {
+            gl::GenBuffers(1, &mut buffer_id);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, buffer_id);
+            gl::BufferData(
+                gl::UNIFORM_BUFFER,
+                size_in_bytes as isize,
+                null(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+
+        Self {
+            buffer_id,
+            size_in_bytes,
+        }
+    }
+
+    // the layout of DataType has to match the std140 layout of the uniform block
+    pub fn update<DataType>(&self, data: &DataType) {
+        let size_of_data = size_of::<DataType>().min(self.size_in_bytes);
+
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, self.buffer_id);
+            gl::BufferSubData(
+                gl::UNIFORM_BUFFER,
+                0,
+                size_of_data as isize,
+                data as *const DataType as *const c_void,
+            );
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+    }
+
+    pub fn bind_to_binding_point(&self, binding_point: GLuint) {
+        unsafe {
+            gl::BindBufferBase(gl::UNIFORM_BUFFER, binding_point, self.buffer_id);
+        }
+    }
+}
+
+impl Drop for UniformBufferObject {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.buffer_id);
+        }
+    }
+}
@@ -1,8 +1,10 @@
+use std::ffi::c_void;
 use std::marker::PhantomData;
 use std::ptr::null;
 
 use gl::types::GLuint;
 
+use super::gl_state_cache::with_gl_state_cache;
 use super::shader_input::ShaderAttribute;
 use super::vertex_buffer_object::{DataCount, DataType};
 use super::{index_buffer_object::IndexBufferObject, vertex_buffer_object::VertexBufferObject};
@@ -59,6 +61,46 @@ impl VertexArrayObjectInterface {
         }
     }
 
+    // the attribute advances once per instance instead of once per vertex
+    pub fn bind_instance_vbo_to_shader_attrib(
+        &self,
+        vbo: &VertexBufferObject,
+        attrib: &ShaderAttribute,
+    ) {
+        self.bind_vbo_to_shader_attrib(vbo, attrib);
+
+        unsafe {
+            gl::VertexAttribDivisor(attrib.0.location as u32, 1);
+        }
+    }
+
+    // a mat4 attribute occupies four consecutive locations, one for each column
+    pub fn bind_instance_matrix_vbo_to_shader_attrib(
+        &self,
+        vbo: &VertexBufferObject,
+        attrib: &ShaderAttribute,
+    ) {
+        let column_size = vbo.size_of_element / 4;
+
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo.buffer_id);
+
+            for column in 0..4 {
+                let location = attrib.0.location as u32 + column as u32;
+                gl::EnableVertexAttribArray(location);
+                gl::VertexAttribPointer(
+                    location,
+                    4,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    vbo.size_of_element as i32,
+                    (column * column_size) as *const c_void,
+                );
+                gl::VertexAttribDivisor(location, 1);
+            }
+        }
+    }
+
     pub fn use_index_buffer_object(&self, ibo: &IndexBufferObject) {
         unsafe {
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ibo.buffer_id);
@@ -75,35 +117,32 @@ impl VertexArrayObject {
         let vao_id = unsafe {
             let mut vao_id = 0;
             gl::GenVertexArrays(1, &mut vao_id);
-            gl::BindVertexArray(vao_id);
+            vao_id
+        };
 
-            setup_fn(VertexArrayObjectInterface {
-                _phantom: PhantomData,
-            });
+        with_gl_state_cache(|cache| cache.bind_vertex_array(vao_id));
 
-            gl::BindVertexArray(0);
+        setup_fn(VertexArrayObjectInterface {
+            _phantom: PhantomData,
+        });
 
-            vao_id
-        };
+        with_gl_state_cache(|cache| cache.bind_vertex_array(0));
 
         Self { vao_id }
     }
 
+    // the vao stays bound after use, so consecutive draws of the same vao skip binding it
     pub fn use_vao(&self, use_fn: impl FnOnce()) {
-        unsafe {
-            gl::BindVertexArray(self.vao_id);
-        }
+        with_gl_state_cache(|cache| cache.bind_vertex_array(self.vao_id));
 
         use_fn();
-
-        unsafe {
-            gl::BindVertexArray(0);
-        }
     }
 }
 
 impl Drop for VertexArrayObject {
     fn drop(&mut self) {
+        with_gl_state_cache(|cache| cache.forget_vertex_array(self.vao_id));
+
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao_id);
         }
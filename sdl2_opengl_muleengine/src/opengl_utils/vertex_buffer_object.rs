@@ -1,6 +1,8 @@
-use std::{ffi::c_void, mem::size_of};
+use std::{ffi::c_void, mem::size_of, ptr::null};
 
-use gl::types::GLuint;
+use gl::types::{GLenum, GLuint};
+
+use super::deferred_buffer_upload::{BufferUploadMode, DeferredBufferUpload};
 
 pub enum DataType {
     F32,
@@ -32,6 +34,7 @@ pub struct VertexBufferObject {
     pub(super) data_type: DataType,
     pub(super) data_count: DataCount,
     data_pointer: *const c_void,
+    number_of_elements: usize,
 }
 
 impl VertexBufferObject {
@@ -41,11 +44,75 @@ impl VertexBufferObject {
         data_type: DataType,
         data_count: DataCount,
     ) -> Self
+    where
+        ElementType: Sized,
+    {
+        Self::new_with_upload_mode(
+            data_pointer,
+            number_of_elements,
+            data_type,
+            data_count,
+            BufferUploadMode::Immediate,
+        )
+    }
+
+    pub fn new_with_upload_mode<ElementType>(
+        data_pointer: *const ElementType,
+        number_of_elements: usize,
+        data_type: DataType,
+        data_count: DataCount,
+        upload_mode: BufferUploadMode,
+    ) -> Self
+    where
+        ElementType: Sized,
+    {
+        Self::new_with_usage(
+            data_pointer,
+            number_of_elements,
+            data_type,
+            data_count,
+            gl::STATIC_DRAW,
+            upload_mode,
+        )
+    }
+
+    // for buffers that are updated every frame with update_from_pointer
+    pub fn new_dynamic<ElementType>(
+        data_pointer: *const ElementType,
+        number_of_elements: usize,
+        data_type: DataType,
+        data_count: DataCount,
+    ) -> Self
+    where
+        ElementType: Sized,
+    {
+        Self::new_with_usage(
+            data_pointer,
+            number_of_elements,
+            data_type,
+            data_count,
+            gl::DYNAMIC_DRAW,
+            BufferUploadMode::Immediate,
+        )
+    }
+
+    fn new_with_usage<ElementType>(
+        data_pointer: *const ElementType,
+        number_of_elements: usize,
+        data_type: DataType,
+        data_count: DataCount,
+        usage: GLenum,
+        upload_mode: BufferUploadMode,
+    ) -> Self
     where
         ElementType: Sized,
     {
         let size_of_element = size_of::<ElementType>();
         let data_pointer = data_pointer as *const c_void;
+        let initial_data_pointer = match upload_mode {
+            BufferUploadMode::Immediate => data_pointer,
+            BufferUploadMode::Deferred => null(),
+        };
         let mut buffer_id = 0;
         unsafe {
             gl::GenBuffers(1, &mut buffer_id);
@@ -53,8 +120,8 @@ impl VertexBufferObject {
             gl::BufferData(
                 gl::ARRAY_BUFFER,
                 (size_of_element * number_of_elements) as isize,
-                data_pointer,
-                gl::STATIC_DRAW,
+                initial_data_pointer,
+                usage,
             );
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
         };
@@ -64,6 +131,44 @@ impl VertexBufferObject {
             data_type,
             data_count,
             data_pointer,
+            number_of_elements,
+        }
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.size_of_element * self.number_of_elements
+    }
+
+    pub fn number_of_elements(&self) -> usize {
+        self.number_of_elements
+    }
+
+    pub fn deferred_upload(&self) -> DeferredBufferUpload {
+        DeferredBufferUpload {
+            buffer_id: self.buffer_id,
+            data_pointer: self.data_pointer,
+            size_in_bytes: self.size_in_bytes(),
+        }
+    }
+
+    // the data is copied into the buffer starting at element_offset, the caller makes sure
+    // that the elements fit into the buffer
+    pub fn update_data<ElementType>(&self, element_offset: usize, data: &[ElementType])
+    where
+        ElementType: Sized,
+    {
+        debug_assert_eq!(size_of::<ElementType>(), self.size_of_element);
+        debug_assert!(element_offset + data.len() <= self.number_of_elements);
+
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.buffer_id);
+            gl::BufferSubData(
+                gl::ARRAY_BUFFER,
+                (self.size_of_element * element_offset) as isize,
+                (self.size_of_element * data.len()) as isize,
+                data.as_ptr() as *const c_void,
+            );
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
         }
     }
 
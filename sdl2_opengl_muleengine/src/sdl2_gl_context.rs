@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use muleengine::renderer::renderer_config::RendererConfig;
 use muleengine::system_container::System;
 use sdl2::event as sdl2_event;
 use sdl2::keyboard::{Keycode, Scancode};
@@ -6,19 +9,51 @@ use sdl2::video::{FullscreenType, GLContext, Window, WindowBuildError};
 use sdl2::{video, EventPump, Sdl, VideoSubsystem};
 use vek::Vec2;
 
-use muleengine::window_context::{Event, EventSender, Key, MouseButton, WindowContext};
+use muleengine::window_context::{
+    Event, EventSender, Key, MouseButton, SecondaryWindowId, WindowContext,
+};
 
 pub struct Sdl2GlContext {
     sdl_context: Sdl,
-    _sdl_video: VideoSubsystem,
-    _gl_context: GLContext,
+    sdl_video: VideoSubsystem,
+    gl_context: GLContext,
     sdl_window: Window,
+    // the windows are identified by their sdl window id
+    secondary_windows: BTreeMap<SecondaryWindowId, Window>,
     event_pump: EventPump,
     event_sender: EventSender,
     window_width_f32: f32,
     window_height_f32: f32,
 }
 
+// a gl context that shares its objects with the context of the main window,
+// it is made current on the thread that uploads the resources
+pub struct SharedGLContext {
+    sdl_window: *mut sdl2::sys::SDL_Window,
+    gl_context: GLContext,
+}
+
+// the context is only made current on a single thread at a time, and the window outlives it
+unsafe impl Send for SharedGLContext {}
+
+impl SharedGLContext {
+    pub fn make_current(&self) -> Result<(), String> {
+        let result =
+            unsafe { sdl2::sys::SDL_GL_MakeCurrent(self.sdl_window, self.gl_context.raw()) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(sdl2::get_error())
+        }
+    }
+
+    pub fn release_current(&self) {
+        unsafe {
+            sdl2::sys::SDL_GL_MakeCurrent(self.sdl_window, std::ptr::null_mut());
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ContextCreationError {
     CouldNotCreateSdlContext(String),
@@ -58,8 +93,15 @@ impl Sdl2GlContext {
         gl_profile: GlProfile,
         gl_major_version: u8,
         gl_minor_version: u8,
+        renderer_config: &RendererConfig,
     ) -> Result<Self, ContextCreationError> {
         let sdl2_gl_profile = gl_profile.into();
+
+        // the desktop drivers only provide es contexts through the es driver of sdl
+        if matches!(gl_profile, GlProfile::GLES) {
+            sdl2::hint::set("SDL_OPENGL_ES_DRIVER", "1");
+        }
+
         let sdl_context = sdl2::init().map_err(ContextCreationError::CouldNotCreateSdlContext)?;
         let sdl_video = sdl_context
             .video()
@@ -68,6 +110,15 @@ impl Sdl2GlContext {
         let gl_attr = sdl_video.gl_attr();
         gl_attr.set_context_profile(sdl2_gl_profile);
         gl_attr.set_context_version(gl_major_version, gl_minor_version);
+        gl_attr.set_stencil_size(8);
+        if renderer_config.msaa_samples > 0 {
+            gl_attr.set_multisample_buffers(1);
+            gl_attr.set_multisample_samples(renderer_config.msaa_samples);
+        }
+        // some drivers only report the debug messages of debug contexts
+        if renderer_config.debug_output {
+            gl_attr.set_context_flags().debug().set();
+        }
 
         let sdl_window = sdl_video
             .window(window_name, window_width, window_height)
@@ -99,8 +150,9 @@ impl Sdl2GlContext {
             Ok(Self {
                 sdl_context,
                 sdl_window,
-                _sdl_video: sdl_video,
-                _gl_context: gl_context,
+                sdl_video,
+                gl_context,
+                secondary_windows: BTreeMap::new(),
                 event_pump,
                 event_sender: EventSender::new(),
                 window_width_f32,
@@ -109,10 +161,55 @@ impl Sdl2GlContext {
         }
     }
 
+    // the window has to outlive the shared context
+    pub fn create_shared_gl_context(&self) -> Result<SharedGLContext, String> {
+        let gl_attr = self.sdl_video.gl_attr();
+        gl_attr.set_share_with_current_context(true);
+        let gl_context = self.sdl_window.gl_create_context();
+        gl_attr.set_share_with_current_context(false);
+
+        // creating the context makes it current, the main context has to be current on this thread
+        let gl_context =
+            gl_context.map_err(|e| format!("Creating shared gl context, msg = {e}"))?;
+        self.sdl_window
+            .gl_make_current(&self.gl_context)
+            .map_err(|e| format!("Creating shared gl context, msg = {e}"))?;
+
+        Ok(SharedGLContext {
+            sdl_window: self.sdl_window.raw(),
+            gl_context,
+        })
+    }
+
+    fn is_main_window(&self, window_id: u32) -> bool {
+        window_id == self.sdl_window.id()
+    }
+
     fn try_from_sdl2_event_to_event(&self, sdl2_event: sdl2_event::Event) -> Option<Event> {
         // log::trace!("SDL2_EVENT = {sdl2_event:?}");
 
         Some(match sdl2_event {
+            // the secondary windows only report that they were closed,
+            // their dimensions are queried by the renderer when it draws into them
+            sdl2_event::Event::Window {
+                window_id,
+                win_event,
+                ..
+            } if !self.is_main_window(window_id) => match win_event {
+                sdl2_event::WindowEvent::Close => Event::SecondaryWindowClosed {
+                    secondary_window_id: SecondaryWindowId(window_id as usize),
+                },
+                _ => None?,
+            },
+            // the mouse positions are relative to the window they happened in
+            sdl2_event::Event::MouseButtonDown { window_id, .. }
+            | sdl2_event::Event::MouseButtonUp { window_id, .. }
+            | sdl2_event::Event::MouseMotion { window_id, .. }
+            | sdl2_event::Event::MouseWheel { window_id, .. }
+                if !self.is_main_window(window_id) =>
+            {
+                None?
+            }
             sdl2_event::Event::Window { win_event, .. } => match win_event {
                 sdl2_event::WindowEvent::Resized(width, height) => Event::Resized {
                     width: width as usize,
@@ -259,6 +356,76 @@ impl WindowContext for Sdl2GlContext {
     fn swap_buffers(&self) {
         self.sdl_window.gl_swap_window()
     }
+
+    fn create_secondary_window(
+        &mut self,
+        title: &str,
+        dimensions: Vec2<usize>,
+    ) -> Result<SecondaryWindowId, String> {
+        // the gl attributes of the main window are still set, so the pixel format of the two windows
+        // is the same and the gl context of the main window can draw into the secondary one
+        let sdl_window = self
+            .sdl_video
+            .window(title, dimensions.x as u32, dimensions.y as u32)
+            .opengl()
+            .resizable()
+            .build()
+            .map_err(|e| format!("Creating secondary window, msg = {e}"))?;
+
+        let secondary_window_id = SecondaryWindowId(sdl_window.id() as usize);
+        self.secondary_windows
+            .insert(secondary_window_id, sdl_window);
+
+        Ok(secondary_window_id)
+    }
+
+    fn destroy_secondary_window(
+        &mut self,
+        secondary_window_id: SecondaryWindowId,
+    ) -> Result<(), String> {
+        self.secondary_windows
+            .remove(&secondary_window_id)
+            .ok_or_else(|| {
+                "Destroying secondary window, msg = could not find secondary window".to_string()
+            })
+            .map(|_| ())
+    }
+
+    fn secondary_window_dimensions(
+        &self,
+        secondary_window_id: SecondaryWindowId,
+    ) -> Option<Vec2<usize>> {
+        self.secondary_windows
+            .get(&secondary_window_id)
+            .map(|sdl_window| Vec2::new(sdl_window.size().0 as usize, sdl_window.size().1 as usize))
+    }
+
+    fn make_window_current(
+        &self,
+        secondary_window_id: Option<SecondaryWindowId>,
+    ) -> Result<(), String> {
+        let sdl_window = match secondary_window_id {
+            Some(secondary_window_id) => self
+                .secondary_windows
+                .get(&secondary_window_id)
+                .ok_or_else(|| {
+                    "Making window current, msg = could not find secondary window".to_string()
+                })?,
+            None => &self.sdl_window,
+        };
+
+        // a single gl context is used for every window, so the gl objects,
+        // including the vertex array objects that cannot be shared between contexts, are usable in all of them
+        sdl_window
+            .gl_make_current(&self.gl_context)
+            .map_err(|e| format!("Making window current, msg = {e}"))
+    }
+
+    fn swap_secondary_window_buffers(&self, secondary_window_id: SecondaryWindowId) {
+        if let Some(sdl_window) = self.secondary_windows.get(&secondary_window_id) {
+            sdl_window.gl_swap_window();
+        }
+    }
 }
 
 fn from_sdl_keycode_to_key(keycode: Keycode) -> Key {
@@ -1,7 +1,9 @@
+use muleengine::renderer::renderer_camera_parameters::RendererCameraParameters;
 use vek::{Mat4, Transform};
 
 pub(crate) struct GLCamera {
     pub transform: Transform<f32, f32, f32>,
+    pub parameters: Option<RendererCameraParameters>,
 }
 
 impl GLCamera {
@@ -3,3 +3,6 @@ pub mod renderer;
 pub mod renderer_group_object;
 pub mod renderer_layer_object;
 pub mod renderer_pipeline_step_object;
+pub mod renderer_probe_object;
+pub mod renderer_transform_hierarchy;
+pub mod renderer_window_object;
@@ -1,9 +1,15 @@
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    ops::Range,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use muleengine::{
     asset_container::AssetContainer,
     bytifex_utils::{
-        containers::object_pool::ObjectPool,
+        containers::object_pool::{ObjectPool, ObjectPoolIndex},
         sync::{
             observable_fn::{Observable, Observer},
             types::{
@@ -11,36 +17,87 @@ use muleengine::{
             },
         },
     },
-    mesh::{Material, Mesh},
+    image::Image,
+    mesh::{Material, MaterialTextureType, Mesh},
+    mesh_creator::rectangle3d,
+    profile_scope,
     renderer::{
-        renderer_impl::RendererImpl, renderer_pipeline_step_impl::RendererPipelineStepImpl,
+        particle_system::ParticleEmitterSettings,
+        renderer_camera_parameters::RendererCameraParameters,
+        renderer_config::RendererConfig,
+        renderer_debug_lines::{RendererDebugLine, RendererDebugLineBuffer},
+        renderer_debug_render_mode::RendererDebugRenderMode,
+        renderer_impl::RendererImpl,
+        renderer_interpolated_transform::RendererInterpolatedTransform,
+        renderer_layer_projection::RendererLayerProjection,
+        renderer_layer_settings::RendererLayerSettings,
+        renderer_layer_stencil::{
+            RendererLayerStencil, RendererStencilFunction, RendererStencilOperation,
+        },
+        renderer_mesh_vertices::RendererMeshVertices,
+        renderer_pipeline_step_impl::RendererPipelineStepImpl,
+        renderer_probe_refresh_mode::RendererProbeRefreshMode,
+        renderer_sprite::RendererSprite,
+        renderer_stats::RendererStats,
+        renderer_uniform_value::RendererUniformValue,
         RendererCamera, RendererGroup, RendererLayer, RendererMaterial, RendererMesh,
-        RendererObject, RendererShader, RendererTransform,
+        RendererObject, RendererProbe, RendererShader, RendererTexture, RendererTransform,
+        RendererWindow,
     },
     window_context::WindowContext,
 };
-use vek::{Transform, Vec2, Vec4};
+use vek::{Mat4, Transform, Vec2, Vec3, Vec4};
 
 use crate::{
-    gl_drawable_mesh::GLDrawableMesh,
-    gl_material::{GLMaterial, RendererMaterialObject},
-    gl_mesh::RendererMeshObject,
+    gl_debug_lines::GLDebugLines,
+    gl_draw_stats::GLDrawStats,
+    gl_drawable_mesh::{GLDrawableMesh, ShadowMapBinding},
+    gl_frame_uniforms::GLFrameUniforms,
+    gl_material::{GLMaterial, GLMaterialTexture, RendererMaterialObject},
+    gl_mesh::{GLMesh, RendererMeshObject},
     gl_mesh_container::GLMeshContainer,
-    gl_shader_program::RendererShaderObject,
+    gl_mesh_shader_program::GLMeshShaderProgram,
+    gl_occlusion_culler::GLOcclusionCuller,
+    gl_particle_emitter::GLParticleEmitter,
+    gl_pipeline_step_timer::GLPipelineStepTimer,
+    gl_shader_program::{GLShaderProgram, RendererShaderObject},
     gl_shader_program_container::GLShaderProgramContainer,
+    gl_sprite_batch::GLSpriteBatch,
     gl_texture_container::GLTextureContainer,
+    gl_upload_queue::GLUploadQueue,
     me_renderer_indices::{
         RendererCameraIndex, RendererGroupIndex, RendererLayerIndex, RendererMaterialIndex,
-        RendererMeshIndex, RendererObjectIndex, RendererShaderIndex, RendererTransformIndex,
+        RendererMeshIndex, RendererObjectIndex, RendererProbeIndex, RendererShaderIndex,
+        RendererTextureIndex, RendererTransformIndex, RendererWindowIndex,
+    },
+    opengl_utils::{
+        depth_texture_2d::DepthTexture2D,
+        frame_buffer_object::{
+            DepthFrameBufferObject, FrameBufferObject, MultisampleFrameBufferObject,
+        },
+        gl_debug_output::set_gl_debug_output_enabled,
+        gl_state_cache::with_gl_state_cache,
+        glsl_version::GLSLVersion,
+        texture_2d::{GLTextureAnisotropyMode, GLTextureMapMode, GLTextureSamplingMode, Texture2D},
+        texture_cubemap::TextureCubemap,
     },
 };
 
 use super::{
-    gl_camera::GLCamera, renderer_group_object::RendererGroupObject,
+    gl_camera::GLCamera,
+    renderer_group_object::RendererGroupObject,
     renderer_layer_object::RendererLayerObject,
-    renderer_pipeline_step_object::RendererPipelineStepObject,
+    renderer_pipeline_step_object::{
+        PostProcessFrameBuffers, RendererPipelineStepObject, MAX_POST_PROCESS_INPUT_TEXTURES,
+    },
+    renderer_probe_object::RendererProbeObject,
+    renderer_transform_hierarchy::{compose_transforms, RendererTransformHierarchy},
+    renderer_window_object::RendererWindowObject,
 };
 
+// walking the containers every frame is wasted work, the released resources can wait a bit
+const UNUSED_RESOURCES_RELEASE_INTERVAL: Duration = Duration::from_millis(500);
+
 type TransformObserver = Observer<Transform<f32, f32, f32>>;
 type MaterialObserver = Observer<RendererMaterialObject>;
 type ShaderObserver = Observer<RendererShaderObject>;
@@ -48,14 +105,23 @@ type MeshObserver = Observer<RendererMeshObject>;
 
 pub struct Renderer {
     renderer_pipeline_steps: Vec<RendererPipelineStepObject>,
+    post_process_frame_buffers: Option<PostProcessFrameBuffers>,
+    renderer_windows: ObjectPool<RcRwLock<RendererWindowObject>>,
 
     renderer_cameras: ObjectPool<(ArcRwLock<GLCamera>, TransformObserver)>,
-    renderer_layers: ObjectPool<RcRwLock<RendererLayerObject>>,
+    renderer_layers: ObjectPool<(RcRwLock<RendererLayerObject>, Option<ShaderObserver>)>,
     renderer_groups: ObjectPool<RcRwLock<RendererGroupObject>>,
     renderer_transforms: ObjectPool<RcRwLock<Observable<Transform<f32, f32, f32>>>>,
+    renderer_transform_hierarchy: RendererTransformHierarchy,
+    // the transforms that are lerped at render time, they are removed once the latest one is reached
+    interpolated_transforms: BTreeMap<ObjectPoolIndex, RendererInterpolatedTransform>,
     renderer_materials: ObjectPool<ArcRwLock<Observable<RendererMaterialObject>>>,
     renderer_shaders: ObjectPool<ArcRwLock<Observable<RendererShaderObject>>>,
     renderer_meshes: ObjectPool<RcRwLock<Observable<RendererMeshObject>>>,
+    renderer_probes: ObjectPool<(RcRwLock<RendererProbeObject>, TransformObserver)>,
+    renderer_textures: ObjectPool<(Arc<Texture2D>, Vec2<usize>)>,
+    image_textures: ObjectPool<Arc<Texture2D>>,
+    cubemap_textures: ObjectPool<Arc<TextureCubemap>>,
 
     mesh_renderer_objects: ObjectPool<(
         RcRwLock<GLDrawableMesh>,
@@ -64,8 +130,39 @@ pub struct Renderer {
         ShaderObserver,
         MeshObserver,
     )>,
-
-    screen_clear_color: Vec4<f32>,
+    // the observers of the farther lod meshes, the nearest one is observed by the mesh renderer object
+    lod_mesh_observers: BTreeMap<ObjectPoolIndex, Vec<MeshObserver>>,
+    instanced_renderer_objects: ObjectPool<(
+        RcRwLock<GLDrawableMesh>,
+        Vec<TransformObserver>,
+        MaterialObserver,
+        ShaderObserver,
+        MeshObserver,
+    )>,
+    skybox_renderer_objects: ObjectPool<RcRwLock<GLDrawableMesh>>,
+    skybox_gl_mesh: Option<Rc<GLMesh>>,
+    skybox_shader_program: Option<Arc<GLMeshShaderProgram>>,
+    particle_emitter_renderer_objects: ObjectPool<(
+        RcRwLock<GLParticleEmitter>,
+        TransformObserver,
+        MaterialObserver,
+    )>,
+    particle_shader_program: Option<Arc<GLShaderProgram>>,
+    sprite_batch_renderer_objects: ObjectPool<(RcRwLock<GLSpriteBatch>, TransformObserver)>,
+    sprite_shader_program: Option<Arc<GLShaderProgram>>,
+    // the particles are simulated with the time that passed since the previous frame
+    last_render_time: Option<Instant>,
+    last_unused_resources_release_time: Option<Instant>,
+
+    draw_stats: Rc<GLDrawStats>,
+    pipeline_step_timer: GLPipelineStepTimer,
+    frame_uniforms: Rc<GLFrameUniforms>,
+    renderer_config: RendererConfig,
+
+    debug_render_mode: RendererDebugRenderMode,
+    debug_normals_shader_program: Option<Arc<GLMeshShaderProgram>>,
+    debug_line_buffer: RendererDebugLineBuffer,
+    gl_debug_lines: Option<GLDebugLines>,
 
     window_dimensions: Vec2<usize>,
     window_context: ArcRwLock<dyn WindowContext>,
@@ -75,6 +172,7 @@ pub struct Renderer {
     gl_mesh_container: GLMeshContainer,
     gl_shader_program_container: ArcMutex<GLShaderProgramContainer>,
     gl_texture_container: GLTextureContainer,
+    gl_upload_queue: Option<RcRwLock<GLUploadQueue>>,
 }
 
 impl Renderer {
@@ -82,20 +180,50 @@ impl Renderer {
         window_context: ArcRwLock<dyn WindowContext>,
         asset_container: AssetContainer,
     ) -> Self {
+        // the renderer is created while the gl context is current
+        let glsl_version = GLSLVersion::detect();
+
         Self {
             renderer_pipeline_steps: Vec::new(),
+            post_process_frame_buffers: None,
+            renderer_windows: ObjectPool::new(),
 
             renderer_cameras: ObjectPool::new(),
             renderer_layers: ObjectPool::new(),
             renderer_groups: ObjectPool::new(),
             renderer_transforms: ObjectPool::new(),
+            renderer_transform_hierarchy: RendererTransformHierarchy::new(),
+            interpolated_transforms: BTreeMap::new(),
             renderer_materials: ObjectPool::new(),
             renderer_shaders: ObjectPool::new(),
             renderer_meshes: ObjectPool::new(),
+            renderer_probes: ObjectPool::new(),
+            renderer_textures: ObjectPool::new(),
+            image_textures: ObjectPool::new(),
+            cubemap_textures: ObjectPool::new(),
 
             mesh_renderer_objects: ObjectPool::new(),
-
-            screen_clear_color: Vec4::zero(),
+            lod_mesh_observers: BTreeMap::new(),
+            instanced_renderer_objects: ObjectPool::new(),
+            skybox_renderer_objects: ObjectPool::new(),
+            skybox_gl_mesh: None,
+            skybox_shader_program: None,
+            particle_emitter_renderer_objects: ObjectPool::new(),
+            particle_shader_program: None,
+            sprite_batch_renderer_objects: ObjectPool::new(),
+            sprite_shader_program: None,
+            last_render_time: None,
+            last_unused_resources_release_time: None,
+
+            draw_stats: Rc::new(GLDrawStats::new()),
+            pipeline_step_timer: GLPipelineStepTimer::new(glsl_version),
+            frame_uniforms: Rc::new(GLFrameUniforms::new()),
+            renderer_config: RendererConfig::default(),
+
+            debug_render_mode: RendererDebugRenderMode::default(),
+            debug_normals_shader_program: None,
+            debug_line_buffer: RendererDebugLineBuffer::new(),
+            gl_debug_lines: None,
 
             window_dimensions: Vec2::zero(),
             window_context,
@@ -103,11 +231,25 @@ impl Renderer {
             asset_container,
 
             gl_mesh_container: GLMeshContainer::new(),
-            gl_shader_program_container: arc_mutex_new(GLShaderProgramContainer::new()),
+            gl_shader_program_container: arc_mutex_new(GLShaderProgramContainer::new(glsl_version)),
             gl_texture_container: GLTextureContainer::new(),
+            gl_upload_queue: None,
         }
     }
 
+    // the meshes and the textures are uploaded on the thread of the upload queue,
+    // so creating them does not stall the frame, they are drawn after their upload finished
+    pub fn with_upload_queue(mut self, upload_queue: GLUploadQueue) -> Self {
+        let upload_queue = rc_rw_lock_new(upload_queue);
+        self.gl_mesh_container
+            .set_upload_queue(Some(upload_queue.clone()));
+        self.gl_texture_container
+            .set_upload_queue(Some(upload_queue.clone()));
+        self.gl_upload_queue = Some(upload_queue);
+
+        self
+    }
+
     fn get_renderer_layer_index(
         &self,
         renderer_layer: &ArcRwLock<dyn RendererLayer>,
@@ -204,114 +346,266 @@ impl Renderer {
             .cloned()
     }
 
-    fn ndc_to_ssc(&self, ndc: &Vec2<f32>) -> Vec2<f32> {
-        Vec2::new(
-            ndc.x * self.window_dimensions.x as f32,
-            ndc.y * self.window_dimensions.y as f32,
-        )
+    fn get_probe_index(
+        &self,
+        renderer_probe: &ArcRwLock<dyn RendererProbe>,
+    ) -> Result<RendererProbeIndex, String> {
+        let renderer_probe = renderer_probe.read();
+        renderer_probe
+            .as_any()
+            .downcast_ref::<RendererProbeIndex>()
+            .ok_or_else(|| "invalid RendererProbe provided".to_string())
+            .cloned()
     }
 
-    fn set_gl_viewport(&self, viewport_start_ndc: &Vec2<f32>, viewport_dimensions_ndc: &Vec2<f32>) {
-        let viewport_start_ssc = self.ndc_to_ssc(viewport_start_ndc);
-        let viewport_dimensions_ssc = self.ndc_to_ssc(viewport_dimensions_ndc);
-        unsafe {
-            gl::Viewport(
-                viewport_start_ssc.x as i32,
-                viewport_start_ssc.y as i32,
-                viewport_dimensions_ssc.x as i32,
-                viewport_dimensions_ssc.y as i32,
-            );
+    fn get_window_index(
+        &self,
+        renderer_window: &ArcRwLock<dyn RendererWindow>,
+    ) -> Result<RendererWindowIndex, String> {
+        let renderer_window = renderer_window.read();
+        renderer_window
+            .as_any()
+            .downcast_ref::<RendererWindowIndex>()
+            .ok_or_else(|| "invalid RendererWindow provided".to_string())
+            .cloned()
+    }
+
+    fn get_texture_index(
+        &self,
+        renderer_texture: &ArcRwLock<dyn RendererTexture>,
+    ) -> Result<RendererTextureIndex, String> {
+        let renderer_texture = renderer_texture.read();
+        renderer_texture
+            .as_any()
+            .downcast_ref::<RendererTextureIndex>()
+            .ok_or_else(|| "invalid RendererTexture provided".to_string())
+            .cloned()
+    }
+
+    fn get_render_texture_index(
+        &self,
+        renderer_texture: &ArcRwLock<dyn RendererTexture>,
+    ) -> Result<ObjectPoolIndex, String> {
+        match self.get_texture_index(renderer_texture)? {
+            RendererTextureIndex::RenderTexture(index) => Ok(index),
+            RendererTextureIndex::Image(_) => {
+                Err("an image texture cannot be used as a render texture".to_string())
+            }
+            RendererTextureIndex::Cubemap(_) => {
+                Err("a cubemap cannot be used as a render texture".to_string())
+            }
         }
     }
-}
 
-impl RendererImpl for Renderer {
-    fn render(&mut self) {
-        unsafe {
-            gl::ClearColor(
-                self.screen_clear_color.x,
-                self.screen_clear_color.y,
-                self.screen_clear_color.z,
-                self.screen_clear_color.w,
-            );
-            gl::Enable(gl::DEPTH_TEST);
-            gl::Enable(gl::BLEND);
-            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+    fn compute_world_transform(
+        &self,
+        index: ObjectPoolIndex,
+        local_transform: Transform<f32, f32, f32>,
+    ) -> Transform<f32, f32, f32> {
+        match self
+            .renderer_transform_hierarchy
+            .parent(index)
+            .and_then(|parent_index| self.renderer_transforms.get_ref(parent_index))
+        {
+            Some(parent_transform) => {
+                compose_transforms(&parent_transform.read(), &local_transform)
+            }
+            None => local_transform,
         }
+    }
 
-        for step in self.renderer_pipeline_steps.iter() {
-            match step {
-                RendererPipelineStepObject::Clear {
-                    depth,
-                    color,
-                    viewport_start_ndc,
-                    viewport_end_ndc: viewport_dimensions_ndc,
-                } => {
-                    self.set_gl_viewport(viewport_start_ndc, viewport_dimensions_ndc);
+    fn propagate_transform_to_children(&self, index: ObjectPoolIndex) {
+        let world_transform = match self.renderer_transforms.get_ref(index) {
+            Some(transform) => **transform.read(),
+            None => return,
+        };
 
-                    if *depth && *color {
-                        unsafe {
-                            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-                        }
-                    } else if *depth {
-                        unsafe {
-                            gl::Clear(gl::DEPTH_BUFFER_BIT);
-                        }
-                    } else if *color {
-                        unsafe {
-                            gl::Clear(gl::COLOR_BUFFER_BIT);
-                        }
-                    }
-                }
-                RendererPipelineStepObject::Draw {
-                    renderer_layer: renderer_layer_object,
-                    viewport_start_ndc,
-                    viewport_end_ndc: viewport_dimensions_ndc,
-                    projection_matrix,
-                    ..
-                } => {
-                    self.set_gl_viewport(viewport_start_ndc, viewport_dimensions_ndc);
+        for child_index in self.renderer_transform_hierarchy.children(index) {
+            let local_transform = self
+                .renderer_transform_hierarchy
+                .local_transform(child_index);
+            let child_transform = self.renderer_transforms.get_ref(child_index);
 
-                    renderer_layer_object.read().draw(projection_matrix);
-                }
+            if let (Some(local_transform), Some(child_transform)) =
+                (local_transform, child_transform)
+            {
+                *child_transform.write().borrow_mut() =
+                    compose_transforms(&world_transform, &local_transform);
+
+                self.propagate_transform_to_children(child_index);
             }
         }
-
-        self.window_context.read().swap_buffers();
     }
 
-    fn window_dimensions_changed(&mut self, width: usize, height: usize) -> Result<(), String> {
-        self.window_dimensions = Vec2::new(width, height);
+    fn clear_for_renderer_layer(&self, settings: &RendererLayerSettings) {
+        // the clear depth of reverse z is 0
+        with_gl_state_cache(|cache| cache.set_reverse_z(settings.reverse_z));
 
-        for step in self.renderer_pipeline_steps.iter_mut() {
-            if let RendererPipelineStepObject::Draw {
-                projection_matrix,
-                compute_projection_matrix,
-                ..
-            } = step
-            {
-                *projection_matrix = compute_projection_matrix(width, height);
+        let mut mask = 0;
+
+        if let Some(clear_color) = settings.clear_color {
+            unsafe {
+                gl::ClearColor(clear_color.x, clear_color.y, clear_color.z, clear_color.w);
             }
+            mask |= gl::COLOR_BUFFER_BIT;
         }
 
-        Ok(())
+        if settings.clear_depth {
+            mask |= gl::DEPTH_BUFFER_BIT;
+        }
+
+        if mask != 0 {
+            unsafe {
+                gl::Clear(mask);
+            }
+        }
+
+        if settings.clear_color.is_some() {
+            set_default_gl_clear_color();
+        }
     }
 
-    fn set_renderer_pipeline(
+    fn set_local_transform(
         &mut self,
-        steps: Vec<RendererPipelineStepImpl>,
+        index: ObjectPoolIndex,
+        new_transform: Transform<f32, f32, f32>,
     ) -> Result<(), String> {
-        self.renderer_pipeline_steps = Vec::with_capacity(steps.capacity());
+        self.renderer_transform_hierarchy
+            .set_local_transform(index, new_transform);
+        let world_transform = self.compute_world_transform(index, new_transform);
+
+        let transform = self
+            .renderer_transforms
+            .get_mut(index)
+            .ok_or_else(|| "could not find RendererTransform".to_string())?;
+
+        *transform.write().borrow_mut() = world_transform;
+
+        self.propagate_transform_to_children(index);
+
+        Ok(())
+    }
+
+    fn apply_interpolated_transforms(&mut self, now: Instant) {
+        let mut current_transforms = Vec::new();
+        self.interpolated_transforms
+            .retain(|index, interpolated_transform| {
+                current_transforms.push((*index, interpolated_transform.transform_at(now)));
+                !interpolated_transform.is_settled(now)
+            });
+
+        for (index, current_transform) in current_transforms {
+            if let Err(e) = self.set_local_transform(index, current_transform) {
+                log::warn!("Interpolating transform, msg = {e}");
+            }
+        }
+    }
+
+    // the gpu resources that are not referenced by any renderer object or material anymore
+    fn release_unused_resources_periodically(&mut self) {
+        let now = Instant::now();
+        let is_due = match self.last_unused_resources_release_time {
+            Some(last_release_time) => now - last_release_time >= UNUSED_RESOURCES_RELEASE_INTERVAL,
+            None => true,
+        };
+        if !is_due {
+            return;
+        }
+        self.last_unused_resources_release_time = Some(now);
+
+        self.gl_texture_container.evict_unused_textures();
+        self.gl_mesh_container.release_unused_meshes();
+        self.gl_shader_program_container
+            .lock()
+            .release_unused_shader_programs();
+    }
+
+    fn debug_shader_program(&self) -> Option<&Arc<GLMeshShaderProgram>> {
+        match self.debug_render_mode {
+            RendererDebugRenderMode::Normals => self.debug_normals_shader_program.as_ref(),
+            RendererDebugRenderMode::Normal | RendererDebugRenderMode::Wireframe => None,
+        }
+    }
+
+    // the screen position is in window pixels with the origin in the top left corner
+    fn compute_picking_ray(
+        &self,
+        screen_position: Vec2<f32>,
+        viewport_start_ndc: &Vec2<f32>,
+        viewport_dimensions_ndc: &Vec2<f32>,
+        view_projection_matrix: &Mat4<f32>,
+    ) -> Option<(Vec3<f32>, Vec3<f32>)> {
+        let window_position = Vec2::new(
+            screen_position.x / self.window_dimensions.x as f32,
+            1.0 - screen_position.y / self.window_dimensions.y as f32,
+        );
+        let viewport_position = (window_position - *viewport_start_ndc) / *viewport_dimensions_ndc;
+        if viewport_position.x < 0.0
+            || viewport_position.x > 1.0
+            || viewport_position.y < 0.0
+            || viewport_position.y > 1.0
+        {
+            return None;
+        }
+
+        let clip_position = viewport_position * 2.0 - Vec2::broadcast(1.0);
+        let inverse_view_projection_matrix = view_projection_matrix.inverted();
+        let unproject = |depth: f32| {
+            let position = inverse_view_projection_matrix
+                * Vec4::new(clip_position.x, clip_position.y, depth, 1.0);
+            position.xyz() / position.w
+        };
+
+        let near_position = unproject(0.0);
+        let far_position = unproject(1.0);
+
+        Some((
+            near_position,
+            (far_position - near_position).try_normalized()?,
+        ))
+    }
+
+    fn set_gl_viewport(
+        &self,
+        window_dimensions: Vec2<usize>,
+        viewport_start_ndc: &Vec2<f32>,
+        viewport_end_ndc: &Vec2<f32>,
+    ) {
+        let viewport_start_ssc = ndc_to_ssc(window_dimensions, viewport_start_ndc);
+        let viewport_dimensions_ssc = ndc_to_ssc(
+            window_dimensions,
+            &(*viewport_end_ndc - *viewport_start_ndc),
+        );
+        unsafe {
+            gl::Viewport(
+                viewport_start_ssc.x as i32,
+                viewport_start_ssc.y as i32,
+                viewport_dimensions_ssc.x as i32,
+                viewport_dimensions_ssc.y as i32,
+            );
+        }
+    }
+
+    fn create_pipeline_step_objects(
+        &self,
+        steps: Vec<RendererPipelineStepImpl>,
+        window_dimensions: Vec2<usize>,
+    ) -> Result<Vec<RendererPipelineStepObject>, String> {
+        let mut step_objects = Vec::with_capacity(steps.capacity());
         for step in steps {
             let step_object = match step {
                 RendererPipelineStepImpl::Clear {
                     depth,
                     color,
+                    stencil,
+                    clear_color,
                     viewport_start_ndc,
                     viewport_end_ndc,
                 } => RendererPipelineStepObject::Clear {
                     depth,
                     color,
+                    stencil,
+                    clear_color,
                     viewport_start_ndc,
                     viewport_end_ndc,
                 },
@@ -332,6 +626,7 @@ impl RendererImpl for Renderer {
                                 "Setting renderer pipeline, msg = could not find RendererLayer"
                                     .to_string()
                             })?
+                            .0
                             .clone()
                     };
 
@@ -339,572 +634,2918 @@ impl RendererImpl for Renderer {
                         renderer_layer,
                         viewport_start_ndc,
                         viewport_end_ndc,
-                        projection_matrix: compute_projection_matrix(
-                            self.window_dimensions.x,
-                            self.window_dimensions.y,
-                        ),
+                        projection_matrix: {
+                            let viewport_dimensions = viewport_dimensions_ssc(
+                                window_dimensions,
+                                viewport_start_ndc,
+                                viewport_end_ndc,
+                            );
+                            compute_projection_matrix(viewport_dimensions.x, viewport_dimensions.y)
+                        },
                         compute_projection_matrix,
                     }
                 }
-            };
+                RendererPipelineStepImpl::ShadowPass {
+                    renderer_layer,
+                    light_camera,
+                    resolution,
+                    light_projection_matrix,
+                } => {
+                    let renderer_layer = {
+                        let index = self
+                            .get_renderer_layer_index(&renderer_layer)
+                            .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
 
-            self.renderer_pipeline_steps.push(step_object);
-        }
+                        self.renderer_layers
+                            .get_ref(index.0)
+                            .ok_or_else(|| {
+                                "Setting renderer pipeline, msg = could not find RendererLayer"
+                                    .to_string()
+                            })?
+                            .0
+                            .clone()
+                    };
 
-        Ok(())
-    }
+                    let light_camera = {
+                        let index = self
+                            .get_camera_index(&light_camera)
+                            .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
 
-    fn create_renderer_layer(
-        &mut self,
-        camera: ArcRwLock<dyn RendererCamera>,
-    ) -> Result<ArcRwLock<dyn RendererLayer>, String> {
-        let camera = {
-            let index = self
-                .get_camera_index(&camera)
-                .map_err(|e| format!("Creating renderer layer, msg = {e}"))?;
+                        self.renderer_cameras
+                            .get_ref(index.0)
+                            .ok_or_else(|| {
+                                "Setting renderer pipeline, msg = could not find RendererCamera"
+                                    .to_string()
+                            })?
+                            .0
+                            .clone()
+                    };
 
-            &self
-                .renderer_cameras
-                .get_ref(index.0)
-                .ok_or_else(|| {
-                    "Creating renderer layer, msg = could not find RendererCamera".to_string()
-                })?
-                .0
-        };
+                    let shadow_map = Rc::new(DepthTexture2D::new(resolution));
+                    let frame_buffer_object = DepthFrameBufferObject::new(&shadow_map);
+                    if !frame_buffer_object.is_complete() {
+                        return Err(
+                            "Setting renderer pipeline, msg = shadow map framebuffer is incomplete"
+                                .to_string(),
+                        );
+                    }
 
-        let renderer_layer = rc_rw_lock_new(RendererLayerObject::new(camera.clone()));
-        let index = self.renderer_layers.create_object(renderer_layer);
+                    RendererPipelineStepObject::ShadowPass {
+                        renderer_layer,
+                        light_camera,
+                        light_projection_matrix,
+                        shadow_map,
+                        frame_buffer_object,
+                    }
+                }
+                RendererPipelineStepImpl::DrawToTexture {
+                    renderer_layer,
+                    renderer_texture,
+                    compute_projection_matrix,
+                } => {
+                    let renderer_layer = {
+                        let index = self
+                            .get_renderer_layer_index(&renderer_layer)
+                            .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
 
-        Ok(arc_rw_lock_new(RendererLayerIndex(index)))
-    }
+                        self.renderer_layers
+                            .get_ref(index.0)
+                            .ok_or_else(|| {
+                                "Setting renderer pipeline, msg = could not find RendererLayer"
+                                    .to_string()
+                            })?
+                            .0
+                            .clone()
+                    };
 
-    fn release_renderer_layer(
-        &mut self,
-        renderer_layer: ArcRwLock<dyn RendererLayer>,
-    ) -> Result<(), String> {
-        let index = self
-            .get_renderer_layer_index(&renderer_layer)
-            .map_err(|e| format!("Releasing renderer layer, msg = {e}"))?;
+                    let (render_texture, dimensions) = {
+                        let index = self
+                            .get_render_texture_index(&renderer_texture)
+                            .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
 
-        self.renderer_layers
-            .release_object(index.0)
-            .ok_or_else(|| {
-                "Releasing renderer layer, msg = could not find RendererLayer".to_string()
-            })
-            .map(|_| ())
-    }
+                        self.renderer_textures
+                            .get_ref(index)
+                            .ok_or_else(|| {
+                                "Setting renderer pipeline, msg = could not find RendererTexture"
+                                    .to_string()
+                            })?
+                            .clone()
+                    };
 
-    fn add_renderer_group_to_layer(
-        &mut self,
-        renderer_group: ArcRwLock<dyn RendererGroup>,
-        renderer_layer: ArcRwLock<dyn RendererLayer>,
-    ) -> Result<(), String> {
-        let renderer_group_index = self
-            .get_renderer_group_index(&renderer_group)
-            .map_err(|e| format!("Adding renderer group to layer, msg = {e}"))?;
+                    let frame_buffer_object = FrameBufferObject::new(dimensions.x, dimensions.y);
+                    frame_buffer_object.use_fbo(gl::TEXTURE_2D, render_texture.texture_id(), || {});
+                    if !frame_buffer_object.is_complete() {
+                        return Err(
+                            "Setting renderer pipeline, msg = render texture framebuffer is incomplete"
+                                .to_string(),
+                        );
+                    }
 
-        let renderer_group = self
-            .renderer_groups
-            .get_ref(renderer_group_index.0)
-            .ok_or_else(|| {
-                "Adding renderer group to layer, msg = could not find RendererGroup".to_string()
-            })?;
+                    let offscreen_msaa_samples =
+                        self.renderer_config.offscreen_msaa_samples as usize;
+                    let multisample_frame_buffer_object = if offscreen_msaa_samples > 1 {
+                        let multisample_frame_buffer_object = MultisampleFrameBufferObject::new(
+                            dimensions.x,
+                            dimensions.y,
+                            offscreen_msaa_samples,
+                        );
+                        if !multisample_frame_buffer_object.is_complete() {
+                            return Err(
+                                "Setting renderer pipeline, msg = multisampled render texture framebuffer is incomplete"
+                                    .to_string(),
+                            );
+                        }
 
-        let renderer_layer_index = self
-            .get_renderer_layer_index(&renderer_layer)
-            .map_err(|e| format!("Adding renderer group to layer, msg = {e}"))?;
+                        Some(multisample_frame_buffer_object)
+                    } else {
+                        None
+                    };
 
-        let renderer_layer = self
-            .renderer_layers
-            .get_ref(renderer_layer_index.0)
-            .ok_or_else(|| {
-                "Adding renderer group to layer, msg = could not find RendererLayer".to_string()
-            })?;
+                    RendererPipelineStepObject::DrawToTexture {
+                        renderer_layer,
+                        projection_matrix: compute_projection_matrix(dimensions.x, dimensions.y),
+                        render_texture,
+                        frame_buffer_object,
+                        multisample_frame_buffer_object,
+                    }
+                }
+                RendererPipelineStepImpl::PostProcess {
+                    shader,
+                    input_textures,
+                } => {
+                    let shader = {
+                        let index = self
+                            .get_shader_index(&shader)
+                            .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
 
-        if renderer_layer
-            .write()
-            .add_renderer_group(renderer_group.clone())
-            .is_some()
+                        self.renderer_shaders
+                            .get_ref(index.0)
+                            .ok_or_else(|| {
+                                "Setting renderer pipeline, msg = could not find RendererShader"
+                                    .to_string()
+                            })?
+                            .clone()
+                    };
+
+                    if input_textures.len() > MAX_POST_PROCESS_INPUT_TEXTURES {
+                        return Err(format!(
+                            "Setting renderer pipeline, msg = a post process step can have at most {MAX_POST_PROCESS_INPUT_TEXTURES} input textures"
+                        ));
+                    }
+
+                    let input_textures = input_textures
+                        .iter()
+                        .map(|input_texture| {
+                            let index = self
+                                .get_render_texture_index(input_texture)
+                                .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
+
+                            self.renderer_textures
+                                .get_ref(index)
+                                .map(|(texture, _dimensions)| texture.clone())
+                                .ok_or_else(|| {
+                                    "Setting renderer pipeline, msg = could not find RendererTexture"
+                                        .to_string()
+                                })
+                        })
+                        .collect::<Result<Vec<_>, String>>()?;
+
+                    RendererPipelineStepObject::PostProcess {
+                        shader,
+                        input_textures,
+                        is_last: false,
+                    }
+                }
+                RendererPipelineStepImpl::DrawDebugLines {
+                    renderer_layer,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                    compute_projection_matrix,
+                } => {
+                    let renderer_layer = {
+                        let index = self
+                            .get_renderer_layer_index(&renderer_layer)
+                            .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
+
+                        self.renderer_layers
+                            .get_ref(index.0)
+                            .ok_or_else(|| {
+                                "Setting renderer pipeline, msg = could not find RendererLayer"
+                                    .to_string()
+                            })?
+                            .0
+                            .clone()
+                    };
+
+                    RendererPipelineStepObject::DrawDebugLines {
+                        renderer_layer,
+                        viewport_start_ndc,
+                        viewport_end_ndc,
+                        projection_matrix: {
+                            let viewport_dimensions = viewport_dimensions_ssc(
+                                window_dimensions,
+                                viewport_start_ndc,
+                                viewport_end_ndc,
+                            );
+                            compute_projection_matrix(viewport_dimensions.x, viewport_dimensions.y)
+                        },
+                        compute_projection_matrix,
+                    }
+                }
+            };
+
+            step_objects.push(step_object);
+        }
+
+        Ok(step_objects)
+    }
+
+    fn create_gl_debug_lines(&mut self) -> Result<(), String> {
+        if self.gl_debug_lines.is_none() {
+            let gl_shader_program = self
+                .gl_shader_program_container
+                .lock()
+                .get_shader_program(
+                    "assets/shaders/debug_lines",
+                    self.asset_container.asset_reader(),
+                )
+                .map_err(|e| format!("{e:?}"))?;
+
+            self.gl_debug_lines = Some(GLDebugLines::new(gl_shader_program));
+        }
+
+        Ok(())
+    }
+
+    // only the steps of the main pipeline are timed
+    fn draw_pipeline_steps(
+        &self,
+        steps: &[RendererPipelineStepObject],
+        window_dimensions: Vec2<usize>,
+        step_timer: Option<&GLPipelineStepTimer>,
+    ) {
+        let mut shadow_map_binding = None;
+
+        for (step_index, step) in steps.iter().enumerate() {
+            if let Some(step_timer) = step_timer {
+                step_timer.begin_step(step_index);
+            }
+
+            match step {
+                RendererPipelineStepObject::Clear {
+                    depth,
+                    color,
+                    stencil,
+                    clear_color,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                } => {
+                    self.set_gl_viewport(window_dimensions, viewport_start_ndc, viewport_end_ndc);
+
+                    if *color {
+                        unsafe {
+                            gl::ClearColor(
+                                clear_color.x,
+                                clear_color.y,
+                                clear_color.z,
+                                clear_color.w,
+                            );
+                        }
+                    }
+
+                    let mut mask = 0;
+                    if *color {
+                        mask |= gl::COLOR_BUFFER_BIT;
+                    }
+                    if *depth {
+                        mask |= gl::DEPTH_BUFFER_BIT;
+                    }
+                    if *stencil {
+                        mask |= gl::STENCIL_BUFFER_BIT;
+                    }
+
+                    if mask != 0 {
+                        unsafe {
+                            gl::Clear(mask);
+                        }
+                    }
+
+                    if *color {
+                        set_default_gl_clear_color();
+                    }
+                }
+                RendererPipelineStepObject::Draw {
+                    renderer_layer: renderer_layer_object,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                    projection_matrix,
+                    ..
+                } => {
+                    self.set_gl_viewport(window_dimensions, viewport_start_ndc, viewport_end_ndc);
+
+                    let renderer_layer_object = renderer_layer_object.read();
+                    self.clear_for_renderer_layer(renderer_layer_object.settings());
+                    set_gl_stencil(renderer_layer_object.settings().stencil.as_ref());
+
+                    let viewport_dimensions = viewport_dimensions_ssc(
+                        window_dimensions,
+                        *viewport_start_ndc,
+                        *viewport_end_ndc,
+                    );
+                    let projection_matrix = renderer_layer_object.projection_matrix_or(
+                        viewport_dimensions.x,
+                        viewport_dimensions.y,
+                        projection_matrix,
+                    );
+                    renderer_layer_object.draw(
+                        &projection_matrix,
+                        shadow_map_binding.as_ref(),
+                        self.debug_shader_program(),
+                    );
+
+                    set_gl_stencil(None);
+                }
+                RendererPipelineStepObject::ShadowPass {
+                    renderer_layer: renderer_layer_object,
+                    light_camera,
+                    light_projection_matrix,
+                    shadow_map,
+                    frame_buffer_object,
+                } => {
+                    let light_camera = light_camera.read();
+                    let view_matrix = light_camera.compute_view_matrix();
+
+                    frame_buffer_object.use_fbo(|| {
+                        unsafe {
+                            gl::Clear(gl::DEPTH_BUFFER_BIT);
+                        }
+
+                        renderer_layer_object.read().draw_from(
+                            &light_camera.transform.position,
+                            light_projection_matrix,
+                            &view_matrix,
+                            None,
+                        );
+                    });
+
+                    shadow_map_binding = Some(ShadowMapBinding {
+                        light_space_matrix: *light_projection_matrix * view_matrix,
+                        shadow_map: shadow_map.clone(),
+                    });
+                }
+                RendererPipelineStepObject::DrawToTexture {
+                    renderer_layer: renderer_layer_object,
+                    projection_matrix,
+                    render_texture,
+                    frame_buffer_object,
+                    multisample_frame_buffer_object,
+                } => {
+                    let draw_renderer_layer = || {
+                        unsafe {
+                            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                        }
+
+                        let renderer_layer_object = renderer_layer_object.read();
+                        self.clear_for_renderer_layer(renderer_layer_object.settings());
+
+                        let projection_matrix = renderer_layer_object.projection_matrix_or(
+                            frame_buffer_object.width(),
+                            frame_buffer_object.height(),
+                            projection_matrix,
+                        );
+                        renderer_layer_object.draw(
+                            &projection_matrix,
+                            shadow_map_binding.as_ref(),
+                            self.debug_shader_program(),
+                        );
+                    };
+
+                    match multisample_frame_buffer_object {
+                        Some(multisample_frame_buffer_object) => {
+                            multisample_frame_buffer_object.use_fbo(|| {
+                                draw_renderer_layer();
+
+                                multisample_frame_buffer_object.resolve_into(
+                                    frame_buffer_object,
+                                    gl::TEXTURE_2D,
+                                    render_texture.texture_id(),
+                                );
+                            });
+                        }
+                        None => frame_buffer_object.use_fbo(
+                            gl::TEXTURE_2D,
+                            render_texture.texture_id(),
+                            draw_renderer_layer,
+                        ),
+                    }
+                }
+                RendererPipelineStepObject::PostProcess {
+                    shader,
+                    input_textures,
+                    is_last,
+                } => {
+                    if let Some(post_process_frame_buffers) = &self.post_process_frame_buffers {
+                        post_process_frame_buffers.draw(
+                            shader.read().gl_shader_program(),
+                            input_textures,
+                            *is_last,
+                            &self.draw_stats,
+                        );
+                    }
+
+                    // the fullscreen pass is always filled
+                    if self.debug_render_mode == RendererDebugRenderMode::Wireframe {
+                        with_gl_state_cache(|cache| cache.set_polygon_mode(gl::LINE));
+                    }
+                }
+                RendererPipelineStepObject::DrawDebugLines {
+                    renderer_layer: renderer_layer_object,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                    projection_matrix,
+                    ..
+                } => {
+                    if let Some(gl_debug_lines) = &self.gl_debug_lines {
+                        self.set_gl_viewport(
+                            window_dimensions,
+                            viewport_start_ndc,
+                            viewport_end_ndc,
+                        );
+
+                        let renderer_layer_object = renderer_layer_object.read();
+                        let viewport_dimensions = viewport_dimensions_ssc(
+                            window_dimensions,
+                            *viewport_start_ndc,
+                            *viewport_end_ndc,
+                        );
+                        let projection_matrix = renderer_layer_object.projection_matrix_or(
+                            viewport_dimensions.x,
+                            viewport_dimensions.y,
+                            projection_matrix,
+                        );
+                        gl_debug_lines.draw(
+                            &projection_matrix,
+                            &renderer_layer_object.compute_view_matrix(),
+                            &self.draw_stats,
+                        );
+                    }
+                }
+            }
+
+            if let Some(step_timer) = step_timer {
+                step_timer.end_step(step_index);
+            }
+        }
+    }
+
+    // the secondary windows are drawn with the gl context of the main window, one after the other
+    fn draw_windows(&mut self) {
+        let renderer_windows = self
+            .renderer_windows
+            .iter_mut()
+            .map(|renderer_window| renderer_window.clone())
+            .collect::<Vec<_>>();
+        if renderer_windows.is_empty() {
+            return;
+        }
+
+        let window_context = self.window_context.read();
+        for renderer_window in renderer_windows {
+            let mut renderer_window = renderer_window.write();
+
+            // the secondary windows do not report their resizes, so their dimensions are polled
+            if let Some(window_dimensions) =
+                window_context.secondary_window_dimensions(renderer_window.secondary_window_id)
+            {
+                if window_dimensions != renderer_window.window_dimensions {
+                    renderer_window.window_dimensions = window_dimensions;
+                    update_projection_matrices(
+                        &mut renderer_window.pipeline_steps,
+                        window_dimensions,
+                    );
+                }
+            }
+
+            if let Err(e) =
+                window_context.make_window_current(Some(renderer_window.secondary_window_id))
+            {
+                log::error!("Drawing window, msg = {e}");
+                continue;
+            }
+
+            self.draw_pipeline_steps(
+                &renderer_window.pipeline_steps,
+                renderer_window.window_dimensions,
+                None,
+            );
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+
+            window_context.swap_secondary_window_buffers(renderer_window.secondary_window_id);
+        }
+
+        let _ = window_context
+            .make_window_current(None)
+            .inspect_err(|e| log::error!("Drawing window, msg = {e}"));
+    }
+}
+
+fn ndc_to_ssc(window_dimensions: Vec2<usize>, ndc: &Vec2<f32>) -> Vec2<f32> {
+    Vec2::new(
+        ndc.x * window_dimensions.x as f32,
+        ndc.y * window_dimensions.y as f32,
+    )
+}
+
+// the projection of a viewport is computed from its own dimensions,
+// so the viewports of a split screen keep their aspect ratio
+fn viewport_dimensions_ssc(
+    window_dimensions: Vec2<usize>,
+    viewport_start_ndc: Vec2<f32>,
+    viewport_end_ndc: Vec2<f32>,
+) -> Vec2<usize> {
+    let viewport_dimensions_ndc = viewport_end_ndc - viewport_start_ndc;
+
+    Vec2::new(
+        ((viewport_dimensions_ndc.x * window_dimensions.x as f32).round() as usize).max(1),
+        ((viewport_dimensions_ndc.y * window_dimensions.y as f32).round() as usize).max(1),
+    )
+}
+
+// the clears outside of the clear steps (e.g. render textures, probes) use transparent black
+fn set_default_gl_clear_color() {
+    unsafe {
+        gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+    }
+}
+
+fn gl_stencil_function(function: RendererStencilFunction) -> gl::types::GLenum {
+    match function {
+        RendererStencilFunction::Never => gl::NEVER,
+        RendererStencilFunction::Less => gl::LESS,
+        RendererStencilFunction::LessOrEqual => gl::LEQUAL,
+        RendererStencilFunction::Greater => gl::GREATER,
+        RendererStencilFunction::GreaterOrEqual => gl::GEQUAL,
+        RendererStencilFunction::Equal => gl::EQUAL,
+        RendererStencilFunction::NotEqual => gl::NOTEQUAL,
+        RendererStencilFunction::Always => gl::ALWAYS,
+    }
+}
+
+fn gl_stencil_operation(operation: RendererStencilOperation) -> gl::types::GLenum {
+    match operation {
+        RendererStencilOperation::Keep => gl::KEEP,
+        RendererStencilOperation::Zero => gl::ZERO,
+        RendererStencilOperation::Replace => gl::REPLACE,
+        RendererStencilOperation::Increment => gl::INCR,
+        RendererStencilOperation::IncrementWrap => gl::INCR_WRAP,
+        RendererStencilOperation::Decrement => gl::DECR,
+        RendererStencilOperation::DecrementWrap => gl::DECR_WRAP,
+        RendererStencilOperation::Invert => gl::INVERT,
+    }
+}
+
+// without a stencil the write mask is restored, because it masks the clears of the stencil buffer too
+fn set_gl_stencil(stencil: Option<&RendererLayerStencil>) {
+    with_gl_state_cache(|cache| cache.set_capability(gl::STENCIL_TEST, stencil.is_some()));
+
+    unsafe {
+        match stencil {
+            Some(stencil) => {
+                gl::StencilFunc(
+                    gl_stencil_function(stencil.function),
+                    stencil.reference as i32,
+                    stencil.test_mask as u32,
+                );
+                gl::StencilOp(
+                    gl_stencil_operation(stencil.stencil_fail),
+                    gl_stencil_operation(stencil.depth_fail),
+                    gl_stencil_operation(stencil.depth_pass),
+                );
+                gl::StencilMask(stencil.write_mask as u32);
+            }
+            None => gl::StencilMask(u8::MAX as u32),
+        }
+    }
+}
+
+fn has_debug_lines_step(steps: &[RendererPipelineStepObject]) -> bool {
+    steps
+        .iter()
+        .any(|step| matches!(step, RendererPipelineStepObject::DrawDebugLines { .. }))
+}
+
+fn update_projection_matrices(
+    steps: &mut [RendererPipelineStepObject],
+    window_dimensions: Vec2<usize>,
+) {
+    for step in steps.iter_mut() {
+        match step {
+            RendererPipelineStepObject::Draw {
+                viewport_start_ndc,
+                viewport_end_ndc,
+                projection_matrix,
+                compute_projection_matrix,
+                ..
+            }
+            | RendererPipelineStepObject::DrawDebugLines {
+                viewport_start_ndc,
+                viewport_end_ndc,
+                projection_matrix,
+                compute_projection_matrix,
+                ..
+            } => {
+                let viewport_dimensions = viewport_dimensions_ssc(
+                    window_dimensions,
+                    *viewport_start_ndc,
+                    *viewport_end_ndc,
+                );
+                *projection_matrix =
+                    compute_projection_matrix(viewport_dimensions.x, viewport_dimensions.y);
+            }
+            _ => (),
+        }
+    }
+}
+
+impl RendererImpl for Renderer {
+    fn render(&mut self) {
+        profile_scope!("renderer.render");
+
+        if let Some(upload_queue) = &self.gl_upload_queue {
+            upload_queue.write().poll_completed_uploads();
+        }
+
+        self.release_unused_resources_periodically();
+        self.draw_stats.reset();
+
+        // the window context may have touched the gl state between the frames
+        with_gl_state_cache(|cache| {
+            cache.invalidate();
+
+            cache.set_capability(gl::DEPTH_TEST, true);
+            cache.set_capability(gl::BLEND, true);
+            cache.set_blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            cache.set_reverse_z(false);
+        });
+
+        set_default_gl_clear_color();
+
+        let now = Instant::now();
+        let delta_secs = self
+            .last_render_time
+            .map(|last_render_time| (now - last_render_time).as_secs_f32())
+            .unwrap_or(0.0);
+        self.last_render_time = Some(now);
+
+        self.apply_interpolated_transforms(now);
+
+        for (particle_emitter, ..) in self.particle_emitter_renderer_objects.iter_mut() {
+            particle_emitter.write().update(delta_secs);
+        }
+
+        for (probe, _transform_observer) in self.renderer_probes.iter_mut() {
+            probe.write().refresh_if_needed();
+        }
+
+        if self.debug_render_mode == RendererDebugRenderMode::Wireframe {
+            with_gl_state_cache(|cache| cache.set_polygon_mode(gl::LINE));
+        }
+
+        if let Some(post_process_frame_buffers) = &self.post_process_frame_buffers {
+            post_process_frame_buffers.begin_frame();
+        }
+
+        if let Some(gl_debug_lines) = &mut self.gl_debug_lines {
+            gl_debug_lines.set_lines(self.debug_line_buffer.lines());
+        }
+
+        self.pipeline_step_timer
+            .begin_frame(self.renderer_pipeline_steps.len());
+        self.draw_pipeline_steps(
+            &self.renderer_pipeline_steps,
+            self.window_dimensions,
+            Some(&self.pipeline_step_timer),
+        );
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        self.window_context.read().swap_buffers();
+
+        self.draw_windows();
+
+        with_gl_state_cache(|cache| cache.set_polygon_mode(gl::FILL));
+
+        self.debug_line_buffer.remove_expired_lines(Instant::now());
+    }
+
+    fn window_dimensions_changed(&mut self, width: usize, height: usize) -> Result<(), String> {
+        self.window_dimensions = Vec2::new(width, height);
+
+        update_projection_matrices(&mut self.renderer_pipeline_steps, self.window_dimensions);
+
+        if self.post_process_frame_buffers.is_some() {
+            self.post_process_frame_buffers = Some(
+                PostProcessFrameBuffers::new(
+                    self.window_dimensions,
+                    self.renderer_config.offscreen_msaa_samples as usize,
+                )
+                .map_err(|e| format!("Changing window dimensions, msg = {e}"))?,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn set_renderer_config(&mut self, renderer_config: RendererConfig) -> Result<(), String> {
+        self.renderer_config = renderer_config;
+
+        with_gl_state_cache(|cache| {
+            cache.set_capability(gl::MULTISAMPLE, renderer_config.msaa_samples > 0)
+        });
+
+        if !set_gl_debug_output_enabled(renderer_config.debug_output)
+            && renderer_config.debug_output
         {
-            Err("Adding renderer object to group, msg = cannot add renderer object twice to the same group".to_string())
-        } else {
-            Ok(())
+            log::warn!(
+                "Setting renderer config, msg = the gl context does not provide debug output"
+            );
+        }
+
+        // the render texture steps get their multisampled framebuffers when the pipeline is set
+        if self.post_process_frame_buffers.is_some() {
+            self.post_process_frame_buffers = Some(
+                PostProcessFrameBuffers::new(
+                    self.window_dimensions,
+                    self.renderer_config.offscreen_msaa_samples as usize,
+                )
+                .map_err(|e| format!("Setting renderer config, msg = {e}"))?,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn set_renderer_pipeline(
+        &mut self,
+        steps: Vec<RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        self.renderer_pipeline_steps =
+            self.create_pipeline_step_objects(steps, self.window_dimensions)?;
+        self.pipeline_step_timer.reset();
+
+        let last_post_process_step = self
+            .renderer_pipeline_steps
+            .iter_mut()
+            .filter(|step| matches!(step, RendererPipelineStepObject::PostProcess { .. }))
+            .last();
+        self.post_process_frame_buffers = match last_post_process_step {
+            Some(RendererPipelineStepObject::PostProcess { is_last, .. }) => {
+                *is_last = true;
+
+                Some(
+                    PostProcessFrameBuffers::new(
+                        self.window_dimensions,
+                        self.renderer_config.offscreen_msaa_samples as usize,
+                    )
+                    .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?,
+                )
+            }
+            _ => None,
+        };
+
+        if has_debug_lines_step(&self.renderer_pipeline_steps) {
+            self.create_gl_debug_lines()
+                .map_err(|e| format!("Setting renderer pipeline, msg = {e}"))?;
+        }
+
+        Ok(())
+    }
+
+    fn set_pipeline_clear_color(
+        &mut self,
+        step_index: usize,
+        clear_color: Vec4<f32>,
+    ) -> Result<(), String> {
+        match self.renderer_pipeline_steps.get_mut(step_index) {
+            Some(RendererPipelineStepObject::Clear {
+                clear_color: step_clear_color,
+                ..
+            }) => {
+                *step_clear_color = clear_color;
+                Ok(())
+            }
+            Some(_) => Err(format!(
+                "Setting pipeline clear color, msg = the step is not a clear step, step_index = {step_index}"
+            )),
+            None => Err(format!(
+                "Setting pipeline clear color, msg = could not find the step, step_index = {step_index}"
+            )),
         }
     }
 
-    fn remove_renderer_group_from_layer(
+    fn create_window(
+        &mut self,
+        title: &str,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererWindow>, String> {
+        let secondary_window_id = self
+            .window_context
+            .write()
+            .create_secondary_window(title, dimensions)
+            .map_err(|e| format!("Creating window, msg = {e}"))?;
+
+        // the window manager may not give the requested dimensions
+        let window_dimensions = self
+            .window_context
+            .read()
+            .secondary_window_dimensions(secondary_window_id)
+            .unwrap_or(dimensions);
+
+        let index = self
+            .renderer_windows
+            .create_object(rc_rw_lock_new(RendererWindowObject {
+                secondary_window_id,
+                window_dimensions,
+                pipeline_steps: Vec::new(),
+            }));
+
+        Ok(arc_rw_lock_new(RendererWindowIndex(index)))
+    }
+
+    fn set_window_pipeline(
+        &mut self,
+        window: ArcRwLock<dyn RendererWindow>,
+        steps: Vec<RendererPipelineStepImpl>,
+    ) -> Result<(), String> {
+        // the post process framebuffers have the dimensions of the main window
+        if steps
+            .iter()
+            .any(|step| matches!(step, RendererPipelineStepImpl::PostProcess { .. }))
+        {
+            return Err(
+                "Setting window pipeline, msg = post process steps are only supported in the main window"
+                    .to_string(),
+            );
+        }
+
+        let index = self
+            .get_window_index(&window)
+            .map_err(|e| format!("Setting window pipeline, msg = {e}"))?;
+
+        let renderer_window = self
+            .renderer_windows
+            .get_ref(index.0)
+            .ok_or_else(|| {
+                "Setting window pipeline, msg = could not find RendererWindow".to_string()
+            })?
+            .clone();
+
+        let window_dimensions = renderer_window.read().window_dimensions;
+        let pipeline_steps = self
+            .create_pipeline_step_objects(steps, window_dimensions)
+            .map_err(|e| format!("Setting window pipeline, msg = {e}"))?;
+
+        if has_debug_lines_step(&pipeline_steps) {
+            self.create_gl_debug_lines()
+                .map_err(|e| format!("Setting window pipeline, msg = {e}"))?;
+        }
+
+        renderer_window.write().pipeline_steps = pipeline_steps;
+
+        Ok(())
+    }
+
+    fn release_window(&mut self, window: ArcRwLock<dyn RendererWindow>) -> Result<(), String> {
+        let index = self
+            .get_window_index(&window)
+            .map_err(|e| format!("Releasing window, msg = {e}"))?;
+
+        let renderer_window = self
+            .renderer_windows
+            .release_object(index.0)
+            .ok_or_else(|| "Releasing window, msg = could not find RendererWindow".to_string())?;
+
+        let secondary_window_id = renderer_window.read().secondary_window_id;
+        self.window_context
+            .write()
+            .destroy_secondary_window(secondary_window_id)
+            .map_err(|e| format!("Releasing window, msg = {e}"))
+    }
+
+    fn create_renderer_layer(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+    ) -> Result<ArcRwLock<dyn RendererLayer>, String> {
+        let camera = {
+            let index = self
+                .get_camera_index(&camera)
+                .map_err(|e| format!("Creating renderer layer, msg = {e}"))?;
+
+            &self
+                .renderer_cameras
+                .get_ref(index.0)
+                .ok_or_else(|| {
+                    "Creating renderer layer, msg = could not find RendererCamera".to_string()
+                })?
+                .0
+        };
+
+        let renderer_layer = rc_rw_lock_new(RendererLayerObject::new(
+            camera.clone(),
+            self.draw_stats.clone(),
+            self.frame_uniforms.clone(),
+        ));
+        let index = self.renderer_layers.create_object((renderer_layer, None));
+
+        Ok(arc_rw_lock_new(RendererLayerIndex(index)))
+    }
+
+    fn release_renderer_layer(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Releasing renderer layer, msg = {e}"))?;
+
+        self.renderer_layers
+            .release_object(index.0)
+            .ok_or_else(|| {
+                "Releasing renderer layer, msg = could not find RendererLayer".to_string()
+            })
+            .map(|_| ())
+    }
+
+    fn set_renderer_layer_settings(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        settings: RendererLayerSettings,
+    ) -> Result<(), String> {
+        let index = self
+            .get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Setting renderer layer settings, msg = {e}"))?;
+
+        let renderer_layer = self
+            .renderer_layers
+            .get_ref(index.0)
+            .ok_or_else(|| {
+                "Setting renderer layer settings, msg = could not find RendererLayer".to_string()
+            })?
+            .0
+            .clone();
+        let mut renderer_layer = renderer_layer.write();
+
+        if !settings.occlusion_culling {
+            renderer_layer.set_occlusion_culler(None);
+        } else if !renderer_layer.has_occlusion_culler() {
+            let gl_shader_program = self
+                .gl_shader_program_container
+                .lock()
+                .get_shader_program(
+                    "assets/shaders/occlusion_box",
+                    self.asset_container.asset_reader(),
+                )
+                .map_err(|e| format!("Setting renderer layer settings, msg = {e:?}"))?;
+
+            renderer_layer.set_occlusion_culler(Some(GLOcclusionCuller::new(gl_shader_program)));
+        }
+
+        renderer_layer.set_settings(settings);
+
+        Ok(())
+    }
+
+    fn update_renderer_layer_projection(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        projection: Option<RendererLayerProjection>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Updating renderer layer projection, msg = {e}"))?;
+
+        self.renderer_layers
+            .get_ref(index.0)
+            .ok_or_else(|| {
+                "Updating renderer layer projection, msg = could not find RendererLayer".to_string()
+            })?
+            .0
+            .write()
+            .set_projection(projection);
+
+        Ok(())
+    }
+
+    fn set_renderer_layer_override_shader(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        renderer_shader: Option<ArcRwLock<dyn RendererShader>>,
+    ) -> Result<(), String> {
+        let shader = match renderer_shader {
+            Some(renderer_shader) => {
+                let index = self
+                    .get_shader_index(&renderer_shader)
+                    .map_err(|e| format!("Setting override shader of renderer layer, msg = {e}"))?;
+
+                Some(self.renderer_shaders.get_ref(index.0).ok_or_else(|| {
+                    "Setting override shader of renderer layer, msg = could not find RendererShader"
+                        .to_string()
+                })?)
+            }
+            None => None,
+        };
+
+        let index = self
+            .get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Setting override shader of renderer layer, msg = {e}"))?;
+
+        let (renderer_layer, shader_observer) =
+            self.renderer_layers.get_mut(index.0).ok_or_else(|| {
+                "Setting override shader of renderer layer, msg = could not find RendererLayer"
+                    .to_string()
+            })?;
+
+        match shader {
+            Some(shader) => {
+                let gl_mesh_shader_program = self
+                    .gl_shader_program_container
+                    .lock()
+                    .get_mesh_shader_program(shader.read().gl_shader_program().clone());
+                renderer_layer
+                    .write()
+                    .set_override_shader_program(Some(gl_mesh_shader_program));
+
+                let renderer_layer = renderer_layer.clone();
+                let gl_shader_program_container = self.gl_shader_program_container.clone();
+                *shader_observer = Some(shader.write().observe(move |shader| {
+                    let gl_mesh_shader_program = gl_shader_program_container
+                        .lock()
+                        .get_mesh_shader_program(shader.gl_shader_program().clone());
+                    renderer_layer
+                        .write()
+                        .set_override_shader_program(Some(gl_mesh_shader_program));
+                }));
+            }
+            None => {
+                renderer_layer.write().set_override_shader_program(None);
+                *shader_observer = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_renderer_group_to_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let renderer_group_index = self
+            .get_renderer_group_index(&renderer_group)
+            .map_err(|e| format!("Adding renderer group to layer, msg = {e}"))?;
+
+        let renderer_group = self
+            .renderer_groups
+            .get_ref(renderer_group_index.0)
+            .ok_or_else(|| {
+                "Adding renderer group to layer, msg = could not find RendererGroup".to_string()
+            })?;
+
+        let renderer_layer_index = self
+            .get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Adding renderer group to layer, msg = {e}"))?;
+
+        let renderer_layer = self
+            .renderer_layers
+            .get_ref(renderer_layer_index.0)
+            .ok_or_else(|| {
+                "Adding renderer group to layer, msg = could not find RendererLayer".to_string()
+            })?;
+
+        if renderer_layer
+            .0
+            .write()
+            .add_renderer_group(renderer_group.clone())
+            .is_some()
+        {
+            Err("Adding renderer object to group, msg = cannot add renderer object twice to the same group".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn remove_renderer_group_from_layer(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+    ) -> Result<(), String> {
+        let renderer_group_index = self
+            .get_renderer_group_index(&renderer_group)
+            .map_err(|e| format!("Removing renderer group from layer, msg = {e}"))?;
+
+        let renderer_group = self
+            .renderer_groups
+            .get_ref(renderer_group_index.0)
+            .ok_or_else(|| {
+                "Removing renderer group from layer, msg = could not find RendererGroup".to_string()
+            })?;
+
+        let renderer_layer_index = self
+            .get_renderer_layer_index(&renderer_layer)
+            .map_err(|e| format!("Removing renderer group from layer, msg = {e}"))?;
+
+        let renderer_layer = self
+            .renderer_layers
+            .get_ref(renderer_layer_index.0)
+            .ok_or_else(|| {
+                "Removing renderer group from layer, msg = could not find RendererLayer".to_string()
+            })?;
+
+        if renderer_layer
+            .0
+            .write()
+            .remove_renderer_group(renderer_group)
+            .is_none()
+        {
+            Err(
+                "Removing renderer group from layer, msg = could not find renderer group in layer"
+                    .to_string(),
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    fn create_renderer_group(&mut self) -> Result<ArcRwLock<dyn RendererGroup>, String> {
+        let renderer_group = rc_rw_lock_new(RendererGroupObject::new());
+        let index = self.renderer_groups.create_object(renderer_group);
+
+        Ok(arc_rw_lock_new(RendererGroupIndex(index)))
+    }
+
+    fn release_renderer_group(
+        &mut self,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_renderer_group_index(&renderer_group)
+            .map_err(|e| format!("Releasing renderer group, msg = {e}"))?;
+
+        self.renderer_groups
+            .release_object(index.0)
+            .ok_or_else(|| {
+                "Releasing renderer group, msg = could not find RendererGroup".to_string()
+            })
+            .map(|_| ())
+    }
+
+    fn create_transform(
+        &mut self,
+        transform: Transform<f32, f32, f32>,
+    ) -> Result<ArcRwLock<dyn RendererTransform>, String> {
+        let index = self
+            .renderer_transforms
+            .create_object(rc_rw_lock_new(Observable::new(transform)));
+
+        Ok(arc_rw_lock_new(RendererTransformIndex(index)))
+    }
+
+    fn update_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_transform_index(&transform)
+            .map_err(|e| format!("Updating transform, msg = {e}"))?;
+
+        self.interpolated_transforms.remove(&index.0);
+
+        self.set_local_transform(index.0, new_transform)
+            .map_err(|e| format!("Updating transform, msg = {e}"))
+    }
+
+    fn update_transform_interpolated(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        new_transform: Transform<f32, f32, f32>,
+        timestamp: Instant,
+    ) -> Result<(), String> {
+        let index = self
+            .get_transform_index(&transform)
+            .map_err(|e| format!("Updating interpolated transform, msg = {e}"))?;
+
+        let interpolated_transform = self
+            .interpolated_transforms
+            .entry(index.0)
+            .and_modify(|interpolated_transform| {
+                interpolated_transform.submit(timestamp, new_transform)
+            })
+            .or_insert_with(|| RendererInterpolatedTransform::new(timestamp, new_transform));
+        let current_transform = interpolated_transform.transform_at(Instant::now());
+
+        self.set_local_transform(index.0, current_transform)
+            .map_err(|e| format!("Updating interpolated transform, msg = {e}"))
+    }
+
+    fn set_transform_parent(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+        parent_transform: Option<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_transform_index(&transform)
+            .map_err(|e| format!("Setting transform parent, msg = {e}"))?;
+
+        let current_transform = **self
+            .renderer_transforms
+            .get_ref(index.0)
+            .ok_or_else(|| {
+                "Setting transform parent, msg = could not find RendererTransform".to_string()
+            })?
+            .read();
+
+        let parent_index = if let Some(parent_transform) = parent_transform {
+            let parent_index = self
+                .get_transform_index(&parent_transform)
+                .map_err(|e| format!("Setting transform parent, msg = {e}"))?;
+
+            self.renderer_transforms
+                .get_ref(parent_index.0)
+                .ok_or_else(|| {
+                    "Setting transform parent, msg = could not find parent RendererTransform"
+                        .to_string()
+                })?;
+
+            if parent_index == index
+                || self
+                    .renderer_transform_hierarchy
+                    .is_ancestor(index.0, parent_index.0)
+            {
+                return Err(
+                    "Setting transform parent, msg = transform hierarchy would contain a cycle"
+                        .to_string(),
+                );
+            }
+
+            Some(parent_index.0)
+        } else {
+            None
+        };
+
+        let local_transform = self
+            .renderer_transform_hierarchy
+            .local_transform(index.0)
+            .unwrap_or(current_transform);
+
+        self.renderer_transform_hierarchy
+            .set_parent(index.0, parent_index, local_transform);
+
+        let world_transform = self.compute_world_transform(index.0, local_transform);
+        if let Some(transform) = self.renderer_transforms.get_ref(index.0) {
+            *transform.write().borrow_mut() = world_transform;
+        }
+
+        self.propagate_transform_to_children(index.0);
+
+        Ok(())
+    }
+
+    fn release_transform(
+        &mut self,
+        transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_transform_index(&transform)
+            .map_err(|e| format!("Releasing transform, msg = {e}"))?;
+
+        self.renderer_transform_hierarchy.remove(index.0);
+        self.interpolated_transforms.remove(&index.0);
+
+        self.renderer_transforms
+            .release_object(index.0)
+            .ok_or_else(|| {
+                "Releasing transform, msg = could not find RendererTransform".to_string()
+            })
+            .map(|_| ())
+    }
+
+    fn create_material(
+        &mut self,
+        material: Material,
+    ) -> Result<ArcRwLock<dyn RendererMaterial>, String> {
+        let gl_material = Arc::new(GLMaterial::new(&material, &mut self.gl_texture_container));
+        let renderer_material =
+            arc_rw_lock_new(Observable::new(RendererMaterialObject::new(gl_material)));
+        let index = self.renderer_materials.create_object(renderer_material);
+
+        Ok(arc_rw_lock_new(RendererMaterialIndex(index)))
+    }
+
+    fn update_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        new_material: Material,
+    ) -> Result<(), String> {
+        let index = self
+            .get_material_index(&material)
+            .map_err(|e| format!("Updating material, msg = {e}"))?;
+
+        let material = self.renderer_materials.get_mut(index.0).ok_or_else(|| {
+            "Updating material, msg = could not find RendererMaterial".to_string()
+        })?;
+
+        let gl_material = Arc::new(GLMaterial::new(
+            &new_material,
+            &mut self.gl_texture_container,
+        ));
+        *material.write().borrow_mut() = RendererMaterialObject::new(gl_material);
+
+        Ok(())
+    }
+
+    fn release_material(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_material_index(&material)
+            .map_err(|e| format!("Releasing material, msg = {e}"))?;
+
+        self.renderer_materials
+            .release_object(index.0)
+            .ok_or_else(|| "Releasing material, msg = could not find RendererMaterial".to_string())
+            .map(|_| ())
+    }
+
+    fn set_material_render_texture(
+        &mut self,
+        material: ArcRwLock<dyn RendererMaterial>,
+        texture_type: MaterialTextureType,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        let texture_index = self
+            .get_texture_index(&texture)
+            .map_err(|e| format!("Setting render texture of material, msg = {e}"))?;
+
+        let index = self
+            .get_material_index(&material)
+            .map_err(|e| format!("Setting render texture of material, msg = {e}"))?;
+
+        let material = self.renderer_materials.get_mut(index.0).ok_or_else(|| {
+            "Setting render texture of material, msg = could not find RendererMaterial".to_string()
+        })?;
+
+        let missing_renderer_texture_error_msg =
+            "Setting render texture of material, msg = could not find RendererTexture".to_string();
+        let gl_material = match (texture_type, texture_index) {
+            (MaterialTextureType::EnvironmentCube, RendererTextureIndex::Cubemap(index)) => {
+                let cubemap = self
+                    .cubemap_textures
+                    .get_ref(index)
+                    .ok_or(missing_renderer_texture_error_msg)?
+                    .clone();
+
+                material
+                    .read()
+                    .gl_material()
+                    .with_environment_cubemap(cubemap)
+            }
+            (
+                MaterialTextureType::EnvironmentCube,
+                RendererTextureIndex::RenderTexture(_) | RendererTextureIndex::Image(_),
+            ) => {
+                return Err(
+                    "Setting render texture of material, msg = an environment cube texture has to be a cubemap"
+                        .to_string(),
+                );
+            }
+            (_, RendererTextureIndex::RenderTexture(index)) => {
+                let render_texture = self
+                    .renderer_textures
+                    .get_ref(index)
+                    .ok_or(missing_renderer_texture_error_msg)?
+                    .0
+                    .clone();
+
+                material
+                    .read()
+                    .gl_material()
+                    .with_texture(GLMaterialTexture {
+                        texture: render_texture,
+                        texture_type,
+                        texture_map_mode: GLTextureMapMode::Clamp,
+                        // the render textures do not have mipmaps
+                        sampling_mode: GLTextureSamplingMode::Linear,
+                        anisotropy_mode: GLTextureAnisotropyMode::Anisotropy1,
+                        uv_channel_id: 0,
+                        blend: 1.0,
+                    })
+            }
+            (_, RendererTextureIndex::Image(index)) => {
+                let texture = self
+                    .image_textures
+                    .get_ref(index)
+                    .ok_or(missing_renderer_texture_error_msg)?
+                    .clone();
+
+                material
+                    .read()
+                    .gl_material()
+                    .with_texture(GLMaterialTexture {
+                        texture,
+                        texture_type,
+                        texture_map_mode: GLTextureMapMode::Repeat,
+                        sampling_mode: GLTextureSamplingMode::LinearMipmapLinear,
+                        anisotropy_mode: GLTextureAnisotropyMode::Anisotropy8,
+                        uv_channel_id: 0,
+                        blend: 1.0,
+                    })
+            }
+            (_, RendererTextureIndex::Cubemap(_)) => {
+                return Err(
+                    "Setting render texture of material, msg = a cubemap can only be an environment cube texture"
+                        .to_string(),
+                );
+            }
+        };
+        let gl_material = Arc::new(gl_material);
+        *material.write().borrow_mut() = RendererMaterialObject::new(gl_material);
+
+        Ok(())
+    }
+
+    fn create_render_texture(
+        &mut self,
+        dimensions: Vec2<usize>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        if dimensions.x == 0 || dimensions.y == 0 {
+            return Err(
+                "Creating render texture, msg = dimensions of the texture cannot be zero"
+                    .to_string(),
+            );
+        }
+
+        let render_texture = Arc::new(Texture2D::new_render_target(dimensions.x, dimensions.y));
+        let index = self
+            .renderer_textures
+            .create_object((render_texture, dimensions));
+
+        Ok(arc_rw_lock_new(RendererTextureIndex::RenderTexture(index)))
+    }
+
+    fn release_render_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_texture_index(&texture)
+            .map_err(|e| format!("Releasing render texture, msg = {e}"))?;
+
+        let missing_renderer_texture_error_msg =
+            "Releasing render texture, msg = could not find RendererTexture".to_string();
+        match index {
+            RendererTextureIndex::RenderTexture(index) => self
+                .renderer_textures
+                .release_object(index)
+                .ok_or(missing_renderer_texture_error_msg)
+                .map(|_| ()),
+            RendererTextureIndex::Image(index) => self
+                .image_textures
+                .release_object(index)
+                .ok_or(missing_renderer_texture_error_msg)
+                .map(|_| ()),
+            RendererTextureIndex::Cubemap(index) => self
+                .cubemap_textures
+                .release_object(index)
+                .ok_or(missing_renderer_texture_error_msg)
+                .map(|_| ()),
+        }
+    }
+
+    fn create_texture(
+        &mut self,
+        image: Arc<Image>,
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        // the texture is not shared with the texture container, because updating it must not affect other materials
+        let texture = Arc::new(Texture2D::new(image));
+        let index = self.image_textures.create_object(texture);
+
+        Ok(arc_rw_lock_new(RendererTextureIndex::Image(index)))
+    }
+
+    fn update_texture(
+        &mut self,
+        texture: ArcRwLock<dyn RendererTexture>,
+        image: Arc<Image>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_texture_index(&texture)
+            .map_err(|e| format!("Updating texture, msg = {e}"))?;
+
+        match index {
+            RendererTextureIndex::Image(index) => {
+                self.image_textures
+                    .get_ref(index)
+                    .ok_or_else(|| {
+                        "Updating texture, msg = could not find RendererTexture".to_string()
+                    })?
+                    .update(&image);
+
+                Ok(())
+            }
+            RendererTextureIndex::RenderTexture(_) | RendererTextureIndex::Cubemap(_) => {
+                Err("Updating texture, msg = only image textures can be updated".to_string())
+            }
+        }
+    }
+
+    fn create_cubemap_texture(
+        &mut self,
+        faces: [Arc<Image>; 6],
+    ) -> Result<ArcRwLock<dyn RendererTexture>, String> {
+        let cubemap = TextureCubemap::from_images(&faces)
+            .map_err(|e| format!("Creating cubemap texture, msg = {e}"))?;
+        let index = self.cubemap_textures.create_object(Arc::new(cubemap));
+
+        Ok(arc_rw_lock_new(RendererTextureIndex::Cubemap(index)))
+    }
+
+    fn create_shader(
+        &mut self,
+        shader_name: String,
+    ) -> Result<ArcRwLock<dyn RendererShader>, String> {
+        let gl_shader_program = match self
+            .gl_shader_program_container
+            .lock()
+            .get_shader_program(&shader_name, self.asset_container.asset_reader())
+        {
+            Ok(shader_program) => Ok(shader_program),
+            Err(e) => Err(format!("Loading shader program, msg = {e:?}")),
+        }?;
+
+        let renderer_shader = arc_rw_lock_new(Observable::new(RendererShaderObject::new(
+            gl_shader_program,
+        )));
+        let index = self.renderer_shaders.create_object(renderer_shader);
+
+        Ok(arc_rw_lock_new(RendererShaderIndex(index)))
+    }
+
+    fn update_shader(
+        &mut self,
+        shader: ArcRwLock<dyn RendererShader>,
+        new_shader_name: String,
+    ) -> Result<(), String> {
+        let index = self
+            .get_shader_index(&shader)
+            .map_err(|e| format!("Updating shader, msg = {e}"))?;
+
+        let shader = self
+            .renderer_shaders
+            .get_mut(index.0)
+            .ok_or_else(|| "Updating shader, msg = could not find RendererShader".to_string())?;
+
+        let gl_shader_program = match self
+            .gl_shader_program_container
+            .lock()
+            .get_shader_program(&new_shader_name, self.asset_container.asset_reader())
+        {
+            Ok(shader_program) => Ok(shader_program),
+            Err(e) => Err(format!("Loading shader program, msg = {e:?}")),
+        }?;
+
+        *shader.write().borrow_mut() = RendererShaderObject::new(gl_shader_program);
+
+        Ok(())
+    }
+
+    fn release_shader(&mut self, shader: ArcRwLock<dyn RendererShader>) -> Result<(), String> {
+        let index = self
+            .get_shader_index(&shader)
+            .map_err(|e| format!("Releasing shader, msg = {e}"))?;
+
+        self.renderer_shaders
+            .release_object(index.0)
+            .ok_or_else(|| "Releasing shader, msg = could not find RendererShader".to_string())
+            .map(|_| ())
+    }
+
+    fn create_mesh(&mut self, mesh: Arc<Mesh>) -> Result<ArcRwLock<dyn RendererMesh>, String> {
+        let gl_mesh = self.gl_mesh_container.get_gl_mesh(mesh);
+
+        let renderer_mesh = rc_rw_lock_new(Observable::new(RendererMeshObject::new(gl_mesh)));
+        let index = self.renderer_meshes.create_object(renderer_mesh);
+
+        Ok(arc_rw_lock_new(RendererMeshIndex(index)))
+    }
+
+    fn update_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        new_mesh: Arc<Mesh>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_mesh_index(&mesh)
+            .map_err(|e| format!("Updating mesh, msg = {e}"))?;
+
+        let mesh = self
+            .renderer_meshes
+            .get_mut(index.0)
+            .ok_or_else(|| "Updating mesh, msg = could not find RendererMesh".to_string())?;
+
+        let gl_mesh = self.gl_mesh_container.get_gl_mesh(new_mesh);
+
+        *mesh.write().borrow_mut() = RendererMeshObject::new(gl_mesh);
+
+        Ok(())
+    }
+
+    fn update_mesh_vertices(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        vertex_range: Range<usize>,
+        vertices: RendererMeshVertices,
+    ) -> Result<(), String> {
+        let index = self
+            .get_mesh_index(&mesh)
+            .map_err(|e| format!("Updating mesh vertices, msg = {e}"))?;
+
+        let mesh = self.renderer_meshes.get_ref(index.0).ok_or_else(|| {
+            "Updating mesh vertices, msg = could not find RendererMesh".to_string()
+        })?;
+
+        mesh.read()
+            .gl_mesh()
+            .update_vertices(vertex_range, &vertices)
+            .map_err(|e| format!("Updating mesh vertices, msg = {e}"))
+    }
+
+    fn release_mesh(&mut self, mesh: ArcRwLock<dyn RendererMesh>) -> Result<(), String> {
+        let index = self
+            .get_mesh_index(&mesh)
+            .map_err(|e| format!("Releasing mesh, msg = {e}"))?;
+
+        self.renderer_meshes
+            .release_object(index.0)
+            .ok_or_else(|| "Releasing mesh, msg = could not find RendererMesh".to_string())
+            .map(|_| ())
+    }
+
+    fn create_renderer_object_from_mesh(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        renderer_transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let transform = {
+            let index = self
+                .get_transform_index(&renderer_transform)
+                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+
+            self.renderer_transforms.get_ref(index.0).ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find RendererTransform"
+                    .to_string()
+            })?
+        };
+
+        let material = {
+            let index = self
+                .get_material_index(&material)
+                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+
+            self.renderer_materials.get_ref(index.0).ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find RendererMaterial"
+                    .to_string()
+            })?
+        };
+
+        let (shader, gl_mesh_shader_program) = {
+            let index = self
+                .get_shader_index(&shader)
+                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+
+            let shader = self.renderer_shaders.get_ref(index.0).ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find RendererShader"
+                    .to_string()
+            })?;
+
+            let gl_mesh_shader_program = self
+                .gl_shader_program_container
+                .lock()
+                .get_mesh_shader_program(shader.read().gl_shader_program().clone());
+
+            (shader, gl_mesh_shader_program)
+        };
+
+        let mesh = {
+            let index = self
+                .get_mesh_index(&mesh)
+                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+
+            self.renderer_meshes.get_ref(index.0).ok_or_else(|| {
+                "Creating renderer object from mesh, msg = could not find RendererMesh".to_string()
+            })?
+        };
+
+        let mesh_renderer_object = rc_rw_lock_new(GLDrawableMesh::new(
+            mesh.read().gl_mesh().clone(),
+            material.read().gl_material().clone(),
+            **transform.read(),
+            gl_mesh_shader_program,
+        ));
+
+        let mesh_renderer_object_clone_0 = mesh_renderer_object.clone();
+        let mesh_renderer_object_clone_1 = mesh_renderer_object.clone();
+        let mesh_renderer_object_clone_2 = mesh_renderer_object.clone();
+        let mesh_renderer_object_clone_3 = mesh_renderer_object.clone();
+
+        let gl_shader_program_container = self.gl_shader_program_container.clone();
+
+        let index = self.mesh_renderer_objects.create_object((
+            mesh_renderer_object,
+            transform.write().observe(move |transform| {
+                mesh_renderer_object_clone_0
+                    .write()
+                    .set_transform(transform);
+            }),
+            material.write().observe(move |material| {
+                mesh_renderer_object_clone_1
+                    .write()
+                    .set_gl_material(material.gl_material().clone())
+            }),
+            shader.write().observe(move |shader| {
+                let gl_mesh_shader_program = gl_shader_program_container
+                    .lock()
+                    .get_mesh_shader_program(shader.gl_shader_program().clone());
+                mesh_renderer_object_clone_2
+                    .write()
+                    .set_gl_mesh_shader_program(gl_mesh_shader_program);
+            }),
+            mesh.write().observe(move |mesh| {
+                mesh_renderer_object_clone_3
+                    .write()
+                    .set_gl_mesh(mesh.gl_mesh().clone());
+            }),
+        ));
+
+        Ok(arc_rw_lock_new(RendererObjectIndex::Mesh(index)))
+    }
+
+    fn create_lod_renderer_object(
+        &mut self,
+        lod_meshes: Vec<(f32, ArcRwLock<dyn RendererMesh>)>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        renderer_transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let transform = {
+            let index = self
+                .get_transform_index(&renderer_transform)
+                .map_err(|e| format!("Creating lod renderer object, msg = {e}"))?;
+
+            self.renderer_transforms.get_ref(index.0).ok_or_else(|| {
+                "Creating lod renderer object, msg = could not find RendererTransform".to_string()
+            })?
+        };
+
+        let material = {
+            let index = self
+                .get_material_index(&material)
+                .map_err(|e| format!("Creating lod renderer object, msg = {e}"))?;
+
+            self.renderer_materials.get_ref(index.0).ok_or_else(|| {
+                "Creating lod renderer object, msg = could not find RendererMaterial".to_string()
+            })?
+        };
+
+        let (shader, gl_mesh_shader_program) = {
+            let index = self
+                .get_shader_index(&shader)
+                .map_err(|e| format!("Creating lod renderer object, msg = {e}"))?;
+
+            let shader = self.renderer_shaders.get_ref(index.0).ok_or_else(|| {
+                "Creating lod renderer object, msg = could not find RendererShader".to_string()
+            })?;
+
+            let gl_mesh_shader_program = self
+                .gl_shader_program_container
+                .lock()
+                .get_mesh_shader_program(shader.read().gl_shader_program().clone());
+
+            (shader, gl_mesh_shader_program)
+        };
+
+        let mut meshes = Vec::with_capacity(lod_meshes.len());
+        for (distance, mesh) in lod_meshes.iter() {
+            if !distance.is_finite() || *distance < 0.0 {
+                return Err(format!(
+                    "Creating lod renderer object, msg = invalid lod distance, distance = {distance}"
+                ));
+            }
+
+            let index = self
+                .get_mesh_index(mesh)
+                .map_err(|e| format!("Creating lod renderer object, msg = {e}"))?;
+
+            meshes.push((
+                *distance,
+                self.renderer_meshes.get_ref(index.0).ok_or_else(|| {
+                    "Creating lod renderer object, msg = could not find RendererMesh".to_string()
+                })?,
+            ));
+        }
+        meshes.sort_by(|(distance_a, _), (distance_b, _)| distance_a.total_cmp(distance_b));
+
+        let mut meshes = meshes.into_iter();
+        let (_, nearest_mesh) = meshes.next().ok_or_else(|| {
+            "Creating lod renderer object, msg = at least one lod mesh is required".to_string()
+        })?;
+        let farther_meshes = meshes.collect::<Vec<_>>();
+
+        let mesh_renderer_object = rc_rw_lock_new(GLDrawableMesh::new_lod(
+            nearest_mesh.read().gl_mesh().clone(),
+            farther_meshes
+                .iter()
+                .map(|(distance, mesh)| (*distance, mesh.read().gl_mesh().clone()))
+                .collect(),
+            material.read().gl_material().clone(),
+            **transform.read(),
+            gl_mesh_shader_program,
+        ));
+
+        let farther_mesh_observers = farther_meshes
+            .iter()
+            .enumerate()
+            .map(|(index, (_, mesh))| {
+                let mesh_renderer_object = mesh_renderer_object.clone();
+                mesh.write().observe(move |mesh| {
+                    mesh_renderer_object
+                        .write()
+                        .set_lod_gl_mesh(index + 1, mesh.gl_mesh().clone());
+                })
+            })
+            .collect();
+
+        let mesh_renderer_object_clone_0 = mesh_renderer_object.clone();
+        let mesh_renderer_object_clone_1 = mesh_renderer_object.clone();
+        let mesh_renderer_object_clone_2 = mesh_renderer_object.clone();
+        let mesh_renderer_object_clone_3 = mesh_renderer_object.clone();
+
+        let gl_shader_program_container = self.gl_shader_program_container.clone();
+
+        let index = self.mesh_renderer_objects.create_object((
+            mesh_renderer_object,
+            transform.write().observe(move |transform| {
+                mesh_renderer_object_clone_0
+                    .write()
+                    .set_transform(transform);
+            }),
+            material.write().observe(move |material| {
+                mesh_renderer_object_clone_1
+                    .write()
+                    .set_gl_material(material.gl_material().clone())
+            }),
+            shader.write().observe(move |shader| {
+                let gl_mesh_shader_program = gl_shader_program_container
+                    .lock()
+                    .get_mesh_shader_program(shader.gl_shader_program().clone());
+                mesh_renderer_object_clone_2
+                    .write()
+                    .set_gl_mesh_shader_program(gl_mesh_shader_program);
+            }),
+            nearest_mesh.write().observe(move |mesh| {
+                mesh_renderer_object_clone_3
+                    .write()
+                    .set_lod_gl_mesh(0, mesh.gl_mesh().clone());
+            }),
+        ));
+        self.lod_mesh_observers
+            .insert(index, farther_mesh_observers);
+
+        Ok(arc_rw_lock_new(RendererObjectIndex::Mesh(index)))
+    }
+
+    fn create_instanced_renderer_object(
+        &mut self,
+        mesh: ArcRwLock<dyn RendererMesh>,
+        shader: ArcRwLock<dyn RendererShader>,
+        material: ArcRwLock<dyn RendererMaterial>,
+        renderer_transforms: Vec<ArcRwLock<dyn RendererTransform>>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        if renderer_transforms.is_empty() {
+            return Err(
+                "Creating instanced renderer object, msg = at least one transform is required"
+                    .to_string(),
+            );
+        }
+
+        let mut transforms = Vec::with_capacity(renderer_transforms.len());
+        for renderer_transform in renderer_transforms.iter() {
+            let index = self
+                .get_transform_index(renderer_transform)
+                .map_err(|e| format!("Creating instanced renderer object, msg = {e}"))?;
+
+            transforms.push(self.renderer_transforms.get_ref(index.0).ok_or_else(|| {
+                "Creating instanced renderer object, msg = could not find RendererTransform"
+                    .to_string()
+            })?);
+        }
+
+        let material = {
+            let index = self
+                .get_material_index(&material)
+                .map_err(|e| format!("Creating instanced renderer object, msg = {e}"))?;
+
+            self.renderer_materials.get_ref(index.0).ok_or_else(|| {
+                "Creating instanced renderer object, msg = could not find RendererMaterial"
+                    .to_string()
+            })?
+        };
+
+        let (shader, gl_mesh_shader_program) = {
+            let index = self
+                .get_shader_index(&shader)
+                .map_err(|e| format!("Creating instanced renderer object, msg = {e}"))?;
+
+            let shader = self.renderer_shaders.get_ref(index.0).ok_or_else(|| {
+                "Creating instanced renderer object, msg = could not find RendererShader"
+                    .to_string()
+            })?;
+
+            let gl_mesh_shader_program = self
+                .gl_shader_program_container
+                .lock()
+                .get_mesh_shader_program(shader.read().gl_shader_program().clone());
+
+            (shader, gl_mesh_shader_program)
+        };
+
+        let mesh = {
+            let index = self
+                .get_mesh_index(&mesh)
+                .map_err(|e| format!("Creating instanced renderer object, msg = {e}"))?;
+
+            self.renderer_meshes.get_ref(index.0).ok_or_else(|| {
+                "Creating instanced renderer object, msg = could not find RendererMesh".to_string()
+            })?
+        };
+
+        let instanced_renderer_object = rc_rw_lock_new(GLDrawableMesh::new_instanced(
+            mesh.read().gl_mesh().clone(),
+            material.read().gl_material().clone(),
+            transforms
+                .iter()
+                .map(|transform| **transform.read())
+                .collect(),
+            gl_mesh_shader_program,
+        ));
+
+        let transform_observers = transforms
+            .iter()
+            .enumerate()
+            .map(|(instance_index, transform)| {
+                let instanced_renderer_object = instanced_renderer_object.clone();
+                transform.write().observe(move |transform| {
+                    instanced_renderer_object
+                        .write()
+                        .set_instance_transform(instance_index, transform);
+                })
+            })
+            .collect();
+
+        let instanced_renderer_object_clone_0 = instanced_renderer_object.clone();
+        let instanced_renderer_object_clone_1 = instanced_renderer_object.clone();
+        let instanced_renderer_object_clone_2 = instanced_renderer_object.clone();
+
+        let gl_shader_program_container = self.gl_shader_program_container.clone();
+
+        let index = self.instanced_renderer_objects.create_object((
+            instanced_renderer_object,
+            transform_observers,
+            material.write().observe(move |material| {
+                instanced_renderer_object_clone_0
+                    .write()
+                    .set_gl_material(material.gl_material().clone())
+            }),
+            shader.write().observe(move |shader| {
+                let gl_mesh_shader_program = gl_shader_program_container
+                    .lock()
+                    .get_mesh_shader_program(shader.gl_shader_program().clone());
+                instanced_renderer_object_clone_1
+                    .write()
+                    .set_gl_mesh_shader_program(gl_mesh_shader_program);
+            }),
+            mesh.write().observe(move |mesh| {
+                instanced_renderer_object_clone_2
+                    .write()
+                    .set_gl_mesh(mesh.gl_mesh().clone());
+            }),
+        ));
+
+        Ok(arc_rw_lock_new(RendererObjectIndex::Instanced(index)))
+    }
+
+    fn create_skybox_renderer_object(
         &mut self,
-        renderer_group: ArcRwLock<dyn RendererGroup>,
-        renderer_layer: ArcRwLock<dyn RendererLayer>,
-    ) -> Result<(), String> {
-        let renderer_group_index = self
-            .get_renderer_group_index(&renderer_group)
-            .map_err(|e| format!("Removing renderer group from layer, msg = {e}"))?;
+        cubemap: ArcRwLock<dyn RendererTexture>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let cubemap = {
+            let index = self
+                .get_texture_index(&cubemap)
+                .map_err(|e| format!("Creating skybox renderer object, msg = {e}"))?;
 
-        let renderer_group = self
-            .renderer_groups
-            .get_ref(renderer_group_index.0)
-            .ok_or_else(|| {
-                "Removing renderer group from layer, msg = could not find RendererGroup".to_string()
-            })?;
+            match index {
+                RendererTextureIndex::Cubemap(index) => self
+                    .cubemap_textures
+                    .get_ref(index)
+                    .ok_or_else(|| {
+                        "Creating skybox renderer object, msg = could not find RendererTexture"
+                            .to_string()
+                    })?
+                    .clone(),
+                RendererTextureIndex::RenderTexture(_) | RendererTextureIndex::Image(_) => {
+                    return Err(
+                        "Creating skybox renderer object, msg = the RendererTexture is not a cubemap"
+                            .to_string(),
+                    );
+                }
+            }
+        };
 
-        let renderer_layer_index = self
-            .get_renderer_layer_index(&renderer_layer)
-            .map_err(|e| format!("Removing renderer group from layer, msg = {e}"))?;
+        let gl_mesh_shader_program = match &self.skybox_shader_program {
+            Some(gl_mesh_shader_program) => gl_mesh_shader_program.clone(),
+            None => {
+                let mut gl_shader_program_container = self.gl_shader_program_container.lock();
+                let gl_shader_program = gl_shader_program_container
+                    .get_shader_program(
+                        "assets/shaders/skybox",
+                        self.asset_container.asset_reader(),
+                    )
+                    .map_err(|e| format!("Creating skybox renderer object, msg = {e:?}"))?;
+
+                let gl_mesh_shader_program =
+                    gl_shader_program_container.get_mesh_shader_program(gl_shader_program);
+                self.skybox_shader_program = Some(gl_mesh_shader_program.clone());
+
+                gl_mesh_shader_program
+            }
+        };
 
-        let renderer_layer = self
-            .renderer_layers
-            .get_ref(renderer_layer_index.0)
-            .ok_or_else(|| {
-                "Removing renderer group from layer, msg = could not find RendererLayer".to_string()
-            })?;
+        let gl_mesh = self
+            .skybox_gl_mesh
+            .get_or_insert_with(|| {
+                Rc::new(GLMesh::new(Arc::new(rectangle3d::create(2.0, 2.0, 2.0))))
+            })
+            .clone();
 
-        if renderer_layer
-            .write()
-            .remove_renderer_group(renderer_group)
-            .is_none()
-        {
-            Err(
-                "Removing renderer group from layer, msg = could not find renderer group in layer"
-                    .to_string(),
-            )
-        } else {
-            Ok(())
-        }
-    }
+        let gl_material = GLMaterial::new(&Material::default(), &mut self.gl_texture_container)
+            .with_environment_cubemap(cubemap);
 
-    fn create_renderer_group(&mut self) -> Result<ArcRwLock<dyn RendererGroup>, String> {
-        let renderer_group = rc_rw_lock_new(RendererGroupObject::new());
-        let index = self.renderer_groups.create_object(renderer_group);
+        let skybox_renderer_object = rc_rw_lock_new(GLDrawableMesh::new_unbounded(
+            gl_mesh,
+            Arc::new(gl_material),
+            gl_mesh_shader_program,
+        ));
+        let index = self
+            .skybox_renderer_objects
+            .create_object(skybox_renderer_object);
 
-        Ok(arc_rw_lock_new(RendererGroupIndex(index)))
+        Ok(arc_rw_lock_new(RendererObjectIndex::Skybox(index)))
     }
 
-    fn release_renderer_group(
+    fn create_particle_emitter_renderer_object(
         &mut self,
-        renderer_group: ArcRwLock<dyn RendererGroup>,
-    ) -> Result<(), String> {
-        let index = self
-            .get_renderer_group_index(&renderer_group)
-            .map_err(|e| format!("Releasing renderer group, msg = {e}"))?;
+        settings: ParticleEmitterSettings,
+        material: ArcRwLock<dyn RendererMaterial>,
+        renderer_transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let transform = {
+            let index = self
+                .get_transform_index(&renderer_transform)
+                .map_err(|e| format!("Creating particle emitter renderer object, msg = {e}"))?;
 
-        self.renderer_groups
-            .release_object(index.0)
-            .ok_or_else(|| {
-                "Releasing renderer group, msg = could not find RendererGroup".to_string()
-            })
-            .map(|_| ())
-    }
+            self.renderer_transforms.get_ref(index.0).ok_or_else(|| {
+                "Creating particle emitter renderer object, msg = could not find RendererTransform"
+                    .to_string()
+            })?
+        };
 
-    fn create_transform(
-        &mut self,
-        transform: Transform<f32, f32, f32>,
-    ) -> Result<ArcRwLock<dyn RendererTransform>, String> {
-        let index = self
-            .renderer_transforms
-            .create_object(rc_rw_lock_new(Observable::new(transform)));
+        let material = {
+            let index = self
+                .get_material_index(&material)
+                .map_err(|e| format!("Creating particle emitter renderer object, msg = {e}"))?;
 
-        Ok(arc_rw_lock_new(RendererTransformIndex(index)))
-    }
+            self.renderer_materials.get_ref(index.0).ok_or_else(|| {
+                "Creating particle emitter renderer object, msg = could not find RendererMaterial"
+                    .to_string()
+            })?
+        };
 
-    fn update_transform(
-        &mut self,
-        transform: ArcRwLock<dyn RendererTransform>,
-        new_transform: Transform<f32, f32, f32>,
-    ) -> Result<(), String> {
-        let index = self
-            .get_transform_index(&transform)
-            .map_err(|e| format!("Updating transform, msg = {e}"))?;
+        let gl_shader_program = match &self.particle_shader_program {
+            Some(gl_shader_program) => gl_shader_program.clone(),
+            None => {
+                let gl_shader_program = self
+                    .gl_shader_program_container
+                    .lock()
+                    .get_shader_program(
+                        "assets/shaders/particle_system",
+                        self.asset_container.asset_reader(),
+                    )
+                    .map_err(|e| {
+                        format!("Creating particle emitter renderer object, msg = {e:?}")
+                    })?;
+                self.particle_shader_program = Some(gl_shader_program.clone());
 
-        let transform = self.renderer_transforms.get_mut(index.0).ok_or_else(|| {
-            "Updating transform, msg = could not find RendererTransform".to_string()
-        })?;
+                gl_shader_program
+            }
+        };
 
-        *transform.write().borrow_mut() = new_transform;
+        let particle_emitter = rc_rw_lock_new(GLParticleEmitter::new(
+            settings,
+            material.read().gl_material().clone(),
+            **transform.read(),
+            gl_shader_program,
+        ));
 
-        Ok(())
-    }
+        let particle_emitter_clone_0 = particle_emitter.clone();
+        let particle_emitter_clone_1 = particle_emitter.clone();
 
-    fn release_transform(
-        &mut self,
-        transform: ArcRwLock<dyn RendererTransform>,
-    ) -> Result<(), String> {
-        let index = self
-            .get_transform_index(&transform)
-            .map_err(|e| format!("Releasing transform, msg = {e}"))?;
+        let index = self.particle_emitter_renderer_objects.create_object((
+            particle_emitter,
+            transform.write().observe(move |transform| {
+                particle_emitter_clone_0.write().set_transform(transform);
+            }),
+            material.write().observe(move |material| {
+                particle_emitter_clone_1
+                    .write()
+                    .set_gl_material(material.gl_material().clone())
+            }),
+        ));
 
-        self.renderer_transforms
-            .release_object(index.0)
-            .ok_or_else(|| {
-                "Releasing transform, msg = could not find RendererTransform".to_string()
-            })
-            .map(|_| ())
+        Ok(arc_rw_lock_new(RendererObjectIndex::ParticleEmitter(index)))
     }
 
-    fn create_material(
+    fn create_sprite_batch_renderer_object(
         &mut self,
-        material: Material,
-    ) -> Result<ArcRwLock<dyn RendererMaterial>, String> {
-        let gl_material = Arc::new(GLMaterial::new(&material, &mut self.gl_texture_container));
-        let renderer_material =
-            arc_rw_lock_new(Observable::new(RendererMaterialObject::new(gl_material)));
-        let index = self.renderer_materials.create_object(renderer_material);
+        texture: ArcRwLock<dyn RendererTexture>,
+        sprites: Vec<RendererSprite>,
+        renderer_transform: ArcRwLock<dyn RendererTransform>,
+    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+        let texture = {
+            let index = self
+                .get_texture_index(&texture)
+                .map_err(|e| format!("Creating sprite batch renderer object, msg = {e}"))?;
+
+            let missing_renderer_texture_error_msg =
+                "Creating sprite batch renderer object, msg = could not find RendererTexture"
+                    .to_string();
+            match index {
+                RendererTextureIndex::Image(index) => self
+                    .image_textures
+                    .get_ref(index)
+                    .ok_or(missing_renderer_texture_error_msg)?
+                    .clone(),
+                RendererTextureIndex::RenderTexture(index) => self
+                    .renderer_textures
+                    .get_ref(index)
+                    .ok_or(missing_renderer_texture_error_msg)?
+                    .0
+                    .clone(),
+                RendererTextureIndex::Cubemap(_) => {
+                    return Err(
+                        "Creating sprite batch renderer object, msg = a cubemap cannot be used as a sprite texture"
+                            .to_string(),
+                    );
+                }
+            }
+        };
 
-        Ok(arc_rw_lock_new(RendererMaterialIndex(index)))
-    }
+        let transform = {
+            let index = self
+                .get_transform_index(&renderer_transform)
+                .map_err(|e| format!("Creating sprite batch renderer object, msg = {e}"))?;
 
-    fn update_material(
-        &mut self,
-        material: ArcRwLock<dyn RendererMaterial>,
-        new_material: Material,
-    ) -> Result<(), String> {
-        let index = self
-            .get_material_index(&material)
-            .map_err(|e| format!("Updating material, msg = {e}"))?;
+            self.renderer_transforms.get_ref(index.0).ok_or_else(|| {
+                "Creating sprite batch renderer object, msg = could not find RendererTransform"
+                    .to_string()
+            })?
+        };
 
-        let material = self.renderer_materials.get_mut(index.0).ok_or_else(|| {
-            "Updating material, msg = could not find RendererMaterial".to_string()
-        })?;
+        let gl_shader_program = match &self.sprite_shader_program {
+            Some(gl_shader_program) => gl_shader_program.clone(),
+            None => {
+                let gl_shader_program = self
+                    .gl_shader_program_container
+                    .lock()
+                    .get_shader_program(
+                        "assets/shaders/sprite_batch",
+                        self.asset_container.asset_reader(),
+                    )
+                    .map_err(|e| format!("Creating sprite batch renderer object, msg = {e:?}"))?;
+                self.sprite_shader_program = Some(gl_shader_program.clone());
+
+                gl_shader_program
+            }
+        };
 
-        let gl_material = Arc::new(GLMaterial::new(
-            &new_material,
-            &mut self.gl_texture_container,
+        let sprite_batch = rc_rw_lock_new(GLSpriteBatch::new(
+            texture,
+            &sprites,
+            **transform.read(),
+            gl_shader_program,
         ));
-        *material.write().borrow_mut() = RendererMaterialObject::new(gl_material);
 
-        Ok(())
-    }
+        let sprite_batch_clone = sprite_batch.clone();
 
-    fn release_material(
-        &mut self,
-        material: ArcRwLock<dyn RendererMaterial>,
-    ) -> Result<(), String> {
-        let index = self
-            .get_material_index(&material)
-            .map_err(|e| format!("Releasing material, msg = {e}"))?;
+        let index = self.sprite_batch_renderer_objects.create_object((
+            sprite_batch,
+            transform.write().observe(move |transform| {
+                sprite_batch_clone.write().set_transform(transform);
+            }),
+        ));
 
-        self.renderer_materials
-            .release_object(index.0)
-            .ok_or_else(|| "Releasing material, msg = could not find RendererMaterial".to_string())
-            .map(|_| ())
+        Ok(arc_rw_lock_new(RendererObjectIndex::SpriteBatch(index)))
     }
 
-    fn create_shader(
+    fn release_renderer_object(
         &mut self,
-        shader_name: String,
-    ) -> Result<ArcRwLock<dyn RendererShader>, String> {
-        let gl_shader_program = match self
-            .gl_shader_program_container
-            .lock()
-            .get_shader_program(&shader_name, self.asset_container.asset_reader())
-        {
-            Ok(shader_program) => Ok(shader_program),
-            Err(e) => Err(format!("Loading shader program, msg = {e:?}")),
-        }?;
+        renderer_object: ArcRwLock<dyn RendererObject>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Releasing renderer object, msg = {e}"))?;
 
-        let renderer_shader = arc_rw_lock_new(Observable::new(RendererShaderObject::new(
-            gl_shader_program,
-        )));
-        let index = self.renderer_shaders.create_object(renderer_shader);
+        match index {
+            RendererObjectIndex::Mesh(index) => {
+                self.mesh_renderer_objects
+                    .release_object(index)
+                    .ok_or_else(|| {
+                        "Releasing renderer object, msg = could not find RendererObject".to_string()
+                    })?;
+                self.lod_mesh_observers.remove(&index);
+            }
+            RendererObjectIndex::Instanced(index) => {
+                self.instanced_renderer_objects
+                    .release_object(index)
+                    .ok_or_else(|| {
+                        "Releasing renderer object, msg = could not find RendererObject".to_string()
+                    })?;
+            }
+            RendererObjectIndex::Skybox(index) => {
+                self.skybox_renderer_objects
+                    .release_object(index)
+                    .ok_or_else(|| {
+                        "Releasing renderer object, msg = could not find RendererObject".to_string()
+                    })?;
+            }
+            RendererObjectIndex::ParticleEmitter(index) => {
+                self.particle_emitter_renderer_objects
+                    .release_object(index)
+                    .ok_or_else(|| {
+                        "Releasing renderer object, msg = could not find RendererObject".to_string()
+                    })?;
+            }
+            RendererObjectIndex::SpriteBatch(index) => {
+                self.sprite_batch_renderer_objects
+                    .release_object(index)
+                    .ok_or_else(|| {
+                        "Releasing renderer object, msg = could not find RendererObject".to_string()
+                    })?;
+            }
+        }
 
-        Ok(arc_rw_lock_new(RendererShaderIndex(index)))
+        Ok(())
     }
 
-    fn update_shader(
+    fn add_renderer_object_to_group(
         &mut self,
-        shader: ArcRwLock<dyn RendererShader>,
-        new_shader_name: String,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
     ) -> Result<(), String> {
+        let renderer_group = {
+            let index = self
+                .get_renderer_group_index(&renderer_group)
+                .map_err(|e| format!("Adding renderer object to group, msg = {e}"))?;
+
+            self.renderer_groups.get_ref(index.0).ok_or_else(|| {
+                "Adding renderer object to group, msg = could not find RendererGroup".to_string()
+            })?
+        };
+
         let index = self
-            .get_shader_index(&shader)
-            .map_err(|e| format!("Updating shader, msg = {e}"))?;
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Adding renderer object to group, msg = {e}"))?;
 
-        let shader = self
-            .renderer_shaders
-            .get_mut(index.0)
-            .ok_or_else(|| "Updating shader, msg = could not find RendererShader".to_string())?;
+        let missing_renderer_object_error_msg =
+            "Adding renderer object to group, msg = could not find renderer object".to_string();
+        let adding_twice_error_msg =
+            "Adding renderer object to group, msg = cannot add renderer object twice to the same group".to_string();
 
-        let gl_shader_program = match self
-            .gl_shader_program_container
-            .lock()
-            .get_shader_program(&new_shader_name, self.asset_container.asset_reader())
-        {
-            Ok(shader_program) => Ok(shader_program),
-            Err(e) => Err(format!("Loading shader program, msg = {e:?}")),
-        }?;
+        if let RendererObjectIndex::ParticleEmitter(index) = index {
+            let (particle_emitter, ..) = self
+                .particle_emitter_renderer_objects
+                .get_ref(index)
+                .ok_or(missing_renderer_object_error_msg)?;
 
-        *shader.write().borrow_mut() = RendererShaderObject::new(gl_shader_program);
+            return match renderer_group
+                .write()
+                .add_particle_emitter(particle_emitter.clone())
+            {
+                Some(_) => Err(adding_twice_error_msg),
+                None => Ok(()),
+            };
+        }
 
-        Ok(())
-    }
+        if let RendererObjectIndex::SpriteBatch(index) = index {
+            let (sprite_batch, _transform_observer) = self
+                .sprite_batch_renderer_objects
+                .get_ref(index)
+                .ok_or(missing_renderer_object_error_msg)?;
 
-    fn release_shader(&mut self, shader: ArcRwLock<dyn RendererShader>) -> Result<(), String> {
-        let index = self
-            .get_shader_index(&shader)
-            .map_err(|e| format!("Releasing shader, msg = {e}"))?;
+            return match renderer_group
+                .write()
+                .add_sprite_batch(sprite_batch.clone())
+            {
+                Some(_) => Err(adding_twice_error_msg),
+                None => Ok(()),
+            };
+        }
 
-        self.renderer_shaders
-            .release_object(index.0)
-            .ok_or_else(|| "Releasing shader, msg = could not find RendererShader".to_string())
-            .map(|_| ())
-    }
+        let renderer_object = match index {
+            RendererObjectIndex::Mesh(index) => self
+                .mesh_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Instanced(index) => self
+                .instanced_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Skybox(index) => self.skybox_renderer_objects.get_ref(index),
+            RendererObjectIndex::ParticleEmitter(_) | RendererObjectIndex::SpriteBatch(_) => None,
+        }
+        .ok_or(missing_renderer_object_error_msg)?;
 
-    fn create_mesh(&mut self, mesh: Arc<Mesh>) -> Result<ArcRwLock<dyn RendererMesh>, String> {
-        let gl_mesh = self.gl_mesh_container.get_gl_mesh(mesh);
+        let old_value = renderer_group
+            .write()
+            .add_mesh_renderer_object(renderer_object.clone());
 
-        let renderer_mesh = rc_rw_lock_new(Observable::new(RendererMeshObject::new(gl_mesh)));
-        let index = self.renderer_meshes.create_object(renderer_mesh);
+        match old_value {
+            Some(_) => Err(adding_twice_error_msg),
+            None => Ok(()),
+        }?;
 
-        Ok(arc_rw_lock_new(RendererMeshIndex(index)))
+        Ok(())
     }
 
-    fn update_mesh(
+    fn remove_renderer_object_from_group(
         &mut self,
-        mesh: ArcRwLock<dyn RendererMesh>,
-        new_mesh: Arc<Mesh>,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_group: ArcRwLock<dyn RendererGroup>,
     ) -> Result<(), String> {
-        let index = self
-            .get_mesh_index(&mesh)
-            .map_err(|e| format!("Updating mesh, msg = {e}"))?;
+        let renderer_group = {
+            let index = self
+                .get_renderer_group_index(&renderer_group)
+                .map_err(|e| format!("Removing renderer object from group, msg = {e}"))?;
 
-        let mesh = self
-            .renderer_meshes
-            .get_mut(index.0)
-            .ok_or_else(|| "Updating mesh, msg = could not find RendererMesh".to_string())?;
+            self.renderer_groups.get_ref(index.0).ok_or_else(|| {
+                "Removing renderer object from group, msg = could not find RendererGroup"
+                    .to_string()
+            })?
+        };
 
-        let gl_mesh = self.gl_mesh_container.get_gl_mesh(new_mesh);
+        let index = self
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Removing renderer object from group, msg = {e}"))?;
 
-        *mesh.write().borrow_mut() = RendererMeshObject::new(gl_mesh);
+        let missing_renderer_object_error_msg =
+            "Removing renderer object from group, msg = could not find renderer object".to_string();
+        let missing_renderer_object_in_group_error_msg =
+            "Removing renderer object from group, msg = could not find renderer object in group"
+                .to_string();
 
-        Ok(())
-    }
+        if let RendererObjectIndex::ParticleEmitter(index) = index {
+            let (particle_emitter, ..) = self
+                .particle_emitter_renderer_objects
+                .get_ref(index)
+                .ok_or(missing_renderer_object_error_msg)?;
+
+            return renderer_group
+                .write()
+                .remove_particle_emitter(particle_emitter)
+                .ok_or(missing_renderer_object_in_group_error_msg)
+                .map(|_| ());
+        }
 
-    fn release_mesh(&mut self, mesh: ArcRwLock<dyn RendererMesh>) -> Result<(), String> {
-        let index = self
-            .get_mesh_index(&mesh)
-            .map_err(|e| format!("Releasing mesh, msg = {e}"))?;
+        if let RendererObjectIndex::SpriteBatch(index) = index {
+            let (sprite_batch, _transform_observer) = self
+                .sprite_batch_renderer_objects
+                .get_ref(index)
+                .ok_or(missing_renderer_object_error_msg)?;
+
+            return renderer_group
+                .write()
+                .remove_sprite_batch(sprite_batch)
+                .ok_or(missing_renderer_object_in_group_error_msg)
+                .map(|_| ());
+        }
 
-        self.renderer_meshes
-            .release_object(index.0)
-            .ok_or_else(|| "Releasing mesh, msg = could not find RendererMesh".to_string())
+        let renderer_object = match index {
+            RendererObjectIndex::Mesh(index) => self
+                .mesh_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Instanced(index) => self
+                .instanced_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Skybox(index) => self.skybox_renderer_objects.get_ref(index),
+            RendererObjectIndex::ParticleEmitter(_) | RendererObjectIndex::SpriteBatch(_) => None,
+        }
+        .ok_or(missing_renderer_object_error_msg)?;
+
+        renderer_group
+            .write()
+            .remove_mesh_renderer_object(renderer_object)
+            .ok_or(missing_renderer_object_in_group_error_msg)
             .map(|_| ())
     }
 
-    fn create_renderer_object_from_mesh(
+    fn set_renderer_object_transform(
         &mut self,
-        mesh: ArcRwLock<dyn RendererMesh>,
-        shader: ArcRwLock<dyn RendererShader>,
-        material: ArcRwLock<dyn RendererMaterial>,
+        renderer_object: ArcRwLock<dyn RendererObject>,
         renderer_transform: ArcRwLock<dyn RendererTransform>,
-    ) -> Result<ArcRwLock<dyn RendererObject>, String> {
+    ) -> Result<(), String> {
         let transform = {
             let index = self
                 .get_transform_index(&renderer_transform)
-                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+                .map_err(|e| format!("Setting transform of renderer object, msg = {e}"))?;
 
             self.renderer_transforms.get_ref(index.0).ok_or_else(|| {
-                "Creating renderer object from mesh, msg = could not find RendererTransform"
+                "Setting transform of renderer object, msg = could not find RendererTransform"
                     .to_string()
             })?
         };
 
+        let index = self
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Setting transform of renderer object, msg = {e}"))?;
+
+        match index {
+            RendererObjectIndex::Mesh(index) => {
+                let (
+                    renderer_object,
+                    transform_observer,
+                    _material_observer,
+                    _shader_observer,
+                    _mesh_observer,
+                ) = self.mesh_renderer_objects.get_mut(index).ok_or_else(|| {
+                    "Setting transform of renderer object, msg = could not find RendererObject"
+                        .to_string()
+                })?;
+
+                renderer_object.write().set_transform(&**transform.read());
+
+                let renderer_object = renderer_object.clone();
+                *transform_observer = transform.write().observe(move |transform| {
+                    renderer_object.write().set_transform(transform);
+                });
+
+                Ok(())
+            }
+            RendererObjectIndex::Instanced(_) => Err(
+                "Setting transform of renderer object, msg = instanced renderer objects have a transform for each instance"
+                    .to_string(),
+            ),
+            RendererObjectIndex::Skybox(_) => Err(
+                "Setting transform of renderer object, msg = skybox renderer objects always surround the camera"
+                    .to_string(),
+            ),
+            RendererObjectIndex::ParticleEmitter(index) => {
+                let (particle_emitter, transform_observer, _material_observer) = self
+                    .particle_emitter_renderer_objects
+                    .get_mut(index)
+                    .ok_or_else(|| {
+                        "Setting transform of renderer object, msg = could not find RendererObject"
+                            .to_string()
+                    })?;
+
+                particle_emitter.write().set_transform(&**transform.read());
+
+                let particle_emitter = particle_emitter.clone();
+                *transform_observer = transform.write().observe(move |transform| {
+                    particle_emitter.write().set_transform(transform);
+                });
+
+                Ok(())
+            }
+            RendererObjectIndex::SpriteBatch(index) => {
+                let (sprite_batch, transform_observer) = self
+                    .sprite_batch_renderer_objects
+                    .get_mut(index)
+                    .ok_or_else(|| {
+                        "Setting transform of renderer object, msg = could not find RendererObject"
+                            .to_string()
+                    })?;
+
+                sprite_batch.write().set_transform(&**transform.read());
+
+                let sprite_batch = sprite_batch.clone();
+                *transform_observer = transform.write().observe(move |transform| {
+                    sprite_batch.write().set_transform(transform);
+                });
+
+                Ok(())
+            }
+        }
+    }
+
+    fn set_renderer_object_material(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_material: ArcRwLock<dyn RendererMaterial>,
+    ) -> Result<(), String> {
         let material = {
             let index = self
-                .get_material_index(&material)
-                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+                .get_material_index(&renderer_material)
+                .map_err(|e| format!("Setting material of renderer object, msg = {e}"))?;
 
             self.renderer_materials.get_ref(index.0).ok_or_else(|| {
-                "Creating renderer object from mesh, msg = could not find RendererMaterial"
+                "Setting material of renderer object, msg = could not find RendererMaterial"
                     .to_string()
             })?
         };
 
-        let (shader, gl_mesh_shader_program) = {
-            let index = self
-                .get_shader_index(&shader)
-                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+        let index = self
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Setting material of renderer object, msg = {e}"))?;
 
-            let shader = self.renderer_shaders.get_ref(index.0).ok_or_else(|| {
-                "Creating renderer object from mesh, msg = could not find RendererShader"
-                    .to_string()
-            })?;
+        if let RendererObjectIndex::ParticleEmitter(index) = index {
+            let (particle_emitter, _transform_observer, material_observer) = self
+                .particle_emitter_renderer_objects
+                .get_mut(index)
+                .ok_or_else(|| {
+                    "Setting material of renderer object, msg = could not find RendererObject"
+                        .to_string()
+                })?;
 
-            let gl_mesh_shader_program = self
-                .gl_shader_program_container
-                .lock()
-                .get_mesh_shader_program(shader.read().gl_shader_program().clone());
+            particle_emitter
+                .write()
+                .set_gl_material(material.read().gl_material().clone());
 
-            (shader, gl_mesh_shader_program)
-        };
+            let particle_emitter = particle_emitter.clone();
+            *material_observer = material.write().observe(move |material| {
+                particle_emitter
+                    .write()
+                    .set_gl_material(material.gl_material().clone())
+            });
 
-        let mesh = {
+            return Ok(());
+        }
+
+        let (renderer_object, material_observer) = match index {
+            RendererObjectIndex::Mesh(index) => self.mesh_renderer_objects.get_mut(index).map(
+                |(renderer_object, _, material_observer, ..)| (renderer_object, material_observer),
+            ),
+            RendererObjectIndex::Instanced(index) => self
+                .instanced_renderer_objects
+                .get_mut(index)
+                .map(|(renderer_object, _, material_observer, ..)| {
+                    (renderer_object, material_observer)
+                }),
+            RendererObjectIndex::Skybox(_) => {
+                return Err(
+                    "Setting material of renderer object, msg = skybox renderer objects get their material from the cubemap"
+                        .to_string(),
+                );
+            }
+            RendererObjectIndex::SpriteBatch(_) => {
+                return Err(
+                    "Setting material of renderer object, msg = sprite batch renderer objects are colored by their sprites"
+                        .to_string(),
+                );
+            }
+            RendererObjectIndex::ParticleEmitter(_) => None,
+        }
+        .ok_or_else(|| {
+            "Setting material of renderer object, msg = could not find RendererObject".to_string()
+        })?;
+
+        renderer_object
+            .write()
+            .set_gl_material(material.read().gl_material().clone());
+
+        let renderer_object = renderer_object.clone();
+        *material_observer = material.write().observe(move |material| {
+            renderer_object
+                .write()
+                .set_gl_material(material.gl_material().clone())
+        });
+
+        Ok(())
+    }
+
+    fn set_renderer_object_shader(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_shader: ArcRwLock<dyn RendererShader>,
+    ) -> Result<(), String> {
+        let shader = {
             let index = self
-                .get_mesh_index(&mesh)
-                .map_err(|e| format!("Creating renderer object from mesh, msg = {e}"))?;
+                .get_shader_index(&renderer_shader)
+                .map_err(|e| format!("Setting shader of renderer object, msg = {e}"))?;
 
-            self.renderer_meshes.get_ref(index.0).ok_or_else(|| {
-                "Creating renderer object from mesh, msg = could not find RendererMesh".to_string()
+            self.renderer_shaders.get_ref(index.0).ok_or_else(|| {
+                "Setting shader of renderer object, msg = could not find RendererShader".to_string()
             })?
         };
 
-        let mesh_renderer_object = rc_rw_lock_new(GLDrawableMesh::new(
-            mesh.read().gl_mesh().clone(),
-            material.read().gl_material().clone(),
-            **transform.read(),
-            gl_mesh_shader_program,
-        ));
+        let index = self
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Setting shader of renderer object, msg = {e}"))?;
+
+        let (renderer_object, shader_observer) = match index {
+            RendererObjectIndex::Mesh(index) => self.mesh_renderer_objects.get_mut(index).map(
+                |(renderer_object, _, _, shader_observer, _)| (renderer_object, shader_observer),
+            ),
+            RendererObjectIndex::Instanced(index) => self
+                .instanced_renderer_objects
+                .get_mut(index)
+                .map(|(renderer_object, _, _, shader_observer, _)| {
+                    (renderer_object, shader_observer)
+                }),
+            RendererObjectIndex::Skybox(_) => {
+                return Err(
+                    "Setting shader of renderer object, msg = skybox renderer objects use the skybox shader"
+                        .to_string(),
+                );
+            }
+            RendererObjectIndex::ParticleEmitter(_) => {
+                return Err(
+                    "Setting shader of renderer object, msg = particle emitter renderer objects use the particle shader"
+                        .to_string(),
+                );
+            }
+            RendererObjectIndex::SpriteBatch(_) => {
+                return Err(
+                    "Setting shader of renderer object, msg = sprite batch renderer objects use the sprite shader"
+                        .to_string(),
+                );
+            }
+        }
+        .ok_or_else(|| {
+            "Setting shader of renderer object, msg = could not find RendererObject".to_string()
+        })?;
 
-        let mesh_renderer_object_clone_0 = mesh_renderer_object.clone();
-        let mesh_renderer_object_clone_1 = mesh_renderer_object.clone();
-        let mesh_renderer_object_clone_2 = mesh_renderer_object.clone();
-        let mesh_renderer_object_clone_3 = mesh_renderer_object.clone();
+        let gl_mesh_shader_program = self
+            .gl_shader_program_container
+            .lock()
+            .get_mesh_shader_program(shader.read().gl_shader_program().clone());
+        renderer_object
+            .write()
+            .set_gl_mesh_shader_program(gl_mesh_shader_program);
 
+        let renderer_object = renderer_object.clone();
         let gl_shader_program_container = self.gl_shader_program_container.clone();
+        *shader_observer = shader.write().observe(move |shader| {
+            let gl_mesh_shader_program = gl_shader_program_container
+                .lock()
+                .get_mesh_shader_program(shader.gl_shader_program().clone());
+            renderer_object
+                .write()
+                .set_gl_mesh_shader_program(gl_mesh_shader_program);
+        });
 
-        let index = self.mesh_renderer_objects.create_object((
-            mesh_renderer_object,
-            transform.write().observe(move |transform| {
-                mesh_renderer_object_clone_0
-                    .write()
-                    .set_transform(transform);
-            }),
-            material.write().observe(move |material| {
-                mesh_renderer_object_clone_1
-                    .write()
-                    .set_gl_material(material.gl_material().clone())
-            }),
-            shader.write().observe(move |shader| {
-                let gl_mesh_shader_program = gl_shader_program_container
-                    .lock()
-                    .get_mesh_shader_program(shader.gl_shader_program().clone());
-                mesh_renderer_object_clone_2
-                    .write()
-                    .set_gl_mesh_shader_program(gl_mesh_shader_program);
-            }),
-            mesh.write().observe(move |mesh| {
-                mesh_renderer_object_clone_3
-                    .write()
-                    .set_gl_mesh(mesh.gl_mesh().clone());
-            }),
-        ));
+        Ok(())
+    }
+
+    fn set_renderer_object_mesh(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_mesh: ArcRwLock<dyn RendererMesh>,
+    ) -> Result<(), String> {
+        let mesh = {
+            let index = self
+                .get_mesh_index(&renderer_mesh)
+                .map_err(|e| format!("Setting mesh of renderer object, msg = {e}"))?;
+
+            self.renderer_meshes.get_ref(index.0).ok_or_else(|| {
+                "Setting mesh of renderer object, msg = could not find RendererMesh".to_string()
+            })?
+        };
+
+        let index = self
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Setting mesh of renderer object, msg = {e}"))?;
+
+        let (renderer_object, mesh_observer) = match index {
+            RendererObjectIndex::Mesh(index) => self
+                .mesh_renderer_objects
+                .get_mut(index)
+                .map(|(renderer_object, .., mesh_observer)| (renderer_object, mesh_observer)),
+            RendererObjectIndex::Instanced(index) => self
+                .instanced_renderer_objects
+                .get_mut(index)
+                .map(|(renderer_object, .., mesh_observer)| (renderer_object, mesh_observer)),
+            RendererObjectIndex::Skybox(_) => {
+                return Err(
+                    "Setting mesh of renderer object, msg = skybox renderer objects use the skybox mesh"
+                        .to_string(),
+                );
+            }
+            RendererObjectIndex::ParticleEmitter(_) => {
+                return Err(
+                    "Setting mesh of renderer object, msg = particle emitter renderer objects draw billboards"
+                        .to_string(),
+                );
+            }
+            RendererObjectIndex::SpriteBatch(_) => {
+                return Err(
+                    "Setting mesh of renderer object, msg = sprite batch renderer objects draw quads"
+                        .to_string(),
+                );
+            }
+        }
+        .ok_or_else(|| {
+            "Setting mesh of renderer object, msg = could not find RendererObject".to_string()
+        })?;
 
-        Ok(arc_rw_lock_new(RendererObjectIndex::Mesh(index)))
+        renderer_object
+            .write()
+            .set_gl_mesh(mesh.read().gl_mesh().clone());
+
+        let renderer_object = renderer_object.clone();
+        *mesh_observer = mesh.write().observe(move |mesh| {
+            renderer_object.write().set_gl_mesh(mesh.gl_mesh().clone());
+        });
+
+        if let RendererObjectIndex::Mesh(index) = index {
+            self.lod_mesh_observers.remove(&index);
+        }
+
+        Ok(())
     }
 
-    fn release_renderer_object(
+    fn set_renderer_object_probe(
         &mut self,
         renderer_object: ArcRwLock<dyn RendererObject>,
+        renderer_probe: Option<ArcRwLock<dyn RendererProbe>>,
     ) -> Result<(), String> {
+        let environment_map = if let Some(renderer_probe) = renderer_probe {
+            let index = self
+                .get_probe_index(&renderer_probe)
+                .map_err(|e| format!("Setting probe of renderer object, msg = {e}"))?;
+
+            let probe = &self
+                .renderer_probes
+                .get_ref(index.0)
+                .ok_or_else(|| {
+                    "Setting probe of renderer object, msg = could not find RendererProbe"
+                        .to_string()
+                })?
+                .0;
+
+            let environment_map = probe.read().environment_map().clone();
+            Some(environment_map)
+        } else {
+            None
+        };
+
         let index = self
             .get_renderer_object_index(&renderer_object)
-            .map_err(|e| format!("Releasing renderer object, msg = {e}"))?;
-
-        match index {
-            RendererObjectIndex::Mesh(index) => {
-                self.mesh_renderer_objects
-                    .release_object(index)
-                    .ok_or_else(|| {
-                        "Releasing renderer object, msg = could not find RendererObject".to_string()
-                    })?;
+            .map_err(|e| format!("Setting probe of renderer object, msg = {e}"))?;
+
+        let renderer_object = match index {
+            RendererObjectIndex::Mesh(index) => self
+                .mesh_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Instanced(index) => self
+                .instanced_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Skybox(index) => self.skybox_renderer_objects.get_ref(index),
+            RendererObjectIndex::ParticleEmitter(_) | RendererObjectIndex::SpriteBatch(_) => {
+                return Err(
+                    "Setting probe of renderer object, msg = particle emitter and sprite batch renderer objects are not lit"
+                        .to_string(),
+                );
             }
         }
+        .ok_or_else(|| {
+            "Setting probe of renderer object, msg = could not find RendererObject".to_string()
+        })?;
+
+        renderer_object.write().set_environment_map(environment_map);
 
         Ok(())
     }
 
-    fn add_renderer_object_to_group(
+    fn set_renderer_object_uniform(
         &mut self,
         renderer_object: ArcRwLock<dyn RendererObject>,
-        renderer_group: ArcRwLock<dyn RendererGroup>,
+        name: String,
+        value: Option<RendererUniformValue>,
     ) -> Result<(), String> {
-        let renderer_group = {
-            let index = self
-                .get_renderer_group_index(&renderer_group)
-                .map_err(|e| format!("Adding renderer object to group, msg = {e}"))?;
+        let index = self
+            .get_renderer_object_index(&renderer_object)
+            .map_err(|e| format!("Setting uniform of renderer object, msg = {e}"))?;
+
+        let renderer_object = match index {
+            RendererObjectIndex::Mesh(index) => self
+                .mesh_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Instanced(index) => self
+                .instanced_renderer_objects
+                .get_ref(index)
+                .map(|(renderer_object, ..)| renderer_object),
+            RendererObjectIndex::Skybox(index) => self.skybox_renderer_objects.get_ref(index),
+            RendererObjectIndex::ParticleEmitter(_) | RendererObjectIndex::SpriteBatch(_) => {
+                return Err(
+                    "Setting uniform of renderer object, msg = particle emitter and sprite batch renderer objects are not drawn with mesh shaders"
+                        .to_string(),
+                );
+            }
+        }
+        .ok_or_else(|| {
+            "Setting uniform of renderer object, msg = could not find RendererObject".to_string()
+        })?;
 
-            self.renderer_groups.get_ref(index.0).ok_or_else(|| {
-                "Adding renderer object to group, msg = could not find RendererGroup".to_string()
-            })?
-        };
+        renderer_object.write().set_uniform_override(name, value);
+
+        Ok(())
+    }
 
+    fn set_particle_emitter_settings(
+        &mut self,
+        renderer_object: ArcRwLock<dyn RendererObject>,
+        settings: ParticleEmitterSettings,
+    ) -> Result<(), String> {
         let index = self
             .get_renderer_object_index(&renderer_object)
-            .map_err(|e| format!("Adding renderer object to group, msg = {e}"))?;
+            .map_err(|e| format!("Setting particle emitter settings, msg = {e}"))?;
 
-        let missing_renderer_object_error_msg =
-            "Adding renderer object to group, msg = could not find renderer object".to_string();
-        let adding_twice_error_msg =
-            "Adding renderer object to group, msg = cannot add renderer object twice to the same group".to_string();
         match index {
-            RendererObjectIndex::Mesh(index) => {
-                let (
-                    renderer_object,
-                    _transform_observer,
-                    _material_observer,
-                    _shader_observer,
-                    _mesh_observer,
-                ) = self
-                    .mesh_renderer_objects
-                    .get_mut(index)
-                    .ok_or(missing_renderer_object_error_msg)?;
-                let old_value = renderer_group
-                    .write()
-                    .add_mesh_renderer_object(renderer_object.clone());
+            RendererObjectIndex::ParticleEmitter(index) => {
+                let (particle_emitter, ..) = self
+                    .particle_emitter_renderer_objects
+                    .get_ref(index)
+                    .ok_or_else(|| {
+                        "Setting particle emitter settings, msg = could not find RendererObject"
+                            .to_string()
+                    })?;
 
-                match old_value {
-                    Some(_) => Err(adding_twice_error_msg),
-                    None => Ok(()),
-                }?;
+                particle_emitter.write().set_settings(settings);
 
                 Ok(())
             }
+            RendererObjectIndex::Mesh(_)
+            | RendererObjectIndex::Instanced(_)
+            | RendererObjectIndex::Skybox(_)
+            | RendererObjectIndex::SpriteBatch(_) => Err(
+                "Setting particle emitter settings, msg = the RendererObject is not a particle emitter"
+                    .to_string(),
+            ),
         }
     }
 
-    fn remove_renderer_object_from_group(
+    fn set_sprite_batch_sprites(
         &mut self,
         renderer_object: ArcRwLock<dyn RendererObject>,
-        renderer_group: ArcRwLock<dyn RendererGroup>,
+        sprites: Vec<RendererSprite>,
     ) -> Result<(), String> {
-        let renderer_group = {
-            let index = self
-                .get_renderer_group_index(&renderer_group)
-                .map_err(|e| format!("Removing renderer object from group, msg = {e}"))?;
-
-            self.renderer_groups.get_ref(index.0).ok_or_else(|| {
-                "Removing renderer object from group, msg = could not find RendererGroup"
-                    .to_string()
-            })?
-        };
-
         let index = self
             .get_renderer_object_index(&renderer_object)
-            .map_err(|e| format!("Removing renderer object from group, msg = {e}"))?;
+            .map_err(|e| format!("Setting sprites of sprite batch, msg = {e}"))?;
 
-        let missing_renderer_object_error_msg =
-            "Removing renderer object from group, msg = could not find renderer object".to_string();
-        let missing_renderer_object_in_group_error_msg =
-            "Removing renderer object from group, msg = could not find renderer object in group"
-                .to_string();
         match index {
-            RendererObjectIndex::Mesh(index) => {
-                let (
-                    renderer_object,
-                    _transform_observer,
-                    _material_observer,
-                    _shader_observer,
-                    _mesh_observer,
-                ) = self
-                    .mesh_renderer_objects
-                    .get_mut(index)
-                    .ok_or(missing_renderer_object_error_msg)?;
+            RendererObjectIndex::SpriteBatch(index) => {
+                let (sprite_batch, _transform_observer) = self
+                    .sprite_batch_renderer_objects
+                    .get_ref(index)
+                    .ok_or_else(|| {
+                        "Setting sprites of sprite batch, msg = could not find RendererObject"
+                            .to_string()
+                    })?;
 
-                renderer_group
-                    .write()
-                    .remove_mesh_renderer_object(renderer_object)
-                    .ok_or(missing_renderer_object_in_group_error_msg)
-                    .map(|_| ())
+                sprite_batch.write().set_sprites(&sprites);
+
+                Ok(())
             }
+            RendererObjectIndex::Mesh(_)
+            | RendererObjectIndex::Instanced(_)
+            | RendererObjectIndex::Skybox(_)
+            | RendererObjectIndex::ParticleEmitter(_) => Err(
+                "Setting sprites of sprite batch, msg = the RendererObject is not a sprite batch"
+                    .to_string(),
+            ),
         }
     }
 
@@ -925,6 +3566,7 @@ impl RendererImpl for Renderer {
 
         let camera = arc_rw_lock_new(GLCamera {
             transform: **transform.read(),
+            parameters: None,
         });
 
         let index = self.renderer_cameras.create_object((
@@ -937,6 +3579,27 @@ impl RendererImpl for Renderer {
         Ok(arc_rw_lock_new(RendererCameraIndex(index)))
     }
 
+    fn update_camera_parameters(
+        &mut self,
+        camera: ArcRwLock<dyn RendererCamera>,
+        parameters: Option<RendererCameraParameters>,
+    ) -> Result<(), String> {
+        let index = self
+            .get_camera_index(&camera)
+            .map_err(|e| format!("Updating camera parameters, msg = {e}"))?;
+
+        self.renderer_cameras
+            .get_ref(index.0)
+            .ok_or_else(|| {
+                "Updating camera parameters, msg = could not find RendererCamera".to_string()
+            })?
+            .0
+            .write()
+            .parameters = parameters;
+
+        Ok(())
+    }
+
     fn release_camera(&mut self, camera: ArcRwLock<dyn RendererCamera>) -> Result<(), String> {
         let index = self
             .get_camera_index(&camera)
@@ -947,4 +3610,374 @@ impl RendererImpl for Renderer {
             .ok_or_else(|| "Releasing camera, msg = could not find RendererCamera".to_string())
             .map(|_| ())
     }
+
+    fn create_probe(
+        &mut self,
+        renderer_transform: ArcRwLock<dyn RendererTransform>,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<ArcRwLock<dyn RendererProbe>, String> {
+        let transform = {
+            let index = self
+                .get_transform_index(&renderer_transform)
+                .map_err(|e| format!("Creating probe, msg = {e}"))?;
+
+            self.renderer_transforms.get_ref(index.0).ok_or_else(|| {
+                "Creating probe, msg = could not find RendererTransform".to_string()
+            })?
+        };
+
+        let renderer_layer = {
+            let index = self
+                .get_renderer_layer_index(&renderer_layer)
+                .map_err(|e| format!("Creating probe, msg = {e}"))?;
+
+            self.renderer_layers
+                .get_ref(index.0)
+                .ok_or_else(|| "Creating probe, msg = could not find RendererLayer".to_string())?
+                .0
+                .clone()
+        };
+
+        let probe = rc_rw_lock_new(RendererProbeObject::new(
+            transform.read().position,
+            renderer_layer,
+            resolution,
+            refresh_mode,
+        ));
+
+        let index = self.renderer_probes.create_object((
+            probe.clone(),
+            transform.write().observe(move |transform| {
+                probe.write().position = transform.position;
+            }),
+        ));
+
+        Ok(arc_rw_lock_new(RendererProbeIndex(index)))
+    }
+
+    fn refresh_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        let index = self
+            .get_probe_index(&probe)
+            .map_err(|e| format!("Refreshing probe, msg = {e}"))?;
+
+        self.renderer_probes
+            .get_ref(index.0)
+            .ok_or_else(|| "Refreshing probe, msg = could not find RendererProbe".to_string())?
+            .0
+            .write()
+            .request_refresh();
+
+        Ok(())
+    }
+
+    fn set_probe_refresh_mode(
+        &mut self,
+        probe: ArcRwLock<dyn RendererProbe>,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Result<(), String> {
+        let index = self
+            .get_probe_index(&probe)
+            .map_err(|e| format!("Setting probe refresh mode, msg = {e}"))?;
+
+        self.renderer_probes
+            .get_ref(index.0)
+            .ok_or_else(|| {
+                "Setting probe refresh mode, msg = could not find RendererProbe".to_string()
+            })?
+            .0
+            .write()
+            .refresh_mode = refresh_mode;
+
+        Ok(())
+    }
+
+    fn release_probe(&mut self, probe: ArcRwLock<dyn RendererProbe>) -> Result<(), String> {
+        let index = self
+            .get_probe_index(&probe)
+            .map_err(|e| format!("Releasing probe, msg = {e}"))?;
+
+        self.renderer_probes
+            .release_object(index.0)
+            .ok_or_else(|| "Releasing probe, msg = could not find RendererProbe".to_string())
+            .map(|_| ())
+    }
+
+    fn stats(&self) -> RendererStats {
+        RendererStats {
+            number_of_textures: self.gl_texture_container.number_of_textures(),
+            texture_memory_bytes: self.gl_texture_container.texture_memory_bytes(),
+            texture_memory_budget_bytes: self.gl_texture_container.texture_memory_budget_bytes(),
+            number_of_evicted_textures: self.gl_texture_container.number_of_evicted_textures(),
+
+            draw_calls: self.draw_stats.draw_calls(),
+            triangles: self.draw_stats.triangles(),
+            pipeline_step_gpu_times_ms: self.pipeline_step_timer.gpu_times_ms().to_vec(),
+
+            number_of_meshes: self.gl_mesh_container.number_of_meshes(),
+            mesh_memory_bytes: self.gl_mesh_container.mesh_memory_bytes(),
+
+            ..Default::default()
+        }
+    }
+
+    fn set_texture_memory_budget(&mut self, budget_bytes: Option<usize>) -> Result<(), String> {
+        self.gl_texture_container
+            .set_texture_memory_budget(budget_bytes);
+        Ok(())
+    }
+
+    fn set_debug_render_mode(&mut self, mode: RendererDebugRenderMode) -> Result<(), String> {
+        if mode == RendererDebugRenderMode::Wireframe
+            && self
+                .gl_shader_program_container
+                .lock()
+                .glsl_version()
+                .is_es()
+        {
+            return Err(
+                "Setting debug render mode, msg = opengl es does not support wireframe rendering"
+                    .to_string(),
+            );
+        }
+
+        if mode == RendererDebugRenderMode::Normals && self.debug_normals_shader_program.is_none() {
+            let mut gl_shader_program_container = self.gl_shader_program_container.lock();
+            let gl_shader_program = gl_shader_program_container
+                .get_shader_program(
+                    "assets/shaders/debug_normals",
+                    self.asset_container.asset_reader(),
+                )
+                .map_err(|e| format!("Setting debug render mode, msg = {e:?}"))?;
+
+            self.debug_normals_shader_program =
+                Some(gl_shader_program_container.get_mesh_shader_program(gl_shader_program));
+        }
+
+        self.debug_render_mode = mode;
+
+        Ok(())
+    }
+
+    fn add_debug_lines(
+        &mut self,
+        lines: Vec<RendererDebugLine>,
+        lifetime_secs: f32,
+    ) -> Result<(), String> {
+        self.debug_line_buffer
+            .add_lines(lines, lifetime_secs, Instant::now());
+        Ok(())
+    }
+
+    fn reload_shaders(&mut self) -> Result<(), String> {
+        // the container is unlocked before notifying the observers, because they lock it too
+        let reload_results = self
+            .gl_shader_program_container
+            .lock()
+            .reload_shader_programs(self.asset_container.asset_reader());
+
+        let mut error_msgs = Vec::new();
+        for reload_result in reload_results {
+            let reloaded_shader_program = match reload_result {
+                Ok(reloaded_shader_program) => reloaded_shader_program,
+                Err(e) => {
+                    error_msgs.push(format!("{e:?}"));
+                    continue;
+                }
+            };
+
+            for renderer_shader in self.renderer_shaders.iter_mut() {
+                let uses_old_shader_program = Arc::ptr_eq(
+                    renderer_shader.read().gl_shader_program(),
+                    &reloaded_shader_program.old_shader_program,
+                );
+                if uses_old_shader_program {
+                    *renderer_shader.write().borrow_mut() = RendererShaderObject::new(
+                        reloaded_shader_program.new_shader_program.clone(),
+                    );
+                }
+            }
+        }
+
+        let mut gl_shader_program_container = self.gl_shader_program_container.lock();
+        if self.debug_normals_shader_program.is_some() {
+            let gl_shader_program = gl_shader_program_container
+                .get_shader_program(
+                    "assets/shaders/debug_normals",
+                    self.asset_container.asset_reader(),
+                )
+                .map_err(|e| format!("Reloading shaders, msg = {e:?}"))?;
+            self.debug_normals_shader_program =
+                Some(gl_shader_program_container.get_mesh_shader_program(gl_shader_program));
+        }
+        if self.gl_debug_lines.is_some() {
+            let gl_shader_program = gl_shader_program_container
+                .get_shader_program(
+                    "assets/shaders/debug_lines",
+                    self.asset_container.asset_reader(),
+                )
+                .map_err(|e| format!("Reloading shaders, msg = {e:?}"))?;
+            self.gl_debug_lines = Some(GLDebugLines::new(gl_shader_program));
+        }
+        if self.skybox_shader_program.is_some() {
+            let gl_shader_program = gl_shader_program_container
+                .get_shader_program("assets/shaders/skybox", self.asset_container.asset_reader())
+                .map_err(|e| format!("Reloading shaders, msg = {e:?}"))?;
+            let gl_mesh_shader_program =
+                gl_shader_program_container.get_mesh_shader_program(gl_shader_program);
+
+            for skybox_renderer_object in self.skybox_renderer_objects.iter_mut() {
+                skybox_renderer_object
+                    .write()
+                    .set_gl_mesh_shader_program(gl_mesh_shader_program.clone());
+            }
+            self.skybox_shader_program = Some(gl_mesh_shader_program);
+        }
+        if self.particle_shader_program.is_some() {
+            let gl_shader_program = gl_shader_program_container
+                .get_shader_program(
+                    "assets/shaders/particle_system",
+                    self.asset_container.asset_reader(),
+                )
+                .map_err(|e| format!("Reloading shaders, msg = {e:?}"))?;
+
+            for (particle_emitter, ..) in self.particle_emitter_renderer_objects.iter_mut() {
+                particle_emitter
+                    .write()
+                    .set_gl_shader_program(gl_shader_program.clone());
+            }
+            self.particle_shader_program = Some(gl_shader_program);
+        }
+        if self.sprite_shader_program.is_some() {
+            let gl_shader_program = gl_shader_program_container
+                .get_shader_program(
+                    "assets/shaders/sprite_batch",
+                    self.asset_container.asset_reader(),
+                )
+                .map_err(|e| format!("Reloading shaders, msg = {e:?}"))?;
+
+            for (sprite_batch, _transform_observer) in self.sprite_batch_renderer_objects.iter_mut()
+            {
+                sprite_batch
+                    .write()
+                    .set_gl_shader_program(gl_shader_program.clone());
+            }
+            self.sprite_shader_program = Some(gl_shader_program);
+        }
+
+        if error_msgs.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Reloading shaders, msg = {}",
+                error_msgs.join(", ")
+            ))
+        }
+    }
+
+    fn pick_renderer_object(
+        &mut self,
+        renderer_layer: ArcRwLock<dyn RendererLayer>,
+        screen_position: Vec2<f32>,
+        renderer_objects: Vec<ArcRwLock<dyn RendererObject>>,
+    ) -> Result<Option<usize>, String> {
+        let renderer_layer = {
+            let index = self
+                .get_renderer_layer_index(&renderer_layer)
+                .map_err(|e| format!("Picking renderer object, msg = {e}"))?;
+
+            self.renderer_layers
+                .get_ref(index.0)
+                .ok_or_else(|| {
+                    "Picking renderer object, msg = could not find RendererLayer".to_string()
+                })?
+                .0
+                .clone()
+        };
+
+        // the layer is picked in the viewport of the first step that draws it under the screen position,
+        // a layer can be drawn into several viewports, like the shared world of a split screen
+        let picking_ray = self
+            .renderer_pipeline_steps
+            .iter()
+            .find_map(|step| match step {
+                RendererPipelineStepObject::Draw {
+                    renderer_layer: step_renderer_layer,
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                    projection_matrix,
+                    ..
+                } if Rc::ptr_eq(step_renderer_layer, &renderer_layer) => {
+                    let view_projection_matrix = {
+                        let renderer_layer = renderer_layer.read();
+                        let viewport_dimensions = viewport_dimensions_ssc(
+                            self.window_dimensions,
+                            *viewport_start_ndc,
+                            *viewport_end_ndc,
+                        );
+                        renderer_layer.projection_matrix_or(
+                            viewport_dimensions.x,
+                            viewport_dimensions.y,
+                            projection_matrix,
+                        ) * renderer_layer.compute_view_matrix()
+                    };
+
+                    self.compute_picking_ray(
+                        screen_position,
+                        viewport_start_ndc,
+                        &(*viewport_end_ndc - *viewport_start_ndc),
+                        &view_projection_matrix,
+                    )
+                }
+                _ => None,
+            });
+        let (ray_origin, ray_direction) = match picking_ray {
+            Some(ray) => ray,
+            None => return Ok(None),
+        };
+
+        let mut picked_renderer_object: Option<(usize, f32)> = None;
+        for (candidate_index, renderer_object) in renderer_objects.iter().enumerate() {
+            let index = self
+                .get_renderer_object_index(renderer_object)
+                .map_err(|e| format!("Picking renderer object, msg = {e}"))?;
+
+            let renderer_object = match index {
+                RendererObjectIndex::Mesh(index) => self
+                    .mesh_renderer_objects
+                    .get_ref(index)
+                    .map(|(renderer_object, ..)| renderer_object),
+                RendererObjectIndex::Instanced(index) => self
+                    .instanced_renderer_objects
+                    .get_ref(index)
+                    .map(|(renderer_object, ..)| renderer_object),
+                RendererObjectIndex::Skybox(index) => self.skybox_renderer_objects.get_ref(index),
+                // the particles are too small and short lived to be picked
+                RendererObjectIndex::ParticleEmitter(_) => continue,
+                // the sprites have no mesh to intersect the picking ray with
+                RendererObjectIndex::SpriteBatch(_) => continue,
+            }
+            .ok_or_else(|| {
+                "Picking renderer object, msg = could not find RendererObject".to_string()
+            })?;
+
+            let distance = match renderer_object
+                .read()
+                .ray_intersection_distance(ray_origin, ray_direction)
+            {
+                Some(distance) => distance,
+                None => continue,
+            };
+
+            if picked_renderer_object
+                .map(|(_, picked_distance)| distance < picked_distance)
+                .unwrap_or(true)
+            {
+                picked_renderer_object = Some((candidate_index, distance));
+            }
+        }
+
+        Ok(picked_renderer_object.map(|(candidate_index, _)| candidate_index))
+    }
 }
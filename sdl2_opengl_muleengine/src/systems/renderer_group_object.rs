@@ -1,12 +1,71 @@
-use std::collections::BTreeMap;
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    rc::Rc,
+};
 
-use muleengine::bytifex_utils::sync::types::RcRwLock;
-use vek::{Mat4, Vec3};
+use muleengine::{bytifex_utils::sync::types::RcRwLock, camera::Frustum};
 
-use crate::gl_drawable_mesh::GLDrawableMesh;
+use crate::{
+    gl_drawable_mesh::{DrawParameters, GLDrawableMesh},
+    gl_occlusion_culler::GLOcclusionCuller,
+    gl_particle_emitter::GLParticleEmitter,
+    gl_sprite_batch::GLSpriteBatch,
+};
+
+// the items are sorted again only if the draw order is marked dirty, e.g. an item is added or
+// removed, or the material, the shader or the mesh of an item is replaced
+pub(crate) struct DrawOrder<ItemType> {
+    sorted_items: Vec<ItemType>,
+    dirty_flag: Rc<Cell<bool>>,
+}
+
+impl<ItemType: Clone> Default for DrawOrder<ItemType> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<ItemType: Clone> DrawOrder<ItemType> {
+    pub fn new() -> Self {
+        Self {
+            sorted_items: Vec::new(),
+            dirty_flag: Rc::new(Cell::new(true)),
+        }
+    }
+
+    // the items set the shared flag when their key changes
+    pub fn dirty_flag(&self) -> &Rc<Cell<bool>> {
+        &self.dirty_flag
+    }
+
+    pub fn invalidate(&mut self) {
+        self.dirty_flag.set(true);
+    }
+
+    pub fn sorted_items<'a, KeyType: Ord>(
+        &mut self,
+        items: impl Iterator<Item = &'a ItemType>,
+        key_of: impl Fn(&ItemType) -> KeyType,
+    ) -> impl Iterator<Item = &ItemType>
+    where
+        ItemType: 'a,
+    {
+        if self.dirty_flag.replace(false) {
+            self.sorted_items = items.cloned().collect();
+            self.sorted_items.sort_by_cached_key(key_of);
+        }
+
+        self.sorted_items.iter()
+    }
+}
 
 pub(crate) struct RendererGroupObject {
     mesh_renderer_objects: BTreeMap<*const GLDrawableMesh, RcRwLock<GLDrawableMesh>>,
+    // sorted by the draw sort key, so consecutive draws change as little gl state as possible
+    draw_order: RefCell<DrawOrder<RcRwLock<GLDrawableMesh>>>,
+    particle_emitters: BTreeMap<*const GLParticleEmitter, RcRwLock<GLParticleEmitter>>,
+    sprite_batches: BTreeMap<*const GLSpriteBatch, RcRwLock<GLSpriteBatch>>,
 }
 
 impl Default for RendererGroupObject {
@@ -19,6 +78,9 @@ impl RendererGroupObject {
     pub fn new() -> Self {
         Self {
             mesh_renderer_objects: BTreeMap::new(),
+            draw_order: RefCell::new(DrawOrder::new()),
+            particle_emitters: BTreeMap::new(),
+            sprite_batches: BTreeMap::new(),
         }
     }
 
@@ -26,8 +88,16 @@ impl RendererGroupObject {
         &mut self,
         renderer_object: RcRwLock<GLDrawableMesh>,
     ) -> Option<RcRwLock<GLDrawableMesh>> {
-        self.mesh_renderer_objects
-            .insert(renderer_object.data_ptr(), renderer_object)
+        let ptr: *const GLDrawableMesh = renderer_object.data_ptr();
+        if !self.mesh_renderer_objects.contains_key(&ptr) {
+            let mut draw_order = self.draw_order.borrow_mut();
+            renderer_object
+                .write()
+                .add_draw_order_dirty_flag(draw_order.dirty_flag().clone());
+            draw_order.invalidate();
+        }
+
+        self.mesh_renderer_objects.insert(ptr, renderer_object)
     }
 
     pub fn remove_mesh_renderer_object(
@@ -35,19 +105,154 @@ impl RendererGroupObject {
         renderer_object: &RcRwLock<GLDrawableMesh>,
     ) -> Option<RcRwLock<GLDrawableMesh>> {
         let ptr: *const GLDrawableMesh = renderer_object.data_ptr();
-        self.mesh_renderer_objects.remove(&ptr)
+        let removed_renderer_object = self.mesh_renderer_objects.remove(&ptr)?;
+
+        let mut draw_order = self.draw_order.borrow_mut();
+        removed_renderer_object
+            .write()
+            .remove_draw_order_dirty_flag(draw_order.dirty_flag());
+        draw_order.invalidate();
+
+        Some(removed_renderer_object)
+    }
+
+    pub fn add_particle_emitter(
+        &mut self,
+        particle_emitter: RcRwLock<GLParticleEmitter>,
+    ) -> Option<RcRwLock<GLParticleEmitter>> {
+        self.particle_emitters
+            .insert(particle_emitter.data_ptr(), particle_emitter)
+    }
+
+    pub fn remove_particle_emitter(
+        &mut self,
+        particle_emitter: &RcRwLock<GLParticleEmitter>,
+    ) -> Option<RcRwLock<GLParticleEmitter>> {
+        let ptr: *const GLParticleEmitter = particle_emitter.data_ptr();
+        self.particle_emitters.remove(&ptr)
     }
 
-    pub fn draw(
+    // the particles are blended, so they are drawn after every renderer object of the layer
+    pub fn draw_particle_emitters(&self, parameters: &DrawParameters) {
+        for particle_emitter in self.particle_emitters.values() {
+            particle_emitter.read().draw(parameters);
+        }
+    }
+
+    pub fn add_sprite_batch(
+        &mut self,
+        sprite_batch: RcRwLock<GLSpriteBatch>,
+    ) -> Option<RcRwLock<GLSpriteBatch>> {
+        self.sprite_batches
+            .insert(sprite_batch.data_ptr(), sprite_batch)
+    }
+
+    pub fn remove_sprite_batch(
+        &mut self,
+        sprite_batch: &RcRwLock<GLSpriteBatch>,
+    ) -> Option<RcRwLock<GLSpriteBatch>> {
+        let ptr: *const GLSpriteBatch = sprite_batch.data_ptr();
+        self.sprite_batches.remove(&ptr)
+    }
+
+    pub fn draw_sprite_batches(&self, parameters: &DrawParameters) {
+        for sprite_batch in self.sprite_batches.values() {
+            sprite_batch.read().draw(parameters);
+        }
+    }
+
+    // the transparent renderer objects are collected, so they can be drawn after the opaque ones
+    pub fn draw_opaque(
         &self,
-        eye_position: &Vec3<f32>,
-        projection_matrix: &Mat4<f32>,
-        view_matrix: &Mat4<f32>,
+        parameters: &DrawParameters,
+        frustum: &Frustum,
+        occlusion_culler: Option<&GLOcclusionCuller>,
+        transparent_renderer_objects: &mut Vec<RcRwLock<GLDrawableMesh>>,
     ) {
-        for renderer_object in self.mesh_renderer_objects.values() {
-            renderer_object
-                .read()
-                .draw(eye_position, projection_matrix, view_matrix);
+        let mut draw_order = self.draw_order.borrow_mut();
+        let sorted_renderer_objects = draw_order
+            .sorted_items(self.mesh_renderer_objects.values(), |renderer_object| {
+                renderer_object.read().draw_sort_key()
+            });
+
+        for renderer_object in sorted_renderer_objects {
+            let renderer_object_guard = renderer_object.read();
+            if !renderer_object_guard.is_visible_in(frustum) {
+                continue;
+            }
+
+            if let Some(occlusion_culler) = occlusion_culler {
+                if occlusion_culler.is_occluded(&renderer_object_guard, parameters.eye_position) {
+                    continue;
+                }
+            }
+
+            if renderer_object_guard.is_transparent() {
+                transparent_renderer_objects.push(renderer_object.clone());
+            } else {
+                renderer_object_guard.draw(parameters);
+            }
         }
     }
 }
+
+pub(crate) fn draw_back_to_front(
+    renderer_objects: &mut [RcRwLock<GLDrawableMesh>],
+    parameters: &DrawParameters,
+) {
+    renderer_objects.sort_by(|a, b| {
+        let distance_a = a.read().distance_squared_from(parameters.eye_position);
+        let distance_b = b.read().distance_squared_from(parameters.eye_position);
+        distance_b.total_cmp(&distance_a)
+    });
+
+    for renderer_object in renderer_objects.iter() {
+        renderer_object.read().draw(parameters);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    // the key stands for the material of a renderer object
+    fn sorted_keys(draw_order: &mut DrawOrder<Rc<Cell<u32>>>, items: &[Rc<Cell<u32>>]) -> Vec<u32> {
+        draw_order
+            .sorted_items(items.iter(), |item| item.get())
+            .map(|item| item.get())
+            .collect()
+    }
+
+    #[test]
+    fn draw_order_follows_the_changed_material_when_it_is_marked_dirty() {
+        let items = vec![
+            Rc::new(Cell::new(3)),
+            Rc::new(Cell::new(1)),
+            Rc::new(Cell::new(2)),
+        ];
+        let mut draw_order = DrawOrder::new();
+
+        assert_eq!(vec![1, 2, 3], sorted_keys(&mut draw_order, &items));
+
+        // the items are not sorted again until the draw order is marked dirty
+        items[0].set(0);
+        assert_eq!(vec![1, 2, 0], sorted_keys(&mut draw_order, &items));
+
+        draw_order.dirty_flag().set(true);
+        assert_eq!(vec![0, 1, 2], sorted_keys(&mut draw_order, &items));
+    }
+
+    #[test]
+    fn draw_order_is_sorted_again_after_invalidation() {
+        let mut items = vec![Rc::new(Cell::new(2)), Rc::new(Cell::new(1))];
+        let mut draw_order = DrawOrder::new();
+
+        assert_eq!(vec![1, 2], sorted_keys(&mut draw_order, &items));
+
+        items.push(Rc::new(Cell::new(0)));
+        draw_order.invalidate();
+        assert_eq!(vec![0, 1, 2], sorted_keys(&mut draw_order, &items));
+    }
+}
@@ -1,20 +1,55 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, rc::Rc, sync::Arc};
 
-use muleengine::bytifex_utils::sync::types::{ArcRwLock, RcRwLock};
-use vek::Mat4;
+use muleengine::{
+    bytifex_utils::sync::types::{ArcRwLock, RcRwLock},
+    camera::Frustum,
+    renderer::{
+        renderer_layer_projection::{reverse_depth, RendererLayerProjection},
+        renderer_layer_settings::RendererLayerSettings,
+    },
+};
+use vek::{Mat4, Vec3};
 
-use super::{gl_camera::GLCamera, renderer_group_object::RendererGroupObject};
+use crate::{
+    gl_draw_stats::GLDrawStats,
+    gl_drawable_mesh::{DrawParameters, ShadowMapBinding},
+    gl_frame_uniforms::GLFrameUniforms,
+    gl_mesh_shader_program::GLMeshShaderProgram,
+    gl_occlusion_culler::GLOcclusionCuller,
+    opengl_utils::gl_state_cache::with_gl_state_cache,
+};
+
+use super::{
+    gl_camera::GLCamera,
+    renderer_group_object::{draw_back_to_front, RendererGroupObject},
+};
 
 pub(crate) struct RendererLayerObject {
     camera: ArcRwLock<GLCamera>,
     renderer_groups: BTreeMap<*const RendererGroupObject, RcRwLock<RendererGroupObject>>,
+    settings: RendererLayerSettings,
+    projection: Option<RendererLayerProjection>,
+    override_shader_program: Option<Arc<GLMeshShaderProgram>>,
+    occlusion_culler: Option<GLOcclusionCuller>,
+    draw_stats: Rc<GLDrawStats>,
+    frame_uniforms: Rc<GLFrameUniforms>,
 }
 
 impl RendererLayerObject {
-    pub fn new(camera: ArcRwLock<GLCamera>) -> Self {
+    pub fn new(
+        camera: ArcRwLock<GLCamera>,
+        draw_stats: Rc<GLDrawStats>,
+        frame_uniforms: Rc<GLFrameUniforms>,
+    ) -> Self {
         Self {
             camera,
             renderer_groups: BTreeMap::new(),
+            settings: RendererLayerSettings::default(),
+            projection: None,
+            override_shader_program: None,
+            occlusion_culler: None,
+            draw_stats,
+            frame_uniforms,
         }
     }
 
@@ -34,15 +69,163 @@ impl RendererLayerObject {
         self.renderer_groups.remove(&ptr)
     }
 
-    pub fn draw(&self, projection_matrix: &Mat4<f32>) {
+    pub fn settings(&self) -> &RendererLayerSettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: RendererLayerSettings) {
+        self.settings = settings;
+    }
+
+    pub fn set_projection(&mut self, projection: Option<RendererLayerProjection>) {
+        self.projection = projection;
+    }
+
+    // the projection of the layer overrides the parameters of the camera,
+    // which override the projection matrix of the pipeline step
+    pub fn projection_matrix_or(
+        &self,
+        width: usize,
+        height: usize,
+        step_projection_matrix: &Mat4<f32>,
+    ) -> Mat4<f32> {
+        match (&self.projection, &self.camera.read().parameters) {
+            (Some(projection), _) => projection.compute_projection_matrix(width, height),
+            (None, Some(parameters)) => parameters.compute_projection_matrix(width, height),
+            (None, None) => *step_projection_matrix,
+        }
+    }
+
+    pub fn set_override_shader_program(
+        &mut self,
+        override_shader_program: Option<Arc<GLMeshShaderProgram>>,
+    ) {
+        self.override_shader_program = override_shader_program;
+    }
+
+    pub fn has_occlusion_culler(&self) -> bool {
+        self.occlusion_culler.is_some()
+    }
+
+    pub fn set_occlusion_culler(&mut self, occlusion_culler: Option<GLOcclusionCuller>) {
+        self.occlusion_culler = occlusion_culler;
+    }
+
+    pub fn compute_view_matrix(&self) -> Mat4<f32> {
+        self.camera.read().compute_view_matrix()
+    }
+
+    // the debug shader program takes precedence over the override shader program of the layer,
+    // the occlusion queries are only made from the camera of the layer
+    pub fn draw(
+        &self,
+        projection_matrix: &Mat4<f32>,
+        shadow_map_binding: Option<&ShadowMapBinding>,
+        debug_shader_program: Option<&Arc<GLMeshShaderProgram>>,
+    ) {
         let camera = self.camera.read();
 
         let view_matrix = camera.compute_view_matrix();
 
+        let projection_matrix = if self.settings.reverse_z {
+            reverse_depth(*projection_matrix)
+        } else {
+            *projection_matrix
+        };
+
+        self.draw_with_shader_program(
+            &camera.transform.position,
+            &projection_matrix,
+            &view_matrix,
+            self.settings.reverse_z,
+            shadow_map_binding,
+            debug_shader_program.or(self.override_shader_program.as_ref()),
+            self.occlusion_culler
+                .as_ref()
+                .filter(|_| self.settings.occlusion_culling),
+        );
+    }
+
+    pub fn draw_from(
+        &self,
+        eye_position: &Vec3<f32>,
+        projection_matrix: &Mat4<f32>,
+        view_matrix: &Mat4<f32>,
+        shadow_map_binding: Option<&ShadowMapBinding>,
+    ) {
+        // the shadow maps and the probes are sampled with the regular depth direction
+        self.draw_with_shader_program(
+            eye_position,
+            projection_matrix,
+            view_matrix,
+            false,
+            shadow_map_binding,
+            self.override_shader_program.as_ref(),
+            None,
+        );
+    }
+
+    fn draw_with_shader_program(
+        &self,
+        eye_position: &Vec3<f32>,
+        projection_matrix: &Mat4<f32>,
+        view_matrix: &Mat4<f32>,
+        reverse_z: bool,
+        shadow_map_binding: Option<&ShadowMapBinding>,
+        override_shader_program: Option<&Arc<GLMeshShaderProgram>>,
+        occlusion_culler: Option<&GLOcclusionCuller>,
+    ) {
+        with_gl_state_cache(|cache| cache.set_reverse_z(reverse_z));
+
+        self.frame_uniforms.update(
+            eye_position,
+            projection_matrix,
+            view_matrix,
+            shadow_map_binding.map(|binding| &binding.light_space_matrix),
+            self.settings.exposure,
+        );
+
+        let parameters = DrawParameters {
+            eye_position,
+            projection_matrix,
+            view_matrix,
+            exposure: self.settings.exposure,
+            override_shader_program,
+            shadow_map_binding,
+            draw_stats: &self.draw_stats,
+        };
+
+        let frustum = Frustum::from_view_projection(*projection_matrix * *view_matrix);
+
+        let mut transparent_renderer_objects = Vec::new();
         for renderer_group in self.renderer_groups.values() {
-            renderer_group
-                .read()
-                .draw(&camera.transform.position, projection_matrix, &view_matrix);
+            renderer_group.read().draw_opaque(
+                &parameters,
+                &frustum,
+                occlusion_culler,
+                &mut transparent_renderer_objects,
+            );
+        }
+
+        // the boxes are tested before the transparent objects, since those do not write depth
+        if let Some(occlusion_culler) = occlusion_culler {
+            occlusion_culler.issue_queries(projection_matrix, view_matrix, &self.draw_stats);
+        }
+
+        draw_back_to_front(&mut transparent_renderer_objects, &parameters);
+
+        // the override shader programs are made for meshes, so they do not apply to particles and sprites
+        if override_shader_program.is_none() {
+            for renderer_group in self.renderer_groups.values() {
+                renderer_group.read().draw_particle_emitters(&parameters);
+            }
+
+            for renderer_group in self.renderer_groups.values() {
+                renderer_group.read().draw_sprite_batches(&parameters);
+            }
         }
+
+        // the clears of the next steps expect the regular clear depth
+        with_gl_state_cache(|cache| cache.set_reverse_z(false));
     }
 }
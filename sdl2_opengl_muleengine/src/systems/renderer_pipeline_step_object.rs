@@ -1,14 +1,33 @@
-use std::sync::Arc;
+use std::{cell::Cell, rc::Rc, sync::Arc};
 
-use muleengine::bytifex_utils::sync::types::RcRwLock;
-use vek::{Mat4, Vec2};
+use muleengine::bytifex_utils::sync::{
+    observable_fn::Observable,
+    types::{ArcRwLock, RcRwLock},
+};
+use vek::{Mat4, Vec2, Vec4};
 
-use super::renderer_layer_object::RendererLayerObject;
+use crate::{
+    gl_draw_stats::GLDrawStats,
+    gl_shader_program::{GLShaderProgram, RendererShaderObject},
+    opengl_utils::{
+        depth_texture_2d::DepthTexture2D,
+        frame_buffer_object::{
+            DepthFrameBufferObject, FrameBufferObject, MultisampleFrameBufferObject,
+        },
+        gl_state_cache::with_gl_state_cache,
+        texture_2d::Texture2D,
+        vertex_array_object::VertexArrayObject,
+    },
+};
+
+use super::{gl_camera::GLCamera, renderer_layer_object::RendererLayerObject};
 
 pub(crate) enum RendererPipelineStepObject {
     Clear {
         depth: bool,
         color: bool,
+        stencil: bool,
+        clear_color: Vec4<f32>,
 
         viewport_start_ndc: Vec2<f32>,
         viewport_end_ndc: Vec2<f32>,
@@ -22,4 +41,198 @@ pub(crate) enum RendererPipelineStepObject {
         projection_matrix: Mat4<f32>,
         compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send>,
     },
+    ShadowPass {
+        renderer_layer: RcRwLock<RendererLayerObject>,
+        light_camera: ArcRwLock<GLCamera>,
+
+        light_projection_matrix: Mat4<f32>,
+        shadow_map: Rc<DepthTexture2D>,
+        frame_buffer_object: DepthFrameBufferObject,
+    },
+    DrawToTexture {
+        renderer_layer: RcRwLock<RendererLayerObject>,
+
+        projection_matrix: Mat4<f32>,
+        render_texture: Arc<Texture2D>,
+        frame_buffer_object: FrameBufferObject,
+        // the layer is drawn into it and resolved into the render texture if multisampling is enabled
+        multisample_frame_buffer_object: Option<MultisampleFrameBufferObject>,
+    },
+    PostProcess {
+        shader: ArcRwLock<Observable<RendererShaderObject>>,
+        input_textures: Vec<Arc<Texture2D>>,
+
+        is_last: bool,
+    },
+    DrawDebugLines {
+        renderer_layer: RcRwLock<RendererLayerObject>,
+
+        viewport_start_ndc: Vec2<f32>,
+        viewport_end_ndc: Vec2<f32>,
+
+        projection_matrix: Mat4<f32>,
+        compute_projection_matrix: Arc<dyn Fn(usize, usize) -> Mat4<f32> + Send>,
+    },
+}
+
+pub(crate) const MAX_POST_PROCESS_INPUT_TEXTURES: usize = 8;
+
+// the steps before a post process step draw into one of the framebuffers, the post process step
+// samples it and draws into the other one, or into the screen if it is the last one
+pub(crate) struct PostProcessFrameBuffers {
+    frame_buffer_objects: [FrameBufferObject; 2],
+    color_textures: [Texture2D; 2],
+    current: Cell<usize>,
+    // the steps before the first post process step draw into it if multisampling is enabled,
+    // it is resolved into the current framebuffer before it is sampled
+    multisample_frame_buffer_object: Option<MultisampleFrameBufferObject>,
+    resolved: Cell<bool>,
+
+    fullscreen_vertex_array_object: VertexArrayObject,
+    window_dimensions: Vec2<usize>,
+}
+
+impl PostProcessFrameBuffers {
+    pub fn new(window_dimensions: Vec2<usize>, msaa_samples: usize) -> Result<Self, String> {
+        let width = window_dimensions.x.max(1);
+        let height = window_dimensions.y.max(1);
+
+        let frame_buffer_objects = [
+            FrameBufferObject::new(width, height),
+            FrameBufferObject::new(width, height),
+        ];
+        let color_textures = [
+            Texture2D::new_render_target(width, height),
+            Texture2D::new_render_target(width, height),
+        ];
+
+        for (frame_buffer_object, color_texture) in
+            frame_buffer_objects.iter().zip(color_textures.iter())
+        {
+            frame_buffer_object.use_fbo(gl::TEXTURE_2D, color_texture.texture_id(), || {});
+            if !frame_buffer_object.is_complete() {
+                return Err("post process framebuffer is incomplete".to_string());
+            }
+        }
+
+        let multisample_frame_buffer_object = if msaa_samples > 1 {
+            let multisample_frame_buffer_object =
+                MultisampleFrameBufferObject::new(width, height, msaa_samples);
+            if !multisample_frame_buffer_object.is_complete() {
+                return Err("multisampled post process framebuffer is incomplete".to_string());
+            }
+
+            Some(multisample_frame_buffer_object)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            frame_buffer_objects,
+            color_textures,
+            current: Cell::new(0),
+            multisample_frame_buffer_object,
+            resolved: Cell::new(false),
+
+            // the fullscreen triangle is generated from gl_VertexID, so there are no vertex attributes
+            fullscreen_vertex_array_object: VertexArrayObject::new(|_| {}),
+            window_dimensions: Vec2::new(width, height),
+        })
+    }
+
+    pub fn begin_frame(&self) {
+        self.current.set(0);
+        self.resolved.set(false);
+
+        match &self.multisample_frame_buffer_object {
+            Some(multisample_frame_buffer_object) => multisample_frame_buffer_object.bind(),
+            None => self.bind_current(),
+        }
+    }
+
+    pub fn draw(
+        &self,
+        gl_shader_program: &GLShaderProgram,
+        input_textures: &[Arc<Texture2D>],
+        is_last: bool,
+        draw_stats: &GLDrawStats,
+    ) {
+        let source_index = self.current.get();
+
+        if let Some(multisample_frame_buffer_object) = &self.multisample_frame_buffer_object {
+            if !self.resolved.get() {
+                multisample_frame_buffer_object.resolve_into(
+                    &self.frame_buffer_objects[source_index],
+                    gl::TEXTURE_2D,
+                    self.color_textures[source_index].texture_id(),
+                );
+                self.resolved.set(true);
+            }
+        }
+
+        if is_last {
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+        } else {
+            self.current.set(1 - source_index);
+            self.bind_current();
+        }
+
+        unsafe {
+            gl::Viewport(
+                0,
+                0,
+                self.window_dimensions.x as i32,
+                self.window_dimensions.y as i32,
+            );
+        }
+
+        with_gl_state_cache(|cache| {
+            cache.set_polygon_mode(gl::FILL);
+            cache.set_capability(gl::DEPTH_TEST, false);
+            cache.set_capability(gl::BLEND, false);
+        });
+
+        let shader_program = &gl_shader_program.shader_program;
+        shader_program.use_program();
+
+        self.color_textures[source_index].use_texture(0);
+        if let Some(uniform) = shader_program.get_uniform_by_name("sceneTexture") {
+            uniform.send_uniform_1i(0);
+        }
+
+        let input_textures =
+            &input_textures[..input_textures.len().min(MAX_POST_PROCESS_INPUT_TEXTURES)];
+        for (index, input_texture) in input_textures.iter().enumerate() {
+            input_texture.use_texture(index + 1);
+        }
+        let texture_layers = (1..=input_textures.len() as i32).collect::<Vec<_>>();
+        if let Some(uniform) = shader_program.get_uniform_by_name("inputTextures") {
+            uniform.send_uniform_1iv(&texture_layers, texture_layers.len());
+        }
+        if let Some(uniform) = shader_program.get_uniform_by_name("numberOfInputTextures") {
+            uniform.send_uniform_1i(input_textures.len() as i32);
+        }
+
+        if let Some(uniform) = shader_program.get_uniform_by_name("resolution") {
+            uniform.send_uniform_2f(
+                self.window_dimensions.x as f32,
+                self.window_dimensions.y as f32,
+            );
+        }
+
+        self.fullscreen_vertex_array_object.use_vao(|| unsafe {
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        });
+        draw_stats.add_draw_call(1);
+
+        with_gl_state_cache(|cache| cache.set_capability(gl::DEPTH_TEST, true));
+    }
+
+    fn bind_current(&self) {
+        let current = self.current.get();
+        self.frame_buffer_objects[current]
+            .bind(gl::TEXTURE_2D, self.color_textures[current].texture_id());
+    }
 }
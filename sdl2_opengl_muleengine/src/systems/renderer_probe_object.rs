@@ -0,0 +1,103 @@
+use std::rc::Rc;
+
+use muleengine::{
+    bytifex_utils::sync::types::{rc_rw_lock_new, RcRwLock},
+    renderer::renderer_probe_refresh_mode::RendererProbeRefreshMode,
+};
+use vek::{Mat4, Vec3};
+
+use crate::opengl_utils::{
+    frame_buffer_object::FrameBufferObject, texture_cubemap::TextureCubemap,
+};
+
+use super::renderer_layer_object::RendererLayerObject;
+
+pub(crate) type EnvironmentMap = RcRwLock<Rc<TextureCubemap>>;
+
+pub(crate) struct RendererProbeObject {
+    pub position: Vec3<f32>,
+    pub refresh_mode: RendererProbeRefreshMode,
+    renderer_layer: RcRwLock<RendererLayerObject>,
+    refresh_requested: bool,
+    projection_matrix: Mat4<f32>,
+    environment_map: EnvironmentMap,
+    back_cubemap: Rc<TextureCubemap>,
+    frame_buffer_object: FrameBufferObject,
+}
+
+fn cubemap_face_orientations() -> [(Vec3<f32>, Vec3<f32>); 6] {
+    [
+        (Vec3::unit_x(), -Vec3::unit_y()),
+        (-Vec3::unit_x(), -Vec3::unit_y()),
+        (Vec3::unit_y(), Vec3::unit_z()),
+        (-Vec3::unit_y(), -Vec3::unit_z()),
+        (Vec3::unit_z(), -Vec3::unit_y()),
+        (-Vec3::unit_z(), -Vec3::unit_y()),
+    ]
+}
+
+impl RendererProbeObject {
+    pub fn new(
+        position: Vec3<f32>,
+        renderer_layer: RcRwLock<RendererLayerObject>,
+        resolution: usize,
+        refresh_mode: RendererProbeRefreshMode,
+    ) -> Self {
+        Self {
+            position,
+            refresh_mode,
+            renderer_layer,
+            refresh_requested: true,
+            projection_matrix: Mat4::perspective_fov_rh_zo(
+                90.0f32.to_radians(),
+                resolution as f32,
+                resolution as f32,
+                0.01,
+                1000.0,
+            ),
+            environment_map: rc_rw_lock_new(Rc::new(TextureCubemap::new(resolution))),
+            back_cubemap: Rc::new(TextureCubemap::new(resolution)),
+            frame_buffer_object: FrameBufferObject::new(resolution, resolution),
+        }
+    }
+
+    pub fn environment_map(&self) -> &EnvironmentMap {
+        &self.environment_map
+    }
+
+    pub fn request_refresh(&mut self) {
+        self.refresh_requested = true;
+    }
+
+    pub fn refresh_if_needed(&mut self) {
+        if !self.refresh_requested && self.refresh_mode != RendererProbeRefreshMode::EveryFrame {
+            return;
+        }
+
+        self.refresh_requested = false;
+
+        let renderer_layer = self.renderer_layer.read();
+        for (face_index, (direction, up)) in cubemap_face_orientations().iter().enumerate() {
+            let view_matrix = Mat4::look_at_rh(self.position, self.position + *direction, *up);
+
+            self.frame_buffer_object.use_fbo(
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X + face_index as u32,
+                self.back_cubemap.texture_id(),
+                || {
+                    unsafe {
+                        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                    }
+
+                    renderer_layer.draw_from(
+                        &self.position,
+                        &self.projection_matrix,
+                        &view_matrix,
+                        None,
+                    );
+                },
+            );
+        }
+
+        std::mem::swap(&mut *self.environment_map.write(), &mut self.back_cubemap);
+    }
+}
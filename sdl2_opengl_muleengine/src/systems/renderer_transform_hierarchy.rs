@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use muleengine::bytifex_utils::containers::object_pool::ObjectPoolIndex;
+use vek::Transform;
+
+struct RendererTransformNode {
+    local_transform: Transform<f32, f32, f32>,
+    parent: Option<ObjectPoolIndex>,
+    children: BTreeSet<ObjectPoolIndex>,
+}
+
+pub(crate) struct RendererTransformHierarchy {
+    nodes: BTreeMap<ObjectPoolIndex, RendererTransformNode>,
+}
+
+impl Default for RendererTransformHierarchy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RendererTransformHierarchy {
+    pub fn new() -> Self {
+        Self {
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    pub fn parent(&self, index: ObjectPoolIndex) -> Option<ObjectPoolIndex> {
+        self.nodes.get(&index).and_then(|node| node.parent)
+    }
+
+    pub fn children(&self, index: ObjectPoolIndex) -> Vec<ObjectPoolIndex> {
+        self.nodes
+            .get(&index)
+            .map(|node| node.children.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn local_transform(&self, index: ObjectPoolIndex) -> Option<Transform<f32, f32, f32>> {
+        self.nodes
+            .get(&index)
+            .filter(|node| node.parent.is_some())
+            .map(|node| node.local_transform)
+    }
+
+    pub fn set_local_transform(
+        &mut self,
+        index: ObjectPoolIndex,
+        local_transform: Transform<f32, f32, f32>,
+    ) {
+        if let Some(node) = self.nodes.get_mut(&index) {
+            node.local_transform = local_transform;
+        }
+    }
+
+    pub fn is_ancestor(&self, ancestor: ObjectPoolIndex, index: ObjectPoolIndex) -> bool {
+        let mut current = self.parent(index);
+        while let Some(current_index) = current {
+            if current_index == ancestor {
+                return true;
+            }
+            current = self.parent(current_index);
+        }
+
+        false
+    }
+
+    pub fn set_parent(
+        &mut self,
+        index: ObjectPoolIndex,
+        parent: Option<ObjectPoolIndex>,
+        local_transform: Transform<f32, f32, f32>,
+    ) {
+        if let Some(old_parent) = self.parent(index) {
+            if let Some(old_parent_node) = self.nodes.get_mut(&old_parent) {
+                old_parent_node.children.remove(&index);
+            }
+            self.remove_node_if_unused(old_parent);
+        }
+
+        let node = self
+            .nodes
+            .entry(index)
+            .or_insert_with(|| RendererTransformNode {
+                local_transform,
+                parent: None,
+                children: BTreeSet::new(),
+            });
+        node.local_transform = local_transform;
+        node.parent = parent;
+
+        if let Some(parent) = parent {
+            self.nodes
+                .entry(parent)
+                .or_insert_with(|| RendererTransformNode {
+                    local_transform: Transform::default(),
+                    parent: None,
+                    children: BTreeSet::new(),
+                })
+                .children
+                .insert(index);
+        }
+
+        self.remove_node_if_unused(index);
+    }
+
+    pub fn remove(&mut self, index: ObjectPoolIndex) {
+        if let Some(node) = self.nodes.remove(&index) {
+            if let Some(parent) = node.parent {
+                if let Some(parent_node) = self.nodes.get_mut(&parent) {
+                    parent_node.children.remove(&index);
+                }
+                self.remove_node_if_unused(parent);
+            }
+
+            for child in node.children {
+                if let Some(child_node) = self.nodes.get_mut(&child) {
+                    child_node.parent = None;
+                }
+                self.remove_node_if_unused(child);
+            }
+        }
+    }
+
+    fn remove_node_if_unused(&mut self, index: ObjectPoolIndex) {
+        if self
+            .nodes
+            .get(&index)
+            .map(|node| node.parent.is_none() && node.children.is_empty())
+            .unwrap_or(false)
+        {
+            self.nodes.remove(&index);
+        }
+    }
+}
+
+pub(crate) fn compose_transforms(
+    parent: &Transform<f32, f32, f32>,
+    local: &Transform<f32, f32, f32>,
+) -> Transform<f32, f32, f32> {
+    Transform {
+        position: parent.position + parent.orientation * (parent.scale * local.position),
+        orientation: parent.orientation * local.orientation,
+        scale: parent.scale * local.scale,
+    }
+}
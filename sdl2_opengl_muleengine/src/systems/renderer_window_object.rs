@@ -0,0 +1,11 @@
+use muleengine::window_context::SecondaryWindowId;
+use vek::Vec2;
+
+use super::renderer_pipeline_step_object::RendererPipelineStepObject;
+
+pub(crate) struct RendererWindowObject {
+    pub secondary_window_id: SecondaryWindowId,
+    // the projection matrices of the pipeline are computed with these dimensions
+    pub window_dimensions: Vec2<usize>,
+    pub pipeline_steps: Vec<RendererPipelineStepObject>,
+}
@@ -5,13 +5,12 @@ use muleengine::{
     application_runner::{ApplicationContext, ClosureTaskSender},
     asset_container::AssetContainer,
     bytifex_utils::sync::{app_loop_state::AppLoopStateWatcher, types::ArcRwLock},
-    font::HackFontContainer,
-    renderer::renderer_system::RendererClient,
+    font::CachedFont,
+    renderer::{renderer_detached_client::RendererDetachedClient, renderer_system::RendererClient},
     service_container::ServiceContainer,
     system_container::SystemContainerClient,
     window_context::EventReceiver,
 };
-use parking_lot::RwLock;
 
 use crate::{
     physics::Rapier3dPhysicsEngineService, systems::renderer_configuration::RendererConfiguration,
@@ -28,16 +27,26 @@ pub struct EssentialServices {
 
     pub renderer_configuration: Arc<RendererConfiguration>,
     pub renderer_client: RendererClient,
+    pub renderer_detached_client: RendererDetachedClient,
 
     pub physics_engine: Arc<Rapier3dPhysicsEngineService>,
 
     pub entity_container: EntityContainer,
 
-    pub hack_font: ArcRwLock<HackFontContainer>,
+    pub hack_font: ArcRwLock<CachedFont>,
 }
 
 impl EssentialServices {
     pub fn new(app_context: &ApplicationContext) -> Self {
+        let asset_container = app_context
+            .service_container_ref()
+            .get_service::<AssetContainer>()
+            .inspect_err(|e| log::error!("{e:?}"))
+            .unwrap()
+            .as_ref()
+            .clone();
+        let hack_font = asset_container.font_container().write().hack_font();
+
         Self {
             event_receiver: app_context
                 .service_container_ref()
@@ -74,13 +83,14 @@ impl EssentialServices {
                 .unwrap()
                 .as_ref()
                 .clone(),
-            asset_container: app_context
+            renderer_detached_client: app_context
                 .service_container_ref()
-                .get_service::<AssetContainer>()
+                .get_service::<RendererDetachedClient>()
                 .inspect_err(|e| log::error!("{e:?}"))
                 .unwrap()
                 .as_ref()
                 .clone(),
+            asset_container,
             entity_container: app_context
                 .service_container_ref()
                 .get_service::<EntityContainer>()
@@ -94,12 +104,7 @@ impl EssentialServices {
                 .inspect_err(|e| log::error!("{e:?}"))
                 .unwrap()
                 .clone(),
-            hack_font: app_context
-                .service_container_ref()
-                .get_service::<RwLock<HackFontContainer>>()
-                .inspect_err(|e| log::error!("{e:?}"))
-                .unwrap()
-                .clone(),
+            hack_font,
         }
     }
 }
@@ -1,18 +1,21 @@
-use entity_component::EntityContainer;
+use entity_component::{ComponentRegistry, EntityContainer};
 use muleengine::{
     application_runner::{Application, ApplicationContext},
     asset_container::AssetContainer,
     asset_reader::AssetReader,
     bytifex_utils::sync::app_loop_state::AppLoopState,
-    font::HackFontContainer,
+    font_container::FontContainer,
     image_container::ImageContainer,
-    renderer::renderer_system::SyncRenderer,
+    prefab_container::PrefabContainer,
+    renderer::{renderer_config::RendererConfig, renderer_system::SyncRenderer},
     scene_container::SceneContainer,
     service_container::ServiceContainer,
+    virtual_clock::VirtualClock,
     window_context::{Event, EventReceiver, WindowContext},
 };
 use parking_lot::RwLock;
 use sdl2_opengl_muleengine::{
+    gl_upload_queue::GLUploadQueue,
     sdl2_gl_context::{GlProfile, Sdl2GlContext},
     systems::renderer::Renderer,
 };
@@ -24,10 +27,13 @@ use crate::{
     physics,
     systems::{
         character_controller_to_transform_coupler_system::CharacterControllerToTransformCouplerSystem,
-        controller_changer, flying_spectator_camera,
+        controller_changer, flying_spectator_camera, handler_leak_detector,
+        particle_emitter_updater,
         physics_object_to_transform_coupler_system::PhysicsObjectToTransformCouplerSystem,
-        renderer_configuration::RendererConfiguration, renderer_transform_updater, terminal,
-        top_down_player_controller, ui_text_positioner,
+        renderer_configuration::RendererConfiguration,
+        renderer_transform_updater::RendererTransformUpdaterSystem, rigid_body_entity_associator,
+        shader_reloader, terminal, top_down_player_controller, transform_hierarchy_updater,
+        ui_text_positioner,
     },
 };
 
@@ -41,16 +47,25 @@ impl Game2 {
         service_container.get_or_insert_service(AssetReader::new);
         service_container.get_or_insert_service(|| RwLock::new(ImageContainer::new()));
         service_container.get_or_insert_service(|| RwLock::new(SceneContainer::new()));
+        service_container.get_or_insert_service(|| RwLock::new(FontContainer::new()));
+        service_container.get_or_insert_service(|| RwLock::new(PrefabContainer::new()));
+        service_container.get_or_insert_service(|| RwLock::new(ComponentRegistry::new()));
         service_container.get_or_insert_service(|| AssetContainer::new(service_container));
         service_container.get_or_insert_service(EntityContainer::new);
-        service_container.get_or_insert_service(|| RwLock::new(HackFontContainer::new()));
+        service_container.get_or_insert_service(VirtualClock::new);
     }
 
     pub fn new(app_context: &mut ApplicationContext) -> Self {
         let app_loop_state = AppLoopState::new();
         Self::add_basic_services(app_context.service_container_ref());
 
-        let window_context = {
+        let renderer_config = RendererConfig {
+            msaa_samples: 4,
+            offscreen_msaa_samples: 4,
+            debug_output: cfg!(debug_assertions),
+        };
+
+        let (window_context, upload_queue) = {
             let initial_window_dimensions = Vec2::new(800, 600);
 
             let window_context = Sdl2GlContext::new(
@@ -60,11 +75,19 @@ impl Game2 {
                 GlProfile::Core,
                 4,
                 0,
+                &renderer_config,
             )
             .inspect_err(|e| log::error!("Could not create Sdl2GlContext, msg = {e:?}"))
             .unwrap();
 
-            window_context
+            // without an upload queue the meshes and the textures are uploaded by the render thread
+            let upload_queue = window_context
+                .create_shared_gl_context()
+                .and_then(GLUploadQueue::new)
+                .inspect_err(|e| log::warn!("Could not create GLUploadQueue, msg = {e}"))
+                .ok();
+
+            (window_context, upload_queue)
         };
 
         let event_receiver = window_context.event_receiver();
@@ -76,7 +99,7 @@ impl Game2 {
             .as_arc_ref()
             .clone();
 
-        let renderer_impl = Renderer::new(
+        let mut renderer_impl = Renderer::new(
             window_context.clone(),
             app_context
                 .service_container_ref()
@@ -86,12 +109,20 @@ impl Game2 {
                 .as_ref()
                 .clone(),
         );
+        if let Some(upload_queue) = upload_queue {
+            renderer_impl = renderer_impl.with_upload_queue(upload_queue);
+        }
 
         // todo!("choose between SyncRenderer and AsyncRenderer automatically");
-        let renderer_system = SyncRenderer::new(renderer_impl, window_context.clone());
+        let renderer_system =
+            SyncRenderer::new(renderer_impl, window_context.clone(), renderer_config);
 
         let renderer_client = renderer_system.client();
         app_context.service_container_ref().insert(renderer_client);
+        let renderer_detached_client = renderer_system.detached_client();
+        app_context
+            .service_container_ref()
+            .insert(renderer_detached_client);
 
         app_context
             .service_container_ref()
@@ -122,14 +153,33 @@ impl Game2 {
         app_context.system_container_mut().add_system(
             CharacterControllerToTransformCouplerSystem::new(&essentials),
         );
+        app_context
+            .system_container_mut()
+            .add_system(RendererTransformUpdaterSystem::new(&essentials));
 
         ui_text_positioner::run(essentials.entity_container.clone(), window_context.clone());
-        renderer_transform_updater::run(&essentials);
+        transform_hierarchy_updater::run(&essentials);
+        rigid_body_entity_associator::run(&essentials);
+        particle_emitter_updater::run(&essentials);
+        handler_leak_detector::run(&essentials);
         terminal::run(&essentials, window_context.clone());
 
         flying_spectator_camera::init(window_context.clone(), app_context, essentials.clone());
         top_down_player_controller::init(window_context.clone(), app_context, essentials.clone());
         controller_changer::init(window_context.read().event_receiver().clone(), &essentials);
+        shader_reloader::init(window_context.read().event_receiver().clone(), &essentials);
+
+        {
+            let essentials = essentials.clone();
+            tokio::spawn(async move {
+                let sky_layer = essentials.renderer_configuration.sky_layer().await;
+                essentials
+                    .system_container_client
+                    .execute_closure_async(|system_container| {
+                        system_container.add_system(sky_layer);
+                    });
+            });
+        }
 
         // adding Renderer as the last system
         app_context
@@ -1,13 +1,12 @@
 use std::sync::Arc;
 
 use muleengine::{
-    font::{HackFontContainer, RenderedGlyph},
     heightmap::HeightMap,
     mesh::{Material, MaterialTexture, MaterialTextureType, TextureMapMode},
     mesh_creator,
     renderer::RendererGroupHandler,
 };
-use vek::{Transform, Vec3};
+use vek::{Transform, Vec2, Vec3, Vec4};
 
 use crate::{
     components::CurrentlyControlledCharacter,
@@ -15,7 +14,7 @@ use crate::{
     physics::{
         character_controller::CharacterLength, collider::ColliderShape, rigid_body::RigidBodyType,
     },
-    systems::ui_text_positioner::UiEntityPosition,
+    systems::ui_text_positioner::{UiEntityPosition, UI_TEXT_SCALE},
 };
 
 use self::{skybox::spawn_skybox, tools::game_object_builder::GameObjectBuilder};
@@ -41,87 +40,69 @@ pub async fn populate_with_objects(essentials: &Arc<EssentialServices>) {
     spawn_scene_from_file(essentials, scene_path, Vec3::new(0.0, 0.0, -5.0)).await;
 }
 
-fn create_material_for_char(
-    chr: char,
-    font: &mut HackFontContainer,
-    pixel_scale: usize,
-) -> Option<(Material, RenderedGlyph)> {
-    let glyph = font.get_rendered_glyph(chr, pixel_scale)?;
-    Some((
-        Material {
-            textures: vec![MaterialTexture {
-                image: glyph.image().clone(),
-                texture_type: MaterialTextureType::Albedo,
-                texture_map_mode: TextureMapMode::Clamp,
-                blend: 1.0,
-                uv_channel_id: 0,
-            }],
-            opacity: 1.0,
-            albedo_color: Vec3::broadcast(1.0),
-            shininess_color: Vec3::broadcast(0.0),
-            emissive_color: Vec3::broadcast(0.0),
-        },
-        glyph,
-    ))
-}
-
 async fn spawn_text(
     text: &str,
-    pixel_scale: usize,
-    text_scale: f32,
+    text_size: f32,
     renderer_group_handler: RendererGroupHandler,
     essentials: &Arc<EssentialServices>,
 ) {
-    let text_scale = text_scale.abs();
-    let pixel_scale_f32 = pixel_scale as f32;
-    let mut position_offset = Vec3::new(text_scale / 2.0, -1.5 * text_scale, 0.0);
-
-    for chr in text.chars() {
-        let material_and_glyph =
-            create_material_for_char(chr, &mut essentials.hack_font.write(), pixel_scale);
+    let transform = Transform {
+        scale: UI_TEXT_SCALE,
+        ..Default::default()
+    };
+    let transform_handler = essentials
+        .renderer_client
+        .create_transform(transform)
+        .await
+        .unwrap()
+        .unwrap();
 
-        if let Some((material, glyph)) = material_and_glyph {
-            let entity_builder = GameObjectBuilder::new(essentials)
-                .mesh(Arc::new(mesh_creator::rectangle2d::create(1.0, 1.0)))
-                .await
-                .shader("assets/shaders/unlit")
-                .await
-                .transform(Transform {
-                    scale: Vec3::new(text_scale, -text_scale, 1.0),
-                    ..Default::default()
-                })
-                .await
-                .material(material)
-                .await
-                .renderer_group_handler(renderer_group_handler.clone())
-                .build()
-                .await;
-
-            let entity_builder = entity_builder.with_component(UiEntityPosition::TopLeftWindow {
-                offset: (position_offset
-                    + glyph.compute_render_offset_px() / pixel_scale_f32
-                        * text_scale
-                        * Vec3::new(1.0, -1.0, 1.0))
-                .xy(),
-            });
-
-            entity_builder.build();
-
-            position_offset.x += glyph.h_advance() / pixel_scale_f32 * text_scale;
-        } else {
-            position_offset.x += text_scale / 2.0;
+    let renderer_object_handler = match essentials
+        .renderer_client
+        .create_text_renderer_object(
+            text.to_string(),
+            essentials.hack_font.clone(),
+            text_size,
+            Vec4::one(),
+            transform_handler.clone(),
+        )
+        .await
+        .unwrap()
+    {
+        Ok(renderer_object_handler) => renderer_object_handler,
+        Err(e) => {
+            log::error!("Could not create the text, error = {e:?}");
+            return;
         }
-    }
+    };
+
+    essentials
+        .renderer_client
+        .add_renderer_object_to_group(renderer_object_handler.clone(), renderer_group_handler)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the text is laid out from its top left corner
+    essentials
+        .entity_container
+        .entity_builder()
+        .with_component(renderer_object_handler)
+        .with_component(transform_handler)
+        .with_component(transform)
+        .with_component(UiEntityPosition::TopLeftWindow {
+            offset: Vec2::zero(),
+        })
+        .build();
 }
 
 async fn spawn_ui(essentials: &Arc<EssentialServices>) {
     spawn_text(
         "Game VII",
-        128,
-        0.1,
+        32.0,
         essentials
             .renderer_configuration
-            .ortho_overlay_renderer_group_handler()
+            .pixel_overlay_renderer_group_handler()
             .await,
         essentials,
     )
@@ -198,13 +179,18 @@ async fn add_heightmap(
             .get_image(albedo_path, essentials.asset_container.asset_reader())
             .unwrap();
 
-        material.add_texture(MaterialTexture::new(
-            albedo_image,
-            muleengine::mesh::MaterialTextureType::Albedo,
-            muleengine::mesh::TextureMapMode::Mirror,
-            1.0,
-            0,
-        ));
+        // the terrain is seen from grazing angles, which needs more anisotropic samples
+        material.add_texture(
+            MaterialTexture::new(
+                albedo_image,
+                muleengine::mesh::MaterialTextureType::Albedo,
+                muleengine::mesh::TextureMapMode::Mirror,
+                1.0,
+                0,
+            )
+            .with_filter_mode(muleengine::mesh::TextureFilterMode::Trilinear)
+            .with_anisotropy_mode(muleengine::mesh::TextureAnisotropyMode::Anisotropy16),
+        );
     }
 
     let heightmap_image = essentials
@@ -270,6 +256,7 @@ async fn spawn_physics_entities(essentials: &Arc<EssentialServices>) {
 
     const POSITION_OFFSET: Vec3<f32> = Vec3::new(-CENTER_OF_MASS.x, 0.0, -30.0);
 
+    let mut entity_builders = Vec::new();
     let mut is_cube = true;
     for x in 0..OBJECT_COUNT.x {
         for y in 0..OBJECT_COUNT.y {
@@ -279,28 +266,32 @@ async fn spawn_physics_entities(essentials: &Arc<EssentialServices>) {
                     y as f32 * SPACE_BETWEEN_OBJECTS,
                     z as f32 * SPACE_BETWEEN_OBJECTS,
                 ) + POSITION_OFFSET;
-                if is_cube {
-                    rigid_bodies::create_box(
+                let entity_builder = if is_cube {
+                    rigid_bodies::box_entity_builder(
                         essentials,
                         position,
                         CUBE_DIMENSIONS,
                         RigidBodyType::Dynamic,
                     )
-                    .await;
+                    .await
                 } else {
-                    rigid_bodies::create_sphere(
+                    rigid_bodies::sphere_entity_builder(
                         essentials,
                         position,
                         SPHERE_RADIUS,
                         RigidBodyType::Dynamic,
                     )
-                    .await;
-                }
+                    .await
+                };
+                entity_builders.push(entity_builder);
 
                 is_cube = !is_cube;
             }
         }
     }
+
+    // the entity groups are notified once instead of once per object
+    essentials.entity_container.spawn_batch(entity_builders);
 }
 
 async fn spawn_sample_capsule(essentials: &Arc<EssentialServices>) {
@@ -363,7 +354,12 @@ async fn spawn_scene_from_file(
     for mesh in scene.meshes_ref().iter() {
         match &mesh {
             Ok(mesh) => {
-                let game_object_builder = game_object_builder.clone();
+                // the static geometry of the scene collides with its exact triangles
+                let game_object_builder = game_object_builder.clone().simple_rigid_body(
+                    position,
+                    ColliderShape::TriMesh(mesh.clone()),
+                    RigidBodyType::Static,
+                );
                 let entity_builder = game_object_builder.mesh(mesh.clone()).await.build().await;
                 entity_builder.build();
             }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use entity_component::EntityId;
+use entity_component::{EntityBuilder, EntityId};
 use muleengine::mesh_creator;
 use vek::{Transform, Vec3};
 
@@ -17,7 +17,30 @@ pub async fn create_box(
     dimensions: Vec3<f32>,
     rigid_body_type: RigidBodyType,
 ) -> EntityId {
-    let entity_builder = GameObjectBuilder::new(essentials)
+    box_entity_builder(essentials, position, dimensions, rigid_body_type)
+        .await
+        .build()
+}
+
+pub async fn create_sphere(
+    essentials: &Arc<EssentialServices>,
+    position: Vec3<f32>,
+    radius: f32,
+    rigid_body_type: RigidBodyType,
+) -> EntityId {
+    sphere_entity_builder(essentials, position, radius, rigid_body_type)
+        .await
+        .build()
+}
+
+// the boxes and the spheres have the same components, so they can be spawned in one batch
+pub async fn box_entity_builder(
+    essentials: &Arc<EssentialServices>,
+    position: Vec3<f32>,
+    dimensions: Vec3<f32>,
+    rigid_body_type: RigidBodyType,
+) -> EntityBuilder {
+    GameObjectBuilder::new(essentials)
         .renderer_group_handler(
             essentials
                 .renderer_configuration
@@ -45,18 +68,25 @@ pub async fn create_box(
             rigid_body_type,
         )
         .build()
-        .await;
-
-    entity_builder.build()
+        .await
 }
 
-pub async fn create_sphere(
+pub async fn sphere_entity_builder(
     essentials: &Arc<EssentialServices>,
     position: Vec3<f32>,
     radius: f32,
     rigid_body_type: RigidBodyType,
-) -> EntityId {
-    let entity_builder = GameObjectBuilder::new(essentials)
+) -> EntityBuilder {
+    // the spheres bounce, so they do not look like the boxes when they fall
+    let collider = essentials
+        .physics_engine
+        .read()
+        .collider_builder(ColliderShape::Sphere { radius })
+        .friction(0.3)
+        .restitution(0.6)
+        .build();
+
+    GameObjectBuilder::new(essentials)
         .renderer_group_handler(
             essentials
                 .renderer_configuration
@@ -74,9 +104,7 @@ pub async fn create_sphere(
         .await
         .mesh(Arc::new(mesh_creator::sphere::create(0.5, 16)))
         .await
-        .simple_rigid_body(position, ColliderShape::Sphere { radius }, rigid_body_type)
+        .rigid_body(position, collider, rigid_body_type)
         .build()
-        .await;
-
-    entity_builder.build()
+        .await
 }
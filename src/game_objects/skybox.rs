@@ -1,39 +1,11 @@
 use std::sync::Arc;
 
-use muleengine::mesh::{Material, MaterialTexture, MaterialTextureType, TextureMapMode};
-use vek::{Transform, Vec3};
-
 use crate::essential_services::EssentialServices;
 
-use super::tools::game_object_builder::GameObjectBuilder;
-
 pub async fn spawn_skybox(essentials: &Arc<EssentialServices>) {
-    let game_object_builder = GameObjectBuilder::new(essentials)
-        .renderer_group_handler(
-            essentials
-                .renderer_configuration
-                .skydome_renderer_group_handler()
-                .await
-                .clone(),
-        )
-        .shader("assets/shaders/unlit")
-        .await
-        .transform(Transform::<f32, f32, f32>::default())
-        .await;
-
-    let scene_path = "assets/objects/skybox/Skybox.obj";
-    let scene = essentials
-        .asset_container
-        .scene_container()
-        .write()
-        .get_scene(
-            scene_path,
-            essentials.asset_container.asset_reader(),
-            &mut essentials.asset_container.image_container().write(),
-        )
-        .inspect_err(|e| log::error!("{e:?}"))
-        .unwrap();
+    let sky_layer = essentials.renderer_configuration.sky_layer().await;
 
+    // in the +x, -x, +y, -y, +z, -z order of the cubemap faces
     let texture_paths = [
         "assets/objects/skybox/skyboxRight.png",
         "assets/objects/skybox/skyboxLeft.png",
@@ -43,43 +15,55 @@ pub async fn spawn_skybox(essentials: &Arc<EssentialServices>) {
         "assets/objects/skybox/skyboxBack.png",
     ];
 
-    if scene.meshes_ref().len() == 6 {
-        for (index, texture_path) in texture_paths.iter().enumerate() {
-            let game_object_builder = game_object_builder.clone();
+    let faces = texture_paths.map(|texture_path| {
+        essentials
+            .asset_container
+            .image_container()
+            .write()
+            .get_image(texture_path, essentials.asset_container.asset_reader())
+            .inspect_err(|e| log::error!("Could not load image, error = {e:?}"))
+            .unwrap()
+    });
 
-            let material = Material {
-                textures: vec![MaterialTexture {
-                    image: essentials
-                        .asset_container
-                        .image_container()
-                        .write()
-                        .get_image(texture_path, essentials.asset_container.asset_reader())
-                        .inspect_err(|e| log::error!("Could not load image, error = {e:?}"))
-                        .unwrap(),
-                    texture_type: MaterialTextureType::Albedo,
-                    texture_map_mode: TextureMapMode::Clamp,
-                    blend: 0.0,
-                    uv_channel_id: 0,
-                }],
-                opacity: 1.0,
-                albedo_color: Vec3::broadcast(1.0),
-                shininess_color: Vec3::broadcast(0.0),
-                emissive_color: Vec3::broadcast(0.0),
-            };
+    let cubemap_handler = match essentials
+        .renderer_client
+        .create_cubemap_texture(faces)
+        .await
+        .unwrap()
+    {
+        Ok(cubemap_handler) => cubemap_handler,
+        Err(e) => {
+            log::error!("Could not create the cubemap of the skybox, error = {e:?}");
+            return;
+        }
+    };
 
-            let mesh = scene.meshes_ref()[index].as_ref().unwrap().clone();
+    let renderer_object_handler = essentials
+        .renderer_client
+        .create_skybox_renderer_object(cubemap_handler)
+        .await
+        .unwrap()
+        .unwrap();
 
-            let entity_builder = game_object_builder
-                .mesh(mesh)
-                .await
-                .material(material)
+    essentials
+        .renderer_client
+        .add_renderer_object_to_group(
+            renderer_object_handler.clone(),
+            essentials
+                .renderer_configuration
+                .skydome_renderer_group_handler()
                 .await
-                .build()
-                .await;
+                .clone(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
 
-            entity_builder.build();
-        }
-    } else {
-        log::error!("Skybox does not contain exactly 6 meshes");
-    }
+    essentials
+        .entity_container
+        .entity_builder()
+        .with_component(renderer_object_handler.clone())
+        .build();
+
+    sky_layer.add_renderer_object(renderer_object_handler);
 }
@@ -4,9 +4,11 @@ use entity_component::EntityBuilder;
 use muleengine::{
     mesh::{Material, Mesh},
     renderer::{
-        RendererGroupHandler, RendererMaterialHandler, RendererMeshHandler, RendererShaderHandler,
+        renderer_batch::RendererBatchBuilder, RendererGroupHandler, RendererMaterialHandler,
+        RendererMeshHandler, RendererShaderHandler,
     },
 };
+use rapier3d::prelude::Collider;
 use vek::{Transform, Vec3};
 
 use crate::{
@@ -136,14 +138,29 @@ impl<'a> GameObjectBuilder<'a> {
     }
 
     pub fn simple_rigid_body(
-        mut self,
+        self,
         position: Vec3<f32>,
         collider_shape: ColliderShape,
         rigid_body_type: RigidBodyType,
     ) -> Self {
-        let physics_engine = self.essentials.physics_engine.write();
+        let collider = self
+            .essentials
+            .physics_engine
+            .read()
+            .collider_builder(collider_shape)
+            .build();
+
+        self.rigid_body(position, collider, rigid_body_type)
+    }
 
-        let collider = physics_engine.collider_builder(collider_shape).build();
+    // e.g. a collider with its own friction and restitution
+    pub fn rigid_body(
+        mut self,
+        position: Vec3<f32>,
+        collider: Collider,
+        rigid_body_type: RigidBodyType,
+    ) -> Self {
+        let physics_engine = self.essentials.physics_engine.write();
 
         let rigid_body_builder = physics_engine
             .rigid_body_builder(collider, rigid_body_type)
@@ -167,19 +184,20 @@ impl<'a> GameObjectBuilder<'a> {
             let shader_handler_ref = objects.1;
             let renderer_group_handler_ref = objects.0 .1;
 
+            // the renderer objects are created with a single round trip to the renderer system
+            let mut batch = RendererBatchBuilder::new();
+
             let transform = self.transform.unwrap_or_default();
-            let transform_handler = self
-                .essentials
-                .renderer_client
-                .create_transform(transform)
-                .await
-                .inspect_err(|e| log::error!("{e:?}"))
-                .unwrap()
-                .unwrap();
+            let transform_slot = batch.create_transform(transform);
 
-            let material_handler =
+            let renderer_object_slot =
                 if let Some(material_handler_ref) = self.material_handler.as_ref() {
-                    material_handler_ref.clone()
+                    batch.create_renderer_object_from_mesh(
+                        mesh_handler_ref.clone(),
+                        shader_handler_ref.clone(),
+                        material_handler_ref.clone(),
+                        transform_slot,
+                    )
                 } else {
                     let material = if let Some(material) = &self.mesh_default_material {
                         material.clone()
@@ -187,38 +205,29 @@ impl<'a> GameObjectBuilder<'a> {
                         Material::default()
                     };
 
-                    self.essentials
-                        .renderer_client
-                        .create_material(material)
-                        .await
-                        .inspect_err(|e| log::error!("{e:?}"))
-                        .unwrap()
-                        .unwrap()
+                    let material_slot = batch.create_material(material);
+                    batch.create_renderer_object_from_mesh(
+                        mesh_handler_ref.clone(),
+                        shader_handler_ref.clone(),
+                        material_slot,
+                        transform_slot,
+                    )
                 };
 
-            let renderer_object_handler = self
-                .essentials
-                .renderer_client
-                .create_renderer_object_from_mesh(
-                    mesh_handler_ref.clone(),
-                    shader_handler_ref.clone(),
-                    material_handler,
-                    transform_handler.clone(),
-                )
-                .await
-                .unwrap()
-                .unwrap();
+            batch.add_renderer_object_to_group(
+                renderer_object_slot,
+                renderer_group_handler_ref.clone(),
+            );
 
-            self.essentials
-                .renderer_client
-                .add_renderer_object_to_group(
-                    renderer_object_handler.clone(),
-                    renderer_group_handler_ref.clone(),
-                )
+            let results = batch
+                .submit(&self.essentials.renderer_client)
                 .await
-                .unwrap()
+                .inspect_err(|e| log::error!("{e:?}"))
                 .unwrap();
 
+            let transform_handler = results.get(transform_slot).unwrap();
+            let renderer_object_handler = results.get(renderer_object_slot).unwrap();
+
             entity_builder
                 .with_component(renderer_object_handler.clone())
                 .with_component(transform_handler)
@@ -1,8 +1,11 @@
-use std::time::Instant;
+use std::{backtrace::Backtrace, sync::Arc, time::Instant};
 
-use muleengine::bytifex_utils::{
-    containers::object_pool::ObjectPoolIndex,
-    sync::{types::ArcRwLock, usage_counter::UsageCounter},
+use muleengine::{
+    bytifex_utils::{
+        containers::object_pool::ObjectPoolIndex,
+        sync::{types::ArcRwLock, usage_counter::UsageCounter},
+    },
+    handler_leak_probe::{HandlerLeakProbe, HandlerLeakProbeSource},
 };
 use rapier3d::{
     control::{
@@ -12,7 +15,7 @@ use rapier3d::{
 };
 use vek::Vec3;
 
-use super::{collider::ColliderShape, Rapier3dPhysicsEngine};
+use super::{collider::ColliderShape, query::PhysicsQueryFilter, Rapier3dPhysicsEngine};
 
 pub enum CharacterLength {
     Absolute(f32),
@@ -41,6 +44,8 @@ pub(super) struct CharacterController {
     pub(super) grounded: bool,
     pub(super) falling_velocity: Vec3<f32>,
     pub(super) gravity: Vec3<f32>,
+    // e.g. the character does not collide with debris
+    pub(super) query_filter: PhysicsQueryFilter,
 }
 
 impl CharacterController {
@@ -69,6 +74,10 @@ impl CharacterController {
         self.shape = collider_shape.as_rapier_collider_shape();
     }
 
+    pub fn set_query_filter(&mut self, query_filter: PhysicsQueryFilter) {
+        self.query_filter = query_filter;
+    }
+
     pub fn set_margin(&mut self, margin: CharacterLength) {
         self.character_controller.offset = margin.as_rapier_character_length();
     }
@@ -126,6 +135,7 @@ impl CharacterControllerBuilder {
             grounded: false,
             gravity,
             falling_velocity: Vec3::zero(),
+            query_filter: PhysicsQueryFilter::default(),
         };
 
         character_controller.set_margin(CharacterLength::Absolute(0.01));
@@ -160,6 +170,11 @@ impl CharacterControllerBuilder {
         self
     }
 
+    pub fn query_filter(mut self, query_filter: PhysicsQueryFilter) -> Self {
+        self.character_controller.set_query_filter(query_filter);
+        self
+    }
+
     pub fn margin(mut self, margin: CharacterLength) -> Self {
         self.character_controller.set_margin(margin);
         self
@@ -217,6 +232,17 @@ pub struct CharacterControllerHandler {
     pub(super) object_pool_index: ObjectPoolIndex,
     pub(super) character_controller: ArcRwLock<CharacterController>,
     pub(super) to_be_dropped_character_controllers: ArcRwLock<Vec<ObjectPoolIndex>>,
+    pub(super) creation_backtrace: Arc<Option<Arc<Backtrace>>>,
+}
+
+impl HandlerLeakProbeSource for CharacterControllerHandler {
+    fn leak_probe(&self) -> HandlerLeakProbe {
+        HandlerLeakProbe::new(
+            "CharacterControllerHandler",
+            &self.creation_backtrace,
+            self.creation_backtrace.as_ref().clone(),
+        )
+    }
 }
 
 impl Drop for CharacterControllerHandler {
@@ -244,6 +270,12 @@ impl CharacterControllerHandler {
         self.character_controller.write().set_gravity(gravity);
     }
 
+    pub fn set_query_filter(&mut self, query_filter: PhysicsQueryFilter) {
+        self.character_controller
+            .write()
+            .set_query_filter(query_filter);
+    }
+
     pub fn set_velocity(&mut self, velocity: Vec3<f32>) {
         self.character_controller.write().set_velocity(velocity);
     }
@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
-use muleengine::{aabb::AxisAlignedBoundingBox, heightmap::HeightMap};
+use muleengine::{aabb::AxisAlignedBoundingBox, heightmap::HeightMap, mesh::Mesh};
 use rapier3d::prelude::{
-    nalgebra::{self, *},
-    Collider, ColliderBuilder as RapierColliderBuilder, ColliderShape as RapierColliderShape,
-    Vector,
+    nalgebra::*, ActiveEvents, Collider, ColliderBuilder as RapierColliderBuilder,
+    ColliderShape as RapierColliderShape, Group, InteractionGroups, Vector,
 };
-use vek::Vec3;
+use vek::{Quaternion, Vec3};
+
+use super::query;
 
 pub enum ColliderShape {
     Capsule {
@@ -33,6 +34,11 @@ pub enum ColliderShape {
         heightmap: Arc<HeightMap>,
         scale: Vec3<f32>,
     },
+    // the smallest convex shape around the vertices, it is cheaper than the trimesh and it can be
+    // dynamic
+    ConvexHullOfMesh(Arc<Mesh>),
+    // the exact triangles of the mesh, e.g. the static geometry of the loaded scenes
+    TriMesh(Arc<Mesh>),
 }
 
 impl ColliderShape {
@@ -83,6 +89,9 @@ impl ColliderShape {
                 aabb.add_vertex(Vec3::new(scale.x, scale.y, scale.z));
                 aabb
             }
+            ColliderShape::ConvexHullOfMesh(mesh) | ColliderShape::TriMesh(mesh) => {
+                *mesh.get_aabb()
+            }
         }
     }
 
@@ -110,35 +119,165 @@ impl ColliderShape {
                 );
                 RapierColliderShape::heightfield(heights, scale)
             }
+            ColliderShape::ConvexHullOfMesh(mesh) => {
+                RapierColliderShape::convex_hull(&points_of_mesh(mesh)).unwrap_or_else(|| {
+                    log::warn!("Could not compute the convex hull of a mesh, its aabb is used");
+                    aabb_collider_shape(mesh.get_aabb())
+                })
+            }
+            ColliderShape::TriMesh(mesh) => {
+                let indices = mesh
+                    .get_faces()
+                    .chunks_exact(3)
+                    .map(|face| [face[0], face[1], face[2]])
+                    .collect::<Vec<[u32; 3]>>();
+                if indices.is_empty() {
+                    log::warn!("Mesh without faces cannot be a trimesh, its aabb is used");
+                    aabb_collider_shape(mesh.get_aabb())
+                } else {
+                    RapierColliderShape::trimesh(points_of_mesh(mesh), indices)
+                }
+            }
+        }
+    }
+}
+
+fn points_of_mesh(mesh: &Mesh) -> Vec<Point3<f32>> {
+    mesh.get_positions()
+        .iter()
+        .map(|position| Point3::new(position.x, position.y, position.z))
+        .collect()
+}
+
+fn aabb_collider_shape(aabb: &AxisAlignedBoundingBox) -> RapierColliderShape {
+    let min = *aabb.get_min_vertex();
+    let max = *aabb.get_max_vertex();
+    let half_extents = (max - min) / 2.0;
+    let center = (min + max) / 2.0;
+    RapierColliderShape::compound(vec![(
+        Isometry::translation(center.x, center.y, center.z),
+        RapierColliderShape::cuboid(
+            half_extents.x.max(f32::EPSILON),
+            half_extents.y.max(f32::EPSILON),
+            half_extents.z.max(f32::EPSILON),
+        ),
+    )])
+}
+
+// two colliders interact if the memberships of each of them intersect the filter of the other one,
+// e.g. the projectiles of the player filter out the group of the player
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CollisionGroups {
+    pub memberships: u32,
+    pub filter: u32,
+}
+
+impl CollisionGroups {
+    pub const ALL: Self = Self {
+        memberships: u32::MAX,
+        filter: u32::MAX,
+    };
+
+    pub fn new(memberships: u32, filter: u32) -> Self {
+        Self {
+            memberships,
+            filter,
         }
     }
+
+    pub(super) fn as_rapier_interaction_groups(&self) -> InteractionGroups {
+        InteractionGroups::new(
+            Group::from_bits_truncate(self.memberships),
+            Group::from_bits_truncate(self.filter),
+        )
+    }
+}
+
+impl Default for CollisionGroups {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+// the mass of the rigid body is the sum of the masses of its colliders
+#[derive(Clone, Copy)]
+enum ColliderMass {
+    Density(f32),
+    Mass(f32),
 }
 
 pub struct ColliderBuilder {
     position: Vec3<f32>,
+    orientation: Quaternion<f32>,
     shape: ColliderShape,
     is_sensor: bool,
+    collision_groups: CollisionGroups,
+    friction: f32,
+    restitution: f32,
+    mass: ColliderMass,
 }
 
 impl ColliderBuilder {
     pub(super) fn new(shape: ColliderShape) -> Self {
         Self {
             position: Vec3::broadcast(0.0),
+            orientation: Quaternion::identity(),
             shape,
             is_sensor: false,
+            collision_groups: CollisionGroups::ALL,
+            friction: 0.5,
+            restitution: 0.0,
+            mass: ColliderMass::Density(1.0),
         }
     }
 
+    // the position and the orientation are relative to the rigid body of the collider
     pub fn position(mut self, position: Vec3<f32>) -> Self {
         self.position = position;
         self
     }
 
-    pub fn is_sensor(mut self, is_sensor: bool) -> Self {
+    pub fn orientation(mut self, orientation: Quaternion<f32>) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    // a sensor does not push the other colliders away, its overlaps are reported as intersection
+    // events, e.g. pickups and zones
+    pub fn sensor(mut self, is_sensor: bool) -> Self {
         self.is_sensor = is_sensor;
         self
     }
 
+    pub fn collision_groups(mut self, collision_groups: CollisionGroups) -> Self {
+        self.collision_groups = collision_groups;
+        self
+    }
+
+    // 0.0 is slippery like ice, 1.0 is rough like rubber on concrete
+    pub fn friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    // 0.0 does not bounce at all, 1.0 bounces back without losing energy
+    pub fn restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    // the mass is computed from the volume of the shape, it overrides the mass set before
+    pub fn density(mut self, density: f32) -> Self {
+        self.mass = ColliderMass::Density(density);
+        self
+    }
+
+    // the mass does not depend on the volume of the shape, it overrides the density set before
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = ColliderMass::Mass(mass);
+        self
+    }
+
     pub fn build(self) -> Collider {
         let mut position_offset = Vec3::zero();
 
@@ -151,13 +290,22 @@ impl ColliderBuilder {
             position_offset.y = -scale.y / 2.0;
         };
 
-        RapierColliderBuilder::new(rapier_shape)
-            .translation(vector![
-                self.position.x + position_offset.x,
-                self.position.y + position_offset.y,
-                self.position.z + position_offset.z
-            ])
+        let collider_builder = RapierColliderBuilder::new(rapier_shape);
+        let collider_builder = match self.mass {
+            ColliderMass::Density(density) => collider_builder.density(density),
+            ColliderMass::Mass(mass) => collider_builder.mass(mass),
+        };
+
+        collider_builder
+            .position(query::to_isometry(
+                self.position + position_offset,
+                self.orientation,
+            ))
             .sensor(self.is_sensor)
+            .friction(self.friction)
+            .restitution(self.restitution)
+            .collision_groups(self.collision_groups.as_rapier_interaction_groups())
+            .active_events(ActiveEvents::COLLISION_EVENTS)
             .build()
     }
 }
@@ -0,0 +1,66 @@
+use entity_component::EntityId;
+use parking_lot::Mutex;
+use rapier3d::prelude::{
+    ColliderSet, CollisionEvent as RapierCollisionEvent, ContactPair, EventHandler, Real,
+    RigidBodySet,
+};
+
+use super::RigidBodyHandler;
+
+// the rigid bodies and their entities are none if the collider is not attached to a rigid body,
+// or it was removed in the step, e.g. a trigger volume that was despawned, the intersection events
+// are sent instead of the collision events if one of the colliders is a sensor
+#[derive(Clone)]
+pub enum CollisionEvent {
+    CollisionStarted {
+        rigid_body_handlers: [Option<RigidBodyHandler>; 2],
+        entity_ids: [Option<EntityId>; 2],
+    },
+    CollisionStopped {
+        rigid_body_handlers: [Option<RigidBodyHandler>; 2],
+        entity_ids: [Option<EntityId>; 2],
+    },
+    IntersectionStarted {
+        rigid_body_handlers: [Option<RigidBodyHandler>; 2],
+        entity_ids: [Option<EntityId>; 2],
+    },
+    IntersectionStopped {
+        rigid_body_handlers: [Option<RigidBodyHandler>; 2],
+        entity_ids: [Option<EntityId>; 2],
+    },
+}
+
+// the events are collected during the step and published after it, so the entities of the rigid
+// bodies can be looked up
+#[derive(Default)]
+pub(super) struct CollisionEventCollector {
+    events: Mutex<Vec<RapierCollisionEvent>>,
+}
+
+impl CollisionEventCollector {
+    pub(super) fn take_events(&self) -> Vec<RapierCollisionEvent> {
+        std::mem::take(&mut *self.events.lock())
+    }
+}
+
+impl EventHandler for CollisionEventCollector {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: RapierCollisionEvent,
+        _contact_pair: Option<&ContactPair>,
+    ) {
+        self.events.lock().push(event);
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: Real,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &ContactPair,
+        _total_force_magnitude: Real,
+    ) {
+    }
+}
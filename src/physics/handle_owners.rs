@@ -0,0 +1,60 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+// the rapier arenas reuse the indices of the removed objects with a new generation, and restoring a
+// snapshot brings back the old generations too, so a handle alone does not tell whether it still
+// refers to the object it was created for, the handlers carry the id of their owner along with the
+// handle
+pub(super) type OwnerId = u64;
+
+#[derive(Clone)]
+pub(super) struct HandleOwners<HandleType> {
+    owners: HashMap<HandleType, OwnerId>,
+}
+
+impl<HandleType: Copy + Eq + Hash> HandleOwners<HandleType> {
+    pub(super) fn new() -> Self {
+        Self {
+            owners: HashMap::new(),
+        }
+    }
+
+    pub(super) fn insert(&mut self, handle: HandleType, owner_id: OwnerId) {
+        self.owners.insert(handle, owner_id);
+    }
+
+    pub(super) fn owner_of(&self, handle: &HandleType) -> Option<OwnerId> {
+        self.owners.get(handle).copied()
+    }
+
+    pub(super) fn is_owned_by(&self, handle: &HandleType, owner_id: OwnerId) -> bool {
+        self.owner_of(handle) == Some(owner_id)
+    }
+
+    // returns false if the handle belongs to an other owner, e.g. its index was reused
+    pub(super) fn remove_if_owned_by(&mut self, handle: &HandleType, owner_id: OwnerId) -> bool {
+        if self.is_owned_by(handle, owner_id) {
+            self.owners.remove(handle);
+            true
+        } else {
+            false
+        }
+    }
+
+    // returns the handles of the other owners, e.g. the objects of the handlers that were dropped
+    // after a snapshot was taken
+    pub(super) fn retain_owners(&mut self, owner_ids: &HashSet<OwnerId>) -> Vec<HandleType> {
+        let removed_handles = self
+            .owners
+            .iter()
+            .filter(|(_, owner_id)| !owner_ids.contains(owner_id))
+            .map(|(handle, _)| *handle)
+            .collect::<Vec<HandleType>>();
+        for handle in removed_handles.iter() {
+            self.owners.remove(handle);
+        }
+        removed_handles
+    }
+}
@@ -0,0 +1,222 @@
+use std::{backtrace::Backtrace, sync::Arc};
+
+use muleengine::{
+    bytifex_utils::sync::{types::ArcRwLock, usage_counter::UsageCounter},
+    handler_leak_probe::{HandlerLeakProbe, HandlerLeakProbeSource},
+};
+use rapier3d::prelude::{
+    nalgebra::*, FixedJointBuilder, GenericJoint, ImpulseJointHandle, JointAxis,
+    PrismaticJointBuilder, RevoluteJointBuilder, SphericalJointBuilder,
+};
+use vek::Vec3;
+
+use super::{handle_owners::OwnerId, query, PhysicsError, Rapier3dPhysicsEngine, RigidBodyHandler};
+
+#[derive(Clone, Copy)]
+pub enum JointType {
+    // e.g. the parts of a breakable object
+    Fixed,
+    // rotation around the axis, e.g. doors and wheels
+    Revolute { axis: Vec3<f32> },
+    // translation along the axis, e.g. sliding doors and suspensions
+    Prismatic { axis: Vec3<f32> },
+    // free rotation around the anchors, e.g. the shoulders of a ragdoll
+    Spherical,
+}
+
+impl JointType {
+    // the limits and the motors act on this axis, the fixed and the spherical joints do not have one
+    pub(super) fn free_axis(&self) -> Option<JointAxis> {
+        match self {
+            JointType::Fixed | JointType::Spherical => None,
+            JointType::Revolute { .. } => Some(JointAxis::AngX),
+            JointType::Prismatic { .. } => Some(JointAxis::LinX),
+        }
+    }
+
+    fn as_generic_joint(&self) -> GenericJoint {
+        match self {
+            JointType::Fixed => FixedJointBuilder::new().build().into(),
+            JointType::Revolute { axis } => RevoluteJointBuilder::new(UnitVector3::new_normalize(
+                query::to_nalgebra_vector3(*axis),
+            ))
+            .build()
+            .into(),
+            JointType::Prismatic { axis } => PrismaticJointBuilder::new(
+                UnitVector3::new_normalize(query::to_nalgebra_vector3(*axis)),
+            )
+            .build()
+            .into(),
+            JointType::Spherical => SphericalJointBuilder::new().build().into(),
+        }
+    }
+}
+
+// the targets are angles in radians for the revolute joints and distances for the prismatic ones
+#[derive(Clone, Copy)]
+pub enum JointMotor {
+    Velocity {
+        target_velocity: f32,
+        factor: f32,
+    },
+    Position {
+        target_position: f32,
+        stiffness: f32,
+        damping: f32,
+    },
+}
+
+impl JointMotor {
+    pub(super) fn apply_to(&self, joint: &mut GenericJoint, axis: JointAxis) {
+        match *self {
+            JointMotor::Velocity {
+                target_velocity,
+                factor,
+            } => {
+                joint.set_motor_velocity(axis, target_velocity, factor);
+            }
+            JointMotor::Position {
+                target_position,
+                stiffness,
+                damping,
+            } => {
+                joint.set_motor_position(axis, target_position, stiffness, damping);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct Joint {
+    pub(super) joint_type: JointType,
+    pub(super) rigid_body_handlers: [RigidBodyHandler; 2],
+    local_anchors: [Vec3<f32>; 2],
+    limits: Option<[f32; 2]>,
+    motor: Option<JointMotor>,
+    contacts_enabled: bool,
+}
+
+impl Joint {
+    pub(super) fn as_generic_joint(&self) -> GenericJoint {
+        let mut joint = self.joint_type.as_generic_joint();
+        joint
+            .set_local_anchor1(Point3::from(query::to_nalgebra_vector3(
+                self.local_anchors[0],
+            )))
+            .set_local_anchor2(Point3::from(query::to_nalgebra_vector3(
+                self.local_anchors[1],
+            )))
+            .set_contacts_enabled(self.contacts_enabled);
+
+        if let Some(axis) = self.joint_type.free_axis() {
+            if let Some(limits) = self.limits {
+                joint.set_limits(axis, limits);
+            }
+            if let Some(motor) = &self.motor {
+                motor.apply_to(&mut joint, axis);
+            }
+        }
+
+        joint
+    }
+}
+
+#[derive(Clone)]
+pub struct JointBuilder {
+    joint: Joint,
+}
+
+impl JointBuilder {
+    pub(super) fn new(
+        joint_type: JointType,
+        rigid_body_handler1: RigidBodyHandler,
+        rigid_body_handler2: RigidBodyHandler,
+    ) -> Self {
+        Self {
+            joint: Joint {
+                joint_type,
+                rigid_body_handlers: [rigid_body_handler1, rigid_body_handler2],
+                local_anchors: [Vec3::zero(), Vec3::zero()],
+                limits: None,
+                motor: None,
+                contacts_enabled: true,
+            },
+        }
+    }
+
+    // the anchors are in the local space of the rigid bodies
+    pub fn local_anchor1(mut self, anchor: Vec3<f32>) -> Self {
+        self.joint.local_anchors[0] = anchor;
+        self
+    }
+
+    pub fn local_anchor2(mut self, anchor: Vec3<f32>) -> Self {
+        self.joint.local_anchors[1] = anchor;
+        self
+    }
+
+    // ignored by the fixed and the spherical joints
+    pub fn limits(mut self, min: f32, max: f32) -> Self {
+        self.joint.limits = Some([min, max]);
+        self
+    }
+
+    // ignored by the fixed and the spherical joints
+    pub fn motor(mut self, motor: JointMotor) -> Self {
+        self.joint.motor = Some(motor);
+        self
+    }
+
+    // e.g. the neighbouring limbs of a ragdoll should not collide with each other
+    pub fn contacts_enabled(mut self, contacts_enabled: bool) -> Self {
+        self.joint.contacts_enabled = contacts_enabled;
+        self
+    }
+
+    pub fn build(
+        self,
+        physics_engine: &mut Rapier3dPhysicsEngine,
+    ) -> Result<JointHandler, PhysicsError> {
+        physics_engine.add_joint(self.joint)
+    }
+}
+
+// the joint is removed with the next step after the last handler is dropped, removing one of its
+// rigid bodies removes the joint too
+#[derive(Clone)]
+pub struct JointHandler {
+    pub(super) inner_handle: ImpulseJointHandle,
+    pub(super) owner_id: OwnerId,
+    pub(super) free_axis: Option<JointAxis>,
+    pub(super) usage_counter: UsageCounter,
+    pub(super) to_be_removed_joints: ArcRwLock<Vec<(ImpulseJointHandle, OwnerId)>>,
+    pub(super) creation_backtrace: Arc<Option<Arc<Backtrace>>>,
+}
+
+impl std::fmt::Debug for JointHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JointHandler")
+            .field("inner_handle", &self.inner_handle)
+            .finish()
+    }
+}
+
+impl HandlerLeakProbeSource for JointHandler {
+    fn leak_probe(&self) -> HandlerLeakProbe {
+        HandlerLeakProbe::new(
+            "JointHandler",
+            &self.creation_backtrace,
+            self.creation_backtrace.as_ref().clone(),
+        )
+    }
+}
+
+impl Drop for JointHandler {
+    fn drop(&mut self) {
+        if self.usage_counter.is_this_the_last() {
+            self.to_be_removed_joints
+                .write()
+                .push((self.inner_handle, self.owner_id));
+        }
+    }
+}
@@ -1,33 +1,48 @@
 pub mod character_controller;
 pub mod collider;
+pub mod collision_event;
+mod handle_owners;
+pub mod joint;
+pub mod query;
 pub mod rigid_body;
 
 use std::{
-    collections::VecDeque,
+    backtrace::Backtrace,
+    collections::{HashMap, HashSet, VecDeque},
     mem::swap,
     ops::DerefMut,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
-use method_taskifier::method_taskifier_impl;
+use entity_component::EntityId;
+use method_taskifier::{
+    method_taskifier_impl,
+    task_channel::{task_channel, TaskReceiver},
+};
 use muleengine::{
     application_runner::ApplicationContext,
     bytifex_utils::{
         containers::object_pool::{ObjectPool, ObjectPoolIndex},
         sync::{
             app_loop_state::AppLoopStateWatcher,
+            broadcast,
             types::{arc_rw_lock_new, ArcRwLock},
             usage_counter::UsageCounter,
         },
     },
+    handler_leak_probe::{capture_creation_backtrace, HandlerLeakProbe, HandlerLeakProbeSource},
+    profile_scope,
+    virtual_clock::VirtualClock,
 };
 use parking_lot::RwLock;
 use rapier3d::{
-    pipeline::{QueryFilter, QueryPipeline},
+    pipeline::QueryPipeline,
     prelude::{
         nalgebra::{self, *},
-        BroadPhase, CCDSolver, Collider, ColliderSet, ImpulseJointSet, IntegrationParameters,
-        IslandManager, MultibodyJointSet, NarrowPhase, PhysicsPipeline,
+        BroadPhase, CCDSolver, Collider, ColliderSet, GenericJoint, ImpulseJointHandle,
+        ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet, NarrowPhase,
+        PhysicsPipeline, Ray, RigidBody as RapierRigidBody,
         RigidBodyBuilder as RapierRigidBodyBuilder, RigidBodyHandle as RapierRigidBodyHandle,
         RigidBodySet,
     },
@@ -40,23 +55,106 @@ use self::{
         CharacterController, CharacterControllerBuilder, CharacterControllerHandler,
     },
     collider::{ColliderBuilder, ColliderShape},
+    collision_event::{CollisionEvent, CollisionEventCollector},
+    handle_owners::{HandleOwners, OwnerId},
+    joint::{Joint, JointBuilder, JointHandler, JointMotor, JointType},
+    query::{PhysicsQueryFilter, PhysicsQueryHit},
     rigid_body::{RigidBody, RigidBodyBuilder, RigidBodyType},
 };
 
 pub type Rapier3dPhysicsEngineService = RwLock<Rapier3dPhysicsEngine>;
 
-const NUMBER_OF_STORED_STATES: usize = 1;
+const DEFAULT_NUMBER_OF_STORED_STATES: usize = 1;
 
+// the rigid body is removed with the next step after the last handler is dropped
 #[derive(Clone)]
 pub struct RigidBodyHandler {
     inner_handle: RapierRigidBodyHandle,
+    owner_id: OwnerId,
+    usage_counter: UsageCounter,
+    // none for the handlers of the query hits and the collision events, they do not own the rigid
+    // body, so dropping them does not remove it
+    to_be_removed_rigid_bodies: Option<ArcRwLock<Vec<(RapierRigidBodyHandle, OwnerId)>>>,
+    creation_backtrace: Arc<Option<Arc<Backtrace>>>,
+}
+
+impl RigidBodyHandler {
+    fn without_ownership(inner_handle: RapierRigidBodyHandle, owner_id: OwnerId) -> Self {
+        Self {
+            inner_handle,
+            owner_id,
+            usage_counter: UsageCounter::new(),
+            to_be_removed_rigid_bodies: None,
+            creation_backtrace: Arc::new(None),
+        }
+    }
+}
+
+impl std::fmt::Debug for RigidBodyHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RigidBodyHandler")
+            .field("inner_handle", &self.inner_handle)
+            .finish()
+    }
+}
+
+impl HandlerLeakProbeSource for RigidBodyHandler {
+    fn leak_probe(&self) -> HandlerLeakProbe {
+        HandlerLeakProbe::new(
+            "RigidBodyHandler",
+            &self.creation_backtrace,
+            self.creation_backtrace.as_ref().clone(),
+        )
+    }
+}
+
+impl Drop for RigidBodyHandler {
+    fn drop(&mut self) {
+        if self.usage_counter.is_this_the_last() {
+            if let Some(to_be_removed_rigid_bodies) = &self.to_be_removed_rigid_bodies {
+                to_be_removed_rigid_bodies
+                    .write()
+                    .push((self.inner_handle, self.owner_id));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PhysicsError {
+    InvalidRigidBodyHandler(RigidBodyHandler),
+    InvalidJointHandler(JointHandler),
+    // the fixed and the spherical joints cannot be limited or driven by a motor
+    JointWithoutFreeAxis(JointHandler),
+    InvalidTimeScale(f32),
+    // more steps than the number of stored states
+    InvalidNumberOfSteps(usize),
+}
+
+// the collision detection state is stored along with the objects, so the contacts continue after
+// the restore as if the simulation had not left the snapshot
+#[derive(Clone)]
+pub struct PhysicsSnapshot {
+    objects_state: Rapier3dObjectsState,
+    island_manager: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+}
+
+impl PhysicsSnapshot {
+    // e.g. the transforms of the rigid bodies for a replay
+    pub fn objects_state(&self) -> &Rapier3dObjectsState {
+        &self.objects_state
+    }
 }
 
 #[derive(Clone)]
 pub struct Rapier3dObjectsState {
     rigid_body_set: RigidBodySet,
+    rigid_body_owners: HandleOwners<RapierRigidBodyHandle>,
     collider_set: ColliderSet,
     impulse_joint_set: ImpulseJointSet,
+    joint_owners: HandleOwners<ImpulseJointHandle>,
     multibody_joint_set: MultibodyJointSet,
 }
 
@@ -77,13 +175,33 @@ pub struct Rapier3dPhysicsEngine {
     character_controllers: ObjectPool<ArcRwLock<CharacterController>>,
 
     current_state: Rapier3dObjectsState,
-    previous_states: VecDeque<Rapier3dObjectsState>,
+    // the state before the last step, it is used for the interpolation
+    previous_state: Rapier3dObjectsState,
+    // the rollback buffer, the last one is the snapshot before the last step, it is empty unless
+    // more than the interpolation state is stored
+    stored_snapshots: VecDeque<PhysicsSnapshot>,
+    number_of_stored_states: usize,
 
     ccd_solver: CCDSolver,
     physics_hooks: (),
-    event_handler: (),
+    collision_event_collector: CollisionEventCollector,
+    collision_event_sender: broadcast::Sender<CollisionEvent>,
+    // set by the game, so the collision events can refer to the entities of the rigid bodies
+    entities_of_rigid_bodies: HashMap<RapierRigidBodyHandle, EntityId>,
 
     to_be_dropped_character_controllers: ArcRwLock<Vec<ObjectPoolIndex>>,
+    to_be_removed_rigid_bodies: ArcRwLock<Vec<(RapierRigidBodyHandle, OwnerId)>>,
+    to_be_removed_joints: ArcRwLock<Vec<(ImpulseJointHandle, OwnerId)>>,
+    next_owner_id: OwnerId,
+    // the owners whose handlers are not dropped yet, the objects of the other owners are removed
+    // from the restored snapshots
+    live_owner_ids: HashSet<OwnerId>,
+
+    // shared with the game, so pausing and the time scale apply to everything that is driven by it
+    virtual_clock: VirtualClock,
+    number_of_pending_single_steps: usize,
+
+    task_receiver: TaskReceiver<ChanneledTask>,
 }
 
 pub fn init(app_context: &mut ApplicationContext) {
@@ -95,9 +213,23 @@ pub fn init(app_context: &mut ApplicationContext) {
         .as_ref()
         .clone();
 
-    let result = app_context
+    let virtual_clock = app_context
         .service_container_ref()
-        .insert(RwLock::new(Rapier3dPhysicsEngine::new()));
+        .get_service::<VirtualClock>()
+        .inspect_err(|e| log::error!("{e:?}"))
+        .unwrap()
+        .as_ref()
+        .clone();
+
+    let (task_sender, task_receiver) = task_channel();
+
+    let result =
+        app_context
+            .service_container_ref()
+            .insert(RwLock::new(Rapier3dPhysicsEngine::new(
+                virtual_clock,
+                task_receiver,
+            )));
 
     result.old_item.inspect(|_| {
         let error_msg = "Rapier3dPhysicsEngine already added to the service container";
@@ -107,6 +239,10 @@ pub fn init(app_context: &mut ApplicationContext) {
 
     let physics_engine = result.new_item.as_arc_ref().clone();
 
+    app_context
+        .service_container_ref()
+        .insert(PhysicsClient::new(task_sender));
+
     tokio::spawn(async move {
         Rapier3dPhysicsEngine::run(app_loop_state_watcher, physics_engine).await;
     });
@@ -137,15 +273,318 @@ impl Rapier3dPhysicsEngine {
         RigidBodyBuilder::new(collider, rigid_body_type)
     }
 
+    pub fn joint_builder(
+        &self,
+        joint_type: JointType,
+        rigid_body_handler1: RigidBodyHandler,
+        rigid_body_handler2: RigidBodyHandler,
+    ) -> JointBuilder {
+        JointBuilder::new(joint_type, rigid_body_handler1, rigid_body_handler2)
+    }
+
+    // the first hit along the ray, the direction does not have to be normalized, the toi of the
+    // hit is measured in its length
+    #[method_taskifier_worker_fn]
+    pub fn raycast(
+        &self,
+        origin: Vec3<f32>,
+        direction: Vec3<f32>,
+        max_toi: f32,
+        filter: PhysicsQueryFilter,
+    ) -> Option<PhysicsQueryHit> {
+        let ray = Ray::new(
+            Point3::new(origin.x, origin.y, origin.z),
+            query::to_nalgebra_vector3(direction),
+        );
+
+        let (collider_handle, intersection) = self.query_pipeline.cast_ray_and_get_normal(
+            &self.current_state.rigid_body_set,
+            &self.current_state.collider_set,
+            &ray,
+            max_toi,
+            true,
+            filter.as_rapier_query_filter(),
+        )?;
+
+        Some(PhysicsQueryHit {
+            rigid_body_handler: query::rigid_body_of_collider(&self.current_state, collider_handle),
+            point: query::to_vek_vec3(&ray.point_at(intersection.toi).coords),
+            normal: query::to_vek_vec3(&intersection.normal),
+            toi: intersection.toi,
+        })
+    }
+
+    // the shape is moved from the position along the direction, e.g. a ground probe below a
+    // character
+    #[method_taskifier_worker_fn]
+    pub fn shape_cast(
+        &self,
+        shape: ColliderShape,
+        position: Vec3<f32>,
+        orientation: Quaternion<f32>,
+        direction: Vec3<f32>,
+        max_toi: f32,
+        filter: PhysicsQueryFilter,
+    ) -> Option<PhysicsQueryHit> {
+        let rapier_shape = shape.as_rapier_collider_shape();
+
+        let (collider_handle, toi) = self.query_pipeline.cast_shape(
+            &self.current_state.rigid_body_set,
+            &self.current_state.collider_set,
+            &query::to_isometry(position, orientation),
+            &query::to_nalgebra_vector3(direction),
+            rapier_shape.0.as_ref(),
+            max_toi,
+            true,
+            filter.as_rapier_query_filter(),
+        )?;
+
+        // the witness and the normal of the hit collider are in its local space
+        let collider_position = self
+            .current_state
+            .collider_set
+            .get(collider_handle)?
+            .position();
+        let point = collider_position * toi.witness1;
+        let normal = collider_position.rotation * *toi.normal1;
+
+        Some(PhysicsQueryHit {
+            rigid_body_handler: query::rigid_body_of_collider(&self.current_state, collider_handle),
+            point: query::to_vek_vec3(&point.coords),
+            normal: query::to_vek_vec3(&normal),
+            toi: toi.toi,
+        })
+    }
+
+    // the impulse changes the velocity of the rigid body at once, e.g. a jump or an explosion
+    #[method_taskifier_worker_fn]
+    pub fn apply_impulse(
+        &mut self,
+        rigid_body_handler: RigidBodyHandler,
+        impulse: Vec3<f32>,
+    ) -> Result<(), PhysicsError> {
+        self.get_rigid_body_mut(&rigid_body_handler)?
+            .apply_impulse(query::to_nalgebra_vector3(impulse), true);
+        Ok(())
+    }
+
+    // the force is applied in every step until the forces of the rigid body are reset, e.g. a
+    // thruster
+    #[method_taskifier_worker_fn]
+    pub fn apply_force(
+        &mut self,
+        rigid_body_handler: RigidBodyHandler,
+        force: Vec3<f32>,
+    ) -> Result<(), PhysicsError> {
+        self.get_rigid_body_mut(&rigid_body_handler)?
+            .add_force(query::to_nalgebra_vector3(force), true);
+        Ok(())
+    }
+
+    #[method_taskifier_worker_fn]
+    pub fn reset_forces(
+        &mut self,
+        rigid_body_handler: RigidBodyHandler,
+    ) -> Result<(), PhysicsError> {
+        self.get_rigid_body_mut(&rigid_body_handler)?
+            .reset_forces(true);
+        Ok(())
+    }
+
+    #[method_taskifier_worker_fn]
+    pub fn set_linear_velocity(
+        &mut self,
+        rigid_body_handler: RigidBodyHandler,
+        velocity: Vec3<f32>,
+    ) -> Result<(), PhysicsError> {
+        self.get_rigid_body_mut(&rigid_body_handler)?
+            .set_linvel(query::to_nalgebra_vector3(velocity), true);
+        Ok(())
+    }
+
+    // the linear velocity of the rigid body
+    #[method_taskifier_worker_fn]
+    pub fn get_velocity(
+        &self,
+        rigid_body_handler: RigidBodyHandler,
+    ) -> Result<Vec3<f32>, PhysicsError> {
+        self.current_state
+            .get_rigid_body(&rigid_body_handler)
+            .map(|rigid_body| query::to_vek_vec3(rigid_body.linvel()))
+            .ok_or(PhysicsError::InvalidRigidBodyHandler(rigid_body_handler))
+    }
+
+    // the rigid body is teleported, so it is not interpolated from its previous position
+    #[method_taskifier_worker_fn]
+    pub fn set_position(
+        &mut self,
+        rigid_body_handler: RigidBodyHandler,
+        position: Vec3<f32>,
+    ) -> Result<(), PhysicsError> {
+        let translation = query::to_nalgebra_vector3(position);
+        self.get_rigid_body_mut(&rigid_body_handler)?
+            .set_translation(translation, true);
+
+        if let Some(rigid_body) = self
+            .previous_state
+            .rigid_body_set
+            .get_mut(rigid_body_handler.inner_handle)
+        {
+            rigid_body.set_translation(translation, false);
+        }
+
+        Ok(())
+    }
+
+    // the rigid body and its colliders are removed at once, the other handlers of the rigid body
+    // become invalid
+    #[method_taskifier_worker_fn]
+    pub fn remove_rigid_body(
+        &mut self,
+        rigid_body_handler: RigidBodyHandler,
+    ) -> Result<(), PhysicsError> {
+        if self
+            .remove_owned_rigid_body(rigid_body_handler.inner_handle, rigid_body_handler.owner_id)
+        {
+            Ok(())
+        } else {
+            Err(PhysicsError::InvalidRigidBodyHandler(rigid_body_handler))
+        }
+    }
+
+    #[method_taskifier_worker_fn]
+    pub fn set_joint_limits(
+        &mut self,
+        joint_handler: JointHandler,
+        min: f32,
+        max: f32,
+    ) -> Result<(), PhysicsError> {
+        let axis = joint_handler
+            .free_axis
+            .ok_or_else(|| PhysicsError::JointWithoutFreeAxis(joint_handler.clone()))?;
+        self.change_joint(&joint_handler, |joint| {
+            joint.set_limits(axis, [min, max]);
+        })
+    }
+
+    // e.g. opening a door or driving the wheels of a vehicle
+    #[method_taskifier_worker_fn]
+    pub fn set_joint_motor(
+        &mut self,
+        joint_handler: JointHandler,
+        motor: JointMotor,
+    ) -> Result<(), PhysicsError> {
+        let axis = joint_handler
+            .free_axis
+            .ok_or_else(|| PhysicsError::JointWithoutFreeAxis(joint_handler.clone()))?;
+        self.change_joint(&joint_handler, |joint| {
+            motor.apply_to(joint, axis);
+        })
+    }
+
+    // the other handlers of the joint become invalid
+    #[method_taskifier_worker_fn]
+    pub fn remove_joint(&mut self, joint_handler: JointHandler) -> Result<(), PhysicsError> {
+        if self.remove_owned_joint(joint_handler.inner_handle, joint_handler.owner_id) {
+            Ok(())
+        } else {
+            Err(PhysicsError::InvalidJointHandler(joint_handler))
+        }
+    }
+
+    // e.g. the menu of the game, the queries and the runtime control of the rigid bodies still work
+    #[method_taskifier_worker_fn]
+    pub fn pause(&mut self) {
+        if !self.virtual_clock.is_paused() {
+            self.virtual_clock.pause();
+            self.freeze_interpolation();
+        }
+    }
+
+    #[method_taskifier_worker_fn]
+    pub fn resume(&mut self) {
+        self.virtual_clock.resume();
+        self.number_of_pending_single_steps = 0;
+    }
+
+    #[method_taskifier_worker_fn]
+    pub fn is_paused(&self) -> bool {
+        self.virtual_clock.is_paused()
+    }
+
+    // the paused simulation advances by a single step with the next tick, e.g. for debugging
+    #[method_taskifier_worker_fn]
+    pub fn step_once(&mut self) {
+        if self.virtual_clock.is_paused() {
+            self.number_of_pending_single_steps += 1;
+        }
+    }
+
+    // e.g. 0.25 for slow motion, the paused simulation has to be resumed instead of setting zero
+    #[method_taskifier_worker_fn]
+    pub fn set_time_scale(&mut self, time_scale: f32) -> Result<(), PhysicsError> {
+        if time_scale > 0.0 && time_scale.is_finite() {
+            self.virtual_clock.set_speed_multiplier(time_scale as f64);
+            Ok(())
+        } else {
+            Err(PhysicsError::InvalidTimeScale(time_scale))
+        }
+    }
+
+    // the character controllers are not part of the snapshot
+    #[method_taskifier_worker_fn]
+    pub fn snapshot(&self) -> PhysicsSnapshot {
+        self.take_snapshot()
+    }
+
+    // e.g. a replay or the correction of a networked game, the handlers of the objects that were
+    // added since the snapshot become invalid, the objects of the handlers that were dropped since
+    // the snapshot are not restored
+    #[method_taskifier_worker_fn]
+    pub fn restore(&mut self, snapshot: PhysicsSnapshot) {
+        self.stored_snapshots.clear();
+        self.restore_snapshot(snapshot);
+    }
+
+    // the state before the given number of steps is restored from the stored snapshots, e.g. a
+    // kill-cam rewind, the snapshots after it are dropped
+    #[method_taskifier_worker_fn]
+    pub fn rollback(&mut self, number_of_steps: usize) -> Result<(), PhysicsError> {
+        if number_of_steps == 0 || number_of_steps > self.stored_snapshots.len() {
+            return Err(PhysicsError::InvalidNumberOfSteps(number_of_steps));
+        }
+
+        let index = self.stored_snapshots.len() - number_of_steps;
+        let snapshot = self.stored_snapshots.drain(index..).next();
+        if let Some(snapshot) = snapshot {
+            self.restore_snapshot(snapshot);
+        }
+
+        Ok(())
+    }
+
+    // one state is always stored for the interpolation, the further ones are snapshots taken
+    // before the steps for the rollback, each of them costs a full copy of the rigid bodies, the
+    // colliders and the joints (the collider shapes are shared), the island manager, the broad
+    // phase and the narrow phase with its contacts
+    #[method_taskifier_worker_fn]
+    pub fn set_number_of_stored_states(&mut self, number_of_stored_states: usize) {
+        self.number_of_stored_states = number_of_stored_states.max(1);
+        while self.stored_snapshots.len() >= self.number_of_stored_states {
+            self.stored_snapshots.pop_front();
+        }
+    }
+
     pub fn get_interpolated_transform_of_rigidbody(
         &self,
         rigid_body_handler: &RigidBodyHandler,
         now: &Instant,
     ) -> Option<(Vec3<f32>, Quaternion<f32>)> {
-        let previous_state = if let Some(previous_state) = self.previous_states.back() {
-            previous_state
-        } else {
+        // the paused simulation shows its current state
+        let previous_state = if self.virtual_clock.is_paused() {
             &self.current_state
+        } else {
+            &self.previous_state
         };
 
         let previous_rigid_body_transform =
@@ -182,13 +621,19 @@ impl Rapier3dPhysicsEngine {
             })
     }
 
-    fn new() -> Self {
-        Self::from_objects_state(Rapier3dObjectsState {
-            rigid_body_set: RigidBodySet::new(),
-            collider_set: ColliderSet::new(),
-            impulse_joint_set: ImpulseJointSet::new(),
-            multibody_joint_set: MultibodyJointSet::new(),
-        })
+    fn new(virtual_clock: VirtualClock, task_receiver: TaskReceiver<ChanneledTask>) -> Self {
+        Self::from_objects_state(
+            virtual_clock,
+            Rapier3dObjectsState {
+                rigid_body_set: RigidBodySet::new(),
+                rigid_body_owners: HandleOwners::new(),
+                collider_set: ColliderSet::new(),
+                impulse_joint_set: ImpulseJointSet::new(),
+                joint_owners: HandleOwners::new(),
+                multibody_joint_set: MultibodyJointSet::new(),
+            },
+            task_receiver,
+        )
     }
 
     fn add_character_controller(
@@ -203,6 +648,7 @@ impl Rapier3dPhysicsEngine {
             character_controller,
             to_be_dropped_character_controllers: self.to_be_dropped_character_controllers.clone(),
             usage_counter: UsageCounter::new(),
+            creation_backtrace: Arc::new(capture_creation_backtrace()),
         }
     }
 
@@ -235,15 +681,60 @@ impl Rapier3dPhysicsEngine {
             );
         }
 
+        let owner_id = self.create_owner_id();
+        self.current_state
+            .rigid_body_owners
+            .insert(rigid_body_handle, owner_id);
+
         RigidBodyHandler {
             inner_handle: rigid_body_handle,
+            owner_id,
+            usage_counter: UsageCounter::new(),
+            to_be_removed_rigid_bodies: Some(self.to_be_removed_rigid_bodies.clone()),
+            creation_backtrace: Arc::new(capture_creation_backtrace()),
         }
     }
 
-    fn from_objects_state(state: Rapier3dObjectsState) -> Self {
-        let mut previous_states = VecDeque::with_capacity(NUMBER_OF_STORED_STATES);
-        previous_states.push_back(state.clone());
+    fn add_joint(&mut self, joint: Joint) -> Result<JointHandler, PhysicsError> {
+        for rigid_body_handler in joint.rigid_body_handlers.iter() {
+            if self
+                .current_state
+                .get_rigid_body(rigid_body_handler)
+                .is_none()
+            {
+                return Err(PhysicsError::InvalidRigidBodyHandler(
+                    rigid_body_handler.clone(),
+                ));
+            }
+        }
+
+        let joint_handle = self.current_state.impulse_joint_set.insert(
+            joint.rigid_body_handlers[0].inner_handle,
+            joint.rigid_body_handlers[1].inner_handle,
+            joint.as_generic_joint(),
+            true,
+        );
+
+        let owner_id = self.create_owner_id();
+        self.current_state
+            .joint_owners
+            .insert(joint_handle, owner_id);
+
+        Ok(JointHandler {
+            inner_handle: joint_handle,
+            owner_id,
+            free_axis: joint.joint_type.free_axis(),
+            usage_counter: UsageCounter::new(),
+            to_be_removed_joints: self.to_be_removed_joints.clone(),
+            creation_backtrace: Arc::new(capture_creation_backtrace()),
+        })
+    }
 
+    fn from_objects_state(
+        virtual_clock: VirtualClock,
+        state: Rapier3dObjectsState,
+        task_receiver: TaskReceiver<ChanneledTask>,
+    ) -> Self {
         let current_time = Instant::now();
 
         Self {
@@ -262,14 +753,27 @@ impl Rapier3dPhysicsEngine {
 
             character_controllers: ObjectPool::new(),
 
+            previous_state: state.clone(),
             current_state: state,
-            previous_states,
+            stored_snapshots: VecDeque::new(),
+            number_of_stored_states: DEFAULT_NUMBER_OF_STORED_STATES,
 
             ccd_solver: CCDSolver::new(),
             physics_hooks: (),
-            event_handler: (),
+            collision_event_collector: CollisionEventCollector::default(),
+            collision_event_sender: broadcast::Sender::new(),
+            entities_of_rigid_bodies: HashMap::new(),
 
             to_be_dropped_character_controllers: arc_rw_lock_new(Vec::new()),
+            to_be_removed_rigid_bodies: arc_rw_lock_new(Vec::new()),
+            to_be_removed_joints: arc_rw_lock_new(Vec::new()),
+            next_owner_id: 0,
+            live_owner_ids: HashSet::new(),
+
+            virtual_clock,
+            number_of_pending_single_steps: 0,
+
+            task_receiver,
         }
     }
 
@@ -281,23 +785,26 @@ impl Rapier3dPhysicsEngine {
         let mut interval = interval(Duration::from_secs_f32(interval_secs));
         interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
 
-        // let mut delta_time_secs = 0.0;
+        // the tasks are executed as they arrive, so the clients do not wait for the next step
+        let mut task_receiver = physics_engine.read().task_receiver.clone();
+        let mut has_task_senders = true;
 
         loop {
-            // let start = Instant::now();
-
             tokio::select! {
                 _ = app_loop_state_watcher.wait_for_quit() => {
                     break;
                 }
+                task = task_receiver.recv(), if has_task_senders => {
+                    match task {
+                        Ok(task) => physics_engine.write().execute_channeled_task(task),
+                        Err(_) => has_task_senders = false,
+                    }
+                }
                 _ = interval.tick() => {
-                    // self.step(delta_time_secs);
-                    physics_engine.write().step(interval_secs);
+                    profile_scope!("physics.step");
+                    physics_engine.write().tick(interval_secs);
                 }
             }
-
-            // let end = Instant::now();
-            // delta_time_secs = (end - start).as_secs_f32();
         }
     }
 
@@ -364,7 +871,7 @@ impl Rapier3dPhysicsEngine {
                 character_controller.shape.0.as_ref(),
                 &position,
                 Vector3::new(translation.x, translation.y, translation.z),
-                QueryFilter::default(),
+                character_controller.query_filter.as_rapier_query_filter(),
                 |collision| collisions.push(collision),
             );
 
@@ -390,25 +897,50 @@ impl Rapier3dPhysicsEngine {
                         character_controller.shape.0.as_ref(),
                         character_controller.mass,
                         &collision,
-                        QueryFilter::default(),
+                        character_controller.query_filter.as_rapier_query_filter(),
                     );
             }
         }
     }
 
-    fn step(&mut self, delta_time_secs: f32) {
+    // the time scale changes the simulated time of a step, the steps keep their real interval,
+    // so the interpolation stays smooth in slow motion
+    fn tick(&mut self, tick_interval_secs: f32) {
+        let delta_time_secs = self
+            .virtual_clock
+            .real_to_virtual_seconds_f32(tick_interval_secs);
+
+        if !self.virtual_clock.is_paused() {
+            self.number_of_pending_single_steps = 0;
+            self.step(delta_time_secs, tick_interval_secs);
+        } else {
+            // the clock can be paused by the game too, not only through the physics engine
+            if self.number_of_pending_single_steps > 0 {
+                self.number_of_pending_single_steps -= 1;
+                self.step(delta_time_secs, tick_interval_secs);
+            }
+            self.freeze_interpolation();
+        }
+    }
+
+    fn step(&mut self, delta_time_secs: f32, tick_interval_secs: f32) {
         self.integration_parameters.dt = delta_time_secs;
 
         self.last_tick_time = Instant::now();
         self.predicted_next_tick_time =
-            self.last_tick_time + Duration::from_secs_f32(delta_time_secs);
+            self.last_tick_time + Duration::from_secs_f32(tick_interval_secs);
 
-        if self.previous_states.len() == NUMBER_OF_STORED_STATES {
-            self.previous_states.pop_front();
+        self.previous_state = self.current_state.clone();
+        if self.number_of_stored_states > 1 {
+            while self.stored_snapshots.len() >= self.number_of_stored_states - 1 {
+                self.stored_snapshots.pop_front();
+            }
+            self.stored_snapshots.push_back(self.take_snapshot());
         }
-        self.previous_states.push_back(self.current_state.clone());
 
         self.handle_to_be_dropped_character_controllers();
+        self.handle_to_be_removed_rigid_bodies();
+        self.handle_to_be_removed_joints();
         self.move_characters(delta_time_secs);
 
         let gravity = vector![self.gravity.x, self.gravity.y, self.gravity.z];
@@ -425,12 +957,84 @@ impl Rapier3dPhysicsEngine {
             &mut self.ccd_solver,
             None,
             &self.physics_hooks,
-            &self.event_handler,
+            &self.collision_event_collector,
         );
         self.query_pipeline.update(
             &self.current_state.rigid_body_set,
             &self.current_state.collider_set,
         );
+
+        self.publish_collision_events();
+    }
+
+    // the interpolation would move the characters past their current position while the simulation
+    // does not advance
+    fn freeze_interpolation(&mut self) {
+        for character_controller in self.character_controllers.iter_mut() {
+            let mut character_controller = character_controller.write();
+            character_controller.previous_position = character_controller.position;
+        }
+    }
+
+    fn take_snapshot(&self) -> PhysicsSnapshot {
+        PhysicsSnapshot {
+            objects_state: self.current_state.clone(),
+            island_manager: self.island_manager.clone(),
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+        }
+    }
+
+    fn restore_snapshot(&mut self, snapshot: PhysicsSnapshot) {
+        // the dropped handlers are known after the removal queues are handled
+        self.handle_to_be_removed_rigid_bodies();
+        self.handle_to_be_removed_joints();
+
+        self.current_state = snapshot.objects_state;
+        self.island_manager = snapshot.island_manager;
+        self.broad_phase = snapshot.broad_phase;
+        self.narrow_phase = snapshot.narrow_phase;
+
+        // the objects of the dropped handlers would come back without an owner and leak
+        for joint_handle in self
+            .current_state
+            .joint_owners
+            .retain_owners(&self.live_owner_ids)
+        {
+            self.current_state
+                .impulse_joint_set
+                .remove(joint_handle, true);
+        }
+        for rigid_body_handle in self
+            .current_state
+            .rigid_body_owners
+            .retain_owners(&self.live_owner_ids)
+        {
+            self.current_state.rigid_body_set.remove(
+                rigid_body_handle,
+                &mut self.island_manager,
+                &mut self.current_state.collider_set,
+                &mut self.current_state.impulse_joint_set,
+                &mut self.current_state.multibody_joint_set,
+                true,
+            );
+        }
+        // the handlers created since the snapshot do not own anything in the restored state, their
+        // handles are given out again to the new objects
+        let rigid_body_owners = &self.current_state.rigid_body_owners;
+        self.entities_of_rigid_bodies
+            .retain(|rigid_body_handle, _| rigid_body_owners.owner_of(rigid_body_handle).is_some());
+
+        self.query_pipeline.update(
+            &self.current_state.rigid_body_set,
+            &self.current_state.collider_set,
+        );
+        // the events of the abandoned steps are not published
+        self.collision_event_collector.take_events();
+
+        // the restored state is not interpolated from the abandoned one
+        self.previous_state = self.current_state.clone();
+        self.freeze_interpolation();
     }
 
     fn handle_to_be_dropped_character_controllers(&mut self) {
@@ -443,6 +1047,186 @@ impl Rapier3dPhysicsEngine {
             self.character_controllers.release_object(object_pool_index);
         }
     }
+
+    fn publish_collision_events(&mut self) {
+        for event in self.collision_event_collector.take_events() {
+            let collider_handles = [event.collider1(), event.collider2()];
+            let rigid_body_handlers = collider_handles.map(|collider_handle| {
+                query::rigid_body_of_collider(&self.current_state, collider_handle)
+            });
+            let entity_ids = [0, 1].map(|index| {
+                rigid_body_handlers[index]
+                    .as_ref()
+                    .and_then(|rigid_body_handler| {
+                        self.entities_of_rigid_bodies
+                            .get(&rigid_body_handler.inner_handle)
+                            .copied()
+                    })
+            });
+
+            self.collision_event_sender
+                .send(match (event.sensor(), event.started()) {
+                    (false, true) => CollisionEvent::CollisionStarted {
+                        rigid_body_handlers,
+                        entity_ids,
+                    },
+                    (false, false) => CollisionEvent::CollisionStopped {
+                        rigid_body_handlers,
+                        entity_ids,
+                    },
+                    (true, true) => CollisionEvent::IntersectionStarted {
+                        rigid_body_handlers,
+                        entity_ids,
+                    },
+                    (true, false) => CollisionEvent::IntersectionStopped {
+                        rigid_body_handlers,
+                        entity_ids,
+                    },
+                });
+        }
+    }
+
+    fn get_rigid_body_mut(
+        &mut self,
+        rigid_body_handler: &RigidBodyHandler,
+    ) -> Result<&mut RapierRigidBody, PhysicsError> {
+        if !self.current_state.rigid_body_owners.is_owned_by(
+            &rigid_body_handler.inner_handle,
+            rigid_body_handler.owner_id,
+        ) {
+            return Err(PhysicsError::InvalidRigidBodyHandler(
+                rigid_body_handler.clone(),
+            ));
+        }
+
+        self.current_state
+            .rigid_body_set
+            .get_mut(rigid_body_handler.inner_handle)
+            .ok_or_else(|| PhysicsError::InvalidRigidBodyHandler(rigid_body_handler.clone()))
+    }
+
+    fn create_owner_id(&mut self) -> OwnerId {
+        let owner_id = self.next_owner_id;
+        self.next_owner_id += 1;
+        self.live_owner_ids.insert(owner_id);
+        owner_id
+    }
+
+    fn handle_to_be_removed_rigid_bodies(&mut self) {
+        let mut to_be_removed_rigid_bodies = Vec::new();
+        swap(
+            self.to_be_removed_rigid_bodies.write().deref_mut(),
+            &mut to_be_removed_rigid_bodies,
+        );
+        for (rigid_body_handle, owner_id) in to_be_removed_rigid_bodies {
+            self.live_owner_ids.remove(&owner_id);
+            self.remove_owned_rigid_body(rigid_body_handle, owner_id);
+        }
+    }
+
+    fn handle_to_be_removed_joints(&mut self) {
+        let mut to_be_removed_joints = Vec::new();
+        swap(
+            self.to_be_removed_joints.write().deref_mut(),
+            &mut to_be_removed_joints,
+        );
+        for (joint_handle, owner_id) in to_be_removed_joints {
+            self.live_owner_ids.remove(&owner_id);
+            self.remove_owned_joint(joint_handle, owner_id);
+        }
+    }
+
+    // the joints of the removed rigid bodies are already gone from the joint set
+    fn remove_owned_joint(&mut self, joint_handle: ImpulseJointHandle, owner_id: OwnerId) -> bool {
+        self.current_state
+            .joint_owners
+            .remove_if_owned_by(&joint_handle, owner_id)
+            && self
+                .current_state
+                .impulse_joint_set
+                .remove(joint_handle, true)
+                .is_some()
+    }
+
+    // the rigid bodies of the joint are woken up, so the change takes effect at once
+    fn change_joint(
+        &mut self,
+        joint_handler: &JointHandler,
+        change: impl FnOnce(&mut GenericJoint),
+    ) -> Result<(), PhysicsError> {
+        let is_owned = self
+            .current_state
+            .joint_owners
+            .is_owned_by(&joint_handler.inner_handle, joint_handler.owner_id);
+        let Some(joint) = self
+            .current_state
+            .impulse_joint_set
+            .get_mut(joint_handler.inner_handle)
+            .filter(|_| is_owned)
+        else {
+            return Err(PhysicsError::InvalidJointHandler(joint_handler.clone()));
+        };
+        change(&mut joint.data);
+        let rigid_body_handles = [joint.body1, joint.body2];
+
+        for rigid_body_handle in rigid_body_handles {
+            if let Some(rigid_body) = self.current_state.rigid_body_set.get_mut(rigid_body_handle) {
+                rigid_body.wake_up(true);
+            }
+        }
+
+        Ok(())
+    }
+
+    // the handle of the rigid body may have been reused by an other one since it was removed, the
+    // owner is compared, so removing it again does nothing
+    fn remove_owned_rigid_body(
+        &mut self,
+        rigid_body_handle: RapierRigidBodyHandle,
+        owner_id: OwnerId,
+    ) -> bool {
+        if !self
+            .current_state
+            .rigid_body_owners
+            .remove_if_owned_by(&rigid_body_handle, owner_id)
+        {
+            return false;
+        }
+
+        self.entities_of_rigid_bodies.remove(&rigid_body_handle);
+        self.current_state
+            .rigid_body_set
+            .remove(
+                rigid_body_handle,
+                &mut self.island_manager,
+                &mut self.current_state.collider_set,
+                &mut self.current_state.impulse_joint_set,
+                &mut self.current_state.multibody_joint_set,
+                true,
+            )
+            .is_some()
+    }
+}
+
+impl Rapier3dPhysicsEngine {
+    pub fn collision_event_receiver(&self) -> broadcast::Receiver<CollisionEvent> {
+        self.collision_event_sender.create_receiver()
+    }
+
+    pub fn set_entity_of_rigid_body(
+        &mut self,
+        rigid_body_handler: &RigidBodyHandler,
+        entity_id: EntityId,
+    ) {
+        if self
+            .current_state
+            .get_rigid_body(rigid_body_handler)
+            .is_some()
+        {
+            self.entities_of_rigid_bodies
+                .insert(rigid_body_handler.inner_handle, entity_id);
+        }
+    }
 }
 
 impl Rapier3dObjectsState {
@@ -450,15 +1234,533 @@ impl Rapier3dObjectsState {
         &self,
         rigid_body_handler: &RigidBodyHandler,
     ) -> Option<(Vec3<f32>, Quaternion<f32>)> {
-        self.rigid_body_set
-            .get(rigid_body_handler.inner_handle)
-            .map(|rigid_body| {
-                let position = rigid_body.translation();
-                let rotation = rigid_body.rotation().as_vector();
-                (
-                    Vec3::new(position.x, position.y, position.z),
-                    Quaternion::from_xyzw(rotation.x, rotation.y, rotation.z, rotation.w),
+        self.get_rigid_body(rigid_body_handler).map(|rigid_body| {
+            let position = rigid_body.translation();
+            let rotation = rigid_body.rotation().as_vector();
+            (
+                Vec3::new(position.x, position.y, position.z),
+                Quaternion::from_xyzw(rotation.x, rotation.y, rotation.z, rotation.w),
+            )
+        })
+    }
+
+    // none if the rigid body of the handler was removed, even if its handle was reused since then
+    fn get_rigid_body(&self, rigid_body_handler: &RigidBodyHandler) -> Option<&RapierRigidBody> {
+        if self.rigid_body_owners.is_owned_by(
+            &rigid_body_handler.inner_handle,
+            rigid_body_handler.owner_id,
+        ) {
+            self.rigid_body_set.get(rigid_body_handler.inner_handle)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use entity_component::EntityContainer;
+    use muleengine::mesh_creator::rectangle3d;
+
+    use super::{collider::CollisionGroups, *};
+
+    const STEP_SECS: f32 = 1.0 / 60.0;
+
+    fn create_physics_engine() -> Rapier3dPhysicsEngine {
+        let (_task_sender, task_receiver) = task_channel();
+        Rapier3dPhysicsEngine::new(VirtualClock::new(), task_receiver)
+    }
+
+    fn create_rigid_body(physics_engine: &mut Rapier3dPhysicsEngine) -> RigidBodyHandler {
+        let collider = physics_engine
+            .collider_builder(ColliderShape::Sphere { radius: 0.5 })
+            .build();
+        physics_engine
+            .rigid_body_builder(collider, RigidBodyType::Dynamic)
+            .build(physics_engine)
+    }
+
+    fn create_static_box(
+        physics_engine: &mut Rapier3dPhysicsEngine,
+        position: Vec3<f32>,
+        size: f32,
+    ) -> RigidBodyHandler {
+        let collider = physics_engine
+            .collider_builder(ColliderShape::Box {
+                x: size,
+                y: size,
+                z: size,
+            })
+            .build();
+        physics_engine
+            .rigid_body_builder(collider, RigidBodyType::Static)
+            .position(position)
+            .build(physics_engine)
+    }
+
+    // the ray goes from the positive z towards the origin
+    fn raycast_along_negative_z(
+        physics_engine: &Rapier3dPhysicsEngine,
+        filter: PhysicsQueryFilter,
+    ) -> Option<PhysicsQueryHit> {
+        physics_engine.raycast(
+            Vec3::new(0.0, 0.0, 10.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            100.0,
+            filter,
+        )
+    }
+
+    fn collision_events(receiver: &mut broadcast::Receiver<CollisionEvent>) -> Vec<CollisionEvent> {
+        let mut events = Vec::new();
+        while let Ok(Some(event)) = receiver.try_pop() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn raycast_hits_the_nearest_rigid_body_that_is_not_excluded() {
+        let mut physics_engine = create_physics_engine();
+        let near_box = create_static_box(&mut physics_engine, Vec3::zero(), 1.0);
+        let far_box = create_static_box(&mut physics_engine, Vec3::new(0.0, 0.0, -5.0), 1.0);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        let hit = raycast_along_negative_z(&physics_engine, PhysicsQueryFilter::default()).unwrap();
+        assert_eq!(
+            Some(near_box.inner_handle),
+            hit.rigid_body_handler.map(|handler| handler.inner_handle)
+        );
+        assert!((hit.toi - 9.5).abs() < 0.001);
+        assert!((hit.normal - Vec3::unit_z()).magnitude() < 0.001);
+
+        let hit = raycast_along_negative_z(
+            &physics_engine,
+            PhysicsQueryFilter {
+                excluded_rigid_body: Some(near_box.clone()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            Some(far_box.inner_handle),
+            hit.rigid_body_handler.map(|handler| handler.inner_handle)
+        );
+        assert!((hit.toi - 14.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn shape_cast_stops_at_the_ground() {
+        let mut physics_engine = create_physics_engine();
+        create_static_box(&mut physics_engine, Vec3::zero(), 1.0);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        let hit = physics_engine
+            .shape_cast(
+                ColliderShape::Sphere { radius: 0.5 },
+                Vec3::new(0.0, 5.0, 0.0),
+                Quaternion::identity(),
+                Vec3::new(0.0, -1.0, 0.0),
+                10.0,
+                PhysicsQueryFilter::default(),
+            )
+            .unwrap();
+        assert!((hit.toi - 4.0).abs() < 0.001);
+        assert!((hit.point - Vec3::new(0.0, 0.5, 0.0)).magnitude() < 0.001);
+        assert!((hit.normal - Vec3::unit_y()).magnitude() < 0.001);
+
+        assert!(physics_engine
+            .shape_cast(
+                ColliderShape::Sphere { radius: 0.5 },
+                Vec3::new(0.0, 5.0, 0.0),
+                Quaternion::identity(),
+                Vec3::new(0.0, 1.0, 0.0),
+                10.0,
+                PhysicsQueryFilter::default(),
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn collision_events_refer_to_the_entities_of_the_rigid_bodies() {
+        let entity_container = EntityContainer::new();
+        let ground_entity_id = entity_container.entity_builder().build();
+        let ball_entity_id = entity_container.entity_builder().build();
+
+        let mut physics_engine = create_physics_engine();
+        let ground = create_static_box(&mut physics_engine, Vec3::zero(), 1.0);
+        let collider = physics_engine
+            .collider_builder(ColliderShape::Sphere { radius: 0.5 })
+            .build();
+        let ball = physics_engine
+            .rigid_body_builder(collider, RigidBodyType::Dynamic)
+            .position(Vec3::new(0.0, 1.5, 0.0))
+            .build(&mut physics_engine);
+        physics_engine.set_entity_of_rigid_body(&ground, ground_entity_id);
+        physics_engine.set_entity_of_rigid_body(&ball, ball_entity_id);
+
+        let mut receiver = physics_engine.collision_event_receiver();
+        for _ in 0..60 {
+            physics_engine.step(STEP_SECS, STEP_SECS);
+        }
+
+        let entity_ids_of_started_collisions = collision_events(&mut receiver)
+            .into_iter()
+            .filter_map(|event| match event {
+                CollisionEvent::CollisionStarted { entity_ids, .. } => Some(entity_ids),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(!entity_ids_of_started_collisions.is_empty());
+        let entity_ids = entity_ids_of_started_collisions[0];
+        assert!(entity_ids.contains(&Some(ground_entity_id)));
+        assert!(entity_ids.contains(&Some(ball_entity_id)));
+    }
+
+    #[test]
+    fn sensors_report_intersections_and_do_not_push_the_rigid_bodies() {
+        let mut physics_engine = create_physics_engine();
+        let sensor_collider = physics_engine
+            .collider_builder(ColliderShape::Box {
+                x: 4.0,
+                y: 4.0,
+                z: 4.0,
+            })
+            .sensor(true)
+            .build();
+        physics_engine
+            .rigid_body_builder(sensor_collider, RigidBodyType::Static)
+            .build(&mut physics_engine);
+        let ball = create_rigid_body(&mut physics_engine);
+        physics_engine.gravity = Vec3::zero();
+
+        let mut receiver = physics_engine.collision_event_receiver();
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        let events = collision_events(&mut receiver);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, CollisionEvent::IntersectionStarted { .. })));
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, CollisionEvent::CollisionStarted { .. })));
+
+        let (position, _) = physics_engine
+            .current_state
+            .get_transform_of_rigidbody(&ball)
+            .unwrap();
+        assert!(position.magnitude() < 0.001);
+    }
+
+    #[test]
+    fn rigid_bodies_can_be_controlled_at_runtime() {
+        let mut physics_engine = create_physics_engine();
+        physics_engine.gravity = Vec3::zero();
+        let rigid_body_handler = create_rigid_body(&mut physics_engine);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        physics_engine
+            .set_linear_velocity(rigid_body_handler.clone(), Vec3::new(1.0, 0.0, 0.0))
+            .unwrap();
+        assert_eq!(
+            Vec3::new(1.0, 0.0, 0.0),
+            physics_engine
+                .get_velocity(rigid_body_handler.clone())
+                .unwrap()
+        );
+
+        physics_engine
+            .set_position(rigid_body_handler.clone(), Vec3::new(3.0, 0.0, 0.0))
+            .unwrap();
+        let (position, _) = physics_engine
+            .current_state
+            .get_transform_of_rigidbody(&rigid_body_handler)
+            .unwrap();
+        assert_eq!(Vec3::new(3.0, 0.0, 0.0), position);
+        // the teleported rigid body is not interpolated from its previous position
+        let (previous_position, _) = physics_engine
+            .previous_state
+            .get_transform_of_rigidbody(&rigid_body_handler)
+            .unwrap();
+        assert_eq!(Vec3::new(3.0, 0.0, 0.0), previous_position);
+
+        assert!(physics_engine
+            .remove_rigid_body(rigid_body_handler.clone())
+            .is_ok());
+        assert!(physics_engine
+            .remove_rigid_body(rigid_body_handler.clone())
+            .is_err());
+        assert!(physics_engine
+            .set_linear_velocity(rigid_body_handler, Vec3::zero())
+            .is_err());
+        assert_eq!(0, physics_engine.current_state.collider_set.len());
+    }
+
+    #[test]
+    fn rigid_bodies_are_removed_when_their_last_handler_is_dropped() {
+        let mut physics_engine = create_physics_engine();
+        let rigid_body_handler = create_static_box(&mut physics_engine, Vec3::zero(), 1.0);
+        let cloned_rigid_body_handler = rigid_body_handler.clone();
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        // the handlers of the query hits do not own the rigid body
+        let hit = raycast_along_negative_z(&physics_engine, PhysicsQueryFilter::default()).unwrap();
+        drop(hit);
+        drop(rigid_body_handler);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+        assert_eq!(1, physics_engine.current_state.rigid_body_set.len());
+
+        drop(cloned_rigid_body_handler);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+        assert_eq!(0, physics_engine.current_state.rigid_body_set.len());
+        assert_eq!(0, physics_engine.current_state.collider_set.len());
+    }
+
+    #[test]
+    fn joints_are_checked_and_removed_when_their_last_handler_is_dropped() {
+        let mut physics_engine = create_physics_engine();
+        let rigid_body_handler1 = create_rigid_body(&mut physics_engine);
+        let rigid_body_handler2 = create_rigid_body(&mut physics_engine);
+
+        let revolute_joint = physics_engine
+            .joint_builder(
+                JointType::Revolute {
+                    axis: Vec3::unit_y(),
+                },
+                rigid_body_handler1.clone(),
+                rigid_body_handler2.clone(),
+            )
+            .local_anchor2(Vec3::new(1.0, 0.0, 0.0))
+            .build(&mut physics_engine)
+            .unwrap();
+        let fixed_joint = physics_engine
+            .joint_builder(
+                JointType::Fixed,
+                rigid_body_handler1.clone(),
+                rigid_body_handler2.clone(),
+            )
+            .build(&mut physics_engine)
+            .unwrap();
+        assert_eq!(2, physics_engine.current_state.impulse_joint_set.len());
+
+        assert!(physics_engine
+            .set_joint_limits(revolute_joint.clone(), -1.0, 1.0)
+            .is_ok());
+        assert!(matches!(
+            physics_engine.set_joint_limits(fixed_joint.clone(), -1.0, 1.0),
+            Err(PhysicsError::JointWithoutFreeAxis(_))
+        ));
+
+        drop(revolute_joint);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+        assert_eq!(1, physics_engine.current_state.impulse_joint_set.len());
+
+        // removing a rigid body removes its joints too
+        physics_engine
+            .remove_rigid_body(rigid_body_handler2.clone())
+            .unwrap();
+        assert_eq!(0, physics_engine.current_state.impulse_joint_set.len());
+        assert!(physics_engine.remove_joint(fixed_joint).is_err());
+
+        assert!(matches!(
+            physics_engine
+                .joint_builder(
+                    JointType::Spherical,
+                    rigid_body_handler1,
+                    rigid_body_handler2
                 )
+                .build(&mut physics_engine),
+            Err(PhysicsError::InvalidRigidBodyHandler(_))
+        ));
+    }
+
+    #[test]
+    fn queries_skip_the_colliders_of_the_filtered_out_collision_groups() {
+        let mut physics_engine = create_physics_engine();
+        let near_collider = physics_engine
+            .collider_builder(ColliderShape::Box {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            })
+            .collision_groups(CollisionGroups::new(0b10, u32::MAX))
+            .build();
+        physics_engine
+            .rigid_body_builder(near_collider, RigidBodyType::Static)
+            .build(&mut physics_engine);
+        let far_box = create_static_box(&mut physics_engine, Vec3::new(0.0, 0.0, -5.0), 1.0);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        let hit = raycast_along_negative_z(
+            &physics_engine,
+            PhysicsQueryFilter {
+                collision_groups: CollisionGroups::new(0b01, 0b01),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            Some(far_box.inner_handle),
+            hit.rigid_body_handler.map(|handler| handler.inner_handle)
+        );
+
+        let hit = raycast_along_negative_z(&physics_engine, PhysicsQueryFilter::default()).unwrap();
+        assert!((hit.toi - 9.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn colliders_are_built_with_their_material_and_mass() {
+        let physics_engine = create_physics_engine();
+
+        let collider = physics_engine
+            .collider_builder(ColliderShape::Sphere { radius: 0.5 })
+            .friction(0.1)
+            .restitution(0.8)
+            .mass(3.0)
+            .build();
+        assert_eq!(0.1, collider.friction());
+        assert_eq!(0.8, collider.restitution());
+        assert!((collider.mass() - 3.0).abs() < 0.001);
+
+        // the mass of a unit cube is its density
+        let collider = physics_engine
+            .collider_builder(ColliderShape::Box {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            })
+            .mass(3.0)
+            .density(2.0)
+            .build();
+        assert!((collider.mass() - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn mesh_collider_shapes_follow_the_mesh() {
+        let mesh = Arc::new(rectangle3d::create(2.0, 2.0, 2.0));
+
+        let convex_hull_shape =
+            ColliderShape::ConvexHullOfMesh(mesh.clone()).as_rapier_collider_shape();
+        assert!(convex_hull_shape.as_convex_polyhedron().is_some());
+
+        let trimesh_shape = ColliderShape::TriMesh(mesh.clone());
+        let aabb = trimesh_shape.compute_aabb();
+        assert_eq!(mesh.get_aabb().get_min_vertex(), aabb.get_min_vertex());
+        assert_eq!(mesh.get_aabb().get_max_vertex(), aabb.get_max_vertex());
+        assert!(trimesh_shape
+            .as_rapier_collider_shape()
+            .as_trimesh()
+            .is_some());
+
+        let mut physics_engine = create_physics_engine();
+        let collider = physics_engine.collider_builder(trimesh_shape).build();
+        let _rigid_body_handler = physics_engine
+            .rigid_body_builder(collider, RigidBodyType::Static)
+            .build(&mut physics_engine);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        let hit = raycast_along_negative_z(&physics_engine, PhysicsQueryFilter::default()).unwrap();
+        assert!((hit.toi - 9.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn colliders_are_placed_relative_to_their_rigid_body() {
+        let mut physics_engine = create_physics_engine();
+        let collider = physics_engine
+            .collider_builder(ColliderShape::Box {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
             })
+            .position(Vec3::new(0.0, 0.0, -5.0))
+            .build();
+        let leg_collider = physics_engine
+            .collider_builder(ColliderShape::Sphere { radius: 0.5 })
+            .position(Vec3::new(0.0, 1.0, 0.0))
+            .build();
+        let _rigid_body_handler = physics_engine
+            .rigid_body_builder(collider, RigidBodyType::Static)
+            .position(Vec3::new(0.0, 0.0, 1.0))
+            .with_collider_at(
+                leg_collider,
+                Vec3::new(2.0, 0.0, 0.0),
+                Quaternion::identity(),
+            )
+            .build(&mut physics_engine);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        let hit = raycast_along_negative_z(&physics_engine, PhysicsQueryFilter::default()).unwrap();
+        assert!((hit.toi - 13.5).abs() < 0.001);
+
+        let mut collider_positions = physics_engine
+            .current_state
+            .collider_set
+            .iter()
+            .map(|(_, collider)| query::to_vek_vec3(collider.translation()))
+            .collect::<Vec<_>>();
+        collider_positions.sort_by(|a, b| a.x.total_cmp(&b.x));
+        assert_eq!(
+            vec![Vec3::new(0.0, 0.0, -4.0), Vec3::new(2.0, 1.0, 1.0)],
+            collider_positions
+        );
+    }
+
+    #[test]
+    fn handlers_created_before_a_restore_do_not_alias_the_new_rigid_bodies() {
+        let mut physics_engine = create_physics_engine();
+
+        let snapshot = physics_engine.snapshot();
+        let created_after_snapshot = create_rigid_body(&mut physics_engine);
+        physics_engine.restore(snapshot);
+        let created_after_restore = create_rigid_body(&mut physics_engine);
+
+        // the restored arena gives out the same handle again
+        assert_eq!(
+            created_after_snapshot.inner_handle,
+            created_after_restore.inner_handle
+        );
+        assert!(physics_engine
+            .get_velocity(created_after_snapshot.clone())
+            .is_err());
+
+        drop(created_after_snapshot);
+        physics_engine.step(STEP_SECS, STEP_SECS);
+
+        assert!(physics_engine
+            .get_velocity(created_after_restore.clone())
+            .is_ok());
+        assert_eq!(1, physics_engine.current_state.rigid_body_set.len());
+    }
+
+    #[test]
+    fn rigid_bodies_of_dropped_handlers_are_not_restored() {
+        let mut physics_engine = create_physics_engine();
+
+        let rigid_body_handler = create_rigid_body(&mut physics_engine);
+        let snapshot = physics_engine.snapshot();
+
+        drop(rigid_body_handler);
+        physics_engine.restore(snapshot);
+
+        assert_eq!(0, physics_engine.current_state.rigid_body_set.len());
+        assert_eq!(0, physics_engine.current_state.collider_set.len());
+    }
+
+    #[test]
+    fn snapshots_are_stored_for_the_rollback_only_when_enabled() {
+        let mut physics_engine = create_physics_engine();
+
+        physics_engine.step(STEP_SECS, STEP_SECS);
+        assert!(physics_engine.stored_snapshots.is_empty());
+        assert!(physics_engine.rollback(1).is_err());
+
+        physics_engine.set_number_of_stored_states(3);
+        for _ in 0..3 {
+            physics_engine.step(STEP_SECS, STEP_SECS);
+        }
+        assert_eq!(2, physics_engine.stored_snapshots.len());
+        assert!(physics_engine.rollback(3).is_err());
+        assert!(physics_engine.rollback(2).is_ok());
+        assert!(physics_engine.stored_snapshots.is_empty());
     }
 }
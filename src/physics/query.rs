@@ -0,0 +1,73 @@
+use rapier3d::prelude::{
+    nalgebra::{self, *},
+    ColliderHandle, QueryFilter,
+};
+use vek::{Quaternion, Vec3};
+
+use super::{collider::CollisionGroups, Rapier3dObjectsState, RigidBodyHandler};
+
+// owned, so it can be sent to the physics engine through the PhysicsClient
+#[derive(Clone, Default)]
+pub struct PhysicsQueryFilter {
+    // e.g. the rigid body of the shooter
+    pub excluded_rigid_body: Option<RigidBodyHandler>,
+    pub exclude_sensors: bool,
+    // the colliders that do not interact with these groups are skipped
+    pub collision_groups: CollisionGroups,
+}
+
+impl PhysicsQueryFilter {
+    pub(super) fn as_rapier_query_filter(&self) -> QueryFilter<'_> {
+        let mut query_filter =
+            QueryFilter::default().groups(self.collision_groups.as_rapier_interaction_groups());
+        if let Some(rigid_body_handler) = &self.excluded_rigid_body {
+            query_filter = query_filter.exclude_rigid_body(rigid_body_handler.inner_handle);
+        }
+        if self.exclude_sensors {
+            query_filter = query_filter.exclude_sensors();
+        }
+        query_filter
+    }
+}
+
+#[derive(Clone)]
+pub struct PhysicsQueryHit {
+    // none if the hit collider is not attached to a rigid body
+    pub rigid_body_handler: Option<RigidBodyHandler>,
+    pub point: Vec3<f32>,
+    pub normal: Vec3<f32>,
+    // the distance along the direction in the units of the direction vector
+    pub toi: f32,
+}
+
+pub(super) fn rigid_body_of_collider(
+    objects_state: &Rapier3dObjectsState,
+    collider_handle: ColliderHandle,
+) -> Option<RigidBodyHandler> {
+    let inner_handle = objects_state
+        .collider_set
+        .get(collider_handle)
+        .and_then(|collider| collider.parent())?;
+    let owner_id = objects_state.rigid_body_owners.owner_of(&inner_handle)?;
+    Some(RigidBodyHandler::without_ownership(inner_handle, owner_id))
+}
+
+pub(super) fn to_vek_vec3(vector: &Vector3<f32>) -> Vec3<f32> {
+    Vec3::new(vector.x, vector.y, vector.z)
+}
+
+pub(super) fn to_nalgebra_vector3(vector: Vec3<f32>) -> Vector3<f32> {
+    Vector3::new(vector.x, vector.y, vector.z)
+}
+
+pub(super) fn to_isometry(position: Vec3<f32>, orientation: Quaternion<f32>) -> Isometry<f32> {
+    Isometry::from_parts(
+        Translation3::new(position.x, position.y, position.z),
+        UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(
+            orientation.w,
+            orientation.x,
+            orientation.y,
+            orientation.z,
+        )),
+    )
+}
@@ -1,7 +1,7 @@
 use rapier3d::prelude::Collider;
-use vek::Vec3;
+use vek::{Quaternion, Vec3};
 
-use super::{Rapier3dPhysicsEngine, RigidBodyHandler};
+use super::{query, Rapier3dPhysicsEngine, RigidBodyHandler};
 
 #[derive(Clone)]
 pub(super) struct RigidBody {
@@ -44,6 +44,19 @@ impl RigidBodyBuilder {
         self
     }
 
+    // the collider is placed relative to the rigid body, on top of the position it was built with,
+    // e.g. the legs of a table
+    pub fn with_collider_at(
+        mut self,
+        mut collider: Collider,
+        position: Vec3<f32>,
+        orientation: Quaternion<f32>,
+    ) -> Self {
+        collider.set_position(query::to_isometry(position, orientation) * collider.position());
+        self.rigid_body.colliders.push(collider);
+        self
+    }
+
     pub fn build(self, physics_engine: &mut Rapier3dPhysicsEngine) -> RigidBodyHandler {
         physics_engine.add_rigid_body(self.rigid_body)
     }
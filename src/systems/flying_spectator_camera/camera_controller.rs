@@ -5,21 +5,21 @@ use std::sync::{
 
 use muleengine::{
     camera::Camera,
-    renderer::{renderer_system::RendererClient, RendererTransformHandler},
+    renderer::{renderer_detached_client::RendererDetachedClient, RendererTransformHandler},
     system_container::System,
 };
 use vek::{Vec2, Vec3};
 
-use crate::essential_services::EssentialServices;
+use crate::{essential_services::EssentialServices, systems::sky_layer::SkyLayer};
 
 use super::input::{InputReceiver, VelocityChangeEvent};
 
 pub(super) struct CameraController {
     enabled: Arc<AtomicBool>,
     camera: Camera,
-    skydome_camera_transform_handler: RendererTransformHandler,
+    sky_layer: SkyLayer,
     main_camera_transform_handler: RendererTransformHandler,
-    renderer_client: RendererClient,
+    renderer_detached_client: RendererDetachedClient,
     input_receiver: InputReceiver,
     mouse_sensitivity: f32,
     camera_vertical_angle_rad: f32,
@@ -36,15 +36,12 @@ impl CameraController {
         Self {
             enabled,
             camera: Camera::new(),
-            skydome_camera_transform_handler: essentials
-                .renderer_configuration
-                .skydome_camera_transform_handler()
-                .await,
+            sky_layer: essentials.renderer_configuration.sky_layer().await,
             main_camera_transform_handler: essentials
                 .renderer_configuration
                 .main_camera_transform_handler()
                 .await,
-            renderer_client: essentials.renderer_client.clone(),
+            renderer_detached_client: essentials.renderer_detached_client.clone(),
             input_receiver,
             mouse_sensitivity: 0.5,
             camera_vertical_angle_rad: 0.0,
@@ -127,17 +124,12 @@ impl System for CameraController {
             self.camera.pitch(self.weighted_turn_value.x);
             self.camera.rotate_around_unit_y(self.weighted_turn_value.y);
 
-            drop(self.renderer_client.update_transform(
+            self.renderer_detached_client.update_transform(
                 self.main_camera_transform_handler.clone(),
                 *self.camera.transform_ref(),
-            ));
-
-            let mut skybox_camera_transform = *self.camera.transform_ref();
-            skybox_camera_transform.position = Vec3::zero();
-            drop(self.renderer_client.update_transform(
-                self.skydome_camera_transform_handler.clone(),
-                skybox_camera_transform,
-            ));
+            );
+
+            self.sky_layer.follow_camera(self.camera.transform_ref());
         }
     }
 }
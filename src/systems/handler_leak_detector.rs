@@ -0,0 +1,144 @@
+use std::{
+    any::Any,
+    backtrace::BacktraceStatus,
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use entity_component::{component_type_list, EntityContainer, EntityGroupEvent, EntityId};
+use muleengine::{
+    bytifex_utils::containers::object_pool::ObjectPoolIndex,
+    handler_leak_probe::{HandlerLeakProbe, HandlerLeakProbeSource},
+    renderer::{RendererObjectHandler, RendererTransformHandler},
+};
+use tokio::time::interval;
+
+use crate::{
+    essential_services::EssentialServices,
+    physics::{
+        character_controller::CharacterControllerHandler, joint::JointHandler, RigidBodyHandler,
+    },
+};
+
+// releasing handlers is asynchronous, so a removed entity gets some time before its handlers are reported
+const LEAK_REPORT_GRACE_PERIOD: Duration = Duration::from_secs(2);
+const LEAK_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+struct RemovedEntityProbe {
+    entity_id: EntityId,
+    removed_at: Instant,
+    probe: HandlerLeakProbe,
+}
+
+pub fn run(essentials: &Arc<EssentialServices>) {
+    watch_handler_component::<RendererObjectHandler>(essentials.entity_container.clone());
+    watch_handler_component::<RendererTransformHandler>(essentials.entity_container.clone());
+    watch_handler_component::<CharacterControllerHandler>(essentials.entity_container.clone());
+    watch_handler_component::<RigidBodyHandler>(essentials.entity_container.clone());
+    watch_handler_component::<JointHandler>(essentials.entity_container.clone());
+}
+
+fn watch_handler_component<HandlerType>(mut entity_container: EntityContainer)
+where
+    HandlerType: HandlerLeakProbeSource + Any + Send,
+{
+    tokio::spawn(async move {
+        let entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list!(HandlerType));
+        let event_receiver = entity_group.event_receiver(true, &mut entity_container.lock());
+
+        let mut probes_of_entities = BTreeMap::<ObjectPoolIndex, HandlerLeakProbe>::new();
+        let mut removed_entity_probes = Vec::<RemovedEntityProbe>::new();
+
+        let mut leak_check_interval = interval(LEAK_CHECK_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = event_receiver.recv() => {
+                    match event {
+                        Some(EntityGroupEvent::EntityAdded { entity_id }) => {
+                            if let Some(probe) = leak_probe_of_entity::<HandlerType>(entity_id, &mut entity_container) {
+                                probes_of_entities.insert(entity_id.index(), probe);
+                            }
+                        }
+                        Some(EntityGroupEvent::EntitiesAdded { entity_ids }) => {
+                            for entity_id in entity_ids {
+                                if let Some(probe) = leak_probe_of_entity::<HandlerType>(entity_id, &mut entity_container) {
+                                    probes_of_entities.insert(entity_id.index(), probe);
+                                }
+                            }
+                        }
+                        Some(EntityGroupEvent::ComponentChanged { entity_id, component_id }) => {
+                            if component_id.is_component_type_of::<HandlerType>() {
+                                if let Some(probe) = leak_probe_of_entity::<HandlerType>(entity_id, &mut entity_container) {
+                                    probes_of_entities.insert(entity_id.index(), probe);
+                                }
+                            }
+                        }
+                        Some(EntityGroupEvent::EntityRemoved { entity_id }) => {
+                            if let Some(probe) = probes_of_entities.remove(&entity_id.index()) {
+                                removed_entity_probes.push(RemovedEntityProbe {
+                                    entity_id,
+                                    removed_at: Instant::now(),
+                                    probe,
+                                });
+                            }
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+                _ = leak_check_interval.tick() => {
+                    let now = Instant::now();
+                    removed_entity_probes.retain(|removed_entity_probe| {
+                        if now - removed_entity_probe.removed_at < LEAK_REPORT_GRACE_PERIOD {
+                            return true;
+                        }
+
+                        if removed_entity_probe.probe.is_alive() {
+                            report_leak(removed_entity_probe);
+                        }
+
+                        false
+                    });
+                }
+            }
+        }
+    });
+}
+
+fn leak_probe_of_entity<HandlerType>(
+    entity_id: EntityId,
+    entity_container: &mut EntityContainer,
+) -> Option<HandlerLeakProbe>
+where
+    HandlerType: HandlerLeakProbeSource + Any + Send,
+{
+    let mut entity_container_guard = entity_container.lock();
+    let entity_handler = entity_container_guard.handler_for_entity(&entity_id)?;
+    let component = entity_handler.get_component_ref::<HandlerType>()?;
+
+    Some(component.leak_probe())
+}
+
+fn report_leak(removed_entity_probe: &RemovedEntityProbe) {
+    let probe = &removed_entity_probe.probe;
+
+    match probe
+        .creation_backtrace()
+        .filter(|backtrace| backtrace.status() == BacktraceStatus::Captured)
+    {
+        Some(backtrace) => log::warn!(
+            "HandlerLeakDetector, msg = {} of a removed entity is still referenced, entity_id = {:?}, creation backtrace:\n{backtrace}",
+            probe.handler_type_name(),
+            removed_entity_probe.entity_id,
+        ),
+        None => log::warn!(
+            "HandlerLeakDetector, msg = {} of a removed entity is still referenced, entity_id = {:?}",
+            probe.handler_type_name(),
+            removed_entity_probe.entity_id,
+        ),
+    }
+}
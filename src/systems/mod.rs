@@ -2,9 +2,15 @@ pub mod character_controller_to_transform_coupler_system;
 pub mod controller_changer;
 pub mod flying_spectator_camera;
 pub mod general_input_providers;
+pub mod handler_leak_detector;
+pub mod particle_emitter_updater;
 pub mod physics_object_to_transform_coupler_system;
 pub mod renderer_configuration;
 pub mod renderer_transform_updater;
+pub mod rigid_body_entity_associator;
+pub mod shader_reloader;
+pub mod sky_layer;
 pub mod terminal;
 pub mod top_down_player_controller;
+pub mod transform_hierarchy_updater;
 pub mod ui_text_positioner;
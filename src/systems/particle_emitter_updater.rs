@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use entity_component::{component_type_list, EntityContainer, EntityGroupEvent, EntityId};
+use muleengine::renderer::{
+    particle_system::ParticleEmitterSettings, renderer_detached_client::RendererDetachedClient,
+    RendererObjectHandler,
+};
+
+use crate::essential_services::EssentialServices;
+
+pub fn run(essentials: &Arc<EssentialServices>) {
+    let mut entity_container = essentials.entity_container.clone();
+    let renderer_detached_client = essentials.renderer_detached_client.clone();
+
+    tokio::spawn(async move {
+        let entity_group = entity_container.lock().entity_group(component_type_list!(
+            RendererObjectHandler,
+            ParticleEmitterSettings,
+        ));
+        let event_receiver = entity_group.event_receiver(true, &mut entity_container.lock());
+
+        while let Some(event) = event_receiver.recv().await {
+            if let EntityGroupEvent::EntityAdded { entity_id } = event {
+                update_particle_emitter_of_entity(
+                    entity_id,
+                    &renderer_detached_client,
+                    &mut entity_container,
+                );
+            } else if let EntityGroupEvent::ComponentChanged { entity_id, .. } = event {
+                update_particle_emitter_of_entity(
+                    entity_id,
+                    &renderer_detached_client,
+                    &mut entity_container,
+                );
+            } else if let EntityGroupEvent::EntitiesAdded { entity_ids } = event {
+                for entity_id in entity_ids {
+                    update_particle_emitter_of_entity(
+                        entity_id,
+                        &renderer_detached_client,
+                        &mut entity_container,
+                    );
+                }
+            }
+        }
+    });
+}
+
+fn update_particle_emitter_of_entity(
+    entity_id: EntityId,
+    renderer_detached_client: &RendererDetachedClient,
+    entity_container: &mut EntityContainer,
+) {
+    if let Some(entity_handler) = entity_container.lock().handler_for_entity(&entity_id) {
+        let settings = if let Some(component) =
+            entity_handler.get_component_ref::<ParticleEmitterSettings>()
+        {
+            component.clone()
+        } else {
+            return;
+        };
+
+        let renderer_object_handler =
+            if let Some(component) = entity_handler.get_component_ref::<RendererObjectHandler>() {
+                component.clone()
+            } else {
+                return;
+            };
+
+        renderer_detached_client.set_particle_emitter_settings(renderer_object_handler, settings);
+    }
+}
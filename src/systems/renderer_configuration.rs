@@ -1,21 +1,80 @@
 use std::sync::Arc;
 
 use muleengine::{
+    asset_container::AssetContainer,
+    asset_reader::AssetReader,
     bytifex_utils::sync::async_item::AsyncItem,
     renderer::{
-        renderer_pipeline_step::RendererPipelineStep, renderer_system::RendererClient,
-        RendererCameraHandler, RendererGroupHandler, RendererLayerHandler,
+        renderer_detached_client::RendererDetachedClient,
+        renderer_layer_projection::{RendererLayerProjection, ScreenSpaceUnits},
+        renderer_pipeline_description::{
+            parse_renderer_pipeline_description, RendererPipelineDescriptionError,
+            RendererPipelineHandlers,
+        },
+        renderer_pipeline_step::{split_screen_viewports_ndc, RendererPipelineStep},
+        renderer_system::RendererClient,
+        RendererCameraHandler, RendererError, RendererGroupHandler, RendererLayerHandler,
         RendererTransformHandler,
     },
     service_container::ServiceContainer,
 };
-use vek::{FrustumPlanes, Mat4, Transform, Vec2};
+use tokio::sync::Mutex as AsyncMutex;
+use vek::{FrustumPlanes, Mat4, Transform, Vec2, Vec4};
+
+use super::sky_layer::SkyLayer;
+
+const FOV_Y_DEGREES: f32 = 45.0;
+const NEAR_PLANE: f32 = 0.01;
+const FAR_PLANE: f32 = 1000.0;
+
+// the ui of the game is placed in pixels from the top left corner of the window
+const UI_LAYER_PROJECTION: RendererLayerProjection = RendererLayerProjection::ScreenSpace {
+    units: ScreenSpaceUnits::Pixels,
+};
+
+// the dimensions are the ones of the viewport, so every view of a split screen has its own aspect ratio
+pub fn perspective_projection_matrix(viewport_width: usize, viewport_height: usize) -> Mat4<f32> {
+    Mat4::perspective_fov_rh_zo(
+        FOV_Y_DEGREES.to_radians(),
+        viewport_width as f32,
+        viewport_height as f32,
+        NEAR_PLANE,
+        FAR_PLANE,
+    )
+}
+
+pub fn ortho_overlay_projection_matrix(window_width: usize, window_height: usize) -> Mat4<f32> {
+    let ratio = window_height as f32 / window_width as f32;
+    Mat4::orthographic_rh_no(FrustumPlanes {
+        left: -1.0,
+        right: 1.0,
+        bottom: -ratio,
+        top: ratio,
+        near: 1.0,
+        far: -1.0,
+    })
+}
+
+// a camera of the world and the part of the window it is drawn into
+#[derive(Clone)]
+pub struct SplitScreenView {
+    pub camera_transform_handler: RendererTransformHandler,
+    pub camera_handler: RendererCameraHandler,
+    pub renderer_layer_handler: RendererLayerHandler,
+
+    pub viewport_start_ndc: Vec2<f32>,
+    pub viewport_end_ndc: Vec2<f32>,
+}
 
 pub struct RendererConfigurationData {
+    renderer_client: RendererClient,
+    asset_reader: Arc<AssetReader>,
+
     skydome_camera_transform_handler: RendererTransformHandler,
     skydome_camera_handler: RendererCameraHandler,
     skydome_renderer_layer_handler: RendererLayerHandler,
     skydome_renderer_group_handler: RendererGroupHandler,
+    sky_layer: SkyLayer,
 
     main_camera_transform_handler: RendererTransformHandler,
     main_camera_handler: RendererCameraHandler,
@@ -26,6 +85,14 @@ pub struct RendererConfigurationData {
     ortho_overlay_camera_handler: RendererCameraHandler,
     ortho_overlay_renderer_layer_handler: RendererLayerHandler,
     ortho_overlay_renderer_group_handler: RendererGroupHandler,
+
+    pixel_overlay_camera_transform_handler: RendererTransformHandler,
+    pixel_overlay_camera_handler: RendererCameraHandler,
+    pixel_overlay_renderer_layer_handler: RendererLayerHandler,
+    pixel_overlay_renderer_group_handler: RendererGroupHandler,
+
+    // the first view is always the main camera
+    split_screen_views: AsyncMutex<Vec<SplitScreenView>>,
 }
 
 #[derive(Clone)]
@@ -34,7 +101,11 @@ pub struct RendererConfiguration {
 }
 
 impl RendererConfigurationData {
-    pub async fn new(renderer_client: RendererClient) -> Self {
+    pub async fn new(
+        renderer_client: RendererClient,
+        renderer_detached_client: RendererDetachedClient,
+        asset_reader: Arc<AssetReader>,
+    ) -> Self {
         let skydome_camera_transform_handler = renderer_client
             .create_transform(Transform::default())
             .await
@@ -74,6 +145,19 @@ impl RendererConfigurationData {
             .unwrap()
             .unwrap();
 
+        let pixel_overlay_camera_transform_handler = renderer_client
+            .create_transform(Transform::default())
+            .await
+            .inspect_err(|e| log::error!("{e:?}"))
+            .unwrap()
+            .unwrap();
+        let pixel_overlay_camera_handler = renderer_client
+            .create_camera(pixel_overlay_camera_transform_handler.clone())
+            .await
+            .inspect_err(|e| log::error!("{e:?}"))
+            .unwrap()
+            .unwrap();
+
         let skydome_renderer_layer_handler = renderer_client
             .create_renderer_layer(skydome_camera_handler.clone())
             .await
@@ -118,10 +202,6 @@ impl RendererConfigurationData {
             .unwrap()
             .unwrap();
 
-        let fov_y_degrees = 45.0f32;
-        let near_plane = 0.01;
-        let far_plane = 1000.0;
-
         let ortho_overlay_renderer_layer_handler = renderer_client
             .create_renderer_layer(ortho_overlay_camera_handler.clone())
             .await
@@ -144,88 +224,61 @@ impl RendererConfigurationData {
             .unwrap()
             .unwrap();
 
+        let pixel_overlay_renderer_layer_handler = renderer_client
+            .create_renderer_layer(pixel_overlay_camera_handler.clone())
+            .await
+            .inspect_err(|e| log::error!("{e:?}"))
+            .unwrap()
+            .unwrap();
+        let pixel_overlay_renderer_group_handler = renderer_client
+            .create_renderer_group()
+            .await
+            .inspect_err(|e| log::error!("{e:?}"))
+            .unwrap()
+            .unwrap();
         renderer_client
-            .set_renderer_pipeline(vec![
-                RendererPipelineStep::Clear {
-                    depth: true,
-                    color: true,
-
-                    viewport_start_ndc: Vec2::broadcast(0.0),
-                    viewport_end_ndc: Vec2::broadcast(1.0),
-                },
-                RendererPipelineStep::Draw {
-                    renderer_layer_handler: skydome_renderer_layer_handler.clone(),
-
-                    viewport_start_ndc: Vec2::broadcast(0.0),
-                    viewport_end_ndc: Vec2::broadcast(1.0),
-
-                    compute_projection_matrix: Arc::new(move |window_width, window_height| {
-                        Mat4::perspective_fov_rh_zo(
-                            fov_y_degrees.to_radians(),
-                            window_width as f32,
-                            window_height as f32,
-                            near_plane,
-                            far_plane,
-                        )
-                    }),
-                },
-                RendererPipelineStep::Clear {
-                    viewport_start_ndc: Vec2::broadcast(0.0),
-                    viewport_end_ndc: Vec2::broadcast(1.0),
-                    depth: true,
-                    color: false,
-                },
-                RendererPipelineStep::Draw {
-                    renderer_layer_handler: main_renderer_layer_handler.clone(),
-
-                    viewport_start_ndc: Vec2::broadcast(0.0),
-                    viewport_end_ndc: Vec2::broadcast(1.0),
-
-                    compute_projection_matrix: Arc::new(move |window_width, window_height| {
-                        Mat4::perspective_fov_rh_zo(
-                            fov_y_degrees.to_radians(),
-                            window_width as f32,
-                            window_height as f32,
-                            near_plane,
-                            far_plane,
-                        )
-                    }),
-                },
-                RendererPipelineStep::Clear {
-                    viewport_start_ndc: Vec2::broadcast(0.0),
-                    viewport_end_ndc: Vec2::broadcast(1.0),
-                    depth: true,
-                    color: false,
-                },
-                RendererPipelineStep::Draw {
-                    renderer_layer_handler: ortho_overlay_renderer_layer_handler.clone(),
-
-                    viewport_start_ndc: Vec2::broadcast(0.0),
-                    viewport_end_ndc: Vec2::broadcast(1.0),
-
-                    compute_projection_matrix: Arc::new(move |window_width, window_height| {
-                        let ratio = window_height as f32 / window_width as f32;
-                        Mat4::orthographic_rh_no(FrustumPlanes {
-                            left: -1.0,
-                            right: 1.0,
-                            bottom: -ratio,
-                            top: ratio,
-                            near: 1.0,
-                            far: -1.0,
-                        })
-                    }),
-                },
-            ])
+            .add_renderer_group_to_layer(
+                pixel_overlay_renderer_group_handler.clone(),
+                pixel_overlay_renderer_layer_handler.clone(),
+            )
+            .await
+            .inspect_err(|e| log::error!("{e:?}"))
+            .unwrap()
+            .unwrap();
+        renderer_client
+            .update_renderer_layer_projection(
+                pixel_overlay_renderer_layer_handler.clone(),
+                Some(UI_LAYER_PROJECTION),
+            )
             .await
             .inspect_err(|e| log::error!("{e:?}"))
             .unwrap()
             .unwrap();
 
-        Self {
+        let sky_layer = SkyLayer::new(
+            renderer_client.clone(),
+            renderer_detached_client,
+            skydome_camera_transform_handler.clone(),
+        );
+
+        let main_view = SplitScreenView {
+            camera_transform_handler: main_camera_transform_handler.clone(),
+            camera_handler: main_camera_handler.clone(),
+            renderer_layer_handler: main_renderer_layer_handler.clone(),
+
+            viewport_start_ndc: Vec2::broadcast(0.0),
+            viewport_end_ndc: Vec2::broadcast(1.0),
+        };
+
+        let data = Self {
+            renderer_client,
+            asset_reader,
+
             skydome_camera_transform_handler,
             skydome_camera_handler,
             skydome_renderer_layer_handler,
             skydome_renderer_group_handler,
+            sky_layer,
 
             main_camera_transform_handler,
             main_camera_handler,
@@ -236,7 +289,200 @@ impl RendererConfigurationData {
             ortho_overlay_camera_handler,
             ortho_overlay_renderer_layer_handler,
             ortho_overlay_renderer_group_handler,
+
+            pixel_overlay_camera_transform_handler,
+            pixel_overlay_camera_handler,
+            pixel_overlay_renderer_layer_handler,
+            pixel_overlay_renderer_group_handler,
+
+            split_screen_views: AsyncMutex::new(vec![main_view.clone()]),
+        };
+
+        data.renderer_client
+            .set_renderer_pipeline(data.renderer_pipeline(&[main_view]))
+            .await
+            .inspect_err(|e| log::error!("{e:?}"))
+            .unwrap()
+            .unwrap();
+
+        data
+    }
+
+    fn renderer_pipeline(&self, views: &[SplitScreenView]) -> Vec<RendererPipelineStep> {
+        let mut steps = vec![RendererPipelineStep::Clear {
+            depth: true,
+            color: true,
+            stencil: false,
+            clear_color: Vec4::zero(),
+
+            viewport_start_ndc: Vec2::broadcast(0.0),
+            viewport_end_ndc: Vec2::broadcast(1.0),
+        }];
+
+        for view in views {
+            // the sky layer follows the orientation of the main camera, so every view shares the same sky
+            steps.push(RendererPipelineStep::Draw {
+                renderer_layer_handler: self.skydome_renderer_layer_handler.clone(),
+
+                viewport_start_ndc: view.viewport_start_ndc,
+                viewport_end_ndc: view.viewport_end_ndc,
+
+                compute_projection_matrix: Arc::new(perspective_projection_matrix),
+            });
+            steps.push(RendererPipelineStep::Clear {
+                viewport_start_ndc: view.viewport_start_ndc,
+                viewport_end_ndc: view.viewport_end_ndc,
+                depth: true,
+                color: false,
+                stencil: false,
+                clear_color: Vec4::zero(),
+            });
+            steps.push(RendererPipelineStep::Draw {
+                renderer_layer_handler: view.renderer_layer_handler.clone(),
+
+                viewport_start_ndc: view.viewport_start_ndc,
+                viewport_end_ndc: view.viewport_end_ndc,
+
+                compute_projection_matrix: Arc::new(perspective_projection_matrix),
+            });
+            steps.push(RendererPipelineStep::DrawDebugLines {
+                renderer_layer_handler: view.renderer_layer_handler.clone(),
+
+                viewport_start_ndc: view.viewport_start_ndc,
+                viewport_end_ndc: view.viewport_end_ndc,
+
+                compute_projection_matrix: Arc::new(perspective_projection_matrix),
+            });
+        }
+
+        steps.extend([
+            RendererPipelineStep::Clear {
+                viewport_start_ndc: Vec2::broadcast(0.0),
+                viewport_end_ndc: Vec2::broadcast(1.0),
+                depth: true,
+                color: false,
+                stencil: false,
+                clear_color: Vec4::zero(),
+            },
+            RendererPipelineStep::Draw {
+                renderer_layer_handler: self.ortho_overlay_renderer_layer_handler.clone(),
+
+                viewport_start_ndc: Vec2::broadcast(0.0),
+                viewport_end_ndc: Vec2::broadcast(1.0),
+
+                compute_projection_matrix: Arc::new(ortho_overlay_projection_matrix),
+            },
+            RendererPipelineStep::Clear {
+                viewport_start_ndc: Vec2::broadcast(0.0),
+                viewport_end_ndc: Vec2::broadcast(1.0),
+                depth: true,
+                color: false,
+                stencil: false,
+                clear_color: Vec4::zero(),
+            },
+            RendererPipelineStep::Draw {
+                renderer_layer_handler: self.pixel_overlay_renderer_layer_handler.clone(),
+
+                viewport_start_ndc: Vec2::broadcast(0.0),
+                viewport_end_ndc: Vec2::broadcast(1.0),
+
+                compute_projection_matrix: Arc::new(|width, height| {
+                    UI_LAYER_PROJECTION.compute_projection_matrix(width, height)
+                }),
+            },
+        ]);
+
+        steps
+    }
+
+    // the names the pipeline descriptions of the assets can refer to
+    fn renderer_pipeline_handlers(&self) -> RendererPipelineHandlers {
+        let mut handlers = RendererPipelineHandlers::default();
+
+        for (name, camera_handler, renderer_layer_handler) in [
+            (
+                "skydome",
+                &self.skydome_camera_handler,
+                &self.skydome_renderer_layer_handler,
+            ),
+            (
+                "main",
+                &self.main_camera_handler,
+                &self.main_renderer_layer_handler,
+            ),
+            (
+                "ortho_overlay",
+                &self.ortho_overlay_camera_handler,
+                &self.ortho_overlay_renderer_layer_handler,
+            ),
+            (
+                "pixel_overlay",
+                &self.pixel_overlay_camera_handler,
+                &self.pixel_overlay_renderer_layer_handler,
+            ),
+        ] {
+            handlers
+                .cameras
+                .insert(name.to_string(), camera_handler.clone());
+            handlers
+                .renderer_layers
+                .insert(name.to_string(), renderer_layer_handler.clone());
         }
+
+        handlers.projections.insert(
+            "perspective".to_string(),
+            Arc::new(perspective_projection_matrix),
+        );
+        handlers.projections.insert(
+            "ortho_overlay".to_string(),
+            Arc::new(ortho_overlay_projection_matrix),
+        );
+        handlers.projections.insert(
+            "pixel_overlay".to_string(),
+            Arc::new(|width, height| UI_LAYER_PROJECTION.compute_projection_matrix(width, height)),
+        );
+
+        handlers
+    }
+
+    async fn create_split_screen_view(
+        &self,
+        viewport_start_ndc: Vec2<f32>,
+        viewport_end_ndc: Vec2<f32>,
+    ) -> Result<SplitScreenView, RendererError> {
+        let camera_transform_handler = self
+            .renderer_client
+            .create_transform(Transform::default())
+            .await
+            .map_err(|_| RendererError::RendererSystemDropped)??;
+        let camera_handler = self
+            .renderer_client
+            .create_camera(camera_transform_handler.clone())
+            .await
+            .map_err(|_| RendererError::RendererSystemDropped)??;
+        let renderer_layer_handler = self
+            .renderer_client
+            .create_renderer_layer(camera_handler.clone())
+            .await
+            .map_err(|_| RendererError::RendererSystemDropped)??;
+
+        // every view looks at the same world
+        self.renderer_client
+            .add_renderer_group_to_layer(
+                self.main_renderer_group_handler.clone(),
+                renderer_layer_handler.clone(),
+            )
+            .await
+            .map_err(|_| RendererError::RendererSystemDropped)??;
+
+        Ok(SplitScreenView {
+            camera_transform_handler,
+            camera_handler,
+            renderer_layer_handler,
+
+            viewport_start_ndc,
+            viewport_end_ndc,
+        })
     }
 }
 
@@ -253,15 +499,114 @@ impl RendererConfiguration {
                     .unwrap()
                     .as_ref()
                     .clone();
+                let renderer_detached_client = service_container
+                    .get_service::<RendererDetachedClient>()
+                    .inspect_err(|e| log::error!("{e:?}"))
+                    .unwrap()
+                    .as_ref()
+                    .clone();
+                let asset_reader = service_container
+                    .get_service::<AssetContainer>()
+                    .inspect_err(|e| log::error!("{e:?}"))
+                    .unwrap()
+                    .asset_reader()
+                    .clone();
 
-                data.set(RendererConfigurationData::new(renderer_client).await)
-                    .await;
+                data.set(
+                    RendererConfigurationData::new(
+                        renderer_client,
+                        renderer_detached_client,
+                        asset_reader,
+                    )
+                    .await,
+                )
+                .await;
             });
         }
 
         Self { data }
     }
 
+    // the first player gets the main camera, the others get their own cameras,
+    // the cameras of the previous split screen are released except for the main one
+    pub async fn set_split_screen(
+        &self,
+        player_count: usize,
+    ) -> Result<Vec<SplitScreenView>, RendererError> {
+        let data = self.data.read().await;
+        let mut split_screen_views = data.split_screen_views.lock().await;
+
+        let viewports = split_screen_viewports_ndc(player_count.max(1));
+
+        let mut views = Vec::with_capacity(viewports.len());
+        for (player_index, (viewport_start_ndc, viewport_end_ndc)) in
+            viewports.into_iter().enumerate()
+        {
+            let view = if player_index == 0 {
+                SplitScreenView {
+                    camera_transform_handler: data.main_camera_transform_handler.clone(),
+                    camera_handler: data.main_camera_handler.clone(),
+                    renderer_layer_handler: data.main_renderer_layer_handler.clone(),
+
+                    viewport_start_ndc,
+                    viewport_end_ndc,
+                }
+            } else {
+                data.create_split_screen_view(viewport_start_ndc, viewport_end_ndc)
+                    .await?
+            };
+            views.push(view);
+        }
+
+        data.renderer_client
+            .set_renderer_pipeline(data.renderer_pipeline(&views))
+            .await
+            .map_err(|_| RendererError::RendererSystemDropped)??;
+
+        *split_screen_views = views.clone();
+
+        Ok(views)
+    }
+
+    // the split screen builds its own pipeline, so set_split_screen replaces the loaded one
+    pub async fn load_pipeline_from_asset(&self, path: &str) -> Result<(), String> {
+        let data = self.data.read().await;
+
+        let steps = data
+            .asset_reader
+            .get_reader(path)
+            .ok_or_else(|| RendererPipelineDescriptionError::CannotOpenAsset {
+                path: path.to_string(),
+            })
+            .and_then(parse_renderer_pipeline_description)
+            .and_then(|descriptions| {
+                data.renderer_pipeline_handlers()
+                    .build_renderer_pipeline(&descriptions)
+            })
+            .map_err(|e| format!("Loading renderer pipeline, path = {path}, msg = {e:?}"))?;
+
+        data.renderer_client
+            .set_renderer_pipeline(steps)
+            .await
+            .map_err(|_| {
+                format!(
+                    "Loading renderer pipeline, path = {path}, msg = {:?}",
+                    RendererError::RendererSystemDropped
+                )
+            })?
+            .map_err(|e| format!("Loading renderer pipeline, path = {path}, msg = {e:?}"))
+    }
+
+    pub async fn split_screen_views(&self) -> Vec<SplitScreenView> {
+        self.data
+            .read()
+            .await
+            .split_screen_views
+            .lock()
+            .await
+            .clone()
+    }
+
     pub async fn skydome_camera_transform_handler(&self) -> RendererTransformHandler {
         self.data
             .read()
@@ -290,6 +635,10 @@ impl RendererConfiguration {
             .clone()
     }
 
+    pub async fn sky_layer(&self) -> SkyLayer {
+        self.data.read().await.sky_layer.clone()
+    }
+
     pub async fn main_camera_transform_handler(&self) -> RendererTransformHandler {
         self.data.read().await.main_camera_transform_handler.clone()
     }
@@ -333,4 +682,32 @@ impl RendererConfiguration {
             .ortho_overlay_renderer_group_handler
             .clone()
     }
+
+    pub async fn pixel_overlay_camera_transform_handler(&self) -> RendererTransformHandler {
+        self.data
+            .read()
+            .await
+            .pixel_overlay_camera_transform_handler
+            .clone()
+    }
+
+    pub async fn pixel_overlay_camera_handler(&self) -> RendererCameraHandler {
+        self.data.read().await.pixel_overlay_camera_handler.clone()
+    }
+
+    pub async fn pixel_overlay_renderer_layer_handler(&self) -> RendererLayerHandler {
+        self.data
+            .read()
+            .await
+            .pixel_overlay_renderer_layer_handler
+            .clone()
+    }
+
+    pub async fn pixel_overlay_renderer_group_handler(&self) -> RendererGroupHandler {
+        self.data
+            .read()
+            .await
+            .pixel_overlay_renderer_group_handler
+            .clone()
+    }
 }
@@ -1,65 +1,51 @@
 use std::sync::Arc;
 
-use entity_component::{component_type_list, EntityContainer, EntityGroupEvent, EntityId};
-use muleengine::renderer::{
-    renderer_system::RendererClient, RendererObjectHandler, RendererTransformHandler,
+use entity_component::{Changed, EntityContainer};
+use muleengine::{
+    renderer::{
+        renderer_detached_client::RendererDetachedClient, RendererObjectHandler,
+        RendererTransformHandler,
+    },
+    system_container::System,
 };
 use vek::Transform;
 
 use crate::essential_services::EssentialServices;
 
-pub fn run(essentials: &Arc<EssentialServices>) {
-    let mut entity_container = essentials.entity_container.clone();
-    let renderer_client = essentials.renderer_client.clone();
-
-    tokio::spawn(async move {
-        let entity_group = entity_container.lock().entity_group(component_type_list!(
-            RendererObjectHandler,
-            RendererTransformHandler,
-            Transform<f32, f32, f32>,
-        ));
-        let event_receiver = entity_group.event_receiver(true, &mut entity_container.lock());
+// only the transforms that changed since the previous tick are sent to the renderer
+pub struct RendererTransformUpdaterSystem {
+    entity_container: EntityContainer,
+    renderer_detached_client: RendererDetachedClient,
+    last_run_change_tick: u64,
+}
 
-        while let Ok(event) = event_receiver.pop().await {
-            if let EntityGroupEvent::EntityAdded { entity_id } = event {
-                update_renderer_transform_of_entity(
-                    entity_id,
-                    &renderer_client,
-                    &mut entity_container,
-                );
-            } else if let EntityGroupEvent::ComponentChanged { entity_id, .. } = event {
-                update_renderer_transform_of_entity(
-                    entity_id,
-                    &renderer_client,
-                    &mut entity_container,
-                );
-            }
+impl RendererTransformUpdaterSystem {
+    pub fn new(essentials: &Arc<EssentialServices>) -> Self {
+        Self {
+            entity_container: essentials.entity_container.clone(),
+            renderer_detached_client: essentials.renderer_detached_client.clone(),
+            last_run_change_tick: 0,
         }
-    });
+    }
 }
 
-fn update_renderer_transform_of_entity(
-    entity_id: EntityId,
-    renderer_client: &RendererClient,
-    entity_container: &mut EntityContainer,
-) {
-    if let Some(entity_handler) = entity_container.lock().handler_for_entity(&entity_id) {
-        let transform = if let Some(component) =
-            entity_handler.get_component_ref::<Transform<f32, f32, f32>>()
+impl System for RendererTransformUpdaterSystem {
+    fn tick(&mut self, _loop_start: &std::time::Instant, _last_loop_time_secs: f32) {
+        let mut entity_container_guard = self.entity_container.lock();
+        let change_tick = entity_container_guard.change_tick();
+
+        for (_, transform_handler, transform, _) in
+            entity_container_guard.query_since::<(
+                &RendererObjectHandler,
+                &RendererTransformHandler,
+                &Transform<f32, f32, f32>,
+                Changed<Transform<f32, f32, f32>>,
+            )>(self.last_run_change_tick)
         {
-            *component
-        } else {
-            return;
-        };
-
-        let transform_handler = if let Some(component) =
-            entity_handler.get_component_ref::<RendererTransformHandler>()
-        {
-            component.clone()
-        } else {
-            return;
-        };
+            self.renderer_detached_client
+                .update_transform(transform_handler.clone(), *transform);
+        }
 
-        drop(renderer_client.update_transform(transform_handler, transform));
+        self.last_run_change_tick = change_tick;
     }
 }
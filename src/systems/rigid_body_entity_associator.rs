@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use entity_component::{component_type_list, EntityGroupEvent};
+
+use crate::{essential_services::EssentialServices, physics::RigidBodyHandler};
+
+// the collision events of the physics engine refer to the entities of the colliding rigid bodies
+pub fn run(essentials: &Arc<EssentialServices>) {
+    let entity_container = essentials.entity_container.clone();
+    let physics_engine = essentials.physics_engine.clone();
+
+    tokio::spawn(async move {
+        let entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list!(RigidBodyHandler));
+        let event_receiver = entity_group.event_receiver(true, &mut entity_container.lock());
+
+        while let Some(event) = event_receiver.recv().await {
+            let entity_ids = match event {
+                EntityGroupEvent::EntityAdded { entity_id } => vec![entity_id],
+                EntityGroupEvent::EntitiesAdded { entity_ids } => entity_ids,
+                EntityGroupEvent::ComponentChanged {
+                    entity_id,
+                    component_id,
+                } if component_id.is_component_type_of::<RigidBodyHandler>() => vec![entity_id],
+                _ => continue,
+            };
+
+            // the physics engine is not locked while the entity container is locked
+            let rigid_body_handlers = {
+                let mut entity_container_guard = entity_container.lock();
+                entity_ids
+                    .into_iter()
+                    .filter_map(|entity_id| {
+                        let rigid_body_handler = entity_container_guard
+                            .handler_for_entity(&entity_id)?
+                            .get_component_ref::<RigidBodyHandler>()?
+                            .clone();
+                        Some((entity_id, rigid_body_handler))
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let mut physics_engine_guard = physics_engine.write();
+            for (entity_id, rigid_body_handler) in rigid_body_handlers {
+                physics_engine_guard.set_entity_of_rigid_body(&rigid_body_handler, entity_id);
+            }
+        }
+    });
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use muleengine::window_context::{Event, EventReceiver, Key};
+
+use crate::essential_services::EssentialServices;
+
+pub fn init(event_receiver: EventReceiver, essentials: &Arc<EssentialServices>) {
+    let renderer_client = essentials.renderer_client.clone();
+
+    tokio::spawn(async move {
+        while let Ok(event) = event_receiver.pop().await {
+            if let Event::KeyDown { key: Key::F5 } = event {
+                match renderer_client.reload_shaders().await {
+                    Ok(Ok(())) => log::info!("ReloadShaders, msg = shaders reloaded"),
+                    Ok(Err(e)) => log::error!("ReloadShaders, msg = {e:?}"),
+                    Err(_) => break,
+                }
+            }
+        }
+    });
+}
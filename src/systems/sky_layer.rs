@@ -0,0 +1,157 @@
+use std::time::Instant;
+
+use muleengine::{
+    bytifex_utils::sync::types::{arc_rw_lock_new, ArcRwLock},
+    mesh::Material,
+    renderer::{
+        renderer_detached_client::RendererDetachedClient, renderer_system::RendererClient,
+        RendererError, RendererObjectHandler, RendererTransformHandler,
+    },
+    system_container::System,
+};
+use vek::{Quaternion, Transform, Vec3};
+
+struct SkyLayerData {
+    renderer_client: RendererClient,
+    renderer_detached_client: RendererDetachedClient,
+    camera_transform_handler: RendererTransformHandler,
+    camera_orientation: Quaternion<f32>,
+    rotation: Quaternion<f32>,
+    angular_velocity: Vec3<f32>,
+    rotation_start_time: Instant,
+    renderer_object_handlers: Vec<RendererObjectHandler>,
+}
+
+#[derive(Clone)]
+pub struct SkyLayer {
+    data: ArcRwLock<SkyLayerData>,
+}
+
+impl SkyLayerData {
+    fn current_rotation(&self, now: Instant) -> Quaternion<f32> {
+        let angle_rad =
+            self.angular_velocity.magnitude() * (now - self.rotation_start_time).as_secs_f32();
+
+        match self.angular_velocity.try_normalized() {
+            Some(axis) => Quaternion::rotation_3d(angle_rad, axis) * self.rotation,
+            None => self.rotation,
+        }
+    }
+
+    fn update_camera_transform(&self, now: Instant) {
+        // rotating the sky is the same as rotating its camera in the opposite direction
+        let orientation = self.current_rotation(now).conjugate() * self.camera_orientation;
+
+        self.renderer_detached_client.update_transform(
+            self.camera_transform_handler.clone(),
+            Transform {
+                position: Vec3::zero(),
+                orientation,
+                scale: Vec3::broadcast(1.0),
+            },
+        );
+    }
+}
+
+impl SkyLayer {
+    pub fn new(
+        renderer_client: RendererClient,
+        renderer_detached_client: RendererDetachedClient,
+        camera_transform_handler: RendererTransformHandler,
+    ) -> Self {
+        Self {
+            data: arc_rw_lock_new(SkyLayerData {
+                renderer_client,
+                renderer_detached_client,
+                camera_transform_handler,
+                camera_orientation: Quaternion::identity(),
+                rotation: Quaternion::identity(),
+                angular_velocity: Vec3::zero(),
+                rotation_start_time: Instant::now(),
+                renderer_object_handlers: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn follow_camera(&self, camera_transform: &Transform<f32, f32, f32>) {
+        let mut data = self.data.write();
+        data.camera_orientation = camera_transform.orientation;
+        data.update_camera_transform(Instant::now());
+    }
+
+    pub fn rotation(&self) -> Quaternion<f32> {
+        self.data.read().current_rotation(Instant::now())
+    }
+
+    pub fn set_rotation(&self, rotation: Quaternion<f32>) {
+        let now = Instant::now();
+
+        let mut data = self.data.write();
+        data.rotation = rotation;
+        data.rotation_start_time = now;
+        data.update_camera_transform(now);
+    }
+
+    pub fn set_angular_velocity(&self, angular_velocity: Vec3<f32>) {
+        let now = Instant::now();
+
+        let mut data = self.data.write();
+        data.rotation = data.current_rotation(now);
+        data.rotation_start_time = now;
+        data.angular_velocity = angular_velocity;
+    }
+
+    pub fn add_renderer_object(&self, renderer_object_handler: RendererObjectHandler) {
+        self.data
+            .write()
+            .renderer_object_handlers
+            .push(renderer_object_handler);
+    }
+
+    pub fn clear_renderer_objects(&self) {
+        self.data.write().renderer_object_handlers.clear();
+    }
+
+    pub async fn set_materials(&self, materials: Vec<Material>) -> Result<(), RendererError> {
+        let (renderer_client, renderer_object_handlers) = {
+            let data = self.data.read();
+            (
+                data.renderer_client.clone(),
+                data.renderer_object_handlers.clone(),
+            )
+        };
+
+        if materials.len() != renderer_object_handlers.len() {
+            log::warn!(
+                "SetSkyMaterials, msg = number of materials ({}) differs from the number of sky objects ({})",
+                materials.len(),
+                renderer_object_handlers.len()
+            );
+        }
+
+        for (renderer_object_handler, material) in
+            renderer_object_handlers.into_iter().zip(materials)
+        {
+            let material_handler = renderer_client
+                .create_material(material)
+                .await
+                .map_err(|_| RendererError::RendererSystemDropped)??;
+
+            renderer_client
+                .set_renderer_object_material(renderer_object_handler, material_handler)
+                .await
+                .map_err(|_| RendererError::RendererSystemDropped)??;
+        }
+
+        Ok(())
+    }
+}
+
+impl System for SkyLayer {
+    fn tick(&mut self, _loop_start: &Instant, _last_loop_time_secs: f32) {
+        let data = self.data.read();
+        if data.angular_velocity != Vec3::zero() {
+            data.update_camera_transform(Instant::now());
+        }
+    }
+}
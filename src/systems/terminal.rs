@@ -1,37 +1,24 @@
 use std::sync::Arc;
 
-use entity_component::EntityId;
 use muleengine::{
     bytifex_utils::sync::{broadcast::Receiver, types::ArcRwLock},
-    font::{HackFontContainer, RenderedGlyph},
-    mesh::{Material, MaterialTexture, MaterialTextureType, TextureMapMode},
-    mesh_creator,
-    renderer::RendererGroupHandler,
+    renderer::{RendererObjectHandler, RendererTransformHandler},
     window_context::{Event, Key, WindowContext},
 };
-use vek::{Transform, Vec2, Vec3};
+use vek::{Transform, Vec2, Vec4};
 
-use crate::{
-    essential_services::EssentialServices,
-    game_objects::tools::game_object_builder::GameObjectBuilder,
-};
+use crate::essential_services::EssentialServices;
 
-const PIXEL_SCALE: usize = 128;
-const PIXEL_SCALE_F32: f32 = PIXEL_SCALE as f32;
-const TEXT_SCALE: f32 = 0.1;
-const START_POSITION: Vec2<f32> = Vec2::new(-1.0 + TEXT_SCALE / 2.0, 0.0);
+use super::ui_text_positioner::{UiEntityPosition, UI_TEXT_SCALE};
 
-struct PrintedCharacter {
-    entity_id: Option<EntityId>,
-    advance: f32,
-}
+// in pixels
+const TEXT_SIZE: f32 = 24.0;
 
 struct Terminal {
     essentials: Arc<EssentialServices>,
     event_receiver: Receiver<Event>,
-    printed_characters: Vec<PrintedCharacter>,
-    next_character_position: Vec3<f32>,
-    renderer_group_handler: RendererGroupHandler,
+    text_renderer_object_handler: RendererObjectHandler,
+    _transform_handler: RendererTransformHandler,
     next_command_text: String,
     terminal_text: String,
 }
@@ -39,7 +26,6 @@ struct Terminal {
 impl Terminal {
     async fn run(&mut self) {
         let mut is_terminal_opened = false;
-        self.add_character('>', false).await;
 
         while let Ok(event) = self.event_receiver.pop().await {
             if let Event::KeyDown { key } = event {
@@ -50,25 +36,11 @@ impl Terminal {
                                 .await;
                         }
                         self.next_command_text = String::new();
-                        self.next_character_position = Vec3::new(
-                            START_POSITION.x,
-                            self.next_character_position.y - TEXT_SCALE,
-                            self.next_character_position.z,
-                        );
-                        self.add_character('>', false).await;
-                    } else if key == Key::Backspace {
-                        if let Some(_chr) = self.next_command_text.pop() {
-                            if let Some(printed_character) = self.printed_characters.pop() {
-                                if let Some(entity_id) = printed_character.entity_id {
-                                    self.essentials
-                                        .entity_container
-                                        .lock()
-                                        .remove_entity(&entity_id);
-                                }
-                                self.next_character_position.x -= printed_character.advance;
-                            }
-                            self.terminal_text.pop();
-                        }
+                        self.terminal_text.push_str("\n>");
+                        self.update_text().await;
+                    } else if key == Key::Backspace && self.next_command_text.pop().is_some() {
+                        self.terminal_text.pop();
+                        self.update_text().await;
                     }
                 }
             } else if let Event::Text { text } = event {
@@ -76,86 +48,32 @@ impl Terminal {
                     is_terminal_opened = !is_terminal_opened;
                     log::info!("terminal opened = {is_terminal_opened}");
                 } else if is_terminal_opened {
-                    for chr in text.chars() {
-                        self.add_character(chr, true).await;
-                    }
+                    self.next_command_text.push_str(&text);
+                    self.terminal_text.push_str(&text);
+                    self.update_text().await;
                 }
             }
         }
     }
 
-    fn create_material_for_char(
-        &self,
-        chr: char,
-        font: &mut HackFontContainer,
-        pixel_scale: usize,
-    ) -> Option<(Material, RenderedGlyph)> {
-        let glyph = font.get_rendered_glyph(chr, pixel_scale)?;
-        Some((
-            Material {
-                textures: vec![MaterialTexture {
-                    image: glyph.image().clone(),
-                    texture_type: MaterialTextureType::Albedo,
-                    texture_map_mode: TextureMapMode::Clamp,
-                    blend: 1.0,
-                    uv_channel_id: 0,
-                }],
-                opacity: 1.0,
-                albedo_color: Vec3::broadcast(1.0),
-                shininess_color: Vec3::broadcast(0.0),
-                emissive_color: Vec3::broadcast(0.0),
-            },
-            glyph,
-        ))
-    }
-
     async fn execute_command(&self, command: &str, _essentials: &Arc<EssentialServices>) {
         log::info!("executing command = {command}");
     }
 
-    async fn add_character(&mut self, chr: char, is_command_character: bool) {
-        self.terminal_text.push(chr);
-        if is_command_character {
-            self.next_command_text.push(chr);
-        }
-
-        let material_and_glyph =
-            self.create_material_for_char(chr, &mut self.essentials.hack_font.write(), PIXEL_SCALE);
-
-        let (advance, entity_id) = if let Some((material, glyph)) = material_and_glyph {
-            let entity_builder = GameObjectBuilder::new(&self.essentials)
-                .mesh(Arc::new(mesh_creator::rectangle2d::create(1.0, 1.0)))
-                .await
-                .shader("Assets/shaders/unlit")
-                .await
-                .transform(Transform {
-                    position: self.next_character_position
-                        + glyph.compute_render_offset_px() / PIXEL_SCALE_F32
-                            * TEXT_SCALE
-                            * Vec3::new(1.0, -1.0, 1.0),
-                    scale: Vec3::new(TEXT_SCALE, -TEXT_SCALE, 1.0),
-                    ..Default::default()
-                })
-                .await
-                .material(material)
-                .await
-                .renderer_group_handler(self.renderer_group_handler.clone())
-                .build()
-                .await;
-
-            let entity_id = entity_builder.build();
-
-            (
-                glyph.h_advance() / PIXEL_SCALE_F32 * TEXT_SCALE,
-                Some(entity_id),
+    // the whole text is laid out again, but the renderer object is kept
+    async fn update_text(&self) {
+        let _ = self
+            .essentials
+            .renderer_client
+            .set_text_renderer_object_text(
+                self.text_renderer_object_handler.clone(),
+                self.terminal_text.clone(),
             )
-        } else {
-            (TEXT_SCALE, None)
-        };
-
-        self.printed_characters
-            .push(PrintedCharacter { entity_id, advance });
-        self.next_character_position.x += advance;
+            .await
+            .unwrap()
+            .inspect_err(|e| {
+                log::error!("Could not update the text of the terminal, error = {e:?}")
+            });
     }
 }
 
@@ -164,16 +82,69 @@ pub fn run(essentials: &Arc<EssentialServices>, window_context: ArcRwLock<dyn Wi
     let essentials = essentials.clone();
 
     tokio::spawn(async move {
+        let terminal_text = ">".to_string();
+
+        let transform = Transform {
+            scale: UI_TEXT_SCALE,
+            ..Default::default()
+        };
+        let transform_handler = essentials
+            .renderer_client
+            .create_transform(transform)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let text_renderer_object_handler = match essentials
+            .renderer_client
+            .create_text_renderer_object(
+                terminal_text.clone(),
+                essentials.hack_font.clone(),
+                TEXT_SIZE,
+                Vec4::one(),
+                transform_handler.clone(),
+            )
+            .await
+            .unwrap()
+        {
+            Ok(text_renderer_object_handler) => text_renderer_object_handler,
+            Err(e) => {
+                log::error!("Could not create the text of the terminal, error = {e:?}");
+                return;
+            }
+        };
+
+        essentials
+            .renderer_client
+            .add_renderer_object_to_group(
+                text_renderer_object_handler.clone(),
+                essentials
+                    .renderer_configuration
+                    .pixel_overlay_renderer_group_handler()
+                    .await,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        // the first line of the terminal starts at the middle of the left edge of the window
+        essentials
+            .entity_container
+            .entity_builder()
+            .with_component(text_renderer_object_handler.clone())
+            .with_component(transform_handler.clone())
+            .with_component(transform)
+            .with_component(UiEntityPosition::MiddleLeftWindow {
+                offset: Vec2::new(0.0, -TEXT_SIZE / 2.0),
+            })
+            .build();
+
         let mut terminal = Terminal {
             essentials: essentials.clone(),
             event_receiver,
-            printed_characters: Vec::new(),
-            next_character_position: Vec3::from(START_POSITION),
-            renderer_group_handler: essentials
-                .renderer_configuration
-                .ortho_overlay_renderer_group_handler()
-                .await,
-            terminal_text: String::new(),
+            text_renderer_object_handler,
+            _transform_handler: transform_handler,
+            terminal_text,
             next_command_text: String::new(),
         };
 
@@ -9,14 +9,14 @@ use std::{
 use entity_component::{component_type_list, EntityContainer, EntityGroup};
 use muleengine::{
     camera::Camera,
-    renderer::{renderer_system::RendererClient, RendererTransformHandler},
+    renderer::{renderer_detached_client::RendererDetachedClient, RendererTransformHandler},
     system_container::System,
 };
 use vek::{Transform, Vec3};
 
 use crate::{
     components::CurrentlyControlledCharacter, essential_services::EssentialServices,
-    physics::character_controller::CharacterControllerHandler,
+    physics::character_controller::CharacterControllerHandler, systems::sky_layer::SkyLayer,
 };
 
 use super::input::InputReceiver;
@@ -26,9 +26,9 @@ pub struct PlayerController {
     input_receiver: InputReceiver,
     entity_container: EntityContainer,
     entity_group: EntityGroup,
-    renderer_client: RendererClient,
+    renderer_detached_client: RendererDetachedClient,
     main_camera_transform_handler: RendererTransformHandler,
-    skydome_camera_transform_handler: RendererTransformHandler,
+    sky_layer: SkyLayer,
 }
 
 impl PlayerController {
@@ -50,15 +50,12 @@ impl PlayerController {
             input_receiver,
             entity_container,
             entity_group,
-            renderer_client: essentials.renderer_client.clone(),
+            renderer_detached_client: essentials.renderer_detached_client.clone(),
             main_camera_transform_handler: essentials
                 .renderer_configuration
                 .main_camera_transform_handler()
                 .await,
-            skydome_camera_transform_handler: essentials
-                .renderer_configuration
-                .skydome_camera_transform_handler()
-                .await,
+            sky_layer: essentials.renderer_configuration.sky_layer().await,
         }
     }
 }
@@ -104,10 +101,7 @@ impl System for PlayerController {
                         let mut camera = Camera::new();
                         camera.pitch(-90.0f32.to_radians());
 
-                        drop(self.renderer_client.update_transform(
-                            self.skydome_camera_transform_handler.clone(),
-                            *camera.transform_ref(),
-                        ));
+                        self.sky_layer.follow_camera(camera.transform_ref());
 
                         camera.move_by(
                             character_position
@@ -116,10 +110,10 @@ impl System for PlayerController {
                                     * character_specs.camera_distance
                                     * 0.2,
                         );
-                        drop(self.renderer_client.update_transform(
+                        self.renderer_detached_client.update_transform(
                             self.main_camera_transform_handler.clone(),
                             *camera.transform_ref(),
-                        ));
+                        );
                     } else {
                         continue;
                     }
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use entity_component::{
+    component_type_list, Children, EntityContainer, EntityContainerGuard, EntityGroupEvent,
+    EntityId, Parent,
+};
+use vek::Transform;
+
+use crate::essential_services::EssentialServices;
+
+// the transform of a child entity relative to its parent, the world transform of the entity is
+// computed into its Transform component, which is forwarded to the renderer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalTransform(pub Transform<f32, f32, f32>);
+
+pub fn run(essentials: &Arc<EssentialServices>) {
+    let entity_container = essentials.entity_container.clone();
+
+    tokio::spawn(async move {
+        let parent_entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list!(Transform<f32, f32, f32>, Children));
+        let parent_event_receiver =
+            parent_entity_group.event_receiver(true, &mut entity_container.lock());
+
+        let child_entity_group = entity_container
+            .lock()
+            .entity_group(component_type_list!(LocalTransform, Parent));
+        let child_event_receiver =
+            child_entity_group.event_receiver(true, &mut entity_container.lock());
+
+        loop {
+            tokio::select! {
+                event = parent_event_receiver.recv() => {
+                    let entity_ids = match event {
+                        Some(EntityGroupEvent::EntityAdded { entity_id }) => vec![entity_id],
+                        Some(EntityGroupEvent::EntitiesAdded { entity_ids }) => entity_ids,
+                        Some(EntityGroupEvent::ComponentChanged { entity_id, .. }) => vec![entity_id],
+                        Some(_) => continue,
+                        None => break,
+                    };
+
+                    let mut entity_container_guard = entity_container.lock();
+                    for entity_id in entity_ids {
+                        for child_id in entity_container_guard.children_of(&entity_id) {
+                            update_world_transform_of_entity(child_id, &mut entity_container_guard);
+                        }
+                    }
+                },
+                event = child_event_receiver.recv() => {
+                    let entity_ids = match event {
+                        Some(EntityGroupEvent::EntityAdded { entity_id }) => vec![entity_id],
+                        Some(EntityGroupEvent::EntitiesAdded { entity_ids }) => entity_ids,
+                        Some(EntityGroupEvent::ComponentChanged { entity_id, .. }) => vec![entity_id],
+                        Some(_) => continue,
+                        None => break,
+                    };
+
+                    let mut entity_container_guard = entity_container.lock();
+                    for entity_id in entity_ids {
+                        update_world_transform_of_entity(entity_id, &mut entity_container_guard);
+                    }
+                },
+            }
+        }
+    });
+}
+
+// changing the world transform of an entity triggers the update of its children
+fn update_world_transform_of_entity(
+    entity_id: EntityId,
+    entity_container_guard: &mut EntityContainerGuard,
+) {
+    let Some(parent_id) = entity_container_guard.parent_of(&entity_id) else {
+        return;
+    };
+
+    let parent_transform = if let Some(entity_handler) =
+        entity_container_guard.handler_for_entity(&parent_id)
+    {
+        if let Some(component) = entity_handler.get_component_ref::<Transform<f32, f32, f32>>() {
+            *component
+        } else {
+            return;
+        }
+    } else {
+        return;
+    };
+
+    if let Some(mut entity_handler) = entity_container_guard.handler_for_entity(&entity_id) {
+        let local_transform =
+            if let Some(component) = entity_handler.get_component_ref::<LocalTransform>() {
+                component.0
+            } else {
+                return;
+            };
+
+        let world_transform = compose_transforms(&parent_transform, &local_transform);
+
+        let current_transform = entity_handler
+            .get_component_ref::<Transform<f32, f32, f32>>()
+            .map(|component| *component);
+        match current_transform {
+            // the unchanged transforms are not written, so the change events do not loop
+            Some(current_transform) if current_transform == world_transform => (),
+            Some(_) => {
+                entity_handler.change_component(|transform: &mut Transform<f32, f32, f32>| {
+                    *transform = world_transform;
+                });
+            }
+            None => {
+                entity_handler.add_component(world_transform);
+            }
+        }
+    }
+}
+
+// a rotated child of a non-uniformly scaled parent would be skewed, that cannot be represented
+// by a transform, so the scale is applied per axis in the local space of the child
+fn compose_transforms(
+    parent: &Transform<f32, f32, f32>,
+    local: &Transform<f32, f32, f32>,
+) -> Transform<f32, f32, f32> {
+    Transform {
+        position: parent.position + parent.orientation * (parent.scale * local.position),
+        orientation: parent.orientation * local.orientation,
+        scale: parent.scale * local.scale,
+    }
+}
@@ -5,8 +5,14 @@ use muleengine::{
     bytifex_utils::sync::types::ArcRwLock,
     window_context::{Event, WindowContext},
 };
-use vek::{Transform, Vec2};
+use vek::{Transform, Vec2, Vec3};
 
+// the text is laid out along the -y axis, so it is flipped to grow downwards in the pixel space of
+// the ui layer
+pub const UI_TEXT_SCALE: Vec3<f32> = Vec3::new(1.0, -1.0, 1.0);
+
+// the positions are in pixels from the top left corner of the window and the y axis points
+// downwards, like the projection of the ui layer
 #[derive(Clone)]
 pub enum UiEntityPosition {
     TopLeftWindow { offset: Vec2<f32> },
@@ -50,20 +56,28 @@ pub fn run(entity_container: EntityContainer, window_context: ArcRwLock<dyn Wind
                         }
                     }
                 },
-                event = entity_group_event_receiver.pop() => {
-                    if event.is_err() {
+                event = entity_group_event_receiver.recv() => {
+                    if event.is_none() {
                         break;
                     }
 
                     let mut entity_container_guard = entity_container.lock();
 
-                    if let Ok(EntityGroupEvent::EntityAdded { entity_id }) = event {
+                    if let Some(EntityGroupEvent::EntityAdded { entity_id }) = event {
                         set_transform_of_entity(
                             entity_id,
                             &mut entity_container_guard,
                             window_dimensions,
                         );
-                    } else if let Ok(EntityGroupEvent::ComponentChanged {
+                    } else if let Some(EntityGroupEvent::EntitiesAdded { entity_ids }) = event {
+                        for entity_id in entity_ids {
+                            set_transform_of_entity(
+                                entity_id,
+                                &mut entity_container_guard,
+                                window_dimensions,
+                            );
+                        }
+                    } else if let Some(EntityGroupEvent::ComponentChanged {
                         entity_id,
                         component_id,
                     }) = event
@@ -107,18 +121,24 @@ fn compute_position_of_entity(
     position: &UiEntityPosition,
     window_dimensions: Vec2<usize>,
 ) -> Vec2<f32> {
-    let ratio = window_dimensions.y as f32 / window_dimensions.x as f32;
-    match position {
-        UiEntityPosition::TopLeftWindow { offset } => Vec2::new(-1.0, ratio) + offset,
-        UiEntityPosition::TopMiddleWindow { offset } => Vec2::new(0.0, ratio) + offset,
-        UiEntityPosition::TopRightWindow { offset } => Vec2::new(1.0, ratio) + offset,
-
-        UiEntityPosition::MiddleLeftWindow { offset } => Vec2::new(-1.0, 0.0) + offset,
-        UiEntityPosition::MiddleMiddleWindow { offset } => Vec2::new(0.0, 0.0) + *offset,
-        UiEntityPosition::MiddleRightWindow { offset } => Vec2::new(1.0, 0.0) + offset,
-
-        UiEntityPosition::BottomLeftWindow { offset } => Vec2::new(-1.0, -ratio) + offset,
-        UiEntityPosition::BottomMiddleWindow { offset } => Vec2::new(0.0, -ratio) + offset,
-        UiEntityPosition::BottomRightWindow { offset } => Vec2::new(1.0, -ratio) + offset,
-    }
+    let width = window_dimensions.x as f32;
+    let height = window_dimensions.y as f32;
+
+    let (anchor, offset) = match position {
+        UiEntityPosition::TopLeftWindow { offset } => (Vec2::new(0.0, 0.0), offset),
+        UiEntityPosition::TopMiddleWindow { offset } => (Vec2::new(width / 2.0, 0.0), offset),
+        UiEntityPosition::TopRightWindow { offset } => (Vec2::new(width, 0.0), offset),
+
+        UiEntityPosition::MiddleLeftWindow { offset } => (Vec2::new(0.0, height / 2.0), offset),
+        UiEntityPosition::MiddleMiddleWindow { offset } => {
+            (Vec2::new(width / 2.0, height / 2.0), offset)
+        }
+        UiEntityPosition::MiddleRightWindow { offset } => (Vec2::new(width, height / 2.0), offset),
+
+        UiEntityPosition::BottomLeftWindow { offset } => (Vec2::new(0.0, height), offset),
+        UiEntityPosition::BottomMiddleWindow { offset } => (Vec2::new(width / 2.0, height), offset),
+        UiEntityPosition::BottomRightWindow { offset } => (Vec2::new(width, height), offset),
+    };
+
+    anchor + offset
 }